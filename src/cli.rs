@@ -11,32 +11,87 @@
 //! println!("Project dir: {:?}", cli.project_dir());
 //! ```
 
-use std::path::PathBuf;
+use std::env;
+use std::path::{Component, Path, PathBuf};
 
-use clap::{CommandFactory, Parser, ValueEnum};
+use chrono::{DateTime, Utc};
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Shell};
 
 use crate::config::SortMode;
+use crate::output::OutputFormat;
 use crate::package::Runner;
+use crate::utils::clock::parse_frozen_time;
 
 /// Fast interactive TUI for running npm scripts.
 #[derive(Parser, Debug)]
 #[command(name = "nrs")]
 #[command(author, version, about, long_about = None)]
 #[command(arg_required_else_help = false)]
+#[command(group(ArgGroup::new("workspace_target").args(["script", "list"]).multiple(true)))]
+#[command(group(ArgGroup::new("listing").args(["list", "history"])))]
 pub struct Cli {
     /// Path to project directory (default: current directory)
     #[arg(value_name = "PATH")]
     pub path: Option<PathBuf>,
 
-    /// Rerun last executed script (no TUI)
-    #[arg(short = 'L', long = "last")]
-    pub last: bool,
+    /// Rerun last executed script, or the last one matching a pattern (no TUI)
+    #[arg(short = 'L', long = "last", value_name = "PATTERN", num_args = 0..=1)]
+    pub last: Option<Option<String>>,
+
+    /// Print the last N runs of this project (default: 10) and exit
+    #[arg(
+        long = "history",
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        conflicts_with_all = ["list", "script", "last"]
+    )]
+    pub history: Option<usize>,
 
     /// List scripts non-interactively (no TUI)
     #[arg(short, long)]
     pub list: bool,
 
+    /// Show full commands and descriptions in --list, wrapped instead of truncated
+    #[arg(long, requires = "list")]
+    pub long: bool,
+
+    /// Columns shown by --list (name, command, desc, lastrun, duration, status)
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        value_enum,
+        value_delimiter = ',',
+        num_args = 1..,
+        requires = "list"
+    )]
+    pub columns: Vec<CliListColumn>,
+
+    /// Output format of --list and --history
+    #[arg(long, value_name = "FORMAT", value_enum, requires = "listing")]
+    pub format: Option<CliListFormat>,
+
+    /// Shorthand for --format json
+    #[arg(long, requires = "listing", conflicts_with = "format")]
+    pub json: bool,
+
+    /// Only list scripts in a category (the name up to the first ':')
+    #[arg(long, value_name = "NAME", requires = "list")]
+    pub category: Option<String>,
+
+    /// Only show and run pinned scripts
+    #[arg(long)]
+    pub favorites: bool,
+
+    /// Show the scripts a script runs, as a tree (no TUI)
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["list", "summary"])]
+    pub graph: Option<String>,
+
+    /// Count scripts per category (no TUI)
+    #[arg(long, conflicts_with = "list")]
+    pub summary: bool,
+
     /// Exclude scripts matching pattern (can be repeated)
     #[arg(short, long, value_name = "PATTERN")]
     pub exclude: Vec<String>,
@@ -53,14 +108,95 @@ pub struct Cli {
     #[arg(short, long, value_name = "ARGS", allow_hyphen_values = true)]
     pub args: Option<String>,
 
-    /// Run script directly without TUI
+    /// Run script directly without TUI, or every script matching a pattern like test:*
     #[arg(short = 'n', long = "script", value_name = "NAME")]
     pub script: Option<String>,
 
+    /// Run the scripts matched by a -n pattern at the same time
+    #[arg(long, requires = "script")]
+    pub parallel: bool,
+
+    /// Run the -n script again whenever files matching a glob change (repeatable)
+    #[arg(
+        long,
+        value_name = "GLOB",
+        requires = "script",
+        conflicts_with_all = ["parallel", "workspace", "compare"]
+    )]
+    pub watch: Vec<String>,
+
+    /// Rerun the script that failed last whenever files change, until it passes
+    #[arg(
+        long,
+        conflicts_with_all = ["script", "last", "list", "workspace", "compare", "watch"]
+    )]
+    pub watch_failed: bool,
+
+    /// Run or list the scripts of a specific workspace (name or path)
+    #[arg(
+        short = 'w',
+        long = "workspace",
+        value_name = "NAME",
+        requires = "workspace_target"
+    )]
+    pub workspace: Option<String>,
+
+    /// Time the script under several runners and compare (e.g. npm,bun)
+    #[arg(
+        long,
+        value_name = "RUNNERS",
+        value_enum,
+        value_delimiter = ',',
+        num_args = 1..,
+        requires = "script",
+        conflicts_with = "workspace"
+    )]
+    pub compare: Vec<CliRunner>,
+
+    /// Run a script in every workspace that defines it, then summarize (no TUI)
+    #[arg(
+        long,
+        value_name = "SCRIPT",
+        conflicts_with_all = ["script", "workspace", "list"]
+    )]
+    pub all_workspaces: Option<String>,
+
+    /// Print the directory of a workspace and exit
+    #[arg(long, value_name = "NAME")]
+    pub print_workspace_path: Option<String>,
+
     /// Show command without executing
     #[arg(short, long)]
     pub dry_run: bool,
 
+    /// Browse without running scripts or writing history
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Run scripts matching scripts.confirm without asking (for CI)
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+
+    /// Save script output to a log file as well (see nrs logs)
+    #[arg(long)]
+    pub log: bool,
+
+    /// Record script output to an asciicast file (asciinema play FILE); scripts see a pipe, not a terminal
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Compact picker for tmux/wezterm popups: print the chosen command instead of running it
+    #[arg(long)]
+    pub popup: bool,
+
+    /// Pick a script inline and print its name instead of running it (for pipelines)
+    #[arg(long, conflicts_with_all = ["list", "script", "popup", "last"])]
+    pub pick: bool,
+
+    /// Print the most used scripts on one line and exit (for `nrs hook`)
+    #[arg(long, conflicts_with_all = ["list", "script", "last"])]
+    pub brief: bool,
+
     /// Path to config file
     #[arg(short, long, value_name = "PATH")]
     pub config: Option<PathBuf>,
@@ -69,13 +205,181 @@ pub struct Cli {
     #[arg(long)]
     pub no_config: bool,
 
+    /// Use a [profile.<name>] from the config (default: $NRS_PROFILE)
+    #[arg(long, value_name = "NAME", conflicts_with = "no_config")]
+    pub profile: Option<String>,
+
+    /// Read package.json even if it is huge or defines thousands of scripts
+    #[arg(long)]
+    pub force: bool,
+
     /// Enable debug output
     #[arg(long)]
     pub debug: bool,
 
+    /// Increase log verbosity (-v info, -vv debug, -vvv trace)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Append log output to a file instead of stderr
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
     /// Generate shell completions
     #[arg(long, value_name = "SHELL", value_enum)]
     pub completions: Option<CliShell>,
+
+    /// Pin the current time (RFC 3339) for reproducible output
+    #[arg(long, value_name = "TIME", hide = true, value_parser = parse_frozen_time)]
+    pub frozen_time: Option<DateTime<Utc>>,
+
+    /// Pin the seed for random values for reproducible output
+    #[arg(long, value_name = "SEED", hide = true)]
+    pub seed: Option<u64>,
+
+    /// Maintenance subcommand
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// Maintenance subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum CliCommand {
+    /// Manage script history
+    History {
+        #[command(subcommand)]
+        action: CliHistoryCommand,
+    },
+    /// Inspect the configuration format
+    Config {
+        #[command(subcommand)]
+        action: CliConfigCommand,
+    },
+    /// Show scripts added, removed or changed since a git ref
+    Diff {
+        /// Branch, tag or commit to compare against
+        #[arg(value_name = "REF")]
+        reference: String,
+    },
+    /// Add a script to package.json (or a workspace's, with --workspace)
+    NewScript {
+        /// Add it to this workspace (name or path) instead of the root
+        #[arg(short, long, value_name = "NAME")]
+        workspace: Option<String>,
+        /// Name of the new script
+        name: String,
+        /// Command the script runs
+        #[arg(required = true, num_args = 1.., trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Suggest standard scripts for the tools in devDependencies
+    Suggest {
+        /// Add the suggested scripts to package.json without asking
+        #[arg(long)]
+        write: bool,
+    },
+    /// Show how often scripts run, fail and how long they take
+    Stats {
+        /// Print the scripts' statistics in this format
+        #[arg(long, value_name = "FORMAT", value_enum)]
+        format: Option<CliReportFormat>,
+    },
+    /// Show which workspaces of a monorepo define which scripts
+    Matrix {
+        /// Scripts to show, comma-separated (default: the shared ones)
+        #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+        scripts: Vec<String>,
+        /// Print the matrix in this format
+        #[arg(long, value_name = "FORMAT", value_enum)]
+        format: Option<CliReportFormat>,
+    },
+    /// List saved run logs (see --log)
+    Logs {
+        /// Print the end of the latest log of this script
+        #[arg(long, value_name = "SCRIPT")]
+        tail: Option<String>,
+    },
+    /// Check config, history, terminal, package managers and workspaces
+    Doctor {
+        /// Print the checks in this format
+        #[arg(long, value_name = "FORMAT", value_enum)]
+        format: Option<CliReportFormat>,
+    },
+    /// Print shell integration that binds Ctrl+J to the script picker
+    Init {
+        /// Shell to print the integration for
+        #[arg(value_enum)]
+        shell: CliInitShell,
+    },
+    /// Print a shell hook that lists a project's top scripts on cd
+    Hook {
+        /// Shell to print the hook for
+        #[arg(value_enum)]
+        shell: CliInitShell,
+    },
+    /// Install shell completions
+    Completions {
+        #[command(subcommand)]
+        action: CliCompletionsCommand,
+    },
+}
+
+/// History maintenance actions.
+#[derive(Debug, Clone, Subcommand)]
+pub enum CliHistoryCommand {
+    /// Recover entries from a corrupt history backup and merge them back
+    Repair,
+    /// Fuzzy-search scripts and arguments run in any project
+    Search {
+        /// Text to look for in script names and arguments
+        query: String,
+        /// Print the runs in this format
+        #[arg(long, value_name = "FORMAT", value_enum)]
+        format: Option<CliReportFormat>,
+    },
+    /// List projects in the history, pinned first, flagging missing ones
+    Projects {
+        /// Print the projects in this format
+        #[arg(long, value_name = "FORMAT", value_enum)]
+        format: Option<CliReportFormat>,
+    },
+    /// Pin a project to the top of the projects list
+    Pin {
+        /// Project directory (default: current directory)
+        path: Option<PathBuf>,
+    },
+    /// Unpin a project
+    Unpin {
+        /// Project directory (default: current directory)
+        path: Option<PathBuf>,
+    },
+    /// Remove projects whose directory no longer exists
+    Prune {
+        /// Show what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Shell completion actions.
+#[derive(Debug, Clone, Subcommand)]
+pub enum CliCompletionsCommand {
+    /// Write completions where the shell loads them from
+    Install {
+        /// Shell to install completions for (default: from $SHELL)
+        #[arg(value_enum)]
+        shell: Option<CliInitShell>,
+        /// Show where completions would be written without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Configuration actions.
+#[derive(Debug, Clone, Subcommand)]
+pub enum CliConfigCommand {
+    /// Print a JSON Schema for config files, for editor validation
+    Schema,
 }
 
 /// Shell type for completion generation.
@@ -93,6 +397,183 @@ pub enum CliShell {
     Elvish,
 }
 
+/// Shell type for `nrs init`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CliInitShell {
+    /// Bash shell
+    Bash,
+    /// Zsh shell
+    Zsh,
+    /// Fish shell
+    Fish,
+}
+
+impl CliInitShell {
+    /// Detect the shell from `$SHELL`.
+    pub fn from_env() -> Option<Self> {
+        Self::from_path(Path::new(&env::var_os("SHELL")?))
+    }
+
+    /// Get the shell of an executable path, like `/bin/zsh`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.file_name()?.to_str()? {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            _ => None,
+        }
+    }
+
+    /// Get the shell name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        }
+    }
+
+    /// Get the file the shell loads completions for nrs from.
+    ///
+    /// bash-completion and fish look in the XDG data and config directories,
+    /// zsh in `~/.zfunc` once it is added to `fpath`.
+    pub fn completions_file(self) -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+        let xdg = |var: &str, default: &str| {
+            env::var_os(var)
+                .filter(|dir| !dir.is_empty())
+                .map_or_else(|| home.join(default), PathBuf::from)
+        };
+        Some(match self {
+            Self::Bash => {
+                xdg("XDG_DATA_HOME", ".local/share").join("bash-completion/completions/nrs")
+            }
+            Self::Zsh => home.join(".zfunc").join("_nrs"),
+            Self::Fish => xdg("XDG_CONFIG_HOME", ".config").join("fish/completions/nrs.fish"),
+        })
+    }
+
+    /// Get the shell integration script.
+    pub fn init_script(self) -> &'static str {
+        match self {
+            Self::Bash => include_str!("init/nrs.bash"),
+            Self::Zsh => include_str!("init/nrs.zsh"),
+            Self::Fish => include_str!("init/nrs.fish"),
+        }
+    }
+
+    /// Get the directory hook script.
+    pub fn hook_script(self) -> &'static str {
+        match self {
+            Self::Bash => include_str!("init/hook.bash"),
+            Self::Zsh => include_str!("init/hook.zsh"),
+            Self::Fish => include_str!("init/hook.fish"),
+        }
+    }
+}
+
+/// Column of the `--list` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CliListColumn {
+    /// Script name.
+    Name,
+    /// Script command.
+    Command,
+    /// Script description.
+    Desc,
+    /// Time of the last run.
+    Lastrun,
+    /// Duration of the last run, and the average.
+    Duration,
+    /// Exit status of the last run.
+    Status,
+}
+
+impl CliListColumn {
+    /// Columns shown when `--columns` isn't given.
+    pub const DEFAULT: [CliListColumn; 3] = [Self::Name, Self::Command, Self::Desc];
+
+    /// Columns shown with `--verbose` when `--columns` isn't given.
+    pub const VERBOSE: [CliListColumn; 6] = [
+        Self::Name,
+        Self::Command,
+        Self::Desc,
+        Self::Lastrun,
+        Self::Duration,
+        Self::Status,
+    ];
+
+    /// Check if the column needs the run history.
+    pub fn uses_history(self) -> bool {
+        matches!(self, Self::Lastrun | Self::Duration | Self::Status)
+    }
+
+    /// Get the column name, as given to `--columns`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Command => "command",
+            Self::Desc => "desc",
+            Self::Lastrun => "lastrun",
+            Self::Duration => "duration",
+            Self::Status => "status",
+        }
+    }
+}
+
+/// Output format of `--list` and `--history`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CliListFormat {
+    /// Aligned columns with a header, colored on a terminal.
+    #[default]
+    Table,
+    /// Tab-separated columns, without header or colors.
+    Plain,
+    /// JSON with every script field and its history.
+    Json,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// A Markdown table.
+    Markdown,
+}
+
+impl From<CliListFormat> for OutputFormat {
+    fn from(format: CliListFormat) -> Self {
+        match format {
+            CliListFormat::Table => OutputFormat::Table,
+            CliListFormat::Plain => OutputFormat::Plain,
+            CliListFormat::Json => OutputFormat::Json,
+            CliListFormat::Csv => OutputFormat::Csv,
+            CliListFormat::Markdown => OutputFormat::Markdown,
+        }
+    }
+}
+
+/// Output format of the reporting commands (`stats`, `history projects`,
+/// `history search`, `matrix`, `doctor`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CliReportFormat {
+    /// Aligned columns with a header.
+    Table,
+    /// A JSON array with an object per row.
+    Json,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// A Markdown table.
+    Markdown,
+}
+
+impl From<CliReportFormat> for OutputFormat {
+    fn from(format: CliReportFormat) -> Self {
+        match format {
+            CliReportFormat::Table => OutputFormat::Table,
+            CliReportFormat::Json => OutputFormat::Json,
+            CliReportFormat::Csv => OutputFormat::Csv,
+            CliReportFormat::Markdown => OutputFormat::Markdown,
+        }
+    }
+}
+
 /// Sort mode for CLI parsing.
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CliSortMode {
@@ -121,6 +602,7 @@ pub enum CliRunner {
     Yarn,
     Pnpm,
     Bun,
+    Deno,
 }
 
 impl From<CliRunner> for Runner {
@@ -130,6 +612,7 @@ impl From<CliRunner> for Runner {
             CliRunner::Yarn => Runner::Yarn,
             CliRunner::Pnpm => Runner::Pnpm,
             CliRunner::Bun => Runner::Bun,
+            CliRunner::Deno => Runner::Deno,
         }
     }
 }
@@ -137,7 +620,23 @@ impl From<CliRunner> for Runner {
 impl Cli {
     /// Parse command line arguments.
     pub fn parse_args() -> Self {
-        Cli::parse()
+        let mut cli = Cli::parse();
+        cli.take_script_from_path();
+        cli
+    }
+
+    /// Take a PATH that doesn't exist and looks like a name as the script to
+    /// run, so `nrs dev` works like `nrs -n dev`.
+    pub fn take_script_from_path(&mut self) {
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        let is_name = path.components().count() == 1
+            && matches!(path.components().next(), Some(Component::Normal(_)));
+        if self.script.is_some() || !is_name || path.exists() {
+            return;
+        }
+        self.script = self.path.take().map(|p| p.to_string_lossy().into_owned());
     }
 
     /// Get the project directory.
@@ -151,7 +650,33 @@ impl Cli {
 
     /// Check if TUI should be shown.
     pub fn should_show_tui(&self) -> bool {
-        !self.list && !self.last && self.script.is_none()
+        !self.list
+            && !self.summary
+            && self.last.is_none()
+            && self.history.is_none()
+            && self.script.is_none()
+            && self.all_workspaces.is_none()
+            && self.print_workspace_path.is_none()
+            && self.command.is_none()
+    }
+
+    /// Get the `--list` output format.
+    pub fn list_format(&self) -> CliListFormat {
+        self.output_format().unwrap_or_default()
+    }
+
+    /// Get the format given with `--format` or `--json`, if any.
+    pub fn output_format(&self) -> Option<CliListFormat> {
+        if self.json {
+            Some(CliListFormat::Json)
+        } else {
+            self.format
+        }
+    }
+
+    /// Check if scripts should only be shown, not run.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run || self.read_only
     }
 
     /// Get the sort mode.
@@ -176,50 +701,124 @@ impl Cli {
         };
         generate(shell, &mut cmd, "nrs", &mut std::io::stdout());
     }
+
+    /// Generate the shell completions installed by `nrs completions install`.
+    pub fn completions_script(shell: CliInitShell) -> Vec<u8> {
+        let mut cmd = Cli::command();
+        let shell = match shell {
+            CliInitShell::Bash => Shell::Bash,
+            CliInitShell::Zsh => Shell::Zsh,
+            CliInitShell::Fish => Shell::Fish,
+        };
+        let mut script = Vec::new();
+        generate(shell, &mut cmd, "nrs", &mut script);
+        script
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn test_default_project_dir() {
         let cli = Cli {
             path: None,
-            last: false,
+            last: None,
+            history: None,
             list: false,
+            long: false,
+            columns: vec![],
+            format: None,
+            json: false,
+            category: None,
+            favorites: false,
+            summary: false,
+            graph: None,
+            all_workspaces: None,
             exclude: vec![],
             sort: None,
             runner: None,
             args: None,
             script: None,
+            parallel: false,
+            watch: vec![],
+            watch_failed: false,
+            workspace: None,
+            compare: vec![],
+            print_workspace_path: None,
             dry_run: false,
+            read_only: false,
+            yes: false,
+            log: false,
+            record: None,
+            popup: false,
+            pick: false,
+            brief: false,
             config: None,
             no_config: false,
+            profile: None,
+            force: false,
             debug: false,
+            verbose: 0,
+            log_file: None,
             completions: None,
+            frozen_time: None,
+            seed: None,
+            command: None,
         };
 
         // Should return current directory
-        assert!(cli.project_dir().is_absolute() || cli.project_dir() == PathBuf::from("."));
+        assert!(cli.project_dir().is_absolute() || cli.project_dir() == Path::new("."));
     }
 
     #[test]
     fn test_should_show_tui() {
         let mut cli = Cli {
             path: None,
-            last: false,
+            last: None,
+            history: None,
             list: false,
+            long: false,
+            columns: vec![],
+            format: None,
+            json: false,
+            category: None,
+            favorites: false,
+            summary: false,
+            graph: None,
+            all_workspaces: None,
             exclude: vec![],
             sort: None,
             runner: None,
             args: None,
             script: None,
+            parallel: false,
+            watch: vec![],
+            watch_failed: false,
+            workspace: None,
+            compare: vec![],
+            print_workspace_path: None,
             dry_run: false,
+            read_only: false,
+            yes: false,
+            log: false,
+            record: None,
+            popup: false,
+            pick: false,
+            brief: false,
             config: None,
             no_config: false,
+            profile: None,
+            force: false,
             debug: false,
+            verbose: 0,
+            log_file: None,
             completions: None,
+            frozen_time: None,
+            seed: None,
+            command: None,
         };
 
         assert!(cli.should_show_tui());
@@ -228,11 +827,259 @@ mod tests {
         assert!(!cli.should_show_tui());
 
         cli.list = false;
-        cli.last = true;
+        cli.summary = true;
+        assert!(!cli.should_show_tui());
+
+        cli.summary = false;
+        cli.last = Some(None);
+        assert!(!cli.should_show_tui());
+
+        cli.last = None;
+        cli.history = Some(10);
         assert!(!cli.should_show_tui());
 
-        cli.last = false;
+        cli.history = None;
         cli.script = Some("dev".to_string());
         assert!(!cli.should_show_tui());
     }
+
+    #[test]
+    fn test_workspace_requires_script() {
+        let cli = Cli::try_parse_from(["nrs", "-n", "build", "--workspace", "@app/web"]).unwrap();
+        assert_eq!(cli.workspace.as_deref(), Some("@app/web"));
+
+        assert!(Cli::try_parse_from(["nrs", "--workspace", "@app/web"]).is_err());
+    }
+
+    #[test]
+    fn test_watch_failed() {
+        let cli = Cli::try_parse_from(["nrs", "--watch-failed"]).unwrap();
+        assert!(cli.watch_failed);
+        assert!(Cli::try_parse_from(["nrs", "--watch-failed", "-n", "test"]).is_err());
+    }
+
+    #[test]
+    fn test_watch_requires_script() {
+        let cli = Cli::try_parse_from([
+            "nrs", "-n", "test", "--watch", "src/**", "--watch", "*.json",
+        ])
+        .unwrap();
+        assert_eq!(cli.watch, ["src/**", "*.json"]);
+
+        assert!(Cli::try_parse_from(["nrs", "--watch", "src/**"]).is_err());
+        assert!(
+            Cli::try_parse_from(["nrs", "-n", "test:*", "--parallel", "--watch", "src/**"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_compare_runners() {
+        let cli = Cli::try_parse_from(["nrs", "-n", "build", "--compare", "npm,bun"]).unwrap();
+        assert!(matches!(cli.compare[..], [CliRunner::Npm, CliRunner::Bun]));
+
+        // Needs a script to compare
+        assert!(Cli::try_parse_from(["nrs", "--compare", "npm,bun"]).is_err());
+    }
+
+    #[test]
+    fn test_all_workspaces() {
+        let cli = Cli::try_parse_from(["nrs", "--all-workspaces", "test", "-a", "--ci"]).unwrap();
+        assert_eq!(cli.all_workspaces.as_deref(), Some("test"));
+        assert!(!cli.should_show_tui());
+
+        assert!(Cli::try_parse_from(["nrs", "--all-workspaces", "test", "-n", "build"]).is_err());
+    }
+
+    #[test]
+    fn test_list_columns() {
+        let cli = Cli::try_parse_from(["nrs", "--list", "--columns", "name,lastrun"]).unwrap();
+        assert_eq!(cli.columns, [CliListColumn::Name, CliListColumn::Lastrun]);
+
+        let cli = Cli::try_parse_from(["nrs", "-l", "--long"]).unwrap();
+        assert!(cli.long);
+
+        // Only meaningful with --list
+        assert!(Cli::try_parse_from(["nrs", "--long"]).is_err());
+        assert!(Cli::try_parse_from(["nrs", "--columns", "name"]).is_err());
+        assert!(Cli::try_parse_from(["nrs", "-l", "--columns", "size"]).is_err());
+    }
+
+    #[test]
+    fn test_summary_and_category() {
+        let cli = Cli::try_parse_from(["nrs", "--summary"]).unwrap();
+        assert!(cli.summary);
+        let cli = Cli::try_parse_from(["nrs", "--list", "--category", "test"]).unwrap();
+        assert_eq!(cli.category.as_deref(), Some("test"));
+
+        assert!(Cli::try_parse_from(["nrs", "--category", "test"]).is_err());
+        assert!(Cli::try_parse_from(["nrs", "--list", "--summary"]).is_err());
+    }
+
+    #[test]
+    fn test_frozen_time_and_seed() {
+        let cli = Cli::try_parse_from([
+            "nrs",
+            "--frozen-time",
+            "2024-01-01T00:00:00Z",
+            "--seed",
+            "7",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.frozen_time.map(|t| t.to_rfc3339()),
+            Some("2024-01-01T00:00:00+00:00".to_string())
+        );
+        assert_eq!(cli.seed, Some(7));
+        assert!(Cli::try_parse_from(["nrs", "--frozen-time", "soon"]).is_err());
+    }
+
+    #[test]
+    fn test_verbose_count() {
+        let cli = Cli::try_parse_from(["nrs", "-vv"]).unwrap();
+        assert_eq!(cli.verbose, 2);
+        let cli = Cli::try_parse_from(["nrs", "--log-file", "/tmp/nrs.log"]).unwrap();
+        assert_eq!(cli.log_file, Some(PathBuf::from("/tmp/nrs.log")));
+    }
+
+    #[test]
+    fn test_config_schema_subcommand() {
+        let cli = Cli::try_parse_from(["nrs", "config", "schema"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Config {
+                action: CliConfigCommand::Schema
+            })
+        ));
+    }
+
+    #[test]
+    fn test_init_subcommand() {
+        let cli = Cli::try_parse_from(["nrs", "init", "zsh"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Init {
+                shell: CliInitShell::Zsh
+            })
+        ));
+        assert!(Cli::try_parse_from(["nrs", "init", "powershell"]).is_err());
+    }
+
+    #[test]
+    fn test_init_scripts_use_popup() {
+        for shell in [CliInitShell::Bash, CliInitShell::Zsh, CliInitShell::Fish] {
+            assert!(shell.init_script().contains("nrs --popup"));
+        }
+    }
+
+    #[test]
+    fn test_hook_subcommand() {
+        let cli = Cli::try_parse_from(["nrs", "hook", "zsh"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Hook {
+                shell: CliInitShell::Zsh
+            })
+        ));
+        for shell in [CliInitShell::Bash, CliInitShell::Zsh, CliInitShell::Fish] {
+            assert!(shell.hook_script().contains("nrs --brief"));
+        }
+    }
+
+    #[test]
+    fn test_completions_install_subcommand() {
+        let cli =
+            Cli::try_parse_from(["nrs", "completions", "install", "fish", "--dry-run"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Completions {
+                action: CliCompletionsCommand::Install {
+                    shell: Some(CliInitShell::Fish),
+                    dry_run: true
+                }
+            })
+        ));
+        let cli = Cli::try_parse_from(["nrs", "completions", "install"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Completions {
+                action: CliCompletionsCommand::Install { shell: None, .. }
+            })
+        ));
+
+        assert_eq!(
+            CliInitShell::from_path(Path::new("/usr/bin/zsh")),
+            Some(CliInitShell::Zsh)
+        );
+        assert_eq!(CliInitShell::from_path(Path::new("/bin/sh")), None);
+        assert!(!Cli::completions_script(CliInitShell::Bash).is_empty());
+    }
+
+    #[test]
+    fn test_diff_subcommand() {
+        let cli = Cli::try_parse_from(["nrs", "diff", "origin/main"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Diff { ref reference }) if reference == "origin/main"
+        ));
+        assert!(Cli::try_parse_from(["nrs", "diff"]).is_err());
+    }
+
+    #[test]
+    fn test_new_script_subcommand() {
+        let cli =
+            Cli::try_parse_from(["nrs", "new-script", "lint", "eslint", ".", "--fix"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::NewScript { workspace: None, ref name, ref command })
+                if name == "lint" && command.join(" ") == "eslint . --fix"
+        ));
+        let cli =
+            Cli::try_parse_from(["nrs", "new-script", "-w", "api", "test", "vitest"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::NewScript { workspace: Some(ref w), .. }) if w == "api"
+        ));
+        assert!(Cli::try_parse_from(["nrs", "new-script", "lint"]).is_err());
+    }
+
+    #[test]
+    fn test_history_repair_subcommand() {
+        let cli = Cli::try_parse_from(["nrs", "history", "repair"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::History {
+                action: CliHistoryCommand::Repair
+            })
+        ));
+        assert!(!cli.should_show_tui());
+
+        let cli = Cli::try_parse_from(["nrs", "history", "search", "--", "--fix"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::History {
+                action: CliHistoryCommand::Search { ref query, .. }
+            }) if query == "--fix"
+        ));
+
+        let cli = Cli::try_parse_from(["nrs", "history", "pin", "../app"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::History {
+                action: CliHistoryCommand::Pin { path: Some(ref p) }
+            }) if p == Path::new("../app")
+        ));
+        let cli = Cli::try_parse_from(["nrs", "history", "prune", "--dry-run"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::History {
+                action: CliHistoryCommand::Prune { dry_run: true }
+            })
+        ));
+
+        // A plain path still works as the project argument
+        let cli = Cli::try_parse_from(["nrs", "./my-project"]).unwrap();
+        assert_eq!(cli.path, Some(PathBuf::from("./my-project")));
+        assert!(cli.command.is_none());
+    }
 }
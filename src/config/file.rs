@@ -1,18 +1,26 @@
 //! Configuration file loading and parsing.
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use tracing::debug;
 
+use super::trust::TrustStore;
 use super::types::Config;
 
+/// Team config shared through the repository, relative to the project root.
+pub const TEAM_CONFIG_FILE: &str = ".nrs/team.toml";
+
+/// Project config, relative to the project root.
+pub const PROJECT_CONFIG_FILE: &str = ".nrsrc.toml";
+
 /// Load configuration from the specified path.
 ///
 /// # Errors
 ///
 /// Returns an error if the file cannot be read or parsed.
-fn load_config_from_path(path: &Path) -> Result<Config> {
+pub fn load_config_from_path(path: &Path) -> Result<Config> {
     let content = fs::read_to_string(path)
         .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
@@ -22,15 +30,45 @@ fn load_config_from_path(path: &Path) -> Result<Config> {
     Ok(config)
 }
 
+/// Load a config file checked into the repository.
+///
+/// Sensitive settings are dropped unless the file is trusted.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed.
+fn load_repo_config(path: &Path, trust: &TrustStore) -> Result<Config> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+    let mut config: Config = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+
+    let settings = config.sensitive_settings();
+    if !settings.is_empty() && !trust.is_trusted(path, &content) {
+        eprintln!(
+            "Warning: Ignoring {} in untrusted {} (run nrs in a terminal to trust it)",
+            settings.join(", "),
+            path.display()
+        );
+        config.clear_sensitive_settings();
+    }
+
+    Ok(config)
+}
+
 /// Load configuration with proper priority and merging.
 ///
 /// Searches for config files in order of priority (lowest to highest):
-/// 1. `~/.config/nrs/config.toml` (user-level, lowest priority)
-/// 2. `.nrsrc.toml` in project root (project-level)
-/// 3. CLI argument `--config <path>` (highest priority)
+/// 1. `.nrs/team.toml` in project root (team-level, lowest priority)
+/// 2. `~/.config/nrs/config.toml` (user-level)
+/// 3. `.nrsrc.toml` in project root (project-level)
+/// 4. CLI argument `--config <path>` (highest priority)
 ///
 /// Configs are merged with higher priority configs overriding lower priority ones.
 /// Missing config files are handled gracefully (defaults are used).
+/// Sensitive settings in the repository configs (1 and 3) only apply once
+/// the file has been trusted (see [`crate::config::TrustStore`]).
 ///
 /// # Arguments
 ///
@@ -43,12 +81,34 @@ fn load_config_from_path(path: &Path) -> Result<Config> {
 /// Missing default config files are not treated as errors.
 pub fn load_config(cli_config_path: Option<&Path>, project_dir: &Path) -> Result<Config> {
     let mut config = Config::default();
+    let trust = TrustStore::load();
+
+    // Load team config checked into the repository (lowest priority)
+    let team_config_path = project_dir.join(TEAM_CONFIG_FILE);
+    if team_config_path.exists() {
+        match load_repo_config(&team_config_path, &trust) {
+            Ok(team_config) => {
+                debug!("Loaded team config from {}", team_config_path.display());
+                config.merge(team_config);
+            }
+            Err(e) => {
+                eprintln!(
+                    "Warning: Failed to load team config at {}: {}",
+                    team_config_path.display(),
+                    e
+                );
+            }
+        }
+    }
 
-    // Load user-level config (lowest priority)
+    // Load user-level config
     if let Some(user_config_path) = Config::user_config_path() {
         if user_config_path.exists() {
             match load_config_from_path(&user_config_path) {
-                Ok(user_config) => config.merge(user_config),
+                Ok(user_config) => {
+                    debug!("Loaded user config from {}", user_config_path.display());
+                    config.merge(user_config);
+                }
                 Err(e) => {
                     // Log warning but don't fail - use defaults
                     eprintln!(
@@ -62,10 +122,16 @@ pub fn load_config(cli_config_path: Option<&Path>, project_dir: &Path) -> Result
     }
 
     // Load project-level config (medium priority)
-    let project_config_path = project_dir.join(".nrsrc.toml");
+    let project_config_path = project_dir.join(PROJECT_CONFIG_FILE);
     if project_config_path.exists() {
-        match load_config_from_path(&project_config_path) {
-            Ok(project_config) => config.merge(project_config),
+        match load_repo_config(&project_config_path, &trust) {
+            Ok(project_config) => {
+                debug!(
+                    "Loaded project config from {}",
+                    project_config_path.display()
+                );
+                config.merge(project_config);
+            }
             Err(e) => {
                 // Log warning but don't fail - use what we have so far
                 eprintln!(
@@ -85,12 +151,43 @@ pub fn load_config(cli_config_path: Option<&Path>, project_dir: &Path) -> Result
                 cli_path.display()
             )
         })?;
+        debug!("Loaded config from {}", cli_path.display());
         config.merge(cli_config);
     }
 
     Ok(config)
 }
 
+/// Get the config files [`load_config`] reads for a project, in load order.
+///
+/// Only files that exist are listed, except a `--config` path, which is
+/// always read. Without a project, only the user config can apply.
+pub fn config_files(cli_config_path: Option<&Path>, project_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = [
+        project_dir.map(|dir| dir.join(TEAM_CONFIG_FILE)),
+        Config::user_config_path(),
+        project_dir.map(|dir| dir.join(PROJECT_CONFIG_FILE)),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|path| path.exists())
+    .collect();
+    files.extend(cli_config_path.map(Path::to_path_buf));
+    files
+}
+
+/// Generate a JSON Schema describing the configuration file format.
+///
+/// Editors with TOML language support (e.g. Even Better TOML) can use it to
+/// validate and autocomplete `.nrsrc.toml` and `config.toml`.
+pub fn generate_config_schema() -> String {
+    let mut schema = schemars::schema_for!(Config);
+    if let Some(metadata) = schema.schema.metadata.as_mut() {
+        metadata.title = Some("nrs configuration".to_string());
+    }
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
 /// Generate an example configuration file with all options documented.
 pub fn generate_example_config() -> String {
     r#"# nrs Configuration File
@@ -100,12 +197,22 @@ pub fn generate_example_config() -> String {
 # General settings
 [general]
 # Default package manager (overrides auto-detection)
-# Options: "npm", "yarn", "pnpm", "bun"
+# Options: "npm", "yarn", "pnpm", "bun", "deno"
 # runner = "pnpm"
 
 # Default sort mode: "recent", "alpha", "category"
 default_sort = "recent"
 
+# Natural name sorting: case-insensitive and numeric-aware (task2 < task10)
+sort_natural = false
+
+# Experimental: in recent sorting, rank scripts named after the directories
+# of uncommitted changes higher (touching e2e/ boosts test:e2e)
+sort_git_boost = false
+
+# Esc clears the filter, leaves the workspace, then quits
+esc_quits = true
+
 # Column direction: "horizontal", "vertical"
 # horizontal: 1 2 3 4 / 5 6 7 8
 # vertical: 1 4 7 / 2 5 8 / 3 6 9
@@ -117,6 +224,31 @@ show_command_preview = true
 # Maximum items to show (0 = unlimited)
 max_items = 0
 
+# Scripts picked in multi-select run in parallel; limit how many
+# run at the same time (0 = all at once)
+max_parallel = 0
+
+# When packageManager in package.json pins a version that isn't the one
+# installed: "off", "warn", "corepack" (run the pinned version through
+# corepack) or "fail"
+enforce_package_manager = "off"
+
+# Offer to run `<package manager> install` first when node_modules is missing
+check_install = true
+
+# Exit code after running scripts: "script" (the script's), "zero" (always
+# 0, for wrappers that stop on failures) or "remap" (through exit_code_map)
+exit_code = "script"
+
+# With exit_code = "remap": script exit code -> nrs exit code ("*" = any
+# other failure)
+# exit_code_map = { "1" = 0, "*" = 2 }
+
+# How monorepo workspaces are found: "glob" (expand the workspace globs)
+# or "manager" (ask `yarn workspaces list` or `pnpm ls -r`, falling back
+# to the globs)
+workspace_resolution = "glob"
+
 # Filter settings
 [filter]
 # Search in descriptions too
@@ -128,6 +260,10 @@ fuzzy = true
 # Case sensitive search
 case_sensitive = false
 
+# Matching algorithm: "skim" (fuzzy), "substring" (plain, predictable)
+# or "nucleo" (fuzzy, faster on very long lists)
+algorithm = "skim"
+
 # History settings
 [history]
 # Enable history tracking
@@ -139,6 +275,84 @@ max_projects = 100
 # Max scripts per project
 max_scripts = 50
 
+# Max history file size in KB; the oldest entries are pruned beyond it
+# and the full file is kept as a rotated snapshot (0 = unlimited)
+max_size_kb = 1024
+
+# Gzip rotated history snapshots
+compress_rotated = false
+
+# Store project paths as salted hashes, so a synced history.json
+# doesn't reveal where your projects live. The salt is kept in
+# history-salt next to it, which shouldn't be synced
+hash_paths = false
+
+# Run hooks
+[hooks]
+# POST a JSON payload (project, script, status, duration) after each run.
+# Only plain http:// URLs are supported, e.g. a local collector
+# webhook_url = "http://localhost:9000/nrs"
+
+# Script names that trigger the webhook (glob syntax, empty = all)
+patterns = [
+    # "deploy*",
+]
+
+# Resource limits (nice: Unix, memory: Linux)
+[limits]
+# Niceness for all scripts (higher = lower CPU priority)
+# nice = 5
+
+# Max memory for all scripts in MB. Uses a systemd scope where available,
+# otherwise a virtual memory limit (ulimit -v; Linux only)
+# max_memory_mb = 4096
+
+# Per-script limits, by script name or glob pattern
+[limits.scripts]
+# "build*" = { nice = 10, max_memory_mb = 2048 }
+
+# Progress for long runs outside the TUI
+[progress]
+# Show the running script and elapsed time in the terminal title
+terminal_title = true
+
+# Print a heartbeat line every N minutes while a script runs (0 = off)
+heartbeat_minutes = 0
+
+# Tell the terminal when a run finishes: "off", "bell" (shown as activity
+# by tmux), "osc9" or "osc777" (desktop notifications from the terminal)
+notify = "off"
+
+# Watch mode (--watch, or W in the TUI)
+[watch]
+# Files that rerun the script in the TUI and with --watch-failed (glob
+# patterns; empty = all files)
+patterns = []
+
+# Wait for changes to settle before rerunning, in milliseconds
+debounce_ms = 200
+
+# Clear the screen between runs
+clear_screen = true
+
+# Run logs (--log), listed with nrs logs
+[logs]
+# Save the output of every run under ~/.local/state/nrs/logs
+enabled = false
+
+# Log files to keep per script (0 = all)
+keep = 20
+
+# Output pane filters, also applied to its logs
+[output]
+# Lines to hide (regular expressions)
+hide = [
+    # "^webpack.Progress",
+]
+
+# Show a run of hidden lines as one "lines hidden" line
+collapse = true
+
 # Exclude patterns
 [exclude]
 # Global patterns to exclude (glob syntax)
@@ -147,9 +361,16 @@ patterns = [
     # "post*",
 ]
 
+# Workspaces to hide in monorepos, matched against the package name
+# or the path relative to the project root (glob syntax)
+[exclude.workspaces]
+patterns = [
+    # "packages/fixtures-*",
+]
+
 # Appearance settings
 [appearance]
-# Color theme: "default", "minimal", "none"
+# Color theme: "default", "minimal", "none", "colorblind"
 theme = "default"
 
 # Show icons
@@ -161,26 +382,106 @@ show_footer = true
 # Compact mode (less padding)
 compact = false
 
+# Scripts grid density: "comfortable", "compact"
+# comfortable: padded cells with descriptions under script names
+# compact: one line per script, narrower columns
+density = "comfortable"
+
 # Keybindings (advanced)
 [keybindings]
-# Custom keybindings
-# quit = ["q", "Ctrl+c"]
+# Keymap preset: "vim", "emacs", "plain"
+# vim: single-letter actions (s sort, f pin, ...) and hjkl navigation
+# emacs: C-n/C-p/C-b/C-f navigation, Alt+letter actions, typing filters
+# plain: arrow keys, Ctrl+letter actions, typing filters
+preset = "vim"
+
+# Move with hjkl and g/G (arrow keys, Home and End always work)
+vim_keys = true
+
+# Custom keybindings for the script list, replacing the default keys
+# Actions: up, down, left, right, first, last, run, run_in_pane,
+# run_all_workspaces, filter, sort, args, multi_select, pin, details, source,
+# dependencies, new_script, rename, remove, categories, help, quit,
+# workspace_back, global_search, yank_path
+# Keys are like "S", "Ctrl+f", "Alt+Enter", "F5"; chords are space-separated
+# quit = ["q", "Ctrl+q"]
 # run = ["Enter", "o"]
 # filter = ["/", "Ctrl+f"]
+# first = ["g g"]
+# sort = ["Space s"]
 
 # Script customizations
 [scripts]
+# Scripts pinned to the top of the list (names or glob patterns)
+favorites = [
+    # "dev",
+]
+
+# Scripts that ask before running (names or glob patterns)
+confirm = [
+    # "deploy*",
+]
+
+# Scripts that need a terminal, e.g. for prompts; --log and --record leave
+# them out (names or glob patterns)
+interactive = [
+    # "db:shell",
+]
 
 # Custom descriptions for scripts (override package.json)
 [scripts.descriptions]
 # dev = "Start dev server on port 3000"
 # build = "Production build with minification"
 
-# Script aliases
+# Descriptions read from package.json
+[descriptions]
+# Sources, highest priority first: "scripts-info", "ntl", "comments"
+# An empty list shows commands instead of descriptions
+sources = ["scripts-info", "ntl", "comments"]
+
+# Monorepo root scripts listed inside its packages, as root:<name>
+[parent]
+# scripts = ["dev", "build*"]
+
+# Script aliases, shown in the list and run like scripts (nrs t)
 [scripts.aliases]
 # d = "dev"
 # b = "build"
-# t = "test"
+# t = "test -- --watch"
+
+# Category overrides for --summary and the category view
+# (script name or glob pattern -> category)
+[scripts.categories]
+# "e2e*" = "test"
+
+# Timeout and retries for a script (name or glob pattern). A script still
+# running after the timeout is stopped with exit code 124, and failed runs
+# are tried again, waiting longer each time
+# [scripts."test:e2e"]
+# timeout = "5m"
+# retries = 2
+
+# Groups of scripts run as one entry, one after another or in parallel
+[groups]
+# ci = ["lint", "typecheck", "test"]
+# checks = { scripts = ["lint", "format:check"], parallel = true }
+
+# Recipes work like groups, for chains like lint, test, build
+[recipes]
+# ship = ["lint", "test", "build"]
+
+# Scripts that mustn't run at the same time; nrs offers to stop the
+# running one first (names or glob patterns)
+[concurrency]
+# servers = ["dev", "dev:https"]
+
+# Profiles, picked with --profile <name> or NRS_PROFILE; each can set
+# appearance, exclude, default_sort and keybindings
+# [profile.demo]
+# default_sort = "alpha"
+#
+# [profile.demo.appearance]
+# density = "compact"
 "#
     .to_string()
 }
@@ -291,6 +592,25 @@ runner = "pnpm"
         assert!(config.appearance.icons);
     }
 
+    #[test]
+    fn test_repo_config_webhook_needs_trust() {
+        let temp = create_temp_dir();
+        let path = temp.path().join(PROJECT_CONFIG_FILE);
+        let content = "[hooks]\nwebhook_url = \"http://localhost:9000\"\n";
+        fs::write(&path, content).unwrap();
+
+        let config = load_repo_config(&path, &TrustStore::default()).unwrap();
+        assert!(config.hooks.webhook_url.is_none());
+
+        let mut trust = TrustStore::default();
+        trust.trust(&path, content);
+        let config = load_repo_config(&path, &trust).unwrap();
+        assert_eq!(
+            config.hooks.webhook_url.as_deref(),
+            Some("http://localhost:9000")
+        );
+    }
+
     #[test]
     fn test_load_cli_config_file_not_found() {
         let temp = create_temp_dir();
@@ -390,7 +710,31 @@ b = "build"
             config.scripts.descriptions.get("dev"),
             Some(&"Start development server".to_string())
         );
-        assert_eq!(config.scripts.aliases.get("d"), Some(&"dev".to_string()));
+        assert_eq!(config.scripts.aliases.get("d"), Some("dev"));
+    }
+
+    #[test]
+    fn test_generate_config_schema() {
+        let schema: serde_json::Value = serde_json::from_str(&generate_config_schema()).unwrap();
+
+        assert_eq!(schema["title"], "nrs configuration");
+        let properties = schema["properties"].as_object().unwrap();
+        for section in [
+            "general",
+            "filter",
+            "history",
+            "hooks",
+            "limits",
+            "progress",
+            "appearance",
+            "scripts",
+        ] {
+            assert!(properties.contains_key(section), "missing {section}");
+        }
+
+        // Enums use the same lowercase names as the TOML file
+        let sort = &schema["definitions"]["SortMode"];
+        assert!(sort.to_string().contains("\"recent\""));
     }
 
     #[test]
@@ -402,6 +746,7 @@ b = "build"
         assert!(example.contains("[filter]"));
         assert!(example.contains("[history]"));
         assert!(example.contains("[exclude]"));
+        assert!(example.contains("[exclude.workspaces]"));
         assert!(example.contains("[appearance]"));
         assert!(example.contains("[keybindings]"));
         assert!(example.contains("[scripts]"));
@@ -425,7 +770,8 @@ b = "build"
         assert!(config.history.enabled);
         assert!(config.exclude.patterns.is_empty());
         assert!(config.appearance.icons);
-        assert!(config.keybindings.quit.is_empty());
+        assert!(config.keybindings.vim_keys);
+        assert!(config.keybindings.bindings.is_empty());
         assert!(config.scripts.descriptions.is_empty());
     }
 }
@@ -6,10 +6,20 @@
 //! - User-level `~/.config/nrs/config.toml`
 
 pub mod file;
+mod trust;
 mod types;
 
-pub use file::{generate_example_config, load_config};
+pub use file::{
+    config_files, generate_config_schema, generate_example_config, load_config,
+    load_config_from_path, PROJECT_CONFIG_FILE, TEAM_CONFIG_FILE,
+};
+pub use trust::{trust_configs, untrusted_configs, TrustStore, UntrustedConfig};
 pub use types::{
-    AppearanceConfig, ColumnDirection, Config, ExcludeConfig, FilterConfig, GeneralConfig,
-    HistoryConfig, KeybindingsConfig, ScriptsConfig, SortMode, Theme,
+    AliasConfig, AppearanceConfig, ColumnDirection, CompletionNotice, ConcurrencyConfig, Config,
+    Density, DescriptionsConfig, ExcludeConfig, ExitCodePolicy, FilterAlgorithm, FilterConfig,
+    GeneralConfig, GroupConfig, GroupsConfig, HistoryConfig, HooksConfig, KeyAction,
+    KeybindingsConfig, KeymapPreset, LimitsConfig, LogsConfig, OutputConfig,
+    PackageManagerEnforcement, ParentConfig, ProfileConfig, ProgressConfig, ScriptLimits,
+    ScriptPolicy, ScriptsConfig, SortMode, Theme, WatchConfig, WorkspaceExcludeConfig,
+    PROFILE_ENV_VAR,
 };
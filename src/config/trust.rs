@@ -0,0 +1,176 @@
+//! Trust for config files checked into a repository.
+//!
+//! `.nrsrc.toml` and `.nrs/team.toml` come with the repository, so a freshly
+//! cloned project could point settings like `hooks.webhook_url` anywhere.
+//! Like direnv, such settings are ignored until the user trusts the file.
+//! Trust is tied to the file contents, so edited files must be trusted again.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::trace;
+
+use super::file::{PROJECT_CONFIG_FILE, TEAM_CONFIG_FILE};
+use super::types::Config;
+
+/// Repository config files that were trusted, with a hash of their contents.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustStore {
+    /// SHA-256 of the trusted contents per config file path.
+    #[serde(default)]
+    pub configs: BTreeMap<PathBuf, String>,
+}
+
+impl TrustStore {
+    /// Get the trust store file path.
+    pub fn file_path() -> Option<PathBuf> {
+        Config::user_config_path().map(|p| p.with_file_name("trusted.json"))
+    }
+
+    /// Load the trust store from the default location.
+    ///
+    /// Missing or unreadable files give an empty store, so nothing is trusted.
+    pub fn load() -> Self {
+        let Some(path) = Self::file_path() else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                eprintln!("Warning: Trust store {} is corrupt: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save the trust store to the default location.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path().context("Could not determine config directory")?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize trust store")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write trust store to {}", path.display()))?;
+        trace!("Saved trust store to {}", path.display());
+
+        Ok(())
+    }
+
+    /// Check if a config file with the given contents is trusted.
+    pub fn is_trusted(&self, path: &Path, content: &str) -> bool {
+        self.configs
+            .get(path)
+            .is_some_and(|hash| *hash == content_hash(content))
+    }
+
+    /// Trust a config file with the given contents.
+    pub fn trust(&mut self, path: &Path, content: &str) {
+        self.configs
+            .insert(path.to_path_buf(), content_hash(content));
+    }
+}
+
+/// A repository config file with settings that need trust.
+#[derive(Debug, Clone)]
+pub struct UntrustedConfig {
+    /// Path to the config file.
+    pub path: PathBuf,
+    /// The settings that are ignored until the file is trusted.
+    pub settings: Vec<&'static str>,
+    content: String,
+}
+
+/// Find the repository config files in `project_dir` that need trust.
+///
+/// Files that can't be read or parsed are skipped; loading reports those.
+pub fn untrusted_configs(project_dir: &Path, store: &TrustStore) -> Vec<UntrustedConfig> {
+    [TEAM_CONFIG_FILE, PROJECT_CONFIG_FILE]
+        .iter()
+        .filter_map(|file| {
+            let path = project_dir.join(file);
+            let content = fs::read_to_string(&path).ok()?;
+            let config: Config = toml::from_str(&content).ok()?;
+            let settings = config.sensitive_settings();
+            if settings.is_empty() || store.is_trusted(&path, &content) {
+                return None;
+            }
+            Some(UntrustedConfig {
+                path,
+                settings,
+                content,
+            })
+        })
+        .collect()
+}
+
+/// Trust config files and save the trust store.
+pub fn trust_configs(configs: &[UntrustedConfig]) -> Result<()> {
+    let mut store = TrustStore::load();
+    for config in configs {
+        store.trust(&config.path, &config.content);
+    }
+    store.save()
+}
+
+/// Hash config file contents.
+fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEBHOOK_CONFIG: &str = "[hooks]\nwebhook_url = \"http://example.com\"\n";
+
+    #[test]
+    fn test_trust_is_tied_to_contents() {
+        let mut store = TrustStore::default();
+        let path = Path::new("/work/app/.nrsrc.toml");
+        assert!(!store.is_trusted(path, WEBHOOK_CONFIG));
+
+        store.trust(path, WEBHOOK_CONFIG);
+        assert!(store.is_trusted(path, WEBHOOK_CONFIG));
+        assert!(!store.is_trusted(path, "[hooks]\nwebhook_url = \"http://evil\"\n"));
+        assert!(!store.is_trusted(Path::new("/other/.nrsrc.toml"), WEBHOOK_CONFIG));
+    }
+
+    #[test]
+    fn test_untrusted_configs() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(PROJECT_CONFIG_FILE), WEBHOOK_CONFIG).unwrap();
+
+        let untrusted = untrusted_configs(dir.path(), &TrustStore::default());
+        assert_eq!(untrusted.len(), 1);
+        assert_eq!(untrusted[0].settings, ["hooks.webhook_url"]);
+
+        let mut store = TrustStore::default();
+        store.trust(&dir.path().join(PROJECT_CONFIG_FILE), WEBHOOK_CONFIG);
+        assert!(untrusted_configs(dir.path(), &store).is_empty());
+    }
+
+    #[test]
+    fn test_configs_without_sensitive_settings_need_no_trust() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "[general]\nmax_items = 10\n",
+        )
+        .unwrap();
+
+        assert!(untrusted_configs(dir.path(), &TrustStore::default()).is_empty());
+    }
+}
@@ -1,14 +1,21 @@
 //! Configuration type definitions.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::time::Duration;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tracing::debug;
 
-use crate::package::Runner;
+use crate::error::{exit_code, NrsError};
+use crate::package::{
+    DescriptionSource, Runner, Script, ScriptAlias, ScriptGroup, Scripts, WorkspaceResolution,
+};
+use crate::utils::{format_duration, global_config_file};
 
 /// Sort mode for script display.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SortMode {
     /// Sort by most recently used.
@@ -20,8 +27,21 @@ pub enum SortMode {
     Category,
 }
 
+/// Matching algorithm for the script filter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAlgorithm {
+    /// Fuzzy matching with the skim algorithm.
+    #[default]
+    Skim,
+    /// Plain substring matching.
+    Substring,
+    /// Fuzzy matching with nucleo, faster on very long lists.
+    Nucleo,
+}
+
 /// Column direction for grid layout.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ColumnDirection {
     /// Fill rows first (1 2 3 4 / 5 6 7 8).
@@ -32,7 +52,7 @@ pub enum ColumnDirection {
 }
 
 /// Color theme for the TUI.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Theme {
     /// Full color theme.
@@ -42,10 +62,84 @@ pub enum Theme {
     Minimal,
     /// No colors (monochrome).
     None,
+    /// Color-blind friendly palette (tuned for deuteranopia).
+    Colorblind,
+}
+
+/// Density of the scripts grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    /// Padded cells with descriptions shown under script names.
+    #[default]
+    Comfortable,
+    /// One line per script with narrow columns, to fit more scripts.
+    Compact,
+}
+
+impl Density {
+    /// Minimum width of a grid column.
+    pub fn min_column_width(self) -> u16 {
+        match self {
+            Density::Comfortable => 28,
+            Density::Compact => 18,
+        }
+    }
+
+    /// Blank cells left between adjacent grid columns.
+    pub fn cell_padding(self) -> u16 {
+        match self {
+            Density::Comfortable => 2,
+            Density::Compact => 0,
+        }
+    }
+
+    /// Whether descriptions are rendered inline under script names.
+    pub fn inline_descriptions(self) -> bool {
+        matches!(self, Density::Comfortable)
+    }
+
+    /// Number of terminal rows used by each grid row.
+    pub fn row_height(self) -> u16 {
+        if self.inline_descriptions() {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// What to do when the installed package manager isn't the version pinned
+/// by `packageManager` in package.json.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManagerEnforcement {
+    /// Don't check the version.
+    #[default]
+    Off,
+    /// Warn and run the installed version.
+    Warn,
+    /// Run the pinned version through corepack.
+    Corepack,
+    /// Refuse to run scripts.
+    Fail,
+}
+
+/// Exit code nrs ends with after running scripts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExitCodePolicy {
+    /// The script's exit code.
+    #[default]
+    Script,
+    /// Always 0, once failures are reported.
+    Zero,
+    /// The script's exit code, changed by `exit_code_map`.
+    Remap,
 }
 
 /// General configuration settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GeneralConfig {
     /// Override package manager detection.
     #[serde(default)]
@@ -62,6 +156,123 @@ pub struct GeneralConfig {
     /// Maximum items to show (0 = unlimited).
     #[serde(default)]
     pub max_items: usize,
+    /// Use case-insensitive, numeric-aware ordering for name sorting.
+    #[serde(default)]
+    pub sort_natural: Option<bool>,
+    /// Quit with Esc in normal mode once there is nothing left to clear.
+    #[serde(default)]
+    pub esc_quits: Option<bool>,
+    /// Scripts picked in multi-select that run at the same time (0 = all).
+    #[serde(default)]
+    pub max_parallel: Option<usize>,
+    /// Experimental: rank scripts related to uncommitted git changes higher
+    /// in recent sorting.
+    #[serde(default)]
+    pub sort_git_boost: Option<bool>,
+    /// Check the package manager version pinned by `packageManager` before
+    /// running scripts.
+    #[serde(default)]
+    pub enforce_package_manager: Option<PackageManagerEnforcement>,
+    /// Offer to install dependencies before running scripts when
+    /// `node_modules` is missing.
+    #[serde(default)]
+    pub check_install: Option<bool>,
+    /// Exit code to end with after running scripts.
+    #[serde(default)]
+    pub exit_code: Option<ExitCodePolicy>,
+    /// Exit codes to end with instead of a script's, with `exit_code =
+    /// "remap"` (e.g. "1" = 0; "*" for any other failure).
+    #[serde(default, deserialize_with = "exit_code_map::deserialize")]
+    pub exit_code_map: Option<BTreeMap<String, i32>>,
+    /// Find monorepo workspaces from their globs, or ask yarn or pnpm.
+    #[serde(default)]
+    pub workspace_resolution: Option<WorkspaceResolution>,
+}
+
+impl GeneralConfig {
+    /// Check if names sort case-insensitively and numeric-aware (off unless set).
+    pub fn sort_natural(&self) -> bool {
+        self.sort_natural.unwrap_or(false)
+    }
+
+    /// Check if Esc quits once there is nothing left to clear (on unless set).
+    pub fn esc_quits(&self) -> bool {
+        self.esc_quits.unwrap_or(true)
+    }
+
+    /// Get the scripts run at the same time from multi-select (0 = all).
+    pub fn max_parallel(&self) -> usize {
+        self.max_parallel.unwrap_or(0)
+    }
+
+    /// Check if git changes boost recent sorting (off unless set).
+    pub fn sort_git_boost(&self) -> bool {
+        self.sort_git_boost.unwrap_or(false)
+    }
+
+    /// Get how the pinned package manager version is enforced.
+    pub fn enforce_package_manager(&self) -> PackageManagerEnforcement {
+        self.enforce_package_manager.unwrap_or_default()
+    }
+
+    /// Check if a missing `node_modules` is offered to install (on unless set).
+    pub fn check_install(&self) -> bool {
+        self.check_install.unwrap_or(true)
+    }
+
+    /// Get how monorepo workspaces are found.
+    pub fn workspace_resolution(&self) -> WorkspaceResolution {
+        self.workspace_resolution.unwrap_or_default()
+    }
+
+    /// Get the exit code to end with after scripts ended with `code`.
+    ///
+    /// Runs stopped with Ctrl+C always end with their own code, so wrappers
+    /// can tell them from failures.
+    pub fn exit_code_for(&self, code: i32) -> i32 {
+        if code == exit_code::INTERRUPTED {
+            return code;
+        }
+        match self.exit_code.unwrap_or_default() {
+            ExitCodePolicy::Script => code,
+            ExitCodePolicy::Zero => exit_code::SUCCESS,
+            ExitCodePolicy::Remap => self
+                .exit_code_map
+                .as_ref()
+                .and_then(|map| {
+                    map.get(&code.to_string())
+                        .or_else(|| map.get("*").filter(|_| code != 0))
+                })
+                .copied()
+                .unwrap_or(code),
+        }
+    }
+}
+
+/// Exit code tables, checked when the config is read.
+mod exit_code_map {
+    use std::collections::BTreeMap;
+
+    use serde::{de, Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<BTreeMap<String, i32>>, D::Error> {
+        let map = BTreeMap::<String, i32>::deserialize(deserializer)?;
+        for (from, to) in &map {
+            if from != "*" && from.parse::<u8>().is_err() {
+                return Err(de::Error::custom(format!(
+                    "invalid exit code \"{from}\", expected 0-255 or \"*\""
+                )));
+            }
+            if u8::try_from(*to).is_err() {
+                return Err(de::Error::custom(format!(
+                    "invalid exit code {to}, expected 0-255"
+                )));
+            }
+        }
+        Ok(Some(map))
+    }
 }
 
 impl Default for GeneralConfig {
@@ -72,12 +283,21 @@ impl Default for GeneralConfig {
             column_direction: ColumnDirection::default(),
             show_command_preview: true,
             max_items: 0,
+            sort_natural: None,
+            esc_quits: None,
+            max_parallel: None,
+            sort_git_boost: None,
+            enforce_package_manager: None,
+            check_install: None,
+            exit_code: None,
+            exit_code_map: None,
+            workspace_resolution: None,
         }
     }
 }
 
 /// Filter configuration settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FilterConfig {
     /// Search in descriptions too.
     #[serde(default = "default_true")]
@@ -88,6 +308,22 @@ pub struct FilterConfig {
     /// Case sensitive search.
     #[serde(default)]
     pub case_sensitive: bool,
+    /// Matching algorithm: skim, substring or nucleo.
+    #[serde(default)]
+    pub algorithm: FilterAlgorithm,
+}
+
+impl FilterConfig {
+    /// Get the algorithm to filter with.
+    ///
+    /// `fuzzy = false` means substring matching, whatever the algorithm.
+    pub fn matching_algorithm(&self) -> FilterAlgorithm {
+        if self.fuzzy {
+            self.algorithm
+        } else {
+            FilterAlgorithm::Substring
+        }
+    }
 }
 
 impl Default for FilterConfig {
@@ -96,12 +332,13 @@ impl Default for FilterConfig {
             search_descriptions: true,
             fuzzy: true,
             case_sensitive: false,
+            algorithm: FilterAlgorithm::default(),
         }
     }
 }
 
 /// History configuration settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HistoryConfig {
     /// Enable history tracking.
     #[serde(default = "default_true")]
@@ -112,6 +349,15 @@ pub struct HistoryConfig {
     /// Max scripts per project.
     #[serde(default = "default_max_scripts")]
     pub max_scripts: usize,
+    /// Max size of the history file in KB (0 = unlimited).
+    #[serde(default = "default_max_size_kb")]
+    pub max_size_kb: usize,
+    /// Gzip snapshots rotated out when the size limit is hit.
+    #[serde(default)]
+    pub compress_rotated: bool,
+    /// Store project paths as salted hashes instead of plain paths.
+    #[serde(default)]
+    pub hash_paths: bool,
 }
 
 impl Default for HistoryConfig {
@@ -120,20 +366,298 @@ impl Default for HistoryConfig {
             enabled: true,
             max_projects: 100,
             max_scripts: 50,
+            max_size_kb: 1024,
+            compress_rotated: false,
+            hash_paths: false,
+        }
+    }
+}
+
+/// Run hooks configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct HooksConfig {
+    /// URL that receives a JSON POST after matching script runs.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Glob patterns of script names that trigger the webhook (empty = all).
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+/// How the terminal is told that a run finished.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionNotice {
+    /// No notice.
+    #[default]
+    Off,
+    /// Ring the terminal bell, which tmux shows as window activity.
+    Bell,
+    /// An OSC 9 notification (iTerm2, WezTerm, Windows Terminal, kitty).
+    Osc9,
+    /// An OSC 777 notification (rxvt, foot, GNOME Terminal and other VTE
+    /// terminals).
+    Osc777,
+}
+
+/// Progress reporting for long runs outside the TUI.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProgressConfig {
+    /// Show the running script and elapsed time in the terminal title.
+    #[serde(default = "default_true")]
+    pub terminal_title: bool,
+    /// Print a heartbeat line every N minutes (0 = off).
+    #[serde(default)]
+    pub heartbeat_minutes: u64,
+    /// Tell the terminal when a run finishes, also for output pane runs.
+    #[serde(default)]
+    pub notify: CompletionNotice,
+}
+
+impl Default for ProgressConfig {
+    fn default() -> Self {
+        Self {
+            terminal_title: true,
+            heartbeat_minutes: 0,
+            notify: CompletionNotice::default(),
+        }
+    }
+}
+
+/// Re-running scripts when files change.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WatchConfig {
+    /// Glob patterns of the files to watch in the TUI and with `--watch-failed`
+    /// (empty = all files).
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Wait for changes to settle this long before rerunning, in milliseconds.
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+    /// Clear the screen between runs.
+    #[serde(default = "default_true")]
+    pub clear_screen: bool,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            debounce_ms: default_debounce_ms(),
+            clear_screen: true,
+        }
+    }
+}
+
+/// Copying script output to log files.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LogsConfig {
+    /// Save the output of every run (same as --log).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Log files to keep per script (0 = all).
+    #[serde(default = "default_keep_logs")]
+    pub keep: usize,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keep: default_keep_logs(),
+        }
+    }
+}
+
+/// Filtering of script output shown in the output pane.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OutputConfig {
+    /// Regular expressions of lines to hide (e.g. "^webpack.Progress").
+    #[serde(default, deserialize_with = "regex_list::deserialize")]
+    pub hide: Vec<String>,
+    /// Show a run of hidden lines as one "lines hidden" line.
+    #[serde(default = "default_true")]
+    pub collapse: bool,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            hide: Vec::new(),
+            collapse: true,
+        }
+    }
+}
+
+/// Regular expressions, checked when the config is read.
+mod regex_list {
+    use serde::{de, Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<String>, D::Error> {
+        let patterns = Vec::<String>::deserialize(deserializer)?;
+        for pattern in &patterns {
+            regex::Regex::new(pattern).map_err(|err| {
+                de::Error::custom(format!("invalid pattern \"{pattern}\": {err}"))
+            })?;
+        }
+        Ok(patterns)
+    }
+}
+
+/// Environment variable picking a profile when `--profile` isn't given.
+pub const PROFILE_ENV_VAR: &str = "NRS_PROFILE";
+
+/// Settings a named profile overrides (`[profile.<name>]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ProfileConfig {
+    /// Appearance settings.
+    #[serde(default)]
+    pub appearance: Option<AppearanceConfig>,
+    /// Exclude patterns, instead of the configured ones.
+    #[serde(default)]
+    pub exclude: Option<ExcludeConfig>,
+    /// Default sort mode.
+    #[serde(default)]
+    pub default_sort: Option<SortMode>,
+    /// Keybindings settings.
+    #[serde(default)]
+    pub keybindings: Option<KeybindingsConfig>,
+}
+
+/// Where script descriptions in package.json are read from.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DescriptionsConfig {
+    /// Sources to read, highest priority first (empty = none).
+    #[serde(default = "default_description_sources")]
+    pub sources: Vec<DescriptionSource>,
+}
+
+fn default_description_sources() -> Vec<DescriptionSource> {
+    DescriptionSource::ALL.to_vec()
+}
+
+impl Default for DescriptionsConfig {
+    fn default() -> Self {
+        Self {
+            sources: default_description_sources(),
+        }
+    }
+}
+
+/// Root scripts listed when running nrs inside a monorepo package.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ParentConfig {
+    /// Root scripts to list, as `root:<name>` entries that run from the
+    /// monorepo root (names or glob patterns, empty = none).
+    #[serde(default)]
+    pub scripts: Vec<String>,
+}
+
+/// Resource limits for a script run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptLimits {
+    /// Niceness to run with (higher = lower priority).
+    #[serde(default)]
+    pub nice: Option<i32>,
+    /// Maximum memory in megabytes.
+    #[serde(default)]
+    pub max_memory_mb: Option<u64>,
+    /// How long the script may run, from its `[scripts.<name>]` table.
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+    /// Times to run the script again after it fails, from its
+    /// `[scripts.<name>]` table.
+    #[serde(skip)]
+    pub retries: u32,
+    /// Whether the script keeps the terminal, so it's never logged or
+    /// recorded, from `scripts.interactive`.
+    #[serde(skip)]
+    pub interactive: bool,
+}
+
+impl ScriptLimits {
+    /// Check if no limits are set.
+    pub fn is_empty(&self) -> bool {
+        self.nice.is_none()
+            && self.max_memory_mb.is_none()
+            && self.timeout.is_none()
+            && self.retries == 0
+    }
+
+    /// Describe the limits for display (e.g. "nice 10, max memory 2048 MB").
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(nice) = self.nice {
+            parts.push(format!("nice {nice}"));
+        }
+        if let Some(mb) = self.max_memory_mb {
+            parts.push(format!("max memory {mb} MB"));
+        }
+        if let Some(timeout) = self.timeout {
+            parts.push(format!("timeout {}", format_duration(timeout)));
+        }
+        match self.retries {
+            0 => {}
+            1 => parts.push("1 retry".to_string()),
+            n => parts.push(format!("{n} retries")),
+        }
+        parts.join(", ")
+    }
+}
+
+/// Resource limits configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LimitsConfig {
+    /// Default niceness for all scripts.
+    #[serde(default)]
+    pub nice: Option<i32>,
+    /// Default maximum memory in megabytes for all scripts.
+    #[serde(default)]
+    pub max_memory_mb: Option<u64>,
+    /// Per-script limits, keyed by script name or glob pattern.
+    #[serde(default)]
+    pub scripts: HashMap<String, ScriptLimits>,
+}
+
+impl LimitsConfig {
+    /// Get the limits for a script.
+    ///
+    /// Unset values fall back to the defaults.
+    pub fn for_script(&self, name: &str) -> ScriptLimits {
+        let specific = lookup_by_script_pattern(&self.scripts, name)
+            .copied()
+            .unwrap_or_default();
+        ScriptLimits {
+            nice: specific.nice.or(self.nice),
+            max_memory_mb: specific.max_memory_mb.or(self.max_memory_mb),
+            ..ScriptLimits::default()
         }
     }
 }
 
 /// Exclude patterns configuration.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ExcludeConfig {
     /// Glob patterns to exclude.
     #[serde(default)]
     pub patterns: Vec<String>,
+    /// Workspace exclusion settings.
+    #[serde(default)]
+    pub workspaces: WorkspaceExcludeConfig,
+}
+
+/// Workspace exclude patterns configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceExcludeConfig {
+    /// Glob patterns matched against workspace names and relative paths.
+    #[serde(default)]
+    pub patterns: Vec<String>,
 }
 
 /// Appearance configuration settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AppearanceConfig {
     /// Color theme.
     #[serde(default)]
@@ -147,6 +671,9 @@ pub struct AppearanceConfig {
     /// Compact mode (less padding).
     #[serde(default)]
     pub compact: bool,
+    /// Scripts grid density.
+    #[serde(default)]
+    pub density: Density,
 }
 
 impl Default for AppearanceConfig {
@@ -156,37 +683,455 @@ impl Default for AppearanceConfig {
             icons: true,
             show_footer: true,
             compact: false,
+            density: Density::default(),
+        }
+    }
+}
+
+/// Built-in keymap presets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapPreset {
+    /// Single-letter actions and hjkl navigation.
+    #[default]
+    Vim,
+    /// Ctrl+N/P/B/F navigation and Alt+letter actions; typing filters.
+    Emacs,
+    /// Arrow keys and Ctrl+letter actions; typing filters.
+    Plain,
+}
+
+/// Script list actions that can be bound to keys.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    /// Move up (k).
+    Up,
+    /// Move down (j).
+    Down,
+    /// Move left in the grid (h).
+    Left,
+    /// Move right in the grid (l).
+    Right,
+    /// Go to the first script (g).
+    First,
+    /// Go to the last script (G).
+    Last,
+    /// Run the selected script (Enter, o).
+    Run,
+    /// Run the selected script in the output pane (r).
+    RunInPane,
+    /// Run the selected script in every workspace (A).
+    RunAllWorkspaces,
+    /// Run the selected script again whenever files change (W).
+    Watch,
+    /// Filter scripts (/).
+    Filter,
+    /// Cycle the sort mode (s).
+    Sort,
+    /// Collapse or expand a category group (Tab).
+    ToggleGroup,
+    /// Run with arguments (a).
+    Args,
+    /// Pick several scripts to run (m).
+    MultiSelect,
+    /// Pin or unpin the selected script (f).
+    Pin,
+    /// Show script details (v).
+    Details,
+    /// Show the command full screen, one chained command per line (z).
+    Preview,
+    /// Show the script in package.json (p).
+    Source,
+    /// Show the scripts the script runs (d).
+    Dependencies,
+    /// Add a script (n).
+    NewScript,
+    /// Rename the script (e).
+    Rename,
+    /// Remove the script (x).
+    Remove,
+    /// Browse categories (c).
+    Categories,
+    /// Show the package's entry points, exports and bin entries (i).
+    ProjectInfo,
+    /// Show how often scripts run, fail and how long they take (u).
+    Stats,
+    /// Toggle help (?).
+    Help,
+    /// Quit (q).
+    Quit,
+    /// Back to the workspace list (w).
+    WorkspaceBack,
+    /// Search the scripts of every workspace (F).
+    GlobalSearch,
+    /// Print the workspace path and quit (y).
+    YankPath,
+}
+
+impl KeyAction {
+    /// Get the name the action is bound by in `[keybindings]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::First => "first",
+            Self::Last => "last",
+            Self::Run => "run",
+            Self::RunInPane => "run_in_pane",
+            Self::RunAllWorkspaces => "run_all_workspaces",
+            Self::Watch => "watch",
+            Self::Filter => "filter",
+            Self::Sort => "sort",
+            Self::ToggleGroup => "toggle_group",
+            Self::Args => "args",
+            Self::MultiSelect => "multi_select",
+            Self::Pin => "pin",
+            Self::Details => "details",
+            Self::Preview => "preview",
+            Self::Source => "source",
+            Self::Dependencies => "dependencies",
+            Self::NewScript => "new_script",
+            Self::Rename => "rename",
+            Self::Remove => "remove",
+            Self::Categories => "categories",
+            Self::ProjectInfo => "project_info",
+            Self::Stats => "stats",
+            Self::Help => "help",
+            Self::Quit => "quit",
+            Self::WorkspaceBack => "workspace_back",
+            Self::GlobalSearch => "global_search",
+            Self::YankPath => "yank_path",
         }
     }
 }
 
 /// Keybindings configuration.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct KeybindingsConfig {
-    /// Quit keys (e.g., ["q", "Ctrl+c"]).
+    /// Keymap preset ("vim", "emacs" or "plain").
     #[serde(default)]
-    pub quit: Vec<String>,
-    /// Run script keys.
-    #[serde(default)]
-    pub run: Vec<String>,
-    /// Enter filter mode keys.
-    #[serde(default)]
-    pub filter: Vec<String>,
+    pub preset: KeymapPreset,
+    /// Move with hjkl and g/G (arrow keys, Home and End always work).
+    #[serde(default = "default_true")]
+    pub vim_keys: bool,
+    /// Keys for script list actions, replacing their default keys
+    /// (e.g. sort = ["S"], first = ["g g"], quit = ["Ctrl+q"]).
+    #[serde(flatten)]
+    pub bindings: BTreeMap<KeyAction, Vec<String>>,
+}
+
+impl Default for KeybindingsConfig {
+    fn default() -> Self {
+        Self {
+            preset: KeymapPreset::default(),
+            vim_keys: true,
+            bindings: BTreeMap::new(),
+        }
+    }
 }
 
 /// Scripts configuration for custom descriptions and aliases.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ScriptsConfig {
     /// Custom descriptions for scripts (overrides package.json).
     #[serde(default)]
     pub descriptions: HashMap<String, String>,
-    /// Script aliases (alias -> script name).
+    /// Script aliases (alias -> script and arguments).
+    #[serde(default)]
+    pub aliases: AliasConfig,
+    /// Scripts pinned to the top of the list (names or glob patterns).
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    /// Scripts that ask for confirmation before running (names or glob patterns).
+    #[serde(default)]
+    pub confirm: Vec<String>,
+    /// Scripts that need a terminal, so `--log` and `--record` leave them
+    /// out (names or glob patterns).
+    #[serde(default)]
+    pub interactive: Vec<String>,
+    /// Category overrides (script name or glob pattern -> category).
+    #[serde(default)]
+    pub categories: HashMap<String, String>,
+    /// Timeout and retries, from `[scripts.<name>]` tables (script name or
+    /// glob pattern -> policy).
+    #[serde(flatten)]
+    pub policies: HashMap<String, ScriptPolicy>,
+}
+
+impl ScriptsConfig {
+    /// Apply configured descriptions and categories to parsed scripts.
+    pub fn apply(&self, scripts: &mut Scripts) {
+        for script in scripts.iter_mut() {
+            if let Some(description) = self.descriptions.get(script.name()) {
+                script.set_description(description.clone());
+            }
+            if let Some(category) = self.category_for(script.name()) {
+                script.set_category(category);
+            }
+        }
+    }
+
+    /// Check if a script is a favorite.
+    pub fn is_favorite(&self, name: &str) -> bool {
+        matches_any(&self.favorites, name)
+    }
+
+    /// Check if a script asks for confirmation before running.
+    pub fn needs_confirm(&self, name: &str) -> bool {
+        matches_any(&self.confirm, name)
+    }
+
+    /// Check if a script needs a terminal.
+    pub fn is_interactive(&self, name: &str) -> bool {
+        matches_any(&self.interactive, name)
+    }
+
+    /// Get the timeout and retries of a script.
+    pub fn policy_for(&self, name: &str) -> ScriptPolicy {
+        lookup_by_script_pattern(&self.policies, name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the configured category of a script.
+    pub fn category_for(&self, name: &str) -> Option<&str> {
+        lookup_by_script_pattern(&self.categories, name).map(String::as_str)
+    }
+}
+
+/// Timeout and retries for a script.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptPolicy {
+    /// Kill the script after this long (e.g. "30s", "5m", "1h30m").
+    #[serde(default, with = "duration_text")]
+    #[schemars(with = "Option<String>")]
+    pub timeout: Option<Duration>,
+    /// Times to run the script again after it fails or times out.
     #[serde(default)]
-    pub aliases: HashMap<String, String>,
+    pub retries: u32,
+}
+
+/// Durations written as text, like "5m".
+mod duration_text {
+    use std::time::Duration;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::utils::{format_duration, parse_duration};
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_str(&format_duration(*duration)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_duration(&text).map(Some).ok_or_else(|| {
+            de::Error::custom(format!(
+                "invalid duration \"{text}\", expected e.g. \"30s\", \"5m\" or \"1h30m\""
+            ))
+        })
+    }
+}
+
+/// Script aliases, by name (e.g. t = "test -- --watch").
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct AliasConfig {
+    /// Script and arguments each alias runs.
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl AliasConfig {
+    /// Get the definition of an alias.
+    pub fn get(&self, alias: &str) -> Option<&str> {
+        self.aliases.get(alias).map(String::as_str)
+    }
+
+    /// Get the script an alias runs and its arguments.
+    ///
+    /// `"test -- --watch"` and `"test --watch"` both run `test` with
+    /// `--watch`, like `npm run` would.
+    pub fn resolve(&self, alias: &str) -> Option<ScriptAlias> {
+        let definition = self.get(alias)?.trim();
+        let (script, args) = definition
+            .split_once(char::is_whitespace)
+            .unwrap_or((definition, ""));
+        if script.is_empty() {
+            return None;
+        }
+        let args = args.trim();
+        let args = match args.strip_prefix("--") {
+            Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => rest.trim(),
+            _ => args,
+        };
+        Some(ScriptAlias {
+            script: script.to_string(),
+            args: (!args.is_empty()).then(|| args.to_string()),
+        })
+    }
+
+    /// Add the aliases to parsed scripts as entries that run their script.
+    ///
+    /// Aliases named like a script, or of scripts that don't exist, aren't
+    /// added.
+    pub fn apply(&self, scripts: &mut Scripts) {
+        for name in self.aliases.keys() {
+            if scripts.get(name).is_some() {
+                debug!("Alias {name} is shadowed by a script");
+                continue;
+            }
+            let Some(alias) = self.resolve(name) else {
+                continue;
+            };
+            let Some(target) = scripts.get(&alias.script).filter(|s| s.alias().is_none()) else {
+                debug!("Alias {name} runs unknown script {}", alias.script);
+                continue;
+            };
+            let entry = Script::from_alias(name, target, alias);
+            scripts.add(entry);
+        }
+    }
+}
+
+/// A group of scripts run as one entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum GroupConfig {
+    /// Scripts run one after another (e.g. ci = ["lint", "test"]).
+    Scripts(Vec<String>),
+    /// Scripts with run options (e.g. ci = { scripts = ["lint", "test"], parallel = true }).
+    Detailed {
+        /// Scripts in the group.
+        scripts: Vec<String>,
+        /// Run the scripts at the same time instead of one after another.
+        #[serde(default)]
+        parallel: bool,
+    },
+}
+
+impl GroupConfig {
+    /// Get the scripts in the group.
+    pub fn scripts(&self) -> &[String] {
+        match self {
+            GroupConfig::Scripts(scripts) | GroupConfig::Detailed { scripts, .. } => scripts,
+        }
+    }
+
+    /// Check if the scripts run at the same time.
+    pub fn parallel(&self) -> bool {
+        matches!(self, GroupConfig::Detailed { parallel: true, .. })
+    }
+}
+
+/// Script groups, by name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct GroupsConfig {
+    /// Groups by name.
+    pub groups: BTreeMap<String, GroupConfig>,
+}
+
+impl GroupsConfig {
+    /// Add the groups to parsed scripts as entries that run their members.
+    ///
+    /// Members that aren't scripts are left out. Groups named like a script,
+    /// or left without members, aren't added.
+    pub fn apply(&self, scripts: &mut Scripts) {
+        for (name, group) in &self.groups {
+            if scripts.get(name).is_some() {
+                debug!("Group {name} is shadowed by a script");
+                continue;
+            }
+            let members: Vec<String> = group
+                .scripts()
+                .iter()
+                .filter(|member| {
+                    let found = scripts.get(member).is_some_and(|s| s.group().is_none());
+                    if !found {
+                        debug!("Group {name} runs unknown script {member}");
+                    }
+                    found
+                })
+                .cloned()
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            scripts.add(Script::from_group(
+                name,
+                ScriptGroup {
+                    scripts: members,
+                    parallel: group.parallel(),
+                },
+            ));
+        }
+    }
+}
+
+/// Scripts that mustn't run at the same time, by group name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct ConcurrencyConfig {
+    /// Script names or glob patterns, by group name.
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+impl ConcurrencyConfig {
+    /// Get the group that keeps two scripts from running at the same time.
+    ///
+    /// A script in a group also conflicts with another run of itself.
+    pub fn shared_group(&self, a: &str, b: &str) -> Option<&str> {
+        self.groups
+            .iter()
+            .find(|(_, scripts)| matches_any(scripts, a) && matches_any(scripts, b))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Check if a name matches any of the given names or glob patterns.
+fn matches_any(patterns: &[String], name: &str) -> bool {
+    patterns.iter().any(|pattern| glob_matches(pattern, name))
+}
+
+/// Get the value configured for a script, keyed by script name or glob.
+///
+/// An exact name match wins over glob patterns, which are tried in sorted
+/// order.
+fn lookup_by_script_pattern<'a, V>(values: &'a HashMap<String, V>, name: &str) -> Option<&'a V> {
+    if let Some(value) = values.get(name) {
+        return Some(value);
+    }
+    let mut patterns: Vec<_> = values.iter().collect();
+    patterns.sort_by(|a, b| a.0.cmp(b.0));
+    patterns
+        .into_iter()
+        .find(|(pattern, _)| glob_matches(pattern, name))
+        .map(|(_, value)| value)
+}
+
+/// Check if a name matches a glob pattern, or equals it if it isn't one.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    match glob::Pattern::new(pattern) {
+        Ok(glob) => glob.matches(name),
+        Err(_) => pattern == name,
+    }
 }
 
 /// Main configuration structure.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
     /// General settings.
     #[serde(default)]
@@ -197,6 +1142,24 @@ pub struct Config {
     /// History settings.
     #[serde(default)]
     pub history: HistoryConfig,
+    /// Run hooks.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Resource limits.
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    /// Progress reporting.
+    #[serde(default)]
+    pub progress: ProgressConfig,
+    /// Watch mode.
+    #[serde(default)]
+    pub watch: WatchConfig,
+    /// Run logs.
+    #[serde(default)]
+    pub logs: LogsConfig,
+    /// Output pane filters.
+    #[serde(default)]
+    pub output: OutputConfig,
     /// Exclude patterns.
     #[serde(default)]
     pub exclude: ExcludeConfig,
@@ -209,6 +1172,24 @@ pub struct Config {
     /// Scripts configuration.
     #[serde(default)]
     pub scripts: ScriptsConfig,
+    /// Description sources.
+    #[serde(default)]
+    pub descriptions: DescriptionsConfig,
+    /// Monorepo root scripts listed inside a package.
+    #[serde(default)]
+    pub parent: ParentConfig,
+    /// Script groups run as one entry (name -> scripts).
+    #[serde(default)]
+    pub groups: GroupsConfig,
+    /// Recipes, another name for groups (name -> scripts).
+    #[serde(default)]
+    pub recipes: GroupsConfig,
+    /// Scripts that mustn't run at the same time (name -> scripts).
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    /// Named profiles, picked with --profile or NRS_PROFILE.
+    #[serde(default)]
+    pub profile: BTreeMap<String, ProfileConfig>,
 }
 
 impl Config {
@@ -219,7 +1200,45 @@ impl Config {
 
     /// Get the config file path for the user's home directory.
     pub fn user_config_path() -> Option<PathBuf> {
-        dirs::config_dir().map(|p| p.join("nrs").join("config.toml"))
+        global_config_file()
+    }
+
+    /// Get the limits for running a script, with its timeout and retries.
+    pub fn limits_for(&self, name: &str) -> ScriptLimits {
+        let policy = self.scripts.policy_for(name);
+        ScriptLimits {
+            timeout: policy.timeout,
+            retries: policy.retries,
+            interactive: self.scripts.is_interactive(name),
+            ..self.limits.for_script(name)
+        }
+    }
+
+    /// Get the settings that reach outside nrs, such as a webhook URL.
+    ///
+    /// Config files checked into a repository may only use these once the
+    /// user has trusted them.
+    pub fn sensitive_settings(&self) -> Vec<&'static str> {
+        let mut settings = Vec::new();
+        if self.hooks.webhook_url.is_some() {
+            settings.push("hooks.webhook_url");
+        }
+        settings
+    }
+
+    /// Add the groups and recipes to parsed scripts as entries that run
+    /// their members.
+    ///
+    /// A group and a recipe with the same name make a single entry, from
+    /// the group.
+    pub fn apply_groups(&self, scripts: &mut Scripts) {
+        self.groups.apply(scripts);
+        self.recipes.apply(scripts);
+    }
+
+    /// Clear the settings listed by [`Config::sensitive_settings`].
+    pub fn clear_sensitive_settings(&mut self) {
+        self.hooks = HooksConfig::default();
     }
 
     /// Merge another config into this one (other takes precedence for set values).
@@ -234,6 +1253,33 @@ impl Config {
         if other.general.max_items > 0 {
             self.general.max_items = other.general.max_items;
         }
+        if other.general.sort_natural.is_some() {
+            self.general.sort_natural = other.general.sort_natural;
+        }
+        if other.general.esc_quits.is_some() {
+            self.general.esc_quits = other.general.esc_quits;
+        }
+        if other.general.max_parallel.is_some() {
+            self.general.max_parallel = other.general.max_parallel;
+        }
+        if other.general.sort_git_boost.is_some() {
+            self.general.sort_git_boost = other.general.sort_git_boost;
+        }
+        if other.general.enforce_package_manager.is_some() {
+            self.general.enforce_package_manager = other.general.enforce_package_manager;
+        }
+        if other.general.check_install.is_some() {
+            self.general.check_install = other.general.check_install;
+        }
+        if other.general.exit_code.is_some() {
+            self.general.exit_code = other.general.exit_code;
+        }
+        if other.general.exit_code_map.is_some() {
+            self.general.exit_code_map = other.general.exit_code_map;
+        }
+        if other.general.workspace_resolution.is_some() {
+            self.general.workspace_resolution = other.general.workspace_resolution;
+        }
 
         // Filter settings
         self.filter = other.filter;
@@ -241,26 +1287,113 @@ impl Config {
         // History settings
         self.history = other.history;
 
+        // Hooks - only override if a webhook is set
+        if other.hooks.webhook_url.is_some() {
+            self.hooks = other.hooks;
+        }
+
+        // Limits - override defaults when set, merge per-script limits
+        if other.limits.nice.is_some() {
+            self.limits.nice = other.limits.nice;
+        }
+        if other.limits.max_memory_mb.is_some() {
+            self.limits.max_memory_mb = other.limits.max_memory_mb;
+        }
+        self.limits.scripts.extend(other.limits.scripts);
+
+        // Progress settings
+        self.progress = other.progress;
+
+        // Watch settings
+        self.watch = other.watch;
+
+        // Log settings
+        self.logs = other.logs;
+
+        // Output filters
+        self.output = other.output;
+
+        // Description sources
+        self.descriptions = other.descriptions;
+
+        // Root scripts listed inside a package - append rather than replace
+        self.parent.scripts.extend(other.parent.scripts);
+
         // Exclude patterns - append rather than replace
         self.exclude.patterns.extend(other.exclude.patterns);
+        self.exclude
+            .workspaces
+            .patterns
+            .extend(other.exclude.workspaces.patterns);
 
         // Appearance settings
         self.appearance = other.appearance;
 
-        // Keybindings - only override if not empty
-        if !other.keybindings.quit.is_empty() {
-            self.keybindings.quit = other.keybindings.quit;
-        }
-        if !other.keybindings.run.is_empty() {
-            self.keybindings.run = other.keybindings.run;
+        // Keybindings - only override if not empty or default
+        if other.keybindings.preset != KeymapPreset::default() {
+            self.keybindings.preset = other.keybindings.preset;
         }
-        if !other.keybindings.filter.is_empty() {
-            self.keybindings.filter = other.keybindings.filter;
+        if !other.keybindings.vim_keys {
+            self.keybindings.vim_keys = false;
         }
+        self.keybindings.bindings.extend(other.keybindings.bindings);
 
         // Scripts - merge hashmaps
         self.scripts.descriptions.extend(other.scripts.descriptions);
-        self.scripts.aliases.extend(other.scripts.aliases);
+        self.scripts
+            .aliases
+            .aliases
+            .extend(other.scripts.aliases.aliases);
+        self.scripts.categories.extend(other.scripts.categories);
+        self.scripts.policies.extend(other.scripts.policies);
+
+        // Favorites, confirm and interactive patterns - append rather than replace
+        self.scripts.favorites.extend(other.scripts.favorites);
+        self.scripts.confirm.extend(other.scripts.confirm);
+        self.scripts.interactive.extend(other.scripts.interactive);
+
+        // Groups and recipes - one defined again replaces the earlier one
+        self.groups.groups.extend(other.groups.groups);
+        self.recipes.groups.extend(other.recipes.groups);
+        self.concurrency.groups.extend(other.concurrency.groups);
+
+        // Profiles - one defined again replaces the earlier one
+        self.profile.extend(other.profile);
+    }
+
+    /// Apply the settings of a `[profile.<name>]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no profile has that name.
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), NrsError> {
+        let Some(profile) = self.profile.get(name).cloned() else {
+            let available: Vec<&str> = self.profile.keys().map(String::as_str).collect();
+            let message = if available.is_empty() {
+                format!("Unknown profile '{name}' (no [profile.<name>] tables are defined)")
+            } else {
+                format!(
+                    "Unknown profile '{name}' (available: {})",
+                    available.join(", ")
+                )
+            };
+            return Err(NrsError::ConfigError { message });
+        };
+        debug!("Using profile {name}");
+
+        if let Some(appearance) = profile.appearance {
+            self.appearance = appearance;
+        }
+        if let Some(exclude) = profile.exclude {
+            self.exclude = exclude;
+        }
+        if let Some(sort) = profile.default_sort {
+            self.general.default_sort = sort;
+        }
+        if let Some(keybindings) = profile.keybindings {
+            self.keybindings = keybindings;
+        }
+        Ok(())
     }
 }
 
@@ -276,6 +1409,18 @@ fn default_max_scripts() -> usize {
     50
 }
 
+fn default_max_size_kb() -> usize {
+    1024
+}
+
+fn default_debounce_ms() -> u64 {
+    200
+}
+
+fn default_keep_logs() -> usize {
+    20
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,13 +1431,19 @@ mod tests {
         assert!(config.filter.fuzzy);
         assert!(config.filter.search_descriptions);
         assert!(!config.filter.case_sensitive);
+        assert_eq!(config.filter.matching_algorithm(), FilterAlgorithm::Skim);
         assert!(config.history.enabled);
         assert_eq!(config.history.max_projects, 100);
         assert_eq!(config.history.max_scripts, 50);
+        assert_eq!(config.history.max_size_kb, 1024);
+        assert!(!config.history.compress_rotated);
+        assert!(!config.history.hash_paths);
         assert!(config.appearance.icons);
         assert!(config.appearance.show_footer);
         assert!(!config.appearance.compact);
         assert_eq!(config.appearance.theme, Theme::Default);
+        assert!(config.progress.terminal_title);
+        assert_eq!(config.progress.heartbeat_minutes, 0);
     }
 
     #[test]
@@ -313,6 +1464,24 @@ mod tests {
         assert_eq!(dir, ColumnDirection::Horizontal);
     }
 
+    #[test]
+    fn test_density_serialization() {
+        let json = serde_json::to_string(&Density::Compact).unwrap();
+        assert_eq!(json, "\"compact\"");
+
+        let density: Density = serde_json::from_str("\"comfortable\"").unwrap();
+        assert_eq!(density, Density::Comfortable);
+    }
+
+    #[test]
+    fn test_density_settings() {
+        assert!(Density::Comfortable.inline_descriptions());
+        assert!(!Density::Compact.inline_descriptions());
+        assert_eq!(Density::Compact.row_height(), 1);
+        assert!(Density::Compact.min_column_width() < Density::Comfortable.min_column_width());
+        assert!(Density::Compact.cell_padding() < Density::Comfortable.cell_padding());
+    }
+
     #[test]
     fn test_theme_serialization() {
         let json = serde_json::to_string(&Theme::Minimal).unwrap();
@@ -320,6 +1489,9 @@ mod tests {
 
         let theme: Theme = serde_json::from_str("\"none\"").unwrap();
         assert_eq!(theme, Theme::None);
+
+        let theme: Theme = serde_json::from_str("\"colorblind\"").unwrap();
+        assert_eq!(theme, Theme::Colorblind);
     }
 
     #[test]
@@ -346,4 +1518,374 @@ mod tests {
             Some(&"Start dev server".to_string())
         );
     }
+
+    #[test]
+    fn test_config_merge_keeps_unset_general_settings() {
+        let mut base: Config = toml::from_str(
+            "[general]\nesc_quits = false\nmax_parallel = 4\ncheck_install = false\nexit_code = \"zero\"\n",
+        )
+        .unwrap();
+        base.merge(toml::from_str("[general]\nrunner = \"pnpm\"\n").unwrap());
+
+        assert_eq!(base.general.runner, Some(Runner::Pnpm));
+        assert!(!base.general.esc_quits());
+        assert_eq!(base.general.max_parallel(), 4);
+        assert!(!base.general.check_install());
+        assert_eq!(base.general.exit_code_for(1), 0);
+
+        base.merge(toml::from_str("[general]\nmax_parallel = 0\nesc_quits = true\n").unwrap());
+        assert_eq!(base.general.max_parallel(), 0);
+        assert!(base.general.esc_quits());
+    }
+
+    #[test]
+    fn test_exclude_workspaces_parse_and_merge() {
+        let config: Config = toml::from_str(
+            r#"
+[exclude]
+patterns = ["pre*"]
+
+[exclude.workspaces]
+patterns = ["packages/fixtures-*"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.exclude.patterns, vec!["pre*".to_string()]);
+        assert_eq!(
+            config.exclude.workspaces.patterns,
+            vec!["packages/fixtures-*".to_string()]
+        );
+
+        let mut base = Config::default();
+        base.exclude
+            .workspaces
+            .patterns
+            .push("@internal/*".to_string());
+        base.merge(config);
+        assert_eq!(base.exclude.workspaces.patterns.len(), 2);
+    }
+
+    #[test]
+    fn test_groups_parse_and_apply() {
+        let config: Config = toml::from_str(
+            r#"
+[groups]
+ci = ["lint", "typecheck", "test"]
+checks = { scripts = ["lint", "test"], parallel = true }
+build = ["lint"]
+nothing = ["deploy"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.groups.groups.get("ci").map(GroupConfig::scripts),
+            Some(
+                &[
+                    "lint".to_string(),
+                    "typecheck".to_string(),
+                    "test".to_string()
+                ][..]
+            )
+        );
+        assert!(config.groups.groups["checks"].parallel());
+
+        let mut scripts = Scripts::from_vec(vec![
+            Script::new("build", "vite build"),
+            Script::new("lint", "eslint ."),
+            Script::new("test", "vitest"),
+        ]);
+        config.groups.apply(&mut scripts);
+
+        // Unknown members are left out, and groups can't replace scripts
+        let ci = scripts.get("ci").and_then(Script::group).unwrap();
+        assert_eq!(ci.scripts, ["lint", "test"]);
+        assert!(!ci.parallel);
+        assert_eq!(scripts.get("checks").unwrap().command(), "lint & test");
+        assert!(scripts.get("build").unwrap().group().is_none());
+        assert!(scripts.get("nothing").is_none());
+    }
+
+    #[test]
+    fn test_recipes_apply_like_groups() {
+        let config: Config = toml::from_str(
+            r#"
+[groups]
+ci = ["lint"]
+
+[recipes]
+ship = ["lint", "test", "build"]
+ci = ["test"]
+"#,
+        )
+        .unwrap();
+
+        let mut scripts = Scripts::from_vec(vec![
+            Script::new("build", "vite build"),
+            Script::new("lint", "eslint ."),
+            Script::new("test", "vitest"),
+        ]);
+        config.apply_groups(&mut scripts);
+
+        let ship = scripts.get("ship").and_then(Script::group).unwrap();
+        assert_eq!(ship.scripts, ["lint", "test", "build"]);
+        let ci = scripts.get("ci").and_then(Script::group).unwrap();
+        assert_eq!(ci.scripts, ["lint"]);
+    }
+
+    #[test]
+    fn test_script_policies_set_limits() {
+        let config: Config = toml::from_str(
+            r#"
+[limits]
+nice = 5
+
+[scripts]
+favorites = ["dev"]
+
+[scripts."test:e2e"]
+timeout = "10m"
+retries = 2
+
+[scripts."test*"]
+timeout = "30s"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.scripts.favorites, ["dev"]);
+        let e2e = config.limits_for("test:e2e");
+        assert_eq!(e2e.timeout, Some(Duration::from_secs(600)));
+        assert_eq!(e2e.retries, 2);
+        assert_eq!(e2e.nice, Some(5));
+        assert_eq!(e2e.describe(), "nice 5, timeout 10m, 2 retries");
+
+        let unit = config.limits_for("test:unit");
+        assert_eq!(unit.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(unit.retries, 0);
+        assert_eq!(config.limits_for("build").timeout, None);
+
+        let err = toml::from_str::<Config>("[scripts.build]\ntimeout = \"soon\"\n").unwrap_err();
+        assert!(err.to_string().contains("invalid duration"), "{err}");
+    }
+
+    #[test]
+    fn test_exit_code_policy() {
+        let general = |toml: &str| toml::from_str::<Config>(toml).unwrap().general;
+
+        let script = GeneralConfig::default();
+        assert_eq!(script.exit_code_for(2), 2);
+
+        let zero = general("[general]\nexit_code = \"zero\"\n");
+        assert_eq!(zero.exit_code_for(2), 0);
+        assert_eq!(zero.exit_code_for(exit_code::INTERRUPTED), 130);
+
+        let remap = general(
+            "[general]\nexit_code = \"remap\"\n[general.exit_code_map]\n\"1\" = 0\n\"*\" = 2\n",
+        );
+        assert_eq!(remap.exit_code_for(0), 0);
+        assert_eq!(remap.exit_code_for(1), 0);
+        assert_eq!(remap.exit_code_for(137), 2);
+
+        let err = toml::from_str::<Config>("[general.exit_code_map]\nfail = 0\n").unwrap_err();
+        assert!(err.to_string().contains("invalid exit code"), "{err}");
+    }
+
+    #[test]
+    fn test_apply_profile() {
+        let mut config: Config = toml::from_str(
+            r#"
+[general]
+default_sort = "recent"
+
+[exclude]
+patterns = ["pre*"]
+
+[profile.demo]
+default_sort = "alpha"
+
+[profile.demo.appearance]
+icons = false
+
+[profile.demo.exclude]
+patterns = ["internal:*"]
+"#,
+        )
+        .unwrap();
+
+        let err = config.clone().apply_profile("ci").unwrap_err();
+        assert!(err.to_string().contains("available: demo"), "{err}");
+
+        config.apply_profile("demo").unwrap();
+        assert_eq!(config.general.default_sort, SortMode::Alpha);
+        assert!(!config.appearance.icons);
+        assert_eq!(config.exclude.patterns, ["internal:*"]);
+        // Settings the profile doesn't mention stay as configured
+        assert!(config.keybindings.vim_keys);
+    }
+
+    #[test]
+    fn test_output_hide_patterns() {
+        let config: Config = toml::from_str("[output]\nhide = [\"^webpack.Progress\"]\n").unwrap();
+        assert_eq!(config.output.hide, ["^webpack.Progress"]);
+        assert!(config.output.collapse);
+
+        let err = toml::from_str::<Config>("[output]\nhide = [\"[unclosed\"]\n").unwrap_err();
+        assert!(err.to_string().contains("invalid pattern"), "{err}");
+    }
+
+    #[test]
+    fn test_concurrency_shared_group() {
+        let config: Config = toml::from_str(
+            r#"
+[concurrency]
+servers = ["dev", "dev:*"]
+db = ["db:reset", "db:seed"]
+"#,
+        )
+        .unwrap();
+
+        let concurrency = &config.concurrency;
+        assert_eq!(
+            concurrency.shared_group("dev", "dev:https"),
+            Some("servers")
+        );
+        assert_eq!(concurrency.shared_group("dev", "dev"), Some("servers"));
+        assert_eq!(concurrency.shared_group("db:seed", "db:reset"), Some("db"));
+        assert_eq!(concurrency.shared_group("dev", "db:reset"), None);
+        assert_eq!(concurrency.shared_group("build", "build"), None);
+    }
+
+    #[test]
+    fn test_aliases_parse_and_apply() {
+        let config: Config = toml::from_str(
+            r#"
+[scripts.aliases]
+t = "test -- --watch"
+b = "build"
+l = "lint --fix"
+build = "dev"
+x = "deploy"
+"#,
+        )
+        .unwrap();
+        let aliases = &config.scripts.aliases;
+        assert_eq!(aliases.get("t"), Some("test -- --watch"));
+        assert_eq!(
+            aliases.resolve("t"),
+            Some(ScriptAlias {
+                script: "test".to_string(),
+                args: Some("--watch".to_string()),
+            })
+        );
+        assert_eq!(aliases.resolve("l").unwrap().args.as_deref(), Some("--fix"));
+        assert_eq!(aliases.resolve("b").unwrap().args, None);
+        assert_eq!(aliases.resolve("missing"), None);
+
+        let mut scripts = Scripts::from_vec(vec![
+            Script::new("build", "vite build"),
+            Script::new("test", "vitest"),
+        ]);
+        aliases.apply(&mut scripts);
+
+        // Aliases of unknown scripts are left out, and aliases can't replace scripts
+        let t = scripts.get("t").unwrap();
+        assert_eq!(t.alias().map(|a| a.script.as_str()), Some("test"));
+        assert_eq!(t.command(), "vitest --watch");
+        assert_eq!(t.description(), Some("Alias of test -- --watch"));
+        assert_eq!(scripts.get("build").unwrap().command(), "vite build");
+        assert!(scripts.get("b").is_some());
+        assert!(scripts.get("x").is_none());
+    }
+
+    #[test]
+    fn test_keybindings_parse_and_merge() {
+        let config: Config = toml::from_str(
+            r#"
+[keybindings]
+preset = "emacs"
+vim_keys = false
+sort = ["S"]
+first = ["g g", "Home"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.keybindings.preset, KeymapPreset::Emacs);
+        assert!(!config.keybindings.vim_keys);
+        assert_eq!(
+            config.keybindings.bindings.get(&KeyAction::First),
+            Some(&vec!["g g".to_string(), "Home".to_string()])
+        );
+
+        let mut base = Config::default();
+        base.keybindings
+            .bindings
+            .insert(KeyAction::Quit, vec!["Ctrl+q".to_string()]);
+        base.merge(config);
+        assert!(!base.keybindings.vim_keys);
+        assert_eq!(base.keybindings.bindings.len(), 3);
+
+        // Misspelled actions are rejected rather than ignored
+        let err = toml::from_str::<Config>("[keybindings]\nsrot = [\"S\"]").unwrap_err();
+        assert!(err.to_string().contains("srot"));
+    }
+
+    #[test]
+    fn test_limits_for_script() {
+        let config: Config = toml::from_str(
+            r#"
+[limits]
+nice = 5
+
+[limits.scripts]
+build = { max_memory_mb = 4096 }
+"build:*" = { nice = 15, max_memory_mb = 2048 }
+"#,
+        )
+        .unwrap();
+
+        let build = config.limits.for_script("build");
+        assert_eq!(build.nice, Some(5));
+        assert_eq!(build.max_memory_mb, Some(4096));
+
+        let build_web = config.limits.for_script("build:web");
+        assert_eq!(build_web.nice, Some(15));
+        assert_eq!(build_web.describe(), "nice 15, max memory 2048 MB");
+
+        assert_eq!(
+            config.limits.for_script("dev"),
+            ScriptLimits {
+                nice: Some(5),
+                ..ScriptLimits::default()
+            }
+        );
+        assert!(Config::default().limits.for_script("dev").is_empty());
+    }
+
+    #[test]
+    fn test_scripts_config_patterns() {
+        let config: Config = toml::from_str(
+            r#"
+[scripts]
+favorites = ["dev", "test:*"]
+confirm = ["deploy*"]
+interactive = ["db:shell"]
+
+[scripts.categories]
+"e2e*" = "test"
+"tsc" = "build"
+"#,
+        )
+        .unwrap();
+
+        assert!(config.scripts.is_favorite("dev"));
+        assert!(config.scripts.is_favorite("test:unit"));
+        assert!(!config.scripts.is_favorite("build"));
+        assert!(config.scripts.needs_confirm("deploy:prod"));
+        assert!(!config.scripts.needs_confirm("dev"));
+        assert!(config.limits_for("db:shell").interactive);
+        assert!(!config.limits_for("dev").interactive);
+        assert_eq!(config.scripts.category_for("e2e:chrome"), Some("test"));
+        assert_eq!(config.scripts.category_for("tsc"), Some("build"));
+        assert_eq!(config.scripts.category_for("lint"), None);
+    }
 }
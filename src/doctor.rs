@@ -0,0 +1,552 @@
+//! Environment checks for `nrs doctor`.
+//!
+//! Each check looks at one thing nrs depends on: the config files, the
+//! history file, the terminal, the package manager binaries and the versions
+//! package.json asks for, and workspace detection. A check passes, warns or
+//! fails, and the ones that don't pass suggest a fix.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::config::{config_files, load_config_from_path};
+use crate::history::History;
+use crate::output::Report;
+use crate::package::{
+    all_workspaces, detect_runner_reason, detect_workspace_info, DescriptionSource, Runner,
+    WorkspaceResolution,
+};
+use crate::runner::pinned::{installed_version, PackageManagerPin};
+use crate::utils::{check_terminal_size, MIN_HEIGHT, MIN_WIDTH};
+
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    /// Nothing to fix.
+    Pass,
+    /// nrs works, but something may not behave as expected.
+    Warn,
+    /// Something nrs needs is broken or missing.
+    Fail,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Warn => write!(f, "warn"),
+            Status::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// Result of one check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// Outcome of the check.
+    pub status: Status,
+    /// What was checked, e.g. "config" or "runner".
+    pub name: &'static str,
+    /// What was found.
+    pub message: String,
+    /// Suggested fix, for checks that didn't pass.
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn new(status: Status, name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            name,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    /// Create a passed check.
+    pub fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Status::Pass, name, message)
+    }
+
+    /// Create a check with a warning.
+    pub fn warn(name: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Status::Warn, name, message)
+    }
+
+    /// Create a failed check.
+    pub fn fail(name: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Status::Fail, name, message)
+    }
+
+    /// Add a suggested fix.
+    #[must_use]
+    pub fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// Get a row per check, for `nrs doctor --format`.
+pub fn checks_report(checks: &[Check]) -> Report {
+    let mut report = Report::new(["status", "check", "message", "fix"]);
+    for check in checks {
+        report.push([
+            json!(check.status.to_string()),
+            json!(check.name),
+            json!(check.message),
+            json!(check.fix),
+        ]);
+    }
+    report
+}
+
+/// Run every check, for a project if one was found.
+pub fn run_checks(project_dir: Option<&Path>, cli_config_path: Option<&Path>) -> Vec<Check> {
+    let mut checks = check_config(project_dir, cli_config_path);
+    checks.push(check_history());
+    checks.push(check_terminal());
+
+    let Some(project_dir) = project_dir else {
+        checks.push(
+            Check::warn("project", "No package.json here or in any parent directory")
+                .with_fix("Run nrs doctor in a project to check its runner and workspaces"),
+        );
+        return checks;
+    };
+
+    let package = match read_package_json(project_dir) {
+        Ok(package) => package,
+        Err(message) => {
+            checks.push(Check::fail("project", message).with_fix("Fix package.json"));
+            return checks;
+        }
+    };
+    checks.extend(check_runner(project_dir, &package));
+    checks.extend(check_engines(&package));
+    checks.push(check_workspaces(project_dir));
+    checks
+}
+
+/// Check that every config file parses.
+fn check_config(project_dir: Option<&Path>, cli_config_path: Option<&Path>) -> Vec<Check> {
+    let files = config_files(cli_config_path, project_dir);
+    if files.is_empty() {
+        return vec![Check::pass("config", "No config files, using the defaults")];
+    }
+
+    files
+        .iter()
+        .map(|path| match load_config_from_path(path) {
+            Ok(_) => Check::pass("config", format!("{} parses", path.display())),
+            Err(err) => {
+                let cause = err.root_cause().to_string();
+                let cause = cause.lines().next().unwrap_or_default();
+                Check::fail("config", format!("{}: {cause}", path.display()))
+                    .with_fix("Fix the file, or validate it against nrs config schema")
+            }
+        })
+        .collect()
+}
+
+/// Check that the history file can be read.
+fn check_history() -> Check {
+    let Some(path) = History::file_path() else {
+        return Check::warn("history", "No state directory, history won't be saved")
+            .with_fix("Set $HOME, or NRS_DATA_DIR to a writable directory");
+    };
+
+    if let Some(backup) = History::backup_path().filter(|p| p.exists()) {
+        return Check::warn(
+            "history",
+            format!("A corrupt history was backed up to {}", backup.display()),
+        )
+        .with_fix("Run nrs history repair to recover its entries");
+    }
+
+    if !path.exists() {
+        return Check::pass("history", format!("No history yet ({})", path.display()));
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) => {
+            return Check::fail("history", format!("Can't read {}: {err}", path.display()))
+                .with_fix("Check the permissions of the file");
+        }
+    };
+    match serde_json::from_str::<History>(&content) {
+        Ok(history) => Check::pass(
+            "history",
+            format!(
+                "{} project(s), {} KB ({})",
+                history.projects.len(),
+                content.len() / 1024,
+                path.display()
+            ),
+        ),
+        Err(err) => Check::fail("history", format!("{} is corrupt: {err}", path.display()))
+            .with_fix("Run nrs once to back it up, then nrs history repair"),
+    }
+}
+
+/// Check that the terminal can show the TUI.
+fn check_terminal() -> Check {
+    if !io::stdout().is_terminal() {
+        return Check::warn("terminal", "Output isn't a terminal, the TUI won't start")
+            .with_fix("Run nrs from an interactive terminal, or use nrs --list");
+    }
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    if term == "dumb" {
+        return Check::warn("terminal", "TERM is dumb, the TUI may not draw")
+            .with_fix("Set TERM to your terminal's type, e.g. xterm-256color");
+    }
+
+    let Some(size) = check_terminal_size() else {
+        return Check::warn("terminal", "Couldn't get the terminal size");
+    };
+    let colors = if std::env::var_os("NO_COLOR").is_some() {
+        ", colors off (NO_COLOR)"
+    } else {
+        ""
+    };
+    let message = format!("{}x{}, TERM={term}{colors}", size.width, size.height);
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        return Check::warn(
+            "terminal",
+            format!("{message}, smaller than {MIN_WIDTH}x{MIN_HEIGHT}"),
+        )
+        .with_fix("Enlarge the window for the full layout");
+    }
+    Check::pass("terminal", message)
+}
+
+/// Read package.json as JSON.
+fn read_package_json(project_dir: &Path) -> Result<Value, String> {
+    let path = project_dir.join("package.json");
+    let content =
+        fs::read_to_string(&path).map_err(|err| format!("Can't read {}: {err}", path.display()))?;
+    serde_json::from_str(&content).map_err(|err| format!("{} is invalid: {err}", path.display()))
+}
+
+/// Check that the detected package manager is installed, in the pinned
+/// version if `packageManager` sets one.
+fn check_runner(project_dir: &Path, package: &Value) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let (runner, reason) = detect_runner_reason(project_dir);
+
+    match installed_version(runner.executable()) {
+        None => checks.push(
+            Check::fail(
+                "runner",
+                format!("{} isn't installed ({reason})", runner.executable()),
+            )
+            .with_fix(install_hint(runner)),
+        ),
+        Some(version) => {
+            let pinned = package["packageManager"]
+                .as_str()
+                .and_then(PackageManagerPin::parse)
+                .map(|pin| pin.version);
+            match pinned {
+                Some(pinned) if pinned != version => checks.push(
+                    Check::warn(
+                        "runner",
+                        format!(
+                            "packageManager pins {} {pinned}, but {version} is installed",
+                            runner.executable()
+                        ),
+                    )
+                    .with_fix("Run corepack enable to use the pinned version"),
+                ),
+                _ => checks.push(Check::pass(
+                    "runner",
+                    format!("{} {version} ({reason})", runner.executable()),
+                )),
+            }
+        }
+    }
+
+    let lock_files: Vec<&str> = Runner::all()
+        .iter()
+        .map(Runner::lock_file)
+        .filter(|file| project_dir.join(file).exists())
+        .collect();
+    if lock_files.len() > 1 {
+        checks.push(
+            Check::warn(
+                "runner",
+                format!(
+                    "Lock files of several package managers: {}",
+                    lock_files.join(", ")
+                ),
+            )
+            .with_fix("Delete the lock files of the package managers the project doesn't use"),
+        );
+    }
+    checks
+}
+
+/// Suggest how to install a package manager.
+fn install_hint(runner: Runner) -> String {
+    match runner {
+        Runner::Npm => "Install Node.js, which comes with npm".to_string(),
+        Runner::Yarn | Runner::Pnpm => {
+            format!("Install {}, or run corepack enable", runner.executable())
+        }
+        _ => format!("Install {}", runner.executable()),
+    }
+}
+
+/// Check the installed tools against the `engines` of package.json.
+fn check_engines(package: &Value) -> Vec<Check> {
+    let Some(engines) = package["engines"].as_object() else {
+        return Vec::new();
+    };
+
+    engines
+        .iter()
+        .filter_map(|(tool, range)| Some((tool.as_str(), range.as_str()?)))
+        .filter(|(tool, _)| matches!(*tool, "node" | "npm" | "yarn" | "pnpm" | "bun" | "deno"))
+        .map(|(tool, range)| {
+            let Some(version) = installed_version(tool) else {
+                return Check::warn(
+                    "engines",
+                    format!("engines.{tool} is {range}, but {tool} isn't installed"),
+                )
+                .with_fix(format!("Install {tool} {range}"));
+            };
+            match satisfies(&version, range) {
+                Some(true) => Check::pass("engines", format!("{tool} {version} matches {range}")),
+                Some(false) => Check::fail(
+                    "engines",
+                    format!("{tool} {version} doesn't match engines.{tool} {range}"),
+                )
+                .with_fix(if tool == "node" {
+                    format!("Switch to node {range}, e.g. with nvm, fnm or volta")
+                } else {
+                    format!("Install {tool} {range}")
+                }),
+                None => Check::warn(
+                    "engines",
+                    format!("Couldn't check {tool} {version} against {range}"),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Check that workspaces are found and readable.
+fn check_workspaces(project_dir: &Path) -> Check {
+    let info = match detect_workspace_info(
+        project_dir,
+        &DescriptionSource::ALL,
+        WorkspaceResolution::default(),
+    ) {
+        Ok(info) => info,
+        Err(err) => {
+            return Check::fail("workspaces", format!("{err:#}")).with_fix(
+                "Fix the workspace config (package.json, pnpm-workspace.yaml or lerna.json)",
+            );
+        }
+    };
+    if !info.is_monorepo {
+        return Check::pass("workspaces", "Not a monorepo");
+    }
+
+    let kind = info
+        .workspace_type
+        .map_or_else(|| "workspaces".to_string(), |kind| kind.to_string());
+    let workspaces = all_workspaces(&info.workspaces);
+    if workspaces.is_empty() {
+        return Check::warn(
+            "workspaces",
+            format!("{kind} declared, but no package matches"),
+        )
+        .with_fix("Check the workspace patterns point at directories with a package.json");
+    }
+
+    let unreadable: Vec<&str> = workspaces
+        .iter()
+        .filter(|workspace| workspace.error().is_some())
+        .map(|workspace| workspace.name())
+        .collect();
+    if !unreadable.is_empty() {
+        return Check::warn(
+            "workspaces",
+            format!(
+                "{} of {} workspace(s) can't be read: {}",
+                unreadable.len(),
+                workspaces.len(),
+                unreadable.join(", ")
+            ),
+        )
+        .with_fix("Fix the package.json of those workspaces");
+    }
+    Check::pass(
+        "workspaces",
+        format!("{} workspace(s) ({kind})", workspaces.len()),
+    )
+}
+
+/// Parse a version or partial version, like "18", "v18.2" or "18.x".
+///
+/// Returns the numbers given, up to the first wildcard. Prerelease and build
+/// suffixes are ignored.
+fn parse_partial(text: &str) -> Option<Vec<u64>> {
+    let text = text.trim().trim_start_matches(['v', '=']);
+    let text = text.split(['-', '+']).next().unwrap_or(text);
+    let mut parts = Vec::new();
+    for part in text.split('.').take(3) {
+        if matches!(part, "x" | "X" | "*") {
+            break;
+        }
+        parts.push(part.parse().ok()?);
+    }
+    Some(parts)
+}
+
+/// Pad a partial version with zeros.
+fn padded(parts: &[u64]) -> [u64; 3] {
+    let mut version = [0; 3];
+    version[..parts.len()].copy_from_slice(parts);
+    version
+}
+
+/// Get the first version past a partial one, e.g. 19.0.0 for "18".
+fn bumped(parts: &[u64]) -> [u64; 3] {
+    let mut version = padded(parts);
+    if let Some(last) = parts.len().checked_sub(1) {
+        version[last] += 1;
+    }
+    version
+}
+
+/// Check a version against one comparator, like ">=18" or "^8.6".
+fn matches_comparator(version: [u64; 3], comparator: &str) -> Option<bool> {
+    let (op, rest) = [">=", "<=", ">", "<", "^", "~", "="]
+        .iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", comparator));
+    let parts = parse_partial(rest)?;
+    if parts.is_empty() {
+        return Some(!matches!(op, "<" | ">"));
+    }
+
+    let lower = padded(&parts);
+    Some(match op {
+        ">=" => version >= lower,
+        ">" if parts.len() == 3 => version > lower,
+        ">" => version >= bumped(&parts),
+        "<" => version < lower,
+        "<=" if parts.len() == 3 => version <= lower,
+        "<=" => version < bumped(&parts),
+        "^" => {
+            // Up to the next change of the first non-zero part given
+            let significant = parts
+                .iter()
+                .position(|&n| n != 0)
+                .unwrap_or(parts.len() - 1);
+            version >= lower && version < bumped(&parts[..=significant])
+        }
+        "~" => version >= lower && version < bumped(&parts[..parts.len().min(2)]),
+        _ => version >= lower && version < bumped(&parts),
+    })
+}
+
+/// Check a version against an npm-style range, like ">=18" or "^8.6 || ^9".
+///
+/// Returns `None` for syntax that isn't understood, such as hyphen ranges.
+pub fn satisfies(version: &str, range: &str) -> Option<bool> {
+    let version = padded(&parse_partial(version)?);
+    let mut any = false;
+    for alternative in range.split("||") {
+        if alternative.contains(" - ") {
+            return None;
+        }
+        // Join operators written apart from their version, like ">= 18"
+        let mut comparators: Vec<String> = Vec::new();
+        for word in alternative.split_whitespace() {
+            match comparators.last_mut() {
+                Some(last) if last.chars().all(|c| "<>=^~".contains(c)) => last.push_str(word),
+                _ => comparators.push(word.to_string()),
+            }
+        }
+        let mut all = true;
+        for comparator in &comparators {
+            all &= matches_comparator(version, comparator)?;
+        }
+        any |= all;
+    }
+    Some(any)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_satisfies() {
+        assert_eq!(satisfies("20.11.0", ">=18"), Some(true));
+        assert_eq!(satisfies("16.20.2", ">=18"), Some(false));
+        assert_eq!(satisfies("18.17.1", ">= 18.17 < 21"), Some(true));
+        assert_eq!(satisfies("21.0.0", ">=18.17 <21"), Some(false));
+        assert_eq!(satisfies("8.15.4", "^8.6.0"), Some(true));
+        assert_eq!(satisfies("9.0.0", "^8.6.0"), Some(false));
+        assert_eq!(satisfies("0.3.9", "^0.3.1"), Some(true));
+        assert_eq!(satisfies("0.4.0", "^0.3.1"), Some(false));
+        assert_eq!(satisfies("1.2.9", "~1.2.3"), Some(true));
+        assert_eq!(satisfies("1.3.0", "~1.2.3"), Some(false));
+        assert_eq!(satisfies("18.19.0", "18.x"), Some(true));
+        assert_eq!(satisfies("20.1.0", "16 || 18 || 20"), Some(true));
+        assert_eq!(satisfies("19.0.0", "16 || 18 || 20"), Some(false));
+        assert_eq!(satisfies("20.0.0", ">18"), Some(true));
+        assert_eq!(satisfies("18.9.0", ">18"), Some(false));
+        assert_eq!(satisfies("3.0.0", "*"), Some(true));
+        assert_eq!(satisfies("18.0.0", "16 - 20"), None);
+        assert_eq!(satisfies("18.0.0", ">=latest"), None);
+    }
+
+    #[test]
+    fn test_check_workspaces() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"name": "app"}"#).unwrap();
+        assert_eq!(check_workspaces(dir.path()).status, Status::Pass);
+
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"name": "root", "workspaces": ["packages/*"]}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("packages/ok")).unwrap();
+        fs::write(
+            dir.path().join("packages/ok/package.json"),
+            r#"{"name": "ok", "scripts": {"build": "tsc"}}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("packages/broken")).unwrap();
+        fs::write(dir.path().join("packages/broken/package.json"), "{").unwrap();
+
+        let check = check_workspaces(dir.path());
+        assert_eq!(check.status, Status::Warn);
+        assert!(check
+            .message
+            .starts_with("1 of 2 workspace(s) can't be read"));
+        assert!(check.fix.is_some());
+    }
+
+    #[test]
+    fn test_check_runner_flags_lock_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(dir.path().join("yarn.lock"), "").unwrap();
+        fs::write(dir.path().join("package-lock.json"), "{}").unwrap();
+
+        let checks = check_runner(dir.path(), &Value::Null);
+        let lock_check = checks.last().unwrap();
+        assert_eq!(lock_check.status, Status::Warn);
+        assert!(lock_check.message.contains("package-lock.json, yarn.lock"));
+    }
+}
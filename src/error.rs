@@ -20,6 +20,8 @@ pub mod exit_code {
     pub const SCRIPT_FAILED: i32 = 4;
     /// Invalid configuration.
     pub const INVALID_CONFIG: i32 = 5;
+    /// Script killed for running past its timeout.
+    pub const TIMED_OUT: i32 = 124;
     /// Interrupted (Ctrl+C).
     pub const INTERRUPTED: i32 = 130;
 }
@@ -62,6 +64,22 @@ pub enum NrsError {
     #[error("Invalid scripts field in {path}: expected an object, got {actual_type}\n\nTip: The scripts field must be an object:\n  \"scripts\": {{ \"name\": \"command\" }}")]
     InvalidScriptsType { path: PathBuf, actual_type: String },
 
+    /// package.json exists but can't be read.
+    #[error("Can't read {path}: {reason}\n\nTip: Check the file's permissions, and that a symlinked package.json points to an existing file.")]
+    UnreadablePackageJson { path: PathBuf, reason: String },
+
+    /// package.json is larger than nrs reads by default.
+    #[error("{path} is larger than {limit_mb} MB\n\nTip: Use --force to read it anyway.")]
+    PackageJsonTooLarge { path: PathBuf, limit_mb: u64 },
+
+    /// package.json defines more scripts than nrs lists by default.
+    #[error("{path} defines {count} scripts, more than the limit of {limit}\n\nTip: Use --force to list them anyway.")]
+    TooManyScripts {
+        path: PathBuf,
+        count: usize,
+        limit: usize,
+    },
+
     /// Script not found.
     #[error("Script '{name}' not found in package.json")]
     ScriptNotFound { name: String },
@@ -70,6 +88,10 @@ pub enum NrsError {
     #[error("Script '{name}' not found\n\nDid you mean: {suggestions}?\n\nRun 'nrs --list' to see all available scripts.")]
     ScriptNotFoundWithSuggestions { name: String, suggestions: String },
 
+    /// Workspace not found in the monorepo.
+    #[error("Workspace '{name}' not found\n\nAvailable workspaces: {available}")]
+    WorkspaceNotFound { name: String, available: String },
+
     /// Script execution failed.
     #[error("Script '{name}' failed with exit code {code}")]
     ScriptFailed { name: String, code: i32 },
@@ -128,8 +150,12 @@ impl NrsError {
             NrsError::NoScriptsAt { .. } => exit_code::NO_SCRIPTS,
             NrsError::EmptyScripts { .. } => exit_code::NO_SCRIPTS,
             NrsError::InvalidScriptsType { .. } => exit_code::NO_PACKAGE_JSON,
+            NrsError::UnreadablePackageJson { .. } => exit_code::NO_PACKAGE_JSON,
+            NrsError::PackageJsonTooLarge { .. } => exit_code::NO_PACKAGE_JSON,
+            NrsError::TooManyScripts { .. } => exit_code::NO_PACKAGE_JSON,
             NrsError::ScriptNotFound { .. } => exit_code::GENERAL_ERROR,
             NrsError::ScriptNotFoundWithSuggestions { .. } => exit_code::GENERAL_ERROR,
+            NrsError::WorkspaceNotFound { .. } => exit_code::GENERAL_ERROR,
             NrsError::ScriptFailed { .. } => exit_code::SCRIPT_FAILED,
             NrsError::ConfigError { .. } => exit_code::INVALID_CONFIG,
             NrsError::InvalidConfig { .. } => exit_code::INVALID_CONFIG,
@@ -1,12 +1,17 @@
 //! Fuzzy matching implementation.
 //!
 //! Uses SkimMatcherV2 for high-performance fuzzy matching with scoring.
+//! The script filter can also use plain substring matching or nucleo.
 
+use std::borrow::Cow;
 use std::sync::OnceLock;
 
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher as FuzzyMatcherTrait;
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
 
+use crate::config::{FilterAlgorithm, FilterConfig};
 use crate::package::Script;
 
 /// Global matcher instance for performance.
@@ -173,11 +178,140 @@ pub fn filter_scripts(
     }
 
     // Sort by score descending (best matches first)
-    matches.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+    matches.sort_unstable_by_key(|&(_, score)| std::cmp::Reverse(score));
 
     matches
 }
 
+/// Filter scripts with the algorithm and options of a filter config.
+///
+/// Returns (index, score) pairs sorted by score descending, like
+/// [`filter_scripts`]. Description matches score half as much as name matches.
+pub fn filter_scripts_with_config(
+    query: &str,
+    scripts: &[Script],
+    config: &FilterConfig,
+) -> Vec<(usize, i64)> {
+    if query.is_empty() {
+        return (0..scripts.len()).map(|i| (i, 0)).collect();
+    }
+
+    let mut scorer = Scorer::new(config.matching_algorithm(), query, config.case_sensitive);
+    let mut matches: Vec<(usize, i64)> = scripts
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, script)| {
+            if let Some(score) = scorer.score(script.name()) {
+                return Some((idx, score));
+            }
+            let desc = script
+                .description()
+                .filter(|_| config.search_descriptions)?;
+            scorer.score(desc).map(|score| (idx, score / 2))
+        })
+        .collect();
+
+    matches.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    matches
+}
+
+/// Scores text against a query with one of the filter algorithms.
+enum Scorer {
+    Skim {
+        matcher: Box<SkimMatcherV2>,
+        query: String,
+        case_sensitive: bool,
+    },
+    Substring {
+        query: String,
+        case_sensitive: bool,
+    },
+    Nucleo {
+        matcher: Box<Matcher>,
+        pattern: Pattern,
+        buffer: Vec<char>,
+    },
+}
+
+impl Scorer {
+    fn new(algorithm: FilterAlgorithm, query: &str, case_sensitive: bool) -> Self {
+        let query_case = |query: &str| {
+            if case_sensitive {
+                query.to_string()
+            } else {
+                query.to_lowercase()
+            }
+        };
+        match algorithm {
+            FilterAlgorithm::Skim => {
+                let matcher = SkimMatcherV2::default();
+                Self::Skim {
+                    matcher: Box::new(if case_sensitive {
+                        matcher.respect_case()
+                    } else {
+                        matcher.ignore_case()
+                    }),
+                    query: query_case(query),
+                    case_sensitive,
+                }
+            }
+            FilterAlgorithm::Substring => Self::Substring {
+                query: query_case(query),
+                case_sensitive,
+            },
+            FilterAlgorithm::Nucleo => {
+                let case = if case_sensitive {
+                    CaseMatching::Respect
+                } else {
+                    CaseMatching::Ignore
+                };
+                Self::Nucleo {
+                    matcher: Box::new(Matcher::new(Config::DEFAULT)),
+                    pattern: Pattern::new(query, case, Normalization::Smart, AtomKind::Fuzzy),
+                    buffer: Vec::new(),
+                }
+            }
+        }
+    }
+
+    fn score(&mut self, text: &str) -> Option<i64> {
+        match self {
+            Self::Skim {
+                matcher,
+                query,
+                case_sensitive,
+            } => {
+                if *case_sensitive {
+                    matcher.fuzzy_match(text, query)
+                } else {
+                    matcher.fuzzy_match(&text.to_lowercase(), query)
+                }
+            }
+            Self::Substring {
+                query,
+                case_sensitive,
+            } => {
+                let text = if *case_sensitive {
+                    Cow::Borrowed(text)
+                } else {
+                    Cow::Owned(text.to_lowercase())
+                };
+                let position = text.find(query.as_str())?;
+                // Earlier and tighter matches first, prefixes best of all
+                let prefix_bonus = if position == 0 { 1000 } else { 0 };
+                Some(prefix_bonus - (position * 10 + text.len() - query.len()) as i64)
+            }
+            Self::Nucleo {
+                matcher,
+                pattern,
+                buffer,
+            } => pattern
+                .score(Utf32Str::new(text, buffer), matcher)
+                .map(i64::from),
+        }
+    }
+}
+
 /// Filter scripts using the FuzzyMatcher instance.
 ///
 /// Returns scripts sorted by match score (best first).
@@ -192,7 +326,7 @@ pub fn filter_scripts_with_matcher<'a>(
         .collect();
 
     // Sort by score (descending)
-    matches.sort_by(|a, b| b.1.cmp(&a.1));
+    matches.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
 
     matches
 }
@@ -283,6 +417,51 @@ mod tests {
 
     // ==================== FuzzyMatcher tests ====================
 
+    #[test]
+    fn test_filter_scripts_with_config_algorithms() {
+        let scripts = vec![
+            Script::new("build", "vite build"),
+            Script::new("test:build", "vitest"),
+            Script::with_description("dev", "vite", "Start the Build server"),
+        ];
+        let config = |algorithm| FilterConfig {
+            algorithm,
+            ..FilterConfig::default()
+        };
+        let names = |matches: Vec<(usize, i64)>| -> Vec<&str> {
+            matches.iter().map(|&(i, _)| scripts[i].name()).collect()
+        };
+
+        // "bd" only matches fuzzily
+        let skim = filter_scripts_with_config("bd", &scripts, &config(FilterAlgorithm::Skim));
+        assert_eq!(names(skim)[0], "build");
+        let nucleo = filter_scripts_with_config("bd", &scripts, &config(FilterAlgorithm::Nucleo));
+        assert_eq!(names(nucleo)[0], "build");
+        assert!(
+            filter_scripts_with_config("bd", &scripts, &config(FilterAlgorithm::Substring))
+                .is_empty()
+        );
+
+        // Prefix, then later, then description matches
+        let substring =
+            filter_scripts_with_config("build", &scripts, &config(FilterAlgorithm::Substring));
+        assert_eq!(names(substring), ["build", "test:build", "dev"]);
+
+        let legacy = FilterConfig {
+            fuzzy: false,
+            algorithm: FilterAlgorithm::Nucleo,
+            ..FilterConfig::default()
+        };
+        assert!(filter_scripts_with_config("bd", &scripts, &legacy).is_empty());
+
+        let case_sensitive = FilterConfig {
+            case_sensitive: true,
+            search_descriptions: false,
+            ..config(FilterAlgorithm::Nucleo)
+        };
+        assert!(filter_scripts_with_config("Build", &scripts, &case_sensitive).is_empty());
+    }
+
     #[test]
     fn test_empty_query_matches_all() {
         let matcher = FuzzyMatcher::new();
@@ -383,7 +562,7 @@ mod tests {
 
         // "dev" should match "development" and "deploy"
         let results = filter_scripts("dev", &scripts, false);
-        assert!(results.len() >= 1);
+        assert!(!results.is_empty());
         // First result should be "development" (better match)
         assert_eq!(results[0].0, 0);
     }
@@ -444,7 +623,7 @@ mod tests {
         ];
 
         let results = filter_scripts("bd", &scripts, false);
-        assert!(results.len() >= 1);
+        assert!(!results.is_empty());
         // Exact match "bd" should score higher
         assert_eq!(results[0].0, 0);
         assert!(results[0].1 > results.get(1).map(|r| r.1).unwrap_or(0));
@@ -458,7 +637,7 @@ mod tests {
         ];
 
         let results = filter_scripts("build", &scripts, false);
-        assert!(results.len() >= 1);
+        assert!(!results.is_empty());
         // "build" (prefix/exact) should score higher than "rebuild" (contains)
         assert_eq!(results[0].0, 1);
     }
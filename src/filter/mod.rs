@@ -5,6 +5,6 @@
 mod fuzzy;
 
 pub use fuzzy::{
-    filter_scripts, filter_scripts_with_matcher, get_match_indices, match_score, matches,
-    FuzzyMatcher,
+    filter_scripts, filter_scripts_with_config, filter_scripts_with_matcher, get_match_indices,
+    match_score, matches, FuzzyMatcher,
 };
@@ -0,0 +1,254 @@
+//! Anonymous project keys.
+//!
+//! With `history.hash_paths` enabled, projects are keyed by a salted SHA-256
+//! of their path instead of the path itself, so a history file synced with
+//! public dotfiles doesn't reveal where projects live.
+//!
+//! The salt is kept in `history-salt` next to the history file and never
+//! written into it: with the salt at hand, a guessed path could be hashed
+//! and looked up. Only history.json should be synced.
+
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use super::storage::History;
+use crate::config::HistoryConfig;
+use crate::utils::clock;
+
+/// Prefix marking a hashed project key.
+pub const HASHED_KEY_PREFIX: &str = "sha256:";
+
+/// Name of the salt file, next to the history file.
+pub(crate) const SALT_FILE: &str = "history-salt";
+
+impl History {
+    /// Get the path of the salt file.
+    pub fn salt_path() -> Option<PathBuf> {
+        Self::file_path().map(|p| p.with_file_name(SALT_FILE))
+    }
+
+    /// Load history from the default location, applying the path settings.
+    pub fn load_with_config(config: &HistoryConfig) -> Result<Self> {
+        let mut history = Self::load()?;
+        history.set_hash_paths(config.hash_paths);
+        Ok(history)
+    }
+
+    /// Enable or disable hashed project keys.
+    ///
+    /// Enabling creates a salt if needed and rehashes any plain paths already
+    /// stored. Hashes can't be reversed, so disabling only affects new entries.
+    /// A salt derived from `--seed` isn't saved, so stored paths aren't
+    /// rehashed with it: they couldn't be looked up again in later runs.
+    pub fn set_hash_paths(&mut self, enabled: bool) {
+        self.hash_paths = enabled;
+        if !enabled {
+            return;
+        }
+
+        let salt = match &self.salt {
+            Some(salt) => salt.clone(),
+            None => {
+                let (salt, seeded) = generate_salt();
+                self.salt = Some(salt.clone());
+                self.seeded_salt = seeded;
+                salt
+            }
+        };
+        if self.seeded_salt {
+            return;
+        }
+        let plain: Vec<PathBuf> = self
+            .projects
+            .keys()
+            .filter(|k| !is_hashed_key(k))
+            .cloned()
+            .collect();
+
+        for path in plain {
+            if let Some(project) = self.projects.remove(&path) {
+                let key = hash_project_path(&salt, &path);
+                match self.projects.get_mut(&key) {
+                    Some(existing) => existing.merge(project),
+                    None => {
+                        self.projects.insert(key, project);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get the key a project is stored under.
+    pub(crate) fn project_key(&self, project_dir: &Path) -> PathBuf {
+        match (&self.salt, self.hash_paths) {
+            (Some(salt), true) => hash_project_path(salt, project_dir),
+            _ => project_dir.to_path_buf(),
+        }
+    }
+}
+
+/// Hash a project path with the given salt.
+pub fn hash_project_path(salt: &str, path: &Path) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(path.to_string_lossy().as_bytes());
+
+    let hex: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    PathBuf::from(format!("{HASHED_KEY_PREFIX}{hex}"))
+}
+
+/// Read the salt from a salt file, if there is one.
+pub(crate) fn read_salt(path: &Path) -> Option<String> {
+    match fs::read_to_string(path) {
+        Ok(salt) => Some(salt.trim().to_string()).filter(|salt| !salt.is_empty()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => {
+            warn!("Failed to read {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+/// Write the salt to a salt file, unless it's already there.
+pub(crate) fn write_salt(path: &Path, salt: &str) -> Result<()> {
+    if read_salt(path).as_deref() == Some(salt) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    fs::write(path, format!("{salt}\n"))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Check if a project key is a hash rather than a path.
+pub(crate) fn is_hashed_key(key: &Path) -> bool {
+    key.to_str()
+        .is_some_and(|k| k.starts_with(HASHED_KEY_PREFIX))
+}
+
+/// Generate a salt, and tell whether it was derived from `--seed`.
+///
+/// With a pinned `--seed` the salt is derived from the seed, for
+/// reproducible output. Anyone could derive it too, so it must not be saved.
+fn generate_salt() -> (String, bool) {
+    if let Some(seed) = clock::seed() {
+        let mut hasher = Sha256::new();
+        hasher.update(seed.to_le_bytes());
+        let salt = hasher.finalize()[..16]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        return (salt, true);
+    }
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+
+    // RandomState is seeded from the OS on creation
+    let salt = (0..2)
+        .map(|i| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(nanos);
+            hasher.write_u32(std::process::id());
+            hasher.write_u8(i);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect();
+    (salt, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashed_keys_hide_paths() {
+        let mut history = History::new();
+        history.set_hash_paths(true);
+        history.record_run(Path::new("/clients/acme/web"), "dev", None);
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert!(!json.contains("acme"));
+        assert!(json.contains(HASHED_KEY_PREFIX));
+
+        // Lookups by path still work
+        assert_eq!(
+            history
+                .get_project(Path::new("/clients/acme/web"))
+                .and_then(|p| p.last_script()),
+            Some("dev")
+        );
+    }
+
+    #[test]
+    fn test_salt_is_left_out_of_history_file() {
+        let mut history = History::new();
+        history.set_hash_paths(true);
+        history.record_run(Path::new("/work/app"), "build", None);
+        let salt = history.salt.clone().unwrap();
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert!(!json.contains(&salt));
+        assert!(!json.contains("\"salt\""));
+    }
+
+    #[test]
+    fn test_salt_from_older_history_file_is_read() {
+        let mut history = History::new();
+        history.salt = Some("old".to_string());
+        history.set_hash_paths(true);
+        history.record_run(Path::new("/work/app"), "build", None);
+
+        let mut json: serde_json::Value = serde_json::to_value(&history).unwrap();
+        json["salt"] = "old".into();
+        let mut loaded: History = serde_json::from_value(json).unwrap();
+        loaded.set_hash_paths(true);
+
+        assert!(loaded.get_project(Path::new("/work/app")).is_some());
+    }
+
+    #[test]
+    fn test_enabling_rehashes_existing_paths() {
+        let mut history = History::new();
+        history.record_run(Path::new("/work/app"), "dev", None);
+
+        history.set_hash_paths(true);
+        history.record_run(Path::new("/work/app"), "dev", None);
+
+        assert_eq!(history.projects.len(), 1);
+        assert!(history.projects.keys().all(|k| is_hashed_key(k)));
+        let stats = history
+            .get_script_stats(Path::new("/work/app"), "dev")
+            .unwrap();
+        assert_eq!(stats.count, 2);
+    }
+
+    #[test]
+    fn test_hash_depends_on_salt() {
+        let path = Path::new("/work/app");
+        assert_eq!(hash_project_path("a", path), hash_project_path("a", path));
+        assert_ne!(hash_project_path("a", path), hash_project_path("b", path));
+    }
+
+    #[test]
+    fn test_generate_salt_is_random() {
+        let (salt, seeded) = generate_salt();
+        assert_eq!(salt.len(), 32);
+        assert!(!seeded);
+        assert_ne!(salt, generate_salt().0);
+    }
+}
@@ -0,0 +1,157 @@
+//! One-line project summaries for the shell directory hook.
+//!
+//! `nrs --brief` runs every time the shell enters a project, so the line is
+//! cached in `brief.json` in the cache directory. An entry is reused until
+//! package.json or the history file changes. Projects are keyed by a hash of
+//! their path, so the cache never holds plain paths.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, trace};
+
+use super::storage::History;
+use crate::utils::cache_dir;
+
+/// Number of scripts named in a summary line.
+pub const BRIEF_SCRIPTS: usize = 5;
+
+/// Projects remembered before the cache starts over.
+const MAX_ENTRIES: usize = 500;
+
+/// Cached summary lines per project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BriefCache {
+    #[serde(default)]
+    entries: BTreeMap<String, BriefEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct BriefEntry {
+    stamp: String,
+    line: String,
+}
+
+impl BriefCache {
+    /// Get the cache file path.
+    pub fn file_path() -> Option<PathBuf> {
+        cache_dir().map(|p| p.join("brief.json"))
+    }
+
+    /// Load the cache, or an empty one if it is missing or unreadable.
+    pub fn load() -> Self {
+        let Some(path) = Self::file_path() else {
+            return Self::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+                debug!("Ignoring corrupt brief cache {}: {err}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save the cache to the default location.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path().context("Could not determine cache directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        let content = serde_json::to_string(self).context("Failed to serialize brief cache")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write brief cache to {}", path.display()))?;
+        trace!("Saved brief cache to {}", path.display());
+        Ok(())
+    }
+
+    /// Get the cached line of a project, if it was made from the same files.
+    pub fn get(&self, project_dir: &Path, stamp: &str) -> Option<&str> {
+        self.entries
+            .get(&cache_key(project_dir))
+            .filter(|entry| entry.stamp == stamp)
+            .map(|entry| entry.line.as_str())
+    }
+
+    /// Cache the line of a project.
+    pub fn insert(&mut self, project_dir: &Path, stamp: String, line: String) {
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.clear();
+        }
+        self.entries
+            .insert(cache_key(project_dir), BriefEntry { stamp, line });
+    }
+}
+
+/// Describe the state of the files a project's summary is made from.
+///
+/// Changes whenever package.json or the history file is written.
+pub fn brief_stamp(project_dir: &Path) -> String {
+    let files = [Some(project_dir.join("package.json")), History::file_path()];
+    files
+        .iter()
+        .map(|path| {
+            let Some(metadata) = path.as_ref().and_then(|p| fs::metadata(p).ok()) else {
+                return "-".to_string();
+            };
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |since| since.as_nanos());
+            format!("{modified}:{}", metadata.len())
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Format the summary line from the top script names and the total count.
+pub fn format_brief(names: &[&str], total: usize) -> String {
+    if names.is_empty() {
+        return "no scripts".to_string();
+    }
+    let mut line = names.join(", ");
+    if total > names.len() {
+        line.push_str(&format!(" +{} more", total - names.len()));
+    }
+    line
+}
+
+/// Hash a project path into a cache key.
+fn cache_key(project_dir: &Path) -> String {
+    let digest = Sha256::digest(project_dir.to_string_lossy().as_bytes());
+    digest.iter().take(8).map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brief_cache() {
+        let dir = Path::new("/home/user/app");
+        let mut cache = BriefCache::default();
+        assert_eq!(cache.get(dir, "1"), None);
+
+        cache.insert(dir, "1".to_string(), "dev, build".to_string());
+        assert_eq!(cache.get(dir, "1"), Some("dev, build"));
+        assert_eq!(cache.get(dir, "2"), None);
+        assert_eq!(cache.get(Path::new("/home/user/other"), "1"), None);
+
+        let json = serde_json::to_string(&cache).unwrap();
+        assert!(!json.contains("/home/user"));
+    }
+
+    #[test]
+    fn test_format_brief() {
+        assert_eq!(format_brief(&["dev", "build"], 2), "dev, build");
+        assert_eq!(format_brief(&["dev", "build"], 9), "dev, build +7 more");
+        assert_eq!(format_brief(&[], 0), "no scripts");
+    }
+}
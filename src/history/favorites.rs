@@ -0,0 +1,189 @@
+//! Pinned scripts.
+//!
+//! Scripts pinned from the TUI are stored per project in `favorites.json`,
+//! next to the history file. Unlike the shared `scripts.favorites` config
+//! list, these are personal and never leave the machine.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
+
+use super::storage::History;
+
+/// Scripts pinned per project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Favorites {
+    /// Pinned script names per project path, in the order they were pinned.
+    #[serde(default)]
+    pub projects: BTreeMap<PathBuf, Vec<String>>,
+}
+
+impl Favorites {
+    /// Create an empty favorites store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the favorites file path.
+    pub fn file_path() -> Option<PathBuf> {
+        History::file_path().map(|p| p.with_file_name("favorites.json"))
+    }
+
+    /// Load favorites from the default location.
+    ///
+    /// Missing or unreadable files give an empty store.
+    pub fn load() -> Result<Self> {
+        let path = Self::file_path().context("Could not determine state directory")?;
+
+        if !path.exists() {
+            debug!("No favorites file at {}", path.display());
+            return Ok(Self::new());
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!(
+                    "Warning: Failed to read favorites file {}: {}",
+                    path.display(),
+                    e
+                );
+                return Ok(Self::new());
+            }
+        };
+
+        match serde_json::from_str(&content) {
+            Ok(favorites) => Ok(favorites),
+            Err(e) => {
+                eprintln!("Warning: Favorites file is corrupt, ignoring it: {}", e);
+                Ok(Self::new())
+            }
+        }
+    }
+
+    /// Save favorites to the default location.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path().context("Could not determine state directory")?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize favorites")?;
+
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write favorites to {}", path.display()))?;
+        trace!("Saved favorites to {}", path.display());
+
+        Ok(())
+    }
+
+    /// Get the scripts pinned in a project.
+    pub fn get(&self, project_dir: &Path) -> &[String] {
+        self.projects
+            .get(project_dir)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Check if a script is pinned in a project.
+    pub fn is_pinned(&self, project_dir: &Path, script: &str) -> bool {
+        self.get(project_dir).iter().any(|name| name == script)
+    }
+
+    /// Pin a script, or unpin it if it is already pinned.
+    ///
+    /// Returns whether the script is pinned afterwards.
+    pub fn toggle(&mut self, project_dir: &Path, script: &str) -> bool {
+        let pinned = self.projects.entry(project_dir.to_path_buf()).or_default();
+        let now_pinned = match pinned.iter().position(|name| name == script) {
+            Some(index) => {
+                pinned.remove(index);
+                false
+            }
+            None => {
+                pinned.push(script.to_string());
+                true
+            }
+        };
+
+        if pinned.is_empty() {
+            self.projects.remove(project_dir);
+        }
+        now_pinned
+    }
+
+    /// Keep a script pinned after it was renamed.
+    ///
+    /// Returns whether the script was pinned.
+    pub fn rename(&mut self, project_dir: &Path, from: &str, to: &str) -> bool {
+        let Some(name) = self
+            .projects
+            .get_mut(project_dir)
+            .and_then(|pinned| pinned.iter_mut().find(|name| *name == from))
+        else {
+            return false;
+        };
+        *name = to.to_string();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_pins_and_unpins() {
+        let mut favorites = Favorites::new();
+        let project = Path::new("/work/app");
+
+        assert!(favorites.toggle(project, "dev"));
+        assert!(favorites.toggle(project, "test"));
+        assert!(favorites.is_pinned(project, "dev"));
+        assert_eq!(favorites.get(project), ["dev", "test"]);
+
+        assert!(favorites.rename(project, "test", "unit"));
+        assert!(!favorites.rename(project, "lint", "check"));
+        assert_eq!(favorites.get(project), ["dev", "unit"]);
+
+        assert!(!favorites.toggle(project, "dev"));
+        assert!(!favorites.is_pinned(project, "dev"));
+        assert_eq!(favorites.get(project), ["unit"]);
+    }
+
+    #[test]
+    fn test_favorites_are_per_project() {
+        let mut favorites = Favorites::new();
+        favorites.toggle(Path::new("/work/app"), "dev");
+
+        assert!(!favorites.is_pinned(Path::new("/work/other"), "dev"));
+        assert!(favorites.get(Path::new("/work/other")).is_empty());
+    }
+
+    #[test]
+    fn test_unpinning_last_script_drops_project() {
+        let mut favorites = Favorites::new();
+        let project = Path::new("/work/app");
+        favorites.toggle(project, "dev");
+        favorites.toggle(project, "dev");
+
+        assert!(favorites.projects.is_empty());
+    }
+
+    #[test]
+    fn test_favorites_roundtrip() {
+        let mut favorites = Favorites::new();
+        favorites.toggle(Path::new("/work/app"), "build");
+
+        let json = serde_json::to_string(&favorites).unwrap();
+        let loaded: Favorites = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, favorites);
+    }
+}
@@ -3,8 +3,27 @@
 //! Tracks script execution history per project for recent sorting
 //! and quick rerun functionality.
 
+mod anonymize;
+mod brief;
+mod favorites;
+mod projects;
+mod repair;
+mod rotate;
+mod search;
+mod stats;
 mod storage;
 
+pub use anonymize::{hash_project_path, HASHED_KEY_PREFIX};
+pub use brief::{brief_stamp, format_brief, BriefCache, BRIEF_SCRIPTS};
+pub use favorites::Favorites;
+pub use projects::{projects_report, RecentProject};
+pub use repair::{repair_history, salvage_history, RepairReport, Salvage};
+pub use rotate::{rotated_snapshots, MAX_ROTATED_SNAPSHOTS};
+pub use search::{
+    last_failed_run, last_matching_run, project_runs, runs_report, search_history, HistoryMatch,
+};
+pub use stats::{weekday, ProjectStats, ScriptStats, STATS_SCRIPTS};
 pub use storage::{
-    History, ProjectHistory, ScriptHistory, DEFAULT_MAX_PROJECTS, DEFAULT_MAX_SCRIPTS,
+    History, ProjectHistory, RecentArgs, RecentRun, ScriptHistory, DEFAULT_MAX_PROJECTS,
+    DEFAULT_MAX_SCRIPTS, MAX_FILTER_QUERIES, MAX_RECENT_ARGS,
 };
@@ -0,0 +1,191 @@
+//! Projects in the history.
+//!
+//! `nrs history projects` lists every project scripts were run in, pinned
+//! ones first, and flags directories that no longer exist so `nrs history
+//! prune` can drop them in one go. Hashed project keys can't be traced back
+//! to a directory, so they are listed but never pruned.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use super::anonymize::{hash_project_path, is_hashed_key};
+use super::storage::History;
+use crate::output::Report;
+
+/// A project in the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentProject {
+    /// Project directory, or its hash with `history.hash_paths`.
+    pub path: PathBuf,
+    /// Last time a script was run in the project.
+    pub last_run: DateTime<Utc>,
+    /// Number of script runs recorded.
+    pub runs: u64,
+    /// Whether the project is pinned to the top.
+    pub pinned: bool,
+    /// Whether the project directory no longer exists.
+    pub missing: bool,
+}
+
+/// Get a row per project, for `nrs history projects --format`.
+pub fn projects_report(projects: &[RecentProject]) -> Report {
+    let mut report = Report::new(["path", "last_run", "runs", "pinned", "missing"]);
+    for project in projects {
+        report.push([
+            json!(project.path.display().to_string()),
+            json!(project.last_run.to_rfc3339()),
+            json!(project.runs),
+            json!(project.pinned),
+            json!(project.missing),
+        ]);
+    }
+    report
+}
+
+impl History {
+    /// List the projects, pinned first, then most recently used first.
+    pub fn recent_projects(&self) -> Vec<RecentProject> {
+        let mut projects: Vec<RecentProject> = self
+            .projects
+            .iter()
+            .map(|(path, project)| RecentProject {
+                path: path.clone(),
+                last_run: project.last_run,
+                runs: project.scripts.values().map(|s| u64::from(s.count)).sum(),
+                pinned: project.pinned,
+                missing: is_missing(path),
+            })
+            .collect();
+
+        projects.sort_by(|a, b| {
+            b.pinned
+                .cmp(&a.pinned)
+                .then(b.last_run.cmp(&a.last_run))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        projects
+    }
+
+    /// Pin a project to the top of the list, or unpin it.
+    ///
+    /// Returns `false` if the project isn't in the history.
+    pub fn set_project_pinned(&mut self, project_dir: &Path, pinned: bool) -> bool {
+        let Some(key) = self.stored_key(project_dir) else {
+            return false;
+        };
+        match self.projects.get_mut(&key) {
+            Some(project) => {
+                project.pinned = pinned;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the projects whose directory no longer exists, sorted.
+    ///
+    /// Pinned projects are left out, so a pinned project on an unmounted
+    /// drive survives pruning.
+    pub fn missing_projects(&self) -> Vec<PathBuf> {
+        let mut missing: Vec<PathBuf> = self
+            .projects
+            .iter()
+            .filter(|(path, project)| !project.pinned && is_missing(path))
+            .map(|(path, _)| path.clone())
+            .collect();
+        missing.sort();
+        missing
+    }
+
+    /// Remove the projects whose directory no longer exists.
+    ///
+    /// Returns the removed paths, sorted.
+    pub fn prune_missing_projects(&mut self) -> Vec<PathBuf> {
+        let missing = self.missing_projects();
+        for path in &missing {
+            self.projects.remove(path);
+        }
+        missing
+    }
+
+    /// Find the key a project is stored under, plain or hashed.
+    ///
+    /// Both are tried, so projects can be pinned without knowing whether
+    /// `history.hash_paths` was on when they were recorded.
+    fn stored_key(&self, project_dir: &Path) -> Option<PathBuf> {
+        if self.projects.contains_key(project_dir) {
+            return Some(project_dir.to_path_buf());
+        }
+        let hashed = hash_project_path(self.salt.as_ref()?, project_dir);
+        self.projects.contains_key(&hashed).then_some(hashed)
+    }
+}
+
+/// Check if a project key is a directory that no longer exists.
+fn is_missing(path: &Path) -> bool {
+    !is_hashed_key(path) && !path.is_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_recent_projects_pinned_first() {
+        let live = tempfile::TempDir::new().unwrap();
+        let gone = Path::new("/nonexistent/nrs/old-app");
+        let mut history = History::new();
+        history.record_run(live.path(), "dev", None);
+        history.record_run(live.path(), "dev", None);
+        history.record_run(gone, "build", None);
+        history.get_project_mut(live.path()).unwrap().last_run -= Duration::days(3);
+
+        let paths: Vec<PathBuf> = history
+            .recent_projects()
+            .into_iter()
+            .map(|p| p.path)
+            .collect();
+        assert_eq!(paths, [gone.to_path_buf(), live.path().to_path_buf()]);
+
+        assert!(history.set_project_pinned(live.path(), true));
+        assert!(!history.set_project_pinned(Path::new("/unknown"), true));
+        let projects = history.recent_projects();
+        assert_eq!(projects[0].path, live.path());
+        assert!(projects[0].pinned);
+        assert_eq!(projects[0].runs, 2);
+        assert!(!projects[0].missing);
+        assert!(projects[1].missing);
+    }
+
+    #[test]
+    fn test_prune_missing_projects() {
+        let live = tempfile::TempDir::new().unwrap();
+        let mut history = History::new();
+        history.record_run(live.path(), "dev", None);
+        history.record_run(Path::new("/nonexistent/nrs/a"), "dev", None);
+        history.record_run(Path::new("/nonexistent/nrs/b"), "dev", None);
+        history.set_project_pinned(Path::new("/nonexistent/nrs/b"), true);
+
+        let removed = history.prune_missing_projects();
+        assert_eq!(removed, [PathBuf::from("/nonexistent/nrs/a")]);
+        assert_eq!(history.projects.len(), 2);
+        assert!(history.missing_projects().is_empty());
+    }
+
+    #[test]
+    fn test_pin_hashed_project() {
+        let mut history = History::new();
+        history.set_hash_paths(true);
+        history.record_run(Path::new("/work/app"), "dev", None);
+        history.set_hash_paths(false);
+
+        assert!(history.set_project_pinned(Path::new("/work/app"), true));
+        let projects = history.recent_projects();
+        assert!(projects[0].pinned);
+        assert!(!projects[0].missing);
+        assert!(history.prune_missing_projects().is_empty());
+    }
+}
@@ -0,0 +1,415 @@
+//! Recovery of corrupt history files.
+//!
+//! A corrupt `history.json` is moved aside to `history.json.bak` when loaded.
+//! Repair salvages every project entry that is still intact, even from a
+//! truncated file, and merges the result back into the live history.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::anonymize::{write_salt, SALT_FILE};
+use super::storage::{History, ProjectHistory, MAX_FILTER_QUERIES, MAX_RECENT_ARGS};
+
+/// Result of salvaging a history file.
+#[derive(Debug, Default)]
+pub struct Salvage {
+    /// History rebuilt from the intact entries.
+    pub history: History,
+    /// Number of project entries recovered.
+    pub recovered: usize,
+    /// Number of project entries that could not be parsed.
+    pub lost: usize,
+}
+
+/// Summary of a repair run.
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    /// Project entries recovered from the backup.
+    pub recovered: usize,
+    /// Project entries in the backup that could not be recovered.
+    pub lost: usize,
+    /// Where the merged backup was moved, if there was one.
+    pub archived_backup: Option<PathBuf>,
+}
+
+/// Salvage intact project entries from possibly corrupt history JSON.
+///
+/// Valid JSON with bad entries keeps every entry that still deserializes.
+/// Invalid JSON (e.g. a truncated write) is scanned entry by entry until the
+/// damage is reached.
+pub fn salvage_history(content: &str) -> Salvage {
+    if let Ok(history) = serde_json::from_str::<History>(content) {
+        let recovered = history.projects.len();
+        return Salvage {
+            history,
+            recovered,
+            lost: 0,
+        };
+    }
+
+    let mut salvage = Salvage::default();
+    let Some(start) = find_projects_object(content) else {
+        return salvage;
+    };
+
+    let mut scanner = Scanner::new(content, start);
+    loop {
+        scanner.skip_whitespace_and_commas();
+        if scanner.at_end() || scanner.peek() == Some('}') {
+            break;
+        }
+
+        let Some(key) = scanner.string() else {
+            break;
+        };
+        scanner.skip_whitespace();
+        if scanner.next() != Some(':') {
+            salvage.lost += 1;
+            break;
+        }
+        scanner.skip_whitespace();
+
+        let Some(value) = scanner.object() else {
+            // Truncated or malformed value: nothing after it can be trusted
+            salvage.lost += 1;
+            break;
+        };
+
+        match (
+            serde_json::from_str::<String>(key),
+            serde_json::from_str::<ProjectHistory>(value),
+        ) {
+            (Ok(path), Ok(project)) => {
+                salvage
+                    .history
+                    .projects
+                    .insert(PathBuf::from(path), project);
+                salvage.recovered += 1;
+            }
+            _ => salvage.lost += 1,
+        }
+    }
+
+    salvage
+}
+
+/// Repair history by merging the salvaged backup into the live file.
+///
+/// The live file is salvaged too if it is itself corrupt. After a successful
+/// merge the backup is renamed to `*.repaired` rather than deleted.
+pub fn repair_history(history_path: &Path, backup_path: &Path) -> Result<RepairReport> {
+    let mut report = RepairReport::default();
+
+    let mut history = match fs::read_to_string(history_path) {
+        Ok(content) => salvage_history(&content).history,
+        Err(_) => History::new(),
+    };
+
+    if !backup_path.exists() {
+        return Ok(report);
+    }
+
+    let content = fs::read_to_string(backup_path)
+        .with_context(|| format!("Failed to read {}", backup_path.display()))?;
+    let salvage = salvage_history(&content);
+    report.recovered = salvage.recovered;
+    report.lost = salvage.lost;
+
+    history.merge(salvage.history);
+    if let Some(salt) = &history.salt {
+        write_salt(&history_path.with_file_name(SALT_FILE), salt)?;
+    }
+    let json = serde_json::to_string_pretty(&history).context("Failed to serialize history")?;
+    fs::write(history_path, json)
+        .with_context(|| format!("Failed to write {}", history_path.display()))?;
+
+    let archived = backup_path.with_extension("bak.repaired");
+    fs::rename(backup_path, &archived)
+        .with_context(|| format!("Failed to archive {}", backup_path.display()))?;
+    report.archived_backup = Some(archived);
+
+    Ok(report)
+}
+
+impl History {
+    /// Merge another history into this one.
+    ///
+    /// Runs recorded in both are added together, keeping the most recent
+    /// timestamps and arguments, so a backup and the history started after
+    /// it combine without losing frecency.
+    pub fn merge(&mut self, other: History) {
+        // Hashed project keys are only usable with the salt they were made with
+        if self.salt.is_none() {
+            self.salt = other.salt;
+        }
+
+        for (path, theirs) in other.projects {
+            match self.projects.get_mut(&path) {
+                Some(ours) => ours.merge(theirs),
+                None => {
+                    self.projects.insert(path, theirs);
+                }
+            }
+        }
+    }
+}
+
+impl ProjectHistory {
+    /// Merge another project's history into this one.
+    pub fn merge(&mut self, other: ProjectHistory) {
+        self.pinned |= other.pinned;
+        if other.last_run > self.last_run {
+            self.last_run = other.last_run;
+            self.last_script = other.last_script;
+        }
+
+        for (name, theirs) in other.scripts {
+            match self.scripts.get_mut(&name) {
+                Some(ours) => {
+                    ours.count = ours.count.saturating_add(theirs.count);
+                    ours.failed_runs = ours.failed_runs.saturating_add(theirs.failed_runs);
+                    if theirs.last_run > ours.last_run {
+                        ours.last_run = theirs.last_run;
+                        ours.last_args = theirs.last_args;
+                        if theirs.last_command.is_some() {
+                            ours.last_command = theirs.last_command;
+                        }
+                    }
+
+                    ours.recent_args.extend(theirs.recent_args);
+                    ours.recent_args
+                        .sort_by_key(|a| std::cmp::Reverse(a.last_run));
+                    let mut seen = HashSet::new();
+                    ours.recent_args.retain(|a| seen.insert(a.args.clone()));
+                    ours.recent_args.truncate(MAX_RECENT_ARGS);
+                }
+                None => {
+                    self.scripts.insert(name, theirs);
+                }
+            }
+        }
+
+        for (ours, theirs) in self.weekday_runs.iter_mut().zip(other.weekday_runs) {
+            *ours = ours.saturating_add(theirs);
+        }
+
+        // Ours are newer; older queries fill the remaining slots
+        for query in other.filters {
+            if !self.filters.contains(&query) {
+                self.filters.push(query);
+            }
+        }
+        self.filters.truncate(MAX_FILTER_QUERIES);
+
+        if self.collapsed_groups.is_empty() {
+            self.collapsed_groups = other.collapsed_groups;
+        }
+    }
+}
+
+/// Find the byte offset just inside the top-level `"projects"` object.
+fn find_projects_object(content: &str) -> Option<usize> {
+    let key = content.find("\"projects\"")?;
+    let rest = &content[key + "\"projects\"".len()..];
+    let colon = rest.find(':')?;
+    let brace = rest[colon..].find('{')?;
+    Some(key + "\"projects\"".len() + colon + brace + 1)
+}
+
+/// Minimal JSON tokenizer for walking a damaged document.
+struct Scanner<'a> {
+    content: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(content: &'a str, pos: usize) -> Self {
+        Self { content, pos }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.content.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.content[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    fn skip_whitespace_and_commas(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace() || c == ',') {
+            self.next();
+        }
+    }
+
+    /// Consume a JSON string, returning it with its quotes.
+    fn string(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        if self.next()? != '"' {
+            return None;
+        }
+        loop {
+            match self.next()? {
+                '\\' => {
+                    self.next()?;
+                }
+                '"' => return Some(&self.content[start..self.pos]),
+                _ => {}
+            }
+        }
+    }
+
+    /// Consume a complete JSON object, returning its text.
+    fn object(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        if self.peek()? != '{' {
+            return None;
+        }
+
+        let mut depth = 0usize;
+        loop {
+            match self.peek()? {
+                '"' => {
+                    self.string()?;
+                    continue;
+                }
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.next();
+                        return Some(&self.content[start..self.pos]);
+                    }
+                }
+                _ => {}
+            }
+            self.next();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_history() -> History {
+        let mut history = History::new();
+        history.record_run(Path::new("/work/app"), "dev", Some("--host".to_string()));
+        history.record_run(Path::new("/work/api"), "build", None);
+        history
+    }
+
+    #[test]
+    fn test_salvage_valid_history() {
+        let json = serde_json::to_string(&sample_history()).unwrap();
+        let salvage = salvage_history(&json);
+        assert_eq!(salvage.recovered, 2);
+        assert_eq!(salvage.lost, 0);
+    }
+
+    #[test]
+    fn test_salvage_truncated_history() {
+        let json = serde_json::to_string_pretty(&sample_history()).unwrap();
+        // Cut the file in the middle of the second project entry
+        let second = json.rfind("\"/work/").unwrap();
+        let truncated = &json[..second + 30];
+
+        let salvage = salvage_history(truncated);
+        assert_eq!(salvage.recovered, 1);
+        assert_eq!(salvage.lost, 1);
+    }
+
+    #[test]
+    fn test_salvage_skips_bad_entries() {
+        let json = r#"{
+            "version": 1,
+            "projects": {
+                "/work/bad": { "last_run": "not a date" },
+                "/work/good": { "last_script": "dev", "last_run": "2024-01-01T00:00:00Z", "scripts": {} }
+            }
+        }"#;
+
+        let salvage = salvage_history(json);
+        assert_eq!(salvage.recovered, 1);
+        assert_eq!(salvage.lost, 1);
+        assert!(salvage
+            .history
+            .get_project(Path::new("/work/good"))
+            .is_some());
+    }
+
+    #[test]
+    fn test_salvage_garbage() {
+        let salvage = salvage_history("not json at all");
+        assert_eq!(salvage.recovered, 0);
+        assert!(salvage.history.projects.is_empty());
+    }
+
+    #[test]
+    fn test_merge_adds_counts() {
+        let mut ours = History::new();
+        ours.record_run(Path::new("/work/app"), "dev", None);
+
+        let mut theirs = sample_history();
+        theirs.record_run(Path::new("/work/app"), "dev", None);
+
+        ours.merge(theirs);
+        let dev = ours
+            .get_script_stats(Path::new("/work/app"), "dev")
+            .unwrap();
+        assert_eq!(dev.count, 3);
+        assert!(ours.get_project(Path::new("/work/api")).is_some());
+    }
+
+    #[test]
+    fn test_repair_history_merges_backup() {
+        let temp = TempDir::new().unwrap();
+        let history_path = temp.path().join("history.json");
+        let backup_path = temp.path().join("history.json.bak");
+
+        let mut current = History::new();
+        current.record_run(Path::new("/work/new"), "test", None);
+        fs::write(&history_path, serde_json::to_string(&current).unwrap()).unwrap();
+
+        let backup = serde_json::to_string_pretty(&sample_history()).unwrap();
+        fs::write(&backup_path, &backup[..backup.len() - 10]).unwrap();
+
+        let report = repair_history(&history_path, &backup_path).unwrap();
+        assert!(!backup_path.exists());
+        assert!(report.archived_backup.unwrap().exists());
+
+        let content = fs::read_to_string(&history_path).unwrap();
+        let repaired: History = serde_json::from_str(&content).unwrap();
+        assert!(repaired.get_project(Path::new("/work/new")).is_some());
+        // Only the entry cut off by the truncation is lost
+        assert_eq!(report.recovered, 1);
+        assert_eq!(repaired.projects.len(), 2);
+    }
+
+    #[test]
+    fn test_repair_without_backup() {
+        let temp = TempDir::new().unwrap();
+        let report = repair_history(
+            &temp.path().join("history.json"),
+            &temp.path().join("history.json.bak"),
+        )
+        .unwrap();
+        assert_eq!(report.recovered, 0);
+        assert!(report.archived_backup.is_none());
+    }
+}
@@ -0,0 +1,291 @@
+//! Size-based history rotation.
+//!
+//! When the history file grows past `history.max_size_kb`, the full file is
+//! kept as a timestamped snapshot next to it and the oldest projects and
+//! scripts are pruned from the live history.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tracing::info;
+
+use super::storage::History;
+use crate::config::HistoryConfig;
+use crate::utils::clock;
+
+/// Number of rotated snapshots kept next to the history file.
+pub const MAX_ROTATED_SNAPSHOTS: usize = 3;
+
+/// Pruning stops at this share of the limit, so the next few saves don't
+/// rotate again straight away.
+const PRUNE_TARGET_PERCENT: usize = 75;
+
+/// File name prefix of rotated snapshots.
+const SNAPSHOT_PREFIX: &str = "history-";
+
+impl History {
+    /// Size of the history as written to disk, in bytes.
+    pub fn serialized_size(&self) -> usize {
+        serde_json::to_string_pretty(self).map_or(0, |s| s.len())
+    }
+
+    /// Prune the oldest entries until the history fits in `max_bytes`.
+    ///
+    /// Whole projects go first, least recently used first, except pinned
+    /// ones. If the projects left are still too large, the oldest scripts of
+    /// one of them are dropped.
+    /// Returns the number of projects and scripts removed.
+    pub fn prune_to_size(&mut self, max_bytes: usize) -> usize {
+        let mut removed = 0;
+
+        while self.serialized_size() > max_bytes {
+            if self.projects.len() > 1 {
+                let oldest = self
+                    .projects
+                    .iter()
+                    .filter(|(_, p)| !p.pinned)
+                    .min_by_key(|(_, p)| p.last_run)
+                    .map(|(path, _)| path.clone());
+                if let Some(path) = oldest {
+                    self.projects.remove(&path);
+                    removed += 1;
+                    continue;
+                }
+            }
+
+            let Some(project) = self.projects.values_mut().next() else {
+                break;
+            };
+            let oldest = project
+                .scripts
+                .iter()
+                .min_by_key(|(_, h)| h.last_run)
+                .map(|(name, _)| name.clone());
+            match oldest {
+                Some(name) => {
+                    project.scripts.remove(&name);
+                    removed += 1;
+                }
+                None => {
+                    project.filters.clear();
+                    break;
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// Save history to the default location, enforcing the configured limits.
+    ///
+    /// Does nothing when history is disabled.
+    pub fn save_with_config(&mut self, config: &HistoryConfig) -> Result<()> {
+        if !config.enabled {
+            return Ok(());
+        }
+        let path = Self::file_path().context("Could not determine state directory")?;
+        self.enforce_limits(&path, config)?;
+        self.save()
+    }
+
+    /// Apply the count limits, rotating a snapshot out if the size limit is hit.
+    fn enforce_limits(&mut self, path: &Path, config: &HistoryConfig) -> Result<()> {
+        self.cleanup(config);
+
+        if config.max_size_kb == 0 {
+            return Ok(());
+        }
+
+        let max_bytes = config.max_size_kb * 1024;
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize history")?;
+        if content.len() <= max_bytes {
+            return Ok(());
+        }
+
+        let dir = path
+            .parent()
+            .context("History file has no parent directory")?;
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+        let snapshot = write_snapshot(dir, &content, config.compress_rotated)?;
+        prune_snapshots(dir, MAX_ROTATED_SNAPSHOTS)?;
+
+        let removed = self.prune_to_size(max_bytes * PRUNE_TARGET_PERCENT / 100);
+        info!(
+            "History exceeded {} KB: pruned {} entries, snapshot at {}",
+            config.max_size_kb,
+            removed,
+            snapshot.display()
+        );
+        Ok(())
+    }
+}
+
+/// Write a timestamped snapshot of the history into `dir`.
+fn write_snapshot(dir: &Path, content: &str, compress: bool) -> Result<PathBuf> {
+    let stamp = clock::now().format("%Y%m%d-%H%M%S");
+    let path = if compress {
+        dir.join(format!("{SNAPSHOT_PREFIX}{stamp}.json.gz"))
+    } else {
+        dir.join(format!("{SNAPSHOT_PREFIX}{stamp}.json"))
+    };
+
+    if compress {
+        let file =
+            File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder
+            .write_all(content.as_bytes())
+            .and_then(|_| encoder.finish().map(|_| ()))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    } else {
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(path)
+}
+
+/// List rotated snapshots in `dir`, oldest first.
+pub fn rotated_snapshots(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                n.starts_with(SNAPSHOT_PREFIX) && (n.ends_with(".json") || n.ends_with(".json.gz"))
+            })
+        })
+        .collect();
+
+    // Timestamps sort lexically
+    snapshots.sort();
+    snapshots
+}
+
+/// Delete the oldest snapshots, keeping at most `keep`.
+fn prune_snapshots(dir: &Path, keep: usize) -> Result<()> {
+    let snapshots = rotated_snapshots(dir);
+    let excess = snapshots.len().saturating_sub(keep);
+    for path in snapshots.into_iter().take(excess) {
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn large_history(projects: usize) -> History {
+        let mut history = History::new();
+        let now = Utc::now();
+        for i in 0..projects {
+            let path = PathBuf::from(format!("/work/project-{i}"));
+            history.record_run(&path, "dev", Some("--host 0.0.0.0".to_string()));
+            history.get_project_mut(&path).unwrap().last_run = now - Duration::days(i as i64);
+        }
+        history
+    }
+
+    #[test]
+    fn test_prune_removes_oldest_projects() {
+        let mut history = large_history(20);
+        history
+            .get_project_mut(Path::new("/work/project-18"))
+            .unwrap()
+            .pinned = true;
+        let limit = history.serialized_size() / 2;
+
+        let removed = history.prune_to_size(limit);
+        assert!(removed > 0);
+        assert!(history.serialized_size() <= limit);
+        assert!(history.get_project(Path::new("/work/project-0")).is_some());
+        assert!(history.get_project(Path::new("/work/project-19")).is_none());
+        // Pinned projects are kept, however old
+        assert!(history.get_project(Path::new("/work/project-18")).is_some());
+    }
+
+    #[test]
+    fn test_prune_removes_scripts_from_last_project() {
+        let mut history = History::new();
+        let path = PathBuf::from("/work/app");
+        for i in 0..30 {
+            history.record_run(&path, &format!("script-{i}"), None);
+        }
+        let limit = history.serialized_size() / 2;
+
+        history.prune_to_size(limit);
+        assert_eq!(history.projects.len(), 1);
+        assert!(history.get_project(&path).unwrap().scripts.len() < 30);
+        assert!(history.serialized_size() <= limit);
+    }
+
+    #[test]
+    fn test_enforce_limits_under_size_keeps_everything() {
+        let temp = TempDir::new().unwrap();
+        let mut history = large_history(5);
+        let config = HistoryConfig::default();
+
+        history
+            .enforce_limits(&temp.path().join("history.json"), &config)
+            .unwrap();
+        assert_eq!(history.projects.len(), 5);
+        assert!(rotated_snapshots(temp.path()).is_empty());
+    }
+
+    #[test]
+    fn test_enforce_limits_rotates_compressed_snapshot() {
+        let temp = TempDir::new().unwrap();
+        let mut history = large_history(50);
+        let full = serde_json::to_string_pretty(&history).unwrap();
+        let config = HistoryConfig {
+            max_size_kb: 1,
+            compress_rotated: true,
+            ..HistoryConfig::default()
+        };
+
+        history
+            .enforce_limits(&temp.path().join("history.json"), &config)
+            .unwrap();
+        assert!(history.serialized_size() <= 1024);
+
+        let snapshots = rotated_snapshots(temp.path());
+        assert_eq!(snapshots.len(), 1);
+        assert!(snapshots[0].to_string_lossy().ends_with(".json.gz"));
+
+        let mut decoded = String::new();
+        GzDecoder::new(File::open(&snapshots[0]).unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, full);
+    }
+
+    #[test]
+    fn test_prune_snapshots_keeps_newest() {
+        let temp = TempDir::new().unwrap();
+        for stamp in ["20240101", "20240102", "20240103", "20240104"] {
+            fs::write(temp.path().join(format!("history-{stamp}.json")), "{}").unwrap();
+        }
+        fs::write(temp.path().join("history.json"), "{}").unwrap();
+
+        prune_snapshots(temp.path(), 2).unwrap();
+        let names: Vec<_> = rotated_snapshots(temp.path())
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["history-20240103.json", "history-20240104.json"]);
+        assert!(temp.path().join("history.json").exists());
+    }
+}
@@ -0,0 +1,246 @@
+//! Searching past runs across projects.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use super::storage::History;
+use crate::filter::match_score;
+use crate::output::Report;
+
+/// A past run matching a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryMatch {
+    /// Project the script was run in.
+    pub project: PathBuf,
+    /// Script name.
+    pub script: String,
+    /// Arguments passed to the script, if any.
+    pub args: Option<String>,
+    /// Last time the script was run this way.
+    pub last_run: DateTime<Utc>,
+    score: i64,
+}
+
+impl HistoryMatch {
+    /// Get the run as it would be typed after `npm run`.
+    pub fn command(&self) -> String {
+        match &self.args {
+            Some(args) => format!("{} -- {}", self.script, args),
+            None => self.script.clone(),
+        }
+    }
+}
+
+/// Build a report of past runs, for `--format`.
+pub fn runs_report(runs: &[HistoryMatch]) -> Report {
+    let mut report = Report::new(["last_run", "project", "script", "args"]);
+    for run in runs {
+        report.push([
+            json!(run.last_run.to_rfc3339()),
+            json!(run.project.display().to_string()),
+            json!(run.script),
+            json!(run.args),
+        ]);
+    }
+    report
+}
+
+/// Fuzzy-search the scripts and arguments run in all projects.
+///
+/// Best matches come first, and equally good ones most recent first.
+pub fn search_history(history: &History, query: &str) -> Vec<HistoryMatch> {
+    let mut matches: Vec<HistoryMatch> = history
+        .projects
+        .iter()
+        .flat_map(|(project, project_history)| {
+            project_history
+                .scripts
+                .iter()
+                .flat_map(move |(script, script_history)| {
+                    script_history
+                        .runs()
+                        .into_iter()
+                        .map(move |run| HistoryMatch {
+                            project: project.clone(),
+                            script: script.clone(),
+                            args: run.args,
+                            last_run: run.last_run,
+                            score: 0,
+                        })
+                })
+        })
+        .filter_map(|mut entry| {
+            let text = match &entry.args {
+                Some(args) => format!("{} {}", entry.script, args),
+                None => entry.script.clone(),
+            };
+            entry.score = match_score(query, &text)?;
+            Some(entry)
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.last_run.cmp(&a.last_run))
+            .then(a.project.cmp(&b.project))
+    });
+    matches
+}
+
+/// Get the runs of a project, most recent first.
+pub fn project_runs(history: &History, project_dir: &Path) -> Vec<HistoryMatch> {
+    let Some(project_history) = history.get_project(project_dir) else {
+        return Vec::new();
+    };
+    let mut runs: Vec<HistoryMatch> = project_history
+        .scripts
+        .iter()
+        .flat_map(|(script, script_history)| {
+            script_history
+                .runs()
+                .into_iter()
+                .map(move |run| HistoryMatch {
+                    project: project_dir.to_path_buf(),
+                    script: script.clone(),
+                    args: run.args,
+                    last_run: run.last_run,
+                    score: 0,
+                })
+        })
+        .collect();
+
+    runs.sort_by(|a, b| {
+        b.last_run
+            .cmp(&a.last_run)
+            .then_with(|| a.script.cmp(&b.script))
+    });
+    runs
+}
+
+/// Find the most recent run of a project whose script name matches a
+/// pattern, fuzzily like the TUI filter.
+pub fn last_matching_run(
+    history: &History,
+    project_dir: &Path,
+    pattern: &str,
+) -> Option<HistoryMatch> {
+    project_runs(history, project_dir)
+        .into_iter()
+        .find(|run| match_score(pattern, &run.script).is_some())
+}
+
+/// Find the most recently run script of a project whose last run failed.
+pub fn last_failed_run(history: &History, project_dir: &Path) -> Option<HistoryMatch> {
+    history
+        .get_project(project_dir)?
+        .scripts
+        .iter()
+        .filter(|(_, script)| script.last_failed() == Some(true))
+        .max_by(|(a_name, a), (b_name, b)| a.last_run.cmp(&b.last_run).then(b_name.cmp(a_name)))
+        .map(|(name, script)| HistoryMatch {
+            project: project_dir.to_path_buf(),
+            script: name.clone(),
+            args: script.last_args.clone(),
+            last_run: script.last_run,
+            score: 0,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock;
+    use chrono::Duration;
+    use std::path::Path;
+
+    #[test]
+    fn test_search_history_across_projects() {
+        let mut history = History::new();
+        history.record_run(Path::new("/work/app"), "lint", Some("--fix".to_string()));
+        history.record_run(Path::new("/work/app"), "lint", None);
+        history.record_run(Path::new("/work/api"), "test", Some("--watch".to_string()));
+
+        let found = search_history(&history, "--fix");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].project, Path::new("/work/app"));
+        assert_eq!(found[0].command(), "lint -- --fix");
+
+        let commands: Vec<String> = search_history(&history, "lint")
+            .iter()
+            .map(HistoryMatch::command)
+            .collect();
+        assert_eq!(commands.len(), 2);
+        assert!(commands.contains(&"lint".to_string()));
+
+        assert!(search_history(&history, "deploy").is_empty());
+    }
+
+    #[test]
+    fn test_project_runs_and_last_match() {
+        let now = clock::now();
+        let app = Path::new("/work/app");
+        let mut history = History::new();
+        history.record_run(app, "test:unit", Some("--watch".to_string()));
+        history.record_run(app, "lint", None);
+        history.record_run(Path::new("/work/api"), "test", None);
+        let project = history.get_project_mut(app).unwrap();
+        project.scripts.get_mut("test:unit").unwrap().last_run = now - Duration::hours(2);
+        project.scripts.get_mut("lint").unwrap().last_run = now - Duration::hours(1);
+
+        let commands: Vec<String> = project_runs(&history, app)
+            .iter()
+            .map(HistoryMatch::command)
+            .collect();
+        assert_eq!(commands, ["lint", "test:unit -- --watch"]);
+
+        let run = last_matching_run(&history, app, "tst").unwrap();
+        assert_eq!(run.command(), "test:unit -- --watch");
+        assert!(last_matching_run(&history, app, "deploy").is_none());
+        assert!(project_runs(&history, Path::new("/elsewhere")).is_empty());
+    }
+
+    #[test]
+    fn test_last_failed_run() {
+        let now = clock::now();
+        let app = Path::new("/work/app");
+        let mut history = History::new();
+        history.record_run(app, "lint", None);
+        history.record_result(app, "lint", 1, std::time::Duration::from_secs(1));
+        history.record_run(app, "test", Some("--bail".to_string()));
+        history.record_result(app, "test", 2, std::time::Duration::from_secs(1));
+        history.record_run(app, "build", None);
+        history.record_result(app, "build", 0, std::time::Duration::from_secs(1));
+        let project = history.get_project_mut(app).unwrap();
+        project.scripts.get_mut("lint").unwrap().last_run = now - Duration::hours(2);
+        project.scripts.get_mut("test").unwrap().last_run = now - Duration::hours(1);
+
+        let run = last_failed_run(&history, app).unwrap();
+        assert_eq!(run.command(), "test -- --bail");
+
+        history.record_result(app, "test", 0, std::time::Duration::from_secs(1));
+        assert_eq!(last_failed_run(&history, app).unwrap().script, "lint");
+        assert!(last_failed_run(&history, Path::new("/elsewhere")).is_none());
+    }
+
+    #[test]
+    fn test_search_history_keeps_times_per_args() {
+        let now = clock::now();
+        let mut history = History::new();
+        history.record_run(Path::new("/work/app"), "test", Some("--grep a".to_string()));
+        let script = history
+            .projects
+            .get_mut(Path::new("/work/app"))
+            .and_then(|p| p.scripts.get_mut("test"))
+            .unwrap();
+        script.recent_args[0].last_run = now - Duration::days(20);
+        script.record_run(Some("--grep b".to_string()));
+
+        let found = search_history(&history, "--grep");
+        let args: Vec<Option<&str>> = found.iter().map(|m| m.args.as_deref()).collect();
+        assert_eq!(args, [Some("--grep b"), Some("--grep a")]);
+        assert!(found[1].last_run < found[0].last_run);
+    }
+}
@@ -0,0 +1,283 @@
+//! Usage statistics of a project.
+//!
+//! Sums up a project's history for `nrs stats` and the TUI usage overlay:
+//! which scripts run most, how often runs fail, how long they take and on
+//! which day of the week most runs happen.
+
+use std::time::Duration;
+
+use chrono::Weekday;
+use serde_json::json;
+
+use super::storage::ProjectHistory;
+use crate::output::Report;
+use crate::utils::format_elapsed;
+
+/// Number of scripts shown in the most-run chart.
+pub const STATS_SCRIPTS: usize = 10;
+
+/// Usage of one script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptStats {
+    /// Script name.
+    pub name: String,
+    /// Number of runs.
+    pub runs: u32,
+    /// Number of runs whose result was recorded.
+    pub timed_runs: u32,
+    /// Number of recorded runs that failed.
+    pub failed_runs: u32,
+    /// Average duration of the recorded runs.
+    pub average_duration: Option<Duration>,
+}
+
+impl ScriptStats {
+    /// Get the share of recorded runs that failed, from 0 to 1.
+    pub fn failure_rate(&self) -> Option<f64> {
+        rate(self.failed_runs, self.timed_runs)
+    }
+
+    /// Describe the runs, e.g. "12 runs, avg 4s, 25% failed".
+    pub fn describe(&self) -> String {
+        let mut parts = vec![plural(u64::from(self.runs), "run")];
+        if let Some(average) = self.average_duration {
+            parts.push(format!("avg {}", format_elapsed(average)));
+        }
+        if let Some(rate) = self.failure_rate() {
+            parts.push(format!("{:.0}% failed", rate * 100.0));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Usage of all scripts of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectStats {
+    /// Scripts, most run first.
+    pub scripts: Vec<ScriptStats>,
+    /// Runs per day of the week, Monday first.
+    pub weekday_runs: [u32; 7],
+}
+
+impl ProjectStats {
+    /// Sum up a project's history.
+    pub fn from_history(project: &ProjectHistory) -> Self {
+        let mut scripts: Vec<ScriptStats> = project
+            .scripts
+            .iter()
+            .map(|(name, history)| ScriptStats {
+                name: name.clone(),
+                runs: history.count,
+                timed_runs: history.timed_runs,
+                failed_runs: history.failed_runs,
+                average_duration: history.average_duration(),
+            })
+            .collect();
+        scripts.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.name.cmp(&b.name)));
+        Self {
+            scripts,
+            weekday_runs: project.weekday_runs,
+        }
+    }
+
+    /// Check if nothing was run yet.
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Get the number of runs of all scripts.
+    pub fn total_runs(&self) -> u64 {
+        self.scripts.iter().map(|s| u64::from(s.runs)).sum()
+    }
+
+    /// Get the number of runs whose result was recorded.
+    pub fn timed_runs(&self) -> u32 {
+        self.scripts.iter().map(|s| s.timed_runs).sum()
+    }
+
+    /// Get the number of recorded runs that failed.
+    pub fn failed_runs(&self) -> u32 {
+        self.scripts.iter().map(|s| s.failed_runs).sum()
+    }
+
+    /// Get the share of recorded runs that failed, from 0 to 1.
+    pub fn failure_rate(&self) -> Option<f64> {
+        rate(self.failed_runs(), self.timed_runs())
+    }
+
+    /// Get the average duration of all recorded runs.
+    pub fn average_duration(&self) -> Option<Duration> {
+        let (total, runs) = self
+            .scripts
+            .iter()
+            .filter_map(|s| Some((s.average_duration?, s.timed_runs)))
+            .fold((Duration::ZERO, 0u32), |(total, runs), (average, n)| {
+                (total + average * n, runs + n)
+            });
+        (runs > 0).then(|| total / runs)
+    }
+
+    /// Get a row per script, most run first, for `nrs stats --format`.
+    ///
+    /// Durations are in milliseconds and failure rates from 0 to 1.
+    pub fn report(&self) -> Report {
+        let mut report = Report::new([
+            "script",
+            "runs",
+            "timed_runs",
+            "failed_runs",
+            "failure_rate",
+            "average_ms",
+        ]);
+        for script in &self.scripts {
+            let failure_rate = script
+                .failure_rate()
+                .map(|rate| (rate * 1000.0).round() / 1000.0);
+            let average_ms = script
+                .average_duration
+                .map(|average| average.as_millis() as u64);
+            report.push([
+                json!(script.name),
+                json!(script.runs),
+                json!(script.timed_runs),
+                json!(script.failed_runs),
+                json!(failure_rate),
+                json!(average_ms),
+            ]);
+        }
+        report
+    }
+
+    /// Get the day of the week with the most runs, and its run count.
+    ///
+    /// Returns `None` until a run was recorded on some day.
+    pub fn busiest_day(&self) -> Option<(Weekday, u32)> {
+        let (index, &runs) = self
+            .weekday_runs
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, &runs)| runs)?;
+        (runs > 0).then(|| (weekday(index), runs))
+    }
+
+    /// Get the overall figures as labels and values.
+    ///
+    /// Figures that weren't recorded yet are left out.
+    pub fn summary(&self) -> Vec<(&'static str, String)> {
+        let mut rows = vec![(
+            "Total runs",
+            format!(
+                "{} in {}",
+                self.total_runs(),
+                plural(self.scripts.len() as u64, "script")
+            ),
+        )];
+        if let Some(rate) = self.failure_rate() {
+            rows.push((
+                "Failed",
+                format!(
+                    "{} of {} timed runs ({:.0}%)",
+                    self.failed_runs(),
+                    self.timed_runs(),
+                    rate * 100.0
+                ),
+            ));
+        }
+        if let Some((day, runs)) = self.busiest_day() {
+            rows.push((
+                "Busiest day",
+                format!("{day} ({})", plural(u64::from(runs), "run")),
+            ));
+        }
+        if let Some(average) = self.average_duration() {
+            rows.push(("Average run", format_elapsed(average)));
+        }
+        rows
+    }
+}
+
+/// Get a weekday from its index, Monday being 0.
+pub fn weekday(index: usize) -> Weekday {
+    const DAYS: [Weekday; 7] = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+    DAYS[index % 7]
+}
+
+fn plural(count: u64, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+fn rate(part: u32, whole: u32) -> Option<f64> {
+    (whole > 0).then(|| f64::from(part) / f64::from(whole))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::ScriptHistory;
+    use chrono::Utc;
+
+    fn script(count: u32, results: &[(i32, u64)]) -> ScriptHistory {
+        let mut history = ScriptHistory::with_values(count, Utc::now(), None);
+        for &(code, millis) in results {
+            history.record_result(code, Duration::from_millis(millis));
+        }
+        history
+    }
+
+    #[test]
+    fn test_project_stats() {
+        let mut project = ProjectHistory::new();
+        project
+            .scripts
+            .insert("build".to_string(), script(3, &[(0, 1000), (1, 3000)]));
+        project
+            .scripts
+            .insert("dev".to_string(), script(8, &[(0, 2000)]));
+        project.scripts.insert("lint".to_string(), script(3, &[]));
+        project.weekday_runs = [2, 5, 0, 0, 5, 1, 1];
+
+        let stats = ProjectStats::from_history(&project);
+        let names: Vec<&str> = stats.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["dev", "build", "lint"]);
+        assert_eq!(stats.total_runs(), 14);
+        assert_eq!(stats.failure_rate(), Some(1.0 / 3.0));
+        assert_eq!(stats.scripts[1].failure_rate(), Some(0.5));
+        assert_eq!(stats.scripts[2].failure_rate(), None);
+        assert_eq!(stats.average_duration(), Some(Duration::from_millis(2000)));
+        assert_eq!(stats.busiest_day(), Some((Weekday::Tue, 5)));
+        assert_eq!(stats.scripts[1].describe(), "3 runs, avg 2s, 50% failed");
+        assert_eq!(stats.scripts[2].describe(), "3 runs");
+
+        let summary = stats.summary();
+        assert_eq!(summary[0], ("Total runs", "14 in 3 scripts".to_string()));
+        assert_eq!(
+            summary[1],
+            ("Failed", "1 of 3 timed runs (33%)".to_string())
+        );
+        assert_eq!(summary[2], ("Busiest day", "Tue (5 runs)".to_string()));
+    }
+
+    #[test]
+    fn test_empty_project_stats() {
+        let stats = ProjectStats::from_history(&ProjectHistory::new());
+        assert!(stats.is_empty());
+        assert_eq!(stats.total_runs(), 0);
+        assert_eq!(stats.failure_rate(), None);
+        assert_eq!(stats.average_duration(), None);
+        assert_eq!(stats.busiest_day(), None);
+        assert_eq!(stats.summary().len(), 1);
+    }
+}
@@ -3,13 +3,17 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Local, Utc};
 use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
 
+use super::anonymize::{read_salt, write_salt, SALT_FILE};
 use crate::config::HistoryConfig;
 use crate::package::Script;
+use crate::utils::{clock, history_file};
 
 /// Default maximum number of projects to track.
 pub const DEFAULT_MAX_PROJECTS: usize = 100;
@@ -17,6 +21,12 @@ pub const DEFAULT_MAX_PROJECTS: usize = 100;
 /// Default maximum number of scripts per project.
 pub const DEFAULT_MAX_SCRIPTS: usize = 50;
 
+/// Maximum number of filter queries remembered per project.
+pub const MAX_FILTER_QUERIES: usize = 20;
+
+/// Maximum number of argument strings remembered per script.
+pub const MAX_RECENT_ARGS: usize = 10;
+
 /// Weight for run count in scoring (30%).
 const RUN_COUNT_WEIGHT: f64 = 0.3;
 
@@ -26,6 +36,24 @@ const RECENCY_WEIGHT: f64 = 0.7;
 /// Days after which recency score fully decays.
 const RECENCY_DECAY_DAYS: i64 = 30;
 
+/// Arguments a script was run with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentArgs {
+    /// The argument string.
+    pub args: String,
+    /// Last time the script was run with these arguments.
+    pub last_run: DateTime<Utc>,
+}
+
+/// A way a script was run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentRun {
+    /// Arguments passed to the script, if any.
+    pub args: Option<String>,
+    /// Last time the script was run this way.
+    pub last_run: DateTime<Utc>,
+}
+
 /// History for a single script.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScriptHistory {
@@ -35,6 +63,39 @@ pub struct ScriptHistory {
     pub last_run: DateTime<Utc>,
     /// Last arguments passed to the script.
     pub last_args: Option<String>,
+    /// Previous arguments, most recent first.
+    #[serde(default)]
+    pub recent_args: Vec<RecentArgs>,
+    /// How long the last run took, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_duration_ms: Option<u64>,
+    /// Average duration of the timed runs, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_duration_ms: Option<u64>,
+    /// Number of runs the average is taken over.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub timed_runs: u32,
+    /// Exit code of the last run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_exit_code: Option<i32>,
+    /// Number of timed runs that exited with a non-zero code.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub failed_runs: u32,
+    /// Full command of the last run, runner and arguments included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_command: Option<String>,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+fn is_zero_week(days: &[u32; 7]) -> bool {
+    days.iter().all(|&n| n == 0)
 }
 
 impl ScriptHistory {
@@ -42,8 +103,15 @@ impl ScriptHistory {
     pub fn new() -> Self {
         Self {
             count: 1,
-            last_run: Utc::now(),
+            last_run: clock::now(),
             last_args: None,
+            recent_args: Vec::new(),
+            last_duration_ms: None,
+            avg_duration_ms: None,
+            timed_runs: 0,
+            last_exit_code: None,
+            failed_runs: 0,
+            last_command: None,
         }
     }
 
@@ -52,23 +120,110 @@ impl ScriptHistory {
         Self {
             count,
             last_run,
+            recent_args: last_args
+                .iter()
+                .map(|args| RecentArgs {
+                    args: args.clone(),
+                    last_run,
+                })
+                .collect(),
             last_args,
+            last_duration_ms: None,
+            avg_duration_ms: None,
+            timed_runs: 0,
+            last_exit_code: None,
+            failed_runs: 0,
+            last_command: None,
         }
     }
 
     /// Record a new execution of the script.
     pub fn record_run(&mut self, args: Option<String>) {
         self.count += 1;
-        self.last_run = Utc::now();
+        self.last_run = clock::now();
+        self.record_args(args);
+    }
+
+    /// Record how the last run ended and how long it took.
+    pub fn record_result(&mut self, exit_code: i32, duration: Duration) {
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        let total = self
+            .avg_duration_ms
+            .unwrap_or(0)
+            .saturating_mul(u64::from(self.timed_runs))
+            .saturating_add(millis);
+        self.timed_runs = self.timed_runs.saturating_add(1);
+        self.avg_duration_ms = Some(total / u64::from(self.timed_runs));
+        self.last_duration_ms = Some(millis);
+        self.last_exit_code = Some(exit_code);
+        if exit_code != 0 {
+            self.failed_runs = self.failed_runs.saturating_add(1);
+        }
+    }
+
+    /// Get how long the last run took, if it was timed.
+    pub fn last_duration(&self) -> Option<Duration> {
+        self.last_duration_ms.map(Duration::from_millis)
+    }
+
+    /// Get the average duration of the timed runs.
+    pub fn average_duration(&self) -> Option<Duration> {
+        self.avg_duration_ms.map(Duration::from_millis)
+    }
+
+    /// Check if the last run failed, if its result was recorded.
+    pub fn last_failed(&self) -> Option<bool> {
+        self.last_exit_code.map(|code| code != 0)
+    }
+
+    /// Remember the arguments of a run, moving them to the front if already present.
+    fn record_args(&mut self, args: Option<String>) {
+        if let Some(args) = args.as_deref().filter(|a| !a.trim().is_empty()) {
+            self.recent_args.retain(|a| a.args != args);
+            self.recent_args.insert(
+                0,
+                RecentArgs {
+                    args: args.to_string(),
+                    last_run: self.last_run,
+                },
+            );
+            self.recent_args.truncate(MAX_RECENT_ARGS);
+        }
         self.last_args = args;
     }
 
+    /// Get previous argument strings, most recent first.
+    pub fn args_history(&self) -> Vec<String> {
+        self.runs().into_iter().filter_map(|run| run.args).collect()
+    }
+
+    /// Get the distinct ways the script was run, most recent first.
+    ///
+    /// The last run comes first, so histories written before arguments were
+    /// listed still give their last arguments.
+    pub fn runs(&self) -> Vec<RecentRun> {
+        let mut runs = vec![RecentRun {
+            args: self.last_args.clone(),
+            last_run: self.last_run,
+        }];
+        runs.extend(
+            self.recent_args
+                .iter()
+                .filter(|recent| self.last_args.as_ref() != Some(&recent.args))
+                .map(|recent| RecentRun {
+                    args: Some(recent.args.clone()),
+                    last_run: recent.last_run,
+                }),
+        );
+        runs
+    }
+
     /// Calculate the score for this script based on run count and recency.
     ///
     /// Score = (run_count * 0.3) + (recency_score * 0.7)
     /// Where recency_score decays from 1.0 to 0.0 over RECENCY_DECAY_DAYS.
     pub fn score(&self) -> f64 {
-        self.score_at(Utc::now())
+        self.score_at(clock::now())
     }
 
     /// Calculate the score at a specific time (for testing).
@@ -105,6 +260,18 @@ pub struct ProjectHistory {
     /// History for each script.
     #[serde(default)]
     pub scripts: HashMap<String, ScriptHistory>,
+    /// Recent filter queries, most recent first.
+    #[serde(default)]
+    pub filters: Vec<String>,
+    /// Runs per day of the week in local time, Monday first.
+    #[serde(default, skip_serializing_if = "is_zero_week")]
+    pub weekday_runs: [u32; 7],
+    /// Category groups collapsed in the TUI.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collapsed_groups: Vec<String>,
+    /// Whether the project is pinned to the top of `nrs history projects`.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub pinned: bool,
 }
 
 impl ProjectHistory {
@@ -112,26 +279,45 @@ impl ProjectHistory {
     pub fn new() -> Self {
         Self {
             last_script: None,
-            last_run: Utc::now(),
+            last_run: clock::now(),
             scripts: HashMap::new(),
+            filters: Vec::new(),
+            weekday_runs: [0; 7],
+            collapsed_groups: Vec::new(),
+            pinned: false,
         }
     }
 
     /// Record a script execution.
     pub fn record_run(&mut self, script: &str, args: Option<String>) {
         self.last_script = Some(script.to_string());
-        self.last_run = Utc::now();
+        self.last_run = clock::now();
+        let weekday = self.last_run.with_timezone(&Local).weekday();
+        let day = &mut self.weekday_runs[weekday.num_days_from_monday() as usize];
+        *day = day.saturating_add(1);
 
         self.scripts
             .entry(script.to_string())
             .and_modify(|h| h.record_run(args.clone()))
             .or_insert_with(|| {
                 let mut h = ScriptHistory::new();
-                h.last_args = args;
+                h.record_args(args);
                 h
             });
     }
 
+    /// Record a filter query, moving it to the front if already present.
+    pub fn record_filter(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+
+        self.filters.retain(|q| q != query);
+        self.filters.insert(0, query.to_string());
+        self.filters.truncate(MAX_FILTER_QUERIES);
+    }
+
     /// Get the last executed script name.
     pub fn last_script(&self) -> Option<&str> {
         self.last_script.as_deref()
@@ -150,6 +336,17 @@ impl ProjectHistory {
         self.scripts.get(name)
     }
 
+    /// Move a script's history to a new name, or drop it if `to` is `None`.
+    pub fn rename_script(&mut self, from: &str, to: Option<&str>) {
+        let script = self.scripts.remove(from);
+        if let (Some(to), Some(script)) = (to, script) {
+            self.scripts.insert(to.to_string(), script);
+        }
+        if self.last_script.as_deref() == Some(from) {
+            self.last_script = to.map(String::from);
+        }
+    }
+
     /// Enforce max_scripts limit using LRU eviction.
     pub fn cleanup(&mut self, max_scripts: usize) {
         if self.scripts.len() <= max_scripts {
@@ -158,7 +355,7 @@ impl ProjectHistory {
 
         // Sort scripts by last_run (oldest first)
         let mut scripts: Vec<_> = self.scripts.iter().collect();
-        scripts.sort_by(|a, b| a.1.last_run.cmp(&b.1.last_run));
+        scripts.sort_by_key(|(_, h)| h.last_run);
 
         // Calculate how many to remove
         let to_remove = self.scripts.len() - max_scripts;
@@ -190,6 +387,20 @@ pub struct History {
     /// History per project path.
     #[serde(default)]
     pub projects: HashMap<PathBuf, ProjectHistory>,
+    /// Salt for hashed project paths.
+    ///
+    /// Kept in a file of its own (see [`History::salt_path`]), so a synced
+    /// history.json can't be used to test guesses at project paths. Salts
+    /// stored here by older versions are still read.
+    #[serde(default, skip_serializing)]
+    pub salt: Option<String>,
+    /// Whether the salt was derived from `--seed`, so anyone could compute
+    /// it. Such a salt is only used for this run and never saved.
+    #[serde(skip)]
+    pub(crate) seeded_salt: bool,
+    /// Whether project paths are looked up and stored hashed.
+    #[serde(skip)]
+    pub(crate) hash_paths: bool,
 }
 
 impl History {
@@ -201,16 +412,19 @@ impl History {
         Self {
             version: Self::VERSION,
             projects: HashMap::new(),
+            salt: None,
+            seeded_salt: false,
+            hash_paths: false,
         }
     }
 
     /// Get the history file path.
     pub fn file_path() -> Option<PathBuf> {
-        dirs::config_dir().map(|p| p.join("nrs").join("history.json"))
+        history_file()
     }
 
-    /// Get the backup file path.
-    fn backup_path() -> Option<PathBuf> {
+    /// Get the path corrupt history files are backed up to.
+    pub fn backup_path() -> Option<PathBuf> {
         Self::file_path().map(|p| p.with_extension("json.bak"))
     }
 
@@ -219,9 +433,10 @@ impl History {
     /// Handles missing files gracefully (returns empty history).
     /// Handles corrupt files by backing up and returning empty history.
     pub fn load() -> Result<Self> {
-        let path = Self::file_path().context("Could not determine config directory")?;
+        let path = Self::file_path().context("Could not determine state directory")?;
 
         if !path.exists() {
+            debug!("No history file at {}", path.display());
             return Ok(Self::new());
         }
 
@@ -238,7 +453,17 @@ impl History {
         };
 
         match serde_json::from_str::<History>(&content) {
-            Ok(history) => Ok(history),
+            Ok(mut history) => {
+                if history.salt.is_none() {
+                    history.salt = Self::salt_path().and_then(|path| read_salt(&path));
+                }
+                debug!(
+                    "Loaded history for {} projects from {}",
+                    history.projects.len(),
+                    path.display()
+                );
+                Ok(history)
+            }
             Err(e) => {
                 // Corrupt file - backup and return empty
                 eprintln!(
@@ -264,7 +489,7 @@ impl History {
 
     /// Save history to the default location.
     pub fn save(&self) -> Result<()> {
-        let path = Self::file_path().context("Could not determine config directory")?;
+        let path = Self::file_path().context("Could not determine state directory")?;
 
         // Ensure directory exists
         if let Some(parent) = path.parent() {
@@ -272,27 +497,34 @@ impl History {
                 .with_context(|| format!("Failed to create directory {}", parent.display()))?;
         }
 
+        // The salt goes first, so hashed keys are never saved without it
+        if let Some(salt) = self.salt.as_ref().filter(|_| !self.seeded_salt) {
+            write_salt(&path.with_file_name(SALT_FILE), salt)?;
+        }
         let content = serde_json::to_string_pretty(self).context("Failed to serialize history")?;
 
         fs::write(&path, content)
             .with_context(|| format!("Failed to write history to {}", path.display()))?;
+        trace!("Saved history to {}", path.display());
 
         Ok(())
     }
 
     /// Get history for a project.
     pub fn get_project(&self, project_dir: &Path) -> Option<&ProjectHistory> {
-        self.projects.get(project_dir)
+        self.projects.get(&self.project_key(project_dir))
     }
 
     /// Get mutable history for a project.
     pub fn get_project_mut(&mut self, project_dir: &Path) -> Option<&mut ProjectHistory> {
-        self.projects.get_mut(project_dir)
+        let key = self.project_key(project_dir);
+        self.projects.get_mut(&key)
     }
 
     /// Get or create history for a project.
     pub fn get_or_create_project(&mut self, project_dir: &Path) -> &mut ProjectHistory {
-        self.projects.entry(project_dir.to_path_buf()).or_default()
+        let key = self.project_key(project_dir);
+        self.projects.entry(key).or_default()
     }
 
     /// Record a script execution.
@@ -301,6 +533,59 @@ impl History {
             .record_run(script, args);
     }
 
+    /// Record how a script's last run ended.
+    ///
+    /// Goes with [`History::record_run`], which must have recorded the run.
+    pub fn record_result(
+        &mut self,
+        project_dir: &Path,
+        script: &str,
+        exit_code: i32,
+        duration: Duration,
+    ) {
+        if let Some(history) = self
+            .get_project_mut(project_dir)
+            .and_then(|p| p.scripts.get_mut(script))
+        {
+            history.record_result(exit_code, duration);
+        }
+    }
+
+    /// Record the full command a script last ran with.
+    pub fn record_command(&mut self, project_dir: &Path, script: &str, command: &str) {
+        if let Some(history) = self
+            .get_project_mut(project_dir)
+            .and_then(|p| p.scripts.get_mut(script))
+        {
+            history.last_command = Some(command.to_string());
+        }
+    }
+
+    /// Record a filter query used in a project.
+    pub fn record_filter(&mut self, project_dir: &Path, query: &str) {
+        self.get_or_create_project(project_dir).record_filter(query);
+    }
+
+    /// Get recent filter queries for a project, most recent first.
+    pub fn get_filters(&self, project_dir: &Path) -> &[String] {
+        self.get_project(project_dir)
+            .map_or(&[], |p| p.filters.as_slice())
+    }
+
+    /// Get the category groups collapsed in a project.
+    pub fn get_collapsed_groups(&self, project_dir: &Path) -> &[String] {
+        self.get_project(project_dir)
+            .map_or(&[], |p| p.collapsed_groups.as_slice())
+    }
+
+    /// Remember the category groups collapsed in a project.
+    pub fn set_collapsed_groups(&mut self, project_dir: &Path, groups: Vec<String>) {
+        if groups.is_empty() && self.get_project(project_dir).is_none() {
+            return;
+        }
+        self.get_or_create_project(project_dir).collapsed_groups = groups;
+    }
+
     /// Get the last executed script for a project with its arguments.
     pub fn get_last_script(&self, project_dir: &Path) -> Option<(String, Option<String>)> {
         self.get_project(project_dir)
@@ -308,6 +593,20 @@ impl History {
             .map(|(name, args)| (name.to_string(), args.map(String::from)))
     }
 
+    /// Move a script's history to a new name after it was renamed.
+    pub fn rename_script(&mut self, project_dir: &Path, from: &str, to: &str) {
+        if let Some(project) = self.get_project_mut(project_dir) {
+            project.rename_script(from, Some(to));
+        }
+    }
+
+    /// Forget a script's history after it was removed.
+    pub fn remove_script(&mut self, project_dir: &Path, name: &str) {
+        if let Some(project) = self.get_project_mut(project_dir) {
+            project.rename_script(name, None);
+        }
+    }
+
     /// Get statistics for a specific script in a project.
     pub fn get_script_stats(&self, project_dir: &Path, script: &str) -> Option<&ScriptHistory> {
         self.get_project(project_dir)
@@ -323,7 +622,17 @@ impl History {
         project_dir: &Path,
         scripts: &'a [Script],
     ) -> Vec<&'a Script> {
-        self.get_sorted_by_recent_at(project_dir, scripts, Utc::now())
+        self.get_sorted_by_recent_at(project_dir, scripts, clock::now())
+    }
+
+    /// Sort scripts by recent usage, adding a boost to each script's score.
+    pub fn get_sorted_by_recent_with_boost<'a>(
+        &self,
+        project_dir: &Path,
+        scripts: &'a [Script],
+        boost: impl Fn(&Script) -> f64,
+    ) -> Vec<&'a Script> {
+        self.sort_by_score(project_dir, scripts, clock::now(), boost)
     }
 
     /// Sort scripts by recent usage at a specific time (for testing).
@@ -332,6 +641,16 @@ impl History {
         project_dir: &Path,
         scripts: &'a [Script],
         now: DateTime<Utc>,
+    ) -> Vec<&'a Script> {
+        self.sort_by_score(project_dir, scripts, now, |_| 0.0)
+    }
+
+    fn sort_by_score<'a>(
+        &self,
+        project_dir: &Path,
+        scripts: &'a [Script],
+        now: DateTime<Utc>,
+        boost: impl Fn(&Script) -> f64,
     ) -> Vec<&'a Script> {
         let project_history = self.get_project(project_dir);
 
@@ -342,7 +661,7 @@ impl History {
                     .and_then(|p| p.get_script(s.name()))
                     .map(|h| h.score_at(now))
                     .unwrap_or(0.0);
-                (s, score)
+                (s, score + boost(s))
             })
             .collect();
 
@@ -373,9 +692,9 @@ impl History {
             return;
         }
 
-        // Sort projects by last_run (oldest first)
+        // Sort projects by last_run (oldest first), pinned ones last
         let mut projects: Vec<_> = self.projects.iter().collect();
-        projects.sort_by(|a, b| a.1.last_run.cmp(&b.1.last_run));
+        projects.sort_by_key(|(_, p)| (p.pinned, p.last_run));
 
         // Calculate how many to remove
         let to_remove = self.projects.len() - max_projects;
@@ -430,6 +749,54 @@ mod tests {
         assert_eq!(history.last_args, Some("--watch".to_string()));
     }
 
+    #[test]
+    fn test_script_history_recent_args() {
+        let mut history = ScriptHistory::new();
+        history.record_run(Some("--filter foo".to_string()));
+        history.record_run(Some("--watch".to_string()));
+        history.record_run(None);
+        history.record_run(Some("--filter foo".to_string()));
+
+        assert_eq!(history.args_history(), ["--filter foo", "--watch"]);
+
+        for i in 0..MAX_RECENT_ARGS + 5 {
+            history.record_run(Some(format!("--shard {i}")));
+        }
+        assert_eq!(history.recent_args.len(), MAX_RECENT_ARGS);
+        assert_eq!(
+            history.recent_args[0].args,
+            format!("--shard {}", MAX_RECENT_ARGS + 4)
+        );
+    }
+
+    #[test]
+    fn test_script_history_record_result() {
+        use std::time::Duration as StdDuration;
+
+        let mut history = ScriptHistory::new();
+        assert_eq!(history.last_failed(), None);
+
+        history.record_result(0, StdDuration::from_secs(10));
+        history.record_result(1, StdDuration::from_secs(20));
+        assert_eq!(history.last_duration(), Some(StdDuration::from_secs(20)));
+        assert_eq!(history.average_duration(), Some(StdDuration::from_secs(15)));
+        assert_eq!(history.last_failed(), Some(true));
+
+        let json = serde_json::to_string(&history).unwrap();
+        let loaded: ScriptHistory = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.timed_runs, 2);
+        assert_eq!(loaded.last_exit_code, Some(1));
+        assert_eq!(loaded.failed_runs, 1);
+    }
+
+    #[test]
+    fn test_script_history_without_recent_args() {
+        // Histories from before the list was added only have the last arguments
+        let json = r#"{"count": 2, "last_run": "2024-01-01T00:00:00Z", "last_args": "--host"}"#;
+        let history: ScriptHistory = serde_json::from_str(json).unwrap();
+        assert_eq!(history.args_history(), ["--host"]);
+    }
+
     #[test]
     fn test_script_history_score_today() {
         let history = ScriptHistory::new();
@@ -478,6 +845,7 @@ mod tests {
 
         assert_eq!(history.last_script(), Some("build"));
         assert_eq!(history.scripts.len(), 2);
+        assert_eq!(history.weekday_runs.iter().sum::<u32>(), 3);
 
         let dev = history.get_script("dev").unwrap();
         assert_eq!(dev.count, 2);
@@ -547,6 +915,45 @@ mod tests {
         assert_eq!(dev.last_args, Some("--host".to_string()));
     }
 
+    #[test]
+    fn test_history_record_filter() {
+        let mut history = History::new();
+        let project = PathBuf::from("/test/project");
+
+        history.record_filter(&project, "e2e");
+        history.record_filter(&project, "storybook");
+        history.record_filter(&project, " e2e ");
+        history.record_filter(&project, "  ");
+
+        assert_eq!(history.get_filters(&project), ["e2e", "storybook"]);
+        assert!(history.get_filters(Path::new("/other")).is_empty());
+    }
+
+    #[test]
+    fn test_history_collapsed_groups() {
+        let mut history = History::new();
+        let project = PathBuf::from("/test/project");
+
+        history.set_collapsed_groups(Path::new("/other"), Vec::new());
+        assert!(history.get_project(Path::new("/other")).is_none());
+
+        history.set_collapsed_groups(&project, vec!["lint".to_string(), "test".to_string()]);
+        let json = serde_json::to_string(&history).unwrap();
+        let history: History = serde_json::from_str(&json).unwrap();
+        assert_eq!(history.get_collapsed_groups(&project), ["lint", "test"]);
+    }
+
+    #[test]
+    fn test_project_history_filters_capped() {
+        let mut proj = ProjectHistory::new();
+        for i in 0..MAX_FILTER_QUERIES + 5 {
+            proj.record_filter(&format!("query{}", i));
+        }
+
+        assert_eq!(proj.filters.len(), MAX_FILTER_QUERIES);
+        assert_eq!(proj.filters[0], format!("query{}", MAX_FILTER_QUERIES + 4));
+    }
+
     #[test]
     fn test_history_get_last_script() {
         let mut history = History::new();
@@ -671,6 +1078,7 @@ mod tests {
             enabled: true,
             max_projects: 100,
             max_scripts: 5,
+            ..HistoryConfig::default()
         };
 
         history.cleanup(&config);
@@ -790,6 +1198,28 @@ mod tests {
         assert!(history.scripts.contains_key("yesterday"));
     }
 
+    #[test]
+    fn test_rename_and_remove_script() {
+        let project = Path::new("/work/app");
+        let mut history = History::new();
+        history.record_run(project, "test", Some("--watch".to_string()));
+        history.record_run(project, "dev", None);
+        history.record_run(project, "test", None);
+
+        history.rename_script(project, "test", "unit");
+        assert!(history.get_script_stats(project, "test").is_none());
+        assert_eq!(history.get_script_stats(project, "unit").unwrap().count, 2);
+        assert_eq!(
+            history.get_last_script(project),
+            Some(("unit".to_string(), None))
+        );
+
+        history.remove_script(project, "unit");
+        assert!(history.get_script_stats(project, "unit").is_none());
+        assert!(history.get_last_script(project).is_none());
+        assert!(history.get_script_stats(project, "dev").is_some());
+    }
+
     #[test]
     fn test_history_version() {
         let history = History::new();
@@ -0,0 +1,20 @@
+# nrs directory hook for bash
+#
+# Add to ~/.bashrc:
+#   eval "$(nrs hook bash)"
+#
+# Entering a directory with a package.json prints its most used scripts
+# on one line.
+
+__nrs_last_pwd="$PWD"
+
+__nrs_chpwd() {
+  if [[ "$PWD" != "$__nrs_last_pwd" ]]; then
+    __nrs_last_pwd="$PWD"
+    if [[ -f package.json ]]; then
+      nrs --brief 2>/dev/null
+    fi
+  fi
+}
+
+PROMPT_COMMAND="__nrs_chpwd${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
@@ -0,0 +1,13 @@
+# nrs directory hook for fish
+#
+# Add to ~/.config/fish/config.fish:
+#   nrs hook fish | source
+#
+# Entering a directory with a package.json prints its most used scripts
+# on one line.
+
+function __nrs_chpwd --on-variable PWD
+    if test -f package.json
+        nrs --brief 2>/dev/null
+    end
+end
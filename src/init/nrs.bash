@@ -0,0 +1,18 @@
+# nrs shell integration for bash
+#
+# Add to ~/.bashrc:
+#   eval "$(nrs init bash)"
+#
+# Ctrl+J opens the script picker and inserts the chosen command at the
+# cursor. Rebind with: bind -x '"<key>": __nrs_widget'
+
+__nrs_widget() {
+  local cmd
+  cmd="$(nrs --popup < /dev/tty)"
+  if [[ -n "$cmd" ]]; then
+    READLINE_LINE="${READLINE_LINE:0:READLINE_POINT}${cmd}${READLINE_LINE:READLINE_POINT}"
+    READLINE_POINT=$((READLINE_POINT + ${#cmd}))
+  fi
+}
+
+bind -x '"\C-j": __nrs_widget'
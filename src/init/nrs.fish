@@ -0,0 +1,20 @@
+# nrs shell integration for fish
+#
+# Add to ~/.config/fish/config.fish:
+#   nrs init fish | source
+#
+# Ctrl+J opens the script picker and inserts the chosen command at the
+# cursor. Rebind with: bind <key> __nrs_widget
+
+function __nrs_widget
+    set -l cmd (nrs --popup < /dev/tty)
+    if test -n "$cmd"
+        commandline -i -- "$cmd"
+    end
+    commandline -f repaint
+end
+
+bind \cj __nrs_widget
+if bind -M insert >/dev/null 2>&1
+    bind -M insert \cj __nrs_widget
+end
@@ -15,9 +15,11 @@
 //!
 //! - [`cli`] - Command-line interface argument parsing
 //! - [`config`] - Configuration file loading and types
+//! - [`doctor`] - Environment checks
 //! - [`error`] - Error types and result helpers
 //! - [`filter`] - Fuzzy filtering for scripts
 //! - [`history`] - Script execution history tracking
+//! - [`output`] - Output formats of the reporting commands
 //! - [`package`] - Package.json parsing and package manager detection
 //! - [`runner`] - Script execution
 //! - [`tui`] - Terminal user interface
@@ -26,12 +28,13 @@
 //! # Example
 //!
 //! ```no_run
-//! use npm_run_scripts::package::{parse_scripts, detect_runner, Runner};
+//! use npm_run_scripts::package::{parse_scripts, detect_runner, DescriptionSource, Runner};
 //! use std::path::Path;
 //!
 //! // Parse scripts from a project
 //! let project_dir = Path::new("./my-project");
-//! let scripts = parse_scripts(project_dir).expect("Failed to parse scripts");
+//! let scripts = parse_scripts(project_dir, &DescriptionSource::ALL)
+//!     .expect("Failed to parse scripts");
 //!
 //! // Detect the package manager
 //! let runner = detect_runner(project_dir);
@@ -47,6 +50,9 @@ pub mod cli;
 /// Configuration system for loading and merging settings.
 pub mod config;
 
+/// Environment checks for `nrs doctor`.
+pub mod doctor;
+
 /// Error types and result helpers.
 pub mod error;
 
@@ -56,6 +62,9 @@ pub mod filter;
 /// Script execution history tracking.
 pub mod history;
 
+/// Output formats of the reporting commands.
+pub mod output;
+
 /// Package.json parsing and package manager detection.
 pub mod package;
 
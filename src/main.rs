@@ -2,21 +2,53 @@
 //!
 //! Entry point for the nrs CLI application.
 
-use std::io::{self, IsTerminal};
-use std::path::Path;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::debug;
 
-use npm_run_scripts::cli::Cli;
-use npm_run_scripts::config::Config;
+use npm_run_scripts::cli::{
+    Cli, CliCommand, CliCompletionsCommand, CliConfigCommand, CliHistoryCommand, CliInitShell,
+    CliListColumn, CliListFormat, CliReportFormat,
+};
+use npm_run_scripts::config::{
+    generate_config_schema, trust_configs, untrusted_configs, Config, Density, TrustStore,
+    PROFILE_ENV_VAR,
+};
+use npm_run_scripts::doctor::{checks_report, run_checks, Status};
 use npm_run_scripts::error::{exit_code, NrsError};
-use npm_run_scripts::history::History;
-use npm_run_scripts::package::{detect_runner_reason, parse_scripts, Runner, Scripts};
-use npm_run_scripts::runner::execute_script;
-use npm_run_scripts::tui::{run_tui, App};
+use npm_run_scripts::history::{
+    brief_stamp, format_brief, last_failed_run, last_matching_run, project_runs, projects_report,
+    repair_history, runs_report, search_history, weekday, BriefCache, Favorites, History,
+    ProjectStats, ScriptHistory, BRIEF_SCRIPTS, STATS_SCRIPTS,
+};
+use npm_run_scripts::output::Report;
+use npm_run_scripts::package::{
+    add_script, all_workspaces, describe_monorepo, detect_monorepo_tools, detect_runner_reason,
+    detect_workspace_info, detect_workspaces, diff_scripts, exclude_workspaces, find_workspace,
+    format_scripts_diff, install_runner, is_monorepo, needs_install, parent_scripts, parse_scripts,
+    parse_scripts_with_limits, scripts_at_ref, suggest_scripts, DescriptionSource, GitActivity,
+    ParseLimits, Runner, Script, ScriptEdit, ScriptGraph, ScriptMatrix, Scripts, Workspace,
+    WorkspaceInfo,
+};
+use npm_run_scripts::runner::{
+    aggregate_exit_code, execute_script_with_limits, execute_workspace_script_with_limits,
+    format_comparison, format_run_summary, format_shell_command, format_workspace_summary, jobs,
+    logging, new_command, notify_run, run_scripts_parallel, split_args, time_runner, watch,
+    watch_until_pass, ExecutionResult, Heartbeat, ParallelJob, RunContext, RunResult,
+    WebhookPayload,
+};
+use npm_run_scripts::tui::{pick, run_tui, App, ErrorAction, ScriptRun};
 use npm_run_scripts::utils::{
-    find_project_root, global_config_file, history_file, local_config_file,
+    check_terminal_size, clock, default_log_file, diff_words, find_project_root, format_bar,
+    format_elapsed, format_time_ago, global_config_file, history_file, init_logging,
+    local_config_file, migrate_state_files, wrap_text, DiffWords, LOG_ENV_VAR, USABLE_MIN_HEIGHT,
+    USABLE_MIN_WIDTH,
 };
 
 fn main() -> ExitCode {
@@ -35,7 +67,7 @@ fn main() -> ExitCode {
 }
 
 fn run() -> Result<i32> {
-    let cli = Cli::parse_args();
+    let mut cli = Cli::parse_args();
 
     // Handle shell completions early
     if let Some(shell) = cli.completions {
@@ -43,6 +75,69 @@ fn run() -> Result<i32> {
         return Ok(exit_code::SUCCESS);
     }
 
+    // History used to live in the config directory
+    if let Err(err) = migrate_state_files() {
+        eprintln!("Warning: {err:#}");
+    }
+
+    match &cli.command {
+        Some(CliCommand::History {
+            action: CliHistoryCommand::Repair,
+        }) => return repair_history_command(),
+        Some(CliCommand::History {
+            action: CliHistoryCommand::Search { query, format },
+        }) => return search_history_command(&cli, query, *format),
+        Some(CliCommand::History {
+            action: CliHistoryCommand::Projects { format },
+        }) => return history_projects_command(*format),
+        Some(CliCommand::History {
+            action: CliHistoryCommand::Pin { path },
+        }) => return pin_project_command(path.as_deref(), true),
+        Some(CliCommand::History {
+            action: CliHistoryCommand::Unpin { path },
+        }) => return pin_project_command(path.as_deref(), false),
+        Some(CliCommand::History {
+            action: CliHistoryCommand::Prune { dry_run },
+        }) => return prune_projects_command(*dry_run),
+        Some(CliCommand::Config {
+            action: CliConfigCommand::Schema,
+        }) => {
+            println!("{}", generate_config_schema());
+            return Ok(exit_code::SUCCESS);
+        }
+        Some(CliCommand::Init { shell }) => {
+            print!("{}", shell.init_script());
+            return Ok(exit_code::SUCCESS);
+        }
+        Some(CliCommand::Doctor { format }) => return doctor_command(&cli, *format),
+        Some(CliCommand::Hook { shell }) => {
+            print!("{}", shell.hook_script());
+            return Ok(exit_code::SUCCESS);
+        }
+        Some(CliCommand::Completions {
+            action: CliCompletionsCommand::Install { shell, dry_run },
+        }) => return install_completions_command(*shell, *dry_run),
+        Some(
+            CliCommand::Diff { .. }
+            | CliCommand::NewScript { .. }
+            | CliCommand::Suggest { .. }
+            | CliCommand::Stats { .. }
+            | CliCommand::Matrix { .. }
+            | CliCommand::Logs { .. },
+        )
+        | None => {}
+    }
+
+    init_diagnostics(&cli);
+
+    // Reproducible output for snapshot tests and demos
+    if let Some(time) = cli.frozen_time {
+        clock::freeze_time(time);
+    }
+    if let Some(seed) = cli.seed {
+        clock::set_seed(seed);
+    }
+
     if cli.debug {
         print_debug_header();
         eprintln!("Debug: CLI arguments = {cli:#?}");
@@ -52,11 +147,86 @@ fn run() -> Result<i32> {
     let project_dir =
         find_project_root(&cli.project_dir()).context("Failed to find project directory")?;
 
+    debug!("Project directory = {}", project_dir.display());
     if cli.debug {
-        eprintln!("Debug: Project directory = {}", project_dir.display());
         print_debug_paths(&project_dir);
     }
 
+    if let Some(CliCommand::Diff { reference }) = &cli.command {
+        return diff_scripts_command(&project_dir, reference);
+    }
+
+    if cli.brief {
+        return brief_command(&cli, &project_dir);
+    }
+
+    // Load config for exclude patterns (used in both list and TUI modes)
+    let mut config = if cli.no_config {
+        Config::default()
+    } else {
+        if !cli.read_only {
+            confirm_trust(&project_dir)?;
+        }
+        load_profile_config(&cli, &project_dir)?
+    };
+    if cli.read_only {
+        config.history.enabled = false;
+    }
+    if cli.yes {
+        config.scripts.confirm.clear();
+    }
+    let mut context = RunContext::new()
+        .with_pinned_package_manager(&project_dir, config.general.enforce_package_manager());
+    if (cli.log || config.logs.enabled) && !cli.read_only {
+        context = context.with_logs(config.logs.keep);
+    }
+    if let Some(path) = cli.record.as_deref().filter(|_| !cli.is_dry_run()) {
+        context = context.with_recording(path)?;
+    }
+
+    if let Some(workspace_name) = &cli.print_workspace_path {
+        // Print the workspace directory for shell integration
+        let workspace = resolve_workspace(&config, &project_dir, workspace_name)?;
+        println!("{}", workspace.path().display());
+        return Ok(exit_code::SUCCESS);
+    }
+
+    if let Some(CliCommand::NewScript {
+        workspace,
+        name,
+        command,
+    }) = &cli.command
+    {
+        if cli.read_only {
+            anyhow::bail!("Can't add scripts in read-only mode");
+        }
+        let dir = match workspace {
+            Some(workspace) => resolve_workspace(&config, &project_dir, workspace)?
+                .path()
+                .to_path_buf(),
+            None => project_dir.clone(),
+        };
+        add_script(&dir, name, &command.join(" "))?;
+        println!("Added {name} to {}", dir.join("package.json").display());
+        return Ok(exit_code::SUCCESS);
+    }
+
+    if let Some(CliCommand::Suggest { write }) = &cli.command {
+        return suggest_command(&project_dir, *write, cli.read_only);
+    }
+
+    if let Some(CliCommand::Stats { format }) = &cli.command {
+        return stats_command(&config, &project_dir, *format);
+    }
+
+    if let Some(CliCommand::Matrix { scripts, format }) = &cli.command {
+        return matrix_command(&config, &project_dir, scripts, *format);
+    }
+
+    if let Some(CliCommand::Logs { tail }) = &cli.command {
+        return logs_command(&project_dir, tail.as_deref());
+    }
+
     // Detect package manager
     let (runner, runner_reason) = if let Some(r) = cli.runner_override() {
         (r, "CLI --runner flag".to_string())
@@ -64,12 +234,39 @@ fn run() -> Result<i32> {
         detect_runner_reason(&project_dir)
     };
 
-    if cli.debug {
-        eprintln!("Debug: Package manager = {} ({})", runner, runner_reason);
+    debug!("Package manager = {} ({})", runner, runner_reason);
+
+    if let Some(script_name) = &cli.all_workspaces {
+        return run_all_workspaces_command(
+            &cli,
+            &config,
+            &context,
+            runner,
+            &project_dir,
+            script_name,
+        );
     }
 
     // Parse scripts
-    let scripts = parse_scripts(&project_dir).context("Failed to parse scripts")?;
+    let limits = if cli.force {
+        ParseLimits::unlimited()
+    } else {
+        ParseLimits::default()
+    };
+    let scripts = load_scripts(&config, &project_dir, limits)?;
+
+    // An alias runs its script, with the alias arguments first
+    let alias = cli
+        .script
+        .as_deref()
+        .and_then(|name| scripts.get(name))
+        .and_then(Script::alias)
+        .cloned();
+    if let Some(alias) = alias {
+        debug!("Running alias {:?} as {alias}", cli.script);
+        cli.args = alias.args_with(cli.args.as_deref());
+        cli.script = Some(alias.script);
+    }
 
     if scripts.is_empty() {
         let package_json_path = project_dir.join("package.json");
@@ -79,19 +276,11 @@ fn run() -> Result<i32> {
         .into());
     }
 
+    debug!("Found {} scripts", scripts.len());
     if cli.debug {
-        eprintln!("Debug: Found {} scripts", scripts.len());
         print_debug_scripts(&scripts);
     }
 
-    // Load config for exclude patterns (used in both list and TUI modes)
-    let config = if cli.no_config {
-        Config::default()
-    } else {
-        npm_run_scripts::config::load_config(cli.config.as_deref(), &project_dir)
-            .unwrap_or_default()
-    };
-
     // Combine config and CLI exclude patterns
     let mut exclude_patterns = config.exclude.patterns.clone();
     exclude_patterns.extend(cli.exclude.clone());
@@ -103,22 +292,103 @@ fn run() -> Result<i32> {
         scripts
     };
 
+    let scripts = if cli.favorites {
+        only_pinned(&scripts, &config, &project_dir)?
+    } else {
+        scripts
+    };
+
     // Handle different modes
+    if cli.summary {
+        // Summary mode: print category counts and exit
+        return summarize_scripts(&scripts, runner);
+    }
+
+    if let Some(name) = &cli.graph {
+        // Graph mode: print the scripts a script runs and exit
+        return print_script_graph(&scripts, name);
+    }
+
     if cli.list {
         // List mode: print scripts and exit
-        return list_scripts(&scripts, runner);
+        let workspace = match &cli.workspace {
+            Some(name) => Some(resolve_workspace(&config, &project_dir, name)?),
+            None => None,
+        };
+        let history_dir = workspace
+            .as_ref()
+            .map_or(project_dir.as_path(), |w| w.path());
+        let scripts = match &workspace {
+            Some(workspace) => {
+                let mut ws_scripts = Scripts::from_vec(workspace_scripts(workspace)?.to_vec());
+                config.scripts.apply(&mut ws_scripts);
+                let ws_scripts = ws_scripts.without_matching(&exclude_patterns);
+                if cli.favorites {
+                    only_pinned(&ws_scripts, &config, history_dir)?
+                } else {
+                    ws_scripts
+                }
+            }
+            None => scripts,
+        };
+        let scripts = match &cli.category {
+            Some(category) => {
+                let in_category = scripts.in_category(category);
+                if in_category.is_empty() {
+                    anyhow::bail!("No scripts in category \"{category}\" (see nrs --summary)");
+                }
+                in_category
+            }
+            None => scripts,
+        };
+        let columns = if !cli.columns.is_empty() {
+            cli.columns.clone()
+        } else if cli.verbose > 0 {
+            CliListColumn::VERBOSE.to_vec()
+        } else {
+            CliListColumn::DEFAULT.to_vec()
+        };
+        let format = cli.list_format();
+        let history = if format == CliListFormat::Json || columns.iter().any(|c| c.uses_history()) {
+            History::load_with_config(&config.history).unwrap_or_default()
+        } else {
+            History::new()
+        };
+        let listing = ScriptListing {
+            scripts: &scripts,
+            runner,
+            workspace: workspace.as_ref().map(|w| w.name()),
+            columns: &columns,
+            long: cli.long,
+        };
+        return list_scripts(&listing, format, |name| {
+            history.get_script_stats(history_dir, name).cloned()
+        });
+    }
+
+    if let Some(count) = cli.history {
+        let history = History::load_with_config(&config.history).unwrap_or_default();
+        return print_recent_runs(&history, &project_dir, count, cli.output_format());
     }
 
-    if cli.last {
-        // Rerun last script
-        let history = History::load().unwrap_or_default();
+    if let Some(pattern) = &cli.last {
+        // Rerun the last script, or the last one matching the pattern
+        let history = History::load_with_config(&config.history).unwrap_or_default();
 
-        let (script_name, stored_args) =
-            history.get_last_script(&project_dir).ok_or_else(|| {
+        let (script_name, stored_args) = match pattern {
+            None => history.get_last_script(&project_dir).ok_or_else(|| {
                 anyhow::anyhow!(
                     "No previous script found for this project. Run nrs first to execute a script."
                 )
-            })?;
+            })?,
+            Some(pattern) => last_matching_run(&history, &project_dir, pattern)
+                .map(|run| (run.script, run.args))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "No script matching '{pattern}' in this project's history. Run nrs --history to see recent runs."
+                    )
+                })?,
+        };
 
         // Check if the script still exists
         if scripts.get(&script_name).is_none() {
@@ -128,6 +398,20 @@ fn run() -> Result<i32> {
         // Use CLI args if provided, otherwise use stored args from history
         let args_str = cli.args.as_deref().or(stored_args.as_deref());
 
+        if let Some(group) = scripts.get(&script_name).filter(|s| s.group().is_some()) {
+            eprintln!("Rerunning group: {script_name}");
+            let run = ScriptRun::new(group.clone(), args_str.map(String::from));
+            return run_group(
+                &config,
+                &context,
+                runner,
+                &project_dir,
+                &scripts,
+                &run,
+                cli.is_dry_run(),
+            );
+        }
+
         // Print what we're running
         eprintln!(
             "Rerunning: {}{}",
@@ -135,29 +419,158 @@ fn run() -> Result<i32> {
             args_str.map(|a| format!(" {}", a)).unwrap_or_default()
         );
 
-        let args_vec: Vec<String> = args_str
-            .map(|a| a.split_whitespace().map(String::from).collect())
-            .unwrap_or_default();
+        let args_vec: Vec<String> = args_str.map(split_args).unwrap_or_default();
+
+        if !confirm_run(
+            &config,
+            runner,
+            &project_dir,
+            &[script_name.as_str()],
+            cli.is_dry_run(),
+        )? {
+            return Ok(exit_code::INTERRUPTED);
+        }
+
+        let heartbeat = start_heartbeat(&script_name, &config, cli.is_dry_run());
+        let runner = scripts
+            .get(&script_name)
+            .map_or(runner, |script| script.runner_or(runner));
+        if cli.is_dry_run() {
+            // Detection may have changed since, e.g. after a lockfile change
+            if let Some(stored) = history
+                .get_script_stats(&project_dir, &script_name)
+                .and_then(|stats| stats.last_command.as_deref())
+            {
+                let current = runner.run_command_with_args(&script_name, &args_vec);
+                print_command_change(stored, &current.join(" "));
+            }
+        }
+        let result = execute_script_with_limits(
+            runner,
+            &script_name,
+            &args_vec,
+            &project_dir,
+            cli.is_dry_run(),
+            &config.limits_for(&script_name),
+            &context,
+        )?;
+        finish_heartbeat(heartbeat, result.success());
+        if !cli.is_dry_run() {
+            record_result(&config, &project_dir, &script_name, &result);
+            notify_run(
+                &config.hooks,
+                &WebhookPayload::new(&project_dir, None, &script_name, &result),
+            );
+        }
+
+        return Ok(script_exit_code(&config, result.exit_code()));
+    }
+
+    if let (Some(script_name), Some(workspace)) = (&cli.script, &cli.workspace) {
+        // Direct script execution in a workspace
+        return run_workspace_script_by_name(
+            &config,
+            &context,
+            runner,
+            workspace,
+            script_name,
+            cli.args.as_deref(),
+            &project_dir,
+            cli.is_dry_run(),
+        );
+    }
+
+    if !cli.compare.is_empty() {
+        if let Some(script_name) = &cli.script {
+            return compare_runners(&cli, &scripts, script_name, &project_dir);
+        }
+    }
+
+    if cli.watch_failed {
+        return watch_failed_command(&cli, &config, &context, &scripts, runner, &project_dir);
+    }
 
-        let result = execute_script(runner, &script_name, &args_vec, &project_dir, cli.dry_run)?;
+    if let Some(script_name) = cli.script.as_deref().filter(|_| !cli.watch.is_empty()) {
+        return watch(&project_dir, &config.watch, &cli.watch, || {
+            if script_name.contains('*') {
+                return run_pattern_command(
+                    &cli,
+                    &config,
+                    &context,
+                    &scripts,
+                    runner,
+                    &project_dir,
+                    script_name,
+                );
+            }
+            run_script_by_name(
+                &config,
+                &context,
+                &scripts,
+                runner,
+                script_name,
+                cli.args.as_deref(),
+                &project_dir,
+                cli.is_dry_run(),
+            )
+        });
+    }
 
-        return Ok(result.code().unwrap_or(0));
+    if let Some(pattern) = cli.script.as_deref().filter(|name| name.contains('*')) {
+        return run_pattern_command(
+            &cli,
+            &config,
+            &context,
+            &scripts,
+            runner,
+            &project_dir,
+            pattern,
+        );
     }
 
     if let Some(script_name) = &cli.script {
         // Direct script execution
         return run_script_by_name(
+            &config,
+            &context,
             &scripts,
             runner,
             script_name,
             cli.args.as_deref(),
             &project_dir,
-            cli.dry_run,
+            cli.is_dry_run(),
         );
     }
 
-    // TUI mode
-    let history = History::load().unwrap_or_default();
+    if cli.pick {
+        let scripts = scripts.without_lifecycle();
+        let history = History::load_with_config(&config.history).unwrap_or_default();
+        let sorted: Vec<Script> = history
+            .get_sorted_by_recent(&project_dir, scripts.as_slice())
+            .into_iter()
+            .cloned()
+            .collect();
+        return Ok(match pick(&sorted, &config.filter)? {
+            Some(name) => {
+                println!("{name}");
+                exit_code::SUCCESS
+            }
+            None => exit_code::INTERRUPTED,
+        });
+    }
+
+    // TUI mode: small terminals get a degraded layout, only refuse when unusable
+    if let Some(size) = check_terminal_size() {
+        if !size.is_usable() {
+            return Err(NrsError::TerminalTooSmall {
+                width: size.width,
+                height: size.height,
+                min_width: USABLE_MIN_WIDTH,
+                min_height: USABLE_MIN_HEIGHT,
+            }
+            .into());
+        }
+    }
 
     // Get project name
     let project_name = project_dir
@@ -167,181 +580,2472 @@ fn run() -> Result<i32> {
         .to_string();
 
     // Filter out lifecycle scripts (exclude patterns already applied above)
-    let scripts = scripts.without_lifecycle();
+    let mut scripts = scripts.without_lifecycle();
 
-    // Create and run the app
-    let app = App::new(
-        scripts,
-        config,
-        history,
-        project_name,
-        project_dir.clone(),
-        runner,
+    // Detect workspaces so the TUI can offer the workspace selector
+    let workspace_info = tui_workspace_info(&config, &project_dir);
+    let monorepo_label = describe_monorepo(
+        workspace_info.workspace_type,
+        &detect_monorepo_tools(&project_dir),
+    );
+    let mut workspaces = exclude_workspaces(
+        workspace_info.workspaces,
+        &project_dir,
+        &config.exclude.workspaces.patterns,
     );
 
-    let scripts_to_run = run_tui(app).context("TUI error")?;
-
-    // Execute selected scripts
-    if scripts_to_run.is_empty() {
-        return Ok(exit_code::SUCCESS);
+    if cli.popup {
+        // Popups are small and should close as soon as a script is picked
+        config.appearance.density = Density::Compact;
+        config.general.esc_quits = Some(true);
+        // Picked scripts are printed, not run
+        config.general.check_install = Some(false);
     }
+    let mut runner = runner;
+    // A run that failed to start, reported in the error overlay on reopen
+    let mut failure: Option<(String, ScriptRun)> = None;
+
+    let git_activity = config
+        .general
+        .sort_git_boost()
+        .then(|| GitActivity::scan(&project_dir));
+
+    'tui: loop {
+        // Create and run the app
+        let mut app = App::with_workspaces(
+            scripts.clone(),
+            config.clone(),
+            History::load_with_config(&config.history).unwrap_or_default(),
+            project_name.clone(),
+            project_dir.clone(),
+            runner,
+            workspaces.clone(),
+        );
+        app.set_monorepo_label(monorepo_label.clone());
+        app.set_favorites(Favorites::load().unwrap_or_default());
+        app.set_read_only(cli.read_only);
+        app.set_run_context(context.clone());
+        if let Some(activity) = &git_activity {
+            app.set_git_activity(activity.clone());
+        }
 
-    // Execute scripts
-    for (i, script_run) in scripts_to_run.iter().enumerate() {
-        if scripts_to_run.len() > 1 {
-            println!(
-                "\n\x1b[1;36mRunning {}/{}: {}...\x1b[0m",
-                i + 1,
-                scripts_to_run.len(),
-                script_run.script.name()
+        if let Some((message, failed_run)) = failure.take() {
+            app.set_failed_run(failed_run);
+            app.show_error(
+                message,
+                vec![
+                    ErrorAction::Retry,
+                    ErrorAction::SwitchRunner,
+                    ErrorAction::OpenConfig,
+                ],
             );
         }
 
-        // Record in history
-        let mut history = History::load().unwrap_or_default();
-        history.record_run(
-            &project_dir,
-            script_run.script.name(),
-            script_run.args.clone(),
-        );
-        let _ = history.save();
+        let outcome = run_tui(app).context("TUI error")?;
 
-        let args: Vec<String> = script_run
-            .args
-            .as_ref()
-            .map(|a| a.split_whitespace().map(String::from).collect())
-            .unwrap_or_default();
+        runner = outcome.runner;
+        record_runs(&config, &project_dir, &outcome.pane_runs);
+        record_script_edits(&config, &outcome.script_edits);
+        if let Some(favorites) = &outcome.favorites {
+            let _ = favorites.save();
+        }
+        if let Some(groups) = outcome.collapsed_groups.clone() {
+            let mut history = History::load_with_config(&config.history).unwrap_or_default();
+            history.set_collapsed_groups(&project_dir, groups);
+            let _ = history.save_with_config(&config.history);
+        }
 
-        let result = execute_script(
-            runner,
-            script_run.script.name(),
-            &args,
-            &project_dir,
-            cli.dry_run,
-        )?;
+        if outcome.action == Some(ErrorAction::OpenConfig) {
+            // Edit the config, then reopen the TUI with it reloaded
+            let path = editable_config_path(cli.config.as_deref(), &project_dir);
+            open_in_editor(&path)?;
+            if !cli.no_config {
+                confirm_trust(&project_dir)?;
+                config = load_profile_config(&cli, &project_dir)?;
+                if cli.read_only {
+                    config.history.enabled = false;
+                }
+                if cli.yes {
+                    config.scripts.confirm.clear();
+                }
+
+                // Read the scripts again, e.g. for edited description sources
+                let mut exclude_patterns = config.exclude.patterns.clone();
+                exclude_patterns.extend(cli.exclude.clone());
+                let mut reloaded = load_scripts(&config, &project_dir, limits)?
+                    .without_matching(&exclude_patterns);
+                if cli.favorites {
+                    reloaded = only_pinned(&reloaded, &config, &project_dir)?;
+                }
+                scripts = reloaded.without_lifecycle();
+                workspaces = exclude_workspaces(
+                    tui_workspace_info(&config, &project_dir).workspaces,
+                    &project_dir,
+                    &config.exclude.workspaces.patterns,
+                );
+            }
+            continue;
+        }
 
-        let code = result.code().unwrap_or(0);
-        if code != 0 {
-            return Ok(code);
+        // Scripts confirmed in the TUI aren't asked about again
+        let mut config = config.clone();
+        if outcome.confirmed {
+            config.scripts.confirm.clear();
+        }
+        if outcome.install_checked {
+            config.general.check_install = Some(false);
         }
-    }
 
-    Ok(exit_code::SUCCESS)
-}
+        if let Some(path) = outcome.yanked_path {
+            // Workspace path yanked from the TUI
+            println!("{}", path.display());
+            return Ok(exit_code::SUCCESS);
+        }
 
-/// Run a script by name directly (non-TUI mode).
-fn run_script_by_name(
-    scripts: &Scripts,
-    runner: Runner,
-    script_name: &str,
-    args: Option<&str>,
-    project_dir: &std::path::Path,
-    dry_run: bool,
-) -> Result<i32> {
-    if scripts.get(script_name).is_none() {
-        // Get available script names for suggestions
-        let script_names: Vec<&str> = scripts.iter().map(|s| s.name()).collect();
-        let err = NrsError::script_not_found_with_suggestions(script_name, &script_names);
-        eprintln!("Error: {err}");
-        eprintln!();
-        eprintln!("Available scripts:");
-        for script in scripts.iter() {
-            eprintln!("  {}", script.name());
+        // Remember the filter that narrowed the list, for Up/Down recall
+        if let Some(filter) = &outcome.filter {
+            let mut history = History::load_with_config(&config.history).unwrap_or_default();
+            history.record_filter(&project_dir, filter);
+            let _ = history.save_with_config(&config.history);
         }
-        return Ok(exit_code::GENERAL_ERROR);
-    }
 
-    let args_vec: Vec<String> = args
-        .map(|a| a.split_whitespace().map(String::from).collect())
-        .unwrap_or_default();
+        let scripts_to_run = outcome.scripts;
 
-    // Record in history
-    let mut history = History::load().unwrap_or_default();
-    history.record_run(project_dir, script_name, args.map(String::from));
-    let _ = history.save();
+        // Execute selected scripts
+        if scripts_to_run.is_empty() {
+            return Ok(exit_code::SUCCESS);
+        }
+        if outcome.install_first {
+            install_dependencies(
+                install_runner(runner, &project_dir),
+                &project_dir,
+                cli.is_dry_run(),
+            )?;
+        }
 
-    let result = execute_script(runner, script_name, &args_vec, project_dir, dry_run)?;
+        if outcome.all_workspaces {
+            return run_in_workspaces(
+                &config,
+                &context,
+                runner,
+                &project_dir,
+                &scripts_to_run,
+                cli.is_dry_run(),
+            );
+        }
 
-    Ok(result.code().unwrap_or(0))
-}
+        if cli.popup {
+            return print_popup_command(&config, runner, &project_dir, &scripts_to_run[0]);
+        }
 
-/// List scripts in a nice format (non-TUI mode).
-fn list_scripts(scripts: &Scripts, runner: Runner) -> Result<i32> {
-    let use_colors = io::stdout().is_terminal();
+        if outcome.watch {
+            let run = &scripts_to_run[0];
+            let patterns = &config.watch.patterns;
+            watch(&project_dir, &config.watch, patterns, || {
+                run_watched(
+                    &config,
+                    &context,
+                    runner,
+                    &project_dir,
+                    &scripts,
+                    run,
+                    cli.is_dry_run(),
+                )
+            })?;
+            // Ctrl+C stops watching and goes back to the list
+            continue 'tui;
+        }
 
-    // Print header
-    if use_colors {
-        println!("\x1b[1;36mAvailable scripts ({}):\x1b[0m", runner);
-    } else {
-        println!("Available scripts ({}):", runner);
-    }
-    println!();
+        if let [run] = scripts_to_run.as_slice() {
+            if run.script.group().is_some() {
+                return run_group(
+                    &config,
+                    &context,
+                    runner,
+                    &project_dir,
+                    &scripts,
+                    run,
+                    cli.is_dry_run(),
+                );
+            }
+        }
+        // Groups picked with other scripts run alongside them
+        let scripts_to_run = expand_groups(scripts_to_run, &scripts);
 
-    // Find the longest script name for alignment
-    let max_name_len = scripts
-        .iter()
-        .map(|s| s.name().len())
-        .max()
-        .unwrap_or(0)
-        .min(30);
+        let names: Vec<&str> = scripts_to_run.iter().map(|r| r.script.name()).collect();
+        if !confirm_run(&config, runner, &project_dir, &names, cli.is_dry_run())? {
+            return Ok(exit_code::INTERRUPTED);
+        }
 
-    // Print each script
-    for script in scripts.iter() {
-        let name = script.name();
-        let command = script.command();
-        let description = script.description();
+        if scripts_to_run.len() > 1 && !cli.is_dry_run() {
+            return run_parallel(&config, &context, runner, &project_dir, &scripts_to_run);
+        }
 
-        if use_colors {
-            // Script name in green
-            print!("  \x1b[1;32m{:width$}\x1b[0m", name, width = max_name_len);
+        // Execute scripts
+        for (i, script_run) in scripts_to_run.iter().enumerate() {
+            if scripts_to_run.len() > 1 {
+                println!(
+                    "\n\x1b[1;36mRunning {}/{}: {}...\x1b[0m",
+                    i + 1,
+                    scripts_to_run.len(),
+                    script_run.script.name()
+                );
+            }
 
-            // Command in dim
-            print!("  \x1b[2m{}\x1b[0m", truncate_string(command, 50));
+            let args: Vec<String> = script_run
+                .args
+                .as_deref()
+                .map(split_args)
+                .unwrap_or_default();
+
+            let limits = config.limits_for(script_run.script.name());
+            let heartbeat = start_heartbeat(script_run.script.name(), &config, cli.is_dry_run());
+            let runner = script_run.script.runner_or(runner);
+            let result = if let Some(workspace) = &script_run.workspace {
+                execute_workspace_script_with_limits(
+                    runner,
+                    workspace,
+                    script_run.script.name(),
+                    &args,
+                    script_run.run_dir(&project_dir),
+                    cli.is_dry_run(),
+                    &limits,
+                    &context,
+                )
+            } else {
+                execute_script_with_limits(
+                    runner,
+                    script_run.script.name(),
+                    &args,
+                    script_run.run_dir(&project_dir),
+                    cli.is_dry_run(),
+                    &limits,
+                    &context,
+                )
+            };
+            finish_heartbeat(
+                heartbeat,
+                result.as_ref().is_ok_and(ExecutionResult::success),
+            );
 
-            // Description if available
-            if let Some(desc) = description {
-                print!("  \x1b[33m{}\x1b[0m", truncate_string(desc, 40));
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    // The runner could not be started: reopen with recovery options
+                    failure = Some((format!("{err:#}"), script_run.clone()));
+                    continue 'tui;
+                }
+            };
+            if !cli.is_dry_run() {
+                notify_run(
+                    &config.hooks,
+                    &WebhookPayload::new(
+                        &project_dir,
+                        script_run.workspace.as_deref(),
+                        script_run.script.name(),
+                        &result,
+                    ),
+                );
             }
-        } else {
-            print!("  {:width$}", name, width = max_name_len);
-            print!("  {}", truncate_string(command, 50));
 
-            if let Some(desc) = description {
-                print!("  {}", truncate_string(desc, 40));
+            // Record in history (per workspace when one was selected)
+            let mut history = History::load_with_config(&config.history).unwrap_or_default();
+            let history_dir = script_run.workspace_path.as_deref().unwrap_or(&project_dir);
+            history.record_run(
+                history_dir,
+                script_run.script.name(),
+                script_run.args.clone(),
+            );
+            if !cli.is_dry_run() {
+                history.record_result(
+                    history_dir,
+                    script_run.script.name(),
+                    result.exit_code(),
+                    result.duration,
+                );
+                history.record_command(history_dir, script_run.script.name(), &result.command);
+            }
+            let _ = history.save_with_config(&config.history);
+
+            let code = result.exit_code();
+            if code != 0 {
+                return Ok(script_exit_code(&config, code));
             }
         }
 
-        println!();
+        return Ok(exit_code::SUCCESS);
     }
+}
 
-    // Print count
-    println!();
-    if use_colors {
-        println!("\x1b[2m{} scripts found\x1b[0m", scripts.len());
-    } else {
-        println!("{} scripts found", scripts.len());
+/// Run a script picked for watch mode once, recording it in history.
+fn run_watched(
+    config: &Config,
+    context: &RunContext,
+    runner: Runner,
+    project_dir: &Path,
+    scripts: &Scripts,
+    run: &ScriptRun,
+    dry_run: bool,
+) -> Result<i32> {
+    if run.script.group().is_some() {
+        return run_group(config, context, runner, project_dir, scripts, run, dry_run);
     }
 
-    Ok(exit_code::SUCCESS)
+    let name = run.script.name();
+    let history_dir = run.workspace_path.as_deref().unwrap_or(project_dir);
+    let mut history = History::load_with_config(&config.history).unwrap_or_default();
+    history.record_run(history_dir, name, None);
+    let _ = history.save_with_config(&config.history);
+
+    let limits = config.limits_for(name);
+    let heartbeat = start_heartbeat(name, config, dry_run);
+    let runner = run.script.runner_or(runner);
+    let result = if let Some(workspace) = &run.workspace {
+        execute_workspace_script_with_limits(
+            runner,
+            workspace,
+            name,
+            &[],
+            run.run_dir(project_dir),
+            dry_run,
+            &limits,
+            context,
+        )?
+    } else {
+        execute_script_with_limits(
+            runner,
+            name,
+            &[],
+            run.run_dir(project_dir),
+            dry_run,
+            &limits,
+            context,
+        )?
+    };
+    finish_heartbeat(heartbeat, result.success());
+    if !dry_run {
+        record_result(config, history_dir, name, &result);
+        notify_run(
+            &config.hooks,
+            &WebhookPayload::new(project_dir, run.workspace.as_deref(), name, &result),
+        );
+    }
+    Ok(script_exit_code(config, result.exit_code()))
 }
 
-/// Truncate a string to a maximum length, adding ellipsis if needed.
-/// Handles Unicode characters properly.
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if max_len < 4 {
-        return s.chars().take(max_len).collect();
+/// Rerun the script whose last run failed whenever watched files change,
+/// until it passes.
+fn watch_failed_command(
+    cli: &Cli,
+    config: &Config,
+    context: &RunContext,
+    scripts: &Scripts,
+    runner: Runner,
+    project_dir: &Path,
+) -> Result<i32> {
+    let history = History::load_with_config(&config.history).unwrap_or_default();
+    let Some(failed) = last_failed_run(&history, project_dir) else {
+        eprintln!("No failing scripts in this project's history");
+        return Ok(exit_code::GENERAL_ERROR);
+    };
+    if scripts.get(&failed.script).is_none() {
+        anyhow::bail!(
+            "Script '{}' no longer exists in package.json",
+            failed.script
+        );
     }
 
-    let char_count = s.chars().count();
-    if char_count <= max_len {
-        s.to_string()
-    } else {
-        let truncated: String = s.chars().take(max_len - 3).collect();
-        format!("{}...", truncated)
+    // Use CLI args if provided, otherwise those of the failed run
+    let args = cli.args.as_deref().or(failed.args.as_deref());
+    eprintln!("Watching {}, which failed last time", failed.command());
+    let code = watch_until_pass(project_dir, &config.watch, &config.watch.patterns, || {
+        run_script_by_name(
+            config,
+            context,
+            scripts,
+            runner,
+            &failed.script,
+            args,
+            project_dir,
+            cli.is_dry_run(),
+        )
+    })?;
+    if code == exit_code::SUCCESS {
+        eprintln!("\n{} passes, stopped watching", failed.script);
     }
+    Ok(code)
 }
 
-// ==================== Debug Functions ====================
+/// Run scripts picked in multi-select mode in parallel.
+///
+/// Every run is recorded in history, and the exit code is that of the first
+/// failed script.
+fn run_parallel(
+    config: &Config,
+    context: &RunContext,
+    runner: Runner,
+    project_dir: &Path,
+    runs: &[ScriptRun],
+) -> Result<i32> {
+    let results = run_jobs_parallel(config, context, runner, project_dir, runs)?;
+    Ok(script_exit_code(config, aggregate_exit_code(&results)))
+}
+
+/// Run scripts in parallel and record them in history, returning their
+/// results in order.
+fn run_jobs_parallel(
+    config: &Config,
+    context: &RunContext,
+    runner: Runner,
+    project_dir: &Path,
+    runs: &[ScriptRun],
+) -> Result<Vec<ExecutionResult>> {
+    let jobs: Vec<ParallelJob> = runs
+        .iter()
+        .map(|run| ParallelJob {
+            runner: run.script.runner_or(runner),
+            script: run.script.name().to_string(),
+            workspace: run.workspace.clone(),
+            args: run.args.as_deref().map(split_args).unwrap_or_default(),
+            limits: config.limits_for(run.script.name()),
+        })
+        .collect();
+
+    let heartbeat = Heartbeat::start(&format!("{} scripts", runs.len()), &config.progress);
+    // Scripts picked together all come from the same workspace
+    let run_dir = runs
+        .first()
+        .map_or(project_dir, |run| run.run_dir(project_dir));
+    let results = run_scripts_parallel(
+        &jobs,
+        run_dir,
+        config.general.max_parallel(),
+        io::stdout().is_terminal(),
+        context,
+    )?;
+    finish_heartbeat(heartbeat, results.iter().all(ExecutionResult::success));
+
+    let finished: Vec<(ScriptRun, ExecutionResult)> =
+        runs.iter().cloned().zip(results.iter().cloned()).collect();
+    record_runs(config, project_dir, &finished);
+
+    Ok(results)
+}
+
+/// Run `nrs -n <pattern>`: every script matching an npm-run-all pattern,
+/// one after another or all at once with `--parallel`.
+///
+/// The matched scripts are listed first and, on a terminal, only run once
+/// confirmed. A sequential run stops at the first failed script.
+fn run_pattern_command(
+    cli: &Cli,
+    config: &Config,
+    context: &RunContext,
+    scripts: &Scripts,
+    runner: Runner,
+    project_dir: &Path,
+    pattern: &str,
+) -> Result<i32> {
+    let matched = scripts.matching_pattern(pattern);
+    if matched.is_empty() {
+        anyhow::bail!("No scripts match \"{pattern}\"");
+    }
+
+    let dry_run = cli.is_dry_run();
+    let width = matched.iter().map(|s| s.name().len()).max().unwrap_or(0);
+    eprintln!(
+        "\"{pattern}\" matches {} script{}:",
+        matched.len(),
+        if matched.len() == 1 { "" } else { "s" }
+    );
+    for script in matched.iter() {
+        eprintln!("  {:<width$}  {}", script.name(), script.command());
+    }
+    if !dry_run && io::stdin().is_terminal() {
+        let how = if cli.parallel {
+            "in parallel"
+        } else {
+            "in order"
+        };
+        if !ask_yes_no(&format!("Run them {how}?"))? {
+            eprintln!("Cancelled");
+            return Ok(exit_code::INTERRUPTED);
+        }
+    }
+
+    let runs: Vec<ScriptRun> = matched
+        .iter()
+        .map(|script| ScriptRun::new(script.clone(), cli.args.clone()))
+        .collect();
+    let runs = expand_groups(runs, scripts);
+    if cli.parallel && !dry_run {
+        return run_parallel(config, context, runner, project_dir, &runs);
+    }
+
+    let finished = run_sequentially(config, context, runner, project_dir, &runs, dry_run)?;
+    let results: Vec<ExecutionResult> = finished.into_iter().map(|(_, result)| result).collect();
+    Ok(script_exit_code(config, aggregate_exit_code(&results)))
+}
+
+/// Run project scripts one after another, stopping at the first failure.
+///
+/// Finished runs are recorded in history, unless it's a dry run.
+fn run_sequentially(
+    config: &Config,
+    context: &RunContext,
+    runner: Runner,
+    project_dir: &Path,
+    runs: &[ScriptRun],
+    dry_run: bool,
+) -> Result<Vec<(ScriptRun, ExecutionResult)>> {
+    let mut finished = Vec::with_capacity(runs.len());
+    for (i, run) in runs.iter().enumerate() {
+        let name = run.script.name();
+        if !dry_run {
+            println!(
+                "\n\x1b[1;36mRunning {}/{}: {name}...\x1b[0m",
+                i + 1,
+                runs.len()
+            );
+        }
+
+        let args: Vec<String> = run.args.as_deref().map(split_args).unwrap_or_default();
+        let heartbeat = start_heartbeat(name, config, dry_run);
+        let result = execute_script_with_limits(
+            run.script.runner_or(runner),
+            name,
+            &args,
+            project_dir,
+            dry_run,
+            &config.limits_for(name),
+            context,
+        )?;
+        finish_heartbeat(heartbeat, result.success());
+
+        let code = result.exit_code();
+        finished.push((run.clone(), result));
+        if code != 0 {
+            eprintln!("\n\x1b[1;31mScript '{name}' failed with exit code {code}\x1b[0m");
+            break;
+        }
+    }
+
+    if !dry_run {
+        record_runs(config, project_dir, &finished);
+    }
+    Ok(finished)
+}
+
+/// Run the members of a script group from `[groups]`, then print a summary
+/// of their exit codes.
+///
+/// Arguments go to every member. A sequential group stops at the first
+/// failed script; the exit code is that of the first failure.
+fn run_group(
+    config: &Config,
+    context: &RunContext,
+    runner: Runner,
+    project_dir: &Path,
+    scripts: &Scripts,
+    group_run: &ScriptRun,
+    dry_run: bool,
+) -> Result<i32> {
+    let runs = expand_groups(vec![group_run.clone()], scripts);
+    let names: Vec<&str> = runs.iter().map(|run| run.script.name()).collect();
+    if !confirm_run(config, runner, project_dir, &names, dry_run)? {
+        return Ok(exit_code::INTERRUPTED);
+    }
+
+    let parallel = group_run.script.group().is_some_and(|group| group.parallel);
+    let started = Instant::now();
+    let finished: Vec<(ScriptRun, ExecutionResult)> = if parallel && !dry_run {
+        let results = run_jobs_parallel(config, context, runner, project_dir, &runs)?;
+        let finished: Vec<_> = runs.iter().cloned().zip(results).collect();
+        record_runs(config, project_dir, &finished);
+        finished
+    } else {
+        run_sequentially(config, context, runner, project_dir, &runs, dry_run)?
+    };
+    if dry_run {
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let results: Vec<ExecutionResult> = finished.iter().map(|(_, result)| result.clone()).collect();
+    let code = aggregate_exit_code(&results);
+
+    // The group itself counts as run, for recent-first sorting
+    let mut history = History::load_with_config(&config.history).unwrap_or_default();
+    history.record_run(project_dir, group_run.script.name(), group_run.args.clone());
+    history.record_result(
+        project_dir,
+        group_run.script.name(),
+        code,
+        started.elapsed(),
+    );
+    let _ = history.save_with_config(&config.history);
+
+    let summary: Vec<RunResult> = finished
+        .iter()
+        .map(|(run, result)| RunResult {
+            name: run.script.name().to_string(),
+            exit_code: result.code(),
+            duration: result.duration,
+        })
+        .collect();
+    let heading = format!("Group \"{}\"", group_run.script.name());
+    print!("\n{}", format_run_summary(&heading, "Script", &summary));
+    let skipped = &names[finished.len()..];
+    if !skipped.is_empty() {
+        println!("Skipped {}", skipped.join(", "));
+    }
+    Ok(script_exit_code(config, code))
+}
+
+/// Replace runs of script groups with runs of their members.
+fn expand_groups(runs: Vec<ScriptRun>, scripts: &Scripts) -> Vec<ScriptRun> {
+    runs.into_iter()
+        .flat_map(|run| match run.script.group() {
+            Some(group) => group
+                .scripts
+                .iter()
+                .filter_map(|name| scripts.get(name))
+                .map(|script| ScriptRun::new(script.clone(), run.args.clone()))
+                .collect(),
+            None => vec![run],
+        })
+        .collect()
+}
+
+/// Run `nrs --all-workspaces <script>`.
+fn run_all_workspaces_command(
+    cli: &Cli,
+    config: &Config,
+    context: &RunContext,
+    runner: Runner,
+    project_dir: &Path,
+    script_name: &str,
+) -> Result<i32> {
+    let workspaces = detect_workspaces(
+        project_dir,
+        &config.exclude.workspaces.patterns,
+        &config.descriptions.sources,
+        config.general.workspace_resolution(),
+    )
+    .context("Failed to detect workspaces")?;
+    for workspace in all_workspaces(&workspaces) {
+        if let Some(error) = workspace.error() {
+            eprintln!("Warning: skipping {}: {error}", workspace.name());
+        }
+    }
+    let runs: Vec<ScriptRun> = all_workspaces(&workspaces)
+        .into_iter()
+        .filter_map(|workspace| {
+            let script = workspace
+                .scripts()
+                .iter()
+                .find(|s| s.name() == script_name)?;
+            Some(ScriptRun::in_workspace(
+                script.clone(),
+                cli.args.clone(),
+                workspace,
+            ))
+        })
+        .collect();
+
+    if runs.is_empty() {
+        if workspaces.is_empty() {
+            anyhow::bail!("No workspaces found in {}", project_dir.display());
+        }
+        anyhow::bail!("No workspace defines a script named \"{script_name}\"");
+    }
+    run_in_workspaces(
+        config,
+        context,
+        runner,
+        project_dir,
+        &runs,
+        cli.is_dry_run(),
+    )
+}
+
+/// Run one script in several workspaces in turn, then print a summary of
+/// their exit codes.
+///
+/// Every workspace runs even if an earlier one failed. The exit code is
+/// that of the first failed workspace.
+fn run_in_workspaces(
+    config: &Config,
+    context: &RunContext,
+    runner: Runner,
+    project_dir: &Path,
+    runs: &[ScriptRun],
+    dry_run: bool,
+) -> Result<i32> {
+    let Some(script_name) = runs.first().map(|run| run.script.name()) else {
+        return Ok(exit_code::SUCCESS);
+    };
+    if !confirm_run(config, runner, project_dir, &[script_name], dry_run)? {
+        return Ok(exit_code::INTERRUPTED);
+    }
+
+    let mut finished = Vec::with_capacity(runs.len());
+    for (i, run) in runs.iter().enumerate() {
+        let workspace = run.workspace.as_deref().unwrap_or_default();
+        if !dry_run {
+            println!(
+                "\n\x1b[1;36mRunning {}/{}: {workspace}...\x1b[0m",
+                i + 1,
+                runs.len()
+            );
+        }
+
+        let args: Vec<String> = run.args.as_deref().map(split_args).unwrap_or_default();
+        let heartbeat = start_heartbeat(&format!("{workspace}:{script_name}"), config, dry_run);
+        let result = execute_workspace_script_with_limits(
+            run.script.runner_or(runner),
+            workspace,
+            script_name,
+            &args,
+            run.run_dir(project_dir),
+            dry_run,
+            &config.limits_for(script_name),
+            context,
+        )?;
+        finish_heartbeat(heartbeat, result.success());
+        finished.push((run.clone(), result));
+    }
+
+    if dry_run {
+        return Ok(exit_code::SUCCESS);
+    }
+    record_runs(config, project_dir, &finished);
+
+    let summary: Vec<RunResult> = finished
+        .iter()
+        .map(|(run, result)| RunResult {
+            name: run.workspace.clone().unwrap_or_default(),
+            exit_code: result.code(),
+            duration: result.duration,
+        })
+        .collect();
+    print!("\n{}", format_workspace_summary(script_name, &summary));
+
+    let results: Vec<ExecutionResult> = finished.into_iter().map(|(_, result)| result).collect();
+    Ok(script_exit_code(config, aggregate_exit_code(&results)))
+}
+
+/// Print the command picked in `--popup` mode for the parent shell to run.
+fn print_popup_command(
+    config: &Config,
+    runner: Runner,
+    project_dir: &Path,
+    script_run: &ScriptRun,
+) -> Result<i32> {
+    let command = if script_run.script.group().is_some() {
+        // Groups only exist in nrs
+        let mut command = format!("nrs -n {}", shell_words::quote(script_run.script.name()));
+        if let Some(args) = &script_run.args {
+            command.push_str(&format!(" --args {}", shell_words::quote(args)));
+        }
+        command
+    } else {
+        format_shell_command(
+            script_run.script.runner_or(runner),
+            script_run.workspace.as_deref(),
+            script_run.script.name(),
+            script_run.args.as_deref(),
+        )
+    };
+
+    // The parent shell may sit in a subdirectory of the project
+    let run_dir = script_run.run_dir(project_dir);
+    let in_project = std::env::current_dir().is_ok_and(|cwd| cwd == run_dir);
+    if in_project {
+        println!("{command}");
+    } else {
+        println!(
+            "cd {} && {command}",
+            shell_words::quote(&run_dir.to_string_lossy())
+        );
+    }
+
+    // The parent shell runs it, so record it as run now
+    let mut history = History::load_with_config(&config.history).unwrap_or_default();
+    history.record_run(
+        script_run.workspace_path.as_deref().unwrap_or(project_dir),
+        script_run.script.name(),
+        script_run.args.clone(),
+    );
+    let _ = history.save_with_config(&config.history);
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run a script under each runner given to `--compare` and report timings.
+fn compare_runners(
+    cli: &Cli,
+    scripts: &Scripts,
+    script_name: &str,
+    project_dir: &Path,
+) -> Result<i32> {
+    if scripts.get(script_name).is_none() {
+        let script_names: Vec<&str> = scripts.iter().map(|s| s.name()).collect();
+        return Err(NrsError::script_not_found_with_suggestions(script_name, &script_names).into());
+    }
+
+    let args: Vec<String> = cli.args.as_deref().map(split_args).unwrap_or_default();
+    let runners: Vec<Runner> = cli.compare.iter().map(|&r| r.into()).collect();
+
+    if cli.is_dry_run() {
+        for runner in runners {
+            println!(
+                "Would run: {}",
+                runner.run_command_with_args(script_name, &args).join(" ")
+            );
+        }
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let mut timings = Vec::new();
+    for runner in runners {
+        println!(
+            "\n\x1b[1;36mRunning {} with {}...\x1b[0m",
+            script_name,
+            runner.executable()
+        );
+        timings.push(time_runner(runner, script_name, &args, project_dir)?);
+    }
+
+    println!();
+    print!("{}", format_comparison(script_name, &timings));
+
+    let all_ok = timings.iter().all(|t| t.success());
+    Ok(if all_ok {
+        exit_code::SUCCESS
+    } else {
+        exit_code::GENERAL_ERROR
+    })
+}
+
+/// Run `nrs history repair`.
+fn repair_history_command() -> Result<i32> {
+    let history_path = History::file_path().context("Could not determine state directory")?;
+    let backup_path = History::backup_path().context("Could not determine state directory")?;
+
+    if !backup_path.exists() {
+        println!("No history backup found at {}", backup_path.display());
+        println!("Nothing to repair.");
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let report = repair_history(&history_path, &backup_path)?;
+    println!(
+        "Recovered {} project(s) from {}",
+        report.recovered,
+        backup_path.display()
+    );
+    if report.lost > 0 {
+        println!(
+            "{} damaged project entries could not be recovered",
+            report.lost
+        );
+    }
+    println!("Merged into {}", history_path.display());
+    if let Some(archived) = report.archived_backup {
+        println!("Backup kept at {}", archived.display());
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Print past runs matching a query, across all projects.
+///
+/// Nothing is run, so the config is read without asking to trust it.
+fn search_history_command(cli: &Cli, query: &str, format: Option<CliReportFormat>) -> Result<i32> {
+    let config = if cli.no_config {
+        Config::default()
+    } else {
+        load_profile_config(cli, &cli.project_dir())?
+    };
+    let history = History::load_with_config(&config.history)?;
+    let matches = search_history(&history, query);
+    if let Some(format) = format {
+        print_report(&runs_report(&matches), format);
+        return Ok(if matches.is_empty() {
+            exit_code::GENERAL_ERROR
+        } else {
+            exit_code::SUCCESS
+        });
+    }
+    if matches.is_empty() {
+        println!("No runs in history match \"{query}\"");
+        return Ok(exit_code::GENERAL_ERROR);
+    }
+
+    let use_colors = io::stdout().is_terminal();
+    let now = clock::now();
+    let rows: Vec<(String, String, String)> = matches
+        .iter()
+        .map(|m| {
+            (
+                format_time_ago(m.last_run, now),
+                m.command(),
+                m.project.display().to_string(),
+            )
+        })
+        .collect();
+    let time_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+    let command_width = rows.iter().map(|r| r.1.chars().count()).max().unwrap_or(0);
+
+    for (time, command, project) in rows {
+        if use_colors {
+            println!(
+                "\x1b[2m{time:>time_width$}\x1b[0m  \x1b[1m{command:<command_width$}\x1b[0m  \x1b[2m{project}\x1b[0m"
+            );
+        } else {
+            println!("{time:>time_width$}  {command:<command_width$}  {project}");
+        }
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run `nrs doctor`, printing a line per check.
+///
+/// Exits with an error if any check failed.
+fn doctor_command(cli: &Cli, format: Option<CliReportFormat>) -> Result<i32> {
+    let project_dir = find_project_root(&cli.project_dir()).ok();
+    let checks = run_checks(project_dir.as_deref(), cli.config.as_deref());
+    let count = |status: Status| checks.iter().filter(|c| c.status == status).count();
+    let failed = count(Status::Fail);
+    let exit = if failed > 0 {
+        exit_code::GENERAL_ERROR
+    } else {
+        exit_code::SUCCESS
+    };
+    if let Some(format) = format {
+        print_report(&checks_report(&checks), format);
+        return Ok(exit);
+    }
+
+    let use_colors = io::stdout().is_terminal();
+    let name_width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in &checks {
+        let status = check.status.to_string();
+        if use_colors {
+            let color = match check.status {
+                Status::Pass => "\x1b[32m",
+                Status::Warn => "\x1b[33m",
+                Status::Fail => "\x1b[31m",
+            };
+            println!(
+                "{color}{status}\x1b[0m  \x1b[1m{:<name_width$}\x1b[0m  {}",
+                check.name, check.message
+            );
+        } else {
+            println!("{status}  {:<name_width$}  {}", check.name, check.message);
+        }
+        if let Some(fix) = &check.fix {
+            println!("      {:<name_width$}  fix: {fix}", "");
+        }
+    }
+
+    println!(
+        "\n{} passed, {} warning(s), {failed} failed",
+        count(Status::Pass),
+        count(Status::Warn)
+    );
+    Ok(exit)
+}
+
+/// Print a report in the format given to `--format`.
+fn print_report(report: &Report, format: CliReportFormat) {
+    print!("{}", report.render(format.into()));
+}
+
+/// List the projects in the history, pinned first.
+fn history_projects_command(format: Option<CliReportFormat>) -> Result<i32> {
+    let history = History::load()?;
+    let projects = history.recent_projects();
+    if let Some(format) = format {
+        print_report(&projects_report(&projects), format);
+        return Ok(exit_code::SUCCESS);
+    }
+    if projects.is_empty() {
+        println!("No projects in history yet");
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let use_colors = io::stdout().is_terminal();
+    let now = clock::now();
+    let rows: Vec<(String, String)> = projects
+        .iter()
+        .map(|p| (format_time_ago(p.last_run, now), format!("{} runs", p.runs)))
+        .collect();
+    let time_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+    let runs_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0);
+
+    for (project, (time, runs)) in projects.iter().zip(rows) {
+        let pin = if project.pinned { "★" } else { " " };
+        let path = project.path.display();
+        let missing = if project.missing { " (missing)" } else { "" };
+        if use_colors {
+            println!(
+                "\x1b[33m{pin}\x1b[0m \x1b[2m{time:>time_width$}  {runs:>runs_width$}\x1b[0m  \x1b[1m{path}\x1b[0m\x1b[31m{missing}\x1b[0m"
+            );
+        } else {
+            println!("{pin} {time:>time_width$}  {runs:>runs_width$}  {path}{missing}");
+        }
+    }
+
+    let prunable = history.missing_projects().len();
+    if prunable > 0 {
+        eprintln!("\n{prunable} missing, remove them with nrs history prune");
+    }
+    Ok(exit_code::SUCCESS)
+}
+
+/// Pin or unpin a project in the history.
+fn pin_project_command(path: Option<&Path>, pinned: bool) -> Result<i32> {
+    let dir = match path {
+        Some(path) => path.to_path_buf(),
+        None => std::env::current_dir().context("Failed to get current directory")?,
+    };
+    // A missing project can still be unpinned by the path it was stored under
+    let project_dir = find_project_root(&dir).unwrap_or(dir);
+
+    let mut history = History::load()?;
+    if !history.set_project_pinned(&project_dir, pinned) {
+        anyhow::bail!(
+            "{} is not in the history. Run a script there first.",
+            project_dir.display()
+        );
+    }
+    history.save()?;
+
+    let action = if pinned { "Pinned" } else { "Unpinned" };
+    println!("{action} {}", project_dir.display());
+    Ok(exit_code::SUCCESS)
+}
+
+/// Remove projects whose directory no longer exists from the history.
+fn prune_projects_command(dry_run: bool) -> Result<i32> {
+    let mut history = History::load()?;
+    let removed = if dry_run {
+        history.missing_projects()
+    } else {
+        history.prune_missing_projects()
+    };
+    if removed.is_empty() {
+        println!("No missing projects to remove");
+        return Ok(exit_code::SUCCESS);
+    }
+
+    for path in &removed {
+        println!("  {}", path.display());
+    }
+    if dry_run {
+        println!("Would remove {} missing project(s)", removed.len());
+    } else {
+        history.save()?;
+        println!("Removed {} missing project(s)", removed.len());
+    }
+    Ok(exit_code::SUCCESS)
+}
+
+/// Show how the command a rerun would use differs from the stored one.
+fn print_command_change(stored: &str, current: &str) {
+    if stored.split_whitespace().eq(current.split_whitespace()) {
+        eprintln!("Same command as the last run");
+        return;
+    }
+
+    let use_colors = io::stderr().is_terminal();
+    let (old, new) = diff_words(stored, current);
+    eprintln!("Command changed since the last run:");
+    print_diff_line("Last run:", &old, "\x1b[31m", use_colors);
+    print_diff_line("Now:", &new, "\x1b[32m", use_colors);
+}
+
+/// Print a command with its changed words colored, or marked with carets
+/// on the line below.
+fn print_diff_line(label: &str, words: &DiffWords, color: &str, use_colors: bool) {
+    let line = words
+        .iter()
+        .map(|(word, changed)| match (changed, use_colors) {
+            (true, true) => format!("{color}{word}\x1b[0m"),
+            _ => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    eprintln!("  {label:<9} {line}");
+
+    if !use_colors && words.iter().any(|(_, changed)| *changed) {
+        let marks = words
+            .iter()
+            .map(|(word, changed)| {
+                let mark = if *changed { "^" } else { " " };
+                mark.repeat(word.chars().count())
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        eprintln!("  {:<9} {}", "", marks.trim_end());
+    }
+}
+
+/// Print the most recent runs of a project, numbered, newest first.
+///
+/// With a `format`, they're printed as a report instead.
+fn print_recent_runs(
+    history: &History,
+    project_dir: &Path,
+    count: usize,
+    format: Option<CliListFormat>,
+) -> Result<i32> {
+    let runs = project_runs(history, project_dir);
+    if let Some(format) = format {
+        let report = runs_report(&runs[..runs.len().min(count)]);
+        print!("{}", report.render(format.into()));
+        return Ok(exit_code::SUCCESS);
+    }
+    if runs.is_empty() {
+        println!("No runs recorded for this project yet");
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let use_colors = io::stdout().is_terminal();
+    let now = clock::now();
+    let rows: Vec<(String, String, String)> = runs
+        .iter()
+        .take(count)
+        .map(|run| {
+            (
+                run.last_run
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                format_time_ago(run.last_run, now),
+                run.command(),
+            )
+        })
+        .collect();
+    let number_width = rows.len().to_string().len();
+    let ago_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0);
+
+    for (i, (time, ago, command)) in rows.into_iter().enumerate() {
+        let number = i + 1;
+        if use_colors {
+            println!(
+                "\x1b[2m{number:>number_width$}  {time}  {ago:<ago_width$}\x1b[0m  \x1b[1m{command}\x1b[0m"
+            );
+        } else {
+            println!("{number:>number_width$}  {time}  {ago:<ago_width$}  {command}");
+        }
+    }
+    eprintln!("Rerun one with nrs --last <script>");
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Record finished script runs in history and send their webhooks.
+fn record_runs(config: &Config, project_dir: &Path, runs: &[(ScriptRun, ExecutionResult)]) {
+    if runs.is_empty() {
+        return;
+    }
+
+    let mut history = History::load_with_config(&config.history).unwrap_or_default();
+    for (run, result) in runs {
+        notify_run(
+            &config.hooks,
+            &WebhookPayload::new(
+                project_dir,
+                run.workspace.as_deref(),
+                run.script.name(),
+                result,
+            ),
+        );
+        let history_dir = run.workspace_path.as_deref().unwrap_or(project_dir);
+        history.record_run(history_dir, run.script.name(), run.args.clone());
+        history.record_result(
+            history_dir,
+            run.script.name(),
+            result.exit_code(),
+            result.duration,
+        );
+        history.record_command(history_dir, run.script.name(), &result.command);
+    }
+    let _ = history.save_with_config(&config.history);
+}
+
+/// Record how a script run ended in history.
+///
+/// The run itself is recorded before the script starts.
+fn record_result(config: &Config, history_dir: &Path, script: &str, result: &ExecutionResult) {
+    let mut history = History::load_with_config(&config.history).unwrap_or_default();
+    history.record_result(history_dir, script, result.exit_code(), result.duration);
+    history.record_command(history_dir, script, &result.command);
+    let _ = history.save_with_config(&config.history);
+}
+
+/// Move or drop the history of scripts renamed or removed in the TUI.
+fn record_script_edits(config: &Config, edits: &[(PathBuf, ScriptEdit)]) {
+    if edits.is_empty() {
+        return;
+    }
+
+    let mut history = History::load_with_config(&config.history).unwrap_or_default();
+    for (dir, edit) in edits {
+        match edit {
+            ScriptEdit::Rename { from, to } => history.rename_script(dir, from, to),
+            ScriptEdit::Remove { name } => history.remove_script(dir, name),
+        }
+    }
+    let _ = history.save_with_config(&config.history);
+}
+
+/// Run `nrs completions install`.
+///
+/// Writes the completions to the file the shell loads them from, detecting
+/// the shell from `$SHELL` when it isn't given.
+fn install_completions_command(shell: Option<CliInitShell>, dry_run: bool) -> Result<i32> {
+    let shell = shell
+        .or_else(CliInitShell::from_env)
+        .context("Could not detect your shell from $SHELL, pass one of bash, zsh or fish")?;
+    let path = shell
+        .completions_file()
+        .context("Could not determine home directory")?;
+
+    if dry_run {
+        println!(
+            "Would write {} completions to {}",
+            shell.name(),
+            path.display()
+        );
+        return Ok(exit_code::SUCCESS);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    std::fs::write(&path, Cli::completions_script(shell))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!(
+        "Installed {} completions to {}",
+        shell.name(),
+        path.display()
+    );
+    match shell {
+        CliInitShell::Zsh => println!(
+            "Add `fpath=(~/.zfunc $fpath)` before `compinit` in ~/.zshrc if it isn't there yet"
+        ),
+        CliInitShell::Bash | CliInitShell::Fish => {
+            println!("Open a new shell to use them");
+        }
+    }
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run `nrs suggest`.
+///
+/// Lists the missing scripts, then adds them with `--write` or when the
+/// user agrees.
+fn suggest_command(project_dir: &Path, write: bool, read_only: bool) -> Result<i32> {
+    let suggestions = suggest_scripts(project_dir)?;
+    if suggestions.is_empty() {
+        println!("No scripts to suggest");
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let width = suggestions.iter().map(|s| s.name.len()).max().unwrap_or(0);
+    for suggestion in &suggestions {
+        println!(
+            "  {:width$}  {}  \x1b[2m({})\x1b[0m",
+            suggestion.name, suggestion.command, suggestion.dependency
+        );
+    }
+
+    let add = if read_only {
+        false
+    } else if write {
+        true
+    } else if io::stdin().is_terminal() {
+        ask_yes_no("\nAdd them to package.json?")?
+    } else {
+        false
+    };
+    if !add {
+        return Ok(exit_code::SUCCESS);
+    }
+
+    for suggestion in &suggestions {
+        add_script(project_dir, &suggestion.name, &suggestion.command)?;
+    }
+    println!(
+        "Added {} scripts to {}",
+        suggestions.len(),
+        project_dir.join("package.json").display()
+    );
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run `nrs stats`: print usage statistics of the project.
+fn stats_command(
+    config: &Config,
+    project_dir: &Path,
+    format: Option<CliReportFormat>,
+) -> Result<i32> {
+    const BAR_WIDTH: usize = 24;
+
+    let history = History::load_with_config(&config.history)?;
+    let stats = history
+        .get_project(project_dir)
+        .map(ProjectStats::from_history)
+        .unwrap_or_default();
+    if let Some(format) = format {
+        print_report(&stats.report(), format);
+        return Ok(exit_code::SUCCESS);
+    }
+    if stats.is_empty() {
+        println!("No runs recorded in {} yet", project_dir.display());
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let use_colors = io::stdout().is_terminal();
+    let dim = |text: String| {
+        if use_colors {
+            format!("\x1b[2m{text}\x1b[0m")
+        } else {
+            text
+        }
+    };
+
+    let summary = stats.summary();
+    let label_width = summary
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    for (label, value) in summary {
+        println!("{label:label_width$}  {value}");
+    }
+
+    let top = &stats.scripts[..stats.scripts.len().min(STATS_SCRIPTS)];
+    let max = top.first().map_or(0, |s| u64::from(s.runs));
+    let name_width = top
+        .iter()
+        .map(|s| s.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    println!("\nMost run");
+    for script in top {
+        let bar = format_bar(u64::from(script.runs), max, BAR_WIDTH);
+        println!(
+            "  {:name_width$}  {bar:BAR_WIDTH$}  {}",
+            script.name,
+            dim(script.describe())
+        );
+    }
+
+    if stats.busiest_day().is_some() {
+        let max = stats.weekday_runs.iter().copied().max().unwrap_or(0);
+        println!("\nRuns per day");
+        for (index, &runs) in stats.weekday_runs.iter().enumerate() {
+            let bar = format_bar(u64::from(runs), u64::from(max), BAR_WIDTH);
+            println!("  {}  {bar:BAR_WIDTH$}  {runs}", weekday(index));
+        }
+    }
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run `nrs matrix`: show which workspaces define which scripts.
+fn matrix_command(
+    config: &Config,
+    project_dir: &Path,
+    scripts: &[String],
+    format: Option<CliReportFormat>,
+) -> Result<i32> {
+    let workspaces = detect_workspaces(
+        project_dir,
+        &config.exclude.workspaces.patterns,
+        &config.descriptions.sources,
+        config.general.workspace_resolution(),
+    )
+    .context("Failed to detect workspaces")?;
+    let workspaces: Vec<&Workspace> = all_workspaces(&workspaces)
+        .into_iter()
+        .filter(|workspace| match workspace.error() {
+            Some(error) => {
+                eprintln!("Warning: skipping {}: {error}", workspace.name());
+                false
+            }
+            None => true,
+        })
+        .collect();
+    if workspaces.is_empty() {
+        eprintln!("No workspaces found in {}", project_dir.display());
+        return Ok(exit_code::GENERAL_ERROR);
+    }
+
+    let matrix = ScriptMatrix::new(&workspaces, scripts);
+    if let Some(format) = format {
+        print_report(&matrix.report(), format);
+        return Ok(exit_code::SUCCESS);
+    }
+    if matrix.scripts.is_empty() {
+        println!("No scripts shared between workspaces");
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let use_colors = io::stdout().is_terminal();
+    let paint = |text: &str, code: &str| {
+        if use_colors {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    };
+    let name_width = matrix
+        .rows
+        .iter()
+        .map(|row| row.workspace.chars().count())
+        .max()
+        .unwrap_or(0);
+    let widths: Vec<usize> = matrix.scripts.iter().map(|s| s.chars().count()).collect();
+
+    let header: Vec<String> = matrix.scripts.iter().map(|s| paint(s, "1")).collect();
+    println!("{:name_width$}  {}", "", header.join("  "));
+    for row in &matrix.rows {
+        let cells: Vec<String> = row
+            .defined
+            .iter()
+            .zip(&widths)
+            .map(|(&defined, &width)| {
+                // Centered under the script name
+                let pad = width.saturating_sub(1);
+                let (left, right) = (pad / 2, pad - pad / 2);
+                let mark = if defined {
+                    paint("✓", "32")
+                } else {
+                    paint("–", "2")
+                };
+                format!("{}{mark}{}", " ".repeat(left), " ".repeat(right))
+            })
+            .collect();
+        let line = format!("{:name_width$}  {}", row.workspace, cells.join("  "));
+        println!("{}", line.trim_end());
+    }
+
+    let total = matrix.rows.len();
+    let gaps: Vec<String> = matrix
+        .scripts
+        .iter()
+        .zip(matrix.missing())
+        .filter(|&(_, missing)| missing > 0)
+        .map(|(script, missing)| format!("{script} ({missing} of {total})"))
+        .collect();
+    if !gaps.is_empty() {
+        println!("\nMissing: {}", gaps.join(", "));
+    }
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run `nrs logs`: list the saved run logs, or print the end of the latest
+/// log of a script.
+fn logs_command(project_dir: &Path, tail: Option<&str>) -> Result<i32> {
+    const TAIL_LINES: usize = 100;
+
+    if let Some(script) = tail {
+        let Some(log) = logging::latest_log(project_dir, script)? else {
+            eprintln!("No logs of {script} yet. Run it with --log to save one.");
+            return Ok(exit_code::GENERAL_ERROR);
+        };
+        let content = std::fs::read_to_string(&log.path)
+            .with_context(|| format!("Failed to read {}", log.path.display()))?;
+        let lines: Vec<&str> = content.lines().collect();
+        let skipped = lines.len().saturating_sub(TAIL_LINES);
+        if skipped > 0 {
+            eprintln!("… {skipped} earlier line(s) in {}", log.path.display());
+        }
+        for line in &lines[skipped..] {
+            println!("{line}");
+        }
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let logs = logging::list_logs(project_dir)?;
+    if logs.is_empty() {
+        println!("No logs for this project yet. Run a script with --log to save one.");
+        return Ok(exit_code::SUCCESS);
+    }
+
+    let use_colors = io::stdout().is_terminal();
+    let now = clock::now();
+    let name_width = logs
+        .iter()
+        .map(|log| log.script.chars().count())
+        .max()
+        .unwrap_or(0);
+    for log in &logs {
+        let time = log
+            .started
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M");
+        let ago = format_time_ago(log.started, now);
+        let size = if log.size < 1024 {
+            format!("{} B", log.size)
+        } else {
+            format!("{} KB", log.size / 1024)
+        };
+        if use_colors {
+            println!(
+                "\x1b[2m{time}  {ago:<8}\x1b[0m  \x1b[1m{:<name_width$}\x1b[0m  {size:>8}",
+                log.script
+            );
+        } else {
+            println!("{time}  {ago:<8}  {:<name_width$}  {size:>8}", log.script);
+        }
+    }
+    if let Some(dir) = logs.first().and_then(|log| log.path.parent()) {
+        eprintln!("Logs in {}", dir.display());
+    }
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run `nrs --brief`: print the most used scripts on one line.
+///
+/// Runs on every `cd` through `nrs hook`, so the line comes from the brief
+/// cache while package.json and history are unchanged. Nothing is run, so
+/// the config is read without asking to trust it.
+fn brief_command(cli: &Cli, project_dir: &Path) -> Result<i32> {
+    let stamp = brief_stamp(project_dir);
+    let mut cache = BriefCache::load();
+    let line = match cache.get(project_dir, &stamp) {
+        Some(line) => line.to_string(),
+        None => {
+            let config = if cli.no_config {
+                Config::default()
+            } else {
+                load_profile_config(cli, project_dir)?
+            };
+            let scripts = parse_scripts(project_dir, &config.descriptions.sources)?
+                .without_lifecycle()
+                .without_matching(&config.exclude.patterns);
+            let history = History::load_with_config(&config.history).unwrap_or_default();
+            let sorted = history.get_sorted_by_recent(project_dir, scripts.as_slice());
+            let names: Vec<&str> = sorted
+                .iter()
+                .take(BRIEF_SCRIPTS)
+                .map(|script| script.name())
+                .collect();
+            let line = format_brief(&names, scripts.len());
+            cache.insert(project_dir, stamp, line.clone());
+            let _ = cache.save();
+            line
+        }
+    };
+
+    if io::stdout().is_terminal() {
+        println!("\x1b[2mnrs:\x1b[0m {line}");
+    } else {
+        println!("nrs: {line}");
+    }
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run `nrs diff <ref>`.
+fn diff_scripts_command(project_dir: &Path, reference: &str) -> Result<i32> {
+    let current =
+        parse_scripts(project_dir, &DescriptionSource::ALL).context("Failed to parse scripts")?;
+    let previous = scripts_at_ref(project_dir, reference)?;
+
+    let diff = diff_scripts(&previous, &current);
+    print!(
+        "{}",
+        format_scripts_diff(&diff, reference, io::stdout().is_terminal())
+    );
+    Ok(exit_code::SUCCESS)
+}
+
+/// Load the config files, with the profile picked by `--profile` or
+/// `NRS_PROFILE` applied.
+fn load_profile_config(cli: &Cli, project_dir: &Path) -> Result<Config> {
+    let mut config = npm_run_scripts::config::load_config(cli.config.as_deref(), project_dir)
+        .unwrap_or_default();
+    let profile = cli
+        .profile
+        .clone()
+        .or_else(|| std::env::var(PROFILE_ENV_VAR).ok())
+        .filter(|name| !name.is_empty());
+    if let Some(profile) = profile {
+        config.apply_profile(&profile)?;
+    }
+    Ok(config)
+}
+
+/// Pick the config file to open for editing.
+///
+/// Prefers an explicit `--config`, then an existing project or user config,
+/// and falls back to creating `.nrsrc.toml` in the project.
+fn editable_config_path(cli_config: Option<&Path>, project_dir: &Path) -> PathBuf {
+    if let Some(path) = cli_config {
+        return path.to_path_buf();
+    }
+    local_config_file(project_dir)
+        .or_else(|| global_config_file().filter(|p| p.exists()))
+        .unwrap_or_else(|| project_dir.join(".nrsrc.toml"))
+}
+
+/// Open a file in `$VISUAL`/`$EDITOR` and wait for the editor to exit.
+fn open_in_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+
+    // The editor value may carry flags, e.g. "code --wait"
+    let mut parts = split_args(&editor);
+    if parts.is_empty() {
+        parts.push(editor);
+    }
+
+    let status = new_command(&parts[0])
+        .args(&parts[1..])
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'", parts[0]))?;
+
+    if !status.success() {
+        eprintln!("Editor exited with {status}");
+    }
+    Ok(())
+}
+
+/// Run a script by name directly (non-TUI mode).
+#[allow(clippy::too_many_arguments)]
+fn run_script_by_name(
+    config: &Config,
+    context: &RunContext,
+    scripts: &Scripts,
+    runner: Runner,
+    script_name: &str,
+    args: Option<&str>,
+    project_dir: &std::path::Path,
+    dry_run: bool,
+) -> Result<i32> {
+    if scripts.get(script_name).is_none() {
+        // Get available script names for suggestions
+        let script_names: Vec<&str> = scripts.iter().map(|s| s.name()).collect();
+        let err = NrsError::script_not_found_with_suggestions(script_name, &script_names);
+        eprintln!("Error: {err}");
+        eprintln!();
+        eprintln!("Available scripts:");
+        for script in scripts.iter() {
+            eprintln!("  {}", script.name());
+        }
+        return Ok(exit_code::GENERAL_ERROR);
+    }
+
+    if let Some(group) = scripts.get(script_name).filter(|s| s.group().is_some()) {
+        let run = ScriptRun::new(group.clone(), args.map(String::from));
+        return run_group(config, context, runner, project_dir, scripts, &run, dry_run);
+    }
+
+    if let Some(parent) = scripts.get(script_name).and_then(Script::parent) {
+        // Run it as if nrs was started at the monorepo root
+        let root_scripts = parse_scripts(&parent.root, &config.descriptions.sources)
+            .context("Failed to parse scripts")?;
+        return run_script_by_name(
+            config,
+            context,
+            &root_scripts,
+            runner,
+            &parent.script,
+            args,
+            &parent.root,
+            dry_run,
+        );
+    }
+
+    if !confirm_run(config, runner, project_dir, &[script_name], dry_run)? {
+        return Ok(exit_code::INTERRUPTED);
+    }
+
+    let args_vec: Vec<String> = args.map(split_args).unwrap_or_default();
+
+    // Record in history
+    let mut history = History::load_with_config(&config.history).unwrap_or_default();
+    history.record_run(project_dir, script_name, args.map(String::from));
+    let _ = history.save_with_config(&config.history);
+
+    let heartbeat = start_heartbeat(script_name, config, dry_run);
+    let runner = scripts
+        .get(script_name)
+        .map_or(runner, |script| script.runner_or(runner));
+    let result = execute_script_with_limits(
+        runner,
+        script_name,
+        &args_vec,
+        project_dir,
+        dry_run,
+        &config.limits_for(script_name),
+        context,
+    )?;
+    finish_heartbeat(heartbeat, result.success());
+    if !dry_run {
+        record_result(config, project_dir, script_name, &result);
+        notify_run(
+            &config.hooks,
+            &WebhookPayload::new(project_dir, None, script_name, &result),
+        );
+    }
+
+    Ok(script_exit_code(config, result.exit_code()))
+}
+
+/// Read the project's scripts and apply the config to them: overrides,
+/// groups, aliases and the monorepo root scripts it lists.
+fn load_scripts(config: &Config, project_dir: &Path, limits: ParseLimits) -> Result<Scripts> {
+    let sources = &config.descriptions.sources;
+    let mut scripts = parse_scripts_with_limits(project_dir, limits, sources)
+        .context("Failed to parse scripts")?;
+    config.scripts.apply(&mut scripts);
+    config.apply_groups(&mut scripts);
+    config.scripts.aliases.apply(&mut scripts);
+
+    // Root scripts of the monorepo, when run inside one of its packages
+    for script in parent_scripts(project_dir, &config.parent.scripts, sources) {
+        if scripts.get(script.name()).is_none() {
+            scripts.add(script);
+        }
+    }
+    Ok(scripts)
+}
+
+/// Detect the workspaces the TUI offers in its workspace selector.
+fn tui_workspace_info(config: &Config, project_dir: &Path) -> WorkspaceInfo {
+    if !is_monorepo(project_dir) {
+        return WorkspaceInfo::default();
+    }
+    detect_workspace_info(
+        project_dir,
+        &config.descriptions.sources,
+        config.general.workspace_resolution(),
+    )
+    .unwrap_or_default()
+}
+
+/// Keep only the scripts pinned in `dir`, from the TUI or the config.
+fn only_pinned(scripts: &Scripts, config: &Config, dir: &Path) -> Result<Scripts> {
+    let favorites = Favorites::load().unwrap_or_default();
+    let pinned = scripts.filter_by(|script| {
+        favorites.is_pinned(dir, script.name()) || config.scripts.is_favorite(script.name())
+    });
+    if pinned.is_empty() {
+        anyhow::bail!("No pinned scripts in this project (press f in the TUI to pin one)");
+    }
+    Ok(pinned)
+}
+
+/// Ask to trust repository configs that use sensitive settings.
+///
+/// Trusted files are remembered until their contents change. Doesn't ask
+/// when stdin isn't a terminal; the settings are then ignored.
+fn confirm_trust(project_dir: &Path) -> Result<()> {
+    let untrusted = untrusted_configs(project_dir, &TrustStore::load());
+    if untrusted.is_empty() || !io::stdin().is_terminal() {
+        return Ok(());
+    }
+
+    for config in &untrusted {
+        eprintln!(
+            "{} sets {}",
+            config.path.display(),
+            config.settings.join(", ")
+        );
+    }
+    if ask_yes_no("Trust this config?")? {
+        trust_configs(&untrusted)?;
+    }
+    Ok(())
+}
+
+/// Ask before running scripts that match a `scripts.confirm` pattern, then
+/// offer to stop running scripts they share a `[concurrency]` group with and
+/// to install the project's dependencies if they're missing.
+///
+/// Doesn't ask for dry runs or when stdin isn't a terminal.
+fn confirm_run(
+    config: &Config,
+    runner: Runner,
+    project_dir: &Path,
+    script_names: &[&str],
+    dry_run: bool,
+) -> Result<bool> {
+    if dry_run {
+        return Ok(true);
+    }
+    let confirm: Vec<&str> = script_names
+        .iter()
+        .copied()
+        .filter(|name| config.scripts.needs_confirm(name))
+        .collect();
+    if !confirm.is_empty() {
+        if !io::stdin().is_terminal() {
+            anyhow::bail!(
+                "{} asks for confirmation. Pass --yes to run it without a terminal.",
+                confirm.join(", ")
+            );
+        }
+        if !ask_yes_no(&format!("Run {}?", confirm.join(", ")))? {
+            eprintln!("Cancelled");
+            return Ok(false);
+        }
+    }
+    if !stop_conflicting_jobs(config, project_dir, script_names)? {
+        return Ok(false);
+    }
+    offer_install(config, runner, project_dir)?;
+    Ok(true)
+}
+
+/// Offer to install a project's dependencies when `node_modules` is missing.
+///
+/// Without a terminal the missing install is only reported and the scripts
+/// run anyway.
+fn offer_install(config: &Config, runner: Runner, project_dir: &Path) -> Result<()> {
+    if !config.general.check_install() {
+        return Ok(());
+    }
+    let manager = install_runner(runner, project_dir);
+    let Some(command) = manager.install_command() else {
+        return Ok(());
+    };
+    if !needs_install(project_dir, manager) {
+        return Ok(());
+    }
+
+    let command = command.join(" ");
+    eprintln!(
+        "Dependencies aren't installed in {} (no node_modules)",
+        project_dir.display()
+    );
+    if !io::stdin().is_terminal() {
+        eprintln!("Warning: running anyway. Run {command} if the script fails");
+        return Ok(());
+    }
+    if ask_yes_no(&format!("Run {command} first?"))? {
+        install_dependencies(manager, project_dir, false)?;
+    }
+    Ok(())
+}
+
+/// Install a project's dependencies with `<runner> install`.
+fn install_dependencies(runner: Runner, project_dir: &Path, dry_run: bool) -> Result<()> {
+    let Some(command) = runner.install_command() else {
+        return Ok(());
+    };
+    let shown = command.join(" ");
+    if dry_run {
+        println!("Would run: {shown}");
+        return Ok(());
+    }
+    eprintln!("Running {shown}");
+    let status = new_command(&command[0])
+        .args(&command[1..])
+        .current_dir(project_dir)
+        .status()
+        .with_context(|| format!("Failed to run {shown}"))?;
+    if !status.success() {
+        anyhow::bail!(
+            "{shown} failed with exit code {}",
+            status.code().unwrap_or(exit_code::GENERAL_ERROR)
+        );
+    }
+    Ok(())
+}
+
+/// Offer to stop running scripts that can't run alongside the given ones.
+///
+/// Without a terminal the conflict is only reported and the scripts run
+/// anyway.
+fn stop_conflicting_jobs(
+    config: &Config,
+    project_dir: &Path,
+    script_names: &[&str],
+) -> Result<bool> {
+    if config.concurrency.groups.is_empty() {
+        return Ok(true);
+    }
+    let now = clock::now();
+    let conflicts: Vec<jobs::Job> = jobs::running_jobs(project_dir)
+        .into_iter()
+        .filter(|job| {
+            let conflict = script_names.iter().find_map(|name| {
+                config
+                    .concurrency
+                    .shared_group(&job.script, name)
+                    .map(|group| (*name, group))
+            });
+            if let Some((name, group)) = conflict {
+                eprintln!(
+                    "{} is already running (pid {}, started {}) and can't run alongside {name} (group {group})",
+                    job.script,
+                    job.pid,
+                    format_time_ago(job.started, now)
+                );
+            }
+            conflict.is_some()
+        })
+        .collect();
+    if conflicts.is_empty() {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        eprintln!("Warning: running anyway");
+        return Ok(true);
+    }
+
+    let question = if conflicts.len() == 1 {
+        "Stop it first?"
+    } else {
+        "Stop them first?"
+    };
+    if !ask_yes_no(question)? {
+        eprintln!("Cancelled");
+        return Ok(false);
+    }
+    for job in &conflicts {
+        jobs::stop_job(job)?;
+        eprintln!("Stopped {} (pid {})", job.script, job.pid);
+    }
+    Ok(true)
+}
+
+/// Ask a yes/no question on stderr, defaulting to no.
+fn ask_yes_no(question: &str) -> Result<bool> {
+    eprint!("{question} [y/N] ");
+    io::stderr().flush().ok();
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .context("Failed to read answer")?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Get the exit code to end with after scripts ended with `code`, saying
+/// so when `general.exit_code` changes it.
+fn script_exit_code(config: &Config, code: i32) -> i32 {
+    let exit = config.general.exit_code_for(code);
+    if exit != code {
+        eprintln!("Exit code {code}, exiting with {exit} (general.exit_code)");
+    }
+    exit
+}
+
+/// Start progress reporting for a script run, unless it's a dry run.
+fn start_heartbeat(script_name: &str, config: &Config, dry_run: bool) -> Option<Heartbeat> {
+    if dry_run {
+        return None;
+    }
+    Heartbeat::start(script_name, &config.progress)
+}
+
+/// Stop progress reporting for a finished run and send its completion
+/// notice.
+fn finish_heartbeat(heartbeat: Option<Heartbeat>, success: bool) {
+    if let Some(heartbeat) = heartbeat {
+        heartbeat.finish(success);
+    }
+}
+
+/// Resolve a workspace by name, relative path or short name.
+fn resolve_workspace(
+    config: &Config,
+    project_dir: &Path,
+    workspace_name: &str,
+) -> Result<Workspace> {
+    let workspaces = detect_workspaces(
+        project_dir,
+        &config.exclude.workspaces.patterns,
+        &config.descriptions.sources,
+        config.general.workspace_resolution(),
+    )
+    .context("Failed to detect workspaces")?;
+
+    find_workspace(&workspaces, project_dir, workspace_name)
+        .cloned()
+        .ok_or_else(|| {
+            NrsError::WorkspaceNotFound {
+                name: workspace_name.to_string(),
+                available: if workspaces.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    workspaces
+                        .iter()
+                        .map(|w| w.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            }
+            .into()
+        })
+}
+
+/// Get the scripts of a workspace, failing if its package.json couldn't be
+/// read.
+fn workspace_scripts(workspace: &Workspace) -> Result<&[Script]> {
+    if let Some(error) = workspace.error() {
+        anyhow::bail!("Can't read workspace {}: {error}", workspace.name());
+    }
+    Ok(workspace.scripts())
+}
+
+/// Run a script in a specific workspace by name (non-TUI mode).
+///
+/// The workspace can be given by package name, by its path relative to
+/// the project root, or by its short name. History is recorded against the workspace directory.
+#[allow(clippy::too_many_arguments)]
+fn run_workspace_script_by_name(
+    config: &Config,
+    context: &RunContext,
+    runner: Runner,
+    workspace_name: &str,
+    script_name: &str,
+    args: Option<&str>,
+    project_dir: &Path,
+    dry_run: bool,
+) -> Result<i32> {
+    let workspace = resolve_workspace(config, project_dir, workspace_name)?;
+    let scripts = workspace_scripts(&workspace)?;
+
+    let Some(script) = scripts.iter().find(|s| s.name() == script_name) else {
+        let script_names: Vec<&str> = scripts.iter().map(|s| s.name()).collect();
+        return Err(NrsError::script_not_found_with_suggestions(script_name, &script_names).into());
+    };
+    let runner = script.runner_or(runner);
+
+    if !confirm_run(config, runner, project_dir, &[script_name], dry_run)? {
+        return Ok(exit_code::INTERRUPTED);
+    }
+
+    let args_vec: Vec<String> = args.map(split_args).unwrap_or_default();
+
+    // Record in history
+    let mut history = History::load_with_config(&config.history).unwrap_or_default();
+    history.record_run(workspace.path(), script_name, args.map(String::from));
+    let _ = history.save_with_config(&config.history);
+
+    let heartbeat = start_heartbeat(script_name, config, dry_run);
+    let result = execute_workspace_script_with_limits(
+        runner,
+        workspace.name(),
+        script_name,
+        &args_vec,
+        workspace.root().unwrap_or(project_dir),
+        dry_run,
+        &config.limits_for(script_name),
+        context,
+    )?;
+    finish_heartbeat(heartbeat, result.success());
+    if !dry_run {
+        record_result(config, workspace.path(), script_name, &result);
+        notify_run(
+            &config.hooks,
+            &WebhookPayload::new(project_dir, Some(workspace.name()), script_name, &result),
+        );
+    }
+
+    Ok(script_exit_code(config, result.exit_code()))
+}
+
+/// What `--list` shows.
+struct ScriptListing<'a> {
+    scripts: &'a Scripts,
+    runner: Runner,
+    /// Workspace the scripts belong to, if not the project root.
+    workspace: Option<&'a str>,
+    columns: &'a [CliListColumn],
+    /// Wrap long values instead of truncating them (table format).
+    long: bool,
+}
+
+/// List scripts (non-TUI mode).
+///
+/// `stats` looks up the run history of a script, for the `lastrun`,
+/// `duration` and `status` columns and JSON output.
+fn list_scripts(
+    listing: &ScriptListing,
+    format: CliListFormat,
+    stats: impl Fn(&str) -> Option<ScriptHistory>,
+) -> Result<i32> {
+    match format {
+        CliListFormat::Table => print_scripts_table(listing, stats),
+        CliListFormat::Plain => {
+            let now = clock::now();
+            for script in listing.scripts.iter() {
+                // Missing values stay as empty fields so columns line up
+                let cells: Vec<String> = list_cells(script, listing.columns, &stats, now)
+                    .into_iter()
+                    .map(|(_, value)| value.unwrap_or_default())
+                    .collect();
+                println!("{}", cells.join("\t"));
+            }
+        }
+        CliListFormat::Json => {
+            let output = JsonListing {
+                runner: listing.runner.to_string(),
+                workspace: listing.workspace,
+                scripts: listing
+                    .scripts
+                    .iter()
+                    .map(|script| JsonScript {
+                        name: script.name(),
+                        command: script.command(),
+                        description: script.description(),
+                        category: script.category(),
+                        runner: script.runner_or(listing.runner).to_string(),
+                        workspace: listing.workspace,
+                        history: stats(script.name()),
+                    })
+                    .collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        CliListFormat::Csv | CliListFormat::Markdown => {
+            let now = clock::now();
+            let mut report = Report::new(listing.columns.iter().map(|column| column.name()));
+            for script in listing.scripts.iter() {
+                report.push(
+                    list_cells(script, listing.columns, &stats, now)
+                        .into_iter()
+                        .map(|(_, value)| value),
+                );
+            }
+            print!("{}", report.render(format.into()));
+        }
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// `--list --format json` output.
+#[derive(Serialize)]
+struct JsonListing<'a> {
+    runner: String,
+    workspace: Option<&'a str>,
+    scripts: Vec<JsonScript<'a>>,
+}
+
+/// A script in `--list --format json` output.
+#[derive(Serialize)]
+struct JsonScript<'a> {
+    name: &'a str,
+    command: &'a str,
+    description: Option<&'a str>,
+    category: &'a str,
+    runner: String,
+    workspace: Option<&'a str>,
+    history: Option<ScriptHistory>,
+}
+
+/// Get the values of the `--list` columns for a script.
+///
+/// Columns without a value for this script (e.g. no description) are `None`.
+fn list_cells(
+    script: &Script,
+    columns: &[CliListColumn],
+    stats: impl Fn(&str) -> Option<ScriptHistory>,
+    now: DateTime<Utc>,
+) -> Vec<(CliListColumn, Option<String>)> {
+    columns
+        .iter()
+        .map(|&column| {
+            let value = match column {
+                CliListColumn::Name => Some(script.name().to_string()),
+                CliListColumn::Command => Some(script.command().to_string()),
+                CliListColumn::Desc => script.description().map(String::from),
+                CliListColumn::Lastrun => Some(stats(script.name()).map_or_else(
+                    || "never".to_string(),
+                    |stats| format_time_ago(stats.last_run, now),
+                )),
+                CliListColumn::Duration => stats(script.name()).and_then(|stats| {
+                    let last = format_elapsed(stats.last_duration()?);
+                    Some(match stats.average_duration() {
+                        Some(avg) if stats.timed_runs > 1 => {
+                            format!("{last} (avg {})", format_elapsed(avg))
+                        }
+                        _ => last,
+                    })
+                }),
+                CliListColumn::Status => stats(script.name())
+                    .and_then(|stats| stats.last_exit_code)
+                    .map(|code| match code {
+                        0 => "ok".to_string(),
+                        code => format!("failed ({code})"),
+                    }),
+            };
+            (column, value)
+        })
+        .collect()
+}
+
+/// Print scripts as aligned columns with a header and count.
+fn print_scripts_table(listing: &ScriptListing, stats: impl Fn(&str) -> Option<ScriptHistory>) {
+    let scripts = listing.scripts;
+    let use_colors = io::stdout().is_terminal();
+
+    // Print header
+    let header = match listing.workspace {
+        Some(workspace) => format!("Available scripts in {} ({}):", workspace, listing.runner),
+        None => format!("Available scripts ({}):", listing.runner),
+    };
+    if use_colors {
+        println!("\x1b[1;36m{}\x1b[0m", header);
+    } else {
+        println!("{}", header);
+    }
+    println!();
+
+    // Find the longest script name for alignment
+    let max_name_len = scripts
+        .iter()
+        .map(|s| s.name().len())
+        .max()
+        .unwrap_or(0)
+        .min(30);
+
+    // Long mode wraps to the terminal, but never when piped
+    let terminal_width = check_terminal_size()
+        .filter(|_| use_colors)
+        .map(|size| size.width as usize);
+    let now = clock::now();
+
+    // Print each script
+    for script in scripts.iter() {
+        let cells: Vec<(CliListColumn, String)> = list_cells(script, listing.columns, &stats, now)
+            .into_iter()
+            .filter_map(|(column, value)| value.map(|value| (column, value)))
+            .collect();
+
+        if listing.long {
+            print_long_row(&cells, max_name_len, terminal_width, use_colors);
+        } else {
+            for (column, value) in &cells {
+                let value = match column {
+                    CliListColumn::Name => format!("{:width$}", value, width = max_name_len),
+                    CliListColumn::Command => truncate_string(value, 50),
+                    CliListColumn::Desc => truncate_string(value, 40),
+                    CliListColumn::Lastrun | CliListColumn::Duration | CliListColumn::Status => {
+                        value.clone()
+                    }
+                };
+                print!("  {}", paint_column(*column, &value, use_colors));
+            }
+            println!();
+        }
+    }
+
+    // Print count
+    println!();
+    if use_colors {
+        println!("\x1b[2m{} scripts found\x1b[0m", scripts.len());
+    } else {
+        println!("{} scripts found", scripts.len());
+    }
+}
+
+/// Print the scripts a script runs as a tree (non-TUI mode).
+fn print_script_graph(scripts: &Scripts, name: &str) -> Result<i32> {
+    if scripts.get(name).is_none() {
+        let script_names: Vec<&str> = scripts.iter().map(|s| s.name()).collect();
+        return Err(NrsError::script_not_found_with_suggestions(name, &script_names).into());
+    }
+
+    let use_colors = io::stdout().is_terminal();
+    let lines = ScriptGraph::from_scripts(scripts).tree(name);
+    for line in &lines {
+        match line.annotation() {
+            _ if !use_colors => println!("{line}"),
+            Some(annotation) => println!(
+                "\x1b[2m{}\x1b[0m{} \x1b[2m{annotation}\x1b[0m",
+                line.prefix, line.name
+            ),
+            None => println!("\x1b[1;32m{}\x1b[0m", line.name),
+        }
+    }
+
+    if lines.len() == 1 {
+        println!();
+        println!("{name} doesn't run any other scripts");
+    }
+    Ok(exit_code::SUCCESS)
+}
+
+/// Print script counts per category (non-TUI mode).
+fn summarize_scripts(scripts: &Scripts, runner: Runner) -> Result<i32> {
+    let use_colors = io::stdout().is_terminal();
+    let categories = scripts.categories();
+
+    if use_colors {
+        println!("\x1b[1;36mScript categories ({}):\x1b[0m", runner);
+    } else {
+        println!("Script categories ({}):", runner);
+    }
+    println!();
+
+    let max_name_len = categories
+        .iter()
+        .map(|(category, _)| category.len())
+        .max()
+        .unwrap_or(0)
+        .min(30);
+
+    for (category, count) in &categories {
+        if use_colors {
+            println!(
+                "  \x1b[1;32m{:width$}\x1b[0m  {:>3}",
+                category,
+                count,
+                width = max_name_len
+            );
+        } else {
+            println!("  {:width$}  {:>3}", category, count, width = max_name_len);
+        }
+    }
+
+    println!();
+    let totals = format!(
+        "{} scripts in {} categories, see one with --list --category <NAME>",
+        scripts.len(),
+        categories.len()
+    );
+    if use_colors {
+        println!("\x1b[2m{totals}\x1b[0m");
+    } else {
+        println!("{totals}");
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Print one script of the `--long` listing.
+///
+/// The name stays on the left and every other column starts on its own
+/// line below the previous one, wrapped with a hanging indent.
+fn print_long_row(
+    cells: &[(CliListColumn, String)],
+    name_width: usize,
+    terminal_width: Option<usize>,
+    use_colors: bool,
+) {
+    let name = cells
+        .iter()
+        .find(|(column, _)| *column == CliListColumn::Name)
+        .map(|(_, name)| name);
+    let indent = match name {
+        Some(_) => 2 + name_width + 2,
+        None => 2,
+    };
+    let wrap_width = terminal_width.map_or(0, |width| width.saturating_sub(indent).max(20));
+
+    let lines: Vec<(CliListColumn, String)> = cells
+        .iter()
+        .filter(|(column, _)| *column != CliListColumn::Name)
+        .flat_map(|(column, value)| {
+            wrap_text(value, wrap_width)
+                .into_iter()
+                .map(move |line| (*column, line))
+        })
+        .collect();
+
+    match name {
+        Some(name) => print!(
+            "  {}",
+            paint_column(
+                CliListColumn::Name,
+                &format!("{:width$}", name, width = name_width),
+                use_colors
+            )
+        ),
+        None => print!("  "),
+    }
+
+    for (i, (column, line)) in lines.iter().enumerate() {
+        if i == 0 {
+            if name.is_some() {
+                print!("  ");
+            }
+        } else {
+            print!("\n{:indent$}", "", indent = indent);
+        }
+        print!("{}", paint_column(*column, line, use_colors));
+    }
+    println!();
+}
+
+/// Color a `--list` cell by its column.
+fn paint_column(column: CliListColumn, value: &str, use_colors: bool) -> String {
+    if !use_colors {
+        return value.to_string();
+    }
+    let style = match column {
+        CliListColumn::Name => "1;32",
+        CliListColumn::Command => "2",
+        CliListColumn::Desc => "33",
+        CliListColumn::Lastrun | CliListColumn::Duration => "36",
+        CliListColumn::Status => "35",
+    };
+    format!("\x1b[{style}m{value}\x1b[0m")
+}
+
+/// Truncate a string to a maximum length, adding ellipsis if needed.
+/// Handles Unicode characters properly.
+fn truncate_string(s: &str, max_len: usize) -> String {
+    if max_len < 4 {
+        return s.chars().take(max_len).collect();
+    }
+
+    let char_count = s.chars().count();
+    if char_count <= max_len {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_len - 3).collect();
+        format!("{}...", truncated)
+    }
+}
+
+// ==================== Debug Functions ====================
+
+/// Set up log output from `-v`, `--debug`, `--log-file` and `NRS_LOG`.
+fn init_diagnostics(cli: &Cli) {
+    let verbosity = if cli.debug {
+        cli.verbose.max(2)
+    } else {
+        cli.verbose
+    };
+    let logging_requested = verbosity > 0 || std::env::var_os(LOG_ENV_VAR).is_some();
+
+    // Logging to stderr would scribble over the TUI
+    let log_file = cli
+        .log_file
+        .clone()
+        .or_else(|| (logging_requested && cli.should_show_tui()).then(default_log_file));
+    if cli.log_file.is_none() {
+        if let Some(path) = &log_file {
+            eprintln!("Logging to {}", path.display());
+        }
+    }
+
+    if let Err(err) = init_logging(verbosity, log_file.as_deref()) {
+        eprintln!("Warning: {err:#}");
+    }
+}
 
 /// Print debug header with version info.
 fn print_debug_header() {
@@ -0,0 +1,280 @@
+//! Output formats of the reporting commands.
+//!
+//! `nrs stats`, `nrs history`, `nrs matrix`, `nrs doctor` and `nrs --history`
+//! build a [`Report`] of rows and print it with `--format`, as aligned
+//! columns, JSON, CSV or a Markdown table. Cells are JSON values, so JSON output keeps
+//! numbers and booleans while the text formats print them as they read.
+
+use serde_json::{Map, Value};
+
+/// Format a report is printed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned columns with a header.
+    #[default]
+    Table,
+    /// A JSON array with an object per row, keyed by column.
+    Json,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// A Markdown (GitHub) table.
+    Markdown,
+    /// Tab-separated values, without header.
+    Plain,
+}
+
+/// Rows of a report, under named columns.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Report {
+    /// Create an empty report with the given columns.
+    ///
+    /// Column names are the keys of the JSON output, so they're best kept
+    /// in snake_case.
+    pub fn new<S: Into<String>>(columns: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            columns: columns.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row, with a cell per column.
+    ///
+    /// Missing cells are empty (`null` in JSON) and extra cells are dropped.
+    pub fn push<V: Into<Value>>(&mut self, cells: impl IntoIterator<Item = V>) {
+        let mut row: Vec<Value> = cells.into_iter().map(Into::into).collect();
+        row.resize(self.columns.len(), Value::Null);
+        self.rows.push(row);
+    }
+
+    /// Get the column names.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Get the rows.
+    pub fn rows(&self) -> &[Vec<Value>] {
+        &self.rows
+    }
+
+    /// Check if the report has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Render the report, ending with a newline.
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Table => self.to_table(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Csv => self.to_csv(),
+            OutputFormat::Markdown => self.to_markdown(),
+            OutputFormat::Plain => self.to_plain(),
+        }
+    }
+
+    /// Get the rows as text, a string per cell.
+    fn text_rows(&self) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|row| row.iter().map(cell_text).collect())
+            .collect()
+    }
+
+    fn to_table(&self) -> String {
+        let rows = self.text_rows();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                rows.iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([column.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{cell:width$}"))
+                .collect();
+            format!("{}\n", padded.join("  ").trim_end())
+        };
+        let mut out = line(&self.columns);
+        for row in &rows {
+            out.push_str(&line(row));
+        }
+        out
+    }
+
+    fn to_json(&self) -> String {
+        let rows: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = self
+                    .columns
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().cloned())
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+        let json = serde_json::to_string_pretty(&rows).unwrap_or_else(|_| "[]".to_string());
+        format!("{json}\n")
+    }
+
+    fn to_csv(&self) -> String {
+        let line = |cells: &[String]| {
+            let quoted: Vec<String> = cells.iter().map(|cell| csv_field(cell)).collect();
+            format!("{}\n", quoted.join(","))
+        };
+        let mut out = line(&self.columns);
+        for row in self.text_rows() {
+            out.push_str(&line(&row));
+        }
+        out
+    }
+
+    fn to_markdown(&self) -> String {
+        let line = |cells: &[String]| {
+            let escaped: Vec<String> = cells.iter().map(|cell| markdown_cell(cell)).collect();
+            format!("| {} |\n", escaped.join(" | "))
+        };
+        let mut out = line(&self.columns);
+        out.push_str(&format!("|{}\n", "---|".repeat(self.columns.len())));
+        for row in self.text_rows() {
+            out.push_str(&line(&row));
+        }
+        out
+    }
+
+    fn to_plain(&self) -> String {
+        self.text_rows()
+            .iter()
+            .map(|row| format!("{}\n", row.join("\t")))
+            .collect()
+    }
+}
+
+/// Get a cell as text: strings without quotes and `null` as nothing.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Quote a CSV field if it holds a comma, quote or line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Escape pipes in a Markdown table cell and keep it on one line.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report() -> Report {
+        let mut report = Report::new(["script", "runs", "note"]);
+        report.push([json!("build"), json!(12), json!("fast, cached")]);
+        report.push([json!("test:e2e"), json!(3), Value::Null]);
+        report
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(
+            report().render(OutputFormat::Table),
+            "script    runs  note\n\
+             build     12    fast, cached\n\
+             test:e2e  3\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json: Value = serde_json::from_str(&report().render(OutputFormat::Json)).unwrap();
+        assert_eq!(
+            json,
+            json!([
+                { "script": "build", "runs": 12, "note": "fast, cached" },
+                { "script": "test:e2e", "runs": 3, "note": null },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut report = report();
+        report.push([json!("say"), json!(1), json!("echo \"hi\"")]);
+        assert_eq!(
+            report.render(OutputFormat::Csv),
+            "script,runs,note\n\
+             build,12,\"fast, cached\"\n\
+             test:e2e,3,\n\
+             say,1,\"echo \"\"hi\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let mut report = report();
+        report.push([json!("lint"), json!(true), json!("a | b")]);
+        assert_eq!(
+            report.render(OutputFormat::Markdown),
+            "| script | runs | note |\n\
+             |---|---|---|\n\
+             | build | 12 | fast, cached |\n\
+             | test:e2e | 3 |  |\n\
+             | lint | true | a \\| b |\n"
+        );
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(
+            report().render(OutputFormat::Plain),
+            "build\t12\tfast, cached\n\
+             test:e2e\t3\t\n"
+        );
+    }
+
+    #[test]
+    fn test_push_pads_and_truncates_rows() {
+        let mut report = Report::new(["a", "b"]);
+        report.push([json!(1)]);
+        report.push([json!(1), json!(2), json!(3)]);
+        assert_eq!(
+            report.rows(),
+            [[json!(1), Value::Null], [json!(1), json!(2)]]
+        );
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = Report::new(["a"]);
+        assert!(report.is_empty());
+        assert_eq!(report.render(OutputFormat::Json), "[]\n");
+        assert_eq!(report.render(OutputFormat::Csv), "a\n");
+    }
+}
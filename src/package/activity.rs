@@ -0,0 +1,128 @@
+//! Scripts related to uncommitted git changes.
+//!
+//! An experimental sort signal: scripts whose name shares a word with the
+//! directories of changed files rank higher, so touching `e2e/` brings
+//! `test:e2e` up.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tracing::debug;
+
+use super::types::Script;
+
+/// Score added to scripts related to changed files in recent sorting.
+///
+/// History scores range from 0 to 1, so this puts a related script that was
+/// never run above scripts last run about two weeks ago.
+pub const GIT_ACTIVITY_BOOST: f64 = 0.5;
+
+/// Words of the paths changed in a git working copy.
+#[derive(Debug, Clone, Default)]
+pub struct GitActivity {
+    keywords: HashSet<String>,
+}
+
+impl GitActivity {
+    /// Scan the changed and untracked files under a directory.
+    ///
+    /// Outside a git repository, or without git, nothing is changed.
+    pub fn scan(dir: &Path) -> Self {
+        Self::from_paths(&changed_paths(dir))
+    }
+
+    /// Collect the words of changed paths: their directories and the file
+    /// names up to the first dot.
+    pub fn from_paths(paths: &[PathBuf]) -> Self {
+        let keywords = paths
+            .iter()
+            .flat_map(|path| {
+                let dirs = path
+                    .parent()
+                    .into_iter()
+                    .flat_map(Path::components)
+                    .filter_map(|c| c.as_os_str().to_str());
+                let stem = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.split('.').next());
+                dirs.chain(stem)
+            })
+            .filter(|word| word.len() > 1)
+            .map(str::to_lowercase)
+            .collect();
+        Self { keywords }
+    }
+
+    /// Check if no changed paths were found.
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+
+    /// Check if a script's name or category matches a changed path.
+    pub fn matches(&self, script: &Script) -> bool {
+        script
+            .name()
+            .split([':', '-', '_', '/'])
+            .chain(std::iter::once(script.category()))
+            .any(|word| self.keywords.contains(&word.to_lowercase()))
+    }
+}
+
+/// List the changed and untracked files under a directory, relative to it.
+fn changed_paths(dir: &Path) -> Vec<PathBuf> {
+    let git = |args: &[&str]| -> Vec<PathBuf> {
+        match Command::new("git").args(args).current_dir(dir).output() {
+            Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(PathBuf::from)
+                .collect(),
+            Ok(output) => {
+                debug!(
+                    "git {} failed: {}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                Vec::new()
+            }
+            Err(err) => {
+                debug!("Failed to run git: {err}");
+                Vec::new()
+            }
+        }
+    };
+
+    let mut paths = git(&["diff", "--name-only", "--relative", "HEAD"]);
+    paths.extend(git(&["ls-files", "--others", "--exclude-standard"]));
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_activity_matches() {
+        let activity = GitActivity::from_paths(&[
+            PathBuf::from("e2e/login.spec.ts"),
+            PathBuf::from("docs/guide/README.md"),
+        ]);
+
+        assert!(activity.matches(&Script::new("test:e2e", "playwright test")));
+        assert!(activity.matches(&Script::new("docs:build", "vitepress build")));
+        assert!(activity.matches(&Script::new("guide", "open docs")));
+        assert!(activity.matches(&Script::new("readme", "doctoc README.md")));
+        assert!(!activity.matches(&Script::new("test:unit", "vitest")));
+        assert!(!activity.matches(&Script::new("ts", "tsc")));
+
+        assert!(GitActivity::from_paths(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_scan_outside_git() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let activity = GitActivity::scan(temp.path());
+        assert!(activity.is_empty());
+    }
+}
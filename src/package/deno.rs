@@ -0,0 +1,219 @@
+//! Deno task parsing.
+//!
+//! Reads the `tasks` of a `deno.json` or `deno.jsonc` next to package.json,
+//! so projects mixing Deno and Node tooling show both sets of scripts.
+
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use super::manager::Runner;
+use super::types::{Script, Scripts};
+
+/// Deno config file names, in lookup order.
+pub const DENO_CONFIG_FILES: [&str; 2] = ["deno.json", "deno.jsonc"];
+
+/// Find the Deno config file in a directory.
+pub fn find_deno_config(dir: &Path) -> Option<PathBuf> {
+    DENO_CONFIG_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Parse the Deno tasks in a directory, if it has a Deno config.
+///
+/// # Errors
+///
+/// Returns an error if the config file can't be read or parsed.
+pub fn parse_deno_tasks(dir: &Path) -> Result<Option<Scripts>> {
+    let Some(path) = find_deno_config(dir) else {
+        return Ok(None);
+    };
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_deno_tasks_from_json(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+        .map(Some)
+}
+
+/// Parse the tasks from deno.json or deno.jsonc content.
+///
+/// Tasks are either a command string or an object with a `command` and an
+/// optional `description`. Tasks without a command (dependency-only tasks)
+/// are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the content isn't valid JSON with comments.
+pub fn parse_deno_tasks_from_json(content: &str) -> Result<Scripts> {
+    let json: Value = serde_json::from_str(&strip_jsonc(content))?;
+
+    let mut scripts = Scripts::new();
+    if let Some(tasks) = json.get("tasks").and_then(Value::as_object) {
+        for (name, task) in tasks {
+            let script = match task {
+                Value::String(command) => Script::new(name, command),
+                Value::Object(task) => {
+                    let Some(command) = task.get("command").and_then(Value::as_str) else {
+                        continue;
+                    };
+                    match task.get("description").and_then(Value::as_str) {
+                        Some(description) => Script::with_description(name, command, description),
+                        None => Script::new(name, command),
+                    }
+                }
+                _ => continue,
+            };
+            scripts.add(script.with_runner(Runner::Deno));
+        }
+    }
+
+    scripts.sort_alphabetically();
+    Ok(scripts)
+}
+
+/// Turn JSON with comments into plain JSON.
+///
+/// Removes `//` and `/* */` comments and trailing commas, leaving strings
+/// untouched.
+pub(super) fn strip_jsonc(content: &str) -> String {
+    remove_trailing_commas(&strip_comments(content))
+}
+
+/// Remove comments outside of strings.
+fn strip_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                copy_string(&mut chars, &mut out);
+            }
+            '/' if chars.next_if_eq(&'/').is_some() => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push(c);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Remove commas directly followed by a closing bracket.
+fn remove_trailing_commas(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                copy_string(&mut chars, &mut out);
+            }
+            ',' => {
+                let rest = chars.clone().find(|c| !c.is_whitespace());
+                if !matches!(rest, Some('}' | ']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Copy the rest of a string literal, up to and including its closing quote.
+fn copy_string(chars: &mut Peekable<Chars<'_>>, out: &mut String) {
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match c {
+            '\\' => out.extend(chars.next()),
+            '"' => break,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deno_tasks() {
+        let json = r#"{
+            "tasks": {
+                "dev": "deno run --watch main.ts",
+                "check": {
+                    "command": "deno check main.ts",
+                    "description": "Type-check the entry point"
+                },
+                "ci": { "dependencies": ["check"] }
+            }
+        }"#;
+
+        let scripts = parse_deno_tasks_from_json(json).unwrap();
+        assert_eq!(scripts.len(), 2);
+        let dev = scripts.get("dev").unwrap();
+        assert_eq!(dev.command(), "deno run --watch main.ts");
+        assert_eq!(dev.runner(), Some(Runner::Deno));
+        assert_eq!(
+            scripts.get("check").unwrap().description(),
+            Some("Type-check the entry point")
+        );
+    }
+
+    #[test]
+    fn test_parse_deno_jsonc() {
+        let jsonc = r#"{
+            // Local tasks
+            "tasks": {
+                /* served on :8000 */
+                "serve": "deno serve https://example.com/main.ts", // not a comment
+            },
+        }"#;
+
+        let scripts = parse_deno_tasks_from_json(jsonc).unwrap();
+        assert_eq!(
+            scripts.get("serve").unwrap().command(),
+            "deno serve https://example.com/main.ts"
+        );
+    }
+
+    #[test]
+    fn test_parse_deno_without_tasks() {
+        assert!(parse_deno_tasks_from_json(r#"{"imports": {}}"#)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_find_deno_config() {
+        let temp = tempfile::tempdir().unwrap();
+        assert!(find_deno_config(temp.path()).is_none());
+
+        std::fs::write(temp.path().join("deno.jsonc"), "{}").unwrap();
+        assert_eq!(
+            find_deno_config(temp.path()),
+            Some(temp.path().join("deno.jsonc"))
+        );
+    }
+}
@@ -1,17 +1,63 @@
 //! Script description extraction from various sources in package.json.
 //!
-//! Descriptions can come from multiple sources, in priority order:
+//! Descriptions can come from multiple sources, by default in priority order:
 //! 1. `scripts-info` object (highest priority)
 //! 2. `ntl.descriptions` object
 //! 3. `// comment` prefixes in scripts object
 //!
+//! The `descriptions.sources` setting picks the sources and their order; an
+//! empty list turns descriptions from package.json off.
+//!
 //! If no description is found, the command itself is used as a fallback.
 
 use std::collections::HashMap;
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
 use super::types::{Package, Script};
 
-/// Extract descriptions from all available sources in a Package.
+/// A place in package.json descriptions are read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum DescriptionSource {
+    /// The `scripts-info` object.
+    ScriptsInfo,
+    /// The `ntl.descriptions` object.
+    Ntl,
+    /// `// comment` keys in the scripts object.
+    Comments,
+}
+
+impl DescriptionSource {
+    /// All sources, in their default priority order.
+    pub const ALL: [Self; 3] = [Self::ScriptsInfo, Self::Ntl, Self::Comments];
+
+    /// Get the descriptions this source defines in a package.
+    fn read(self, package: &Package) -> Vec<(String, String)> {
+        match self {
+            Self::ScriptsInfo => package
+                .scripts_info
+                .iter()
+                .map(|(name, desc)| (name.clone(), desc.clone()))
+                .collect(),
+            Self::Ntl => package
+                .ntl
+                .iter()
+                .flat_map(|ntl| &ntl.descriptions)
+                .map(|(name, desc)| (name.clone(), desc.clone()))
+                .collect(),
+            // Look for keys like "//scriptName", "// scriptName", "//scriptName//"
+            Self::Comments => package
+                .scripts
+                .iter()
+                .filter_map(|(key, value)| Some((parse_comment_key(key)?, value.clone())))
+                .collect(),
+        }
+    }
+}
+
+/// Extract descriptions from all sources in a Package.
 ///
 /// Sources are checked in priority order:
 /// 1. `scripts-info` - Direct descriptions object
@@ -25,32 +71,20 @@ use super::types::{Package, Script};
 /// let descriptions = extract_descriptions(&package);
 /// ```
 pub fn extract_descriptions(package: &Package) -> HashMap<String, String> {
-    let mut descriptions = HashMap::new();
-
-    // Priority 1: scripts-info (highest priority)
-    for (name, desc) in &package.scripts_info {
-        descriptions.insert(name.clone(), desc.clone());
-    }
-
-    // Priority 2: ntl.descriptions
-    if let Some(ntl) = &package.ntl {
-        for (name, desc) in &ntl.descriptions {
-            descriptions
-                .entry(name.clone())
-                .or_insert_with(|| desc.clone());
-        }
-    }
+    extract_descriptions_from(package, &DescriptionSource::ALL)
+}
 
-    // Priority 3: // comments in scripts
-    // Look for keys like "//scriptName", "// scriptName", "//scriptName//"
-    for (key, value) in &package.scripts {
-        if let Some(script_name) = parse_comment_key(key) {
-            descriptions
-                .entry(script_name)
-                .or_insert_with(|| value.clone());
+/// Extract descriptions from the given sources, highest priority first.
+pub fn extract_descriptions_from(
+    package: &Package,
+    sources: &[DescriptionSource],
+) -> HashMap<String, String> {
+    let mut descriptions = HashMap::new();
+    for source in sources {
+        for (name, desc) in source.read(package) {
+            descriptions.entry(name).or_insert(desc);
         }
     }
-
     descriptions
 }
 
@@ -291,4 +325,42 @@ mod tests {
             Some(&"NTL description".to_string())
         );
     }
+
+    #[test]
+    fn test_extract_descriptions_from_sources() {
+        use super::super::types::NtlConfig;
+
+        let package = Package {
+            scripts: [
+                ("//dev".to_string(), "Comment description".to_string()),
+                ("dev".to_string(), "vite".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+            scripts_info: [("dev".to_string(), "Scripts-info description".to_string())]
+                .into_iter()
+                .collect(),
+            ntl: Some(NtlConfig {
+                descriptions: [("dev".to_string(), "NTL description".to_string())]
+                    .into_iter()
+                    .collect(),
+            }),
+            ..Default::default()
+        };
+
+        let sources = [DescriptionSource::Comments, DescriptionSource::ScriptsInfo];
+        let descriptions = extract_descriptions_from(&package, &sources);
+        assert_eq!(
+            descriptions.get("dev"),
+            Some(&"Comment description".to_string())
+        );
+
+        let descriptions = extract_descriptions_from(&package, &[DescriptionSource::Ntl]);
+        assert_eq!(
+            descriptions.get("dev"),
+            Some(&"NTL description".to_string())
+        );
+
+        assert!(extract_descriptions_from(&package, &[]).is_empty());
+    }
 }
@@ -0,0 +1,183 @@
+//! Script differences between git revisions.
+//!
+//! Compares the scripts in the working copy's package.json with those at
+//! another git ref, to review changes to a project's script surface.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+use super::scripts::parse_scripts_from_json;
+use super::types::{Script, Scripts};
+
+/// Differences between two sets of scripts.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptsDiff {
+    /// Scripts only in the new set.
+    pub added: Vec<Script>,
+    /// Scripts only in the old set.
+    pub removed: Vec<Script>,
+    /// Scripts whose command changed, as (old, new).
+    pub changed: Vec<(Script, Script)>,
+}
+
+impl ScriptsDiff {
+    /// Check if the script sets are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare two sets of scripts.
+///
+/// Added and changed scripts keep the order of `new`, removed scripts the
+/// order of `old`.
+pub fn diff_scripts(old: &Scripts, new: &Scripts) -> ScriptsDiff {
+    let mut diff = ScriptsDiff::default();
+
+    for script in new.iter() {
+        match old.get(script.name()) {
+            None => diff.added.push(script.clone()),
+            Some(previous) if previous.command() != script.command() => {
+                diff.changed.push((previous.clone(), script.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+
+    diff.removed = old
+        .iter()
+        .filter(|script| new.get(script.name()).is_none())
+        .cloned()
+        .collect();
+
+    diff
+}
+
+/// Read the scripts of the package.json in `project_dir` at a git ref.
+pub fn scripts_at_ref(project_dir: &Path, reference: &str) -> Result<Scripts> {
+    // "./" makes the path relative to the project, not the repository root
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!("{reference}:./package.json"))
+        .current_dir(project_dir)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "Failed to read package.json at {reference}: {}",
+            stderr.trim()
+        );
+    }
+
+    let content = String::from_utf8_lossy(&output.stdout);
+    parse_scripts_from_json(&content)
+        .with_context(|| format!("Failed to parse package.json at {reference}"))
+}
+
+/// Format a diff for the terminal, optionally with ANSI colors.
+pub fn format_scripts_diff(diff: &ScriptsDiff, reference: &str, use_colors: bool) -> String {
+    if diff.is_empty() {
+        return format!("No script changes since {reference}\n");
+    }
+
+    let paint = |code: &str, text: String| {
+        if use_colors {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text
+        }
+    };
+
+    let width = diff
+        .added
+        .iter()
+        .chain(&diff.removed)
+        .chain(diff.changed.iter().map(|(_, new)| new))
+        .map(|s| s.name().chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(30);
+
+    let mut out = format!("Script changes since {reference}:\n\n");
+    for script in &diff.added {
+        let line = format!("+ {:width$}  {}", script.name(), script.command());
+        out.push_str(&format!("  {}\n", paint("32", line)));
+    }
+    for script in &diff.removed {
+        let line = format!("- {:width$}  {}", script.name(), script.command());
+        out.push_str(&format!("  {}\n", paint("31", line)));
+    }
+    for (old, new) in &diff.changed {
+        let line = format!("~ {:width$}  {}", new.name(), old.command());
+        out.push_str(&format!("  {}\n", paint("33", line)));
+        out.push_str(&format!("    {:width$}  -> {}\n", "", new.command()));
+    }
+
+    out.push_str(&format!(
+        "\n{} added, {} removed, {} changed\n",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scripts(pairs: &[(&str, &str)]) -> Scripts {
+        Scripts::from_vec(pairs.iter().map(|(n, c)| Script::new(*n, *c)).collect())
+    }
+
+    #[test]
+    fn test_diff_scripts() {
+        let old = scripts(&[
+            ("dev", "vite"),
+            ("build", "vite build"),
+            ("format", "prettier"),
+        ]);
+        let new = scripts(&[
+            ("dev", "vite"),
+            ("build", "vite build --mode prod"),
+            ("lint", "eslint ."),
+        ]);
+
+        let diff = diff_scripts(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].name(), "lint");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].name(), "format");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].0.command(), "vite build");
+        assert_eq!(diff.changed[0].1.command(), "vite build --mode prod");
+    }
+
+    #[test]
+    fn test_diff_identical_scripts() {
+        let old = scripts(&[("dev", "vite")]);
+        let diff = diff_scripts(&old, &old.clone());
+        assert!(diff.is_empty());
+        assert_eq!(
+            format_scripts_diff(&diff, "main", false),
+            "No script changes since main\n"
+        );
+    }
+
+    #[test]
+    fn test_format_scripts_diff() {
+        let old = scripts(&[("build", "vite build"), ("format", "prettier")]);
+        let new = scripts(&[("build", "vite build --mode prod"), ("lint", "eslint .")]);
+        let text = format_scripts_diff(&diff_scripts(&old, &new), "main", false);
+
+        assert!(text.contains("  + lint    eslint .\n"));
+        assert!(text.contains("  - format  prettier\n"));
+        assert!(text.contains("  ~ build   vite build\n"));
+        assert!(text.contains("-> vite build --mode prod\n"));
+        assert!(text.contains("1 added, 1 removed, 1 changed"));
+    }
+}
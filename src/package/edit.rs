@@ -0,0 +1,490 @@
+//! Adding, renaming and removing scripts in package.json.
+//!
+//! The file is edited as text rather than re-serialized, so key order,
+//! indentation and line endings stay as the user wrote them. New scripts go
+//! at the end of `scripts`, which is created if missing.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use super::excerpt::{depth_after, json_key};
+
+/// Add a script to the package.json in `dir`.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or written, already has the
+/// script, or isn't laid out one entry per line (e.g. minified JSON).
+pub fn add_script(dir: &Path, name: &str, command: &str) -> Result<()> {
+    let path = dir.join("package.json");
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = insert_script(&content, name, command)
+        .with_context(|| format!("Couldn't add {name} to {}", path.display()))?;
+    fs::write(&path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Insert a script into package.json source text.
+pub fn insert_script(content: &str, name: &str, command: &str) -> Result<String> {
+    if name.trim().is_empty() {
+        bail!("Script name is empty");
+    }
+    if command.trim().is_empty() {
+        bail!("Command is empty");
+    }
+
+    let json: serde_json::Value = serde_json::from_str(content).context("Invalid JSON")?;
+    if json.get("scripts").and_then(|s| s.get(name)).is_some() {
+        bail!("A script named {name} already exists");
+    }
+
+    let crlf = content.contains("\r\n");
+    let text = content.replace("\r\n", "\n");
+    let mut lines: Vec<String> = text.split('\n').map(String::from).collect();
+
+    let unit = indent_unit(&lines);
+    let entry = format!(
+        "{}: {}",
+        json_key(name),
+        serde_json::to_string(command).context("Failed to encode command")?
+    );
+
+    match find_section(&lines, "scripts") {
+        Some(Section::Empty { line }) => {
+            let indent = leading_whitespace(&lines[line]).to_string();
+            let (before, after) = lines[line]
+                .split_once("{}")
+                .map(|(b, a)| (b.to_string(), a.to_string()))
+                .context("Unexpected scripts layout")?;
+            lines.splice(
+                line..=line,
+                [
+                    format!("{before}{{"),
+                    format!("{indent}{unit}{entry}"),
+                    format!("{indent}}}{after}"),
+                ],
+            );
+        }
+        Some(Section::Block { start, end }) => {
+            let indent = format!("{}{unit}", leading_whitespace(&lines[start]));
+            insert_entry(&mut lines, start, end, format!("{indent}{entry}"))?;
+        }
+        Some(Section::Inline) => bail!("Unexpected layout: scripts are on a single line"),
+        None => {
+            let end = root_end(&lines).context("Couldn't find the end of package.json")?;
+            let block = [
+                format!("{unit}\"scripts\": {{"),
+                format!("{unit}{unit}{entry}"),
+                format!("{unit}}}"),
+            ];
+            insert_entry(&mut lines, 0, end, block.join("\n"))?;
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if crlf {
+        updated = updated.replace('\n', "\r\n");
+    }
+
+    // Never write something that doesn't parse back to the script
+    let check: serde_json::Value =
+        serde_json::from_str(&updated).context("Couldn't find where to add the script")?;
+    if check.get("scripts").and_then(|s| s.get(name)) != Some(&command.into()) {
+        bail!("Couldn't find where to add the script");
+    }
+    Ok(updated)
+}
+
+/// A change to an existing script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptEdit {
+    /// Give a script a new name, keeping its place and command.
+    Rename { from: String, to: String },
+    /// Delete a script.
+    Remove { name: String },
+}
+
+impl ScriptEdit {
+    /// Get the name of the script being edited.
+    pub fn script(&self) -> &str {
+        match self {
+            Self::Rename { from, .. } => from,
+            Self::Remove { name } => name,
+        }
+    }
+
+    /// Apply the edit to package.json source text.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the script is missing, a rename would clash with
+    /// another script, or the script isn't on a line of its own.
+    pub fn apply(&self, content: &str) -> Result<String> {
+        let json: serde_json::Value = serde_json::from_str(content).context("Invalid JSON")?;
+        let scripts = json
+            .get("scripts")
+            .and_then(|s| s.as_object())
+            .context("No scripts in package.json")?;
+        let command = scripts
+            .get(self.script())
+            .with_context(|| format!("No script named {}", self.script()))?;
+        if let Self::Rename { to, .. } = self {
+            if to.trim().is_empty() {
+                bail!("Script name is empty");
+            }
+            if scripts.contains_key(to) {
+                bail!("A script named {to} already exists");
+            }
+        }
+
+        let crlf = content.contains("\r\n");
+        let text = content.replace("\r\n", "\n");
+        let mut lines: Vec<String> = text.split('\n').map(String::from).collect();
+
+        let Some(Section::Block { start, end }) = find_section(&lines, "scripts") else {
+            bail!("Unexpected layout: scripts are on a single line");
+        };
+        let entry = find_entry(&lines, start, end, self.script())
+            .with_context(|| format!("{} isn't on a line of its own", self.script()))?;
+
+        match self {
+            Self::Rename { from, to } => {
+                lines[entry] = lines[entry].replacen(&json_key(from), &json_key(to), 1);
+            }
+            Self::Remove { .. } => remove_entry(&mut lines, start, end, entry),
+        }
+
+        let mut updated = lines.join("\n");
+        if crlf {
+            updated = updated.replace('\n', "\r\n");
+        }
+
+        // Never write something that doesn't parse back to the edit
+        let check: serde_json::Value =
+            serde_json::from_str(&updated).context("Couldn't edit the script")?;
+        let edited = check.get("scripts").and_then(|s| s.as_object());
+        let ok = edited.is_some_and(|edited| match self {
+            Self::Rename { from, to } => {
+                edited.len() == scripts.len()
+                    && !edited.contains_key(from)
+                    && edited.get(to) == Some(command)
+            }
+            Self::Remove { name } => {
+                edited.len() + 1 == scripts.len() && !edited.contains_key(name)
+            }
+        });
+        if !ok {
+            bail!("Couldn't edit the script");
+        }
+        Ok(updated)
+    }
+}
+
+/// Apply an edit to the package.json in `dir`.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or written, or the edit can't
+/// be applied (see [`ScriptEdit::apply`]).
+pub fn edit_script(dir: &Path, edit: &ScriptEdit) -> Result<()> {
+    let path = dir.join("package.json");
+    let (_, updated) = read_and_apply(&path, edit)?;
+    fs::write(&path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// A line of an edit preview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreviewLine {
+    /// A line left as it is, shown for context.
+    Unchanged(String),
+    /// A line the edit removes or replaces.
+    Removed(String),
+    /// A line the edit adds.
+    Added(String),
+}
+
+/// Preview an edit to the package.json in `dir` as changed lines with one
+/// line of context around them, without writing anything.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or the edit can't be applied.
+pub fn preview_edit(dir: &Path, edit: &ScriptEdit) -> Result<Vec<PreviewLine>> {
+    let (content, updated) = read_and_apply(&dir.join("package.json"), edit)?;
+    Ok(preview_lines(&content, &updated))
+}
+
+/// Read a package.json and get its content with and without an edit.
+fn read_and_apply(path: &Path, edit: &ScriptEdit) -> Result<(String, String)> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = edit
+        .apply(&content)
+        .with_context(|| format!("Couldn't edit {} in {}", edit.script(), path.display()))?;
+    Ok((content, updated))
+}
+
+/// Compare two versions of a file that differ in one place.
+fn preview_lines(old: &str, new: &str) -> Vec<PreviewLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let unchanged = |line: &&str| PreviewLine::Unchanged(line.to_string());
+    let mut preview: Vec<PreviewLine> = old[prefix.saturating_sub(1)..prefix]
+        .iter()
+        .map(unchanged)
+        .collect();
+    preview.extend(
+        old[prefix..old.len() - suffix]
+            .iter()
+            .map(|line| PreviewLine::Removed(line.to_string())),
+    );
+    preview.extend(
+        new[prefix..new.len() - suffix]
+            .iter()
+            .map(|line| PreviewLine::Added(line.to_string())),
+    );
+    let after = old.len() - suffix;
+    preview.extend(old[after..(after + 1).min(old.len())].iter().map(unchanged));
+    preview
+}
+
+/// Where a top-level object section is in the source.
+enum Section {
+    /// `"key": {}` on a single line.
+    Empty { line: usize },
+    /// An object from the line with its key to the line closing it.
+    Block { start: usize, end: usize },
+    /// A non-empty object on a single line.
+    Inline,
+}
+
+/// Find a top-level object section by key.
+fn find_section(lines: &[String], section: &str) -> Option<Section> {
+    let key = json_key(section);
+    let mut depth = 0;
+    let mut start = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let line_depth = depth;
+        depth = depth_after(line, depth);
+
+        match start {
+            None if line_depth == 1 && line.trim_start().starts_with(&key) => {
+                if depth == 1 {
+                    return Some(if line.contains("{}") {
+                        Section::Empty { line: index }
+                    } else {
+                        Section::Inline
+                    });
+                }
+                start = Some(index);
+            }
+            Some(start) if depth <= 1 => {
+                return Some(Section::Block { start, end: index });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Find the line of an entry directly inside the section between `start`
+/// and `end`, if it is on a single line.
+fn find_entry(lines: &[String], start: usize, end: usize, name: &str) -> Option<usize> {
+    let key = json_key(name);
+    let mut depth = 0;
+    for (index, line) in lines.iter().enumerate().take(end) {
+        let line_depth = depth;
+        depth = depth_after(line, depth);
+        if index > start && line_depth == 2 && depth == 2 && line.trim_start().starts_with(&key) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// Remove the entry on line `entry` of the section between `start` and `end`.
+///
+/// Drops the comma the entry before it no longer needs, and collapses the
+/// section to `{}` if it was the only entry.
+fn remove_entry(lines: &mut Vec<String>, start: usize, end: usize, entry: usize) {
+    lines.remove(entry);
+    let end = end - 1;
+
+    let was_last = (entry..end).all(|i| lines[i].trim().is_empty());
+    if !was_last {
+        return;
+    }
+    match (start + 1..entry)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+    {
+        Some(previous) => {
+            let trimmed_len = lines[previous].trim_end().len();
+            if lines[previous][..trimmed_len].ends_with(',') {
+                lines[previous].remove(trimmed_len - 1);
+            }
+        }
+        None => {
+            let after_close = lines[end].trim_start()[1..].to_string();
+            lines[start] = format!("{}}}{after_close}", lines[start].trim_end());
+            lines.drain(start + 1..=end);
+        }
+    }
+}
+
+/// Find the line closing the root object.
+fn root_end(lines: &[String]) -> Option<usize> {
+    let mut depth = 0;
+    for (index, line) in lines.iter().enumerate() {
+        let line_depth = depth;
+        depth = depth_after(line, depth);
+        if line_depth == 1 && depth == 0 {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// Insert text before the closing line `end` of the object opened on line
+/// `start`, after its last entry.
+fn insert_entry(lines: &mut Vec<String>, start: usize, end: usize, text: String) -> Result<()> {
+    if !lines[end].trim_start().starts_with('}') || start == end {
+        bail!("Unexpected layout: the closing brace isn't on its own line");
+    }
+
+    // The last entry needs a comma, unless the object is empty
+    if let Some(last) = (start + 1..end)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+    {
+        let trimmed_len = lines[last].trim_end().len();
+        if !lines[last][..trimmed_len].ends_with(',') {
+            lines[last].insert(trimmed_len, ',');
+        }
+    }
+    lines.insert(end, text);
+    Ok(())
+}
+
+/// Get the indentation used for one level, from the first indented line.
+fn indent_unit(lines: &[String]) -> String {
+    lines
+        .iter()
+        .map(|line| leading_whitespace(line))
+        .find(|indent| !indent.is_empty())
+        .unwrap_or("  ")
+        .to_string()
+}
+
+/// Get the leading whitespace of a line.
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_keeps_order_and_indent() {
+        let content = "{\n    \"name\": \"app\",\n    \"scripts\": {\n        \"dev\": \"vite\"\n    },\n    \"dependencies\": {}\n}\n";
+        let updated = insert_script(content, "lint", "eslint . --fix").unwrap();
+        assert_eq!(
+            updated,
+            "{\n    \"name\": \"app\",\n    \"scripts\": {\n        \"dev\": \"vite\",\n        \"lint\": \"eslint . --fix\"\n    },\n    \"dependencies\": {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_into_empty_scripts() {
+        let content = "{\n\t\"name\": \"app\",\n\t\"scripts\": {}\n}";
+        let updated = insert_script(content, "dev", "vite").unwrap();
+        assert_eq!(
+            updated,
+            "{\n\t\"name\": \"app\",\n\t\"scripts\": {\n\t\t\"dev\": \"vite\"\n\t}\n}"
+        );
+    }
+
+    #[test]
+    fn test_insert_creates_scripts() {
+        let content = "{\r\n  \"name\": \"app\"\r\n}\r\n";
+        let updated = insert_script(content, "test", "node --test \"src/**\"").unwrap();
+        assert_eq!(
+            updated,
+            "{\r\n  \"name\": \"app\",\r\n  \"scripts\": {\r\n    \"test\": \"node --test \\\"src/**\\\"\"\r\n  }\r\n}\r\n"
+        );
+    }
+
+    const SCRIPTS: &str = "{\n  \"scripts\": {\n    \"dev\": \"vite\",\n    \"test\": \"vitest\"\n  },\n  \"private\": true\n}\n";
+
+    fn rename(from: &str, to: &str) -> ScriptEdit {
+        ScriptEdit::Rename {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    fn remove(name: &str) -> ScriptEdit {
+        ScriptEdit::Remove {
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rename_keeps_place() {
+        let updated = rename("dev", "serve").apply(SCRIPTS).unwrap();
+        assert_eq!(updated, SCRIPTS.replace("\"dev\"", "\"serve\""));
+
+        assert!(rename("dev", "test").apply(SCRIPTS).is_err());
+        assert!(rename("build", "compile").apply(SCRIPTS).is_err());
+    }
+
+    #[test]
+    fn test_remove_fixes_commas() {
+        let updated = remove("test").apply(SCRIPTS).unwrap();
+        assert_eq!(
+            updated,
+            "{\n  \"scripts\": {\n    \"dev\": \"vite\"\n  },\n  \"private\": true\n}\n"
+        );
+
+        let updated = remove("dev").apply(&updated).unwrap();
+        assert_eq!(updated, "{\n  \"scripts\": {},\n  \"private\": true\n}\n");
+    }
+
+    #[test]
+    fn test_preview_lines() {
+        let updated = remove("test").apply(SCRIPTS).unwrap();
+        assert_eq!(
+            preview_lines(SCRIPTS, &updated),
+            [
+                PreviewLine::Unchanged("  \"scripts\": {".to_string()),
+                PreviewLine::Removed("    \"dev\": \"vite\",".to_string()),
+                PreviewLine::Removed("    \"test\": \"vitest\"".to_string()),
+                PreviewLine::Added("    \"dev\": \"vite\"".to_string()),
+                PreviewLine::Unchanged("  },".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_rejects_existing_and_minified() {
+        let content = "{\n  \"scripts\": {\n    \"dev\": \"vite\"\n  }\n}";
+        assert!(insert_script(content, "dev", "vite --host").is_err());
+        assert!(insert_script(content, "", "vite").is_err());
+        assert!(insert_script(r#"{"scripts":{"dev":"vite"}}"#, "lint", "eslint").is_err());
+        let inline = "{\n  \"scripts\": { \"dev\": \"vite\" }\n}";
+        assert!(insert_script(inline, "lint", "eslint").is_err());
+    }
+}
@@ -0,0 +1,228 @@
+//! Source excerpts of scripts.
+//!
+//! Finds the lines of package.json (or the Deno, Turborepo or Nx config for
+//! their tasks) that define a script and its `pre`/`post` hooks, so the exact
+//! JSON, quoting included, can be shown without opening the file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::deno::find_deno_config;
+use super::manager::Runner;
+use super::types::Script;
+
+/// A line of a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLine {
+    /// Line number (1-based).
+    pub number: usize,
+    /// Line text, without the line break.
+    pub text: String,
+    /// Whether the line defines the script itself rather than a hook.
+    pub is_target: bool,
+}
+
+/// The lines defining a script and its hooks in a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Excerpt {
+    /// The file the lines come from.
+    pub file: PathBuf,
+    /// Lines in file order. Line numbers skip where unrelated lines were left out.
+    pub lines: Vec<SourceLine>,
+}
+
+/// Get the excerpt defining a script in `dir`.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or the script isn't found in
+/// it (e.g. in minified JSON).
+pub fn script_excerpt(dir: &Path, script: &Script) -> Result<Excerpt> {
+    let (file, sections): (PathBuf, &[&str]) = match script.runner() {
+        Some(Runner::Deno) => (
+            find_deno_config(dir).context("No deno.json found")?,
+            &["tasks"],
+        ),
+        // Turborepo 1 called its tasks a pipeline
+        Some(Runner::Turbo) => (dir.join("turbo.json"), &["tasks", "pipeline"]),
+        Some(Runner::Nx) => (dir.join("nx.json"), &["targetDefaults"]),
+        _ => (dir.join("package.json"), &["scripts"]),
+    };
+
+    let content =
+        fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let lines = sections
+        .iter()
+        .find_map(|section| excerpt_from_source(&content, section, script.name()))
+        .with_context(|| {
+            format!(
+                "Couldn't find {} in {}",
+                script.name(),
+                file.file_name().unwrap_or_default().to_string_lossy()
+            )
+        })?;
+
+    Ok(Excerpt { file, lines })
+}
+
+/// Find the lines defining `name` and its hooks in a top-level `section` of
+/// JSON source text.
+///
+/// Returns `None` if `name` isn't on a line of its own in the section.
+pub fn excerpt_from_source(content: &str, section: &str, name: &str) -> Option<Vec<SourceLine>> {
+    let section_key = json_key(section);
+    let keys = [
+        json_key(&format!("pre{name}")),
+        json_key(name),
+        json_key(&format!("post{name}")),
+    ];
+
+    let mut lines = Vec::new();
+    let mut depth = 0;
+    let mut in_section = false;
+    // Set while an entry spans several lines, to whether it is the script itself
+    let mut in_entry: Option<bool> = None;
+
+    for (index, text) in content.lines().enumerate() {
+        let line_depth = depth;
+        depth = depth_after(text, depth);
+        let trimmed = text.trim_start();
+
+        if line_depth == 1 && trimmed.starts_with(&section_key) {
+            in_section = true;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if line_depth < 2 {
+            // Past the end of the section
+            break;
+        }
+
+        let entry = match in_entry {
+            Some(is_target) => Some(is_target),
+            None if line_depth == 2 => keys
+                .iter()
+                .position(|key| starts_with_key(trimmed, key))
+                .map(|i| i == 1),
+            None => None,
+        };
+
+        if let Some(is_target) = entry {
+            lines.push(SourceLine {
+                number: index + 1,
+                text: text.to_string(),
+                is_target,
+            });
+            // Object values (Deno tasks) continue until the depth is back
+            in_entry = (depth > 2).then_some(is_target);
+        }
+    }
+
+    lines.iter().any(|l| l.is_target).then_some(lines)
+}
+
+/// Encode a JSON object key as it appears in source text.
+pub(super) fn json_key(name: &str) -> String {
+    serde_json::to_string(name).unwrap_or_else(|_| format!("\"{name}\""))
+}
+
+/// Check if a trimmed line starts with a key followed by a colon.
+fn starts_with_key(line: &str, key: &str) -> bool {
+    line.strip_prefix(key)
+        .is_some_and(|rest| rest.trim_start().starts_with(':'))
+}
+
+/// Get the nesting depth after a line, ignoring brackets in strings and comments.
+pub(super) fn depth_after(line: &str, mut depth: usize) -> usize {
+    let mut in_string = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_string {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth = depth.saturating_sub(1),
+            '/' if chars.peek() == Some(&'/') => break,
+            _ => {}
+        }
+    }
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKAGE_JSON: &str = r#"{
+  "name": "app",
+  "config": {
+    "build": "not a script"
+  },
+  "scripts": {
+    "prebuild": "rimraf dist",
+    "build": "tsc -p \"tsconfig.build.json\"",
+    "dev": "vite",
+    "postbuild": "cp {a,b} dist"
+  }
+}"#;
+
+    fn numbers(lines: &[SourceLine]) -> Vec<usize> {
+        lines.iter().map(|l| l.number).collect()
+    }
+
+    #[test]
+    fn test_excerpt_includes_hooks() {
+        let lines = excerpt_from_source(PACKAGE_JSON, "scripts", "build").unwrap();
+        assert_eq!(numbers(&lines), [7, 8, 10]);
+        assert_eq!(
+            lines[1].text,
+            r#"    "build": "tsc -p \"tsconfig.build.json\"","#
+        );
+        assert!(lines[1].is_target);
+        assert!(!lines[0].is_target);
+    }
+
+    #[test]
+    fn test_excerpt_without_hooks() {
+        let lines = excerpt_from_source(PACKAGE_JSON, "scripts", "dev").unwrap();
+        assert_eq!(numbers(&lines), [9]);
+    }
+
+    #[test]
+    fn test_excerpt_missing_script() {
+        assert!(excerpt_from_source(PACKAGE_JSON, "scripts", "lint").is_none());
+        // Minified JSON has no line per script
+        let minified = r#"{"scripts":{"dev":"vite"}}"#;
+        assert!(excerpt_from_source(minified, "scripts", "dev").is_none());
+    }
+
+    #[test]
+    fn test_excerpt_object_task() {
+        let deno = r#"{
+  // Tasks
+  "tasks": {
+    "dev": {
+      "description": "Start {dev} server",
+      "command": "deno run -A main.ts"
+    },
+    "test": "deno test"
+  }
+}"#;
+        let lines = excerpt_from_source(deno, "tasks", "dev").unwrap();
+        assert_eq!(numbers(&lines), [4, 5, 6, 7]);
+        assert!(lines.iter().all(|l| l.is_target));
+    }
+}
@@ -0,0 +1,435 @@
+//! Script dependency graph.
+//!
+//! Scripts depend on each other through npm's `pre<name>`/`post<name>` hooks
+//! and by running other scripts from their commands, either directly
+//! (`npm run lint`) or through `npm-run-all`, `run-s` and `run-p`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use glob::{MatchOptions, Pattern};
+
+use super::types::Scripts;
+
+/// How a script depends on another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// `pre<name>` hook, run before the script.
+    Pre,
+    /// `post<name>` hook, run after the script.
+    Post,
+    /// Run by the command (`npm run`, `yarn`, `deno task`, ...).
+    Run,
+    /// Run in sequence by `npm-run-all -s` or `run-s`.
+    Serial,
+    /// Run in parallel by `npm-run-all -p` or `run-p`.
+    Parallel,
+}
+
+impl EdgeKind {
+    /// Get the label shown next to the dependency.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pre => "pre",
+            Self::Post => "post",
+            Self::Run => "run",
+            Self::Serial => "run-s",
+            Self::Parallel => "run-p",
+        }
+    }
+}
+
+/// A script another script depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    /// Name of the script depended on.
+    pub name: String,
+    /// How it is run.
+    pub kind: EdgeKind,
+}
+
+/// A line of a rendered dependency tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeLine {
+    /// Tree drawing characters before the name.
+    pub prefix: String,
+    /// Script name.
+    pub name: String,
+    /// How the parent depends on the script (`None` for the root).
+    pub kind: Option<EdgeKind>,
+    /// Whether the script is already one of its own ancestors.
+    pub cycle: bool,
+}
+
+impl TreeLine {
+    /// Get the annotation shown after the name (e.g. "(run-s)", "(run, cycle)").
+    pub fn annotation(&self) -> Option<String> {
+        let kind = self.kind?;
+        Some(if self.cycle {
+            format!("({}, cycle)", kind.label())
+        } else {
+            format!("({})", kind.label())
+        })
+    }
+}
+
+impl fmt::Display for TreeLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix, self.name)?;
+        match self.annotation() {
+            Some(annotation) => write!(f, " {annotation}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Dependencies between the scripts of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptGraph {
+    edges: BTreeMap<String, Vec<Dependency>>,
+}
+
+impl ScriptGraph {
+    /// Build the graph of a set of scripts.
+    ///
+    /// Only scripts in the set are linked; commands running anything else
+    /// (binaries, other packages' scripts) add no edges.
+    pub fn from_scripts(scripts: &Scripts) -> Self {
+        let mut edges = BTreeMap::new();
+        for script in scripts.iter() {
+            let name = script.name();
+            let mut deps = Vec::new();
+            let mut add = |dep: Dependency| {
+                if dep.name != name && !deps.iter().any(|d: &Dependency| d.name == dep.name) {
+                    deps.push(dep);
+                }
+            };
+
+            let pre = format!("pre{name}");
+            if scripts.get(&pre).is_some() {
+                add(Dependency {
+                    name: pre,
+                    kind: EdgeKind::Pre,
+                });
+            }
+            for dep in command_dependencies(script.command(), scripts) {
+                add(dep);
+            }
+            let post = format!("post{name}");
+            if scripts.get(&post).is_some() {
+                add(Dependency {
+                    name: post,
+                    kind: EdgeKind::Post,
+                });
+            }
+
+            if !deps.is_empty() {
+                edges.insert(name.to_string(), deps);
+            }
+        }
+        Self { edges }
+    }
+
+    /// Get the direct dependencies of a script, in the order they run.
+    pub fn dependencies(&self, name: &str) -> &[Dependency] {
+        self.edges.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Render the dependencies of a script as a tree.
+    ///
+    /// The first line is the script itself. Scripts depending on one of
+    /// their ancestors are marked as a cycle and not expanded again.
+    pub fn tree(&self, root: &str) -> Vec<TreeLine> {
+        let mut lines = vec![TreeLine {
+            prefix: String::new(),
+            name: root.to_string(),
+            kind: None,
+            cycle: false,
+        }];
+        let mut path = vec![root];
+        self.add_subtree(root, "", &mut path, &mut lines);
+        lines
+    }
+
+    fn add_subtree<'a>(
+        &'a self,
+        name: &str,
+        indent: &str,
+        path: &mut Vec<&'a str>,
+        lines: &mut Vec<TreeLine>,
+    ) {
+        let deps = self.dependencies(name);
+        for (i, dep) in deps.iter().enumerate() {
+            let last = i + 1 == deps.len();
+            let cycle = path.contains(&dep.name.as_str());
+            lines.push(TreeLine {
+                prefix: format!("{indent}{}", if last { "└─ " } else { "├─ " }),
+                name: dep.name.clone(),
+                kind: Some(dep.kind),
+                cycle,
+            });
+            if !cycle {
+                path.push(&dep.name);
+                let indent = format!("{indent}{}", if last { "   " } else { "│  " });
+                self.add_subtree(&dep.name, &indent, path, lines);
+                path.pop();
+            }
+        }
+    }
+}
+
+/// Shell operators separating commands.
+const OPERATORS: &[&str] = &["&&", "||", ";", "|", "&"];
+
+/// Flags that make a run command target another package.
+const ELSEWHERE_FLAGS: &[&str] = &[
+    "-w",
+    "--workspace",
+    "--workspaces",
+    "--prefix",
+    "-C",
+    "--cwd",
+    "--filter",
+    "-F",
+    "--recursive",
+    "-r",
+];
+
+/// npm-run-all options that take a value.
+const RUN_ALL_VALUE_OPTIONS: &[&str] = &["--max-parallel", "--npm-path"];
+
+/// Find the scripts a command runs.
+fn command_dependencies(command: &str, scripts: &Scripts) -> Vec<Dependency> {
+    let tokens = shell_words::split(command)
+        .unwrap_or_else(|_| command.split_whitespace().map(String::from).collect());
+
+    let mut deps = Vec::new();
+    let mut segment: Vec<&str> = Vec::new();
+    for token in &tokens {
+        if OPERATORS.contains(&token.as_str()) {
+            deps.extend(segment_dependencies(&segment, scripts));
+            segment.clear();
+        } else if let Some(token) = token.strip_suffix(';') {
+            segment.push(token);
+            deps.extend(segment_dependencies(&segment, scripts));
+            segment.clear();
+        } else {
+            segment.push(token);
+        }
+    }
+    deps.extend(segment_dependencies(&segment, scripts));
+    deps
+}
+
+/// Find the scripts a single command (without shell operators) runs.
+fn segment_dependencies(tokens: &[&str], scripts: &Scripts) -> Vec<Dependency> {
+    // Skip env assignments and wrappers that just run the rest
+    let mut tokens = tokens;
+    while let Some((first, rest)) = tokens.split_first() {
+        let is_assignment = first.split_once('=').is_some_and(|(var, _)| {
+            !var.is_empty() && var.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        if is_assignment || matches!(*first, "cross-env" | "cross-env-shell" | "npx" | "env") {
+            tokens = rest;
+        } else {
+            break;
+        }
+    }
+
+    let Some((program, args)) = tokens.split_first() else {
+        return Vec::new();
+    };
+    match *program {
+        "npm-run-all" | "npm-run-all2" => run_all_dependencies(args, EdgeKind::Serial, scripts),
+        "run-s" => run_all_dependencies(args, EdgeKind::Serial, scripts),
+        "run-p" => run_all_dependencies(args, EdgeKind::Parallel, scripts),
+        _ => run_dependency(program, args, scripts).into_iter().collect(),
+    }
+}
+
+/// Find the scripts run by `npm-run-all`, `run-s` or `run-p` arguments.
+fn run_all_dependencies(args: &[&str], mut kind: EdgeKind, scripts: &Scripts) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--" => break,
+            "-s" | "--serial" | "--sequential" => kind = EdgeKind::Serial,
+            "-p" | "--parallel" => kind = EdgeKind::Parallel,
+            option if RUN_ALL_VALUE_OPTIONS.contains(&option) => {
+                args.next();
+            }
+            option if option.starts_with('-') => {}
+            pattern => {
+                // Quoted patterns can carry arguments ("build -- --watch")
+                let pattern = pattern.split_whitespace().next().unwrap_or_default();
+                for name in scripts.names() {
+                    if matches_run_all_pattern(pattern, name) {
+                        deps.push(Dependency {
+                            name: name.to_string(),
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    deps
+}
+
+/// Check if a script name matches an npm-run-all pattern.
+///
+/// `*` matches within a `:`-separated segment and `**` across segments.
+pub(super) fn matches_run_all_pattern(pattern: &str, name: &str) -> bool {
+    if !pattern.contains('*') {
+        return pattern == name;
+    }
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    Pattern::new(&pattern.replace(':', "/"))
+        .is_ok_and(|p| p.matches_with(&name.replace(':', "/"), options))
+}
+
+/// Find the script run by a package manager command, if any.
+fn run_dependency(program: &str, args: &[&str], scripts: &Scripts) -> Option<Dependency> {
+    if args.iter().any(|arg| {
+        ELSEWHERE_FLAGS
+            .iter()
+            .any(|flag| *arg == *flag || arg.starts_with(&format!("{flag}=")))
+    }) {
+        return None;
+    }
+
+    let mut words = args.iter().copied().filter(|arg| !arg.starts_with('-'));
+    let name = match (program, words.next()?) {
+        ("npm", "run" | "run-script" | "rum" | "urn") => words.next()?,
+        ("npm", shorthand @ ("test" | "start" | "stop" | "restart")) => shorthand,
+        ("npm", "t") => "test",
+        ("yarn" | "pnpm" | "bun", "run") => words.next()?,
+        ("yarn" | "pnpm", name) => name,
+        ("deno", "task") => words.next()?,
+        _ => return None,
+    };
+
+    scripts.get(name).map(|_| Dependency {
+        name: name.to_string(),
+        kind: EdgeKind::Run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Script;
+
+    fn scripts(entries: &[(&str, &str)]) -> Scripts {
+        Scripts::from_vec(
+            entries
+                .iter()
+                .map(|(name, command)| Script::new(*name, *command))
+                .collect(),
+        )
+    }
+
+    fn names(deps: &[Dependency]) -> Vec<(&str, EdgeKind)> {
+        deps.iter().map(|d| (d.name.as_str(), d.kind)).collect()
+    }
+
+    #[test]
+    fn test_hooks_wrap_commands() {
+        let scripts = scripts(&[
+            ("prebuild", "rimraf dist"),
+            ("build", "npm run compile && yarn lint"),
+            ("postbuild", "cp -r public dist"),
+            ("compile", "tsc"),
+            ("lint", "eslint ."),
+        ]);
+        let graph = ScriptGraph::from_scripts(&scripts);
+
+        assert_eq!(
+            names(graph.dependencies("build")),
+            [
+                ("prebuild", EdgeKind::Pre),
+                ("compile", EdgeKind::Run),
+                ("lint", EdgeKind::Run),
+                ("postbuild", EdgeKind::Post),
+            ]
+        );
+        assert!(graph.dependencies("compile").is_empty());
+    }
+
+    #[test]
+    fn test_run_all_patterns() {
+        let scripts = scripts(&[
+            ("build", "run-s clean \"build:* -- --watch\""),
+            ("build:css", "sass"),
+            ("build:js", "esbuild"),
+            ("build:js:min", "terser"),
+            ("clean", "rimraf dist"),
+            ("dev", "npm-run-all clean --parallel watch:**"),
+            ("watch:css", "sass -w"),
+            ("watch:js:app", "esbuild --watch"),
+        ]);
+        let graph = ScriptGraph::from_scripts(&scripts);
+
+        assert_eq!(
+            names(graph.dependencies("build")),
+            [
+                ("clean", EdgeKind::Serial),
+                ("build:css", EdgeKind::Serial),
+                ("build:js", EdgeKind::Serial),
+            ]
+        );
+        assert_eq!(
+            names(graph.dependencies("dev")),
+            [
+                ("clean", EdgeKind::Serial),
+                ("watch:css", EdgeKind::Parallel),
+                ("watch:js:app", EdgeKind::Parallel),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignores_other_packages_and_binaries() {
+        let scripts = scripts(&[
+            (
+                "build",
+                "npm run build -w web && yarn tsc && NODE_ENV=test jest",
+            ),
+            ("test", "cross-env CI=1 npm test"),
+            ("tsc", "tsc -p ."),
+        ]);
+        let graph = ScriptGraph::from_scripts(&scripts);
+
+        // `yarn tsc` runs the tsc script when there is one
+        assert_eq!(names(graph.dependencies("build")), [("tsc", EdgeKind::Run)]);
+        // A script running itself isn't a dependency
+        assert!(graph.dependencies("test").is_empty());
+    }
+
+    #[test]
+    fn test_tree_marks_cycles() {
+        let scripts = scripts(&[
+            ("a", "run-p b c"),
+            ("b", "npm run a"),
+            ("c", "echo c"),
+            ("prec", "echo pre"),
+        ]);
+        let graph = ScriptGraph::from_scripts(&scripts);
+
+        let lines: Vec<String> = graph.tree("a").iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "a",
+                "├─ b (run-p)",
+                "│  └─ a (run, cycle)",
+                "└─ c (run-p)",
+                "   └─ prec (pre)",
+            ]
+        );
+    }
+}
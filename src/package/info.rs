@@ -0,0 +1,192 @@
+//! Project metadata besides scripts.
+//!
+//! Collects what package.json says about the package itself: its module
+//! type, entry points, exported subpaths, executables and `config` values,
+//! for the project info popover.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+/// Non-script metadata of a package.json.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectInfo {
+    /// Package name, if set.
+    pub name: Option<String>,
+    /// Package version, if set.
+    pub version: Option<String>,
+    /// Package description, if set.
+    pub description: Option<String>,
+    /// Whether `.js` files are ES modules (`"type": "module"`).
+    pub is_module: bool,
+    /// Entry points: `main`, `module`, `types` and `browser` when set.
+    pub entry_points: Vec<(String, String)>,
+    /// Exported subpaths with their target, or their conditions.
+    pub exports: Vec<(String, String)>,
+    /// Executables installed by the package, with their file.
+    pub bin: Vec<(String, String)>,
+    /// Values of the `config` object, exposed to scripts as
+    /// `npm_package_config_*`.
+    pub config: Vec<(String, String)>,
+    /// Required runtime versions from `engines`.
+    pub engines: Vec<(String, String)>,
+}
+
+/// Read the project info of the package.json in `dir`.
+///
+/// # Errors
+///
+/// Returns an error if package.json can't be read or isn't valid JSON.
+pub fn project_info(dir: &Path) -> Result<ProjectInfo> {
+    let path = dir.join("package.json");
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    project_info_from_json(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Read the project info of package.json content.
+///
+/// # Errors
+///
+/// Returns an error if the content isn't valid JSON.
+pub fn project_info_from_json(content: &str) -> Result<ProjectInfo> {
+    let json: Value = serde_json::from_str(content)?;
+    let string = |key: &str| json[key].as_str().map(String::from);
+
+    let entry_points = ["main", "module", "types", "browser"]
+        .iter()
+        .filter_map(|key| Some(((*key).to_string(), string(key)?)))
+        .collect();
+
+    Ok(ProjectInfo {
+        name: string("name"),
+        version: string("version"),
+        description: string("description"),
+        is_module: json["type"].as_str() == Some("module"),
+        entry_points,
+        exports: exports(&json["exports"]),
+        bin: bin(&json["bin"], json["name"].as_str()),
+        config: entries(json["config"].as_object()),
+        engines: entries(json["engines"].as_object()),
+    })
+}
+
+/// List exported subpaths.
+///
+/// A string or a conditions object exports `.`. Targets that depend on
+/// conditions are summarized by the condition names.
+fn exports(value: &Value) -> Vec<(String, String)> {
+    match value {
+        Value::String(target) => vec![(".".to_string(), target.clone())],
+        Value::Object(map) if map.keys().all(|key| key.starts_with('.')) => map
+            .iter()
+            .map(|(subpath, target)| (subpath.clone(), export_target(target)))
+            .collect(),
+        Value::Object(_) => vec![(".".to_string(), export_target(value))],
+        _ => Vec::new(),
+    }
+}
+
+/// Describe the target of one exported subpath.
+fn export_target(target: &Value) -> String {
+    match target {
+        Value::String(path) => path.clone(),
+        Value::Object(conditions) => conditions
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::Null => "(private)".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// List executables. A string `bin` is named after the package, without
+/// its scope.
+fn bin(value: &Value, package_name: Option<&str>) -> Vec<(String, String)> {
+    match value {
+        Value::String(path) => {
+            let name =
+                package_name.map_or("(unnamed)", |name| name.rsplit('/').next().unwrap_or(name));
+            vec![(name.to_string(), path.clone())]
+        }
+        Value::Object(map) => entries(Some(map)),
+        _ => Vec::new(),
+    }
+}
+
+/// List the entries of an object, with non-string values as JSON.
+fn entries(map: Option<&Map<String, Value>>) -> Vec<(String, String)> {
+    map.into_iter()
+        .flatten()
+        .map(|(key, value)| {
+            let value = value
+                .as_str()
+                .map_or_else(|| value.to_string(), String::from);
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_project_info_from_json() {
+        let json = r#"{
+            "name": "@acme/cli",
+            "version": "1.2.0",
+            "type": "module",
+            "main": "./dist/index.cjs",
+            "types": "./dist/index.d.ts",
+            "exports": {
+                ".": { "import": "./dist/index.js", "require": "./dist/index.cjs" },
+                "./utils": "./dist/utils.js",
+                "./internal": null
+            },
+            "bin": "./bin/cli.js",
+            "config": { "port": 8080, "host": "localhost" },
+            "engines": { "node": ">=18" }
+        }"#;
+
+        let info = project_info_from_json(json).unwrap();
+        assert_eq!(info.name.as_deref(), Some("@acme/cli"));
+        assert!(info.is_module);
+        assert_eq!(
+            info.entry_points,
+            pairs(&[("main", "./dist/index.cjs"), ("types", "./dist/index.d.ts")])
+        );
+        assert_eq!(
+            info.exports,
+            pairs(&[
+                (".", "import, require"),
+                ("./internal", "(private)"),
+                ("./utils", "./dist/utils.js"),
+            ])
+        );
+        assert_eq!(info.bin, pairs(&[("cli", "./bin/cli.js")]));
+        assert_eq!(
+            info.config,
+            pairs(&[("host", "localhost"), ("port", "8080")])
+        );
+        assert_eq!(info.engines, pairs(&[("node", ">=18")]));
+    }
+
+    #[test]
+    fn test_project_info_minimal() {
+        let info = project_info_from_json(r#"{ "exports": { "import": "./a.js" } }"#).unwrap();
+        assert!(!info.is_module);
+        assert_eq!(info.exports, pairs(&[(".", "import")]));
+        assert!(info.bin.is_empty());
+        assert!(project_info_from_json("[").is_err());
+    }
+}
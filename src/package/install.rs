@@ -0,0 +1,129 @@
+//! Installed dependencies.
+//!
+//! Scripts usually fail right away when the project's dependencies were
+//! never installed, e.g. in a fresh clone. Before running, nrs checks for
+//! `node_modules` (or pnpm's virtual store, or Yarn Plug'n'Play files) in
+//! the project or a directory above it, so a workspace package sharing the
+//! monorepo root's `node_modules` counts as installed.
+
+use std::fs;
+use std::path::Path;
+
+use super::manager::{detect_runner, Runner};
+
+/// Yarn Plug'n'Play files, used instead of `node_modules`.
+const PNP_FILES: &[&str] = &[".pnp.cjs", ".pnp.js"];
+
+/// Check if a project has dependencies that aren't installed.
+///
+/// Projects without dependencies or workspaces never need an install, and
+/// neither do deno projects, since deno fetches dependencies on first use.
+pub fn needs_install(project_dir: &Path, runner: Runner) -> bool {
+    if runner == Runner::Deno || !has_dependencies(project_dir) {
+        return false;
+    }
+    !project_dir
+        .ancestors()
+        .any(|dir| is_installed_in(dir, runner))
+}
+
+/// Get the package manager that installs a project's dependencies.
+///
+/// Task runners don't install anything, so the project's package manager is
+/// detected instead.
+pub fn install_runner(runner: Runner, project_dir: &Path) -> Runner {
+    if runner.is_task_runner() {
+        detect_runner(project_dir)
+    } else {
+        runner
+    }
+}
+
+/// Check if package.json declares dependencies or workspaces to install.
+fn has_dependencies(project_dir: &Path) -> bool {
+    if project_dir.join("pnpm-workspace.yaml").exists() {
+        return true;
+    }
+    let Ok(content) = fs::read_to_string(project_dir.join("package.json")) else {
+        return false;
+    };
+    let Ok(package) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return false;
+    };
+    let declares = |field: &str| match &package[field] {
+        serde_json::Value::Object(map) => !map.is_empty(),
+        serde_json::Value::Array(list) => !list.is_empty(),
+        _ => false,
+    };
+    [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "workspaces",
+    ]
+    .into_iter()
+    .any(declares)
+}
+
+/// Check if dependencies were installed in `dir` by `runner`.
+fn is_installed_in(dir: &Path, runner: Runner) -> bool {
+    match runner {
+        Runner::Pnpm => dir.join("node_modules").join(".pnpm").is_dir(),
+        Runner::Yarn => {
+            PNP_FILES.iter().any(|file| dir.join(file).is_file())
+                || dir.join("node_modules").is_dir()
+        }
+        _ => dir.join("node_modules").is_dir(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project(package_json: &str) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("package.json"), package_json).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_needs_install() {
+        let dir = project(r#"{"dependencies": {"react": "^18.0.0"}}"#);
+        assert!(needs_install(dir.path(), Runner::Npm));
+        assert!(!needs_install(dir.path(), Runner::Deno));
+
+        fs::create_dir(dir.path().join("node_modules")).unwrap();
+        assert!(!needs_install(dir.path(), Runner::Npm));
+        // pnpm installs leave its virtual store behind
+        assert!(needs_install(dir.path(), Runner::Pnpm));
+        fs::create_dir(dir.path().join("node_modules/.pnpm")).unwrap();
+        assert!(!needs_install(dir.path(), Runner::Pnpm));
+    }
+
+    #[test]
+    fn test_needs_install_without_dependencies() {
+        let dir = project(r#"{"scripts": {"build": "tsc"}, "dependencies": {}}"#);
+        assert!(!needs_install(dir.path(), Runner::Npm));
+
+        let dir = project(r#"{"workspaces": ["packages/*"]}"#);
+        assert!(needs_install(dir.path(), Runner::Npm));
+    }
+
+    #[test]
+    fn test_workspace_uses_root_install() {
+        let root = project(r#"{"workspaces": ["packages/*"]}"#);
+        let package = root.path().join("packages/app");
+        fs::create_dir_all(&package).unwrap();
+        fs::write(
+            package.join("package.json"),
+            r#"{"devDependencies": {"vite": "^5.0.0"}}"#,
+        )
+        .unwrap();
+        assert!(needs_install(&package, Runner::Yarn));
+
+        fs::write(root.path().join(".pnp.cjs"), "").unwrap();
+        assert!(!needs_install(&package, Runner::Yarn));
+    }
+}
@@ -7,10 +7,11 @@
 
 use std::path::Path;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Supported package managers.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Runner {
     /// Node Package Manager (npm)
@@ -22,6 +23,12 @@ pub enum Runner {
     Pnpm,
     /// Bun - Fast all-in-one JavaScript runtime
     Bun,
+    /// Deno - runs deno.json tasks and package.json scripts
+    Deno,
+    /// Turborepo - runs turbo.json tasks (a task runner, not a package manager)
+    Turbo,
+    /// Nx - runs nx.json targets (a task runner, not a package manager)
+    Nx,
 }
 
 impl Runner {
@@ -32,9 +39,17 @@ impl Runner {
             Runner::Yarn => "yarn",
             Runner::Pnpm => "pnpm",
             Runner::Bun => "bun",
+            Runner::Deno => "deno",
+            Runner::Turbo => "turbo",
+            Runner::Nx => "nx",
         }
     }
 
+    /// Check if this is a monorepo task runner rather than a package manager.
+    pub fn is_task_runner(&self) -> bool {
+        matches!(self, Runner::Turbo | Runner::Nx)
+    }
+
     /// Get the base run command (without script name).
     ///
     /// Returns the command prefix used to run scripts:
@@ -42,12 +57,18 @@ impl Runner {
     /// - yarn: "yarn"
     /// - pnpm: "pnpm"
     /// - bun: "bun run"
+    /// - deno: "deno task"
+    /// - turbo: "turbo run"
+    /// - nx: "nx run-many -t"
     pub fn run_prefix(&self) -> &'static str {
         match self {
             Runner::Npm => "npm run",
             Runner::Yarn => "yarn",
             Runner::Pnpm => "pnpm",
             Runner::Bun => "bun run",
+            Runner::Deno => "deno task",
+            Runner::Turbo => "turbo run",
+            Runner::Nx => "nx run-many -t",
         }
     }
 
@@ -70,6 +91,9 @@ impl Runner {
             Runner::Yarn => vec!["yarn".into(), script.into()],
             Runner::Pnpm => vec!["pnpm".into(), script.into()],
             Runner::Bun => vec!["bun".into(), "run".into(), script.into()],
+            Runner::Deno => vec!["deno".into(), "task".into(), script.into()],
+            Runner::Turbo => vec!["turbo".into(), "run".into(), script.into()],
+            Runner::Nx => vec!["nx".into(), "run-many".into(), "-t".into(), script.into()],
         }
     }
 
@@ -93,8 +117,8 @@ impl Runner {
         let mut cmd = self.run_command(script);
 
         if !args.is_empty() {
-            // npm and pnpm require -- before args to pass them to the script
-            if matches!(self, Runner::Npm | Runner::Pnpm) {
+            // npm, pnpm and turbo require -- before args to pass them to the script
+            if matches!(self, Runner::Npm | Runner::Pnpm | Runner::Turbo) {
                 cmd.push("--".into());
             }
             cmd.extend(args.iter().cloned());
@@ -141,6 +165,9 @@ impl Runner {
             Runner::Yarn => "yarn",
             Runner::Pnpm => "pnpm",
             Runner::Bun => "bun",
+            Runner::Deno => "deno",
+            Runner::Turbo => "turbo",
+            Runner::Nx => "nx",
         }
     }
 
@@ -151,22 +178,55 @@ impl Runner {
             Runner::Yarn => "\u{1F9F6}", // 🧶 yarn
             Runner::Pnpm => "\u{1F4C0}", // 📀 disc
             Runner::Bun => "\u{1F95F}",  // 🥟 dumpling
+            Runner::Deno => "\u{1F995}", // 🦕 sauropod
+            Runner::Turbo => "\u{26A1}", // ⚡ high voltage
+            Runner::Nx => "\u{1F537}",   // 🔷 blue diamond
         }
     }
 
     /// Get the lock file name for this runner.
+    ///
+    /// Task runners have no lock file and give their config file instead.
     pub fn lock_file(&self) -> &'static str {
         match self {
             Runner::Npm => "package-lock.json",
             Runner::Yarn => "yarn.lock",
             Runner::Pnpm => "pnpm-lock.yaml",
             Runner::Bun => "bun.lockb",
+            Runner::Deno => "deno.lock",
+            Runner::Turbo => "turbo.json",
+            Runner::Nx => "nx.json",
         }
     }
 
-    /// Get all supported runners.
+    /// Get the command that installs a project's dependencies.
+    ///
+    /// Task runners don't install anything and return `None`.
+    pub fn install_command(&self) -> Option<Vec<String>> {
+        if self.is_task_runner() {
+            return None;
+        }
+        Some(vec![self.executable().to_string(), "install".to_string()])
+    }
+
+    /// Get all supported package managers.
+    ///
+    /// Task runners aren't included: they only run their own tasks.
     pub fn all() -> &'static [Runner] {
-        &[Runner::Npm, Runner::Yarn, Runner::Pnpm, Runner::Bun]
+        &[
+            Runner::Npm,
+            Runner::Yarn,
+            Runner::Pnpm,
+            Runner::Bun,
+            Runner::Deno,
+        ]
+    }
+
+    /// Get the next runner in [`Runner::all`] order, wrapping around.
+    pub fn next(self) -> Runner {
+        let all = Self::all();
+        let index = all.iter().position(|&r| r == self).unwrap_or(0);
+        all[(index + 1) % all.len()]
     }
 
     /// Get the command to run a workspace script.
@@ -203,6 +263,21 @@ impl Runner {
                 workspace.into(),
                 script.into(),
             ],
+            Runner::Deno => vec![
+                "deno".into(),
+                "task".into(),
+                "--filter".into(),
+                workspace.into(),
+                script.into(),
+            ],
+            Runner::Turbo => vec![
+                "turbo".into(),
+                "run".into(),
+                script.into(),
+                "--filter".into(),
+                workspace.into(),
+            ],
+            Runner::Nx => vec!["nx".into(), "run".into(), format!("{workspace}:{script}")],
         }
     }
 
@@ -222,8 +297,8 @@ impl Runner {
         let mut cmd = self.workspace_command(workspace, script);
 
         if !args.is_empty() {
-            // npm and pnpm require -- before args
-            if matches!(self, Runner::Npm | Runner::Pnpm) {
+            // npm, pnpm and turbo require -- before args
+            if matches!(self, Runner::Npm | Runner::Pnpm | Runner::Turbo) {
                 cmd.push("--".into());
             }
             cmd.extend(args.iter().cloned());
@@ -264,8 +339,9 @@ impl std::str::FromStr for Runner {
             "yarn" => Ok(Runner::Yarn),
             "pnpm" => Ok(Runner::Pnpm),
             "bun" => Ok(Runner::Bun),
+            "deno" => Ok(Runner::Deno),
             _ => Err(format!(
-                "Unknown package manager: '{s}'. Valid options are: npm, yarn, pnpm, bun"
+                "Unknown package manager: '{s}'. Valid options are: npm, yarn, pnpm, bun, deno"
             )),
         }
     }
@@ -280,6 +356,7 @@ impl std::str::FromStr for Runner {
 ///    - `pnpm-lock.yaml` → pnpm
 ///    - `yarn.lock` → Yarn
 ///    - `package-lock.json` → npm
+///    - `deno.lock` → Deno
 /// 3. Fallback to npm
 ///
 /// # Arguments
@@ -349,6 +426,12 @@ pub fn detect_runner_reason(project_dir: &Path) -> (Runner, String) {
         return (Runner::Npm, format!("found {}", npm_lock.display()));
     }
 
+    // Last, as Deno projects with Node tooling often have a Node lock file too
+    let deno_lock = project_dir.join("deno.lock");
+    if deno_lock.exists() {
+        return (Runner::Deno, format!("found {}", deno_lock.display()));
+    }
+
     // Priority 3: Fallback to npm
     (Runner::Npm, "default (no lock file found)".to_string())
 }
@@ -402,6 +485,7 @@ mod tests {
         assert_eq!("yarn".parse::<Runner>().unwrap(), Runner::Yarn);
         assert_eq!("pnpm".parse::<Runner>().unwrap(), Runner::Pnpm);
         assert_eq!("bun".parse::<Runner>().unwrap(), Runner::Bun);
+        assert_eq!("deno".parse::<Runner>().unwrap(), Runner::Deno);
     }
 
     #[test]
@@ -419,6 +503,15 @@ mod tests {
         assert!(result.unwrap_err().contains("Unknown package manager"));
     }
 
+    #[test]
+    fn test_runner_install_command() {
+        assert_eq!(
+            Runner::Pnpm.install_command(),
+            Some(vec!["pnpm".to_string(), "install".to_string()])
+        );
+        assert_eq!(Runner::Turbo.install_command(), None);
+    }
+
     #[test]
     fn test_runner_display() {
         assert_eq!(format!("{}", Runner::Npm), "npm");
@@ -446,11 +539,19 @@ mod tests {
     #[test]
     fn test_runner_all() {
         let all = Runner::all();
-        assert_eq!(all.len(), 4);
+        assert_eq!(all.len(), 5);
         assert!(all.contains(&Runner::Npm));
         assert!(all.contains(&Runner::Yarn));
         assert!(all.contains(&Runner::Pnpm));
         assert!(all.contains(&Runner::Bun));
+        assert!(all.contains(&Runner::Deno));
+    }
+
+    #[test]
+    fn test_runner_next_wraps() {
+        assert_eq!(Runner::Npm.next(), Runner::Yarn);
+        assert_eq!(Runner::Pnpm.next(), Runner::Bun);
+        assert_eq!(Runner::Deno.next(), Runner::Npm);
     }
 
     // ==================== Run command tests ====================
@@ -485,6 +586,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_run_command_deno() {
+        assert_eq!(Runner::Deno.run_command("dev"), vec!["deno", "task", "dev"]);
+        let args = vec!["--watch".to_string()];
+        assert_eq!(
+            Runner::Deno.run_command_with_args("test", &args),
+            vec!["deno", "task", "test", "--watch"]
+        );
+    }
+
     // ==================== Run command with args tests ====================
 
     #[test]
@@ -677,6 +788,18 @@ mod tests {
         assert_eq!(detect_runner(temp.path()), Runner::Npm);
     }
 
+    #[test]
+    fn test_detect_from_deno_lock() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("package.json"), "{}").unwrap();
+        fs::write(temp.path().join("deno.lock"), "{}").unwrap();
+        assert_eq!(detect_runner(temp.path()), Runner::Deno);
+
+        // A Node lock file wins
+        fs::write(temp.path().join("pnpm-lock.yaml"), "").unwrap();
+        assert_eq!(detect_runner(temp.path()), Runner::Pnpm);
+    }
+
     #[test]
     fn test_detect_fallback_to_npm() {
         let temp = TempDir::new().unwrap();
@@ -0,0 +1,142 @@
+//! Script matrix of a monorepo.
+//!
+//! `nrs matrix` shows which workspaces define which scripts, one row per
+//! workspace, so packages lacking a `test` or `lint` stand out.
+
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use super::types::is_lifecycle_script;
+use super::workspace::Workspace;
+use crate::output::Report;
+
+/// Most scripts shown when none are picked.
+pub const MATRIX_SCRIPTS: usize = 12;
+
+/// Which workspaces define which scripts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptMatrix {
+    /// Script names, one per column.
+    pub scripts: Vec<String>,
+    /// Workspaces, one per row.
+    pub rows: Vec<MatrixRow>,
+}
+
+/// A workspace's row in the matrix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixRow {
+    /// The workspace name.
+    pub workspace: String,
+    /// Whether the workspace defines each script, in column order.
+    pub defined: Vec<bool>,
+}
+
+impl ScriptMatrix {
+    /// Build the matrix of `workspaces` for the given scripts.
+    ///
+    /// Without scripts, the columns are the scripts more than one workspace
+    /// defines (or all of them with a single workspace), most common first,
+    /// up to [`MATRIX_SCRIPTS`]. Lifecycle scripts are left out.
+    pub fn new(workspaces: &[&Workspace], scripts: &[String]) -> Self {
+        let scripts = if scripts.is_empty() {
+            common_scripts(workspaces)
+        } else {
+            scripts.to_vec()
+        };
+        let rows = workspaces
+            .iter()
+            .map(|workspace| MatrixRow {
+                workspace: workspace.name().to_string(),
+                defined: scripts
+                    .iter()
+                    .map(|name| workspace.scripts().iter().any(|s| s.name() == name))
+                    .collect(),
+            })
+            .collect();
+        Self { scripts, rows }
+    }
+
+    /// Get a row per workspace, with whether it defines each script, for
+    /// `nrs matrix --format`.
+    pub fn report(&self) -> Report {
+        let mut report = Report::new(
+            std::iter::once("workspace").chain(self.scripts.iter().map(String::as_str)),
+        );
+        for row in &self.rows {
+            let defined = row.defined.iter().map(|defined| json!(defined));
+            report.push(std::iter::once(json!(row.workspace)).chain(defined));
+        }
+        report
+    }
+
+    /// Count the workspaces missing each script, in column order.
+    pub fn missing(&self) -> Vec<usize> {
+        (0..self.scripts.len())
+            .map(|column| self.rows.iter().filter(|row| !row.defined[column]).count())
+            .collect()
+    }
+}
+
+/// Get the scripts shared by workspaces, most common first.
+fn common_scripts(workspaces: &[&Workspace]) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for workspace in workspaces {
+        for script in workspace.scripts() {
+            if !is_lifecycle_script(script.name()) {
+                *counts.entry(script.name()).or_default() += 1;
+            }
+        }
+    }
+    let shared = if workspaces.len() > 1 { 2 } else { 1 };
+    let mut common: Vec<(&str, usize)> = counts
+        .into_iter()
+        .filter(|&(_, count)| count >= shared)
+        .collect();
+    common.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    common
+        .into_iter()
+        .take(MATRIX_SCRIPTS)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::Script;
+
+    fn workspace(name: &str, scripts: &[&str]) -> Workspace {
+        Workspace::with_scripts(
+            name,
+            format!("packages/{name}"),
+            scripts.iter().map(|s| Script::new(*s, "true")).collect(),
+        )
+    }
+
+    #[test]
+    fn test_common_scripts_as_columns() {
+        let web = workspace("web", &["build", "test", "lint", "dev", "postinstall"]);
+        let api = workspace("api", &["build", "test", "start", "postinstall"]);
+        let docs = workspace("docs", &["build", "dev"]);
+        let matrix = ScriptMatrix::new(&[&web, &api, &docs], &[]);
+
+        assert_eq!(matrix.scripts, ["build", "dev", "test"]);
+        assert_eq!(matrix.rows[0].workspace, "web");
+        assert_eq!(matrix.rows[1].defined, [true, false, true]);
+        assert_eq!(matrix.rows[2].defined, [true, true, false]);
+        assert_eq!(matrix.missing(), [0, 1, 1]);
+    }
+
+    #[test]
+    fn test_picked_scripts_as_columns() {
+        let web = workspace("web", &["build", "test"]);
+        let api = workspace("api", &["build"]);
+        let picked = ["lint".to_string(), "test".to_string()];
+        let matrix = ScriptMatrix::new(&[&web, &api], &picked);
+
+        assert_eq!(matrix.scripts, ["lint", "test"]);
+        assert_eq!(matrix.rows[0].defined, [false, true]);
+        assert_eq!(matrix.missing(), [2, 1]);
+    }
+}
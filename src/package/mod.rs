@@ -2,20 +2,51 @@
 //!
 //! Handles package.json parsing, script extraction, and package manager detection.
 
+mod activity;
+mod deno;
 mod descriptions;
+mod diff;
+mod edit;
+mod excerpt;
+mod graph;
+mod info;
+mod install;
 mod manager;
+mod matrix;
 pub mod scripts;
+mod suggest;
+mod taskrunner;
 mod types;
 mod workspace;
 
-pub use descriptions::{extract_descriptions, get_description, get_short_description};
+pub use activity::{GitActivity, GIT_ACTIVITY_BOOST};
+pub use deno::{find_deno_config, parse_deno_tasks, parse_deno_tasks_from_json, DENO_CONFIG_FILES};
+pub use descriptions::{
+    extract_descriptions, extract_descriptions_from, get_description, get_short_description,
+    DescriptionSource,
+};
+pub use diff::{diff_scripts, format_scripts_diff, scripts_at_ref, ScriptsDiff};
+pub use edit::{add_script, edit_script, insert_script, preview_edit, PreviewLine, ScriptEdit};
+pub use excerpt::{excerpt_from_source, script_excerpt, Excerpt, SourceLine};
+pub use graph::{Dependency, EdgeKind, ScriptGraph, TreeLine};
+pub use info::{project_info, project_info_from_json, ProjectInfo};
+pub use install::{install_runner, needs_install};
 pub use manager::{detect_runner, detect_runner_reason, has_lock_file, Runner};
+pub use matrix::{MatrixRow, ScriptMatrix, MATRIX_SCRIPTS};
 pub use scripts::{
     parse_package_json, parse_scripts, parse_scripts_from_json, parse_scripts_required,
+    parse_scripts_with_limits, ParseLimits,
+};
+pub use suggest::{suggest_scripts, suggest_scripts_from_json, SuggestedScript};
+pub use taskrunner::{
+    parse_nx_targets_from_json, parse_task_runner_tasks, parse_turbo_tasks_from_json,
 };
 pub use types::{
-    is_lifecycle_script, NtlConfig, Package, Script, Scripts, WorkspacesConfig, LIFECYCLE_SCRIPTS,
+    is_lifecycle_script, NtlConfig, Package, ParentScript, Script, ScriptAlias, ScriptGroup,
+    Scripts, WorkspacesConfig, LIFECYCLE_SCRIPTS,
 };
 pub use workspace::{
-    detect_workspace_info, detect_workspaces, is_monorepo, Workspace, WorkspaceInfo, WorkspaceType,
+    all_workspaces, describe_monorepo, detect_monorepo_tools, detect_workspace_info,
+    detect_workspaces, exclude_workspaces, find_parent_monorepo, find_workspace, is_monorepo,
+    parent_scripts, MonorepoTool, Workspace, WorkspaceInfo, WorkspaceResolution, WorkspaceType,
 };
@@ -1,29 +1,164 @@
 //! Script parsing from package.json.
 
+use std::io::{self, Read};
 use std::path::Path;
 
 use anyhow::{bail, Context, Result};
 
-use super::descriptions::extract_descriptions;
+use tracing::debug;
+
+use super::deno::parse_deno_tasks;
+use super::descriptions::{extract_descriptions_from, DescriptionSource};
+use super::taskrunner::parse_task_runner_tasks;
 use super::types::{Package, Script, Scripts};
+use crate::error::NrsError;
+
+/// Limits on the package.json read at startup.
+///
+/// They keep a pathological package.json from stalling startup or using
+/// unbounded memory. `--force` lifts them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Largest package.json to read, in bytes.
+    pub max_bytes: u64,
+    /// Most scripts to list.
+    pub max_scripts: usize,
+}
+
+impl ParseLimits {
+    /// Default size limit (10 MB).
+    pub const MAX_BYTES: u64 = 10 * 1024 * 1024;
+    /// Default script limit.
+    pub const MAX_SCRIPTS: usize = 10_000;
+
+    /// No limits at all.
+    pub fn unlimited() -> Self {
+        Self {
+            max_bytes: u64::MAX,
+            max_scripts: usize::MAX,
+        }
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: Self::MAX_BYTES,
+            max_scripts: Self::MAX_SCRIPTS,
+        }
+    }
+}
 
 /// Parse a package.json file from a directory.
 ///
+/// Tasks from a `deno.json` or `deno.jsonc`, a `turbo.json` or an `nx.json`
+/// in the same directory are added too. A package.json script wins over a
+/// task of the same name.
+///
 /// # Arguments
 ///
 /// * `project_dir` - The directory containing package.json
+/// * `sources` - Where descriptions are read from, highest priority first
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The package.json file cannot be read
 /// - The JSON is malformed
-pub fn parse_scripts(project_dir: &Path) -> Result<Scripts> {
+/// - The Deno, Turborepo or Nx config is malformed
+/// - The package.json is over the default [`ParseLimits`]
+pub fn parse_scripts(project_dir: &Path, sources: &[DescriptionSource]) -> Result<Scripts> {
+    parse_scripts_with_limits(project_dir, ParseLimits::default(), sources)
+}
+
+/// Parse a package.json file from a directory within the given limits.
+///
+/// The file is read no further than the size limit, so an oversized one
+/// fails fast instead of being loaded whole.
+///
+/// # Errors
+///
+/// Returns the errors of [`parse_scripts`], with the limits given here.
+pub fn parse_scripts_with_limits(
+    project_dir: &Path,
+    limits: ParseLimits,
+    sources: &[DescriptionSource],
+) -> Result<Scripts> {
     let package_json = project_dir.join("package.json");
-    let content = std::fs::read_to_string(&package_json)
-        .with_context(|| format!("Failed to read {}", package_json.display()))?;
+    let content = read_limited(&package_json, limits.max_bytes)?;
+
+    let package = parse_package_json(&content)?;
+    let mut scripts = extract_scripts_from_package(&package, sources)?;
+    if scripts.len() > limits.max_scripts {
+        return Err(NrsError::TooManyScripts {
+            path: package_json,
+            count: scripts.len(),
+            limit: limits.max_scripts,
+        }
+        .into());
+    }
+    if let Some(tasks) = parse_deno_tasks(project_dir)? {
+        merge_tasks(&mut scripts, tasks);
+    }
+    if let Some(tasks) = parse_task_runner_tasks(project_dir)? {
+        merge_tasks(&mut scripts, tasks);
+    }
+    Ok(scripts)
+}
+
+/// Read a file, failing once it goes past `max_bytes`.
+fn read_limited(path: &Path, max_bytes: u64) -> Result<String> {
+    let file = std::fs::File::open(path).map_err(|err| NrsError::UnreadablePackageJson {
+        path: path.to_path_buf(),
+        reason: unreadable_reason(path, &err),
+    })?;
+
+    let mut bytes = Vec::new();
+    file.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    if bytes.len() as u64 > max_bytes {
+        return Err(NrsError::PackageJsonTooLarge {
+            path: path.to_path_buf(),
+            limit_mb: max_bytes / (1024 * 1024),
+        }
+        .into());
+    }
+    String::from_utf8(bytes).with_context(|| format!("{} is not valid UTF-8", path.display()))
+}
+
+/// Explain why a file that exists couldn't be opened.
+///
+/// Broken symlinks and missing permissions get a plain description, other
+/// errors are shown as the OS reports them.
+pub fn unreadable_reason(path: &Path, err: &io::Error) -> String {
+    let is_symlink = path
+        .symlink_metadata()
+        .is_ok_and(|metadata| metadata.file_type().is_symlink());
+    if is_symlink && err.kind() == io::ErrorKind::NotFound {
+        return "broken symlink".to_string();
+    }
+    match err.kind() {
+        io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+        _ => err.to_string(),
+    }
+}
 
-    parse_scripts_from_json(&content)
+/// Add tasks to package.json scripts, skipping names already taken.
+fn merge_tasks(scripts: &mut Scripts, tasks: Scripts) {
+    for task in tasks.iter() {
+        if scripts.get(task.name()).is_some() {
+            debug!(
+                "{} task {} is shadowed by a script",
+                task.runner().unwrap_or_default(),
+                task.name()
+            );
+            continue;
+        }
+        scripts.add(task.clone());
+    }
+    scripts.sort_alphabetically();
 }
 
 /// Parse the full package.json structure.
@@ -70,7 +205,7 @@ pub fn parse_package_json(content: &str) -> Result<Package> {
 /// ```
 pub fn parse_scripts_from_json(content: &str) -> Result<Scripts> {
     let package = parse_package_json(content)?;
-    extract_scripts_from_package(&package)
+    extract_scripts_from_package(&package, &DescriptionSource::ALL)
 }
 
 /// Parse scripts from package.json content, returning error if no scripts exist.
@@ -90,10 +225,12 @@ pub fn parse_scripts_required(content: &str) -> Result<Scripts> {
     Ok(scripts)
 }
 
-/// Extract scripts from a parsed Package.
-pub fn extract_scripts_from_package(package: &Package) -> Result<Scripts> {
-    // Extract descriptions from various sources
-    let descriptions = extract_descriptions(package);
+/// Extract scripts from a parsed Package, with descriptions from `sources`.
+pub fn extract_scripts_from_package(
+    package: &Package,
+    sources: &[DescriptionSource],
+) -> Result<Scripts> {
+    let descriptions = extract_descriptions_from(package, sources);
 
     let mut scripts = Scripts::new();
 
@@ -139,6 +276,7 @@ fn format_json_error(content: &str, error: &serde_json::Error) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::package::Runner;
 
     #[test]
     fn test_parse_basic_scripts() {
@@ -156,6 +294,50 @@ mod tests {
         assert_eq!(scripts.get("dev").unwrap().command(), "vite");
     }
 
+    #[test]
+    fn test_parse_scripts_merges_deno_tasks() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("package.json"),
+            r#"{"scripts": {"dev": "vite", "build": "vite build"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("deno.json"),
+            r#"{"tasks": {"dev": "deno run main.ts", "fmt": "deno fmt"}}"#,
+        )
+        .unwrap();
+
+        let scripts = parse_scripts(temp.path(), &DescriptionSource::ALL).unwrap();
+        let names: Vec<&str> = scripts.iter().map(|s| s.name()).collect();
+        assert_eq!(names, ["build", "dev", "fmt"]);
+        // package.json wins on name clashes
+        assert_eq!(scripts.get("dev").unwrap().command(), "vite");
+        assert_eq!(scripts.get("dev").unwrap().runner(), None);
+        assert_eq!(scripts.get("fmt").unwrap().runner(), Some(Runner::Deno));
+    }
+
+    #[test]
+    fn test_parse_scripts_reads_given_description_sources() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("package.json"),
+            r#"{
+                "scripts": {"dev": "vite", "//dev": "From a comment"},
+                "scripts-info": {"dev": "From scripts-info"}
+            }"#,
+        )
+        .unwrap();
+
+        let scripts = parse_scripts(temp.path(), &[DescriptionSource::Comments]).unwrap();
+        assert_eq!(
+            scripts.get("dev").unwrap().description(),
+            Some("From a comment")
+        );
+        let scripts = parse_scripts(temp.path(), &[]).unwrap();
+        assert_eq!(scripts.get("dev").unwrap().description(), None);
+    }
+
     #[test]
     fn test_parse_empty_scripts() {
         let json = r#"{
@@ -460,6 +642,69 @@ mod tests {
         assert!(scripts.get("script_999").is_some());
     }
 
+    #[test]
+    fn test_parse_scripts_with_limits() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("package.json"),
+            r#"{"scripts": {"a": "echo a", "b": "echo b", "c": "echo c"}}"#,
+        )
+        .unwrap();
+
+        let too_many = ParseLimits {
+            max_scripts: 2,
+            ..ParseLimits::default()
+        };
+        let err =
+            parse_scripts_with_limits(temp.path(), too_many, &DescriptionSource::ALL).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NrsError>(),
+            Some(NrsError::TooManyScripts {
+                count: 3,
+                limit: 2,
+                ..
+            })
+        ));
+
+        let too_large = ParseLimits {
+            max_bytes: 16,
+            ..ParseLimits::default()
+        };
+        let err =
+            parse_scripts_with_limits(temp.path(), too_large, &DescriptionSource::ALL).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NrsError>(),
+            Some(NrsError::PackageJsonTooLarge { .. })
+        ));
+
+        let scripts = parse_scripts_with_limits(
+            temp.path(),
+            ParseLimits::unlimited(),
+            &DescriptionSource::ALL,
+        )
+        .unwrap();
+        assert_eq!(scripts.len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_broken_symlink() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::os::unix::fs::symlink(
+            temp.path().join("missing.json"),
+            temp.path().join("package.json"),
+        )
+        .unwrap();
+
+        let err = parse_scripts(temp.path(), &DescriptionSource::ALL).unwrap_err();
+        match err.downcast_ref::<NrsError>() {
+            Some(NrsError::UnreadablePackageJson { reason, .. }) => {
+                assert_eq!(reason, "broken symlink");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
     #[test]
     fn test_parse_special_characters_in_command() {
         let json = r#"{
@@ -0,0 +1,122 @@
+//! Standard scripts for the tools a project depends on.
+//!
+//! Looks at `dependencies` and `devDependencies` for common tools and
+//! proposes the scripts projects usually define for them, like `lint` for
+//! eslint. Scripts the project already has, by name or by command, are left
+//! out.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+/// Scripts proposed for each tool: dependency, script name and command.
+///
+/// When two tools propose the same name, the first one listed wins.
+const TEMPLATES: &[(&str, &str, &str)] = &[
+    ("vite", "dev", "vite"),
+    ("vite", "build", "vite build"),
+    ("vite", "preview", "vite preview"),
+    ("jest", "test", "jest"),
+    ("eslint", "lint", "eslint ."),
+    ("prettier", "format", "prettier --write ."),
+    ("typescript", "typecheck", "tsc --noEmit"),
+];
+
+/// A script a project is missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuggestedScript {
+    /// Script name.
+    pub name: String,
+    /// Command it runs.
+    pub command: String,
+    /// Dependency it was suggested for.
+    pub dependency: String,
+}
+
+/// Suggest scripts for the package.json in `dir`.
+///
+/// # Errors
+///
+/// Returns an error if package.json can't be read or isn't valid JSON.
+pub fn suggest_scripts(dir: &Path) -> Result<Vec<SuggestedScript>> {
+    let path = dir.join("package.json");
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    suggest_scripts_from_json(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Suggest scripts for package.json content.
+///
+/// # Errors
+///
+/// Returns an error if the content isn't valid JSON.
+pub fn suggest_scripts_from_json(content: &str) -> Result<Vec<SuggestedScript>> {
+    let json: Value = serde_json::from_str(content)?;
+    let depends_on = |dependency: &str| {
+        ["dependencies", "devDependencies"]
+            .iter()
+            .any(|section| json[*section].get(dependency).is_some())
+    };
+    let scripts = json["scripts"].as_object();
+    let has_script = |name: &str, command: &str| {
+        scripts.into_iter().flatten().any(|(existing, value)| {
+            existing == name || value.as_str().map(str::trim) == Some(command)
+        })
+    };
+
+    let mut suggestions: Vec<SuggestedScript> = Vec::new();
+    for (dependency, name, command) in TEMPLATES {
+        let taken = suggestions.iter().any(|s| s.name == *name);
+        if taken || !depends_on(dependency) || has_script(name, command) {
+            continue;
+        }
+        suggestions.push(SuggestedScript {
+            name: (*name).to_string(),
+            command: (*command).to_string(),
+            dependency: (*dependency).to_string(),
+        });
+    }
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_scripts_from_json() {
+        let json = r#"{
+            "scripts": {
+                "dev": "vite --host",
+                "check": "tsc --noEmit"
+            },
+            "dependencies": { "vue": "^3.4.0" },
+            "devDependencies": {
+                "vite": "^5.0.0",
+                "eslint": "^8.0.0",
+                "typescript": "^5.3.0"
+            }
+        }"#;
+
+        let suggestions = suggest_scripts_from_json(json).unwrap();
+        let names: Vec<&str> = suggestions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["build", "preview", "lint"]);
+        assert_eq!(suggestions[2].command, "eslint .");
+        assert_eq!(suggestions[2].dependency, "eslint");
+    }
+
+    #[test]
+    fn test_suggest_scripts_without_tools() {
+        let json = r#"{ "name": "app", "dependencies": { "jest": "^29.0.0" } }"#;
+        let suggestions = suggest_scripts_from_json(json).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].name, "test");
+
+        assert!(suggest_scripts_from_json(r#"{ "name": "app" }"#)
+            .unwrap()
+            .is_empty());
+        assert!(suggest_scripts_from_json("{").is_err());
+    }
+}
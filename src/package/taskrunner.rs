@@ -0,0 +1,200 @@
+//! Turborepo and Nx task discovery.
+//!
+//! Lists the tasks of a `turbo.json` and the target defaults of an `nx.json`
+//! next to package.json scripts. They run through `turbo run <task>` and
+//! `nx run-many -t <target>` so the tools' caching and task ordering apply.
+//! `nx run` needs a project, so Nx targets run in every project that has
+//! them, as `turbo run` does.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use super::deno::strip_jsonc;
+use super::manager::Runner;
+use super::types::{Script, Scripts};
+use super::workspace::{detect_monorepo_tools, MonorepoTool};
+
+/// Parse the tasks of the task runners configured in a directory.
+///
+/// Returns `None` if there is no `turbo.json` or `nx.json`. When both
+/// define a task, the Turborepo one is kept.
+///
+/// # Errors
+///
+/// Returns an error if a config file can't be read or parsed.
+pub fn parse_task_runner_tasks(dir: &Path) -> Result<Option<Scripts>> {
+    let tools = detect_monorepo_tools(dir);
+    if tools.is_empty() {
+        return Ok(None);
+    }
+
+    let mut tasks = Scripts::new();
+    for tool in tools {
+        let path = dir.join(tool.config_file());
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let parsed = match tool {
+            MonorepoTool::Turbo => parse_turbo_tasks_from_json(&content),
+            MonorepoTool::Nx => parse_nx_targets_from_json(&content),
+        }
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        for task in parsed {
+            if tasks.get(task.name()).is_none() {
+                tasks.add(task);
+            }
+        }
+    }
+    Ok(Some(tasks))
+}
+
+/// Parse the tasks from turbo.json content.
+///
+/// Reads `tasks` (Turborepo 2) or `pipeline` (Turborepo 1). Package-specific
+/// entries like `web#build` and root tasks like `//#lint` configure the
+/// `build` and `lint` tasks, so they are listed under those names.
+///
+/// # Errors
+///
+/// Returns an error if the content isn't valid JSON with comments.
+pub fn parse_turbo_tasks_from_json(content: &str) -> Result<Scripts> {
+    let json: Value = serde_json::from_str(&strip_jsonc(content))?;
+    let tasks = json
+        .get("tasks")
+        .or_else(|| json.get("pipeline"))
+        .and_then(Value::as_object);
+
+    let mut scripts = Scripts::new();
+    for (key, task) in tasks.into_iter().flatten() {
+        let name = key.rsplit('#').next().unwrap_or(key);
+        if name.is_empty() || scripts.get(name).is_some() {
+            continue;
+        }
+        scripts.add(task_script(name, task, Runner::Turbo));
+    }
+
+    scripts.sort_alphabetically();
+    Ok(scripts)
+}
+
+/// Parse the targets from nx.json content.
+///
+/// Only `targetDefaults` keyed by target name are listed; defaults keyed by
+/// executor (`@nx/vite:build`) don't name a target.
+///
+/// # Errors
+///
+/// Returns an error if the content isn't valid JSON.
+pub fn parse_nx_targets_from_json(content: &str) -> Result<Scripts> {
+    let json: Value = serde_json::from_str(content)?;
+    let targets = json.get("targetDefaults").and_then(Value::as_object);
+
+    let mut scripts = Scripts::new();
+    for (name, target) in targets.into_iter().flatten() {
+        if name.contains(':') {
+            continue;
+        }
+        scripts.add(task_script(name, target, Runner::Nx));
+    }
+
+    scripts.sort_alphabetically();
+    Ok(scripts)
+}
+
+/// Build the script for a task, describing what it depends on.
+fn task_script(name: &str, task: &Value, runner: Runner) -> Script {
+    let command = runner.format_command(name);
+    let depends_on: Vec<&str> = task
+        .get("dependsOn")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+
+    let script = if depends_on.is_empty() {
+        Script::new(name, command)
+    } else {
+        Script::with_description(
+            name,
+            command,
+            format!("Depends on {}", depends_on.join(", ")),
+        )
+    };
+    script.with_runner(runner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_turbo_tasks() {
+        let json = r#"{
+            // Turborepo 2
+            "tasks": {
+                "build": { "dependsOn": ["^build"], "outputs": ["dist/**"] },
+                "web#build": { "dependsOn": ["codegen"] },
+                "//#format": {},
+                "dev": { "persistent": true, "cache": false },
+            }
+        }"#;
+
+        let scripts = parse_turbo_tasks_from_json(json).unwrap();
+        assert_eq!(scripts.names(), ["build", "dev", "format"]);
+        let build = scripts.get("build").unwrap();
+        assert_eq!(build.command(), "turbo run build");
+        assert_eq!(build.runner(), Some(Runner::Turbo));
+        assert_eq!(build.description(), Some("Depends on ^build"));
+    }
+
+    #[test]
+    fn test_parse_turbo_pipeline() {
+        let json = r#"{ "pipeline": { "test": {} } }"#;
+        let scripts = parse_turbo_tasks_from_json(json).unwrap();
+        assert_eq!(scripts.names(), ["test"]);
+    }
+
+    #[test]
+    fn test_parse_nx_targets() {
+        let json = r#"{
+            "targetDefaults": {
+                "build": { "dependsOn": ["^build"], "cache": true },
+                "e2e": { "dependsOn": [{ "target": "build" }] },
+                "@nx/vite:test": { "cache": true }
+            }
+        }"#;
+
+        let scripts = parse_nx_targets_from_json(json).unwrap();
+        assert_eq!(scripts.names(), ["build", "e2e"]);
+        assert_eq!(scripts.get("e2e").unwrap().command(), "nx run-many -t e2e");
+        assert_eq!(scripts.get("e2e").unwrap().description(), None);
+    }
+
+    #[test]
+    fn test_parse_task_runner_tasks() {
+        let temp = TempDir::new().unwrap();
+        assert!(parse_task_runner_tasks(temp.path()).unwrap().is_none());
+
+        std::fs::write(
+            temp.path().join("turbo.json"),
+            r#"{"tasks": {"build": {}}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("nx.json"),
+            r#"{"targetDefaults": {"build": {}, "lint": {}}}"#,
+        )
+        .unwrap();
+
+        let tasks = parse_task_runner_tasks(temp.path()).unwrap().unwrap();
+        assert_eq!(tasks.get("build").unwrap().runner(), Some(Runner::Turbo));
+        assert_eq!(tasks.get("lint").unwrap().runner(), Some(Runner::Nx));
+
+        std::fs::write(temp.path().join("nx.json"), "{").unwrap();
+        assert!(parse_task_runner_tasks(temp.path()).is_err());
+    }
+}
@@ -2,15 +2,77 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use super::graph::matches_run_all_pattern;
+use super::manager::Runner;
+
 /// A script defined in package.json.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Script {
     name: String,
     command: String,
     description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    runner: Option<Runner>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<ScriptGroup>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alias: Option<ScriptAlias>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<ParentScript>,
+}
+
+/// Scripts run together under one name, from `[groups]` in the config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptGroup {
+    /// Member script names, in run order.
+    pub scripts: Vec<String>,
+    /// Whether the members run at the same time.
+    pub parallel: bool,
+}
+
+/// Another name for a script and arguments, from `[scripts.aliases]` in the
+/// config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptAlias {
+    /// Name of the script the alias runs.
+    pub script: String,
+    /// Arguments the script gets, before any given when running the alias.
+    pub args: Option<String>,
+}
+
+/// A script of the monorepo root listed in one of its packages, from
+/// `[parent]` in the config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParentScript {
+    /// Name of the script in the root package.json.
+    pub script: String,
+    /// Monorepo root the script runs from.
+    pub root: PathBuf,
+}
+
+impl ScriptAlias {
+    /// Get the arguments of a run of the alias, its own first.
+    pub fn args_with(&self, extra: Option<&str>) -> Option<String> {
+        match (self.args.as_deref(), extra.filter(|a| !a.trim().is_empty())) {
+            (Some(own), Some(extra)) => Some(format!("{own} {extra}")),
+            (own, extra) => own.or(extra).map(String::from),
+        }
+    }
+}
+
+impl fmt::Display for ScriptAlias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.args {
+            Some(args) => write!(f, "{} -- {args}", self.script),
+            None => write!(f, "{}", self.script),
+        }
+    }
 }
 
 impl Script {
@@ -20,6 +82,11 @@ impl Script {
             name: name.into(),
             command: command.into(),
             description: None,
+            runner: None,
+            category: None,
+            group: None,
+            alias: None,
+            parent: None,
         }
     }
 
@@ -33,9 +100,85 @@ impl Script {
             name: name.into(),
             command: command.into(),
             description: Some(description.into()),
+            runner: None,
+            category: None,
+            group: None,
+            alias: None,
+            parent: None,
         }
     }
 
+    /// Create an entry that runs a group of scripts.
+    ///
+    /// The command lists the members as they run: `lint && test`, or
+    /// `lint & test` for a parallel group.
+    pub fn from_group(name: impl Into<String>, group: ScriptGroup) -> Self {
+        let separator = if group.parallel { " & " } else { " && " };
+        let mut script = Self::new(name, group.scripts.join(separator));
+        script.group = Some(group);
+        script
+    }
+
+    /// Create an entry that runs a script of the monorepo root at `root`.
+    ///
+    /// The entry is named `root:<name>`, which puts it in the `root`
+    /// category, and keeps the script's command, description and runner.
+    pub fn from_parent(target: &Script, root: &Path) -> Self {
+        let mut script = Self {
+            name: format!("root:{}", target.name),
+            parent: Some(ParentScript {
+                script: target.name.clone(),
+                root: root.to_path_buf(),
+            }),
+            ..target.clone()
+        };
+        script.category = None;
+        script
+    }
+
+    /// Get the root script this entry runs, if it comes from the monorepo
+    /// root.
+    pub fn parent(&self) -> Option<&ParentScript> {
+        self.parent.as_ref()
+    }
+
+    /// Get the root script an entry from [`Script::from_parent`] runs, under
+    /// its own name.
+    pub fn root_script(&self) -> Option<Script> {
+        let parent = self.parent.as_ref()?;
+        Some(Self {
+            name: parent.script.clone(),
+            parent: None,
+            ..self.clone()
+        })
+    }
+
+    /// Get the scripts this entry runs, if it is a group.
+    pub fn group(&self) -> Option<&ScriptGroup> {
+        self.group.as_ref()
+    }
+
+    /// Create an entry that runs `target` under another name.
+    ///
+    /// The entry shows the target's command with the alias arguments, and
+    /// keeps its runner and category.
+    pub fn from_alias(name: impl Into<String>, target: &Script, alias: ScriptAlias) -> Self {
+        let command = match &alias.args {
+            Some(args) => format!("{} {args}", target.command),
+            None => target.command.clone(),
+        };
+        let mut script = Self::with_description(name, command, format!("Alias of {alias}"));
+        script.runner = target.runner;
+        script.category = Some(target.category().to_string());
+        script.alias = Some(alias);
+        script
+    }
+
+    /// Get the script and arguments this entry stands for, if it is an alias.
+    pub fn alias(&self) -> Option<&ScriptAlias> {
+        self.alias.as_ref()
+    }
+
     /// Get the script name.
     pub fn name(&self) -> &str {
         &self.name
@@ -56,11 +199,47 @@ impl Script {
         self.description = Some(description.into());
     }
 
+    /// Set the runner this script always runs with, whatever the project uses.
+    pub fn with_runner(mut self, runner: Runner) -> Self {
+        self.runner = Some(runner);
+        self
+    }
+
+    /// Get the runner this script is tied to, if any.
+    ///
+    /// Deno tasks are tied to Deno; package.json scripts use the project's
+    /// package manager.
+    pub fn runner(&self) -> Option<Runner> {
+        self.runner
+    }
+
+    /// Get the runner to run this script with in a project using `project`.
+    pub fn runner_or(&self, project: Runner) -> Runner {
+        self.runner.unwrap_or(project)
+    }
+
     /// Check if this is a lifecycle script.
     pub fn is_lifecycle(&self) -> bool {
         is_lifecycle_script(&self.name)
     }
 
+    /// Get the script's category: its name up to the first `:`.
+    ///
+    /// `test:unit` and `test:e2e` are both in the `test` category, and a
+    /// script without a `:` is its own category. A category set with
+    /// [`Script::set_category`] takes precedence.
+    pub fn category(&self) -> &str {
+        match &self.category {
+            Some(category) => category,
+            None => self.name.split(':').next().unwrap_or(&self.name),
+        }
+    }
+
+    /// Override the category derived from the name.
+    pub fn set_category(&mut self, category: impl Into<String>) {
+        self.category = Some(category.into());
+    }
+
     /// Check if this is a pre/post script for another script.
     pub fn is_hook_for(&self, script_name: &str) -> bool {
         self.name == format!("pre{script_name}") || self.name == format!("post{script_name}")
@@ -73,6 +252,11 @@ impl fmt::Debug for Script {
             .field("name", &self.name)
             .field("command", &self.command)
             .field("description", &self.description)
+            .field("runner", &self.runner)
+            .field("category", &self.category)
+            .field("group", &self.group)
+            .field("alias", &self.alias)
+            .field("parent", &self.parent)
             .finish()
     }
 }
@@ -173,6 +357,70 @@ impl Scripts {
         }
     }
 
+    /// Keep only scripts matching the given patterns.
+    /// Supports glob patterns with '*' wildcard.
+    pub fn only_matching(&self, patterns: &[String]) -> Self {
+        self.filter_by(|script| matches_any_pattern(script.name(), patterns))
+    }
+
+    /// Count scripts per category, largest categories first.
+    ///
+    /// Categories with the same count are ordered by name.
+    pub fn categories(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for script in &self.scripts {
+            match counts.iter_mut().find(|(c, _)| *c == script.category()) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((script.category(), 1)),
+            }
+        }
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        counts
+    }
+
+    /// Keep only scripts in the given category.
+    pub fn in_category(&self, category: &str) -> Self {
+        Self {
+            scripts: self
+                .scripts
+                .iter()
+                .filter(|s| s.category() == category)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Keep only scripts matching an npm-run-all pattern such as `test:*`.
+    ///
+    /// `*` matches within a `:`-separated segment and `**` across segments.
+    pub fn matching_pattern(&self, pattern: &str) -> Self {
+        self.filter_by(|script| matches_run_all_pattern(pattern, script.name()))
+    }
+
+    /// Keep only scripts for which `keep` returns true.
+    pub fn filter_by(&self, keep: impl Fn(&Script) -> bool) -> Self {
+        Self {
+            scripts: self.scripts.iter().filter(|s| keep(s)).cloned().collect(),
+        }
+    }
+
+    /// Rename a script, returning false if there's none named `from`.
+    pub fn rename(&mut self, from: &str, to: impl Into<String>) -> bool {
+        match self.get_mut(from) {
+            Some(script) => {
+                script.name = to.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a script by name.
+    pub fn remove(&mut self, name: &str) -> Option<Script> {
+        let index = self.scripts.iter().position(|s| s.name == name)?;
+        Some(self.scripts.remove(index))
+    }
+
     /// Get script names as a vector.
     pub fn names(&self) -> Vec<&str> {
         self.scripts.iter().map(|s| s.name()).collect()
@@ -450,6 +698,72 @@ mod tests {
         assert!(names.contains(&"build"));
     }
 
+    #[test]
+    fn test_script_category() {
+        assert_eq!(Script::new("test:unit", "vitest").category(), "test");
+        assert_eq!(Script::new("build", "vite build").category(), "build");
+        assert_eq!(Script::new("a:b:c", "x").category(), "a");
+    }
+
+    #[test]
+    fn test_script_from_parent() {
+        let mut dev = Script::with_description("dev", "turbo dev", "All apps");
+        dev.set_category("serve");
+        let entry = Script::from_parent(&dev, Path::new("/repo"));
+
+        assert_eq!(entry.name(), "root:dev");
+        assert_eq!(entry.command(), "turbo dev");
+        assert_eq!(entry.description(), Some("All apps"));
+        assert_eq!(entry.category(), "root");
+        let parent = entry.parent().unwrap();
+        assert_eq!(parent.script, "dev");
+        assert_eq!(parent.root, Path::new("/repo"));
+        assert_eq!(entry.root_script().unwrap().name(), "dev");
+        assert!(dev.parent().is_none());
+    }
+
+    #[test]
+    fn test_scripts_categories() {
+        let scripts = Scripts::from_vec(vec![
+            Script::new("build", "vite build"),
+            Script::new("test", "vitest"),
+            Script::new("test:unit", "vitest unit"),
+            Script::new("test:e2e", "playwright test"),
+            Script::new("deploy:prod", "deploy prod"),
+            Script::new("deploy:staging", "deploy staging"),
+            Script::new("dev", "vite"),
+        ]);
+
+        assert_eq!(
+            scripts.categories(),
+            [("test", 3), ("deploy", 2), ("build", 1), ("dev", 1)]
+        );
+        assert_eq!(
+            scripts.in_category("deploy").names(),
+            ["deploy:prod", "deploy:staging"]
+        );
+        assert!(scripts.in_category("lint").is_empty());
+    }
+
+    #[test]
+    fn test_scripts_matching_pattern() {
+        let scripts = Scripts::from_vec(vec![
+            Script::new("test", "vitest"),
+            Script::new("test:unit", "vitest unit"),
+            Script::new("test:e2e", "playwright test"),
+            Script::new("test:e2e:ci", "playwright test --ci"),
+            Script::new("build", "vite build"),
+        ]);
+
+        assert_eq!(
+            scripts.matching_pattern("test:*").names(),
+            ["test:unit", "test:e2e"]
+        );
+        assert_eq!(scripts.matching_pattern("test:**").len(), 3);
+        assert_eq!(scripts.matching_pattern("build").names(), ["build"]);
+        assert!(scripts.matching_pattern("lint:*").is_empty());
+    }
+
     #[test]
     fn test_package_display_name() {
         let pkg = Package {
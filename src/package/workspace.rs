@@ -4,14 +4,31 @@
 //! - npm/yarn workspaces (package.json `workspaces` field)
 //! - pnpm workspaces (pnpm-workspace.yaml)
 //! - Lerna (lerna.json)
+//!
+//! Workspaces that are monorepos themselves (e.g. a pnpm workspace under
+//! `apps/`) have their own members, found a few levels deep.
+//!
+//! Workspace globs are expanded by nrs. With `workspace_resolution =
+//! "manager"`, yarn (berry) and pnpm projects ask the package manager for
+//! the exact set instead (`yarn workspaces list`, `pnpm ls -r`), falling
+//! back to the globs when that fails.
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
 
-use super::scripts::parse_scripts;
+use super::descriptions::DescriptionSource;
+use super::manager::{detect_runner, Runner};
+use super::scripts::{parse_scripts, unreadable_reason};
 use super::types::Script;
+use crate::runner::new_command;
+
+/// Deepest level of nested monorepos searched for workspaces.
+const MAX_NESTING_DEPTH: usize = 3;
 
 /// Represents a workspace in a monorepo.
 #[derive(Debug, Clone)]
@@ -22,6 +39,12 @@ pub struct Workspace {
     path: PathBuf,
     /// Scripts available in this workspace.
     scripts: Vec<Script>,
+    /// Workspaces of this workspace, if it is a monorepo itself.
+    members: Vec<Workspace>,
+    /// Nested monorepo this workspace belongs to, if not the project root.
+    root: Option<PathBuf>,
+    /// Why the workspace's package.json couldn't be read, if it couldn't.
+    error: Option<String>,
 }
 
 impl Workspace {
@@ -31,6 +54,23 @@ impl Workspace {
             name: name.into(),
             path: path.into(),
             scripts: Vec::new(),
+            members: Vec::new(),
+            root: None,
+            error: None,
+        }
+    }
+
+    /// Create a workspace whose package.json couldn't be read.
+    ///
+    /// It is listed with the reason instead of its scripts.
+    pub fn unreadable(
+        name: impl Into<String>,
+        path: impl Into<PathBuf>,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            error: Some(error.into()),
+            ..Self::new(name, path)
         }
     }
 
@@ -44,6 +84,9 @@ impl Workspace {
             name: name.into(),
             path: path.into(),
             scripts,
+            members: Vec::new(),
+            root: None,
+            error: None,
         }
     }
 
@@ -57,6 +100,11 @@ impl Workspace {
         &self.path
     }
 
+    /// Get why the workspace's package.json couldn't be read, if it couldn't.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
     /// Get the workspace scripts.
     pub fn scripts(&self) -> &[Script] {
         &self.scripts
@@ -72,13 +120,57 @@ impl Workspace {
         !self.scripts.is_empty()
     }
 
+    /// Get the workspaces of this workspace, if it is a monorepo itself.
+    pub fn members(&self) -> &[Workspace] {
+        &self.members
+    }
+
+    /// Get the workspaces of this workspace for changing them.
+    pub fn members_mut(&mut self) -> &mut [Workspace] {
+        &mut self.members
+    }
+
+    /// Check if this workspace is a monorepo with workspaces of its own.
+    pub fn has_members(&self) -> bool {
+        !self.members.is_empty()
+    }
+
+    /// Set the workspaces of this workspace.
+    ///
+    /// Members run their scripts from this workspace's directory.
+    pub fn set_members(&mut self, members: Vec<Workspace>) {
+        self.members = members
+            .into_iter()
+            .map(|mut member| {
+                member.root = Some(self.path.clone());
+                member
+            })
+            .collect();
+    }
+
+    /// Get the nested monorepo root to run this workspace's scripts from.
+    ///
+    /// Returns `None` for workspaces of the project root itself.
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
     /// Load scripts from the workspace's package.json.
-    pub fn load_scripts(&mut self) -> Result<()> {
+    ///
+    /// If it can't be read, the reason is kept and the scripts are cleared.
+    pub fn load_scripts(&mut self, sources: &[DescriptionSource]) -> Result<()> {
         let package_json = self.path.join("package.json");
-        if package_json.exists() {
-            self.scripts = parse_scripts(&self.path)
-                .map(|scripts| scripts.into_iter().collect())
-                .unwrap_or_default();
+        if package_json.symlink_metadata().is_ok() {
+            match parse_scripts(&self.path, sources) {
+                Ok(scripts) => {
+                    self.scripts = scripts.into_iter().collect();
+                    self.error = None;
+                }
+                Err(err) => {
+                    self.scripts.clear();
+                    self.error = Some(format!("{err:#}"));
+                }
+            }
         }
         Ok(())
     }
@@ -116,6 +208,73 @@ impl std::fmt::Display for WorkspaceType {
     }
 }
 
+/// Monorepo build orchestrator found alongside the workspaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonorepoTool {
+    /// Turborepo (turbo.json)
+    Turbo,
+    /// Nx (nx.json)
+    Nx,
+}
+
+impl MonorepoTool {
+    /// Config file that marks the tool's presence.
+    pub fn config_file(&self) -> &'static str {
+        match self {
+            MonorepoTool::Turbo => "turbo.json",
+            MonorepoTool::Nx => "nx.json",
+        }
+    }
+}
+
+impl std::fmt::Display for MonorepoTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonorepoTool::Turbo => write!(f, "turbo"),
+            MonorepoTool::Nx => write!(f, "nx"),
+        }
+    }
+}
+
+/// Detect monorepo build tools (turbo, nx) in the project root.
+pub fn detect_monorepo_tools(project_dir: &Path) -> Vec<MonorepoTool> {
+    [MonorepoTool::Turbo, MonorepoTool::Nx]
+        .into_iter()
+        .filter(|tool| project_dir.join(tool.config_file()).exists())
+        .collect()
+}
+
+/// Describe the monorepo setup for display, e.g. `pnpm workspaces + turbo`.
+///
+/// Returns `None` when neither a workspace type nor any tool was found.
+pub fn describe_monorepo(
+    workspace_type: Option<WorkspaceType>,
+    tools: &[MonorepoTool],
+) -> Option<String> {
+    let parts: Vec<String> = workspace_type
+        .map(|t| t.to_string())
+        .into_iter()
+        .chain(tools.iter().map(|t| t.to_string()))
+        .collect();
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" + "))
+    }
+}
+
+/// How the workspaces of a monorepo are found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceResolution {
+    /// Expand the workspace globs.
+    #[default]
+    Glob,
+    /// Ask yarn or pnpm, falling back to the globs.
+    Manager,
+}
+
 /// pnpm-workspace.yaml structure.
 #[derive(Debug, Deserialize)]
 struct PnpmWorkspace {
@@ -135,31 +294,145 @@ struct LernaConfig {
 /// - `pnpm-workspace.yaml`
 /// - `lerna.json`
 ///
-/// Returns a list of workspaces with their scripts loaded.
-pub fn detect_workspaces(project_dir: &Path) -> Result<Vec<Workspace>> {
-    let info = detect_workspace_info(project_dir)?;
-    Ok(info.workspaces)
+/// Returns a list of workspaces with their scripts loaded, descriptions read
+/// from `sources`. Workspaces whose name or path relative to `project_dir`
+/// matches any of the `exclude` glob patterns are left out.
+pub fn detect_workspaces(
+    project_dir: &Path,
+    exclude: &[String],
+    sources: &[DescriptionSource],
+    resolution: WorkspaceResolution,
+) -> Result<Vec<Workspace>> {
+    let info = detect_workspace_info(project_dir, sources, resolution)?;
+    Ok(exclude_workspaces(info.workspaces, project_dir, exclude))
+}
+
+/// Remove workspaces matching any of the `exclude` glob patterns.
+pub fn exclude_workspaces(
+    workspaces: Vec<Workspace>,
+    project_dir: &Path,
+    exclude: &[String],
+) -> Vec<Workspace> {
+    workspaces
+        .into_iter()
+        .filter(|workspace| !is_workspace_excluded(workspace, project_dir, exclude))
+        .map(|mut workspace| {
+            let members = std::mem::take(&mut workspace.members);
+            workspace.members = exclude_workspaces(members, project_dir, exclude);
+            workspace
+        })
+        .collect()
+}
+
+/// Find a workspace by package name, relative path or short name.
+///
+/// Lookups are tried in order: exact package name (`@app/web`), path
+/// relative to `project_dir` (`packages/web`), then the short name, which is
+/// the unscoped package name or directory name (`web`). A short name only
+/// resolves when it is unambiguous. Members of nested monorepos are
+/// included.
+pub fn find_workspace<'a>(
+    workspaces: &'a [Workspace],
+    project_dir: &Path,
+    query: &str,
+) -> Option<&'a Workspace> {
+    let workspaces = all_workspaces(workspaces);
+    if let Some(workspace) = workspaces.iter().find(|w| w.name() == query) {
+        return Some(workspace);
+    }
+
+    let query_path = project_dir.join(query.trim_end_matches('/'));
+    if let Some(workspace) = workspaces.iter().find(|w| w.path() == query_path) {
+        return Some(workspace);
+    }
+
+    let mut short_matches = workspaces.iter().filter(|w| {
+        let unscoped = w.name().rsplit('/').next().unwrap_or(w.name());
+        let dir_name = w.path().file_name().and_then(|n| n.to_str());
+        unscoped == query || dir_name == Some(query)
+    });
+
+    match (short_matches.next(), short_matches.next()) {
+        (Some(workspace), None) => Some(workspace),
+        _ => None,
+    }
+}
+
+/// Check if a workspace matches any exclude pattern.
+///
+/// Patterns are matched against both the package name (e.g. `@app/*`) and
+/// the directory relative to the project root (e.g. `packages/fixtures-*`).
+fn is_workspace_excluded(workspace: &Workspace, project_dir: &Path, patterns: &[String]) -> bool {
+    let relative_path = workspace
+        .path()
+        .strip_prefix(project_dir)
+        .unwrap_or(workspace.path())
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    patterns
+        .iter()
+        .any(|pattern| match glob::Pattern::new(pattern) {
+            Ok(glob) => glob.matches(workspace.name()) || glob.matches(&relative_path),
+            Err(_) => pattern == workspace.name() || *pattern == relative_path,
+        })
 }
 
 /// Detect workspace configuration and return detailed info.
-pub fn detect_workspace_info(project_dir: &Path) -> Result<WorkspaceInfo> {
+///
+/// Members of workspaces that are monorepos themselves are detected too,
+/// skipping any that the project root already lists. `resolution` picks
+/// whether the globs are expanded or the package manager is asked.
+pub fn detect_workspace_info(
+    project_dir: &Path,
+    sources: &[DescriptionSource],
+    resolution: WorkspaceResolution,
+) -> Result<WorkspaceInfo> {
+    let mut info = detect_level(project_dir, sources, resolution)?;
+
+    let mut seen: HashSet<PathBuf> = info.workspaces.iter().map(|w| w.path.clone()).collect();
+    seen.insert(project_dir.to_path_buf());
+    detect_nested_members(&mut info.workspaces, &mut seen, 1, sources, resolution);
+
+    Ok(info)
+}
+
+/// Detect the workspaces of one monorepo root, without nested members.
+fn detect_level(
+    project_dir: &Path,
+    sources: &[DescriptionSource],
+    resolution: WorkspaceResolution,
+) -> Result<WorkspaceInfo> {
     // Check for pnpm-workspace.yaml first (most specific)
     let pnpm_workspace = project_dir.join("pnpm-workspace.yaml");
     if pnpm_workspace.exists() {
-        return detect_pnpm_workspaces(project_dir, &pnpm_workspace);
+        if let Some(workspaces) = listed_workspaces(project_dir, Runner::Pnpm, sources, resolution)
+        {
+            return Ok(WorkspaceInfo {
+                is_monorepo: true,
+                workspace_type: Some(WorkspaceType::Pnpm),
+                workspaces,
+            });
+        }
+        return detect_pnpm_workspaces(project_dir, &pnpm_workspace, sources);
     }
 
     // Check for lerna.json
     let lerna_json = project_dir.join("lerna.json");
     if lerna_json.exists() {
-        return detect_lerna_workspaces(project_dir, &lerna_json);
+        return detect_lerna_workspaces(project_dir, &lerna_json, sources);
     }
 
     // Check for workspaces in package.json
     let package_json = project_dir.join("package.json");
     if package_json.exists() {
-        let info = detect_npm_workspaces(project_dir, &package_json)?;
+        let mut info = detect_npm_workspaces(project_dir, &package_json, sources)?;
         if info.is_monorepo {
+            if let Some(workspaces) =
+                listed_workspaces(project_dir, Runner::Yarn, sources, resolution)
+            {
+                info.workspaces = workspaces;
+            }
             return Ok(info);
         }
     }
@@ -167,6 +440,44 @@ pub fn detect_workspace_info(project_dir: &Path) -> Result<WorkspaceInfo> {
     Ok(WorkspaceInfo::default())
 }
 
+/// Detect the members of workspaces that are monorepos themselves.
+///
+/// Paths in `seen` were found already and aren't listed again. Nested
+/// configs that fail to parse are ignored, like workspaces without a
+/// package.json.
+fn detect_nested_members(
+    workspaces: &mut [Workspace],
+    seen: &mut HashSet<PathBuf>,
+    depth: usize,
+    sources: &[DescriptionSource],
+    resolution: WorkspaceResolution,
+) {
+    if depth > MAX_NESTING_DEPTH {
+        return;
+    }
+
+    for workspace in workspaces {
+        let Ok(info) = detect_level(&workspace.path, sources, resolution) else {
+            continue;
+        };
+        let mut members: Vec<Workspace> = info
+            .workspaces
+            .into_iter()
+            .filter(|member| seen.insert(member.path.clone()))
+            .collect();
+        detect_nested_members(&mut members, seen, depth + 1, sources, resolution);
+        workspace.set_members(members);
+    }
+}
+
+/// Flatten workspaces and their nested members, depth first.
+pub fn all_workspaces(workspaces: &[Workspace]) -> Vec<&Workspace> {
+    workspaces
+        .iter()
+        .flat_map(|workspace| std::iter::once(workspace).chain(all_workspaces(&workspace.members)))
+        .collect()
+}
+
 /// Check if a directory is a monorepo root.
 pub fn is_monorepo(project_dir: &Path) -> bool {
     let pnpm_workspace = project_dir.join("pnpm-workspace.yaml");
@@ -191,8 +502,53 @@ pub fn is_monorepo(project_dir: &Path) -> bool {
     false
 }
 
+/// Find the monorepo root above a package directory.
+///
+/// Returns the nearest parent directory that is a monorepo root, if any.
+pub fn find_parent_monorepo(project_dir: &Path) -> Option<PathBuf> {
+    project_dir
+        .ancestors()
+        .skip(1)
+        .find(|dir| is_monorepo(dir))
+        .map(Path::to_path_buf)
+}
+
+/// Get the scripts of the monorepo root above a package, as entries that run
+/// from the root.
+///
+/// Only root scripts matching `patterns` are listed; without patterns, or
+/// outside a monorepo, there are none. Descriptions are read from `sources`.
+pub fn parent_scripts(
+    project_dir: &Path,
+    patterns: &[String],
+    sources: &[DescriptionSource],
+) -> Vec<Script> {
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+    let Some(root) = find_parent_monorepo(project_dir) else {
+        return Vec::new();
+    };
+    let scripts = match parse_scripts(&root, sources) {
+        Ok(scripts) => scripts,
+        Err(err) => {
+            warn!("Failed to read the scripts of {}: {err:#}", root.display());
+            return Vec::new();
+        }
+    };
+    scripts
+        .only_matching(patterns)
+        .iter()
+        .map(|script| Script::from_parent(script, &root))
+        .collect()
+}
+
 /// Detect workspaces from package.json workspaces field.
-fn detect_npm_workspaces(project_dir: &Path, package_json: &Path) -> Result<WorkspaceInfo> {
+fn detect_npm_workspaces(
+    project_dir: &Path,
+    package_json: &Path,
+    sources: &[DescriptionSource],
+) -> Result<WorkspaceInfo> {
     let content = std::fs::read_to_string(package_json)
         .with_context(|| format!("Failed to read {}", package_json.display()))?;
     let json: serde_json::Value = serde_json::from_str(&content)
@@ -221,7 +577,7 @@ fn detect_npm_workspaces(project_dir: &Path, package_json: &Path) -> Result<Work
         return Ok(WorkspaceInfo::default());
     }
 
-    let workspaces = resolve_workspace_patterns(project_dir, &workspace_patterns)?;
+    let workspaces = resolve_workspace_patterns(project_dir, &workspace_patterns, sources)?;
 
     Ok(WorkspaceInfo {
         is_monorepo: true,
@@ -231,7 +587,11 @@ fn detect_npm_workspaces(project_dir: &Path, package_json: &Path) -> Result<Work
 }
 
 /// Detect workspaces from pnpm-workspace.yaml.
-fn detect_pnpm_workspaces(project_dir: &Path, workspace_file: &Path) -> Result<WorkspaceInfo> {
+fn detect_pnpm_workspaces(
+    project_dir: &Path,
+    workspace_file: &Path,
+    sources: &[DescriptionSource],
+) -> Result<WorkspaceInfo> {
     let content = std::fs::read_to_string(workspace_file)
         .with_context(|| format!("Failed to read {}", workspace_file.display()))?;
 
@@ -248,7 +608,7 @@ fn detect_pnpm_workspaces(project_dir: &Path, workspace_file: &Path) -> Result<W
         });
     }
 
-    let workspaces = resolve_workspace_patterns(project_dir, &patterns)?;
+    let workspaces = resolve_workspace_patterns(project_dir, &patterns, sources)?;
 
     Ok(WorkspaceInfo {
         is_monorepo: true,
@@ -258,7 +618,11 @@ fn detect_pnpm_workspaces(project_dir: &Path, workspace_file: &Path) -> Result<W
 }
 
 /// Detect workspaces from lerna.json.
-fn detect_lerna_workspaces(project_dir: &Path, lerna_file: &Path) -> Result<WorkspaceInfo> {
+fn detect_lerna_workspaces(
+    project_dir: &Path,
+    lerna_file: &Path,
+    sources: &[DescriptionSource],
+) -> Result<WorkspaceInfo> {
     let content = std::fs::read_to_string(lerna_file)
         .with_context(|| format!("Failed to read {}", lerna_file.display()))?;
 
@@ -270,7 +634,7 @@ fn detect_lerna_workspaces(project_dir: &Path, lerna_file: &Path) -> Result<Work
         .packages
         .unwrap_or_else(|| vec!["packages/*".to_string()]);
 
-    let workspaces = resolve_workspace_patterns(project_dir, &patterns)?;
+    let workspaces = resolve_workspace_patterns(project_dir, &patterns, sources)?;
 
     Ok(WorkspaceInfo {
         is_monorepo: true,
@@ -280,12 +644,32 @@ fn detect_lerna_workspaces(project_dir: &Path, lerna_file: &Path) -> Result<Work
 }
 
 /// Resolve workspace glob patterns to actual directories.
-fn resolve_workspace_patterns(project_dir: &Path, patterns: &[String]) -> Result<Vec<Workspace>> {
+///
+/// Negated patterns (`!packages/internal-*`) leave out the directories they
+/// match, wherever they appear in the list.
+fn resolve_workspace_patterns(
+    project_dir: &Path,
+    patterns: &[String],
+    sources: &[DescriptionSource],
+) -> Result<Vec<Workspace>> {
     let mut workspaces = Vec::new();
     let mut seen_paths = std::collections::HashSet::new();
+    let negated: Vec<glob::Pattern> = patterns
+        .iter()
+        .filter_map(|pattern| pattern.strip_prefix('!'))
+        .filter_map(|pattern| {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            glob::Pattern::new(pattern)
+                .map_err(|err| warn!("Ignoring workspace pattern !{pattern}: {err}"))
+                .ok()
+        })
+        .collect();
+    let is_negated = |path: &Path| {
+        let relative = path.strip_prefix(project_dir).unwrap_or(path);
+        negated.iter().any(|pattern| pattern.matches_path(relative))
+    };
 
     for pattern in patterns {
-        // Skip negation patterns (we'll filter later if needed)
         if pattern.starts_with('!') {
             continue;
         }
@@ -305,19 +689,19 @@ fn resolve_workspace_patterns(project_dir: &Path, patterns: &[String]) -> Result
                         continue;
                     }
 
-                    // Skip if we've already seen this path
-                    if !seen_paths.insert(entry.clone()) {
+                    // Skip if we've already seen this path or it's negated
+                    if is_negated(&entry) || !seen_paths.insert(entry.clone()) {
                         continue;
                     }
 
-                    // Check for package.json
+                    // Check for package.json, even a broken symlink
                     let package_json = entry.join("package.json");
-                    if !package_json.exists() {
+                    if package_json.symlink_metadata().is_err() {
                         continue;
                     }
 
                     // Read workspace info
-                    if let Some(workspace) = create_workspace_from_path(&entry) {
+                    if let Some(workspace) = create_workspace_from_path(&entry, sources) {
                         workspaces.push(workspace);
                     }
                 }
@@ -327,9 +711,10 @@ fn resolve_workspace_patterns(project_dir: &Path, patterns: &[String]) -> Result
                 let direct_path = project_dir.join(pattern.trim_end_matches("/*"));
                 if direct_path.is_dir()
                     && direct_path.join("package.json").exists()
+                    && !is_negated(&direct_path)
                     && seen_paths.insert(direct_path.clone())
                 {
-                    if let Some(workspace) = create_workspace_from_path(&direct_path) {
+                    if let Some(workspace) = create_workspace_from_path(&direct_path, sources) {
                         workspaces.push(workspace);
                     }
                 }
@@ -343,6 +728,103 @@ fn resolve_workspace_patterns(project_dir: &Path, patterns: &[String]) -> Result
     Ok(workspaces)
 }
 
+/// List a monorepo's workspaces with its package manager, if `resolution`
+/// asks for it and `runner` manages the project.
+///
+/// Returns `None` when the package manager can't list them, e.g. because
+/// it isn't installed or is yarn 1, so the globs are used instead.
+fn listed_workspaces(
+    project_dir: &Path,
+    runner: Runner,
+    sources: &[DescriptionSource],
+    resolution: WorkspaceResolution,
+) -> Option<Vec<Workspace>> {
+    if resolution != WorkspaceResolution::Manager {
+        return None;
+    }
+    let args: &[&str] = match runner {
+        Runner::Yarn if detect_runner(project_dir) == Runner::Yarn => {
+            &["workspaces", "list", "--json"]
+        }
+        Runner::Pnpm => &["ls", "-r", "--depth", "-1", "--json"],
+        _ => return None,
+    };
+
+    let output = match new_command(runner.executable())
+        .args(args)
+        .current_dir(project_dir)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            debug!(
+                "{runner} can't list workspaces: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+        Err(err) => {
+            debug!("Can't run {runner} to list workspaces: {err}");
+            return None;
+        }
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let paths = match runner {
+        Runner::Pnpm => parse_pnpm_workspaces(&stdout, project_dir)?,
+        _ => parse_yarn_workspaces(&stdout, project_dir),
+    };
+
+    let mut workspaces: Vec<Workspace> = paths
+        .iter()
+        .filter_map(|path| create_workspace_from_path(path, sources))
+        .collect();
+    workspaces.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(workspaces)
+}
+
+/// Get the workspace directories from `yarn workspaces list --json`, one
+/// JSON object per line. The root (`"."`) isn't a workspace.
+fn parse_yarn_workspaces(output: &str, project_dir: &Path) -> Vec<PathBuf> {
+    #[derive(Deserialize)]
+    struct Listed {
+        location: String,
+    }
+
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Listed>(line).ok())
+        .filter(|listed| listed.location != ".")
+        .map(|listed| project_dir.join(listed.location))
+        .collect()
+}
+
+/// Get the workspace directories from `pnpm ls -r --json`, a JSON array
+/// including the root.
+///
+/// Paths are absolute; they're made relative to `project_dir` as given, so
+/// they match the ones found from globs.
+fn parse_pnpm_workspaces(output: &str, project_dir: &Path) -> Option<Vec<PathBuf>> {
+    #[derive(Deserialize)]
+    struct Listed {
+        path: PathBuf,
+    }
+
+    let listed: Vec<Listed> = serde_json::from_str(output).ok()?;
+    let root = project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.to_path_buf());
+    Some(
+        listed
+            .into_iter()
+            .filter_map(|listed| {
+                let path = listed.path.canonicalize().unwrap_or(listed.path);
+                let relative = path.strip_prefix(&root).ok()?;
+                (!relative.as_os_str().is_empty()).then(|| project_dir.join(relative))
+            })
+            .collect(),
+    )
+}
+
 /// Normalize a glob pattern for the glob crate.
 fn normalize_glob_pattern(pattern: &str) -> String {
     let mut normalized = pattern.to_string();
@@ -363,24 +845,55 @@ fn normalize_glob_pattern(pattern: &str) -> String {
 }
 
 /// Create a Workspace from a directory path.
-fn create_workspace_from_path(path: &Path) -> Option<Workspace> {
+///
+/// A package.json that can't be read or parsed gives a workspace named
+/// after its directory, with the reason, so it's listed rather than lost.
+fn create_workspace_from_path(path: &Path, sources: &[DescriptionSource]) -> Option<Workspace> {
+    let dir_name = path.file_name().and_then(|n| n.to_str()).map(String::from);
     let package_json = path.join("package.json");
-    let content = std::fs::read_to_string(&package_json).ok()?;
-    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let content = match std::fs::read_to_string(&package_json) {
+        Ok(content) => content,
+        Err(err) => {
+            let reason = unreadable_reason(&package_json, &err);
+            warn!("Can't read {}: {reason}", package_json.display());
+            return Some(Workspace::unreadable(
+                dir_name?,
+                path,
+                format!("package.json: {reason}"),
+            ));
+        }
+    };
+    let json: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(json) => json,
+        Err(err) => {
+            warn!("Can't parse {}: {err}", package_json.display());
+            return Some(Workspace::unreadable(
+                dir_name?,
+                path,
+                format!("package.json: invalid JSON ({err})"),
+            ));
+        }
+    };
 
     // Get the package name, falling back to directory name
     let name = json
         .get("name")
         .and_then(|n| n.as_str())
         .map(String::from)
-        .or_else(|| path.file_name().and_then(|n| n.to_str()).map(String::from))?;
+        .or(dir_name)?;
 
     // Parse scripts - parse_scripts takes a directory path
-    let scripts: Vec<Script> = parse_scripts(path)
-        .map(|s| s.into_iter().collect())
-        .unwrap_or_default();
-
-    Some(Workspace::with_scripts(name, path.to_path_buf(), scripts))
+    match parse_scripts(path, sources) {
+        Ok(scripts) => Some(Workspace::with_scripts(
+            name,
+            path.to_path_buf(),
+            scripts.into_iter().collect(),
+        )),
+        Err(err) => {
+            warn!("Can't load scripts of {}: {err:#}", path.display());
+            Some(Workspace::unreadable(name, path, format!("{err:#}")))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -389,6 +902,41 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_parse_yarn_workspaces() {
+        let output = r#"{"location":".","name":"root"}
+{"location":"packages/web","name":"@app/web"}
+{"location":"tools/lint","name":"lint"}
+"#;
+        let project = Path::new("/repo");
+        assert_eq!(
+            parse_yarn_workspaces(output, project),
+            [
+                PathBuf::from("/repo/packages/web"),
+                PathBuf::from("/repo/tools/lint")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pnpm_workspaces() {
+        let temp = TempDir::new().unwrap();
+        let web = temp.path().join("apps/web");
+        fs::create_dir_all(&web).unwrap();
+        let output = serde_json::json!([
+            {"name": "root", "path": temp.path()},
+            {"name": "web", "path": web.canonicalize().unwrap()},
+            {"name": "elsewhere", "path": "/somewhere/else"},
+        ])
+        .to_string();
+
+        assert_eq!(
+            parse_pnpm_workspaces(&output, temp.path()).unwrap(),
+            [temp.path().join("apps/web")]
+        );
+        assert!(parse_pnpm_workspaces("not json", temp.path()).is_none());
+    }
+
     fn create_package_json(dir: &Path, name: &str, scripts: &[(&str, &str)]) {
         let scripts_obj: serde_json::Map<_, _> = scripts
             .iter()
@@ -496,7 +1044,8 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let root = create_monorepo(&temp, "npm");
 
-        let info = detect_workspace_info(&root).unwrap();
+        let info = detect_workspace_info(&root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
         assert!(info.is_monorepo);
         assert_eq!(info.workspace_type, Some(WorkspaceType::Npm));
         assert_eq!(info.workspaces.len(), 2);
@@ -527,7 +1076,8 @@ mod tests {
         fs::create_dir_all(&pkg).unwrap();
         create_package_json(&pkg, "pkg", &[("build", "tsc")]);
 
-        let info = detect_workspace_info(root).unwrap();
+        let info = detect_workspace_info(root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
         assert!(info.is_monorepo);
         assert_eq!(info.workspaces.len(), 1);
     }
@@ -539,12 +1089,27 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let root = create_monorepo(&temp, "pnpm");
 
-        let info = detect_workspace_info(&root).unwrap();
+        let info = detect_workspace_info(&root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
         assert!(info.is_monorepo);
         assert_eq!(info.workspace_type, Some(WorkspaceType::Pnpm));
         assert_eq!(info.workspaces.len(), 2);
     }
 
+    #[test]
+    fn test_globs_dont_ask_the_package_manager() {
+        let temp = TempDir::new().unwrap();
+        let root = create_monorepo(&temp, "pnpm");
+
+        let listed = listed_workspaces(
+            &root,
+            Runner::Pnpm,
+            &DescriptionSource::ALL,
+            WorkspaceResolution::Glob,
+        );
+        assert!(listed.is_none());
+    }
+
     #[test]
     fn test_detect_pnpm_empty_packages() {
         let temp = TempDir::new().unwrap();
@@ -553,7 +1118,8 @@ mod tests {
         create_package_json(root, "monorepo", &[]);
         fs::write(root.join("pnpm-workspace.yaml"), "packages: []\n").unwrap();
 
-        let info = detect_workspace_info(root).unwrap();
+        let info = detect_workspace_info(root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
         assert!(info.is_monorepo);
         assert_eq!(info.workspace_type, Some(WorkspaceType::Pnpm));
         assert!(info.workspaces.is_empty());
@@ -566,7 +1132,8 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let root = create_monorepo(&temp, "lerna");
 
-        let info = detect_workspace_info(&root).unwrap();
+        let info = detect_workspace_info(&root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
         assert!(info.is_monorepo);
         assert_eq!(info.workspace_type, Some(WorkspaceType::Lerna));
         assert_eq!(info.workspaces.len(), 2);
@@ -587,7 +1154,8 @@ mod tests {
         fs::create_dir_all(&pkg).unwrap();
         create_package_json(&pkg, "pkg", &[("build", "tsc")]);
 
-        let info = detect_workspace_info(root).unwrap();
+        let info = detect_workspace_info(root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
         assert!(info.is_monorepo);
         assert_eq!(info.workspace_type, Some(WorkspaceType::Lerna));
         assert_eq!(info.workspaces.len(), 1);
@@ -624,6 +1192,24 @@ mod tests {
         assert!(!is_monorepo(root));
     }
 
+    #[test]
+    fn test_parent_scripts() {
+        let temp = TempDir::new().unwrap();
+        let root = create_monorepo(&temp, "pnpm");
+        let pkg_a = root.join("packages/pkg-a");
+
+        assert_eq!(find_parent_monorepo(&pkg_a), Some(root.clone()));
+        assert_eq!(find_parent_monorepo(&root), None);
+
+        let scripts = parent_scripts(&pkg_a, &["dev".to_string()], &DescriptionSource::ALL);
+        let names: Vec<&str> = scripts.iter().map(Script::name).collect();
+        assert_eq!(names, ["root:dev"]);
+        assert_eq!(scripts[0].parent().unwrap().root, root);
+
+        assert!(parent_scripts(&pkg_a, &[], &DescriptionSource::ALL).is_empty());
+        assert!(parent_scripts(&root, &["*".to_string()], &DescriptionSource::ALL).is_empty());
+    }
+
     // ==================== Workspace Scripts Tests ====================
 
     #[test]
@@ -631,7 +1217,13 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let root = create_monorepo(&temp, "npm");
 
-        let workspaces = detect_workspaces(&root).unwrap();
+        let workspaces = detect_workspaces(
+            &root,
+            &[],
+            &DescriptionSource::ALL,
+            WorkspaceResolution::Glob,
+        )
+        .unwrap();
 
         // Find pkg-a and check its scripts
         let pkg_a = workspaces.iter().find(|w| w.name() == "@monorepo/pkg-a");
@@ -645,6 +1237,87 @@ mod tests {
         assert!(script_names.contains(&"test"));
     }
 
+    #[test]
+    fn test_detect_workspaces_exclude_by_path() {
+        let temp = TempDir::new().unwrap();
+        let root = create_monorepo(&temp, "npm");
+
+        let exclude = vec!["packages/pkg-a".to_string()];
+        let workspaces = detect_workspaces(
+            &root,
+            &exclude,
+            &DescriptionSource::ALL,
+            WorkspaceResolution::Glob,
+        )
+        .unwrap();
+
+        let names: Vec<&str> = workspaces.iter().map(|w| w.name()).collect();
+        assert!(!names.contains(&"@monorepo/pkg-a"));
+        assert!(names.contains(&"@monorepo/pkg-b"));
+    }
+
+    #[test]
+    fn test_detect_workspaces_exclude_by_name() {
+        let temp = TempDir::new().unwrap();
+        let root = create_monorepo(&temp, "pnpm");
+
+        let exclude = vec!["@monorepo/*".to_string()];
+        let workspaces = detect_workspaces(
+            &root,
+            &exclude,
+            &DescriptionSource::ALL,
+            WorkspaceResolution::Glob,
+        )
+        .unwrap();
+        assert!(workspaces.is_empty());
+    }
+
+    #[test]
+    fn test_find_workspace() {
+        let root = Path::new("/repo");
+        let workspaces = vec![
+            Workspace::new("@app/web", "/repo/packages/web"),
+            Workspace::new("@app/api", "/repo/packages/api"),
+            Workspace::new("@tools/api", "/repo/tools/api"),
+        ];
+
+        let find = |query| find_workspace(&workspaces, root, query).map(|w| w.name());
+
+        assert_eq!(find("@app/web"), Some("@app/web"));
+        assert_eq!(find("packages/api"), Some("@app/api"));
+        assert_eq!(find("packages/api/"), Some("@app/api"));
+        assert_eq!(find("web"), Some("@app/web"));
+        // Ambiguous short name
+        assert_eq!(find("api"), None);
+        assert_eq!(find("missing"), None);
+    }
+
+    #[test]
+    fn test_detect_monorepo_tools() {
+        let temp = TempDir::new().unwrap();
+        assert!(detect_monorepo_tools(temp.path()).is_empty());
+
+        fs::write(temp.path().join("turbo.json"), "{}").unwrap();
+        fs::write(temp.path().join("nx.json"), "{}").unwrap();
+        assert_eq!(
+            detect_monorepo_tools(temp.path()),
+            vec![MonorepoTool::Turbo, MonorepoTool::Nx]
+        );
+    }
+
+    #[test]
+    fn test_describe_monorepo() {
+        assert_eq!(describe_monorepo(None, &[]), None);
+        assert_eq!(
+            describe_monorepo(Some(WorkspaceType::Pnpm), &[MonorepoTool::Turbo]),
+            Some("pnpm workspaces + turbo".to_string())
+        );
+        assert_eq!(
+            describe_monorepo(None, &[MonorepoTool::Nx]),
+            Some("nx".to_string())
+        );
+    }
+
     // ==================== Edge Cases ====================
 
     #[test]
@@ -652,7 +1325,12 @@ mod tests {
         let temp = TempDir::new().unwrap();
         create_package_json(temp.path(), "simple", &[("build", "tsc")]);
 
-        let info = detect_workspace_info(temp.path()).unwrap();
+        let info = detect_workspace_info(
+            temp.path(),
+            &DescriptionSource::ALL,
+            WorkspaceResolution::Glob,
+        )
+        .unwrap();
         assert!(!info.is_monorepo);
         assert!(info.workspaces.is_empty());
     }
@@ -675,12 +1353,115 @@ mod tests {
         // Create directory without package.json
         fs::create_dir_all(packages_dir.join("no-pkg")).unwrap();
 
-        let info = detect_workspace_info(root).unwrap();
+        let info = detect_workspace_info(root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
         assert!(info.is_monorepo);
         // Should not include the directory without package.json
         assert!(info.workspaces.is_empty());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_workspace_with_broken_package_json_symlink() {
+        let temp = TempDir::new().unwrap();
+        let root = create_monorepo(&temp, "npm");
+        let broken = root.join("packages/broken");
+        fs::create_dir_all(&broken).unwrap();
+        std::os::unix::fs::symlink(broken.join("gone.json"), broken.join("package.json")).unwrap();
+
+        let info = detect_workspace_info(&root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
+        let workspace = info
+            .workspaces
+            .iter()
+            .find(|w| w.name() == "broken")
+            .expect("unreadable workspace is kept");
+        assert_eq!(workspace.error(), Some("package.json: broken symlink"));
+        assert!(workspace.scripts().is_empty());
+        assert!(info.workspaces.iter().any(|w| w.error().is_none()));
+    }
+
+    /// Create a monorepo whose `apps/mobile` is a pnpm monorepo itself.
+    fn create_nested_monorepo(temp: &TempDir, root_patterns: &str) -> PathBuf {
+        let root = temp.path().to_path_buf();
+        fs::write(
+            root.join("package.json"),
+            format!(r#"{{"name": "root", "workspaces": [{root_patterns}]}}"#),
+        )
+        .unwrap();
+
+        let web = root.join("apps/web");
+        fs::create_dir_all(&web).unwrap();
+        create_package_json(&web, "web", &[("dev", "vite")]);
+
+        let mobile = root.join("apps/mobile");
+        let ui = mobile.join("packages/ui");
+        fs::create_dir_all(&ui).unwrap();
+        create_package_json(&mobile, "mobile", &[("start", "expo start")]);
+        fs::write(
+            mobile.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'packages/*'\n",
+        )
+        .unwrap();
+        create_package_json(&ui, "@mobile/ui", &[("build", "tsc")]);
+
+        root
+    }
+
+    #[test]
+    fn test_detect_nested_workspaces() {
+        let temp = TempDir::new().unwrap();
+        let root = create_nested_monorepo(&temp, r#""apps/*""#);
+
+        let workspaces = detect_workspaces(
+            &root,
+            &[],
+            &DescriptionSource::ALL,
+            WorkspaceResolution::Glob,
+        )
+        .unwrap();
+        let names: Vec<&str> = workspaces.iter().map(|w| w.name()).collect();
+        assert_eq!(names, vec!["mobile", "web"]);
+
+        let mobile = &workspaces[0];
+        assert!(mobile.has_members());
+        assert!(!workspaces[1].has_members());
+        assert_eq!(mobile.members()[0].name(), "@mobile/ui");
+        assert_eq!(mobile.members()[0].root(), Some(mobile.path()));
+        assert_eq!(mobile.root(), None);
+
+        // Nested members can be looked up and excluded like any other
+        let ui = find_workspace(&workspaces, &root, "ui").unwrap();
+        assert_eq!(ui.path(), root.join("apps/mobile/packages/ui"));
+        let excluded = detect_workspaces(
+            &root,
+            &["@mobile/*".to_string()],
+            &DescriptionSource::ALL,
+            WorkspaceResolution::Glob,
+        )
+        .unwrap();
+        assert!(!excluded[0].has_members());
+        assert_eq!(all_workspaces(&workspaces).len(), 3);
+    }
+
+    #[test]
+    fn test_nested_workspaces_listed_at_root_stay_there() {
+        let temp = TempDir::new().unwrap();
+        let root = create_nested_monorepo(&temp, r#""apps/*", "apps/mobile/packages/*""#);
+
+        let workspaces = detect_workspaces(
+            &root,
+            &[],
+            &DescriptionSource::ALL,
+            WorkspaceResolution::Glob,
+        )
+        .unwrap();
+        assert_eq!(workspaces.len(), 3);
+        assert!(workspaces
+            .iter()
+            .all(|w| !w.has_members() && w.root().is_none()));
+    }
+
     #[test]
     fn test_workspace_type_display() {
         assert_eq!(format!("{}", WorkspaceType::Npm), "npm workspaces");
@@ -715,7 +1496,8 @@ mod tests {
         fs::create_dir_all(&app).unwrap();
         create_package_json(&app, "@monorepo/web", &[("dev", "vite")]);
 
-        let info = detect_workspace_info(root).unwrap();
+        let info = detect_workspace_info(root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
         assert!(info.is_monorepo);
         assert_eq!(info.workspaces.len(), 2);
 
@@ -723,4 +1505,33 @@ mod tests {
         assert!(names.contains(&"@monorepo/lib"));
         assert!(names.contains(&"@monorepo/web"));
     }
+
+    #[test]
+    fn test_negated_workspace_patterns() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("pnpm-workspace.yaml"),
+            "packages:\n  - packages/**/*\n  - '!packages/internal-*'\n  - '!**/fixtures/**'\n",
+        )
+        .unwrap();
+        for dir in [
+            "packages/web",
+            "packages/internal-tools",
+            "packages/web/fixtures/app",
+        ] {
+            let path = root.join(dir);
+            fs::create_dir_all(&path).unwrap();
+            let name = dir.rsplit('/').next().unwrap();
+            create_package_json(&path, name, &[("build", "tsc")]);
+        }
+
+        let info = detect_workspace_info(root, &DescriptionSource::ALL, WorkspaceResolution::Glob)
+            .unwrap();
+        let names: Vec<&str> = all_workspaces(&info.workspaces)
+            .iter()
+            .map(|w| w.name())
+            .collect();
+        assert_eq!(names, ["web"]);
+    }
 }
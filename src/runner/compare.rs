@@ -0,0 +1,176 @@
+//! Runner comparison.
+//!
+//! Runs the same script through several package managers and reports how
+//! long each took, to help evaluate switching runners (e.g. npm to bun).
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+use super::executor::execute_script;
+use super::platform::new_command;
+use crate::package::Runner;
+
+/// Timings of a script run through one runner.
+#[derive(Debug, Clone)]
+pub struct RunnerTiming {
+    /// The runner used.
+    pub runner: Runner,
+    /// Time for the runner itself to start (`<runner> --version`).
+    pub startup: Duration,
+    /// Total time of the script run.
+    pub total: Duration,
+    /// Exit code of the script run.
+    pub exit_code: Option<i32>,
+}
+
+impl RunnerTiming {
+    /// Check if the script run succeeded.
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Measure how long a runner takes to start.
+///
+/// Runs `<runner> --version`, which boots the runner without doing any work.
+pub fn measure_startup(runner: Runner, project_dir: &Path) -> Result<Duration> {
+    let started = Instant::now();
+    new_command(runner.executable())
+        .arg("--version")
+        .current_dir(project_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("Failed to start {}", runner.executable()))?;
+    Ok(started.elapsed())
+}
+
+/// Run a script through a runner and time it.
+pub fn time_runner(
+    runner: Runner,
+    script: &str,
+    args: &[String],
+    project_dir: &Path,
+) -> Result<RunnerTiming> {
+    let startup = measure_startup(runner, project_dir)?;
+    let result = execute_script(runner, script, args, project_dir, false)?;
+
+    Ok(RunnerTiming {
+        runner,
+        startup,
+        total: result.duration,
+        exit_code: result.code(),
+    })
+}
+
+/// Format timings as a table, with differences relative to the first runner.
+pub fn format_comparison(script: &str, timings: &[RunnerTiming]) -> String {
+    let mut out = format!("Runner comparison for \"{script}\":\n\n");
+    out.push_str(&format!(
+        "  {:<8} {:>10} {:>10} {:>10}  {}\n",
+        "Runner", "Startup", "Total", "vs first", "Result"
+    ));
+
+    let baseline = timings.first().map(|t| t.total);
+    for timing in timings {
+        let diff = match baseline {
+            Some(base) if timing.runner != timings[0].runner => format_diff(base, timing.total),
+            _ => "-".to_string(),
+        };
+        let status = match timing.exit_code {
+            Some(0) => "ok".to_string(),
+            Some(code) => format!("exit {code}"),
+            None => "killed".to_string(),
+        };
+        out.push_str(&format!(
+            "  {:<8} {:>10} {:>10} {:>10}  {}\n",
+            timing.runner.executable(),
+            format_duration(timing.startup),
+            format_duration(timing.total),
+            diff,
+            status
+        ));
+    }
+
+    if timings.iter().any(|t| !t.success()) {
+        out.push_str("\nNote: some runs failed, so their times may not be comparable.\n");
+    }
+
+    out
+}
+
+/// Format a duration as seconds with millisecond precision.
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}s", duration.as_secs_f64())
+}
+
+/// Format the relative difference between two durations.
+fn format_diff(base: Duration, other: Duration) -> String {
+    let base = base.as_secs_f64();
+    if base <= 0.0 {
+        return "-".to_string();
+    }
+    let percent = (other.as_secs_f64() - base) / base * 100.0;
+    format!("{percent:+.0}%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(runner: Runner, startup_ms: u64, total_ms: u64, code: i32) -> RunnerTiming {
+        RunnerTiming {
+            runner,
+            startup: Duration::from_millis(startup_ms),
+            total: Duration::from_millis(total_ms),
+            exit_code: Some(code),
+        }
+    }
+
+    #[test]
+    fn test_format_diff() {
+        assert_eq!(
+            format_diff(Duration::from_secs(2), Duration::from_secs(1)),
+            "-50%"
+        );
+        assert_eq!(
+            format_diff(Duration::from_secs(1), Duration::from_millis(1250)),
+            "+25%"
+        );
+        assert_eq!(format_diff(Duration::ZERO, Duration::from_secs(1)), "-");
+    }
+
+    #[test]
+    fn test_format_comparison() {
+        let table = format_comparison(
+            "build",
+            &[
+                timing(Runner::Npm, 180, 4000, 0),
+                timing(Runner::Bun, 20, 1000, 0),
+            ],
+        );
+
+        assert!(table.contains("\"build\""));
+        assert!(table.contains("0.180s"));
+        assert!(table.contains("4.000s"));
+        assert!(table.contains("-75%"));
+        assert!(!table.contains("Note:"));
+    }
+
+    #[test]
+    fn test_format_comparison_notes_failures() {
+        let table = format_comparison(
+            "test",
+            &[
+                timing(Runner::Npm, 100, 900, 0),
+                timing(Runner::Bun, 10, 200, 1),
+            ],
+        );
+        assert!(table.contains("exit 1"));
+        assert!(table.contains("Note:"));
+    }
+}
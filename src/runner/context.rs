@@ -0,0 +1,124 @@
+//! Settings shared by the script runs of one nrs invocation.
+//!
+//! `--log`, `--record` and `general.enforce_package_manager` are read at
+//! startup into a [`RunContext`], which is passed along with every script
+//! nrs runs. Clones share the recording and the package manager checks.
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use super::logging::{self, RunLog};
+use super::pinned::{PinEnforcer, Prepared};
+use super::record::Recording;
+use crate::config::PackageManagerEnforcement;
+
+/// How scripts are run: logged, recorded, and checked against a pinned
+/// package manager.
+///
+/// The default context does none of these.
+#[derive(Debug, Clone, Default)]
+pub struct RunContext {
+    /// Log files to keep per script, when runs are logged (0 = all).
+    log_keep: Option<usize>,
+    recording: Option<Recording>,
+    pin: Option<Arc<PinEnforcer>>,
+}
+
+impl RunContext {
+    /// Create a context that runs scripts as they are.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Log the output of the runs, keeping `keep` logs per script (0 = all).
+    #[must_use]
+    pub fn with_logs(mut self, keep: usize) -> Self {
+        self.log_keep = Some(keep);
+        self
+    }
+
+    /// Record the output of the runs to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created.
+    pub fn with_recording(mut self, path: &Path) -> Result<Self> {
+        self.recording = Some(Recording::start(path)?);
+        Ok(self)
+    }
+
+    /// Check the package manager version pinned in `project_dir` before
+    /// runs, as `mode` says.
+    #[must_use]
+    pub fn with_pinned_package_manager(
+        mut self,
+        project_dir: &Path,
+        mode: PackageManagerEnforcement,
+    ) -> Self {
+        self.pin = PinEnforcer::read(project_dir, mode).map(Arc::new);
+        self
+    }
+
+    /// Start a log for a run of `script`, if runs are logged.
+    pub fn start_log(&self, project_dir: &Path, script: &str) -> Option<RunLog> {
+        logging::start(project_dir, script, self.log_keep?)
+    }
+
+    /// Add a line from nrs to the recording, if there is one.
+    pub fn note(&self, text: &str) {
+        if let Some(recording) = &self.recording {
+            recording.note(text);
+        }
+    }
+
+    /// Get a writer adding script output to the recording, if there is one.
+    pub fn recorder(&self) -> Option<Box<dyn Write + Send>> {
+        self.recording.as_ref().map(Recording::writer)
+    }
+
+    /// Prepare a command for running, checking the package manager it runs
+    /// against the pinned version, if one is enforced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pinned version is enforced and the command
+    /// can't run with it.
+    pub fn prepare(&self, cmd_parts: Vec<String>) -> Result<Prepared> {
+        match &self.pin {
+            Some(pin) => pin.prepare(cmd_parts),
+            None => Ok(Prepared {
+                cmd_parts,
+                warning: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_package_manager_only_when_enforced() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"packageManager": "pnpm@9.1.0"}"#,
+        )
+        .unwrap();
+        let build = || vec!["pnpm".to_string(), "run".to_string(), "build".to_string()];
+
+        let off = RunContext::new()
+            .with_pinned_package_manager(dir.path(), PackageManagerEnforcement::Off);
+        assert!(off.pin.is_none());
+        assert_eq!(off.prepare(build()).unwrap().cmd_parts, build());
+
+        let warned = RunContext::new()
+            .with_pinned_package_manager(dir.path(), PackageManagerEnforcement::Warn);
+        assert!(warned.pin.is_some());
+        assert!(warned.start_log(dir.path(), "build").is_none());
+    }
+}
@@ -5,23 +5,51 @@
 
 use std::io::{self, Write};
 use std::path::Path;
-use std::process::{Command, ExitStatus};
+use std::process::{Child, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
+use tracing::{debug, info};
 
+use super::context::RunContext;
+use super::limits::limited_command;
+use super::platform::{self, join_command, new_command, split_args};
+use super::{jobs, logging};
+use crate::config::ScriptLimits;
 use crate::package::{Runner, Script};
+use crate::utils::format_duration;
 
 /// Exit code when interrupted by Ctrl+C (SIGINT).
 /// On Unix, this is 128 + signal number (SIGINT = 2).
 pub const EXIT_CODE_INTERRUPTED: i32 = 130;
 
+/// Exit code when a script was killed for running past its timeout, as
+/// with `timeout(1)`.
+pub const EXIT_CODE_TIMED_OUT: i32 = 124;
+
+/// How often a script with a timeout is checked on.
+pub(super) const TIMEOUT_POLL: Duration = Duration::from_millis(50);
+
+/// How long a timed out or stopped script gets to exit before it's killed.
+pub(super) const TIMEOUT_GRACE: Duration = Duration::from_secs(5);
+
+/// Wait before the first retry, doubled for each one after it.
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
 /// Result of script execution.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ExecutionResult {
     /// Exit status of the script.
     pub status: ExitStatus,
     /// The command that was executed.
     pub command: String,
+    /// How long the script ran.
+    pub duration: Duration,
+    /// Whether the script was killed for running past its timeout.
+    pub timed_out: bool,
 }
 
 impl ExecutionResult {
@@ -34,6 +62,16 @@ impl ExecutionResult {
     pub fn code(&self) -> Option<i32> {
         self.status.code()
     }
+
+    /// Get the exit code, or [`EXIT_CODE_INTERRUPTED`] if the script was
+    /// interrupted and [`EXIT_CODE_TIMED_OUT`] if it timed out.
+    pub fn exit_code(&self) -> i32 {
+        if self.timed_out {
+            EXIT_CODE_TIMED_OUT
+        } else {
+            platform::exit_code(self.status)
+        }
+    }
 }
 
 /// Run a single script with the given runner.
@@ -59,14 +97,12 @@ pub fn run_script(
     args: Option<&str>,
     dry_run: bool,
 ) -> Result<i32> {
-    let args_vec: Vec<String> = args
-        .map(|a| shell_words::split(a).unwrap_or_else(|_| vec![a.to_string()]))
-        .unwrap_or_default();
+    let args_vec: Vec<String> = args.map(split_args).unwrap_or_default();
 
     let project_dir = std::env::current_dir().context("Failed to get current directory")?;
 
     execute_script(runner, script.name(), &args_vec, &project_dir, dry_run)
-        .map(|result| result.code().unwrap_or(EXIT_CODE_INTERRUPTED))
+        .map(|result| result.exit_code())
 }
 
 /// Run a single script in a specific directory.
@@ -94,12 +130,10 @@ pub fn run_script_in_dir(
     project_dir: &Path,
     dry_run: bool,
 ) -> Result<i32> {
-    let args_vec: Vec<String> = args
-        .map(|a| shell_words::split(a).unwrap_or_else(|_| vec![a.to_string()]))
-        .unwrap_or_default();
+    let args_vec: Vec<String> = args.map(split_args).unwrap_or_default();
 
     execute_script(runner, script.name(), &args_vec, project_dir, dry_run)
-        .map(|result| result.code().unwrap_or(EXIT_CODE_INTERRUPTED))
+        .map(|result| result.exit_code())
 }
 
 /// Run multiple scripts sequentially.
@@ -141,13 +175,10 @@ pub fn run_scripts(
         );
         io::stdout().flush().ok();
 
-        let args_vec: Vec<String> = args
-            .as_ref()
-            .map(|a| shell_words::split(a).unwrap_or_else(|_| vec![a.to_string()]))
-            .unwrap_or_default();
+        let args_vec: Vec<String> = args.as_deref().map(split_args).unwrap_or_default();
 
         let result = execute_script(runner, script.name(), &args_vec, &project_dir, dry_run)?;
-        let exit_code = result.code().unwrap_or(EXIT_CODE_INTERRUPTED);
+        let exit_code = result.exit_code();
         results.push(exit_code);
 
         // Stop on first failure (non-zero exit code)
@@ -204,13 +235,10 @@ pub fn run_scripts_in_dir(
         );
         io::stdout().flush().ok();
 
-        let args_vec: Vec<String> = args
-            .as_ref()
-            .map(|a| shell_words::split(a).unwrap_or_else(|_| vec![a.to_string()]))
-            .unwrap_or_default();
+        let args_vec: Vec<String> = args.as_deref().map(split_args).unwrap_or_default();
 
         let result = execute_script(runner, script.name(), &args_vec, project_dir, dry_run)?;
-        let exit_code = result.code().unwrap_or(EXIT_CODE_INTERRUPTED);
+        let exit_code = result.exit_code();
         results.push(exit_code);
 
         // Stop on first failure (non-zero exit code)
@@ -229,7 +257,8 @@ pub fn run_scripts_in_dir(
 
 /// Execute a script with the given runner.
 ///
-/// This is the low-level execution function that spawns the process.
+/// This is the low-level execution function that spawns the process. The
+/// run isn't logged or recorded, and no pinned package manager is enforced.
 ///
 /// # Arguments
 ///
@@ -248,45 +277,254 @@ pub fn execute_script(
     args: &[String],
     project_dir: &Path,
     dry_run: bool,
+) -> Result<ExecutionResult> {
+    execute_script_with_limits(
+        runner,
+        script,
+        args,
+        project_dir,
+        dry_run,
+        &ScriptLimits::default(),
+        &RunContext::default(),
+    )
+}
+
+/// Execute a script with resource limits.
+///
+/// Like [`execute_script`], but runs the script under `limits` (niceness,
+/// max memory) where the platform supports them, logged, recorded and
+/// checked as `context` says.
+///
+/// # Errors
+///
+/// Returns an error if the script fails to execute.
+pub fn execute_script_with_limits(
+    runner: Runner,
+    script: &str,
+    args: &[String],
+    project_dir: &Path,
+    dry_run: bool,
+    limits: &ScriptLimits,
+    context: &RunContext,
 ) -> Result<ExecutionResult> {
     let cmd_parts = runner.run_command_with_args(script, args);
-    let command_str = cmd_parts.join(" ");
+    execute_command(cmd_parts, script, project_dir, dry_run, limits, context)
+}
+
+/// Run a command with inherited stdio, or print it in dry-run mode.
+///
+/// With run logs enabled in `context`, the output is also copied to a log
+/// for `script`.
+/// A failed run is tried again up to `limits.retries` times, waiting longer
+/// before each attempt. Interrupted runs aren't retried.
+fn execute_command(
+    cmd_parts: Vec<String>,
+    script: &str,
+    project_dir: &Path,
+    dry_run: bool,
+    limits: &ScriptLimits,
+    context: &RunContext,
+) -> Result<ExecutionResult> {
+    // Quoted, so arguments holding spaces show as the words they are
+    let command_str = join_command(&cmd_parts);
 
     if dry_run {
-        println!("Would run: {command_str}");
+        if limits.is_empty() {
+            println!("Would run: {command_str}");
+        } else {
+            println!("Would run: {command_str} ({})", limits.describe());
+        }
         return Ok(ExecutionResult {
             status: std::process::ExitStatus::default(),
             command: command_str,
+            duration: Duration::ZERO,
+            timed_out: false,
         });
     }
 
-    let mut command = Command::new(&cmd_parts[0]);
+    let prepared = context.prepare(cmd_parts)?;
+    if let Some(warning) = &prepared.warning {
+        eprintln!("Warning: {warning}");
+    }
+    let cmd_parts = limited_command(prepared.cmd_parts, limits);
+    let attempts = limits.retries + 1;
+    let mut attempt = 1;
+    loop {
+        let result = run_command(
+            &cmd_parts,
+            &command_str,
+            script,
+            project_dir,
+            limits,
+            context,
+        )?;
+        if result.timed_out {
+            eprintln!(
+                "\x1b[1;31m{script} timed out after {}\x1b[0m",
+                format_duration(limits.timeout.unwrap_or_default())
+            );
+        }
+        let retry = !result.success() && (result.timed_out || !interrupted(&result));
+        if !retry || attempt == attempts {
+            return Ok(result);
+        }
+
+        let backoff = RETRY_BACKOFF * 2u32.saturating_pow(attempt - 1);
+        attempt += 1;
+        eprintln!(
+            "\x1b[1;33m{script} failed with exit code {}, retrying in {} (attempt {attempt}/{attempts})\x1b[0m",
+            result.exit_code(),
+            format_duration(backoff)
+        );
+        thread::sleep(backoff);
+    }
+}
+
+/// Check if a run ended with Ctrl+C.
+fn interrupted(result: &ExecutionResult) -> bool {
+    result.exit_code() == EXIT_CODE_INTERRUPTED
+}
+
+/// Run a command once, killing it if it runs past `limits.timeout`.
+fn run_command(
+    cmd_parts: &[String],
+    command_str: &str,
+    script: &str,
+    project_dir: &Path,
+    limits: &ScriptLimits,
+    context: &RunContext,
+) -> Result<ExecutionResult> {
+    let mut command = new_command(&cmd_parts[0]);
     command.args(&cmd_parts[1..]);
     command.current_dir(project_dir);
 
-    // Inherit stdio for interactive scripts
-    command.stdin(std::process::Stdio::inherit());
-    command.stdout(std::process::Stdio::inherit());
-    command.stderr(std::process::Stdio::inherit());
+    // Inherit stdio, unless the output is logged or recorded: that pipes it,
+    // so scripts that need a terminal are left out. Scripts with a timeout
+    // get a process group to kill, which leaves them no terminal to read from.
+    let sinks: Vec<Box<dyn Write + Send>> = if limits.interactive {
+        Vec::new()
+    } else {
+        context
+            .start_log(project_dir, script)
+            .map(|log| Box::new(log) as Box<dyn Write + Send>)
+            .into_iter()
+            .chain(context.recorder())
+            .collect()
+    };
+    if limits.timeout.is_some() {
+        platform::use_own_process_group(&mut command);
+        command.stdin(Stdio::null());
+    } else {
+        command.stdin(Stdio::inherit());
+    }
+    if sinks.is_empty() {
+        command.stdout(Stdio::inherit());
+        command.stderr(Stdio::inherit());
+    } else {
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+    }
 
-    let status = command
-        .status()
+    info!("Running {command_str} in {}", project_dir.display());
+    context.note(&format!("$ {command_str}"));
+    if limits.interactive {
+        context.note("(interactive script, output not recorded)");
+    }
+    let started = Instant::now();
+    let mut child = command
+        .spawn()
         .with_context(|| format!("Failed to execute: {command_str}"))?;
+    let _job = jobs::register(project_dir, script, child.id());
+    let tee = (!sinks.is_empty()).then(|| logging::tee(&mut child, sinks));
+    let waited = match limits.timeout {
+        Some(timeout) => wait_with_timeout(&mut child, timeout),
+        None => child.wait().map(|status| (status, false)),
+    };
+    if let Some(tee) = tee {
+        tee.finish();
+    }
+    let (status, timed_out) =
+        waited.with_context(|| format!("Failed to execute: {command_str}"))?;
+    debug!("{command_str} exited with {status}");
 
-    Ok(ExecutionResult {
+    let result = ExecutionResult {
         status,
-        command: command_str,
-    })
+        command: command_str.to_string(),
+        duration: started.elapsed(),
+        timed_out,
+    };
+    context.note(&format!("exit code {}", result.exit_code()));
+    Ok(result)
+}
+
+/// Wait for a process started in its own process group, stopping the group
+/// once `timeout` has passed.
+///
+/// The group gets `SIGTERM` first, then `SIGKILL` if it's still running
+/// after a grace period. Ctrl+C is passed on, since the terminal only sends
+/// it to its foreground group. Returns the exit status and whether the
+/// process timed out.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> io::Result<(ExitStatus, bool)> {
+    let ctrl_c = Arc::new(AtomicBool::new(false));
+    let signal = signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&ctrl_c))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut terminated = false;
+    let mut killed = false;
+    let result = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok((status, terminated)),
+            Ok(None) => {}
+            Err(err) => break Err(err),
+        }
+
+        let pid = child.id();
+        if ctrl_c.swap(false, Ordering::Relaxed) {
+            let _ = platform::signal_process_group(pid, "INT");
+        }
+        let now = Instant::now();
+        if !terminated && now >= deadline {
+            info!(
+                "Timed out after {}, stopping process group {pid}",
+                format_duration(timeout)
+            );
+            terminated = true;
+            let _ = platform::signal_process_group(pid, "TERM");
+        } else if terminated && !killed && now >= deadline + TIMEOUT_GRACE {
+            killed = true;
+            let _ = platform::signal_process_group(pid, "KILL");
+        }
+        thread::sleep(TIMEOUT_POLL);
+    };
+
+    signal_hook::low_level::unregister(signal);
+    result
 }
 
 /// Format a command for display in dry-run mode.
 pub fn format_dry_run_command(runner: Runner, script: &str, args: Option<&str>) -> String {
-    let args_vec: Vec<String> = args
-        .map(|a| shell_words::split(a).unwrap_or_else(|_| vec![a.to_string()]))
-        .unwrap_or_default();
+    let args_vec: Vec<String> = args.map(split_args).unwrap_or_default();
 
     let cmd = runner.run_command_with_args(script, &args_vec);
-    format!("Would run: {}", cmd.join(" "))
+    format!("Would run: {}", join_command(&cmd))
+}
+
+/// Format the full command for a script as a shell-quoted string.
+///
+/// Used by `--popup` to hand the command back to the parent shell.
+pub fn format_shell_command(
+    runner: Runner,
+    workspace: Option<&str>,
+    script: &str,
+    args: Option<&str>,
+) -> String {
+    let args_vec: Vec<String> = args.map(split_args).unwrap_or_default();
+
+    let cmd = match workspace {
+        Some(workspace) => runner.workspace_command_with_args(workspace, script, &args_vec),
+        None => runner.run_command_with_args(script, &args_vec),
+    };
+    join_command(&cmd)
 }
 
 /// Execute a workspace script with the given runner.
@@ -313,34 +551,36 @@ pub fn execute_workspace_script(
     project_dir: &Path,
     dry_run: bool,
 ) -> Result<ExecutionResult> {
-    let cmd_parts = runner.workspace_command_with_args(workspace, script, args);
-    let command_str = cmd_parts.join(" ");
-
-    if dry_run {
-        println!("Would run: {command_str}");
-        return Ok(ExecutionResult {
-            status: std::process::ExitStatus::default(),
-            command: command_str,
-        });
-    }
-
-    let mut command = Command::new(&cmd_parts[0]);
-    command.args(&cmd_parts[1..]);
-    command.current_dir(project_dir);
-
-    // Inherit stdio for interactive scripts
-    command.stdin(std::process::Stdio::inherit());
-    command.stdout(std::process::Stdio::inherit());
-    command.stderr(std::process::Stdio::inherit());
-
-    let status = command
-        .status()
-        .with_context(|| format!("Failed to execute: {command_str}"))?;
+    execute_workspace_script_with_limits(
+        runner,
+        workspace,
+        script,
+        args,
+        project_dir,
+        dry_run,
+        &ScriptLimits::default(),
+        &RunContext::default(),
+    )
+}
 
-    Ok(ExecutionResult {
-        status,
-        command: command_str,
-    })
+/// Execute a workspace script with resource limits.
+///
+/// # Errors
+///
+/// Returns an error if the script fails to execute.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_workspace_script_with_limits(
+    runner: Runner,
+    workspace: &str,
+    script: &str,
+    args: &[String],
+    project_dir: &Path,
+    dry_run: bool,
+    limits: &ScriptLimits,
+    context: &RunContext,
+) -> Result<ExecutionResult> {
+    let cmd_parts = runner.workspace_command_with_args(workspace, script, args);
+    execute_command(cmd_parts, script, project_dir, dry_run, limits, context)
 }
 
 /// Run a workspace script.
@@ -365,9 +605,7 @@ pub fn run_workspace_script(
     project_dir: &Path,
     dry_run: bool,
 ) -> Result<i32> {
-    let args_vec: Vec<String> = args
-        .map(|a| shell_words::split(a).unwrap_or_else(|_| vec![a.to_string()]))
-        .unwrap_or_default();
+    let args_vec: Vec<String> = args.map(split_args).unwrap_or_default();
 
     execute_workspace_script(
         runner,
@@ -377,7 +615,7 @@ pub fn run_workspace_script(
         project_dir,
         dry_run,
     )
-    .map(|result| result.code().unwrap_or(EXIT_CODE_INTERRUPTED))
+    .map(|result| result.exit_code())
 }
 
 /// Format a workspace command for display in dry-run mode.
@@ -387,12 +625,10 @@ pub fn format_workspace_dry_run_command(
     script: &str,
     args: Option<&str>,
 ) -> String {
-    let args_vec: Vec<String> = args
-        .map(|a| shell_words::split(a).unwrap_or_else(|_| vec![a.to_string()]))
-        .unwrap_or_default();
+    let args_vec: Vec<String> = args.map(split_args).unwrap_or_default();
 
     let cmd = runner.workspace_command_with_args(workspace, script, &args_vec);
-    format!("Would run: {}", cmd.join(" "))
+    format!("Would run: {}", join_command(&cmd))
 }
 
 #[cfg(test)]
@@ -442,6 +678,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_shell_command() {
+        assert_eq!(
+            format_shell_command(Runner::Npm, None, "test", Some("--grep 'a b'")),
+            "npm run test -- --grep 'a b'"
+        );
+        assert_eq!(
+            format_shell_command(Runner::Pnpm, Some("@app/web"), "build", None),
+            "pnpm --filter @app/web build"
+        );
+    }
+
     #[test]
     fn test_run_script_dry_run() {
         let script = Script::new("dev", "vite");
@@ -0,0 +1,114 @@
+//! Output filters.
+//!
+//! Lines matching one of the `output.hide` patterns are left out of the
+//! output pane and its log, so a dev server's progress spam doesn't bury
+//! the lines that matter. With `output.collapse`, each run of hidden lines
+//! shows up as a single "lines hidden" line instead.
+
+use regex::RegexSet;
+use tracing::warn;
+
+use super::stream::OutputLine;
+use crate::config::OutputConfig;
+
+/// Hides the noisy lines of a run's output.
+#[derive(Debug, Clone, Default)]
+pub struct OutputFilter {
+    hide: Option<RegexSet>,
+    collapse: bool,
+    /// Lines hidden since the last line shown.
+    hidden: usize,
+}
+
+impl OutputFilter {
+    /// Create a filter from the output settings.
+    ///
+    /// Patterns are checked when the config is read, so a set that still
+    /// fails to build is warned about and nothing is hidden.
+    pub fn new(config: &OutputConfig) -> Self {
+        let hide = if config.hide.is_empty() {
+            None
+        } else {
+            RegexSet::new(&config.hide)
+                .map_err(|err| warn!("Ignoring output.hide: {err}"))
+                .ok()
+        };
+        Self {
+            hide,
+            collapse: config.collapse,
+            hidden: 0,
+        }
+    }
+
+    /// Filter a line of output.
+    ///
+    /// Returns the line when it's shown. A hidden line returns nothing, or
+    /// a marker counting the hidden lines so far when collapsing; markers of
+    /// the same run replace each other.
+    pub fn apply(&mut self, line: OutputLine) -> Option<OutputLine> {
+        if !self
+            .hide
+            .as_ref()
+            .is_some_and(|hide| hide.is_match(&line.text))
+        {
+            self.hidden = 0;
+            return Some(line);
+        }
+        self.hidden += 1;
+        self.collapse.then(|| OutputLine::hidden(self.hidden))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> OutputLine {
+        OutputLine {
+            text: text.to_string(),
+            stderr: false,
+            hidden: 0,
+        }
+    }
+
+    fn filter(collapse: bool) -> OutputFilter {
+        OutputFilter::new(&OutputConfig {
+            hide: vec!["^webpack.Progress".to_string(), r"\bHMR\b".to_string()],
+            collapse,
+        })
+    }
+
+    #[test]
+    fn test_hides_matching_lines() {
+        let mut filter = filter(false);
+        assert_eq!(filter.apply(line("compiling")), Some(line("compiling")));
+        assert_eq!(filter.apply(line("webpack.Progress 10%")), None);
+        assert_eq!(filter.apply(line("[vite] HMR update")), None);
+        assert_eq!(filter.apply(line("done")), Some(line("done")));
+    }
+
+    #[test]
+    fn test_collapses_hidden_runs() {
+        let mut filter = filter(true);
+        let shown: Vec<OutputLine> = [
+            "webpack.Progress 10%",
+            "webpack.Progress 90%",
+            "compiled",
+            "HMR ready",
+        ]
+        .into_iter()
+        .filter_map(|text| filter.apply(line(text)))
+        .collect();
+
+        let hidden: Vec<usize> = shown.iter().map(|line| line.hidden).collect();
+        assert_eq!(hidden, [1, 2, 0, 1]);
+        assert_eq!(shown[1].text, "… 2 lines hidden");
+        assert_eq!(shown[2].text, "compiled");
+    }
+
+    #[test]
+    fn test_without_patterns_shows_everything() {
+        let mut filter = OutputFilter::new(&OutputConfig::default());
+        assert_eq!(filter.apply(line("anything")), Some(line("anything")));
+    }
+}
@@ -0,0 +1,200 @@
+//! Signs of life for long script runs.
+//!
+//! While a script runs outside the TUI, the terminal title shows the script
+//! and how long it has been running, and an optional faint heartbeat line is
+//! printed every few minutes, so detached builds can be checked at a glance.
+//! When it finishes, the terminal can be told with a bell or an OSC 9/777
+//! notification.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::config::{CompletionNotice, ProgressConfig};
+use crate::utils::format_elapsed;
+
+/// How often the terminal title is refreshed.
+const TICK: Duration = Duration::from_secs(1);
+
+/// Progress reporting for a running script.
+///
+/// Reporting stops and the previous terminal title is restored on drop.
+/// Use [`Heartbeat::finish`] to also send the completion notice.
+pub struct Heartbeat {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+    label: String,
+    started: Instant,
+    notice: CompletionNotice,
+}
+
+impl Heartbeat {
+    /// Start reporting progress for `label` on stderr.
+    ///
+    /// Returns `None` when stderr isn't a terminal or reporting is disabled.
+    pub fn start(label: &str, config: &ProgressConfig) -> Option<Self> {
+        if !io::stderr().is_terminal() {
+            return None;
+        }
+        let interval = (config.heartbeat_minutes > 0)
+            .then(|| Duration::from_secs(config.heartbeat_minutes * 60));
+        if !config.terminal_title && interval.is_none() && config.notify == CompletionNotice::Off {
+            return None;
+        }
+
+        let title = config.terminal_title;
+        let notice_label = label.to_string();
+        let label = label.to_string();
+        let (stop, receiver) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            let started = Instant::now();
+            let mut next_beat = interval;
+            if title {
+                // Save the current title so it can be restored afterwards
+                write_stderr(&format!("\x1b[22;0t{}", set_title(&label, Duration::ZERO)));
+            }
+
+            while let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(TICK) {
+                let elapsed = started.elapsed();
+                if title {
+                    write_stderr(&set_title(&label, elapsed));
+                }
+                if let (Some(beat), Some(interval)) = (next_beat, interval) {
+                    if elapsed >= beat {
+                        write_stderr(&heartbeat_line(&label, elapsed));
+                        next_beat = Some(beat + interval);
+                    }
+                }
+            }
+
+            if title {
+                write_stderr("\x1b[23;0t");
+            }
+        });
+
+        Some(Self {
+            stop: Some(stop),
+            handle: Some(handle),
+            label: notice_label,
+            started: Instant::now(),
+            notice: config.notify,
+        })
+    }
+
+    /// Stop reporting and tell the terminal that the run finished.
+    pub fn finish(mut self, success: bool) {
+        self.stop();
+        send_notice(self.notice, &self.label, success, self.started.elapsed());
+    }
+
+    /// Stop the reporting thread and restore the terminal title.
+    fn stop(&mut self) {
+        // Dropping the sender wakes the thread up
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Tell the terminal on stderr that a run of `label` finished.
+///
+/// Does nothing when stderr isn't a terminal.
+pub fn send_notice(notice: CompletionNotice, label: &str, success: bool, elapsed: Duration) {
+    if let Some(sequence) = notice_sequence(notice, label, success, elapsed) {
+        if io::stderr().is_terminal() {
+            write_stderr(&sequence);
+        }
+    }
+}
+
+/// Build the escape sequence for a completion notice.
+fn notice_sequence(
+    notice: CompletionNotice,
+    label: &str,
+    success: bool,
+    elapsed: Duration,
+) -> Option<String> {
+    let outcome = if success { "finished" } else { "failed" };
+    // Control characters would end the sequence early, and `;` separates
+    // the OSC 777 fields
+    let label: String = label
+        .chars()
+        .filter(|c| !c.is_control() && *c != ';')
+        .collect();
+    let message = format!("{label} {outcome} after {}", format_elapsed(elapsed));
+    match notice {
+        CompletionNotice::Off => None,
+        CompletionNotice::Bell => Some("\x07".to_string()),
+        CompletionNotice::Osc9 => Some(format!("\x1b]9;nrs: {message}\x07")),
+        CompletionNotice::Osc777 => Some(format!("\x1b]777;notify;nrs;{message}\x07")),
+    }
+}
+
+/// Build the escape sequence setting the terminal title.
+fn set_title(label: &str, elapsed: Duration) -> String {
+    format!("\x1b]0;nrs: {label} · {}\x07", format_elapsed(elapsed))
+}
+
+/// Build a faint heartbeat line.
+fn heartbeat_line(label: &str, elapsed: Duration) -> String {
+    format!(
+        "\x1b[2m[nrs] {label} still running ({})\x1b[0m\n",
+        format_elapsed(elapsed)
+    )
+}
+
+/// Write to stderr, ignoring errors.
+fn write_stderr(text: &str) {
+    let mut stderr = io::stderr().lock();
+    let _ = stderr.write_all(text.as_bytes());
+    let _ = stderr.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_title() {
+        assert_eq!(
+            set_title("build", Duration::from_secs(185)),
+            "\x1b]0;nrs: build · 3m 05s\x07"
+        );
+    }
+
+    #[test]
+    fn test_notice_sequence() {
+        let elapsed = Duration::from_secs(42);
+        assert_eq!(
+            notice_sequence(CompletionNotice::Off, "build", true, elapsed),
+            None
+        );
+        assert_eq!(
+            notice_sequence(CompletionNotice::Bell, "build", true, elapsed).as_deref(),
+            Some("\x07")
+        );
+        assert_eq!(
+            notice_sequence(CompletionNotice::Osc9, "build", true, elapsed).as_deref(),
+            Some("\x1b]9;nrs: build finished after 42s\x07")
+        );
+        assert_eq!(
+            notice_sequence(CompletionNotice::Osc777, "a;b\x07", false, elapsed).as_deref(),
+            Some("\x1b]777;notify;nrs;ab failed after 42s\x07")
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_line() {
+        let line = heartbeat_line("build", Duration::from_secs(600));
+        assert!(line.contains("[nrs] build still running (10m 00s)"));
+        assert!(line.ends_with('\n'));
+    }
+}
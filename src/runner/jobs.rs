@@ -0,0 +1,248 @@
+//! Running jobs.
+//!
+//! Each script nrs starts is recorded under `jobs/<project>/` in the state
+//! directory while it runs, one file per process. Another nrs can then tell
+//! that a script from the same `[concurrency]` group is already running and
+//! offer to stop it first. Records left by processes that are gone, e.g.
+//! after a crash, are removed when read. So are records whose PID now
+//! belongs to another process: a record keeps the process start time where
+//! the platform has one, or else the PID of the nrs that wrote it, which
+//! must still be running.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use super::platform::{process_alive, process_start_time, stop_process};
+use crate::utils::{clock, project_slug, state_dir};
+
+/// How long to wait for a stopped job to exit.
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A script running in a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Job {
+    /// The script name.
+    pub script: String,
+    /// Process ID of the package manager running the script.
+    pub pid: u32,
+    /// When the script started.
+    pub started: DateTime<Utc>,
+    /// Start time of the process, as [`process_start_time`] gives it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_start: Option<u64>,
+    /// Process ID of the nrs that started the script.
+    #[serde(default)]
+    pub owner: u32,
+}
+
+impl Job {
+    /// Check if the recorded process is still running.
+    pub fn is_running(&self) -> bool {
+        self.is_running_with(process_alive, process_start_time)
+    }
+
+    /// Check if the recorded process is still running, given how to check
+    /// a PID and get a process's start time.
+    ///
+    /// A PID that's alive could have been given to another process since.
+    /// With a start time on both sides they must match; otherwise the nrs
+    /// that recorded the job must still be running.
+    fn is_running_with(
+        &self,
+        alive: impl Fn(u32) -> bool,
+        start_time: impl Fn(u32) -> Option<u64>,
+    ) -> bool {
+        if !alive(self.pid) {
+            return false;
+        }
+        match (self.process_start, start_time(self.pid)) {
+            (Some(recorded), Some(current)) => recorded == current,
+            _ => self.owner != 0 && alive(self.owner),
+        }
+    }
+}
+
+/// The record of a running job, removed when dropped.
+#[derive(Debug)]
+pub struct JobRecord {
+    path: PathBuf,
+}
+
+impl Drop for JobRecord {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Get the directory a project's running jobs are recorded in.
+fn jobs_dir(project_dir: &Path) -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join("jobs").join(project_slug(project_dir)))
+}
+
+/// Record a script started in a project, until the record is dropped.
+///
+/// A record that can't be written is only logged, the script runs anyway.
+pub fn register(project_dir: &Path, script: &str, pid: u32) -> Option<JobRecord> {
+    let job = Job {
+        script: script.to_string(),
+        pid,
+        started: clock::now(),
+        process_start: process_start_time(pid),
+        owner: std::process::id(),
+    };
+    match register_in(&jobs_dir(project_dir)?, &job) {
+        Ok(record) => Some(record),
+        Err(err) => {
+            warn!("Failed to record {script} as running: {err:#}");
+            None
+        }
+    }
+}
+
+/// Write a job's record in `dir`.
+fn register_in(dir: &Path, job: &Job) -> Result<JobRecord> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    // Written aside and renamed, so a reader never sees half a record
+    let path = dir.join(format!("{}.json", job.pid));
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, serde_json::to_string(job)?)
+        .with_context(|| format!("Failed to write {}", temp.display()))?;
+    fs::rename(&temp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(JobRecord { path })
+}
+
+/// List the scripts running in a project, oldest first.
+pub fn running_jobs(project_dir: &Path) -> Vec<Job> {
+    match jobs_dir(project_dir) {
+        Some(dir) => running_jobs_in(&dir, Job::is_running),
+        None => Vec::new(),
+    }
+}
+
+/// List the jobs recorded in `dir` that are `running`, removing the rest.
+fn running_jobs_in(dir: &Path, running: impl Fn(&Job) -> bool) -> Vec<Job> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut jobs: Vec<Job> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let job = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Job>(&content).ok());
+            match job {
+                Some(job) if running(&job) => Some(job),
+                _ => {
+                    debug!("Removing stale job record {}", path.display());
+                    let _ = fs::remove_file(&path);
+                    None
+                }
+            }
+        })
+        .collect();
+    jobs.sort_by_key(|job| job.started);
+    jobs
+}
+
+/// Stop a running job and wait for it to exit.
+///
+/// A job that has stopped since it was listed is left alone, so its PID
+/// isn't signalled once it could belong to another process.
+///
+/// # Errors
+///
+/// Returns an error if the process can't be signalled or is still running
+/// after a few seconds.
+pub fn stop_job(job: &Job) -> Result<()> {
+    if !job.is_running() {
+        return Ok(());
+    }
+    stop_process(job.pid).with_context(|| format!("Failed to stop {}", job.script))?;
+
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while job.is_running() {
+        if Instant::now() >= deadline {
+            anyhow::bail!("{} (pid {}) is still running", job.script, job.pid);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(script: &str, pid: u32) -> Job {
+        Job {
+            script: script.to_string(),
+            pid,
+            started: clock::now(),
+            process_start: Some(1000),
+            owner: 40,
+        }
+    }
+
+    #[test]
+    fn test_record_removed_when_dropped() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let record = register_in(dir.path(), &job("dev", 41)).unwrap();
+
+        let jobs = running_jobs_in(dir.path(), |_| true);
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].script, "dev");
+        assert_eq!(jobs[0].pid, 41);
+
+        drop(record);
+        assert!(running_jobs_in(dir.path(), |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_stale_records_are_removed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let live = register_in(dir.path(), &job("dev", 41)).unwrap();
+        let dead = register_in(dir.path(), &job("dev:https", 42)).unwrap();
+        fs::write(dir.path().join("garbage.json"), "{").unwrap();
+
+        let jobs = running_jobs_in(dir.path(), |job| job.pid == 41);
+        let scripts: Vec<&str> = jobs.iter().map(|j| j.script.as_str()).collect();
+        assert_eq!(scripts, ["dev"]);
+        assert!(!dead.path.exists());
+        assert!(live.path.exists());
+        assert!(!dir.path().join("garbage.json").exists());
+    }
+
+    #[test]
+    fn test_reused_pid_is_not_running() {
+        let job = job("dev", 41);
+        let alive = |pid| pid == 41;
+        assert!(job.is_running_with(alive, |_| Some(1000)));
+        // Another process was given PID 41 since
+        assert!(!job.is_running_with(alive, |_| Some(2000)));
+        assert!(!job.is_running_with(|_| false, |_| Some(1000)));
+    }
+
+    #[test]
+    fn test_without_start_time_the_owner_must_run() {
+        let job = Job {
+            process_start: None,
+            ..job("dev", 41)
+        };
+        assert!(job.is_running_with(|pid| pid == 41 || pid == 40, |_| None));
+        assert!(!job.is_running_with(|pid| pid == 41, |_| None));
+
+        // Records without an owner can't be told from a reused PID
+        let unowned = Job { owner: 0, ..job };
+        assert!(!unowned.is_running_with(|_| true, |_| None));
+    }
+}
@@ -0,0 +1,125 @@
+//! Resource limits for script runs.
+//!
+//! Limits are applied by wrapping the runner command: `nice` for CPU
+//! priority, and a transient systemd scope or `ulimit -v` for memory.
+
+use std::env;
+use std::path::Path;
+
+use tracing::{debug, warn};
+
+use crate::config::ScriptLimits;
+
+/// Wrap a command so it runs with the given limits.
+///
+/// Limits the platform doesn't support are skipped with a warning.
+pub fn limited_command(cmd_parts: Vec<String>, limits: &ScriptLimits) -> Vec<String> {
+    if limits.is_empty() {
+        return cmd_parts;
+    }
+    let cmd = wrap_command(cmd_parts, limits, systemd_scope_available());
+    debug!("Limited command: {}", cmd.join(" "));
+    cmd
+}
+
+/// Wrap a command with limits, using a systemd scope for memory if allowed.
+fn wrap_command(cmd_parts: Vec<String>, limits: &ScriptLimits, use_systemd: bool) -> Vec<String> {
+    let mut cmd = cmd_parts;
+
+    if let Some(mb) = limits.max_memory_mb {
+        if !cfg!(target_os = "linux") {
+            warn!("Memory limits are only supported on Linux, ignoring max_memory_mb");
+        } else if use_systemd {
+            let mut scoped = vec![
+                "systemd-run".to_string(),
+                "--user".to_string(),
+                "--scope".to_string(),
+                "--quiet".to_string(),
+                "--collect".to_string(),
+                "-p".to_string(),
+                format!("MemoryMax={mb}M"),
+                "--".to_string(),
+            ];
+            scoped.extend(cmd);
+            cmd = scoped;
+        } else {
+            // Virtual memory, so this is looser than a cgroup limit
+            let mut ulimit = vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"ulimit -v "$1" && shift && exec "$@""#.to_string(),
+                "nrs".to_string(),
+                (mb * 1024).to_string(),
+            ];
+            ulimit.extend(cmd);
+            cmd = ulimit;
+        }
+    }
+
+    if let Some(nice) = limits.nice {
+        if cfg!(unix) {
+            let mut niced = vec!["nice".to_string(), "-n".to_string(), nice.to_string()];
+            niced.extend(cmd);
+            cmd = niced;
+        } else {
+            warn!("nice is only supported on Unix, ignoring it");
+        }
+    }
+
+    cmd
+}
+
+/// Check if memory limits can use a transient systemd user scope.
+fn systemd_scope_available() -> bool {
+    Path::new("/run/systemd/system").exists()
+        && env::var_os("XDG_RUNTIME_DIR").is_some()
+        && env::var_os("PATH").is_some_and(|path| {
+            env::split_paths(&path).any(|dir| dir.join("systemd-run").is_file())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn npm_run(script: &str) -> Vec<String> {
+        vec!["npm".to_string(), "run".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn test_no_limits_leaves_command() {
+        let limits = ScriptLimits::default();
+        assert_eq!(limited_command(npm_run("dev"), &limits), npm_run("dev"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_nice_prefix() {
+        let limits = ScriptLimits {
+            nice: Some(10),
+            ..ScriptLimits::default()
+        };
+        assert_eq!(
+            wrap_command(npm_run("build"), &limits, false).join(" "),
+            "nice -n 10 npm run build"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_memory_limit() {
+        let limits = ScriptLimits {
+            nice: Some(5),
+            max_memory_mb: Some(2048),
+            ..ScriptLimits::default()
+        };
+        assert_eq!(
+            wrap_command(npm_run("build"), &limits, true).join(" "),
+            "nice -n 5 systemd-run --user --scope --quiet --collect -p MemoryMax=2048M -- npm run build"
+        );
+
+        let cmd = wrap_command(npm_run("build"), &limits, false);
+        assert_eq!(cmd[..5], ["nice", "-n", "5", "sh", "-c"]);
+        assert_eq!(cmd[6..], ["nrs", "2097152", "npm", "run", "build"]);
+    }
+}
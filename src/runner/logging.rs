@@ -0,0 +1,329 @@
+//! Run logs.
+//!
+//! With `--log` or `logs.enabled`, script output is copied to a timestamped
+//! file under `logs/<project>/` in the state directory while it still goes
+//! to the terminal. `nrs logs` lists the files and `nrs logs --tail <script>`
+//! shows the latest one.
+//!
+//! File names are `<timestamp>-<script>.log`, with characters that aren't
+//! safe in file names percent-encoded, so the script name can be read back.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tracing::{info, warn};
+
+use crate::utils::{clock, project_slug, state_dir};
+
+/// Timestamp at the start of log file names, sortable as text.
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+/// Length of a formatted timestamp.
+const TIMESTAMP_LEN: usize = 19;
+
+/// Get the directory a project's logs are kept in.
+pub fn logs_dir(project_dir: &Path) -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join("logs").join(project_slug(project_dir)))
+}
+
+/// A log file being written.
+#[derive(Debug)]
+pub struct RunLog {
+    file: File,
+    path: PathBuf,
+}
+
+impl RunLog {
+    /// Create a log file for a run of `script` in `dir`.
+    ///
+    /// Older logs of the same script beyond `keep` are removed (0 = keep all).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or file can't be created.
+    pub fn create(dir: &Path, script: &str, keep: usize) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        // Two runs starting in the same millisecond get consecutive times
+        let mut started = clock::now();
+        let (file, path) = loop {
+            let path = dir.join(format!(
+                "{}-{}.log",
+                started.format(TIMESTAMP_FORMAT),
+                encode_name(script)
+            ));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => break (file, path),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    started += chrono::Duration::milliseconds(1);
+                }
+                Err(err) => {
+                    return Err(err).with_context(|| format!("Failed to create {}", path.display()))
+                }
+            }
+        };
+
+        if keep > 0 {
+            remove_old_logs(dir, script, keep);
+        }
+        Ok(Self { file, path })
+    }
+
+    /// Get the path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Write for RunLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Start a log for a run of `script`, keeping `keep` logs per script
+/// (0 = all).
+///
+/// A log that can't be created is reported and the script runs without one.
+pub fn start(project_dir: &Path, script: &str, keep: usize) -> Option<RunLog> {
+    let dir = logs_dir(project_dir)?;
+    match RunLog::create(&dir, script, keep) {
+        Ok(log) => {
+            info!("Logging {script} to {}", log.path().display());
+            Some(log)
+        }
+        Err(err) => {
+            eprintln!("Warning: {err:#}");
+            None
+        }
+    }
+}
+
+/// Output of a process being copied to the terminal and other writers.
+#[derive(Debug)]
+pub struct Tee {
+    copiers: Vec<thread::JoinHandle<()>>,
+}
+
+impl Tee {
+    /// Wait for the output of the exited process to be copied.
+    pub fn finish(self) {
+        for copier in self.copiers {
+            let _ = copier.join();
+        }
+    }
+}
+
+/// Copy the output of a process started with piped stdout and stderr to the
+/// terminal and `sinks`, like a log or a recording, until it exits.
+///
+/// Stdin stays connected, so scripts can still prompt, though they see a
+/// pipe instead of a terminal on their output.
+pub fn tee(child: &mut Child, sinks: Vec<Box<dyn Write + Send>>) -> Tee {
+    let sinks = Arc::new(Mutex::new(sinks));
+    let mut copiers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        copiers.push(copy_to_sinks(stdout, io::stdout, Arc::clone(&sinks)));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        copiers.push(copy_to_sinks(stderr, io::stderr, Arc::clone(&sinks)));
+    }
+    Tee { copiers }
+}
+
+/// Copy a pipe to a terminal stream and the sinks on a background thread.
+///
+/// A sink that fails to write is warned about and left out from then on.
+fn copy_to_sinks<W: Write + 'static>(
+    mut pipe: impl Read + Send + 'static,
+    terminal: fn() -> W,
+    sinks: Arc<Mutex<Vec<Box<dyn Write + Send>>>>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            let n = match pipe.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            let mut out = terminal();
+            let _ = out.write_all(&buf[..n]);
+            let _ = out.flush();
+
+            if let Ok(mut sinks) = sinks.lock() {
+                sinks.retain_mut(|sink| match sink.write_all(&buf[..n]) {
+                    Ok(()) => true,
+                    Err(err) => {
+                        warn!("Failed to save script output: {err}");
+                        false
+                    }
+                });
+            }
+        }
+    })
+}
+
+/// A saved log file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFile {
+    /// Path of the file.
+    pub path: PathBuf,
+    /// Name of the script that was run.
+    pub script: String,
+    /// When the run started.
+    pub started: DateTime<Utc>,
+    /// File size in bytes.
+    pub size: u64,
+}
+
+/// List a project's log files, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the logs directory can't be read.
+pub fn list_logs(project_dir: &Path) -> Result<Vec<LogFile>> {
+    match logs_dir(project_dir) {
+        Some(dir) => list_logs_in(&dir),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Find the latest log of a script in a project.
+///
+/// # Errors
+///
+/// Returns an error if the logs directory can't be read.
+pub fn latest_log(project_dir: &Path, script: &str) -> Result<Option<LogFile>> {
+    Ok(list_logs(project_dir)?
+        .into_iter()
+        .find(|log| log.script == script))
+}
+
+/// List the log files in a directory, newest first.
+fn list_logs_in(dir: &Path) -> Result<Vec<LogFile>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+
+    let mut logs: Vec<LogFile> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let (started, script) = parse_file_name(&name)?;
+            Some(LogFile {
+                path: entry.path(),
+                script,
+                started,
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    logs.sort_by_key(|log| std::cmp::Reverse(log.started));
+    Ok(logs)
+}
+
+/// Remove the oldest logs of a script so at most `keep` are left.
+fn remove_old_logs(dir: &Path, script: &str, keep: usize) {
+    let Ok(logs) = list_logs_in(dir) else {
+        return;
+    };
+    for log in logs.iter().filter(|log| log.script == script).skip(keep) {
+        if let Err(err) = fs::remove_file(&log.path) {
+            warn!("Failed to remove {}: {err}", log.path.display());
+        }
+    }
+}
+
+/// Read the start time and script name back from a log file name.
+fn parse_file_name(name: &str) -> Option<(DateTime<Utc>, String)> {
+    let stem = name.strip_suffix(".log")?;
+    let timestamp = stem.get(..TIMESTAMP_LEN)?;
+    let script = stem.get(TIMESTAMP_LEN..)?.strip_prefix('-')?;
+    let started = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    Some((started.and_utc(), decode_name(script)?))
+}
+
+/// Percent-encode the characters of a script name that aren't safe in file
+/// names on every platform (`test:unit` becomes `test%3Aunit`).
+fn encode_name(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Decode a script name encoded by [`encode_name`].
+fn decode_name(encoded: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_names_round_trip() {
+        for name in ["build", "test:unit", "db/seed.dev", "déploy"] {
+            assert_eq!(decode_name(&encode_name(name)).as_deref(), Some(name));
+        }
+        assert_eq!(encode_name("test:unit"), "test%3Aunit");
+
+        let (started, script) = parse_file_name("20261016-153012.250-test%3Aunit.log").unwrap();
+        assert_eq!(script, "test:unit");
+        assert_eq!(
+            started.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            "2026-10-16 15:30:12.250"
+        );
+        assert!(parse_file_name("notes.txt").is_none());
+        assert!(parse_file_name("build.log").is_none());
+    }
+
+    #[test]
+    fn test_create_keeps_latest_logs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for _ in 0..4 {
+            let mut log = RunLog::create(dir.path(), "test:unit", 2).unwrap();
+            writeln!(log, "ok").unwrap();
+        }
+        RunLog::create(dir.path(), "build", 2).unwrap();
+
+        let logs = list_logs_in(dir.path()).unwrap();
+        let scripts: Vec<&str> = logs.iter().map(|l| l.script.as_str()).collect();
+        assert_eq!(scripts.iter().filter(|s| **s == "test:unit").count(), 2);
+        assert_eq!(scripts.iter().filter(|s| **s == "build").count(), 1);
+        assert!(logs.iter().all(|l| l.path.exists()));
+        assert_eq!(
+            logs.iter().find(|l| l.script == "test:unit").unwrap().size,
+            3
+        );
+    }
+}
@@ -2,10 +2,38 @@
 //!
 //! Handles script execution with the appropriate package manager.
 
+mod compare;
+mod context;
 mod executor;
+mod filter;
+mod heartbeat;
+pub mod jobs;
+mod limits;
+pub mod logging;
+mod parallel;
+pub mod pinned;
+mod platform;
+pub mod record;
+mod stream;
+mod summary;
+mod watcher;
+mod webhook;
 
+pub use compare::{format_comparison, measure_startup, time_runner, RunnerTiming};
+pub use context::RunContext;
 pub use executor::{
-    execute_script, execute_workspace_script, format_dry_run_command,
+    execute_script, execute_script_with_limits, execute_workspace_script,
+    execute_workspace_script_with_limits, format_dry_run_command, format_shell_command,
     format_workspace_dry_run_command, run_script, run_script_in_dir, run_scripts,
     run_scripts_in_dir, run_workspace_script, ExecutionResult, EXIT_CODE_INTERRUPTED,
+    EXIT_CODE_TIMED_OUT,
 };
+pub use filter::OutputFilter;
+pub use heartbeat::{send_notice, Heartbeat};
+pub use limits::limited_command;
+pub use parallel::{aggregate_exit_code, run_scripts_parallel, ParallelJob};
+pub use platform::{join_command, new_command, split_args};
+pub use stream::{spawn_streamed, OutputLine, StreamedRun};
+pub use summary::{format_run_summary, format_workspace_summary, RunResult};
+pub use watcher::{watch, watch_until_pass, WatchFilter};
+pub use webhook::{notify_run, send_webhook, webhook_matches, WebhookPayload};
@@ -0,0 +1,278 @@
+//! Parallel script execution.
+//!
+//! Runs several scripts at once, like `concurrently`: each output line is
+//! prefixed with its script's name, and the exit codes are combined.
+
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::info;
+
+use super::context::RunContext;
+use super::executor::ExecutionResult;
+use super::filter::OutputFilter;
+use super::stream::{spawn_streamed, OutputLine, StreamedRun};
+use crate::config::ScriptLimits;
+use crate::package::Runner;
+
+/// How long to wait between output polls.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// ANSI colors cycled through for the script prefixes.
+const PREFIX_COLORS: [&str; 6] = ["36", "35", "33", "32", "34", "31"];
+
+/// A script to run in parallel with others.
+#[derive(Debug, Clone, Default)]
+pub struct ParallelJob {
+    /// The package manager to run the script with.
+    pub runner: Runner,
+    /// The script name.
+    pub script: String,
+    /// Workspace to run the script in.
+    pub workspace: Option<String>,
+    /// Additional arguments to pass to the script.
+    pub args: Vec<String>,
+    /// Resource limits for the run.
+    pub limits: ScriptLimits,
+}
+
+impl ParallelJob {
+    /// Get the name shown in front of the job's output.
+    fn label(&self) -> String {
+        match &self.workspace {
+            Some(workspace) => format!("{workspace}:{}", self.script),
+            None => self.script.clone(),
+        }
+    }
+}
+
+/// A job that has been started.
+struct RunningJob {
+    index: usize,
+    prefix: String,
+    run: StreamedRun,
+}
+
+/// Run scripts concurrently with prefixed output.
+///
+/// At most `max_parallel` scripts run at the same time (0 = all at once).
+/// Unlike [`run_scripts`](super::run_scripts), a failing script doesn't stop
+/// the others. Results are returned in job order. Runs are logged and
+/// checked as `context` says.
+///
+/// Each script runs in a process group of its own, so Ctrl+C is passed on
+/// to the running scripts, and scripts not started yet are left out.
+///
+/// # Errors
+///
+/// Returns an error if a script fails to start. Scripts already running
+/// are stopped.
+pub fn run_scripts_parallel(
+    jobs: &[ParallelJob],
+    project_dir: &Path,
+    max_parallel: usize,
+    use_colors: bool,
+    context: &RunContext,
+) -> Result<Vec<ExecutionResult>> {
+    let limit = if max_parallel == 0 {
+        jobs.len()
+    } else {
+        max_parallel
+    };
+    let width = jobs
+        .iter()
+        .map(|job| job.label().chars().count())
+        .max()
+        .unwrap_or(0);
+    info!("Running {} scripts, {limit} at a time", jobs.len());
+
+    let ctrl_c = Arc::new(AtomicBool::new(false));
+    let signal = signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&ctrl_c))?;
+    let results = run_jobs(
+        jobs,
+        project_dir,
+        limit,
+        width,
+        use_colors,
+        context,
+        &ctrl_c,
+    );
+    signal_hook::low_level::unregister(signal);
+    results
+}
+
+/// Run the jobs, `limit` at a time, until all have finished.
+fn run_jobs(
+    jobs: &[ParallelJob],
+    project_dir: &Path,
+    limit: usize,
+    width: usize,
+    use_colors: bool,
+    context: &RunContext,
+    ctrl_c: &AtomicBool,
+) -> Result<Vec<ExecutionResult>> {
+    let mut pending: VecDeque<usize> = (0..jobs.len()).collect();
+    let mut running: Vec<RunningJob> = Vec::new();
+    let mut results: Vec<Option<ExecutionResult>> = vec![None; jobs.len()];
+
+    while !pending.is_empty() || !running.is_empty() {
+        while running.len() < limit {
+            let Some(index) = pending.pop_front() else {
+                break;
+            };
+            let job = &jobs[index];
+            let run = spawn_streamed(
+                job.runner,
+                job.workspace.as_deref(),
+                &job.script,
+                &job.args,
+                project_dir,
+                &job.limits,
+                OutputFilter::default(),
+                context,
+            )?;
+            running.push(RunningJob {
+                index,
+                prefix: format_prefix(&job.label(), width, index, use_colors),
+                run,
+            });
+        }
+
+        if ctrl_c.swap(false, Ordering::Relaxed) {
+            pending.clear();
+            for job in &mut running {
+                job.run.interrupt();
+            }
+        }
+        for job in &mut running {
+            for line in job.run.poll() {
+                print_line(&job.prefix, &line);
+            }
+        }
+
+        let mut still_running = Vec::with_capacity(running.len());
+        for job in running {
+            match job.run.status() {
+                Some(status) => {
+                    let result = ExecutionResult {
+                        status,
+                        command: job.run.command().to_string(),
+                        duration: job.run.duration(),
+                        timed_out: job.run.timed_out(),
+                    };
+                    print_exit(&job.prefix, &result);
+                    results[job.index] = Some(result);
+                }
+                None => still_running.push(job),
+            }
+        }
+        running = still_running;
+        io::stdout().flush().ok();
+
+        if !running.is_empty() {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Combine the exit codes of parallel runs.
+///
+/// Returns 0 when every script succeeded, otherwise the code of the first
+/// failed script in job order.
+pub fn aggregate_exit_code(results: &[ExecutionResult]) -> i32 {
+    results
+        .iter()
+        .map(ExecutionResult::exit_code)
+        .find(|&code| code != 0)
+        .unwrap_or(0)
+}
+
+/// Build the padded, optionally colored prefix for a job.
+fn format_prefix(label: &str, width: usize, index: usize, use_colors: bool) -> String {
+    let prefix = format!("[{label}]{:pad$}", "", pad = width - label.chars().count());
+    if use_colors {
+        let color = PREFIX_COLORS[index % PREFIX_COLORS.len()];
+        format!("\x1b[{color}m{prefix}\x1b[0m")
+    } else {
+        prefix
+    }
+}
+
+/// Print an output line, on stderr if the script wrote it there.
+fn print_line(prefix: &str, line: &OutputLine) {
+    if line.stderr {
+        eprintln!("{prefix} {}", line.text);
+    } else {
+        println!("{prefix} {}", line.text);
+    }
+}
+
+/// Print how a job exited.
+fn print_exit(prefix: &str, result: &ExecutionResult) {
+    if result.timed_out {
+        println!("{prefix} timed out");
+        return;
+    }
+    match result.status.code() {
+        Some(code) => println!("{prefix} exited with code {code}"),
+        None => println!("{prefix} was stopped"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(script: &str) -> ParallelJob {
+        ParallelJob {
+            script: script.to_string(),
+            ..ParallelJob::default()
+        }
+    }
+
+    #[test]
+    fn test_format_prefix_pads_labels() {
+        assert_eq!(format_prefix("dev", 5, 0, false), "[dev]  ");
+        assert_eq!(format_prefix("build", 5, 1, false), "[build]");
+        assert_eq!(format_prefix("dev", 3, 1, true), "\x1b[35m[dev]\x1b[0m");
+    }
+
+    #[test]
+    fn test_job_label_includes_workspace() {
+        let mut web = job("build");
+        assert_eq!(web.label(), "build");
+        web.workspace = Some("web".to_string());
+        assert_eq!(web.label(), "web:build");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_scripts_parallel() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"ok": "echo ok", "fail": "exit 4", "also-ok": "true"}}"#,
+        )
+        .unwrap();
+
+        let jobs = [job("ok"), job("fail"), job("also-ok")];
+        let Ok(results) = run_scripts_parallel(&jobs, dir.path(), 2, false, &RunContext::default())
+        else {
+            // npm isn't installed
+            return;
+        };
+
+        let codes: Vec<Option<i32>> = results.iter().map(ExecutionResult::code).collect();
+        assert_eq!(codes, [Some(0), Some(4), Some(0)]);
+        assert_eq!(aggregate_exit_code(&results), 4);
+        assert_eq!(aggregate_exit_code(&[results[0].clone()]), 0);
+    }
+}
@@ -0,0 +1,260 @@
+//! Pinned package manager versions.
+//!
+//! `packageManager` in package.json can pin a version, like `pnpm@9.1.0`.
+//! With `general.enforce_package_manager` set, the installed version is
+//! checked before the first script runs with that package manager. A
+//! different version is warned about, run through corepack instead, or
+//! refused.
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use tracing::debug;
+
+use super::platform::new_command;
+use crate::config::PackageManagerEnforcement;
+use crate::package::Runner;
+
+/// A package manager version pinned by `packageManager`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageManagerPin {
+    /// The pinned package manager.
+    pub runner: Runner,
+    /// The pinned version, without the hash.
+    pub version: String,
+}
+
+impl PackageManagerPin {
+    /// Parse a `packageManager` field, like "pnpm@9.1.0+sha512.abc".
+    ///
+    /// Returns `None` without a version.
+    pub fn parse(field: &str) -> Option<Self> {
+        let (name, version) = field.split_once('@')?;
+        let version = version.split('+').next().unwrap_or(version);
+        if version.is_empty() {
+            return None;
+        }
+        Some(Self {
+            runner: name.parse().ok()?,
+            version: version.to_string(),
+        })
+    }
+
+    /// Read the pin from a project's package.json.
+    pub fn read(project_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(project_dir.join("package.json")).ok()?;
+        let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+        Self::parse(package["packageManager"].as_str()?)
+    }
+}
+
+/// A command ready to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prepared {
+    /// The command to run.
+    pub cmd_parts: Vec<String>,
+    /// A warning to show about the package manager version.
+    pub warning: Option<String>,
+}
+
+/// A pinned version being enforced, with what was found installed.
+#[derive(Debug)]
+pub struct PinEnforcer {
+    pin: PackageManagerPin,
+    mode: PackageManagerEnforcement,
+    /// The installed version of the pinned package manager, once checked.
+    installed: OnceLock<Option<String>>,
+    /// Whether corepack is installed, once checked.
+    corepack: OnceLock<bool>,
+    /// Whether a version mismatch was already warned about.
+    warned: AtomicBool,
+}
+
+impl PinEnforcer {
+    /// Read the version pinned in a project to enforce as `mode` says.
+    ///
+    /// Returns `None` when `mode` is off or nothing is pinned.
+    pub fn read(project_dir: &Path, mode: PackageManagerEnforcement) -> Option<Self> {
+        if mode == PackageManagerEnforcement::Off {
+            return None;
+        }
+        let pin = PackageManagerPin::read(project_dir)?;
+        debug!("Enforcing {} {} ({mode:?})", pin.runner, pin.version);
+        Some(Self {
+            pin,
+            mode,
+            installed: OnceLock::new(),
+            corepack: OnceLock::new(),
+            warned: AtomicBool::new(false),
+        })
+    }
+
+    /// Prepare a command for running, checking the package manager it runs
+    /// against the pinned version.
+    ///
+    /// The installed versions are looked up on the first command run with
+    /// the pinned package manager, and a mismatch is only warned about once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the installed version doesn't match and the
+    /// enforcement is `fail`, or is `corepack` without corepack installed.
+    pub fn prepare(&self, cmd_parts: Vec<String>) -> Result<Prepared> {
+        if cmd_parts.first().map(String::as_str) != Some(self.pin.runner.executable()) {
+            return Ok(Prepared {
+                cmd_parts,
+                warning: None,
+            });
+        }
+
+        let installed = self
+            .installed
+            .get_or_init(|| installed_version(self.pin.runner.executable()));
+        let corepack = || {
+            *self
+                .corepack
+                .get_or_init(|| installed_version("corepack").is_some())
+        };
+        let mut prepared = check(
+            cmd_parts,
+            &self.pin,
+            installed.as_deref(),
+            self.mode,
+            corepack,
+        )?;
+        if prepared.warning.is_some() && self.warned.swap(true, Ordering::Relaxed) {
+            prepared.warning = None;
+        }
+        Ok(prepared)
+    }
+}
+
+/// Check a command against the pin, given the installed version.
+fn check(
+    cmd_parts: Vec<String>,
+    pin: &PackageManagerPin,
+    installed: Option<&str>,
+    mode: PackageManagerEnforcement,
+    corepack: impl FnOnce() -> bool,
+) -> Result<Prepared> {
+    if installed == Some(pin.version.as_str()) {
+        return Ok(Prepared {
+            cmd_parts,
+            warning: None,
+        });
+    }
+
+    let tool = pin.runner.executable();
+    let problem = match installed {
+        Some(installed) => format!(
+            "packageManager pins {tool} {}, but {installed} is installed",
+            pin.version
+        ),
+        None => format!(
+            "packageManager pins {tool} {}, but {tool} isn't installed",
+            pin.version
+        ),
+    };
+    match mode {
+        PackageManagerEnforcement::Off => Ok(Prepared {
+            cmd_parts,
+            warning: None,
+        }),
+        PackageManagerEnforcement::Warn => Ok(Prepared {
+            cmd_parts,
+            warning: Some(format!(
+                "{problem}. Run corepack enable to use the pinned version"
+            )),
+        }),
+        PackageManagerEnforcement::Corepack => {
+            if !matches!(pin.runner, Runner::Npm | Runner::Yarn | Runner::Pnpm) {
+                anyhow::bail!("{problem}, and corepack can't run {tool}");
+            }
+            if !corepack() {
+                anyhow::bail!(
+                    "{problem}, and corepack isn't installed. Install it with npm install -g corepack"
+                );
+            }
+            let mut corepacked = vec!["corepack".to_string()];
+            corepacked.extend(cmd_parts);
+            Ok(Prepared {
+                cmd_parts: corepacked,
+                warning: None,
+            })
+        }
+        PackageManagerEnforcement::Fail => anyhow::bail!(
+            "{problem}. Run corepack enable to use the pinned version, or set \
+             general.enforce_package_manager = \"corepack\" to run it through corepack"
+        ),
+    }
+}
+
+/// Get the version of an installed tool, from `<tool> --version`.
+pub fn installed_version(tool: &str) -> Option<String> {
+    let output = new_command(tool).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // "v20.11.0", "10.2.4" or "deno 1.40.0 (release, ...)"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .split_whitespace()
+        .map(|word| word.trim_start_matches('v'))
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pnpm_run() -> Vec<String> {
+        vec!["pnpm".to_string(), "run".to_string(), "build".to_string()]
+    }
+
+    #[test]
+    fn test_parse_pin() {
+        assert_eq!(
+            PackageManagerPin::parse("pnpm@9.1.0+sha512.abc"),
+            Some(PackageManagerPin {
+                runner: Runner::Pnpm,
+                version: "9.1.0".to_string()
+            })
+        );
+        assert_eq!(PackageManagerPin::parse("pnpm"), None);
+        assert_eq!(PackageManagerPin::parse("pnpm@"), None);
+        assert_eq!(PackageManagerPin::parse("rush@5.0.0"), None);
+    }
+
+    #[test]
+    fn test_check_enforcement() {
+        let pin = PackageManagerPin::parse("pnpm@9.1.0").unwrap();
+        let check =
+            |installed, mode, corepack| check(pnpm_run(), &pin, installed, mode, || corepack);
+
+        let matching = check(Some("9.1.0"), PackageManagerEnforcement::Fail, false).unwrap();
+        assert_eq!(matching.cmd_parts, pnpm_run());
+        assert_eq!(matching.warning, None);
+
+        let warned = check(Some("8.15.4"), PackageManagerEnforcement::Warn, false).unwrap();
+        assert_eq!(warned.cmd_parts, pnpm_run());
+        assert!(warned
+            .warning
+            .unwrap()
+            .starts_with("packageManager pins pnpm 9.1.0, but 8.15.4 is installed"));
+
+        let corepacked = check(None, PackageManagerEnforcement::Corepack, true).unwrap();
+        assert_eq!(corepacked.cmd_parts.join(" "), "corepack pnpm run build");
+        let err = check(None, PackageManagerEnforcement::Corepack, false).unwrap_err();
+        assert!(
+            err.to_string().contains("corepack isn't installed"),
+            "{err}"
+        );
+
+        let err = check(Some("8.15.4"), PackageManagerEnforcement::Fail, true).unwrap_err();
+        assert!(err.to_string().contains("Run corepack enable"), "{err}");
+    }
+}
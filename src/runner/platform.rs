@@ -0,0 +1,323 @@
+//! Platform differences in running commands.
+//!
+//! On Windows, package managers are installed as `.cmd` shims (`npm.cmd`)
+//! that `Command` won't find by bare name, arguments don't use POSIX
+//! quoting, and Ctrl+C ends a process with an NTSTATUS code rather than a
+//! signal.
+
+use std::io;
+use std::process::{Command, ExitStatus, Stdio};
+
+use super::executor::EXIT_CODE_INTERRUPTED;
+
+/// Exit code of a process ended by Ctrl+C on Windows (`STATUS_CONTROL_C_EXIT`).
+const STATUS_CONTROL_C_EXIT: i32 = 0xC000_013A_u32 as i32;
+
+/// Create a command for a program.
+///
+/// On Windows the program is looked up on `PATH` with the `PATHEXT`
+/// extensions, so `npm` finds `npm.cmd`. Std runs `.cmd` and `.bat` files
+/// through `cmd.exe` with the arguments escaped for it.
+pub fn new_command(program: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".into());
+        if let Some(resolved) = find_program(program, &path, &pathext, |p| p.is_file()) {
+            return Command::new(resolved);
+        }
+    }
+    Command::new(program)
+}
+
+/// Find a program on a `PATH`-style search path, trying each extension.
+///
+/// Names with a directory or an extension are left to the OS.
+#[cfg(any(windows, test))]
+fn find_program(
+    program: &str,
+    path: &std::ffi::OsStr,
+    pathext: &str,
+    is_file: impl Fn(&std::path::Path) -> bool,
+) -> Option<std::path::PathBuf> {
+    let name = std::path::Path::new(program);
+    if name.components().count() > 1 || name.extension().is_some() {
+        return None;
+    }
+
+    std::env::split_paths(path).find_map(|dir| {
+        pathext
+            .split(';')
+            .filter(|ext| !ext.is_empty())
+            .map(|ext| dir.join(format!("{program}{}", ext.to_ascii_lowercase())))
+            .find(|candidate| is_file(candidate))
+    })
+}
+
+/// Get the exit code of a finished process.
+///
+/// Processes killed by a signal on Unix, or by Ctrl+C on Windows, give
+/// [`EXIT_CODE_INTERRUPTED`] like a shell would.
+pub fn exit_code(status: ExitStatus) -> i32 {
+    match status.code() {
+        Some(STATUS_CONTROL_C_EXIT) if cfg!(windows) => EXIT_CODE_INTERRUPTED,
+        Some(code) => code,
+        None => EXIT_CODE_INTERRUPTED,
+    }
+}
+
+/// Split user-supplied arguments into words.
+///
+/// Quotes work as in a POSIX shell. On Windows backslashes are kept as they
+/// are, since they are path separators there rather than escapes.
+pub fn split_args(args: &str) -> Vec<String> {
+    if cfg!(windows) {
+        return split_windows_args(args);
+    }
+    shell_words::split(args).unwrap_or_else(|_| vec![args.to_string()])
+}
+
+/// Split arguments at whitespace outside quotes, keeping backslashes.
+///
+/// Single and double quotes group words and are removed; an unclosed quote
+/// runs to the end.
+fn split_windows_args(args: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for c in args.chars() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => word.get_or_insert_with(String::new).push(c),
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            None if c.is_whitespace() => words.extend(word.take()),
+            None => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// Join a command into a string the user's shell can run.
+///
+/// POSIX quoting on Unix; on Windows, double quotes as understood by both
+/// cmd.exe and PowerShell.
+pub fn join_command(parts: &[String]) -> String {
+    if cfg!(windows) {
+        windows_join(parts)
+    } else {
+        shell_words::join(parts)
+    }
+}
+
+/// Check if a process is still running.
+///
+/// Uses `kill -0` on Unix and `tasklist` on Windows.
+pub fn process_alive(pid: u32) -> bool {
+    if cfg!(windows) {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+            .stderr(Stdio::null())
+            .output()
+            .is_ok_and(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .split_whitespace()
+                    .any(|word| word == pid.to_string())
+            })
+    } else {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+}
+
+/// Get when a process started, to tell it from a later process that was
+/// given the same PID.
+///
+/// Read from `/proc/<pid>/stat` (in clock ticks after boot), so only
+/// available on Linux.
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name in parentheses can hold spaces; the start time is
+    // the 22nd field, counting from the PID
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+/// Ask a process to stop: SIGTERM on Unix, `taskkill /T` (with its child
+/// processes) on Windows.
+///
+/// # Errors
+///
+/// Returns an error if the process can't be signalled.
+pub fn stop_process(pid: u32) -> io::Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("taskkill");
+        command.args(["/PID", &pid.to_string(), "/T", "/F"]);
+        command
+    } else {
+        let mut command = Command::new("kill");
+        command.args(["-TERM", &pid.to_string()]);
+        command
+    };
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("couldn't stop process {pid}"),
+        ))
+    }
+}
+
+/// Start a command in a process group of its own, so it can be stopped
+/// together with the processes it starts.
+///
+/// Does nothing on Windows, where `taskkill /T` finds them instead.
+pub fn use_own_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+/// Send a signal (`TERM`, `KILL`, `INT`) to a process group started with
+/// [`use_own_process_group`].
+///
+/// On Windows the process and its child processes are ended with
+/// `taskkill /T /F`, whatever the signal, except `INT`, which the console
+/// already delivers to them.
+///
+/// # Errors
+///
+/// Returns an error if the processes can't be signalled.
+pub fn signal_process_group(pid: u32, signal: &str) -> io::Result<()> {
+    let mut command = if cfg!(windows) {
+        if signal == "INT" {
+            return Ok(());
+        }
+        let mut command = Command::new("taskkill");
+        command.args(["/PID", &pid.to_string(), "/T", "/F"]);
+        command
+    } else {
+        let mut command = Command::new("kill");
+        command.args([&format!("-{signal}"), "--", &format!("-{pid}")]);
+        command
+    };
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("couldn't signal process group {pid}"),
+        ))
+    }
+}
+
+/// Join words with Windows double-quote quoting where needed.
+fn windows_join(parts: &[String]) -> String {
+    parts
+        .iter()
+        .map(|part| {
+            let plain = !part.is_empty()
+                && !part
+                    .chars()
+                    .any(|c| c.is_whitespace() || "\"&|<>^%;,'`$()".contains(c));
+            if plain {
+                part.clone()
+            } else {
+                format!("\"{}\"", part.replace('"', "\\\""))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_find_program_tries_extensions() {
+        let bin = PathBuf::from("/nodejs");
+        let path = std::env::join_paths([PathBuf::from("/empty"), bin.clone()]).unwrap();
+        let exists = |p: &Path| p == bin.join("npm.cmd");
+
+        assert_eq!(
+            find_program("npm", &path, ".COM;.EXE;.BAT;.CMD", exists),
+            Some(bin.join("npm.cmd"))
+        );
+        assert_eq!(find_program("yarn", &path, ".EXE;.CMD", exists), None);
+        // Explicit extensions and paths are used as given
+        assert_eq!(find_program("npm.cmd", &path, ".CMD", exists), None);
+        assert_eq!(find_program("", &OsString::new(), ".CMD", exists), None);
+    }
+
+    #[test]
+    fn test_windows_join_quotes() {
+        let parts: Vec<String> = ["npm", "run", "test", "--", "--grep", "a b", ""]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(windows_join(&parts), r#"npm run test -- --grep "a b" """#);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_start_time() {
+        let own = process_start_time(std::process::id());
+        assert!(own.is_some());
+        assert_eq!(own, process_start_time(std::process::id()));
+        assert_eq!(process_start_time(u32::MAX), None);
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(split_args("--grep 'a b' -x"), ["--grep", "a b", "-x"]);
+        if cfg!(windows) {
+            assert_eq!(split_args(r"src\app.test.ts"), [r"src\app.test.ts"]);
+        }
+    }
+
+    #[test]
+    fn test_split_windows_args() {
+        assert_eq!(
+            split_windows_args(r#"--grep 'a b' src\app.ts "C:\My Files\x" ''"#),
+            ["--grep", "a b", r"src\app.ts", r"C:\My Files\x", ""]
+        );
+        assert_eq!(split_windows_args(r"'C:\tmp\new'"), [r"C:\tmp\new"]);
+        assert!(split_windows_args("   ").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code_signal() {
+        use std::os::unix::process::ExitStatusExt;
+
+        assert_eq!(exit_code(ExitStatus::from_raw(3 << 8)), 3);
+        // Killed by SIGINT
+        assert_eq!(exit_code(ExitStatus::from_raw(2)), EXIT_CODE_INTERRUPTED);
+    }
+}
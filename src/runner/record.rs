@@ -0,0 +1,212 @@
+//! Run recordings.
+//!
+//! With `--record <file>`, the output of the scripts nrs runs is saved as
+//! an [asciicast v2] file, which `asciinema play` and the asciinema web
+//! player replay with the original timing. Each run starts with its command
+//! and ends with its exit code, so a recording shows what was run too.
+//!
+//! Output is copied from pipes like `--log` does, so scripts see a pipe
+//! rather than a terminal on their output. Scripts in `scripts.interactive`
+//! keep the terminal, and the recording only notes that they ran.
+//!
+//! [asciicast v2]: https://docs.asciinema.org/manual/asciicast/v2/
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use crate::utils::clock;
+
+/// Terminal size written to the header when stdout isn't a terminal.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// An asciicast being written.
+#[derive(Debug)]
+pub struct Cast<W: Write = BufWriter<File>> {
+    out: W,
+    started: Instant,
+    /// Bytes at the end of the last write that don't form a whole character.
+    partial: Vec<u8>,
+    /// Whether the last output ended with a carriage return.
+    after_cr: bool,
+}
+
+impl<W: Write> Cast<W> {
+    /// Start a cast, writing its header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header can't be written.
+    pub fn new(mut out: W, width: u16, height: u16) -> io::Result<Self> {
+        let mut header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": clock::now().timestamp(),
+        });
+        if let Ok(term) = std::env::var("TERM") {
+            header["env"] = json!({ "TERM": term });
+        }
+        writeln!(out, "{header}")?;
+        out.flush()?;
+        Ok(Self {
+            out,
+            started: Instant::now(),
+            partial: Vec::new(),
+            after_cr: false,
+        })
+    }
+
+    /// Add an output event, turning bare line feeds into CRLF as a
+    /// terminal would.
+    fn event(&mut self, text: &str) -> io::Result<()> {
+        let mut data = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c == '\n' && !self.after_cr {
+                data.push('\r');
+            }
+            data.push(c);
+            self.after_cr = c == '\r';
+        }
+        let time = self.started.elapsed().as_secs_f64();
+        writeln!(
+            self.out,
+            "{}",
+            json!([(time * 1e6).round() / 1e6, "o", data])
+        )?;
+        self.out.flush()
+    }
+
+    /// Add a line written by nrs, like the command being run.
+    fn note(&mut self, text: &str) -> io::Result<()> {
+        self.event(&format!("\x1b[2m{text}\x1b[0m\n"))
+    }
+}
+
+impl<W: Write> Write for Cast<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Events are text, so a character split between writes waits for
+        // the rest of it
+        self.partial.extend_from_slice(buf);
+        let valid = match std::str::from_utf8(&self.partial) {
+            Ok(_) => self.partial.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => {
+                let text = String::from_utf8_lossy(&self.partial).into_owned();
+                self.partial.clear();
+                self.event(&text)?;
+                return Ok(buf.len());
+            }
+        };
+        let rest = self.partial.split_off(valid);
+        let text =
+            String::from_utf8(std::mem::replace(&mut self.partial, rest)).unwrap_or_default();
+        if !text.is_empty() {
+            self.event(&text)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// A recording that script runs add their output to.
+///
+/// Clones add to the same file.
+#[derive(Debug, Clone)]
+pub struct Recording(Arc<Mutex<Cast>>);
+
+impl Recording {
+    /// Start recording to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created.
+    pub fn start(path: &Path) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let (width, height) = crossterm::terminal::size().unwrap_or(DEFAULT_SIZE);
+        let cast = Cast::new(BufWriter::new(file), width, height)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Self(Arc::new(Mutex::new(cast))))
+    }
+
+    /// Add a line from nrs to the recording.
+    pub fn note(&self, text: &str) {
+        if let Ok(mut cast) = self.0.lock() {
+            let _ = cast.note(text);
+        }
+    }
+
+    /// Get a writer adding script output to the recording.
+    pub fn writer(&self) -> Box<dyn Write + Send> {
+        Box::new(Recorder(Arc::clone(&self.0)))
+    }
+}
+
+/// Writes to the recording.
+struct Recorder(Arc<Mutex<Cast>>);
+
+impl Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.0.lock() {
+            Ok(mut cast) => cast.write(buf),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "recording is unavailable",
+            )),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cast_events() {
+        let mut cast = Cast::new(Vec::new(), 100, 30).unwrap();
+        cast.note("$ npm run build").unwrap();
+        cast.write_all(b"one\ntwo\r\n").unwrap();
+        // "é" split across writes
+        cast.write_all(b"caf\xc3").unwrap();
+        cast.write_all(b"\xa9\n").unwrap();
+
+        let text = String::from_utf8(cast.out).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 100);
+        assert_eq!(lines[0]["height"], 30);
+
+        let output: Vec<&str> = lines[1..]
+            .iter()
+            .map(|event| {
+                assert_eq!(event[1], "o");
+                event[2].as_str().unwrap()
+            })
+            .collect();
+        assert_eq!(
+            output,
+            [
+                "\x1b[2m$ npm run build\x1b[0m\r\n",
+                "one\r\ntwo\r\n",
+                "caf",
+                "é\r\n"
+            ]
+        );
+    }
+}
@@ -0,0 +1,555 @@
+//! Streamed script execution.
+//!
+//! Runs a script with its output captured line by line, so the TUI can show
+//! it in an output pane instead of handing over the terminal.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tracing::{debug, info};
+
+use super::context::RunContext;
+use super::executor::{TIMEOUT_GRACE, TIMEOUT_POLL};
+use super::filter::OutputFilter;
+use super::jobs::{self, JobRecord};
+use super::limits::limited_command;
+use super::logging::RunLog;
+use super::platform::{self, new_command};
+use crate::config::ScriptLimits;
+use crate::package::Runner;
+use crate::utils::format_duration;
+
+/// A line of script output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    /// Line text, without the line break or terminal escape codes.
+    pub text: String,
+    /// Whether the line was written to stderr.
+    pub stderr: bool,
+    /// Hidden lines this line stands for (0 for script output).
+    pub hidden: usize,
+}
+
+impl OutputLine {
+    /// Create a line standing for `count` lines hidden by an output filter.
+    pub fn hidden(count: usize) -> Self {
+        let lines = if count == 1 { "line" } else { "lines" };
+        Self {
+            text: format!("… {count} {lines} hidden"),
+            stderr: false,
+            hidden: count,
+        }
+    }
+
+    /// Check if the line stands for hidden lines.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden > 0
+    }
+}
+
+/// Message from an output reader thread.
+enum Chunk {
+    Line(OutputLine),
+    Closed,
+}
+
+/// A script running with captured output.
+///
+/// The script runs in a process group of its own, which is stopped when
+/// this is dropped before it finishes.
+pub struct StreamedRun {
+    child: Child,
+    receiver: Receiver<Chunk>,
+    open_streams: usize,
+    status: Option<ExitStatus>,
+    command: String,
+    started: Instant,
+    duration: Duration,
+    log: Option<RunLog>,
+    filter: OutputFilter,
+    /// Hidden lines not written to the log yet.
+    log_hidden: usize,
+    /// How long the script may run before it's stopped.
+    timeout: Option<Duration>,
+    /// Whether the script ran past its timeout.
+    timed_out: bool,
+    /// Whether the script was killed after ignoring `SIGTERM`.
+    killed: bool,
+    _job: Option<JobRecord>,
+}
+
+impl StreamedRun {
+    /// Get the command being run.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Get the process ID of the package manager running the script.
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Get the exit status, once the script has finished.
+    pub fn status(&self) -> Option<ExitStatus> {
+        self.status
+    }
+
+    /// Check if the script was stopped for running past its timeout.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Check if the script has finished.
+    pub fn is_finished(&self) -> bool {
+        self.status.is_some()
+    }
+
+    /// Get how long the script ran, or has been running so far.
+    pub fn duration(&self) -> Duration {
+        if self.is_finished() {
+            self.duration
+        } else {
+            self.started.elapsed()
+        }
+    }
+
+    /// Collect output received since the last poll.
+    ///
+    /// Also checks whether the process has exited, and stops it once it runs
+    /// past its timeout. The status is only set after all of its output has
+    /// been read.
+    pub fn poll(&mut self) -> Vec<OutputLine> {
+        let mut lines = Vec::new();
+        if let Some(line) = self.check_timeout() {
+            self.write_log(&line);
+            lines.push(line);
+        }
+        while let Ok(chunk) = self.receiver.try_recv() {
+            match chunk {
+                Chunk::Line(line) => {
+                    if let Some(line) = self.filter.apply(line) {
+                        self.write_log(&line);
+                        lines.push(line);
+                    }
+                }
+                Chunk::Closed => self.open_streams = self.open_streams.saturating_sub(1),
+            }
+        }
+
+        if self.open_streams == 0 && self.status.is_none() {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                debug!("{} exited with {status}", self.command);
+                self.status = Some(status);
+                self.duration = self.started.elapsed();
+                self.flush_log();
+            }
+        }
+
+        lines
+    }
+
+    /// Stop the process group once the script runs past its timeout, as
+    /// scripts run in the terminal are: `SIGTERM` first, then `SIGKILL` after
+    /// a grace period.
+    ///
+    /// Returns a line saying the script timed out when it's first stopped.
+    fn check_timeout(&mut self) -> Option<OutputLine> {
+        let timeout = self.timeout?;
+        if self.status.is_some() || self.killed {
+            return None;
+        }
+        let elapsed = self.started.elapsed();
+        let pid = self.pid();
+        if !self.timed_out && elapsed >= timeout {
+            info!(
+                "Timed out after {}, stopping process group {pid}",
+                format_duration(timeout)
+            );
+            self.timed_out = true;
+            let _ = platform::signal_process_group(pid, "TERM");
+            return Some(OutputLine {
+                text: format!("Timed out after {}", format_duration(timeout)),
+                stderr: true,
+                hidden: 0,
+            });
+        }
+        if self.timed_out && elapsed >= timeout + TIMEOUT_GRACE {
+            self.killed = true;
+            let _ = platform::signal_process_group(pid, "KILL");
+        }
+        None
+    }
+
+    /// Write a line to the log, if there is one.
+    ///
+    /// A run of hidden lines is written as one line once it ends.
+    fn write_log(&mut self, line: &OutputLine) {
+        if line.is_hidden() {
+            self.log_hidden = line.hidden;
+            return;
+        }
+        self.flush_log();
+        if let Some(log) = &mut self.log {
+            let _ = writeln!(log, "{}", line.text);
+        }
+    }
+
+    /// Write the hidden lines not in the log yet.
+    fn flush_log(&mut self) {
+        let hidden = std::mem::take(&mut self.log_hidden);
+        if let (Some(log), true) = (&mut self.log, hidden > 0) {
+            let _ = writeln!(log, "{}", OutputLine::hidden(hidden).text);
+        }
+    }
+
+    /// Pass Ctrl+C on to the script, which doesn't get it from the terminal
+    /// in its own process group.
+    pub fn interrupt(&mut self) {
+        if self.status.is_none() {
+            let _ = platform::signal_process_group(self.pid(), "INT");
+        }
+    }
+
+    /// Stop the script if it is still running.
+    ///
+    /// The whole process group gets `SIGTERM`, so the processes the package
+    /// manager started stop with it, then `SIGKILL` after a grace period.
+    pub fn kill(&mut self) {
+        if self.status.is_some() {
+            return;
+        }
+        let pid = self.pid();
+        debug!("Stopping process group {pid}");
+        let _ = platform::signal_process_group(pid, "TERM");
+        let deadline = Instant::now() + TIMEOUT_GRACE;
+        while Instant::now() < deadline && matches!(self.child.try_wait(), Ok(None)) {
+            thread::sleep(TIMEOUT_POLL);
+        }
+        // Also reaches processes left in the group after the package manager exited
+        let _ = platform::signal_process_group(pid, "KILL");
+        if let Ok(status) = self.child.wait() {
+            self.status = Some(status);
+            self.duration = self.started.elapsed();
+        }
+    }
+}
+
+impl Drop for StreamedRun {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// Start a script with its output captured.
+///
+/// Runs in `workspace` when given, under `limits`, with lines `filter` hides left out. The
+/// run is logged and checked as `context` says. Stdin is closed, so scripts that prompt
+/// for input see end-of-file.
+///
+/// A script running past `limits.timeout` is stopped when polled. Runs
+/// aren't tried again, so `limits.retries` only gets a warning.
+///
+/// # Errors
+///
+/// Returns an error if the runner fails to start.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_streamed(
+    runner: Runner,
+    workspace: Option<&str>,
+    script: &str,
+    args: &[String],
+    project_dir: &Path,
+    limits: &ScriptLimits,
+    filter: OutputFilter,
+    context: &RunContext,
+) -> Result<StreamedRun> {
+    let cmd_parts = match workspace {
+        Some(workspace) => runner.workspace_command_with_args(workspace, script, args),
+        None => runner.run_command_with_args(script, args),
+    };
+    let command_str = cmd_parts.join(" ");
+    let prepared = context.prepare(cmd_parts)?;
+    let cmd_parts = limited_command(prepared.cmd_parts, limits);
+
+    info!(
+        "Running {command_str} in {} (captured)",
+        project_dir.display()
+    );
+    let mut command = new_command(&cmd_parts[0]);
+    command
+        .args(&cmd_parts[1..])
+        .current_dir(project_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Stopping the run stops the script's processes too, not just the
+    // package manager
+    platform::use_own_process_group(&mut command);
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to execute: {command_str}"))?;
+
+    let job = jobs::register(project_dir, script, child.id());
+    let (sender, receiver) = mpsc::channel();
+    let retries = (limits.retries > 0).then(|| {
+        format!(
+            "retries = {} doesn't apply here, the script runs once",
+            limits.retries
+        )
+    });
+    for warning in prepared.warning.into_iter().chain(retries) {
+        let _ = sender.send(Chunk::Line(OutputLine {
+            text: format!("Warning: {warning}"),
+            stderr: true,
+            hidden: 0,
+        }));
+    }
+    let mut open_streams = 0;
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, false, sender.clone());
+        open_streams += 1;
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, true, sender);
+        open_streams += 1;
+    }
+
+    Ok(StreamedRun {
+        child,
+        receiver,
+        open_streams,
+        status: None,
+        command: command_str,
+        started: Instant::now(),
+        duration: Duration::ZERO,
+        log: context.start_log(project_dir, script),
+        filter,
+        log_hidden: 0,
+        timeout: limits.timeout,
+        timed_out: false,
+        killed: false,
+        _job: job,
+    })
+}
+
+/// Read lines from a pipe on a background thread and send them on.
+fn forward_lines(pipe: impl Read + Send + 'static, stderr: bool, sender: Sender<Chunk>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = clean_line(&String::from_utf8_lossy(&buf));
+                    if sender
+                        .send(Chunk::Line(OutputLine {
+                            text,
+                            stderr,
+                            hidden: 0,
+                        }))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        }
+        let _ = sender.send(Chunk::Closed);
+    });
+}
+
+/// Prepare a raw output line for display.
+///
+/// Drops the line break and ANSI escape sequences, and keeps only the text
+/// after the last carriage return, as a terminal would for progress bars.
+fn clean_line(raw: &str) -> String {
+    let raw = raw.trim_end_matches(['\n', '\r']);
+    let raw = raw.rsplit('\r').next().unwrap_or(raw);
+
+    let mut text = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            if c == '\t' {
+                text.push_str("    ");
+            } else if !c.is_control() {
+                text.push(c);
+            }
+            continue;
+        }
+
+        // CSI sequences end with a byte in @..~, OSC sequences with BEL or ST
+        match chars.next() {
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_line_strips_escapes() {
+        assert_eq!(clean_line("\x1b[32m✓\x1b[0m passed\n"), "✓ passed");
+        assert_eq!(clean_line("\x1b]8;;https://x\x07link\x1b]8;;\x07"), "link");
+        assert_eq!(clean_line("plain\r\n"), "plain");
+    }
+
+    #[test]
+    fn test_clean_line_keeps_last_progress_update() {
+        assert_eq!(clean_line("10%\r50%\r100%\n"), "100%");
+        assert_eq!(clean_line("a\tb"), "a    b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_streamed_run_captures_output() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"hello": "echo out && echo err >&2 && exit 3"}}"#,
+        )
+        .unwrap();
+
+        let Ok(mut run) = spawn_streamed(
+            Runner::Npm,
+            None,
+            "hello",
+            &[],
+            dir.path(),
+            &ScriptLimits::default(),
+            OutputFilter::default(),
+            &RunContext::default(),
+        ) else {
+            // npm isn't installed
+            return;
+        };
+
+        let mut lines = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while !run.is_finished() && Instant::now() < deadline {
+            lines.extend(run.poll());
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(run.status().and_then(|s| s.code()), Some(3));
+        assert!(lines.iter().any(|l| l.text == "out" && !l.stderr));
+        assert!(lines.iter().any(|l| l.text == "err" && l.stderr));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_stops_run() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"hang": "sleep 60"}}"#,
+        )
+        .unwrap();
+        let limits = ScriptLimits {
+            timeout: Some(Duration::from_millis(500)),
+            retries: 1,
+            ..ScriptLimits::default()
+        };
+
+        let Ok(mut run) = spawn_streamed(
+            Runner::Npm,
+            None,
+            "hang",
+            &[],
+            dir.path(),
+            &limits,
+            OutputFilter::default(),
+            &RunContext::default(),
+        ) else {
+            // npm isn't installed
+            return;
+        };
+
+        let mut lines = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while !run.is_finished() && Instant::now() < deadline {
+            lines.extend(run.poll());
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(run.is_finished());
+        assert!(run.timed_out());
+        assert!(run.duration() < Duration::from_secs(30));
+        assert!(
+            lines.iter().any(|l| l.text == "Timed out after 500ms"),
+            "{lines:?}"
+        );
+        assert!(lines.iter().any(|l| l.text.contains("retries = 1")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_stops_background_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"serve": "sleep 60 & echo $! > child.pid; echo started; sleep 60"}}"#,
+        )
+        .unwrap();
+
+        let Ok(mut run) = spawn_streamed(
+            Runner::Npm,
+            None,
+            "serve",
+            &[],
+            dir.path(),
+            &ScriptLimits::default(),
+            OutputFilter::default(),
+            &RunContext::default(),
+        ) else {
+            // npm isn't installed
+            return;
+        };
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while !run.poll().iter().any(|l| l.text == "started") && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let child_pid: u32 = std::fs::read_to_string(dir.path().join("child.pid"))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        let npm_pid = run.pid();
+        assert!(platform::process_alive(child_pid));
+
+        run.kill();
+        assert!(run.is_finished());
+        assert!(!platform::process_alive(npm_pid));
+        // The orphaned sleep is reaped by init, which can take a moment
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while platform::process_alive(child_pid) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!platform::process_alive(child_pid));
+    }
+}
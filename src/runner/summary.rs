@@ -0,0 +1,121 @@
+//! Summaries of scripts run together.
+//!
+//! Used when one script runs in every workspace, and for script groups.
+//! Workspaces run one after another through the runner's workspace command
+//! (`npm run -w`, `pnpm --filter`, `yarn workspace`, ...) rather than one
+//! recursive `npm run -ws` or `pnpm -r`, so each gets its own exit code for
+//! the summary.
+
+use std::time::Duration;
+
+/// The result of one run in a summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunResult {
+    /// Workspace or script name.
+    pub name: String,
+    /// Exit code, or `None` if the script was killed.
+    pub exit_code: Option<i32>,
+    /// How long the script took.
+    pub duration: Duration,
+}
+
+impl RunResult {
+    /// Check if the run succeeded.
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Format the results of a script run in several workspaces as a table.
+pub fn format_workspace_summary(script: &str, results: &[RunResult]) -> String {
+    let heading = format!(
+        "\"{script}\" in {} workspace{}",
+        results.len(),
+        if results.len() == 1 { "" } else { "s" }
+    );
+    format_run_summary(&heading, "Workspace", results)
+}
+
+/// Format run results as a table under a heading, with the number of
+/// failed runs at the end.
+///
+/// `column` names what the runs are, like "Workspace" or "Script".
+pub fn format_run_summary(heading: &str, column: &str, results: &[RunResult]) -> String {
+    let width = results
+        .iter()
+        .map(|r| r.name.chars().count())
+        .chain([column.chars().count()])
+        .max()
+        .unwrap_or(0);
+
+    let mut out = format!("{heading}:\n\n");
+    out.push_str(&format!(
+        "  {:<width$}  {:<8} {:>9}\n",
+        column, "Result", "Time"
+    ));
+    for result in results {
+        let status = match result.exit_code {
+            Some(0) => "ok".to_string(),
+            Some(code) => format!("exit {code}"),
+            None => "killed".to_string(),
+        };
+        out.push_str(&format!(
+            "  {:<width$}  {:<8} {:>8.3}s\n",
+            result.name,
+            status,
+            result.duration.as_secs_f64()
+        ));
+    }
+
+    let failed = results.iter().filter(|r| !r.success()).count();
+    if failed > 0 {
+        out.push_str(&format!("\n{failed} of {} failed\n", results.len()));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, exit_code: Option<i32>, millis: u64) -> RunResult {
+        RunResult {
+            name: name.to_string(),
+            exit_code,
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_format_workspace_summary() {
+        let results = [
+            result("@app/web", Some(0), 1200),
+            result("@app/api", Some(2), 410),
+            result("docs", None, 5),
+        ];
+
+        assert_eq!(
+            format_workspace_summary("test", &results),
+            "\"test\" in 3 workspaces:\n\n\
+             \x20 Workspace  Result        Time\n\
+             \x20 @app/web   ok          1.200s\n\
+             \x20 @app/api   exit 2      0.410s\n\
+             \x20 docs       killed      0.005s\n\
+             \n2 of 3 failed\n"
+        );
+    }
+
+    #[test]
+    fn test_format_workspace_summary_all_ok() {
+        let summary = format_workspace_summary("build", &[result("web", Some(0), 10)]);
+        assert!(summary.starts_with("\"build\" in 1 workspace:"));
+        assert!(!summary.contains("failed"));
+    }
+
+    #[test]
+    fn test_format_run_summary() {
+        let summary = format_run_summary("Group \"ci\"", "Script", &[result("lint", Some(0), 10)]);
+        assert!(summary.starts_with("Group \"ci\":\n\n  Script  Result"));
+        assert!(summary.contains("  lint    ok"));
+    }
+}
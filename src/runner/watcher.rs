@@ -0,0 +1,270 @@
+//! Re-running a script when files change.
+//!
+//! Watches the project for changes to files matching glob patterns and
+//! calls back after each burst of changes settles. Ctrl+C stops watching
+//! and returns instead of exiting nrs; the running script gets it too.
+
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use glob::Pattern;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::{debug, warn};
+
+use crate::config::WatchConfig;
+
+/// Directories never watched: too large, or changed by the tools themselves.
+const IGNORED_DIRS: [&str; 2] = ["node_modules", ".git"];
+
+/// How often to check for Ctrl+C while waiting for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Decides which changed paths trigger a rerun.
+#[derive(Debug, Clone, Default)]
+pub struct WatchFilter {
+    patterns: Vec<Pattern>,
+}
+
+impl WatchFilter {
+    /// Build a filter from glob patterns relative to the project.
+    ///
+    /// Without patterns every file matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern is not a valid glob.
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Pattern::new(p).with_context(|| format!("Invalid watch pattern '{p}'")))
+            .collect::<Result<_>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Check if a change to a path, relative to the project, triggers a rerun.
+    pub fn matches(&self, path: &Path) -> bool {
+        let ignored = path
+            .components()
+            .any(|c| IGNORED_DIRS.iter().any(|dir| c.as_os_str() == *dir));
+        if ignored {
+            return false;
+        }
+        self.patterns.is_empty() || self.patterns.iter().any(|p| p.matches_path(path))
+    }
+}
+
+/// Run a script, then run it again whenever watched files change.
+///
+/// `run` is called for every run and returns its exit code. Changes made
+/// while it runs are ignored, so a build writing its output doesn't trigger
+/// itself. Returns the exit code of the last run once Ctrl+C is pressed.
+///
+/// # Errors
+///
+/// Returns an error if the patterns are invalid, the project can't be
+/// watched, or `run` fails.
+pub fn watch(
+    root: &Path,
+    config: &WatchConfig,
+    patterns: &[String],
+    run: impl FnMut() -> Result<i32>,
+) -> Result<i32> {
+    watch_runs(root, config, patterns, run, false)
+}
+
+/// Like [`watch`], but stops once a run passes.
+///
+/// # Errors
+///
+/// Returns an error if the patterns are invalid, the project can't be
+/// watched, or `run` fails.
+pub fn watch_until_pass(
+    root: &Path,
+    config: &WatchConfig,
+    patterns: &[String],
+    run: impl FnMut() -> Result<i32>,
+) -> Result<i32> {
+    watch_runs(root, config, patterns, run, true)
+}
+
+/// Run and watch, stopping on Ctrl+C or, with `until_pass`, a passing run.
+fn watch_runs(
+    root: &Path,
+    config: &WatchConfig,
+    patterns: &[String],
+    mut run: impl FnMut() -> Result<i32>,
+    until_pass: bool,
+) -> Result<i32> {
+    let filter = WatchFilter::new(patterns)?;
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    watch_project(&mut watcher, root)?;
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let signal = signal_hook::flag::register(signal_hook::consts::SIGINT, interrupted.clone())
+        .context("Failed to handle Ctrl+C")?;
+
+    let result = watch_loop(
+        root,
+        config,
+        &filter,
+        &rx,
+        &interrupted,
+        &mut run,
+        until_pass,
+    );
+    signal_hook::low_level::unregister(signal);
+    result
+}
+
+/// Watch the project's top-level directories, except ignored ones.
+///
+/// Watching recursively from the root would walk all of node_modules.
+fn watch_project(watcher: &mut RecommendedWatcher, root: &Path) -> Result<()> {
+    watcher
+        .watch(root, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch {}", root.display()))?;
+
+    let entries =
+        std::fs::read_dir(root).with_context(|| format!("Failed to read {}", root.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let ignored = IGNORED_DIRS.iter().any(|dir| entry.file_name() == *dir);
+        if ignored || !path.is_dir() {
+            continue;
+        }
+        if let Err(err) = watcher.watch(&path, RecursiveMode::Recursive) {
+            warn!("Failed to watch {}: {err}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn watch_loop(
+    root: &Path,
+    config: &WatchConfig,
+    filter: &WatchFilter,
+    rx: &Receiver<notify::Result<Event>>,
+    interrupted: &AtomicBool,
+    run: &mut impl FnMut() -> Result<i32>,
+    until_pass: bool,
+) -> Result<i32> {
+    let debounce = Duration::from_millis(config.debounce_ms);
+    loop {
+        let code = run()?;
+        if interrupted.load(Ordering::Relaxed) || (until_pass && code == 0) {
+            return Ok(code);
+        }
+
+        // Skip what the run changed itself
+        while rx.try_recv().is_ok() {}
+        eprintln!("\nWatching for changes (Ctrl+C to stop)");
+
+        let Some(changed) = wait_for_change(root, filter, rx, interrupted, debounce) else {
+            return Ok(code);
+        };
+        if config.clear_screen && io::stdout().is_terminal() {
+            print!("\x1b[2J\x1b[3J\x1b[H");
+            let _ = io::stdout().flush();
+        }
+        eprintln!("Changed: {}", changed.display());
+    }
+}
+
+/// Wait until a watched file changes and changes settle for `debounce`.
+///
+/// Returns the first changed path, or `None` on Ctrl+C.
+fn wait_for_change(
+    root: &Path,
+    filter: &WatchFilter,
+    rx: &Receiver<notify::Result<Event>>,
+    interrupted: &AtomicBool,
+    debounce: Duration,
+) -> Option<PathBuf> {
+    let mut changed = None;
+    loop {
+        if interrupted.load(Ordering::Relaxed) {
+            return None;
+        }
+        let timeout = if changed.is_some() {
+            debounce
+        } else {
+            POLL_INTERVAL
+        };
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Access(_)) {
+                    continue;
+                }
+                let path = event
+                    .paths
+                    .iter()
+                    .map(|path| path.strip_prefix(root).unwrap_or(path))
+                    .find(|path| filter.matches(path));
+                if let (None, Some(path)) = (&changed, path) {
+                    debug!("Watched file changed: {}", path.display());
+                    changed = Some(path.to_path_buf());
+                }
+            }
+            Ok(Err(err)) => warn!("File watcher error: {err}"),
+            Err(RecvTimeoutError::Timeout) if changed.is_some() => return changed,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_filter() {
+        let filter = WatchFilter::new(&["src/**/*.ts".to_string(), "*.json".to_string()]).unwrap();
+        assert!(filter.matches(Path::new("src/app.ts")));
+        assert!(filter.matches(Path::new("src/lib/util.ts")));
+        assert!(filter.matches(Path::new("package.json")));
+        assert!(!filter.matches(Path::new("README.md")));
+        assert!(!filter.matches(Path::new("node_modules/pkg/package.json")));
+
+        let all = WatchFilter::new(&[]).unwrap();
+        assert!(all.matches(Path::new("README.md")));
+        assert!(!all.matches(Path::new(".git/index")));
+
+        assert!(WatchFilter::new(&["src/[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_wait_for_change_debounces() {
+        let (tx, rx) = mpsc::channel();
+        let root = Path::new("/project");
+        let event = |path: &str| {
+            Ok(
+                Event::new(EventKind::Modify(notify::event::ModifyKind::Any))
+                    .add_path(root.join(path)),
+            )
+        };
+        tx.send(event("README.md")).unwrap();
+        tx.send(event("src/a.ts")).unwrap();
+        tx.send(event("src/b.ts")).unwrap();
+
+        let filter = WatchFilter::new(&["src/*.ts".to_string()]).unwrap();
+        let changed = wait_for_change(
+            root,
+            &filter,
+            &rx,
+            &AtomicBool::new(false),
+            Duration::from_millis(10),
+        );
+        assert_eq!(changed, Some(PathBuf::from("src/a.ts")));
+
+        let interrupted = AtomicBool::new(true);
+        let changed = wait_for_change(root, &filter, &rx, &interrupted, Duration::ZERO);
+        assert_eq!(changed, None);
+    }
+}
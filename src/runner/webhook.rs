@@ -0,0 +1,320 @@
+//! Run webhooks.
+//!
+//! After a script finishes, a JSON payload describing the run can be POSTed
+//! to `hooks.webhook_url`. This is meant for local observability (e.g. a
+//! collector on localhost), so only plain `http://` URLs are supported and
+//! failures never affect the script's exit code.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use tracing::debug;
+
+use super::executor::ExecutionResult;
+use crate::config::HooksConfig;
+
+/// Timeout for connecting to and talking with the webhook server.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// JSON payload sent after a run.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    /// Project directory the script ran in.
+    pub project: String,
+    /// Workspace package name, when run in a monorepo workspace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    /// Script name.
+    pub script: String,
+    /// `"success"` or `"failure"`.
+    pub status: &'static str,
+    /// Exit code, if the script exited normally.
+    pub exit_code: Option<i32>,
+    /// Run duration in milliseconds.
+    pub duration_ms: u128,
+}
+
+impl WebhookPayload {
+    /// Build the payload for a finished run.
+    pub fn new(
+        project: &Path,
+        workspace: Option<&str>,
+        script: &str,
+        result: &ExecutionResult,
+    ) -> Self {
+        Self {
+            project: project.display().to_string(),
+            workspace: workspace.map(String::from),
+            script: script.to_string(),
+            status: if result.success() {
+                "success"
+            } else {
+                "failure"
+            },
+            exit_code: result.code(),
+            duration_ms: result.duration.as_millis(),
+        }
+    }
+}
+
+/// Check if a script should trigger the webhook.
+pub fn webhook_matches(config: &HooksConfig, script: &str) -> bool {
+    config.patterns.is_empty()
+        || config
+            .patterns
+            .iter()
+            .any(|pattern| match glob::Pattern::new(pattern) {
+                Ok(glob) => glob.matches(script),
+                Err(_) => pattern == script,
+            })
+}
+
+/// Send the run webhook if one is configured for this script.
+///
+/// Errors are reported as a warning on stderr.
+pub fn notify_run(config: &HooksConfig, payload: &WebhookPayload) {
+    let Some(url) = config.webhook_url.as_deref() else {
+        return;
+    };
+    if !webhook_matches(config, &payload.script) {
+        return;
+    }
+
+    match send_webhook(url, payload) {
+        Ok(()) => debug!("Sent run webhook for {} to {url}", payload.script),
+        Err(err) => eprintln!("Warning: Webhook to {url} failed: {err:#}"),
+    }
+}
+
+/// POST a payload as JSON to an `http://` URL.
+pub fn send_webhook(url: &str, payload: &WebhookPayload) -> Result<()> {
+    let (host, port, path) = parse_http_url(url)?;
+    let body = serde_json::to_string(payload).context("Failed to serialize webhook payload")?;
+
+    let addr = (host.as_str(), port)
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve {host}"))?
+        .next()
+        .with_context(|| format!("No address found for {host}"))?;
+    // IPv6 addresses keep their brackets next to a port
+    let authority = if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    };
+    let mut stream = TcpStream::connect_timeout(&addr, WEBHOOK_TIMEOUT)
+        .with_context(|| format!("Failed to connect to {authority}"))?;
+    stream.set_read_timeout(Some(WEBHOOK_TIMEOUT))?;
+    stream.set_write_timeout(Some(WEBHOOK_TIMEOUT))?;
+
+    let request = format!(
+        "POST {path} HTTP/1.1\r\n\
+         Host: {authority}\r\n\
+         User-Agent: nrs/{}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {body}",
+        env!("CARGO_PKG_VERSION"),
+        body.len(),
+    );
+    stream
+        .write_all(request.as_bytes())
+        .context("Failed to send webhook request")?;
+
+    // Only the status line matters
+    let mut response = [0u8; 64];
+    let read = stream
+        .read(&mut response)
+        .context("Failed to read webhook response")?;
+    let status_line = String::from_utf8_lossy(&response[..read]);
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .context("Invalid webhook response")?;
+
+    if !(200..300).contains(&status) {
+        bail!("server responded with status {status}");
+    }
+    Ok(())
+}
+
+/// Split an `http://` URL into host, port and path.
+///
+/// IPv6 hosts are written in brackets (`http://[::1]:8080/`) and returned
+/// without them.
+fn parse_http_url(url: &str) -> Result<(String, u16, String)> {
+    let Some(rest) = url.strip_prefix("http://") else {
+        bail!("only http:// webhook URLs are supported");
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, rest) = bracketed
+                .split_once(']')
+                .context("Missing ] after the IPv6 host in webhook URL")?;
+            match rest {
+                "" => (host, None),
+                _ => match rest.strip_prefix(':') {
+                    Some(port) => (host, Some(port)),
+                    None => bail!("Invalid text after the IPv6 host in webhook URL: {rest}"),
+                },
+            }
+        }
+        None => match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    let port = match port {
+        Some(port) => port
+            .parse::<u16>()
+            .with_context(|| format!("Invalid port in webhook URL: {port}"))?,
+        None => 80,
+    };
+
+    if host.is_empty() {
+        bail!("missing host in webhook URL");
+    }
+
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn payload(script: &str) -> WebhookPayload {
+        WebhookPayload {
+            project: "/work/app".to_string(),
+            workspace: None,
+            script: script.to_string(),
+            status: "success",
+            exit_code: Some(0),
+            duration_ms: 1200,
+        }
+    }
+
+    #[test]
+    fn test_parse_http_url() {
+        assert_eq!(
+            parse_http_url("http://localhost:9000/hooks/nrs").unwrap(),
+            ("localhost".to_string(), 9000, "/hooks/nrs".to_string())
+        );
+        assert_eq!(
+            parse_http_url("http://example.test").unwrap(),
+            ("example.test".to_string(), 80, "/".to_string())
+        );
+        assert!(parse_http_url("https://example.test").is_err());
+        assert!(parse_http_url("http://:80/").is_err());
+    }
+
+    #[test]
+    fn test_parse_http_url_ipv6() {
+        assert_eq!(
+            parse_http_url("http://[::1]:8080/hooks").unwrap(),
+            ("::1".to_string(), 8080, "/hooks".to_string())
+        );
+        assert_eq!(
+            parse_http_url("http://[fe80::1]").unwrap(),
+            ("fe80::1".to_string(), 80, "/".to_string())
+        );
+        assert!(parse_http_url("http://[::1/").is_err());
+        assert!(parse_http_url("http://[::1]8080/").is_err());
+        assert!(parse_http_url("http://[]:80/").is_err());
+    }
+
+    #[test]
+    fn test_webhook_matches_patterns() {
+        let mut config = HooksConfig::default();
+        assert!(webhook_matches(&config, "dev"));
+
+        config.patterns = vec!["deploy*".to_string(), "release".to_string()];
+        assert!(webhook_matches(&config, "deploy:prod"));
+        assert!(webhook_matches(&config, "release"));
+        assert!(!webhook_matches(&config, "dev"));
+    }
+
+    #[test]
+    fn test_payload_json() {
+        let json = serde_json::to_string(&payload("deploy")).unwrap();
+        assert!(json.contains(r#""script":"deploy""#));
+        assert!(json.contains(r#""status":"success""#));
+        assert!(json.contains(r#""duration_ms":1200"#));
+        assert!(!json.contains("workspace"));
+    }
+
+    #[test]
+    fn test_send_webhook_posts_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(len) = line.strip_prefix("Content-Length: ") {
+                    content_length = len.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let mut stream = stream;
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .unwrap();
+            (request_line, String::from_utf8(body).unwrap())
+        });
+
+        send_webhook(&format!("http://127.0.0.1:{port}/runs"), &payload("build")).unwrap();
+
+        let (request_line, body) = server.join().unwrap();
+        assert_eq!(request_line, "POST /runs HTTP/1.1\r\n");
+        assert!(body.contains(r#""script":"build""#));
+    }
+
+    #[test]
+    fn test_send_webhook_error_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            stream
+                .write_all(b"HTTP/1.1 500 Internal Server Error\r\n\r\n")
+                .unwrap();
+        });
+
+        let err =
+            send_webhook(&format!("http://127.0.0.1:{port}/"), &payload("build")).unwrap_err();
+        server.join().unwrap();
+        assert!(err.to_string().contains("500"));
+    }
+}
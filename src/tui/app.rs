@@ -1,15 +1,35 @@
 //! Application state for the TUI.
 
-use std::collections::HashSet;
-use std::path::PathBuf;
-
-use crate::config::{Config, SortMode};
-use crate::history::History;
-use crate::package::{Runner, Script, Scripts, Workspace};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyEvent;
+
+use super::groups::GroupLayout;
+use super::keymap::{Keymap, Resolved, ESSENTIAL_ACTIONS};
+use super::message::{Message, MessageLevel, MessageQueue};
+use super::profile::Profile;
+use super::text_input::TextInput;
+use crate::config::{Config, KeyAction, SortMode};
+use crate::history::{Favorites, History, ProjectStats, ScriptHistory};
+use crate::package::{
+    add_script, all_workspaces, edit_script, install_runner, needs_install, preview_edit,
+    project_info, script_excerpt, Excerpt, GitActivity, PreviewLine, ProjectInfo, Runner, Script,
+    ScriptEdit, ScriptGraph, Scripts, TreeLine, Workspace, GIT_ACTIVITY_BOOST,
+};
+use crate::runner::{
+    jobs, send_notice, spawn_streamed, split_args, ExecutionResult, OutputFilter, OutputLine,
+    RunContext, StreamedRun,
+};
+use crate::utils::natural_cmp;
 
 /// Minimum column width for script items.
 const MIN_COLUMN_WIDTH: u16 = 28;
 
+/// Maximum number of output lines kept in the output pane.
+const MAX_OUTPUT_LINES: usize = 10_000;
+
 /// Application mode/state.
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum AppMode {
@@ -22,12 +42,100 @@ pub enum AppMode {
     MultiSelect { selected: HashSet<usize> },
     /// Help overlay.
     Help,
-    /// Error display.
-    Error { message: String },
+    /// Full details popover for the selected script.
+    Details,
+    /// Full-screen, highlighted preview of the selected script's command.
+    Preview,
+    /// Source popover with the JSON defining the selected script.
+    Source { excerpt: Excerpt },
+    /// Tree of the scripts the selected script runs.
+    Dependencies { tree: Vec<TreeLine> },
+    /// Package metadata besides scripts: entry points, exports, bin entries.
+    ProjectInfo { info: ProjectInfo },
+    /// Usage statistics of the project, from its history.
+    Stats { stats: ProjectStats },
+    /// Error display with recovery actions (selected with 1-9).
+    Error {
+        message: String,
+        actions: Vec<ErrorAction>,
+    },
     /// Arguments input mode.
     Args { script_index: usize, input: String },
+    /// Adding a script: the name is typed first, then the command.
+    NewScript { name: Option<String>, input: String },
+    /// Typing a new name for a script.
+    RenameScript { from: String, input: String },
+    /// Confirming a rename or removal, with a preview of the package.json change.
+    ConfirmEdit {
+        edit: ScriptEdit,
+        preview: Vec<PreviewLine>,
+    },
+    /// Asking before running scripts matching `scripts.confirm`.
+    ConfirmRun { names: Vec<String> },
+    /// Offering to install missing dependencies with `command` first.
+    InstallPrompt { command: String },
+    /// Key bindings leave these essential actions without a key.
+    KeyConflicts { actions: Vec<KeyAction> },
     /// Workspace selection mode (for monorepos).
     WorkspaceSelect,
+    /// Category summary with script counts.
+    Categories,
+    /// Searching the scripts of the root and every workspace at once.
+    GlobalSearch { query: String, selected: usize },
+    /// Output pane for a script running inside the TUI.
+    Running,
+}
+
+impl AppMode {
+    /// Get a short name for the mode, without any user input it holds.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Filter { .. } => "filter",
+            Self::MultiSelect { .. } => "multi-select",
+            Self::Help => "help",
+            Self::Details => "details",
+            Self::Preview => "preview",
+            Self::Source { .. } => "source",
+            Self::Dependencies { .. } => "dependencies",
+            Self::ProjectInfo { .. } => "project-info",
+            Self::Stats { .. } => "stats",
+            Self::Error { .. } => "error",
+            Self::Args { .. } => "args",
+            Self::NewScript { .. } => "new-script",
+            Self::RenameScript { .. } => "rename-script",
+            Self::ConfirmEdit { .. } => "confirm-edit",
+            Self::ConfirmRun { .. } => "confirm-run",
+            Self::InstallPrompt { .. } => "install-prompt",
+            Self::KeyConflicts { .. } => "key-conflicts",
+            Self::WorkspaceSelect => "workspace-select",
+            Self::Categories => "categories",
+            Self::GlobalSearch { .. } => "global-search",
+            Self::Running => "running",
+        }
+    }
+}
+
+/// Recovery action offered in the error overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Try the failed run again.
+    Retry,
+    /// Switch to the next package manager.
+    SwitchRunner,
+    /// Open the config file in the editor.
+    OpenConfig,
+}
+
+impl ErrorAction {
+    /// Get the label shown in the error overlay.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Retry => "Retry",
+            Self::SwitchRunner => "Switch runner",
+            Self::OpenConfig => "Open config",
+        }
+    }
 }
 
 /// Currently selected workspace context.
@@ -35,8 +143,21 @@ pub enum AppMode {
 pub enum WorkspaceContext {
     /// Root scripts (no specific workspace selected).
     Root,
-    /// A specific workspace is selected.
-    Workspace(usize),
+    /// A specific workspace is selected, by its member indices from the top
+    /// level (one index unless it belongs to a nested monorepo).
+    Workspace(Vec<usize>),
+}
+
+/// A script found by searching every workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalMatch {
+    /// Member indices of the workspace from the top level, empty for the
+    /// project root.
+    pub workspace: Vec<usize>,
+    /// Name of the workspace, or of the project for root scripts.
+    pub label: String,
+    /// The script name.
+    pub script: String,
 }
 
 /// Information about a script to run after TUI exits.
@@ -48,14 +169,66 @@ pub struct ScriptRun {
     pub args: Option<String>,
     /// Workspace name if running from a specific workspace.
     pub workspace: Option<String>,
-    /// Workspace path if running from a specific workspace.
+    /// Workspace path if running from a specific workspace, or the monorepo
+    /// root for a root script run from one of its packages.
     pub workspace_path: Option<PathBuf>,
+    /// Nested monorepo to run the workspace script from, or monorepo root to
+    /// run a root script from, if not the project root.
+    pub workspace_root: Option<PathBuf>,
+}
+
+impl ScriptRun {
+    /// Create a run of a project script.
+    pub fn new(script: Script, args: Option<String>) -> Self {
+        Self {
+            script,
+            args,
+            workspace: None,
+            workspace_path: None,
+            workspace_root: None,
+        }
+    }
+
+    /// Create a run of a workspace's script.
+    pub fn in_workspace(script: Script, args: Option<String>, workspace: &Workspace) -> Self {
+        Self {
+            script,
+            args,
+            workspace: Some(workspace.name().to_string()),
+            workspace_path: Some(workspace.path().to_path_buf()),
+            workspace_root: workspace.root().map(Path::to_path_buf),
+        }
+    }
+
+    /// Create a run of a monorepo root script from one of its packages.
+    ///
+    /// Entries that don't come from the root run as project scripts.
+    pub fn from_parent(entry: Script, args: Option<String>) -> Self {
+        let Some(script) = entry.root_script() else {
+            return Self::new(entry, args);
+        };
+        let root = entry.parent().map(|parent| parent.root.clone());
+        Self {
+            script,
+            args,
+            workspace: None,
+            workspace_path: root.clone(),
+            workspace_root: root,
+        }
+    }
+
+    /// Get the directory to run the script from.
+    pub fn run_dir<'a>(&'a self, project_dir: &'a Path) -> &'a Path {
+        self.workspace_root.as_deref().unwrap_or(project_dir)
+    }
 }
 
 impl std::fmt::Display for ScriptRun {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let prefix = if let Some(ws) = &self.workspace {
             format!("{} > ", ws)
+        } else if self.workspace_root.is_some() {
+            "root > ".to_string()
         } else {
             String::new()
         };
@@ -68,6 +241,47 @@ impl std::fmt::Display for ScriptRun {
     }
 }
 
+/// A script running in the output pane.
+pub struct PaneRun {
+    /// The script being run.
+    run: ScriptRun,
+    /// The running process.
+    process: StreamedRun,
+    /// Output received so far.
+    lines: Vec<OutputLine>,
+    /// Lines scrolled up from the end (0 = follow new output).
+    scroll: usize,
+    /// Whether the finished run was reported in `finished_runs`.
+    reported: bool,
+}
+
+impl PaneRun {
+    /// Get the script being run.
+    pub fn run(&self) -> &ScriptRun {
+        &self.run
+    }
+
+    /// Get the output received so far.
+    pub fn lines(&self) -> &[OutputLine] {
+        &self.lines
+    }
+
+    /// Get how many lines the view is scrolled up from the end.
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// Get the exit code, `Some(None)` if killed, or `None` while running.
+    pub fn exit_code(&self) -> Option<Option<i32>> {
+        self.process.status().map(|status| status.code())
+    }
+
+    /// Check if the script has finished.
+    pub fn is_finished(&self) -> bool {
+        self.process.is_finished()
+    }
+}
+
 /// Main application state.
 pub struct App {
     // Data
@@ -77,6 +291,16 @@ pub struct App {
     config: Config,
     /// Execution history.
     history: History,
+    /// Scripts pinned from the TUI.
+    favorites: Favorites,
+    /// Uncommitted git changes, boosting related scripts in recent sorting.
+    git_activity: GitActivity,
+    /// Whether a script was pinned or unpinned this session.
+    favorites_changed: bool,
+    /// Show commands instead of running them.
+    read_only: bool,
+    /// How output pane runs are logged and checked.
+    run_context: RunContext,
     /// Detected package manager.
     runner: Runner,
     /// Project name.
@@ -89,10 +313,22 @@ pub struct App {
     is_monorepo: bool,
     /// Available workspaces (if monorepo).
     workspaces: Vec<Workspace>,
+    /// Root project scripts, restored when leaving a workspace.
+    root_scripts: Scripts,
+    /// Description of the monorepo tooling (e.g. "pnpm workspaces + turbo").
+    monorepo_label: Option<String>,
     /// Currently selected workspace context.
     workspace_context: WorkspaceContext,
     /// Selected workspace index (for workspace selector).
     workspace_selected: usize,
+    /// Nested monorepos drilled into in the workspace selector, as member
+    /// indices from the top level.
+    workspace_trail: Vec<usize>,
+    /// Scripts matching the search across all workspaces.
+    global_matches: Vec<GlobalMatch>,
+    /// Whether the search across all workspaces was opened from the
+    /// workspace selector, to go back there.
+    search_from_selector: bool,
 
     // UI State
     /// Current application mode.
@@ -105,16 +341,70 @@ pub struct App {
     filter_text: String,
     /// Current sort mode.
     sort_mode: SortMode,
+    /// Category the scripts are narrowed to, if any.
+    category: Option<String>,
+    /// Selected row in the category summary (0 = all scripts).
+    category_selected: usize,
+    /// Category groups collapsed to their header in category sort.
+    collapsed_groups: BTreeSet<String>,
+    /// Whether a group was collapsed or expanded this session.
+    collapsed_changed: bool,
 
     // Computed (cached)
     /// Indices of visible scripts (after filtering and sorting).
     visible_indices: Vec<usize>,
     /// Number of columns in the grid.
     columns: usize,
+    /// Number of script rows that fit on screen (0 = unknown).
+    viewport_rows: usize,
+    /// Cursor position (byte offset) in the filter or args input.
+    input_cursor: usize,
+    /// Position in the filter query or argument history while recalling with Up/Down.
+    history_index: Option<usize>,
+    /// Arguments typed before recalling older ones, restored past the newest.
+    args_draft: String,
+    /// Number typed so far for multi-digit quick select.
+    pending_number: Option<usize>,
+    /// Key bindings from the config on top of the keymap preset.
+    keymap: Keymap,
+    /// Keys of a chord typed so far.
+    pending_keys: Vec<KeyEvent>,
+    /// Transient footer notifications.
+    messages: MessageQueue,
+    /// Timings of filtering, sorting and drawing.
+    profile: Profile,
+    /// Whether the debug overlay with the timings is shown.
+    show_profile: bool,
+    /// Run that failed, offered again by the Retry error action.
+    failed_run: Option<ScriptRun>,
+    /// Error action that must be completed outside the TUI.
+    requested_action: Option<ErrorAction>,
     /// Should the app quit.
     should_quit: bool,
     /// Script to run after exit.
     script_to_run: Option<ScriptRun>,
+    /// Scripts picked in multi-select mode, run together after exit.
+    multi_run: Vec<ScriptRun>,
+    /// Whether `multi_run` runs one script in every workspace defining it.
+    all_workspaces_run: bool,
+    /// Whether `script_to_run` reruns whenever files change.
+    watch_run: bool,
+    /// Whether the scripts to run were confirmed in the overlay.
+    run_confirmed: bool,
+    /// Whether missing dependencies were asked about.
+    install_checked: bool,
+    /// Whether missing dependencies are installed before the scripts run.
+    install_first: bool,
+    /// Run waiting for the install prompt to start in the output pane.
+    install_pane_run: Option<ScriptRun>,
+    /// Workspace directory to print after exit.
+    yanked_path: Option<PathBuf>,
+    /// Script running in the output pane.
+    pane: Option<PaneRun>,
+    /// Runs finished in the output pane, for history and webhooks.
+    finished_runs: Vec<(ScriptRun, ExecutionResult)>,
+    /// Scripts renamed or removed, with the directory of their package.json.
+    script_edits: Vec<(PathBuf, ScriptEdit)>,
 }
 
 impl App {
@@ -151,6 +441,12 @@ impl App {
         let sort_mode = config.general.default_sort;
         let visible_indices: Vec<usize> = (0..scripts.len()).collect();
         let is_monorepo = !workspaces.is_empty();
+        let (keymap, invalid_bindings) = Keymap::new(&config.keybindings);
+        let collapsed_groups = history
+            .get_collapsed_groups(&project_path)
+            .iter()
+            .cloned()
+            .collect();
 
         // Start in workspace select mode if this is a monorepo with workspaces
         let initial_mode = if is_monorepo {
@@ -160,9 +456,16 @@ impl App {
         };
 
         let mut app = Self {
+            root_scripts: scripts.clone(),
+            monorepo_label: None,
             scripts,
             config,
             history,
+            favorites: Favorites::new(),
+            git_activity: GitActivity::default(),
+            favorites_changed: false,
+            read_only: false,
+            run_context: RunContext::default(),
             runner,
             project_name,
             project_path,
@@ -170,19 +473,73 @@ impl App {
             workspaces,
             workspace_context: WorkspaceContext::Root,
             workspace_selected: 0,
+            workspace_trail: Vec::new(),
+            global_matches: Vec::new(),
+            search_from_selector: false,
             mode: initial_mode,
             selected: 0,
             scroll_offset: 0,
             filter_text: String::new(),
             sort_mode,
+            category: None,
+            category_selected: 0,
+            collapsed_groups,
+            collapsed_changed: false,
             visible_indices,
             columns: 1,
+            viewport_rows: 0,
+            input_cursor: 0,
+            history_index: None,
+            args_draft: String::new(),
+            pending_number: None,
+            keymap,
+            pending_keys: Vec::new(),
+            messages: MessageQueue::new(),
+            profile: Profile::default(),
+            show_profile: false,
+            failed_run: None,
+            requested_action: None,
             should_quit: false,
             script_to_run: None,
+            multi_run: Vec::new(),
+            all_workspaces_run: false,
+            watch_run: false,
+            run_confirmed: false,
+            install_checked: false,
+            install_first: false,
+            install_pane_run: None,
+            yanked_path: None,
+            pane: None,
+            finished_runs: Vec::new(),
+            script_edits: Vec::new(),
         };
 
         // Initial sort based on default sort mode
         app.update_visible_scripts();
+        for message in invalid_bindings {
+            app.notify(MessageLevel::Warn, message);
+        }
+        let lost = app
+            .keymap
+            .unreachable(&ESSENTIAL_ACTIONS, app.config.general.esc_quits());
+        if !lost.is_empty() {
+            app.mode = AppMode::KeyConflicts { actions: lost };
+        }
+        let unreadable = all_workspaces(&app.workspaces)
+            .into_iter()
+            .filter(|workspace| workspace.error().is_some())
+            .count();
+        if unreadable > 0 {
+            let noun = if unreadable == 1 {
+                "workspace manifest"
+            } else {
+                "workspace manifests"
+            };
+            app.notify(
+                MessageLevel::Warn,
+                format!("Couldn't read {unreadable} {noun}, marked in the list"),
+            );
+        }
         app
     }
 
@@ -203,6 +560,23 @@ impl App {
         self.script_to_run.as_ref()
     }
 
+    /// Get all scripts to run after exit.
+    ///
+    /// This is every script picked in multi-select mode, or the single
+    /// script to run otherwise.
+    pub fn scripts_to_run(&self) -> Vec<ScriptRun> {
+        if self.multi_run.is_empty() {
+            self.script_to_run.iter().cloned().collect()
+        } else {
+            self.multi_run.clone()
+        }
+    }
+
+    /// Get the workspace directory yanked for printing, if any.
+    pub fn yanked_path(&self) -> Option<&Path> {
+        self.yanked_path.as_deref()
+    }
+
     /// Get the project name.
     pub fn project_name(&self) -> &str {
         &self.project_name
@@ -233,6 +607,11 @@ impl App {
         self.sort_mode
     }
 
+    /// Get the category the scripts are narrowed to.
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
     /// Get the number of columns.
     pub fn columns(&self) -> usize {
         self.columns
@@ -270,6 +649,40 @@ impl App {
         &self.workspaces
     }
 
+    /// Get the workspaces listed at the selector's current level.
+    pub fn workspace_level(&self) -> &[Workspace] {
+        match self.workspace_at(&self.workspace_trail) {
+            Some(parent) => parent.members(),
+            None => &self.workspaces,
+        }
+    }
+
+    /// Get the nested monorepos drilled into in the selector, outermost first.
+    pub fn workspace_trail(&self) -> Vec<&Workspace> {
+        (1..=self.workspace_trail.len())
+            .filter_map(|len| self.workspace_at(&self.workspace_trail[..len]))
+            .collect()
+    }
+
+    /// Get the workspace at a path of member indices, for changing it.
+    fn workspace_at_mut(&mut self, path: &[usize]) -> Option<&mut Workspace> {
+        let (first, rest) = path.split_first()?;
+        let mut workspace = self.workspaces.get_mut(*first)?;
+        for &i in rest {
+            workspace = workspace.members_mut().get_mut(i)?;
+        }
+        Some(workspace)
+    }
+
+    /// Get the workspace at a path of member indices from the top level.
+    fn workspace_at(&self, path: &[usize]) -> Option<&Workspace> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.workspaces.get(*first)?, |workspace, &i| {
+                workspace.members().get(i)
+            })
+    }
+
     /// Get the current workspace context.
     pub fn workspace_context(&self) -> &WorkspaceContext {
         &self.workspace_context
@@ -284,23 +697,156 @@ impl App {
     pub fn current_workspace(&self) -> Option<&Workspace> {
         match &self.workspace_context {
             WorkspaceContext::Root => None,
-            WorkspaceContext::Workspace(idx) => self.workspaces.get(*idx),
+            WorkspaceContext::Workspace(path) => self.workspace_at(path),
+        }
+    }
+
+    /// Get the monorepo tooling description, if any.
+    pub fn monorepo_label(&self) -> Option<&str> {
+        self.monorepo_label.as_deref()
+    }
+
+    /// Set the monorepo tooling description shown in the header.
+    pub fn set_monorepo_label(&mut self, label: Option<String>) {
+        self.monorepo_label = label;
+    }
+
+    /// Show commands instead of running them, and don't change any state on disk.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Set how output pane runs are logged and checked.
+    pub fn set_run_context(&mut self, context: RunContext) {
+        self.run_context = context;
+    }
+
+    /// Check if the app is in read-only mode.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Show the commands for runs instead of running them (read-only mode).
+    fn show_commands(&mut self, runs: &[ScriptRun]) {
+        let commands: Vec<String> = runs
+            .iter()
+            .map(|run| {
+                let runner = run.script.runner_or(self.runner);
+                let args: Vec<String> = run.args.as_deref().map(split_args).unwrap_or_default();
+                match &run.workspace {
+                    Some(workspace) => {
+                        runner.workspace_command_with_args(workspace, run.script.name(), &args)
+                    }
+                    None => runner.run_command_with_args(run.script.name(), &args),
+                }
+                .join(" ")
+            })
+            .collect();
+        self.notify(
+            MessageLevel::Info,
+            format!("Would run: {}", commands.join(", ")),
+        );
+    }
+
+    /// Set the pinned scripts store.
+    pub fn set_favorites(&mut self, favorites: Favorites) {
+        self.favorites = favorites;
+        self.update_visible_scripts();
+    }
+
+    /// Set the uncommitted git changes that boost related scripts.
+    pub fn set_git_activity(&mut self, activity: GitActivity) {
+        self.git_activity = activity;
+        self.update_visible_scripts();
+    }
+
+    /// Get the pinned scripts, if any were pinned or unpinned this session.
+    pub fn changed_favorites(&self) -> Option<&Favorites> {
+        self.favorites_changed.then_some(&self.favorites)
+    }
+
+    /// Check if a script is pinned, from the TUI or the shared config.
+    pub fn is_pinned(&self, script: &str) -> bool {
+        self.favorites.is_pinned(self.history_path(), script)
+            || self.config.scripts.is_favorite(script)
+    }
+
+    /// Get how many scripts at the top of the list are pinned.
+    pub fn pinned_count(&self) -> usize {
+        if !self.filter_text.is_empty() {
+            return 0;
+        }
+        let scripts = self.scripts.as_slice();
+        self.visible_indices
+            .iter()
+            .take_while(|&&i| self.is_pinned(scripts[i].name()))
+            .count()
+    }
+
+    /// Pin the selected script, or unpin it if it is pinned.
+    ///
+    /// Shared favorites from the config can't be unpinned here.
+    pub fn toggle_pinned(&mut self) {
+        let Some(name) = self.selected_script().map(|s| s.name().to_string()) else {
+            return;
+        };
+        if self.read_only {
+            self.notify(MessageLevel::Info, "Pinning is disabled in read-only mode");
+            return;
+        }
+        if self.config.scripts.is_favorite(&name) {
+            self.notify(
+                MessageLevel::Info,
+                format!("{name} is pinned in the config"),
+            );
+            return;
+        }
+
+        let project = self.history_path().to_path_buf();
+        let pinned = self.favorites.toggle(&project, &name);
+        self.favorites_changed = true;
+        self.update_visible_scripts();
+
+        // Keep the cursor on the script as it moves in or out of the pinned section
+        let scripts = self.scripts.as_slice();
+        if let Some(pos) = self
+            .visible_indices
+            .iter()
+            .position(|&i| scripts[i].name() == name)
+        {
+            self.selected = pos;
+            self.keep_selection_visible();
         }
+
+        let verb = if pinned { "Pinned" } else { "Unpinned" };
+        self.notify(MessageLevel::Info, format!("{verb} {name}"));
+    }
+
+    /// Get the directory history is recorded against.
+    ///
+    /// This is the selected workspace's directory, or the project root when
+    /// no workspace is selected, so each workspace gets its own frecency.
+    pub fn history_path(&self) -> &Path {
+        self.current_workspace()
+            .map_or(&self.project_path, |ws| ws.path())
     }
 
     /// Get the breadcrumb path for display.
     /// Returns something like "monorepo > packages/web > scripts"
     pub fn breadcrumb(&self) -> String {
-        match &self.workspace_context {
-            WorkspaceContext::Root => self.project_name.clone(),
-            WorkspaceContext::Workspace(idx) => {
-                if let Some(ws) = self.workspaces.get(*idx) {
-                    format!("{} > {}", self.project_name, ws.name())
-                } else {
-                    self.project_name.clone()
+        let mut path = self.project_name.clone();
+        if let WorkspaceContext::Workspace(indices) = &self.workspace_context {
+            for len in 1..=indices.len() {
+                if let Some(ws) = self.workspace_at(&indices[..len]) {
+                    path = format!("{path} > {}", ws.name());
                 }
             }
         }
+
+        match &self.category {
+            Some(category) => format!("{path} > {category}"),
+            None => path,
+        }
     }
 
     // ==================== Script Access ====================
@@ -314,14 +860,19 @@ impl App {
     }
 
     /// Get the currently selected script.
+    ///
+    /// Returns `None` while a collapsed group's header is selected.
     pub fn selected_script(&self) -> Option<&Script> {
-        self.visible_indices
-            .get(self.selected)
-            .and_then(|&i| self.scripts.iter().nth(i))
+        self.get_visible_script(self.selected)
     }
 
     /// Get a visible script by its display index (0-based).
+    ///
+    /// Returns `None` for the slot of a collapsed group's header.
     pub fn get_visible_script(&self, index: usize) -> Option<&Script> {
+        if self.is_group_header(index) {
+            return None;
+        }
         self.visible_indices
             .get(index)
             .and_then(|&i| self.scripts.iter().nth(i))
@@ -332,6 +883,72 @@ impl App {
     /// Set the application mode.
     pub fn set_mode(&mut self, mode: AppMode) {
         self.mode = mode;
+        self.reset_input_cursor();
+        self.history_index = None;
+    }
+
+    /// Get the text of the active filter or args input.
+    fn input_text(&self) -> Option<&str> {
+        match &self.mode {
+            AppMode::Filter { query } => Some(query),
+            AppMode::Args { input, .. } => Some(input),
+            AppMode::NewScript { input, .. } => Some(input),
+            AppMode::RenameScript { input, .. } => Some(input),
+            AppMode::GlobalSearch { query, .. } => Some(query),
+            _ => None,
+        }
+    }
+
+    /// Move the input cursor to the end of the current input.
+    fn reset_input_cursor(&mut self) {
+        self.input_cursor = self.input_text().map_or(0, str::len);
+    }
+
+    /// Get the input cursor position as a byte offset.
+    pub fn input_cursor(&self) -> usize {
+        self.input_cursor
+    }
+
+    /// Get the active filter or args input for editing.
+    pub fn text_input(&self) -> Option<TextInput> {
+        self.input_text()
+            .map(|text| TextInput::with_cursor(text, self.input_cursor))
+    }
+
+    /// Write an edited input back to the active filter or args mode.
+    pub fn apply_text_input(&mut self, input: TextInput) {
+        let cursor = input.cursor();
+        match self.mode.clone() {
+            AppMode::Filter { .. } => self.set_filter(input.into_text()),
+            AppMode::Args { script_index, .. } => {
+                self.mode = AppMode::Args {
+                    script_index,
+                    input: input.into_text(),
+                };
+            }
+            AppMode::NewScript { name, .. } => {
+                self.mode = AppMode::NewScript {
+                    name,
+                    input: input.into_text(),
+                };
+            }
+            AppMode::RenameScript { from, .. } => {
+                self.mode = AppMode::RenameScript {
+                    from,
+                    input: input.into_text(),
+                };
+            }
+            AppMode::GlobalSearch { .. } => {
+                self.mode = AppMode::GlobalSearch {
+                    query: input.into_text(),
+                    selected: 0,
+                };
+                self.update_global_matches();
+            }
+            _ => return,
+        }
+        self.input_cursor = cursor;
+        self.history_index = None;
     }
 
     /// Toggle filter mode.
@@ -346,6 +963,7 @@ impl App {
                 self.mode = AppMode::Filter {
                     query: String::new(),
                 };
+                self.input_cursor = 0;
             }
             _ => {}
         }
@@ -358,6 +976,8 @@ impl App {
                 script_index: self.selected,
                 input: String::new(),
             };
+            self.input_cursor = 0;
+            self.history_index = None;
         }
     }
 
@@ -388,856 +1008,3657 @@ impl App {
         }
     }
 
-    // ==================== Workspace Management ====================
-
-    /// Enter workspace selection mode.
-    pub fn enter_workspace_select(&mut self) {
-        if self.is_monorepo {
-            self.mode = AppMode::WorkspaceSelect;
-            self.workspace_selected = 0;
+    /// Toggle the details popover for the selected script.
+    pub fn toggle_details(&mut self) {
+        match self.mode {
+            AppMode::Details => {
+                self.mode = AppMode::Normal;
+            }
+            _ if self.selected_script().is_some() => {
+                self.mode = AppMode::Details;
+            }
+            _ => {}
         }
     }
 
-    /// Exit workspace selection and go to scripts.
-    pub fn exit_workspace_select(&mut self) {
-        self.mode = AppMode::Normal;
-        self.selected = 0;
-        self.update_visible_scripts();
+    /// Toggle the full-screen command preview for the selected script.
+    pub fn toggle_preview(&mut self) {
+        match self.mode {
+            AppMode::Preview => {
+                self.mode = AppMode::Normal;
+            }
+            _ if self.selected_script().is_some() => {
+                self.mode = AppMode::Preview;
+            }
+            _ => {}
+        }
     }
 
-    /// Select a workspace and show its scripts.
-    pub fn select_workspace(&mut self, index: usize) {
-        // Index 0 is "root", indices 1+ are workspaces
-        if index == 0 {
-            self.workspace_context = WorkspaceContext::Root;
-            // Keep root scripts
-        } else if let Some(workspace) = self.workspaces.get(index - 1) {
-            self.workspace_context = WorkspaceContext::Workspace(index - 1);
-            // Load workspace scripts
-            self.scripts = Scripts::from_vec(workspace.scripts().to_vec());
+    /// Toggle the source popover for the selected script.
+    ///
+    /// Shows the script's line in package.json with its pre/post hooks.
+    pub fn toggle_source(&mut self) {
+        if matches!(self.mode, AppMode::Source { .. }) {
+            self.mode = AppMode::Normal;
+            return;
         }
+        let Some(script) = self.selected_script() else {
+            return;
+        };
 
-        self.selected = 0;
-        self.mode = AppMode::Normal;
-        self.update_visible_scripts();
+        match script_excerpt(self.history_path(), script) {
+            Ok(excerpt) => self.mode = AppMode::Source { excerpt },
+            Err(err) => self.notify(MessageLevel::Error, format!("{err:#}")),
+        }
     }
 
-    /// Select the currently highlighted workspace.
-    pub fn select_current_workspace(&mut self) {
-        self.select_workspace(self.workspace_selected);
+    /// Toggle the tree of scripts the selected script runs.
+    pub fn toggle_dependencies(&mut self) {
+        if matches!(self.mode, AppMode::Dependencies { .. }) {
+            self.mode = AppMode::Normal;
+            return;
+        }
+        let Some(script) = self.selected_script() else {
+            return;
+        };
+
+        let tree = ScriptGraph::from_scripts(&self.scripts).tree(script.name());
+        self.mode = AppMode::Dependencies { tree };
     }
 
-    /// Go back to workspace selection from script view.
-    pub fn back_to_workspace_select(&mut self) {
-        if self.is_monorepo {
-            self.mode = AppMode::WorkspaceSelect;
+    /// Toggle the project info popover for the package.json of the scripts
+    /// shown.
+    pub fn toggle_project_info(&mut self) {
+        if matches!(self.mode, AppMode::ProjectInfo { .. }) {
+            self.mode = AppMode::Normal;
+            return;
+        }
+        match project_info(self.history_path()) {
+            Ok(info) => self.mode = AppMode::ProjectInfo { info },
+            Err(err) => self.notify(MessageLevel::Error, format!("{err:#}")),
         }
     }
 
-    /// Move workspace selection up.
-    pub fn workspace_move_up(&mut self) {
-        if self.workspace_selected > 0 {
-            self.workspace_selected -= 1;
+    /// Toggle the usage statistics of the project whose scripts are shown.
+    pub fn toggle_stats(&mut self) {
+        if matches!(self.mode, AppMode::Stats { .. }) {
+            self.mode = AppMode::Normal;
+            return;
         }
+        let stats = self
+            .history
+            .get_project(self.history_path())
+            .map(ProjectStats::from_history)
+            .unwrap_or_default();
+        self.mode = AppMode::Stats { stats };
     }
 
-    /// Move workspace selection down.
-    pub fn workspace_move_down(&mut self) {
-        // +1 for the "root" option
-        let max_index = self.workspaces.len();
-        if self.workspace_selected < max_index {
-            self.workspace_selected += 1;
-        }
+    /// Toggle the debug overlay with filter, sort and frame timings.
+    pub fn toggle_profile(&mut self) {
+        self.show_profile = !self.show_profile;
     }
 
-    /// Move workspace selection left (in grid).
-    pub fn workspace_move_left(&mut self) {
-        if self.workspace_selected > 0 {
-            self.workspace_selected -= 1;
-        }
+    /// Check if the debug overlay is shown.
+    pub fn is_profile_shown(&self) -> bool {
+        self.show_profile
     }
 
-    /// Move workspace selection right (in grid).
-    pub fn workspace_move_right(&mut self) {
-        let max_index = self.workspaces.len();
-        if self.workspace_selected < max_index {
-            self.workspace_selected += 1;
-        }
+    /// Get the filter, sort and frame timings.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
     }
 
-    /// Select workspace by number (1-9).
-    pub fn select_workspace_by_number(&mut self, num: usize) {
-        // num 1 = root (index 0), num 2 = first workspace (index 1), etc.
-        if num > 0 && num <= self.workspaces.len() + 1 {
-            self.select_workspace(num - 1);
-        }
+    /// Record the time the last frame took to draw.
+    pub fn record_frame(&mut self, elapsed: Duration) {
+        self.profile.record_frame(elapsed);
     }
 
-    /// Get the count of items in workspace selector (root + workspaces).
-    pub fn workspace_count(&self) -> usize {
-        self.workspaces.len() + 1 // +1 for root
+    /// Start adding a script to the package.json of the scripts shown.
+    pub fn enter_new_script_mode(&mut self) {
+        if self.read_only {
+            self.notify(MessageLevel::Warn, "Read-only mode: scripts can't be added");
+            return;
+        }
+        self.set_mode(AppMode::NewScript {
+            name: None,
+            input: String::new(),
+        });
     }
 
-    // ==================== Filter Management ====================
+    /// Confirm the name or command typed while adding a script.
+    ///
+    /// The script is written to package.json once both are given, and
+    /// selected.
+    pub fn submit_new_script(&mut self) {
+        let AppMode::NewScript { name, input } = self.mode.clone() else {
+            return;
+        };
+        let input = input.trim().to_string();
+        if input.is_empty() {
+            return;
+        }
 
-    /// Set the filter text.
-    pub fn set_filter(&mut self, text: String) {
-        self.filter_text = text.clone();
-        self.mode = AppMode::Filter { query: text };
-        self.update_visible_scripts();
-    }
+        let Some(name) = name else {
+            if self.scripts.get(&input).is_some() {
+                self.notify(MessageLevel::Warn, format!("{input} already exists"));
+                return;
+            }
+            self.set_mode(AppMode::NewScript {
+                name: Some(input),
+                input: String::new(),
+            });
+            return;
+        };
 
-    /// Append a character to the filter text.
-    pub fn push_filter_char(&mut self, c: char) {
-        self.filter_text.push(c);
-        self.update_visible_scripts();
+        self.set_mode(AppMode::Normal);
+        if let Err(err) = add_script(self.history_path(), &name, &input) {
+            self.notify(MessageLevel::Error, format!("{err:#}"));
+            return;
+        }
+
+        self.scripts.add(Script::new(name.as_str(), input.as_str()));
+        self.store_scripts();
+
+        // Show the new script even if a filter or category would hide it
+        self.category = None;
+        self.clear_filter();
+        self.select_script_named(&name);
+        self.notify(MessageLevel::Info, format!("Added {name} to package.json"));
     }
 
-    /// Remove the last character from the filter text.
-    pub fn pop_filter_char(&mut self) {
-        self.filter_text.pop();
-        self.update_visible_scripts();
+    /// Start renaming the selected script.
+    pub fn enter_rename_mode(&mut self) {
+        let Some(name) = self.editable_script_name("renamed") else {
+            return;
+        };
+        self.set_mode(AppMode::RenameScript {
+            input: name.clone(),
+            from: name,
+        });
     }
 
-    /// Clear the filter text.
-    pub fn clear_filter(&mut self) {
-        self.filter_text.clear();
-        self.update_visible_scripts();
+    /// Confirm the new name typed for a script and preview the rename.
+    pub fn submit_rename(&mut self) {
+        let AppMode::RenameScript { from, input } = self.mode.clone() else {
+            return;
+        };
+        let to = input.trim().to_string();
+        if to.is_empty() || to == from {
+            return;
+        }
+        self.preview_script_edit(ScriptEdit::Rename { from, to });
     }
 
-    // ==================== Sort Management ====================
+    /// Preview removing the selected script, to be confirmed.
+    pub fn remove_selected(&mut self) {
+        if let Some(name) = self.editable_script_name("removed") {
+            self.preview_script_edit(ScriptEdit::Remove { name });
+        }
+    }
 
-    /// Cycle through sort modes: Recent -> Alphabetical -> Category -> Recent.
-    pub fn cycle_sort_mode(&mut self) {
-        self.sort_mode = match self.sort_mode {
-            SortMode::Recent => SortMode::Alpha,
-            SortMode::Alpha => SortMode::Category,
-            SortMode::Category => SortMode::Recent,
+    /// Write the confirmed rename or removal to package.json.
+    ///
+    /// The script's history and pin move with a rename and are dropped with
+    /// a removal.
+    pub fn confirm_edit(&mut self) {
+        let AppMode::ConfirmEdit { edit, .. } = self.mode.clone() else {
+            return;
         };
-        self.update_visible_scripts();
-    }
+        self.set_mode(AppMode::Normal);
 
-    /// Set the sort mode.
-    pub fn set_sort_mode(&mut self, mode: SortMode) {
-        self.sort_mode = mode;
+        let dir = self.history_path().to_path_buf();
+        if let Err(err) = edit_script(&dir, &edit) {
+            self.notify(MessageLevel::Error, format!("{err:#}"));
+            return;
+        }
+
+        let message = match &edit {
+            ScriptEdit::Rename { from, to } => {
+                self.scripts.rename(from, to.as_str());
+                self.history.rename_script(&dir, from, to);
+                self.favorites_changed |= self.favorites.rename(&dir, from, to);
+                format!("Renamed {from} to {to}")
+            }
+            ScriptEdit::Remove { name } => {
+                self.scripts.remove(name);
+                self.history.remove_script(&dir, name);
+                if self.favorites.is_pinned(&dir, name) {
+                    self.favorites.toggle(&dir, name);
+                    self.favorites_changed = true;
+                }
+                format!("Removed {name} from package.json")
+            }
+        };
+        self.store_scripts();
         self.update_visible_scripts();
+        if let ScriptEdit::Rename { to, .. } = &edit {
+            self.select_script_named(to);
+        }
+        self.script_edits.push((dir, edit));
+        self.notify(MessageLevel::Info, message);
     }
 
-    // ==================== Visibility Update ====================
+    /// Get the scripts renamed or removed this session, for updating history.
+    pub fn script_edits(&self) -> &[(PathBuf, ScriptEdit)] {
+        &self.script_edits
+    }
 
-    /// Update the visible scripts based on current filter and sort mode.
-    pub fn update_visible_scripts(&mut self) {
-        // Step 1: Filter
-        let filtered_indices: Vec<usize> = if self.filter_text.is_empty() {
-            (0..self.scripts.len()).collect()
-        } else {
-            // Use the optimized filter_scripts that returns (index, score) pairs
-            let matches = crate::filter::filter_scripts(
-                &self.filter_text,
-                self.scripts.as_slice(),
-                self.config.filter.search_descriptions,
+    /// Get the name of the selected script if it can be edited.
+    fn editable_script_name(&mut self, action: &str) -> Option<String> {
+        if self.read_only {
+            self.notify(
+                MessageLevel::Warn,
+                format!("Read-only mode: scripts can't be {action}"),
             );
+            return None;
+        }
+        let script = self.selected_script()?;
+        if script.runner().is_some() || script.group().is_some() {
+            self.notify(
+                MessageLevel::Warn,
+                format!("Only package.json scripts can be {action} here"),
+            );
+            return None;
+        }
+        Some(script.name().to_string())
+    }
 
-            matches.into_iter().map(|(idx, _score)| idx).collect()
-        };
+    /// Show the package.json change an edit would make, to be confirmed.
+    fn preview_script_edit(&mut self, edit: ScriptEdit) {
+        match preview_edit(self.history_path(), &edit) {
+            Ok(preview) => self.set_mode(AppMode::ConfirmEdit { edit, preview }),
+            Err(err) => self.notify(MessageLevel::Error, format!("{err:#}")),
+        }
+    }
 
-        // Step 2: Sort
-        self.visible_indices = self.sort_indices(filtered_indices);
+    /// Store the shown scripts back where they were loaded from, after
+    /// package.json was edited.
+    fn store_scripts(&mut self) {
+        match self.workspace_context.clone() {
+            WorkspaceContext::Root => self.root_scripts = self.scripts.clone(),
+            WorkspaceContext::Workspace(path) => {
+                let scripts: Vec<Script> = self.scripts.iter().cloned().collect();
+                if let Some(workspace) = self.workspace_at_mut(&path) {
+                    workspace.set_scripts(scripts);
+                }
+            }
+        }
+    }
 
-        // Adjust selection if needed
-        if self.selected >= self.visible_indices.len() {
-            self.selected = self.visible_indices.len().saturating_sub(1);
+    /// Select a visible script by name.
+    fn select_script_named(&mut self, name: &str) {
+        if let Some(index) = self.visible_scripts().iter().position(|s| s.name() == name) {
+            self.selected = index;
         }
     }
 
-    /// Sort indices based on current sort mode.
-    fn sort_indices(&self, mut indices: Vec<usize>) -> Vec<usize> {
-        match self.sort_mode {
-            SortMode::Recent => {
-                // Sort by history score (recent/frequent first)
-                // Clone scripts so we can pass them to get_sorted_by_recent
-                let scripts_owned: Vec<Script> = indices
-                    .iter()
-                    .filter_map(|&i| self.scripts.iter().nth(i).cloned())
-                    .collect();
+    // ==================== Workspace Management ====================
+
+    /// Enter workspace selection mode.
+    pub fn enter_workspace_select(&mut self) {
+        if self.is_monorepo {
+            self.mode = AppMode::WorkspaceSelect;
+            self.workspace_selected = 0;
+            self.workspace_trail.clear();
+        }
+    }
 
-                let sorted = self
-                    .history
-                    .get_sorted_by_recent(&self.project_path, &scripts_owned);
+    /// Exit workspace selection and go to scripts.
+    pub fn exit_workspace_select(&mut self) {
+        self.mode = AppMode::Normal;
+        self.selected = 0;
+        self.update_visible_scripts();
+    }
 
-                // Map back to indices
-                sorted
-                    .iter()
-                    .filter_map(|s| {
-                        self.scripts
-                            .iter()
-                            .position(|script| script.name() == s.name())
-                    })
-                    .filter(|i| indices.contains(i))
-                    .collect()
+    /// Select a workspace at the selector's current level and show its scripts.
+    pub fn select_workspace(&mut self, index: usize) {
+        // Index 0 is the level's own root, indices 1+ are its workspaces
+        let path = if index == 0 {
+            self.workspace_trail.clone()
+        } else if index <= self.workspace_level().len() {
+            let mut path = self.workspace_trail.clone();
+            path.push(index - 1);
+            path
+        } else {
+            return;
+        };
+
+        // Unreadable workspaces stay in the selector, with the reason
+        let unreadable = self
+            .workspace_at(&path)
+            .and_then(|w| Some(format!("{}: {}", w.name(), w.error()?)));
+        if let Some(message) = unreadable {
+            self.notify(MessageLevel::Warn, message);
+            return;
+        }
+
+        match self.workspace_at(&path) {
+            Some(workspace) => {
+                self.scripts = Scripts::from_vec(workspace.scripts().to_vec());
+                self.workspace_context = WorkspaceContext::Workspace(path);
             }
-            SortMode::Alpha => {
-                // Sort alphabetically by name
-                indices.sort_by(|&a, &b| {
-                    let name_a = self.scripts.iter().nth(a).map(|s| s.name()).unwrap_or("");
-                    let name_b = self.scripts.iter().nth(b).map(|s| s.name()).unwrap_or("");
-                    name_a.cmp(name_b)
-                });
-                indices
+            None => {
+                self.workspace_context = WorkspaceContext::Root;
+                self.scripts = self.root_scripts.clone();
             }
-            SortMode::Category => {
-                // Sort by category (prefix before colon) then alphabetically
-                indices.sort_by(|&a, &b| {
-                    let name_a = self.scripts.iter().nth(a).map(|s| s.name()).unwrap_or("");
-                    let name_b = self.scripts.iter().nth(b).map(|s| s.name()).unwrap_or("");
+        }
 
-                    let category_a = name_a.split(':').next().unwrap_or(name_a);
-                    let category_b = name_b.split(':').next().unwrap_or(name_b);
+        self.selected = 0;
+        self.category = None;
+        self.mode = AppMode::Normal;
+        self.update_visible_scripts();
+    }
 
-                    category_a.cmp(category_b).then_with(|| name_a.cmp(name_b))
-                });
-                indices
-            }
+    /// Select the currently highlighted workspace.
+    ///
+    /// A workspace that is a monorepo itself is drilled into instead, listing
+    /// its own workspaces.
+    pub fn select_current_workspace(&mut self) {
+        let index = self.workspace_selected;
+        let nested = index > 0
+            && self
+                .workspace_level()
+                .get(index - 1)
+                .is_some_and(Workspace::has_members);
+        if nested {
+            self.workspace_trail.push(index - 1);
+            self.workspace_selected = 0;
+        } else {
+            self.select_workspace(index);
         }
     }
 
-    // ==================== Column Management ====================
+    /// Go up from a nested monorepo in the workspace selector.
+    ///
+    /// Returns false if already at the top level.
+    pub fn workspace_level_up(&mut self) -> bool {
+        match self.workspace_trail.pop() {
+            Some(index) => {
+                self.workspace_selected = index + 1;
+                true
+            }
+            None => false,
+        }
+    }
 
-    /// Update the number of columns based on terminal width.
-    pub fn update_columns(&mut self, width: u16) {
-        self.columns = calculate_columns(width);
+    /// Go back to workspace selection from script view.
+    pub fn back_to_workspace_select(&mut self) {
+        if self.is_monorepo {
+            self.mode = AppMode::WorkspaceSelect;
+        }
     }
 
-    // ==================== Navigation ====================
+    // ==================== Search Across Workspaces ====================
 
-    /// Calculate the number of rows based on visible items and columns.
-    fn row_count(&self) -> usize {
-        let count = self.visible_indices.len();
-        if count == 0 || self.columns == 0 {
-            return 0;
+    /// Search the scripts of the root and every workspace at once.
+    pub fn enter_global_search(&mut self) {
+        if !self.is_monorepo {
+            return;
         }
-        (count + self.columns - 1) / self.columns
+        self.search_from_selector = self.mode == AppMode::WorkspaceSelect;
+        self.set_mode(AppMode::GlobalSearch {
+            query: String::new(),
+            selected: 0,
+        });
+        self.update_global_matches();
     }
 
-    /// Get the current row and column from the selected index.
-    fn current_position(&self) -> (usize, usize) {
-        let row = self.selected / self.columns;
-        let col = self.selected % self.columns;
-        (row, col)
+    /// Get the scripts matching the search across all workspaces.
+    pub fn global_matches(&self) -> &[GlobalMatch] {
+        &self.global_matches
     }
 
-    /// Move selection up by one row.
-    pub fn move_up(&mut self) {
-        if self.visible_indices.is_empty() || self.columns == 0 {
+    /// Find the scripts matching the search across all workspaces, best
+    /// first.
+    ///
+    /// Without a query every script is listed, the root's first.
+    fn update_global_matches(&mut self) {
+        let AppMode::GlobalSearch { query, selected } = &self.mode else {
             return;
-        }
+        };
+        let query = query.trim();
 
-        let (row, col) = self.current_position();
-        if row > 0 {
-            let new_index = (row - 1) * self.columns + col;
-            if new_index < self.visible_indices.len() {
-                self.selected = new_index;
+        let root = (
+            Vec::new(),
+            self.project_name.as_str(),
+            self.root_scripts.as_slice(),
+        );
+        let workspaces = workspace_paths(&self.workspaces, &[])
+            .into_iter()
+            .map(|(path, workspace)| (path, workspace.name(), workspace.scripts()));
+        let mut scored: Vec<(i64, GlobalMatch)> = Vec::new();
+        for (path, label, scripts) in std::iter::once(root).chain(workspaces) {
+            for script in scripts {
+                let score = if query.is_empty() {
+                    Some(0)
+                } else {
+                    crate::filter::match_score(query, script.name())
+                };
+                if let Some(score) = score {
+                    let found = GlobalMatch {
+                        workspace: path.clone(),
+                        label: label.to_string(),
+                        script: script.name().to_string(),
+                    };
+                    scored.push((score, found));
+                }
             }
         }
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        let selected = (*selected).min(scored.len().saturating_sub(1));
+        self.global_matches = scored.into_iter().map(|(_, found)| found).collect();
+        if let AppMode::GlobalSearch {
+            selected: current, ..
+        } = &mut self.mode
+        {
+            *current = selected;
+        }
     }
 
-    /// Move selection down by one row.
-    pub fn move_down(&mut self) {
-        if self.visible_indices.is_empty() || self.columns == 0 {
-            return;
+    /// Move the highlighted match in the search across all workspaces.
+    pub fn global_search_move(&mut self, down: bool) {
+        let last = self.global_matches.len().saturating_sub(1);
+        if let AppMode::GlobalSearch { selected, .. } = &mut self.mode {
+            *selected = if down {
+                (*selected + 1).min(last)
+            } else {
+                selected.saturating_sub(1)
+            };
         }
+    }
 
-        let (row, col) = self.current_position();
-        let new_index = (row + 1) * self.columns + col;
-
-        if new_index < self.visible_indices.len() {
-            self.selected = new_index;
-        } else {
-            // Try to go to last row, same column or last item
-            let last_row = self.row_count().saturating_sub(1);
-            if row < last_row {
-                // Go to last item if the target column doesn't exist in last row
-                self.selected = self.visible_indices.len().saturating_sub(1);
+    /// Show the workspace of the highlighted match, with its script
+    /// selected.
+    pub fn open_global_match(&mut self) {
+        let AppMode::GlobalSearch { selected, .. } = &self.mode else {
+            return;
+        };
+        let Some(found) = self.global_matches.get(*selected).cloned() else {
+            return;
+        };
+        self.global_matches.clear();
+        self.filter_text.clear();
+        let index = match found.workspace.split_last() {
+            Some((&last, parent)) => {
+                self.workspace_trail = parent.to_vec();
+                last + 1
             }
+            None => {
+                self.workspace_trail.clear();
+                0
+            }
+        };
+        self.workspace_selected = index;
+        self.select_workspace(index);
+
+        let scripts = self.scripts.as_slice();
+        if let Some(position) = self
+            .visible_indices
+            .iter()
+            .position(|&i| scripts[i].name() == found.script)
+        {
+            self.selected = position;
+            self.keep_selection_visible();
         }
     }
 
-    /// Move selection left by one column.
-    pub fn move_left(&mut self) {
-        if self.selected > 0 {
-            self.selected -= 1;
+    /// Close the search across all workspaces, going back to where it was
+    /// opened.
+    pub fn close_global_search(&mut self) {
+        self.global_matches.clear();
+        self.mode = if self.search_from_selector {
+            AppMode::WorkspaceSelect
+        } else {
+            AppMode::Normal
+        };
+    }
+
+    /// Move workspace selection up.
+    pub fn workspace_move_up(&mut self) {
+        if self.workspace_selected > 0 {
+            self.workspace_selected -= 1;
         }
     }
 
-    /// Move selection right by one column.
-    pub fn move_right(&mut self) {
-        if self.selected < self.visible_indices.len().saturating_sub(1) {
-            self.selected += 1;
+    /// Move workspace selection down.
+    pub fn workspace_move_down(&mut self) {
+        // +1 for the "root" option
+        let max_index = self.workspace_level().len();
+        if self.workspace_selected < max_index {
+            self.workspace_selected += 1;
         }
     }
 
-    /// Move selection to the first item.
-    pub fn move_to_first(&mut self) {
-        self.selected = 0;
+    /// Move workspace selection left (in grid).
+    pub fn workspace_move_left(&mut self) {
+        if self.workspace_selected > 0 {
+            self.workspace_selected -= 1;
+        }
     }
 
-    /// Move selection to the last item.
-    pub fn move_to_last(&mut self) {
-        self.selected = self.visible_indices.len().saturating_sub(1);
+    /// Move workspace selection right (in grid).
+    pub fn workspace_move_right(&mut self) {
+        let max_index = self.workspace_level().len();
+        if self.workspace_selected < max_index {
+            self.workspace_selected += 1;
+        }
     }
 
-    /// Select a script by number (1-9).
-    pub fn select_by_number(&mut self, num: usize) {
-        if num > 0 && num <= self.visible_indices.len() {
-            self.selected = num - 1;
+    /// Select workspace by number (1-9).
+    pub fn select_workspace_by_number(&mut self, num: usize) {
+        // num 1 = root (index 0), num 2 = first workspace (index 1), etc.
+        if num > 0 && num <= self.workspace_count() {
+            self.workspace_selected = num - 1;
+            self.select_current_workspace();
         }
     }
 
-    // ==================== Navigation Aliases (for compatibility) ====================
+    /// Yank the highlighted (or current) workspace directory and quit.
+    ///
+    /// In the workspace selector this uses the highlighted entry, otherwise
+    /// the workspace whose scripts are shown. The path is printed on exit.
+    pub fn yank_workspace_path(&mut self) {
+        let path = match self.mode {
+            AppMode::WorkspaceSelect => match self.workspace_selected {
+                0 => match self.workspace_at(&self.workspace_trail) {
+                    Some(ws) => ws.path().to_path_buf(),
+                    None => self.project_path.clone(),
+                },
+                idx => match self.workspace_level().get(idx - 1) {
+                    Some(ws) => ws.path().to_path_buf(),
+                    None => return,
+                },
+            },
+            _ => self.history_path().to_path_buf(),
+        };
 
-    /// Move selection up (alias for move_up).
-    pub fn select_prev(&mut self) {
-        self.move_up();
+        self.yanked_path = Some(path);
+        self.quit();
     }
 
-    /// Move selection down (alias for move_down).
-    pub fn select_next(&mut self) {
-        self.move_down();
+    /// Get the count of items in workspace selector (root + workspaces).
+    pub fn workspace_count(&self) -> usize {
+        self.workspace_level().len() + 1 // +1 for root
     }
 
-    /// Move to first item (alias for move_to_first).
-    pub fn select_first(&mut self) {
-        self.move_to_first();
-    }
+    // ==================== Errors ====================
 
-    /// Move to last item (alias for move_to_last).
-    pub fn select_last(&mut self) {
-        self.move_to_last();
+    /// Show the error overlay with recovery actions.
+    pub fn show_error(&mut self, message: impl Into<String>, actions: Vec<ErrorAction>) {
+        self.set_mode(AppMode::Error {
+            message: message.into(),
+            actions,
+        });
     }
 
-    // ==================== Actions ====================
+    /// Remember a run that failed so Retry can start it again.
+    pub fn set_failed_run(&mut self, run: ScriptRun) {
+        self.failed_run = Some(run);
+    }
 
-    /// Request the app to quit.
-    pub fn quit(&mut self) {
-        self.should_quit = true;
+    /// Get the error action that must be completed outside the TUI.
+    pub fn requested_action(&self) -> Option<ErrorAction> {
+        self.requested_action
     }
 
-    /// Run the currently selected script.
-    pub fn run_selected(&mut self) -> Option<ScriptRun> {
-        if let Some(script) = self.selected_script() {
-            let (workspace, workspace_path) = self.get_workspace_info();
-            let run = ScriptRun {
-                script: script.clone(),
-                args: None,
-                workspace,
-                workspace_path,
-            };
-            self.script_to_run = Some(run.clone());
-            self.should_quit = true;
-            Some(run)
-        } else {
-            None
+    /// Run the numbered (1-based) action of the error overlay.
+    ///
+    /// Returns false if there is no such action.
+    pub fn run_error_action(&mut self, num: usize) -> bool {
+        let action = match &self.mode {
+            AppMode::Error { actions, .. } => match num.checked_sub(1).and_then(|i| actions.get(i))
+            {
+                Some(&action) => action,
+                None => return false,
+            },
+            _ => return false,
+        };
+
+        match action {
+            ErrorAction::Retry => match self.failed_run.take() {
+                Some(run) => {
+                    self.script_to_run = Some(run);
+                    self.finish_run();
+                }
+                None => self.set_mode(AppMode::Normal),
+            },
+            ErrorAction::SwitchRunner => {
+                // Stay in the overlay so Retry can follow
+                self.runner = self.runner.next();
+                self.notify(
+                    MessageLevel::Info,
+                    format!("Runner switched to {}", self.runner),
+                );
+            }
+            ErrorAction::OpenConfig => {
+                self.requested_action = Some(ErrorAction::OpenConfig);
+                self.should_quit = true;
+            }
         }
+        true
     }
 
-    /// Get workspace info for script execution.
-    fn get_workspace_info(&self) -> (Option<String>, Option<PathBuf>) {
-        match &self.workspace_context {
-            WorkspaceContext::Root => (None, None),
-            WorkspaceContext::Workspace(idx) => {
-                if let Some(ws) = self.workspaces.get(*idx) {
-                    (Some(ws.name().to_string()), Some(ws.path().to_path_buf()))
-                } else {
-                    (None, None)
-                }
+    // ==================== Messages ====================
+
+    /// Show a transient message in the footer.
+    pub fn notify(&mut self, level: MessageLevel, text: impl Into<String>) {
+        self.messages.push(Message::new(level, text));
+    }
+
+    /// Get the footer message currently shown, if any.
+    pub fn current_message(&self) -> Option<&Message> {
+        self.messages.current()
+    }
+
+    /// Expire footer messages whose time is up.
+    pub fn tick_messages(&mut self, now: Instant) {
+        self.messages.tick(now);
+    }
+
+    // ==================== Output Pane ====================
+
+    /// Get the script running in the output pane.
+    pub fn pane_run(&self) -> Option<&PaneRun> {
+        self.pane.as_ref()
+    }
+
+    /// Get the runs finished in the output pane.
+    pub fn finished_runs(&self) -> &[(ScriptRun, ExecutionResult)] {
+        &self.finished_runs
+    }
+
+    /// Run the selected script in the output pane, without leaving the TUI.
+    ///
+    /// Scripts that need confirmation run outside the TUI instead, where
+    /// they can be confirmed.
+    pub fn run_in_pane(&mut self) {
+        let Some(script) = self.selected_script().cloned() else {
+            return;
+        };
+        // Confirmed scripts and groups run after exit instead
+        if self.config.scripts.needs_confirm(script.name()) || script.group().is_some() {
+            self.run_selected();
+            return;
+        }
+        let run = self.new_run(script, None);
+        if self.read_only {
+            self.show_commands(&[run]);
+            return;
+        }
+        // So do scripts that conflict with a running one, to offer stopping it
+        if self.conflicts_with_running_job(&run) {
+            self.run_selected();
+            return;
+        }
+        if let Some(command) = self.missing_install() {
+            self.install_pane_run = Some(run);
+            self.set_mode(AppMode::InstallPrompt { command });
+            return;
+        }
+        self.start_pane_run(run);
+    }
+
+    /// Get the command installing the project's dependencies if they're
+    /// missing and weren't asked about yet.
+    fn missing_install(&self) -> Option<String> {
+        if self.install_checked || !self.config.general.check_install() {
+            return None;
+        }
+        let runner = install_runner(self.runner, &self.project_path);
+        let command = runner.install_command()?;
+        needs_install(&self.project_path, runner).then(|| command.join(" "))
+    }
+
+    /// Install the missing dependencies, then run the picked scripts after
+    /// exit.
+    ///
+    /// A script picked for the output pane runs after exit too.
+    pub fn install_first(&mut self) {
+        if let Some(run) = self.install_pane_run.take() {
+            self.script_to_run = Some(run);
+        }
+        self.install_checked = true;
+        self.install_first = true;
+        self.set_mode(AppMode::Normal);
+        self.finish_run();
+    }
+
+    /// Run the picked scripts without installing the missing dependencies.
+    pub fn skip_install(&mut self) {
+        self.install_checked = true;
+        self.set_mode(AppMode::Normal);
+        match self.install_pane_run.take() {
+            Some(run) => self.start_pane_run(run),
+            None => self.finish_run(),
+        }
+    }
+
+    /// Check if missing dependencies were asked about in the TUI.
+    pub fn is_install_checked(&self) -> bool {
+        self.install_checked
+    }
+
+    /// Check if missing dependencies are installed before the scripts run.
+    pub fn is_install_first(&self) -> bool {
+        self.install_first
+    }
+
+    /// Check if a script shares a `[concurrency]` group with one already
+    /// running, other than the pane's own run it would replace.
+    fn conflicts_with_running_job(&self, run: &ScriptRun) -> bool {
+        let concurrency = &self.config.concurrency;
+        if concurrency.groups.is_empty() {
+            return false;
+        }
+        let pane_pid = self.pane.as_ref().map(|pane| pane.process.pid());
+        jobs::running_jobs(run.run_dir(&self.project_path))
+            .iter()
+            .filter(|job| Some(job.pid) != pane_pid)
+            .any(|job| {
+                concurrency
+                    .shared_group(&job.script, run.script.name())
+                    .is_some()
+            })
+    }
+
+    /// Run the script in the output pane again.
+    pub fn rerun_pane(&mut self) {
+        if let Some(run) = self.pane.as_ref().map(|pane| pane.run.clone()) {
+            self.start_pane_run(run);
+        }
+    }
+
+    /// Start a run in the output pane, replacing the current one.
+    fn start_pane_run(&mut self, run: ScriptRun) {
+        self.close_pane();
+
+        let args: Vec<String> = run.args.as_deref().map(split_args).unwrap_or_default();
+
+        match spawn_streamed(
+            run.script.runner_or(self.runner),
+            run.workspace.as_deref(),
+            run.script.name(),
+            &args,
+            run.run_dir(&self.project_path),
+            &self.config.limits_for(run.script.name()),
+            OutputFilter::new(&self.config.output),
+            &self.run_context,
+        ) {
+            Ok(process) => {
+                let history_path = run
+                    .workspace_path
+                    .clone()
+                    .unwrap_or_else(|| self.project_path.clone());
+                self.history
+                    .record_run(&history_path, run.script.name(), run.args.clone());
+
+                self.pane = Some(PaneRun {
+                    run,
+                    process,
+                    lines: Vec::new(),
+                    scroll: 0,
+                    reported: false,
+                });
+                self.mode = AppMode::Running;
+            }
+            Err(err) => {
+                self.failed_run = Some(run);
+                self.show_error(
+                    format!("{err:#}"),
+                    vec![
+                        ErrorAction::Retry,
+                        ErrorAction::SwitchRunner,
+                        ErrorAction::OpenConfig,
+                    ],
+                );
             }
         }
     }
 
-    /// Run a script by number (1-9).
-    pub fn run_numbered(&mut self, num: usize) -> Option<ScriptRun> {
-        if num > 0 && num <= self.visible_indices.len() {
-            self.selected = num - 1;
-            self.run_selected()
-        } else {
-            None
+    /// Collect new output from the script in the output pane.
+    pub fn poll_pane(&mut self) {
+        self.collect_pane_output(false);
+    }
+
+    /// Collect new output from the output pane, telling the terminal when
+    /// the script finished unless it was stopped.
+    fn collect_pane_output(&mut self, stopped: bool) {
+        let Some(pane) = self.pane.as_mut() else {
+            return;
+        };
+
+        let added = pane.lines.len();
+        for line in pane.process.poll() {
+            // The count of a run of hidden lines goes up in place
+            match pane.lines.last_mut() {
+                Some(last) if last.is_hidden() && line.is_hidden() => *last = line,
+                _ => pane.lines.push(line),
+            }
+        }
+        if pane.scroll > 0 {
+            // Keep a scrolled view on the same lines
+            pane.scroll += pane.lines.len() - added;
+        }
+        if pane.lines.len() > MAX_OUTPUT_LINES {
+            let excess = pane.lines.len() - MAX_OUTPUT_LINES;
+            pane.lines.drain(..excess);
+        }
+        pane.scroll = pane.scroll.min(pane.lines.len());
+
+        if let (Some(status), false) = (pane.process.status(), pane.reported) {
+            pane.reported = true;
+            if !stopped {
+                send_notice(
+                    self.config.progress.notify,
+                    &pane.run.to_string(),
+                    status.success(),
+                    pane.process.duration(),
+                );
+            }
+            self.finished_runs.push((
+                pane.run.clone(),
+                ExecutionResult {
+                    status,
+                    command: pane.process.command().to_string(),
+                    duration: pane.process.duration(),
+                    timed_out: pane.process.timed_out(),
+                },
+            ));
         }
     }
 
-    /// Run a script by number (alias for run_numbered, for compatibility).
-    pub fn run_by_number(&mut self, num: usize) {
-        self.run_numbered(num);
+    /// Stop the script in the output pane if it is still running.
+    pub fn stop_pane(&mut self) {
+        if let Some(pane) = self.pane.as_mut() {
+            pane.process.kill();
+        }
+        self.collect_pane_output(true);
     }
 
-    /// Run the selected script with arguments.
-    pub fn run_with_args(&mut self, args: String) -> Option<ScriptRun> {
-        if let Some(script) = self.selected_script() {
-            let (workspace, workspace_path) = self.get_workspace_info();
-            let run = ScriptRun {
-                script: script.clone(),
-                args: if args.is_empty() { None } else { Some(args) },
-                workspace,
-                workspace_path,
-            };
-            self.script_to_run = Some(run.clone());
-            self.should_quit = true;
-            Some(run)
-        } else {
-            None
+    /// Stop the script in the output pane and go back to the script list.
+    pub fn close_pane(&mut self) {
+        self.stop_pane();
+        self.pane = None;
+        if self.mode == AppMode::Running {
+            self.mode = AppMode::Normal;
         }
     }
 
-    /// Toggle selection of current item in multi-select mode.
-    pub fn toggle_current_selection(&mut self) {
-        if let AppMode::MultiSelect { ref mut selected } = self.mode {
-            if selected.contains(&self.selected) {
-                selected.remove(&self.selected);
+    /// Scroll the output pane up (towards older output).
+    pub fn pane_scroll_up(&mut self, lines: usize) {
+        if let Some(pane) = self.pane.as_mut() {
+            pane.scroll = (pane.scroll + lines).min(pane.lines.len());
+        }
+    }
+
+    /// Scroll the output pane down (towards newer output).
+    pub fn pane_scroll_down(&mut self, lines: usize) {
+        if let Some(pane) = self.pane.as_mut() {
+            pane.scroll = pane.scroll.saturating_sub(lines);
+        }
+    }
+
+    /// Scroll the output pane to the first line.
+    pub fn pane_scroll_to_top(&mut self) {
+        if let Some(pane) = self.pane.as_mut() {
+            pane.scroll = pane.lines.len();
+        }
+    }
+
+    /// Scroll the output pane to the end and follow new output.
+    pub fn pane_scroll_to_bottom(&mut self) {
+        if let Some(pane) = self.pane.as_mut() {
+            pane.scroll = 0;
+        }
+    }
+
+    /// Get the number of lines to scroll for a page in the output pane.
+    pub fn pane_page_size(&self) -> usize {
+        self.viewport_rows.max(1)
+    }
+
+    // ==================== Category Summary ====================
+
+    /// Get script counts per category, largest first.
+    pub fn categories(&self) -> Vec<(&str, usize)> {
+        self.scripts.categories()
+    }
+
+    /// Get the selected row in the category summary (0 = all scripts).
+    pub fn category_selected(&self) -> usize {
+        self.category_selected
+    }
+
+    /// Toggle the category summary.
+    ///
+    /// Opening it highlights the current category.
+    pub fn toggle_categories(&mut self) {
+        if self.mode == AppMode::Categories {
+            self.mode = AppMode::Normal;
+            return;
+        }
+
+        self.category_selected = self
+            .category
+            .as_deref()
+            .and_then(|current| self.categories().iter().position(|(c, _)| *c == current))
+            .map_or(0, |i| i + 1);
+        self.mode = AppMode::Categories;
+    }
+
+    /// Move the category selection up.
+    pub fn category_move_up(&mut self) {
+        self.category_selected = self.category_selected.saturating_sub(1);
+    }
+
+    /// Move the category selection down.
+    pub fn category_move_down(&mut self) {
+        // +1 for the "all scripts" row
+        if self.category_selected < self.categories().len() {
+            self.category_selected += 1;
+        }
+    }
+
+    /// Narrow the scripts to a category row (0 = all scripts).
+    pub fn select_category(&mut self, index: usize) {
+        self.category = match index {
+            0 => None,
+            i => match self.categories().get(i - 1) {
+                Some((category, _)) => Some(category.to_string()),
+                None => return,
+            },
+        };
+
+        self.selected = 0;
+        self.mode = AppMode::Normal;
+        self.update_visible_scripts();
+    }
+
+    /// Narrow the scripts to the highlighted category.
+    pub fn select_current_category(&mut self) {
+        self.select_category(self.category_selected);
+    }
+
+    /// Select a category row by number (1 = all scripts).
+    pub fn select_category_by_number(&mut self, num: usize) {
+        if num > 0 && num <= self.categories().len() + 1 {
+            self.select_category(num - 1);
+        }
+    }
+
+    /// Show all scripts again.
+    pub fn clear_category(&mut self) {
+        self.category = None;
+        self.update_visible_scripts();
+    }
+
+    // ==================== Category Groups ====================
+
+    /// Check if the grid shows category groups.
+    ///
+    /// Groups show in category sort, unless filtering or narrowed to a
+    /// category.
+    pub fn is_grouped(&self) -> bool {
+        self.sort_mode == SortMode::Category
+            && self.filter_text.is_empty()
+            && self.category.is_none()
+    }
+
+    /// Lay out the visible scripts in category groups, if grouped.
+    pub fn group_layout(&self) -> Option<GroupLayout> {
+        if !self.is_grouped() {
+            return None;
+        }
+        let scripts = self.scripts.as_slice();
+        let categories: Vec<&str> = self
+            .visible_indices
+            .iter()
+            .map(|&i| scripts[i].category())
+            .collect();
+        let count = |category: &str| scripts.iter().filter(|s| s.category() == category).count();
+        Some(GroupLayout::new(
+            &categories,
+            self.columns,
+            &self.collapsed_groups,
+            count,
+        ))
+    }
+
+    /// Check if a visible slot is the header of a collapsed group.
+    pub fn is_group_header(&self, index: usize) -> bool {
+        self.is_grouped()
+            && self
+                .visible_indices
+                .get(index)
+                .and_then(|&i| self.scripts.iter().nth(i))
+                .is_some_and(|s| self.collapsed_groups.contains(s.category()))
+    }
+
+    /// Collapse the selected script's group, or expand the selected header.
+    pub fn toggle_group(&mut self) {
+        if !self.is_grouped() {
+            let hint = if self.sort_mode == SortMode::Category {
+                "Clear the filter to collapse groups"
             } else {
-                selected.insert(self.selected);
-            }
+                "Sort by category (s) to group scripts"
+            };
+            self.notify(MessageLevel::Info, hint);
+            return;
+        }
+        let Some(category) = self
+            .visible_indices
+            .get(self.selected)
+            .and_then(|&i| self.scripts.iter().nth(i))
+            .map(|s| s.category().to_string())
+        else {
+            return;
+        };
+
+        if !self.collapsed_groups.remove(&category) {
+            self.collapsed_groups.insert(category.clone());
+        }
+        self.collapsed_changed = true;
+        self.update_visible_scripts();
+
+        // Keep the cursor on the group: its header or its first script
+        let scripts = self.scripts.as_slice();
+        if let Some(index) = self
+            .visible_indices
+            .iter()
+            .position(|&i| scripts[i].category() == category)
+        {
+            self.selected = index;
         }
+        self.keep_selection_visible();
     }
 
-    /// Get selected indices in multi-select mode.
-    pub fn multi_selected_indices(&self) -> Option<&HashSet<usize>> {
-        if let AppMode::MultiSelect { ref selected } = self.mode {
-            Some(selected)
-        } else {
-            None
+    /// Get the collapsed groups, if any were collapsed or expanded this session.
+    pub fn changed_collapsed_groups(&self) -> Option<Vec<String>> {
+        self.collapsed_changed
+            .then(|| self.collapsed_groups.iter().cloned().collect())
+    }
+
+    // ==================== Filter Management ====================
+
+    /// Set the filter text.
+    pub fn set_filter(&mut self, text: String) {
+        self.filter_text = text.clone();
+        self.mode = AppMode::Filter { query: text };
+        self.reset_input_cursor();
+        self.update_visible_scripts();
+    }
+
+    /// Append a character to the filter text.
+    pub fn push_filter_char(&mut self, c: char) {
+        self.filter_text.push(c);
+        self.update_visible_scripts();
+    }
+
+    /// Remove the last character from the filter text.
+    pub fn pop_filter_char(&mut self) {
+        self.filter_text.pop();
+        self.update_visible_scripts();
+    }
+
+    /// Clear the filter text.
+    pub fn clear_filter(&mut self) {
+        self.filter_text.clear();
+        self.update_visible_scripts();
+    }
+
+    /// Get recent filter queries for this project, most recent first.
+    pub fn filter_history(&self) -> &[String] {
+        self.history.get_filters(&self.project_path)
+    }
+
+    /// Recall the next older filter query from history.
+    ///
+    /// Recall starts only from an empty filter, so Up keeps navigating the
+    /// list once something has been typed. Returns false if nothing was
+    /// recalled.
+    pub fn recall_older_filter(&mut self) -> bool {
+        if !matches!(self.mode, AppMode::Filter { .. }) {
+            return false;
         }
+
+        let next = match self.history_index {
+            Some(index) => index + 1,
+            None if self.filter_text.is_empty() => 0,
+            None => return false,
+        };
+        let Some(query) = self.filter_history().get(next).cloned() else {
+            // Already at the oldest entry; stay there
+            return self.history_index.is_some();
+        };
+
+        self.set_filter(query);
+        self.history_index = Some(next);
+        true
     }
 
-    /// Run all selected scripts in multi-select mode.
-    pub fn run_multi_selected(&mut self) -> Vec<ScriptRun> {
-        let (workspace, workspace_path) = self.get_workspace_info();
-        let runs: Vec<ScriptRun> = if let AppMode::MultiSelect { ref selected } = self.mode {
-            selected
-                .iter()
-                .filter_map(|&idx| {
-                    self.get_visible_script(idx).map(|script| ScriptRun {
-                        script: script.clone(),
-                        args: None,
-                        workspace: workspace.clone(),
-                        workspace_path: workspace_path.clone(),
-                    })
-                })
-                .collect()
-        } else {
-            vec![]
+    /// Recall the next newer filter query, or the empty filter past the newest.
+    ///
+    /// Returns false if not currently recalling from history.
+    pub fn recall_newer_filter(&mut self) -> bool {
+        let Some(index) = self.history_index else {
+            return false;
         };
 
-        if !runs.is_empty() {
-            // Set first script to run, the rest would need to be handled by the caller
-            self.script_to_run = runs.first().cloned();
-            self.should_quit = true;
+        if index == 0 {
+            self.set_filter(String::new());
+            self.history_index = None;
+        } else {
+            let query = self.filter_history()[index - 1].clone();
+            self.set_filter(query);
+            self.history_index = Some(index - 1);
         }
+        true
+    }
 
-        runs
+    /// Get the filter query that led to the script being run, if any.
+    pub fn filter_for_run(&self) -> Option<&str> {
+        if self.script_to_run.is_some() && !self.filter_text.trim().is_empty() {
+            Some(&self.filter_text)
+        } else {
+            None
+        }
     }
-}
 
-/// Calculate grid columns based on terminal width.
-pub fn calculate_columns(width: u16) -> usize {
-    if width < 60 {
-        1
-    } else if width < 90 {
-        2
-    } else if width < 120 {
-        3
-    } else if width < 160 {
-        4
-    } else {
-        5
+    // ==================== Sort Management ====================
+
+    /// Cycle through sort modes: Recent -> Alphabetical -> Category -> Recent.
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort_mode = match self.sort_mode {
+            SortMode::Recent => SortMode::Alpha,
+            SortMode::Alpha => SortMode::Category,
+            SortMode::Category => SortMode::Recent,
+        };
+        self.update_visible_scripts();
     }
-}
 
-/// Calculate column width for the scripts grid.
-pub fn calculate_column_width(total_width: u16, columns: usize) -> u16 {
-    if columns == 0 {
-        return total_width;
+    /// Set the sort mode.
+    pub fn set_sort_mode(&mut self, mode: SortMode) {
+        self.sort_mode = mode;
+        self.update_visible_scripts();
     }
-    let padding = 2; // Left and right padding
-    let available = total_width.saturating_sub(padding * 2);
+
+    // ==================== Visibility Update ====================
+
+    /// Update the visible scripts based on current filter and sort mode.
+    pub fn update_visible_scripts(&mut self) {
+        // Step 1: Filter
+        let started = Instant::now();
+        let mut filtered_indices: Vec<usize> = if self.filter_text.is_empty() {
+            (0..self.scripts.len()).collect()
+        } else {
+            let matches = crate::filter::filter_scripts_with_config(
+                &self.filter_text,
+                self.scripts.as_slice(),
+                &self.config.filter,
+            );
+
+            matches.into_iter().map(|(idx, _score)| idx).collect()
+        };
+
+        if let Some(category) = &self.category {
+            let scripts = self.scripts.as_slice();
+            filtered_indices.retain(|&i| scripts[i].category() == category);
+        }
+
+        self.profile.record_match(started.elapsed());
+
+        // Step 2: Sort
+        let started = Instant::now();
+        self.visible_indices = self.sort_indices(filtered_indices);
+        if self.filter_text.is_empty() {
+            // Root scripts of the monorepo come after the package's own
+            let scripts = self.scripts.as_slice();
+            let (mut own, parent): (Vec<usize>, Vec<usize>) = self
+                .visible_indices
+                .iter()
+                .partition(|&&i| scripts[i].parent().is_none());
+            own.extend(parent);
+            self.visible_indices = own;
+        }
+        self.profile.record_sort(started.elapsed());
+
+        // Step 3: Pin favorites to the top, unless filtering or grouped
+        if self.is_grouped() {
+            // Collapsed groups keep one slot, for their header
+            let scripts = self.scripts.as_slice();
+            let mut headers = HashSet::new();
+            self.visible_indices.retain(|&i| {
+                let category = scripts[i].category();
+                !self.collapsed_groups.contains(category) || headers.insert(category)
+            });
+        } else if self.filter_text.is_empty() {
+            let scripts = self.scripts.as_slice();
+            let (mut pinned, rest): (Vec<usize>, Vec<usize>) = self
+                .visible_indices
+                .iter()
+                .partition(|&&i| self.is_pinned(scripts[i].name()));
+            pinned.extend(rest);
+            self.visible_indices = pinned;
+        }
+
+        // Adjust selection if needed
+        if self.selected >= self.visible_indices.len() {
+            self.selected = self.visible_indices.len().saturating_sub(1);
+        }
+
+        self.keep_selection_visible();
+    }
+
+    /// Sort indices based on current sort mode.
+    fn sort_indices(&self, mut indices: Vec<usize>) -> Vec<usize> {
+        match self.sort_mode {
+            SortMode::Recent => {
+                // Sort by history score (recent/frequent first)
+                // Clone scripts so we can pass them to get_sorted_by_recent
+                let scripts_owned: Vec<Script> = indices
+                    .iter()
+                    .filter_map(|&i| self.scripts.iter().nth(i).cloned())
+                    .collect();
+
+                let sorted = self.history.get_sorted_by_recent_with_boost(
+                    self.history_path(),
+                    &scripts_owned,
+                    |script| {
+                        if self.git_activity.matches(script) {
+                            GIT_ACTIVITY_BOOST
+                        } else {
+                            0.0
+                        }
+                    },
+                );
+
+                // Map back to indices
+                sorted
+                    .iter()
+                    .filter_map(|s| {
+                        self.scripts
+                            .iter()
+                            .position(|script| script.name() == s.name())
+                    })
+                    .filter(|i| indices.contains(i))
+                    .collect()
+            }
+            SortMode::Alpha => {
+                // Sort alphabetically by name
+                indices.sort_by(|&a, &b| {
+                    let name_a = self.scripts.iter().nth(a).map(|s| s.name()).unwrap_or("");
+                    let name_b = self.scripts.iter().nth(b).map(|s| s.name()).unwrap_or("");
+                    self.compare_names(name_a, name_b)
+                });
+                indices
+            }
+            SortMode::Category => {
+                // Sort by category (prefix before colon) then alphabetically
+                let scripts = self.scripts.as_slice();
+                indices.sort_by(|&a, &b| {
+                    let (a, b) = (&scripts[a], &scripts[b]);
+                    self.compare_names(a.category(), b.category())
+                        .then_with(|| self.compare_names(a.name(), b.name()))
+                });
+                indices
+            }
+        }
+    }
+
+    /// Compare two script names using the configured ordering.
+    fn compare_names(&self, a: &str, b: &str) -> std::cmp::Ordering {
+        if self.config.general.sort_natural() {
+            natural_cmp(a, b)
+        } else {
+            a.cmp(b)
+        }
+    }
+
+    // ==================== Column Management ====================
+
+    /// Update the number of columns based on terminal width.
+    ///
+    /// The scroll anchor is realigned to the start of its row so the same
+    /// scripts stay on screen when the column count changes.
+    pub fn update_columns(&mut self, width: u16) {
+        let columns = crate::tui::layout::calculate_columns_for_density(
+            width,
+            self.config.appearance.density,
+        );
+
+        if columns != self.columns {
+            let anchor = self.scroll_offset;
+            self.columns = columns;
+            // Grouped, the anchor is a line rather than a script
+            if !self.is_grouped() {
+                self.scroll_offset = (anchor / columns) * columns;
+            }
+            self.keep_selection_visible();
+        }
+    }
+
+    /// Handle a terminal resize.
+    ///
+    /// Preserves the mode (including in-flight filter or args input), the
+    /// selected script and the scroll anchor.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let area = ratatui::layout::Rect::new(0, 0, width, height);
+        let layout = crate::tui::layout::MainLayout::with_config(area, &self.config.appearance);
+        let row_height = if layout.degraded {
+            1
+        } else {
+            self.config.appearance.density.row_height()
+        };
+
+        self.viewport_rows = (layout.scripts.height / row_height) as usize;
+        self.update_columns(width);
+        self.keep_selection_visible();
+    }
+
+    /// Adjust the scroll offset so the selected script is on screen.
+    fn keep_selection_visible(&mut self) {
+        if self.columns == 0 {
+            return;
+        }
+        if let Some(layout) = self.group_layout() {
+            self.scroll_offset =
+                layout.scroll(self.scroll_offset, self.selected, self.viewport_rows);
+            return;
+        }
+
+        let selected_row = self.selected / self.columns;
+        let mut first_row = self.scroll_offset / self.columns;
+
+        if selected_row < first_row {
+            first_row = selected_row;
+        } else if self.viewport_rows > 0 && selected_row >= first_row + self.viewport_rows {
+            first_row = selected_row + 1 - self.viewport_rows;
+        }
+
+        self.scroll_offset = first_row * self.columns;
+    }
+
+    // ==================== Navigation ====================
+
+    /// Calculate the number of rows based on visible items and columns.
+    fn row_count(&self) -> usize {
+        let count = self.visible_indices.len();
+        if count == 0 || self.columns == 0 {
+            return 0;
+        }
+        (count + self.columns - 1) / self.columns
+    }
+
+    /// Get the current row and column from the selected index.
+    fn current_position(&self) -> (usize, usize) {
+        let row = self.selected / self.columns;
+        let col = self.selected % self.columns;
+        (row, col)
+    }
+
+    /// Move selection up by one row.
+    pub fn move_up(&mut self) {
+        if self.visible_indices.is_empty() || self.columns == 0 {
+            return;
+        }
+
+        if let Some(layout) = self.group_layout() {
+            if let Some(index) = layout.slot_above(self.selected) {
+                self.selected = index;
+            }
+            self.keep_selection_visible();
+            return;
+        }
+
+        let (row, col) = self.current_position();
+        if row > 0 {
+            let new_index = (row - 1) * self.columns + col;
+            if new_index < self.visible_indices.len() {
+                self.selected = new_index;
+            }
+        }
+
+        self.keep_selection_visible();
+    }
+
+    /// Move selection down by one row.
+    pub fn move_down(&mut self) {
+        if self.visible_indices.is_empty() || self.columns == 0 {
+            return;
+        }
+
+        if let Some(layout) = self.group_layout() {
+            if let Some(index) = layout.slot_below(self.selected) {
+                self.selected = index;
+            }
+            self.keep_selection_visible();
+            return;
+        }
+
+        let (row, col) = self.current_position();
+        let new_index = (row + 1) * self.columns + col;
+
+        if new_index < self.visible_indices.len() {
+            self.selected = new_index;
+        } else {
+            // Try to go to last row, same column or last item
+            let last_row = self.row_count().saturating_sub(1);
+            if row < last_row {
+                // Go to last item if the target column doesn't exist in last row
+                self.selected = self.visible_indices.len().saturating_sub(1);
+            }
+        }
+
+        self.keep_selection_visible();
+    }
+
+    /// Move selection left by one column.
+    pub fn move_left(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
+
+        self.keep_selection_visible();
+    }
+
+    /// Move selection right by one column.
+    pub fn move_right(&mut self) {
+        if self.selected < self.visible_indices.len().saturating_sub(1) {
+            self.selected += 1;
+        }
+
+        self.keep_selection_visible();
+    }
+
+    /// Move selection to the first item.
+    pub fn move_to_first(&mut self) {
+        self.selected = 0;
+        self.keep_selection_visible();
+    }
+
+    /// Move selection to the last item.
+    pub fn move_to_last(&mut self) {
+        self.selected = self.visible_indices.len().saturating_sub(1);
+        self.keep_selection_visible();
+    }
+
+    /// Select a script by number (1-9).
+    pub fn select_by_number(&mut self, num: usize) {
+        if num > 0 && num <= self.visible_indices.len() {
+            self.selected = num - 1;
+        }
+
+        self.keep_selection_visible();
+    }
+
+    // ==================== Navigation Aliases (for compatibility) ====================
+
+    /// Move selection up (alias for move_up).
+    pub fn select_prev(&mut self) {
+        self.move_up();
+    }
+
+    /// Move selection down (alias for move_down).
+    pub fn select_next(&mut self) {
+        self.move_down();
+    }
+
+    /// Move to first item (alias for move_to_first).
+    pub fn select_first(&mut self) {
+        self.move_to_first();
+    }
+
+    /// Move to last item (alias for move_to_last).
+    pub fn select_last(&mut self) {
+        self.move_to_last();
+    }
+
+    // ==================== Actions ====================
+
+    /// Request the app to quit.
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+
+    /// Handle Esc in normal mode, undoing one step at a time.
+    ///
+    /// Clears the filter first, then the category, then returns to workspace
+    /// selection in a monorepo, and finally quits when `general.esc_quits`
+    /// is enabled.
+    pub fn escape(&mut self) {
+        if !self.filter_text.is_empty() {
+            self.clear_filter();
+        } else if self.category.is_some() {
+            self.clear_category();
+        } else if self.is_monorepo {
+            self.back_to_workspace_select();
+        } else if self.config.general.esc_quits() {
+            self.quit();
+        }
+    }
+
+    /// Run the currently selected script.
+    pub fn run_selected(&mut self) -> Option<ScriptRun> {
+        // Enter on a collapsed group expands it
+        if self.is_group_header(self.selected) {
+            self.toggle_group();
+            return None;
+        }
+        if let Some(script) = self.selected_script() {
+            let run = self.new_run(script.clone(), None);
+            if self.read_only {
+                self.show_commands(&[run]);
+                return None;
+            }
+            self.script_to_run = Some(run.clone());
+            self.finish_run();
+            Some(run)
+        } else {
+            None
+        }
+    }
+
+    /// Create a run of a script in the current workspace context.
+    ///
+    /// Aliases run the script they stand for, with their arguments first.
+    fn new_run(&self, script: Script, args: Option<String>) -> ScriptRun {
+        let target = script
+            .alias()
+            .and_then(|alias| Some((self.scripts.get(&alias.script)?, alias)));
+        let (script, args) = match target {
+            Some((target, alias)) => (target.clone(), alias.args_with(args.as_deref())),
+            None => (script, args),
+        };
+        match self.current_workspace() {
+            Some(workspace) => ScriptRun::in_workspace(script, args, workspace),
+            None if script.parent().is_some() => ScriptRun::from_parent(script, args),
+            None => ScriptRun::new(script, args),
+        }
+    }
+
+    /// Run the selected script in every workspace that defines it, one
+    /// after another, with a summary at the end.
+    pub fn run_in_all_workspaces(&mut self) {
+        if !self.is_monorepo {
+            return;
+        }
+        let Some(name) = self.selected_script().map(|s| s.name().to_string()) else {
+            return;
+        };
+
+        let runs: Vec<ScriptRun> = all_workspaces(&self.workspaces)
+            .into_iter()
+            .filter_map(|workspace| {
+                let script = workspace.scripts().iter().find(|s| s.name() == name)?;
+                Some(ScriptRun::in_workspace(script.clone(), None, workspace))
+            })
+            .collect();
+        if runs.is_empty() {
+            self.notify(MessageLevel::Warn, format!("No workspace defines {name}"));
+            return;
+        }
+
+        if self.read_only {
+            self.show_commands(&runs);
+            return;
+        }
+        self.script_to_run = runs.first().cloned();
+        self.multi_run = runs;
+        self.all_workspaces_run = true;
+        self.finish_run();
+    }
+
+    /// Quit to run the picked scripts, offering to install missing
+    /// dependencies and asking first if any of them matches
+    /// `scripts.confirm`.
+    fn finish_run(&mut self) {
+        if let Some(command) = self.missing_install() {
+            self.set_mode(AppMode::InstallPrompt { command });
+            return;
+        }
+        let names = self.names_to_confirm();
+        if names.is_empty() {
+            self.should_quit = true;
+        } else {
+            self.set_mode(AppMode::ConfirmRun { names });
+        }
+    }
+
+    /// Get the picked scripts that ask for confirmation, group members
+    /// included.
+    fn names_to_confirm(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for run in self.scripts_to_run() {
+            let members = run.script.group().map_or(&[][..], |g| &g.scripts[..]);
+            let run_names =
+                std::iter::once(run.script.name()).chain(members.iter().map(String::as_str));
+            for name in run_names {
+                if self.config.scripts.needs_confirm(name) && !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names
+    }
+
+    /// Run the scripts waiting for confirmation.
+    pub fn confirm_run(&mut self) {
+        self.run_confirmed = true;
+        self.should_quit = true;
+        self.set_mode(AppMode::Normal);
+    }
+
+    /// Drop the scripts waiting for confirmation and go back to the list.
+    pub fn cancel_run(&mut self) {
+        self.script_to_run = None;
+        self.install_pane_run = None;
+        self.multi_run.clear();
+        self.all_workspaces_run = false;
+        self.watch_run = false;
+        self.set_mode(AppMode::Normal);
+        self.notify(MessageLevel::Info, "Cancelled");
+    }
+
+    /// Check if the scripts to run were confirmed in the TUI.
+    pub fn is_run_confirmed(&self) -> bool {
+        self.run_confirmed
+    }
+
+    /// Check if the scripts to run are one script in every workspace.
+    pub fn is_all_workspaces_run(&self) -> bool {
+        self.all_workspaces_run
+    }
+
+    /// Run the selected script, then run it again whenever files matching
+    /// `watch.patterns` change.
+    pub fn watch_selected(&mut self) {
+        if self.run_selected().is_some() {
+            self.watch_run = true;
+        }
+    }
+
+    /// Check if the script to run is rerun on file changes.
+    pub fn is_watch_run(&self) -> bool {
+        self.watch_run
+    }
+
+    /// Run a script by number (1-9).
+    pub fn run_numbered(&mut self, num: usize) -> Option<ScriptRun> {
+        if num > 0 && num <= self.visible_indices.len() {
+            self.selected = num - 1;
+            self.run_selected()
+        } else {
+            None
+        }
+    }
+
+    /// Get the number typed so far for multi-digit quick select.
+    pub fn pending_number(&self) -> Option<usize> {
+        self.pending_number
+    }
+
+    /// Discard any partially typed quick-select number.
+    pub fn clear_pending_number(&mut self) {
+        self.pending_number = None;
+    }
+
+    /// Get the keymap preset with the user's key bindings.
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Give the actions in the key conflicts overlay back their default
+    /// keys for this session.
+    pub fn restore_default_keys(&mut self) {
+        let AppMode::KeyConflicts { actions } = &self.mode else {
+            return;
+        };
+        let actions = actions.clone();
+        self.keymap.restore_defaults(&actions);
+        let names: Vec<&str> = actions.iter().map(|a| a.name()).collect();
+        self.notify(
+            MessageLevel::Info,
+            format!("Default keys restored for {}", names.join(", ")),
+        );
+        self.close_key_conflicts();
+    }
+
+    /// Open the config to fix the key bindings, then restart the TUI.
+    pub fn edit_key_bindings(&mut self) {
+        self.requested_action = Some(ErrorAction::OpenConfig);
+        self.should_quit = true;
+    }
+
+    /// Close the key conflicts overlay, leaving the bindings as they are.
+    pub fn close_key_conflicts(&mut self) {
+        self.mode = if self.is_monorepo {
+            AppMode::WorkspaceSelect
+        } else {
+            AppMode::Normal
+        };
+    }
+
+    /// Get the keys of a chord typed so far.
+    pub fn pending_keys(&self) -> &[KeyEvent] {
+        &self.pending_keys
+    }
+
+    /// Discard a partially typed chord.
+    ///
+    /// Returns true if there was one.
+    pub fn clear_pending_keys(&mut self) -> bool {
+        let was_pending = !self.pending_keys.is_empty();
+        self.pending_keys.clear();
+        was_pending
+    }
+
+    /// Resolve a key against the user's key bindings, waiting for the rest
+    /// of a chord.
+    ///
+    /// A key that breaks off a chord is resolved on its own.
+    pub fn resolve_key(&mut self, key: KeyEvent) -> Resolved {
+        self.pending_keys.push(key);
+        match self.keymap.resolve(&self.pending_keys) {
+            Resolved::Pending => Resolved::Pending,
+            Resolved::Unbound if self.pending_keys.len() > 1 => {
+                self.pending_keys.clear();
+                self.resolve_key(key)
+            }
+            resolved => {
+                self.pending_keys.clear();
+                resolved
+            }
+        }
+    }
+
+    /// Add a digit to the quick-select number.
+    ///
+    /// The script runs as soon as the number is unambiguous, i.e. no further
+    /// digit could still name a visible script. Otherwise the matching script
+    /// is selected and Enter runs it.
+    pub fn push_number_digit(&mut self, digit: usize) -> Option<ScriptRun> {
+        let number = self.pending_number.unwrap_or(0) * 10 + digit;
+        let count = self.visible_indices.len();
+
+        if number == 0 || number > count {
+            self.pending_number = None;
+            if number > 0 {
+                self.notify(MessageLevel::Info, format!("No script #{}", number));
+            }
+            return None;
+        }
+
+        if number * 10 > count {
+            self.pending_number = None;
+            self.run_numbered(number)
+        } else {
+            self.pending_number = Some(number);
+            self.select_by_number(number);
+            None
+        }
+    }
+
+    /// Run a script by number (alias for run_numbered, for compatibility).
+    pub fn run_by_number(&mut self, num: usize) {
+        self.run_numbered(num);
+    }
+
+    /// Run the selected script with arguments.
+    pub fn run_with_args(&mut self, args: String) -> Option<ScriptRun> {
+        if let Some(script) = self.selected_script() {
+            let args = if args.is_empty() { None } else { Some(args) };
+            let run = self.new_run(script.clone(), args);
+            if self.read_only {
+                self.show_commands(&[run]);
+                self.set_mode(AppMode::Normal);
+                return None;
+            }
+            self.script_to_run = Some(run.clone());
+            self.finish_run();
+            Some(run)
+        } else {
+            None
+        }
+    }
+
+    /// Get the run history of a script in the current project or workspace.
+    pub fn script_history(&self, name: &str) -> Option<&ScriptHistory> {
+        self.history.get_script_stats(self.history_path(), name)
+    }
+
+    /// Get the arguments last used with the selected script, if any.
+    pub fn last_args_for_selected(&self) -> Option<String> {
+        let script = self.selected_script()?;
+        self.history
+            .get_script_stats(self.history_path(), script.name())
+            .and_then(|h| h.last_args.clone())
+    }
+
+    /// Run the selected script with the arguments it was last run with.
+    pub fn run_with_last_args(&mut self) -> Option<ScriptRun> {
+        let args = self.last_args_for_selected().unwrap_or_default();
+        self.run_with_args(args)
+    }
+
+    /// Enter args mode pre-filled with the selected script's last arguments.
+    pub fn enter_args_mode_with_last(&mut self) {
+        let last_args = self.last_args_for_selected();
+        if last_args.is_none() {
+            if let Some(name) = self.selected_script().map(|s| s.name().to_string()) {
+                self.notify(
+                    MessageLevel::Info,
+                    format!("No previous arguments for {}", name),
+                );
+            }
+        }
+        let input = last_args.unwrap_or_default();
+        if self.selected < self.visible_indices.len() {
+            self.mode = AppMode::Args {
+                script_index: self.selected,
+                input,
+            };
+            self.reset_input_cursor();
+            self.history_index = None;
+        }
+    }
+
+    /// Get previous arguments of the script being given arguments, most recent first.
+    pub fn args_history(&self) -> Vec<String> {
+        let AppMode::Args { script_index, .. } = self.mode else {
+            return Vec::new();
+        };
+        self.get_visible_script(script_index)
+            .and_then(|s| self.history.get_script_stats(self.history_path(), s.name()))
+            .map(|h| h.args_history())
+            .unwrap_or_default()
+    }
+
+    /// Replace the args input, keeping the position in the argument history.
+    fn set_args_input(&mut self, input: String) {
+        if let AppMode::Args { script_index, .. } = self.mode {
+            self.mode = AppMode::Args {
+                script_index,
+                input,
+            };
+            self.reset_input_cursor();
+        }
+    }
+
+    /// Recall the next older arguments used with the script.
+    ///
+    /// Returns false if there is nothing older to recall.
+    pub fn recall_older_args(&mut self) -> bool {
+        let AppMode::Args { ref input, .. } = self.mode else {
+            return false;
+        };
+        let input = input.clone();
+        let history = self.args_history();
+
+        let next = match self.history_index {
+            Some(index) => index + 1,
+            // Skip an entry that is already in the input (e.g. prefilled last args)
+            None if history.first() == Some(&input) => 1,
+            None => 0,
+        };
+        let Some(args) = history.get(next).cloned() else {
+            return false;
+        };
+
+        if self.history_index.is_none() {
+            self.args_draft = input;
+        }
+        self.set_args_input(args);
+        self.history_index = Some(next);
+        true
+    }
+
+    /// Recall the next newer arguments, or what was typed past the newest.
+    ///
+    /// Returns false if not currently recalling from history.
+    pub fn recall_newer_args(&mut self) -> bool {
+        let Some(index) = self.history_index else {
+            return false;
+        };
+
+        // An entry matching the draft was skipped on the way up
+        let newer = index
+            .checked_sub(1)
+            .and_then(|i| self.args_history().get(i).cloned())
+            .filter(|args| *args != self.args_draft);
+        match newer {
+            Some(args) => {
+                self.set_args_input(args);
+                self.history_index = Some(index - 1);
+            }
+            None => {
+                let draft = std::mem::take(&mut self.args_draft);
+                self.set_args_input(draft);
+                self.history_index = None;
+            }
+        }
+        true
+    }
+
+    /// Complete the flag before the cursor from flags previously passed to the script.
+    ///
+    /// Completes as far as all matching flags agree, and lists them when that
+    /// adds nothing. Returns false if no flag matches.
+    pub fn complete_args_flag(&mut self) -> bool {
+        let Some(mut input) = self.text_input() else {
+            return false;
+        };
+        if !matches!(self.mode, AppMode::Args { .. }) {
+            return false;
+        }
+
+        let word = input.word_before_cursor().to_string();
+        let mut flags: Vec<String> = Vec::new();
+        for args in self.args_history() {
+            for flag in split_args(&args) {
+                if flag.starts_with('-') && flag.starts_with(&word) && !flags.contains(&flag) {
+                    flags.push(flag);
+                }
+            }
+        }
+        // Nothing left to complete for a flag that's already typed out
+        flags.retain(|flag| *flag != word);
+        if flags.is_empty() {
+            return false;
+        }
+
+        let common = common_prefix(&flags);
+        if flags.len() == 1 {
+            input.insert_str(&format!("{} ", &common[word.len()..]));
+        } else if common.len() > word.len() {
+            input.insert_str(&common[word.len()..]);
+        } else {
+            self.notify(MessageLevel::Info, flags.join("  "));
+            return true;
+        }
+        self.apply_text_input(input);
+        true
+    }
+
+    /// Toggle selection of current item in multi-select mode.
+    pub fn toggle_current_selection(&mut self) {
+        if self.is_group_header(self.selected) {
+            return;
+        }
+        if let AppMode::MultiSelect { ref mut selected } = self.mode {
+            if selected.contains(&self.selected) {
+                selected.remove(&self.selected);
+            } else {
+                selected.insert(self.selected);
+            }
+        }
+    }
+
+    /// Get selected indices in multi-select mode.
+    pub fn multi_selected_indices(&self) -> Option<&HashSet<usize>> {
+        if let AppMode::MultiSelect { ref selected } = self.mode {
+            Some(selected)
+        } else {
+            None
+        }
+    }
+
+    /// Run all selected scripts in multi-select mode.
+    pub fn run_multi_selected(&mut self) -> Vec<ScriptRun> {
+        let runs: Vec<ScriptRun> = if let AppMode::MultiSelect { ref selected } = self.mode {
+            // Run in grid order rather than set order
+            let mut indices: Vec<usize> = selected.iter().copied().collect();
+            indices.sort_unstable();
+            indices
+                .into_iter()
+                .filter_map(|idx| {
+                    self.get_visible_script(idx)
+                        .map(|script| self.new_run(script.clone(), None))
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        // Picked scripts run together from one directory
+        if runs
+            .iter()
+            .any(|run| run.run_dir(&self.project_path) != runs[0].run_dir(&self.project_path))
+        {
+            self.notify(
+                MessageLevel::Warn,
+                "Root scripts can't run together with the package's own",
+            );
+            return Vec::new();
+        }
+
+        if !runs.is_empty() && self.read_only {
+            self.show_commands(&runs);
+            self.set_mode(AppMode::Normal);
+            return Vec::new();
+        }
+
+        if !runs.is_empty() {
+            self.script_to_run = runs.first().cloned();
+            self.multi_run = runs.clone();
+            self.finish_run();
+        }
+
+        runs
+    }
+}
+
+/// Calculate grid columns based on terminal width.
+pub fn calculate_columns(width: u16) -> usize {
+    if width < 60 {
+        1
+    } else if width < 90 {
+        2
+    } else if width < 120 {
+        3
+    } else if width < 160 {
+        4
+    } else {
+        5
+    }
+}
+
+/// Calculate column width for the scripts grid.
+pub fn calculate_column_width(total_width: u16, columns: usize) -> u16 {
+    if columns == 0 {
+        return total_width;
+    }
+    let padding = 2; // Left and right padding
+    let available = total_width.saturating_sub(padding * 2);
     (available / columns as u16).max(MIN_COLUMN_WIDTH)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Get the longest prefix shared by all strings.
+fn common_prefix(strings: &[String]) -> &str {
+    let Some(first) = strings.first() else {
+        return "";
+    };
+    let len = strings[1..].iter().fold(first.len(), |len, s| {
+        first[..len]
+            .char_indices()
+            .zip(s.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(s.len()), |((i, _), _)| i)
+    });
+    &first[..len]
+}
+
+/// List the readable workspaces with their member indices from the top
+/// level, each followed by its own members.
+fn workspace_paths<'a>(
+    workspaces: &'a [Workspace],
+    parent: &[usize],
+) -> Vec<(Vec<usize>, &'a Workspace)> {
+    let mut found = Vec::new();
+    for (index, workspace) in workspaces.iter().enumerate() {
+        let mut path = parent.to_vec();
+        path.push(index);
+        let members = workspace_paths(workspace.members(), &path);
+        if workspace.error().is_none() {
+            found.push((path, workspace));
+        }
+        found.extend(members);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_scripts() -> Scripts {
+        let mut scripts = Scripts::new();
+        scripts.add(Script::new("dev", "vite"));
+        scripts.add(Script::new("build", "vite build"));
+        scripts.add(Script::new("test", "vitest"));
+        scripts.add(Script::new("lint", "eslint ."));
+        scripts.add(Script::new("format", "prettier --write ."));
+        scripts.add(Script::new("typecheck", "tsc --noEmit"));
+        scripts.add(Script::new("build:prod", "vite build --mode production"));
+        scripts.add(Script::new("build:dev", "vite build --mode development"));
+        scripts.add(Script::new("test:unit", "vitest unit"));
+        scripts
+    }
+
+    fn create_test_app() -> App {
+        let scripts = create_test_scripts();
+        let config = Config::default();
+        let history = History::new();
+        App::new(
+            scripts,
+            config,
+            history,
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        )
+    }
+
+    // ==================== Basic Tests ====================
+
+    #[test]
+    fn test_app_new() {
+        let app = create_test_app();
+        assert_eq!(app.project_name(), "test-project");
+        assert_eq!(app.runner(), Runner::Npm);
+        assert!(!app.should_quit());
+        assert!(app.script_to_run().is_none());
+        assert_eq!(app.mode(), &AppMode::Normal);
+    }
+
+    #[test]
+    fn test_visible_scripts() {
+        let app = create_test_app();
+        let visible = app.visible_scripts();
+        assert_eq!(visible.len(), 9);
+    }
+
+    #[test]
+    fn test_selected_script() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha); // Ensure predictable order
+        let script = app.selected_script().unwrap();
+        // First alphabetically should be "build"
+        assert_eq!(script.name(), "build");
+    }
+
+    #[test]
+    fn test_favorites_pinned_first() {
+        let mut config = Config::default();
+        config.scripts.favorites = vec!["test*".to_string()];
+        let mut app = App::new(
+            create_test_scripts(),
+            config,
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        app.set_sort_mode(SortMode::Alpha);
+
+        let names: Vec<&str> = (0..3)
+            .filter_map(|i| app.get_visible_script(i).map(|s| s.name()))
+            .collect();
+        assert_eq!(names, ["test", "test:unit", "build"]);
+
+        // Filtering ranks by match instead
+        app.set_filter("build".to_string());
+        assert_eq!(app.get_visible_script(0).unwrap().name(), "build");
+    }
+
+    #[test]
+    fn test_toggle_pinned() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha);
+        assert!(app.changed_favorites().is_none());
+
+        // Pinning "lint" moves it to the top and keeps the cursor on it
+        app.select_by_number(6);
+        assert_eq!(app.selected_script().unwrap().name(), "lint");
+        app.toggle_pinned();
+        assert_eq!(app.get_visible_script(0).unwrap().name(), "lint");
+        assert_eq!(app.selected_index(), 0);
+        assert_eq!(app.pinned_count(), 1);
+        assert!(app
+            .changed_favorites()
+            .unwrap()
+            .is_pinned(Path::new("/test/project"), "lint"));
+
+        app.toggle_pinned();
+        assert_eq!(app.pinned_count(), 0);
+        assert_eq!(app.get_visible_script(0).unwrap().name(), "build");
+    }
+
+    // ==================== Navigation Tests ====================
+
+    #[test]
+    fn test_move_left_right() {
+        let mut app = create_test_app();
+        app.update_columns(100); // 3 columns
+        app.set_sort_mode(SortMode::Alpha);
+
+        assert_eq!(app.selected_index(), 0);
+
+        app.move_right();
+        assert_eq!(app.selected_index(), 1);
+
+        app.move_right();
+        assert_eq!(app.selected_index(), 2);
+
+        app.move_left();
+        assert_eq!(app.selected_index(), 1);
+
+        app.move_left();
+        assert_eq!(app.selected_index(), 0);
+
+        // Should not go below 0
+        app.move_left();
+        assert_eq!(app.selected_index(), 0);
+    }
+
+    #[test]
+    fn test_move_up_down_single_column() {
+        let mut app = create_test_app();
+        app.update_columns(50); // 1 column
+        app.set_sort_mode(SortMode::Alpha);
+
+        assert_eq!(app.selected_index(), 0);
+
+        app.move_down();
+        assert_eq!(app.selected_index(), 1);
+
+        app.move_down();
+        assert_eq!(app.selected_index(), 2);
+
+        app.move_up();
+        assert_eq!(app.selected_index(), 1);
+
+        app.move_up();
+        assert_eq!(app.selected_index(), 0);
+
+        // Should not go above 0
+        app.move_up();
+        assert_eq!(app.selected_index(), 0);
+    }
+
+    #[test]
+    fn test_move_up_down_multi_column() {
+        let mut app = create_test_app();
+        app.update_columns(100); // 3 columns
+        app.set_sort_mode(SortMode::Alpha);
+
+        // Grid layout (9 items, 3 columns):
+        // 0 1 2
+        // 3 4 5
+        // 6 7 8
+
+        assert_eq!(app.selected_index(), 0);
+
+        app.move_down(); // 0 -> 3
+        assert_eq!(app.selected_index(), 3);
+
+        app.move_down(); // 3 -> 6
+        assert_eq!(app.selected_index(), 6);
+
+        app.move_right(); // 6 -> 7
+        assert_eq!(app.selected_index(), 7);
+
+        app.move_up(); // 7 -> 4
+        assert_eq!(app.selected_index(), 4);
+
+        app.move_up(); // 4 -> 1
+        assert_eq!(app.selected_index(), 1);
+    }
+
+    #[test]
+    fn test_move_to_first_last() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha);
+
+        app.move_to_last();
+        assert_eq!(app.selected_index(), 8); // 9 items, 0-indexed
+
+        app.move_to_first();
+        assert_eq!(app.selected_index(), 0);
+    }
+
+    #[test]
+    fn test_select_by_number() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha);
+
+        app.select_by_number(5);
+        assert_eq!(app.selected_index(), 4); // 5 -> index 4
+
+        app.select_by_number(1);
+        assert_eq!(app.selected_index(), 0);
+
+        app.select_by_number(9);
+        assert_eq!(app.selected_index(), 8);
+
+        // Invalid numbers should not change selection
+        app.select_by_number(0);
+        assert_eq!(app.selected_index(), 8);
+
+        app.select_by_number(100);
+        assert_eq!(app.selected_index(), 8);
+    }
+
+    // ==================== Category Group Tests ====================
+
+    #[test]
+    fn test_collapse_and_expand_group() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Category);
+        assert!(app.is_grouped());
+
+        // Down crosses from the last build script to the dev group
+        app.select_by_number(3);
+        app.move_down();
+        assert_eq!(app.selected_script().unwrap().name(), "dev");
+
+        app.move_to_first();
+        app.toggle_group();
+        assert_eq!(app.visible_scripts().len(), 7);
+        assert!(app.is_group_header(0));
+        assert_eq!(app.selected_index(), 0);
+        assert!(app.selected_script().is_none());
+        assert_eq!(
+            app.changed_collapsed_groups(),
+            Some(vec!["build".to_string()])
+        );
+
+        app.move_down();
+        assert_eq!(app.selected_script().unwrap().name(), "dev");
+        app.move_up();
+        assert!(app.is_group_header(app.selected_index()));
+
+        // Enter on the header expands the group instead of running
+        assert!(app.run_selected().is_none());
+        assert!(!app.should_quit());
+        assert_eq!(app.visible_scripts().len(), 9);
+        assert_eq!(app.selected_script().unwrap().name(), "build");
+    }
+
+    #[test]
+    fn test_collapsed_groups_restored_and_shown_when_filtering() {
+        let mut history = History::new();
+        history.set_collapsed_groups(Path::new("/test/project"), vec!["test".to_string()]);
+        let mut app = App::new(
+            create_test_scripts(),
+            Config::default(),
+            history,
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        assert!(app.group_layout().is_none());
+
+        app.set_sort_mode(SortMode::Category);
+        assert_eq!(app.visible_scripts().len(), 8);
+        assert_eq!(app.changed_collapsed_groups(), None);
+
+        // Filtering shows every match, headers aside
+        app.set_filter("test".to_string());
+        assert!(!app.is_grouped());
+        assert_eq!(app.visible_scripts().len(), 2);
+        assert!(app.selected_script().is_some());
+    }
+
+    // ==================== Filter Tests ====================
+
+    #[test]
+    fn test_filter_updates_visible() {
+        let mut app = create_test_app();
+
+        app.set_filter("build".to_string());
+        let visible = app.visible_scripts();
+
+        // Should match: build, build:prod, build:dev
+        assert_eq!(visible.len(), 3);
+        assert!(visible.iter().all(|s| s.name().contains("build")));
+    }
+
+    #[test]
+    fn test_filter_adjusts_selection() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha);
+
+        // Select last item
+        app.move_to_last();
+        assert_eq!(app.selected_index(), 8);
+
+        // Filter to fewer items
+        app.set_filter("test".to_string());
+
+        // Selection should be adjusted to be within bounds
+        assert!(app.selected_index() < app.visible_count());
+    }
+
+    #[test]
+    fn test_filter_clear() {
+        let mut app = create_test_app();
+
+        app.set_filter("dev".to_string());
+        assert!(app.visible_count() < 9);
+
+        app.clear_filter();
+        assert_eq!(app.visible_count(), 9);
+    }
+
+    #[test]
+    fn test_filter_char_operations() {
+        let mut app = create_test_app();
+
+        app.push_filter_char('t');
+        assert_eq!(app.filter_text(), "t");
+
+        app.push_filter_char('e');
+        assert_eq!(app.filter_text(), "te");
+
+        app.pop_filter_char();
+        assert_eq!(app.filter_text(), "t");
+
+        app.pop_filter_char();
+        assert_eq!(app.filter_text(), "");
+    }
+
+    // ==================== Sort Mode Tests ====================
+
+    #[test]
+    fn test_cycle_sort_mode() {
+        let mut app = create_test_app();
+
+        assert_eq!(app.sort_mode(), SortMode::Recent); // Default
+
+        app.cycle_sort_mode();
+        assert_eq!(app.sort_mode(), SortMode::Alpha);
+
+        app.cycle_sort_mode();
+        assert_eq!(app.sort_mode(), SortMode::Category);
+
+        app.cycle_sort_mode();
+        assert_eq!(app.sort_mode(), SortMode::Recent);
+    }
+
+    #[test]
+    fn test_sort_recent_boosts_git_activity() {
+        let mut app = create_test_app();
+        assert_eq!(app.visible_scripts()[0].name(), "build");
+
+        app.set_git_activity(GitActivity::from_paths(&[PathBuf::from(
+            "unit/math.test.ts",
+        )]));
+        assert_eq!(app.visible_scripts()[0].name(), "test:unit");
+
+        // Other sort modes ignore it
+        app.set_sort_mode(SortMode::Alpha);
+        assert_eq!(app.visible_scripts()[0].name(), "build");
+    }
+
+    #[test]
+    fn test_sort_mode_alpha() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha);
+
+        let visible = app.visible_scripts();
+        let names: Vec<&str> = visible.iter().map(|s| s.name()).collect();
+
+        // Should be alphabetically sorted
+        let mut sorted_names = names.clone();
+        sorted_names.sort();
+        assert_eq!(names, sorted_names);
+    }
+
+    #[test]
+    fn test_sort_mode_alpha_natural() {
+        let mut scripts = Scripts::new();
+        scripts.add(Script::new("task10", "echo 10"));
+        scripts.add(Script::new("task2", "echo 2"));
+        scripts.add(Script::new("Build", "echo build"));
+        scripts.add(Script::new("audit", "echo audit"));
+
+        let mut config = Config::default();
+        config.general.sort_natural = Some(true);
+
+        let mut app = App::new(
+            scripts,
+            config,
+            History::new(),
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+        );
+        app.set_sort_mode(SortMode::Alpha);
+
+        let names: Vec<&str> = app.visible_scripts().iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["audit", "Build", "task2", "task10"]);
+    }
+
+    #[test]
+    fn test_sort_mode_category() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Category);
+
+        let visible = app.visible_scripts();
+        let names: Vec<&str> = visible.iter().map(|s| s.name()).collect();
+
+        // Scripts with "build" prefix should be together
+        let build_indices: Vec<usize> = names
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.starts_with("build"))
+            .map(|(i, _)| i)
+            .collect();
+
+        // Build scripts should be consecutive
+        if build_indices.len() > 1 {
+            for i in 1..build_indices.len() {
+                assert!(build_indices[i] - build_indices[i - 1] <= 1);
+            }
+        }
+    }
+
+    // ==================== Output Pane Tests ====================
+
+    #[test]
+    fn test_run_in_pane_spawn_failure_shows_error() {
+        // The project directory doesn't exist, so the runner can't start
+        let mut app = create_test_app();
+        app.run_in_pane();
+
+        assert!(matches!(app.mode(), AppMode::Error { .. }));
+        assert!(app.pane_run().is_none());
+        assert!(!app.should_quit());
+    }
+
+    #[test]
+    fn test_run_in_pane_asks_before_confirmed_scripts() {
+        let mut config = Config::default();
+        config.scripts.confirm = vec!["*".to_string()];
+        let mut app = App::new(
+            create_test_scripts(),
+            config,
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        app.run_in_pane();
+
+        // Confirmed scripts run after exit, once confirmed in the overlay
+        assert!(app.pane_run().is_none());
+        assert!(matches!(app.mode(), AppMode::ConfirmRun { names } if names.len() == 1));
+        assert!(!app.should_quit());
+
+        app.confirm_run();
+        assert!(app.should_quit());
+        assert!(app.is_run_confirmed());
+        assert!(app.script_to_run().is_some());
+    }
+
+    #[test]
+    fn test_run_in_pane_offers_install() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"react": "^18.0.0"}}"#,
+        )
+        .unwrap();
+        let mut app = App::new(
+            create_test_scripts(),
+            Config::default(),
+            History::new(),
+            "test-project".to_string(),
+            dir.path().to_path_buf(),
+            Runner::Pnpm,
+        );
+        app.run_in_pane();
+        let AppMode::InstallPrompt { command } = app.mode() else {
+            panic!("expected the install prompt");
+        };
+        assert_eq!(command, "pnpm install");
+        assert!(app.pane_run().is_none());
+
+        // Installing first runs the script after exit
+        app.install_first();
+        assert!(app.should_quit());
+        assert!(app.is_install_first());
+        assert!(app.is_install_checked());
+        assert!(app.script_to_run().is_some());
+    }
+
+    #[test]
+    fn test_cancel_confirmed_multi_run() {
+        let mut config = Config::default();
+        config.scripts.confirm = vec!["b*".to_string()];
+        let mut app = App::new(
+            create_test_scripts(),
+            config,
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        app.set_mode(AppMode::MultiSelect {
+            selected: (0..9).collect(),
+        });
+        app.run_multi_selected();
+        let AppMode::ConfirmRun { names } = app.mode() else {
+            panic!("expected the confirm overlay");
+        };
+        assert_eq!(names.len(), 3);
+        assert!(names.iter().all(|n| n.starts_with("build")));
+
+        app.cancel_run();
+        assert!(!app.should_quit());
+        assert!(app.scripts_to_run().is_empty());
+        assert_eq!(app.mode(), &AppMode::Normal);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_in_pane_streams_output() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"hello": "echo hello from pane"}}"#,
+        )
+        .unwrap();
+        let scripts = Scripts::from_vec(vec![Script::new("hello", "echo hello from pane")]);
+        let mut app = App::new(
+            scripts,
+            Config::default(),
+            History::new(),
+            "pane".to_string(),
+            dir.path().to_path_buf(),
+            Runner::Npm,
+        );
+
+        app.run_in_pane();
+        if app.pane_run().is_none() {
+            // npm isn't installed
+            return;
+        }
+        assert_eq!(app.mode(), &AppMode::Running);
+
+        let deadline = Instant::now() + std::time::Duration::from_secs(30);
+        while !app.pane_run().unwrap().is_finished() && Instant::now() < deadline {
+            app.poll_pane();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let pane = app.pane_run().unwrap();
+        assert_eq!(pane.exit_code(), Some(Some(0)));
+        assert!(pane.lines().iter().any(|l| l.text == "hello from pane"));
+        assert_eq!(app.finished_runs().len(), 1);
+        assert!(app.finished_runs()[0].1.success());
+
+        app.close_pane();
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert!(app.pane_run().is_none());
+        assert!(!app.should_quit());
+    }
+
+    // ==================== Category Tests ====================
+
+    #[test]
+    fn test_categories_largest_first() {
+        let app = create_test_app();
+        let categories = app.categories();
+        assert_eq!(categories[0], ("build", 3));
+        assert_eq!(categories[1], ("test", 2));
+        assert_eq!(categories.len(), 6);
+    }
+
+    #[test]
+    fn test_select_category_narrows_scripts() {
+        let mut app = create_test_app();
+        app.toggle_categories();
+        assert_eq!(app.mode(), &AppMode::Categories);
+        assert_eq!(app.category_selected(), 0);
+
+        // Row 1 is the largest category
+        app.category_move_down();
+        app.select_current_category();
+
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert_eq!(app.category(), Some("build"));
+        assert_eq!(app.visible_count(), 3);
+        assert_eq!(app.breadcrumb(), "test-project > build");
+
+        // Reopening highlights the current category
+        app.toggle_categories();
+        assert_eq!(app.category_selected(), 1);
+
+        // Row 0 shows all scripts again
+        app.select_category_by_number(1);
+        assert_eq!(app.category(), None);
+        assert_eq!(app.visible_count(), 9);
+    }
+
+    #[test]
+    fn test_category_combines_with_filter() {
+        let mut app = create_test_app();
+        app.select_category(1);
+        app.set_filter("prod".to_string());
+        let names: Vec<&str> = app.visible_scripts().iter().map(|s| s.name()).collect();
+        assert_eq!(names, ["build:prod"]);
+    }
+
+    #[test]
+    fn test_escape_clears_category_after_filter() {
+        let mut app = create_test_app();
+        app.select_category(2);
+        app.set_filter("unit".to_string());
+        app.set_mode(AppMode::Normal);
+
+        app.escape();
+        assert!(app.filter_text().is_empty());
+        assert_eq!(app.category(), Some("test"));
+
+        app.escape();
+        assert_eq!(app.category(), None);
+        assert!(!app.should_quit());
+    }
+
+    // ==================== Action Tests ====================
+
+    #[test]
+    fn test_run_selected() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha);
+
+        let run = app.run_selected();
+        assert!(run.is_some());
 
-    fn create_test_scripts() -> Scripts {
-        let mut scripts = Scripts::new();
-        scripts.add(Script::new("dev", "vite"));
-        scripts.add(Script::new("build", "vite build"));
-        scripts.add(Script::new("test", "vitest"));
-        scripts.add(Script::new("lint", "eslint ."));
-        scripts.add(Script::new("format", "prettier --write ."));
-        scripts.add(Script::new("typecheck", "tsc --noEmit"));
-        scripts.add(Script::new("build:prod", "vite build --mode production"));
-        scripts.add(Script::new("build:dev", "vite build --mode development"));
-        scripts.add(Script::new("test:unit", "vitest unit"));
-        scripts
+        let run = run.unwrap();
+        assert_eq!(run.script.name(), "build"); // First alphabetically
+        assert!(run.args.is_none());
+        assert!(app.should_quit());
     }
 
-    fn create_test_app() -> App {
-        let scripts = create_test_scripts();
-        let config = Config::default();
-        let history = History::new();
-        App::new(
+    #[test]
+    fn test_run_alias() {
+        let mut scripts = Scripts::new();
+        scripts.add(Script::new("test", "vitest"));
+        let mut config = Config::default();
+        config
+            .scripts
+            .aliases
+            .aliases
+            .insert("t".to_string(), "test -- --watch".to_string());
+        config.scripts.aliases.apply(&mut scripts);
+        let mut app = App::new(
             scripts,
             config,
-            history,
+            History::new(),
             "test-project".to_string(),
             PathBuf::from("/test/project"),
             Runner::Npm,
-        )
-    }
+        );
+        app.set_sort_mode(SortMode::Alpha);
 
-    // ==================== Basic Tests ====================
+        let run = app.run_selected().unwrap();
+        assert_eq!(run.script.name(), "test");
+        assert_eq!(run.args.as_deref(), Some("--watch"));
+    }
 
     #[test]
-    fn test_app_new() {
-        let app = create_test_app();
-        assert_eq!(app.project_name(), "test-project");
-        assert_eq!(app.runner(), Runner::Npm);
-        assert!(!app.should_quit());
-        assert!(app.script_to_run().is_none());
+    fn test_toggle_dependencies() {
+        let mut scripts = Scripts::new();
+        scripts.add(Script::new("ci", "run-s lint test"));
+        scripts.add(Script::new("lint", "eslint ."));
+        scripts.add(Script::new("test", "vitest"));
+        let mut app = App::new(
+            scripts,
+            Config::default(),
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        app.set_sort_mode(SortMode::Alpha);
+
+        app.toggle_dependencies();
+        let AppMode::Dependencies { tree } = app.mode() else {
+            panic!("expected the dependencies popover");
+        };
+        let names: Vec<&str> = tree.iter().map(|line| line.name.as_str()).collect();
+        assert_eq!(names, ["ci", "lint", "test"]);
+
+        app.toggle_dependencies();
         assert_eq!(app.mode(), &AppMode::Normal);
     }
 
     #[test]
-    fn test_visible_scripts() {
-        let app = create_test_app();
-        let visible = app.visible_scripts();
-        assert_eq!(visible.len(), 9);
-    }
+    fn test_toggle_project_info() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"name": "tool", "type": "module", "bin": "./cli.js"}"#,
+        )
+        .unwrap();
+        let mut app = App::new(
+            Scripts::new(),
+            Config::default(),
+            History::new(),
+            "test-project".to_string(),
+            dir.path().to_path_buf(),
+            Runner::Npm,
+        );
 
-    #[test]
-    fn test_selected_script() {
+        app.toggle_project_info();
+        let AppMode::ProjectInfo { info } = app.mode() else {
+            panic!("expected the project info popover");
+        };
+        assert!(info.is_module);
+        assert_eq!(info.bin, [("tool".to_string(), "./cli.js".to_string())]);
+        app.toggle_project_info();
+        assert_eq!(app.mode(), &AppMode::Normal);
+
+        // A missing package.json reports an error instead
         let mut app = create_test_app();
-        app.set_sort_mode(SortMode::Alpha); // Ensure predictable order
-        let script = app.selected_script().unwrap();
-        // First alphabetically should be "build"
-        assert_eq!(script.name(), "build");
+        app.toggle_project_info();
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert!(app.current_message().is_some());
     }
 
-    // ==================== Navigation Tests ====================
+    #[test]
+    fn test_toggle_stats() {
+        let mut history = History::new();
+        let project = Path::new("/test/project");
+        history.record_run(project, "dev", None);
+        history.record_run(project, "dev", None);
+        history.record_run(project, "build", None);
+        history.record_result(project, "build", 1, std::time::Duration::from_secs(2));
+        let mut app = App::new(
+            create_test_scripts(),
+            Config::default(),
+            history,
+            "test-project".to_string(),
+            project.to_path_buf(),
+            Runner::Npm,
+        );
+
+        app.toggle_stats();
+        let AppMode::Stats { stats } = app.mode() else {
+            panic!("expected the usage overlay");
+        };
+        assert_eq!(stats.total_runs(), 3);
+        assert_eq!(stats.scripts[0].name, "dev");
+        assert_eq!(stats.failure_rate(), Some(1.0));
+        app.toggle_stats();
+        assert_eq!(app.mode(), &AppMode::Normal);
+    }
 
     #[test]
-    fn test_move_left_right() {
-        let mut app = create_test_app();
-        app.update_columns(100); // 3 columns
+    fn test_toggle_source() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            "{\n  \"scripts\": {\n    \"dev\": \"vite\"\n  }\n}\n",
+        )
+        .unwrap();
+        let mut scripts = Scripts::new();
+        scripts.add(Script::new("dev", "vite"));
+        scripts.add(Script::new("gone", "echo"));
+        let mut app = App::new(
+            scripts,
+            Config::default(),
+            History::new(),
+            "test-project".to_string(),
+            dir.path().to_path_buf(),
+            Runner::Npm,
+        );
         app.set_sort_mode(SortMode::Alpha);
 
-        assert_eq!(app.selected_index(), 0);
-
-        app.move_right();
-        assert_eq!(app.selected_index(), 1);
+        app.toggle_source();
+        let AppMode::Source { excerpt } = app.mode() else {
+            panic!("expected the source popover");
+        };
+        assert_eq!(excerpt.lines.len(), 1);
+        assert_eq!(excerpt.lines[0].number, 3);
+        app.toggle_source();
+        assert_eq!(app.mode(), &AppMode::Normal);
 
-        app.move_right();
-        assert_eq!(app.selected_index(), 2);
+        // Scripts that aren't in the file report an error instead
+        app.move_to_last();
+        app.toggle_source();
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert!(app
+            .current_message()
+            .unwrap()
+            .text
+            .contains("Couldn't find gone"));
+    }
 
-        app.move_left();
-        assert_eq!(app.selected_index(), 1);
+    #[test]
+    fn test_read_only_shows_command() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha);
+        app.set_read_only(true);
 
-        app.move_left();
-        assert_eq!(app.selected_index(), 0);
+        assert!(app.run_selected().is_none());
+        assert!(!app.should_quit());
+        assert!(app.scripts_to_run().is_empty());
+        assert_eq!(
+            app.current_message().unwrap().text,
+            "Would run: npm run build"
+        );
 
-        // Should not go below 0
-        app.move_left();
-        assert_eq!(app.selected_index(), 0);
+        app.run_in_pane();
+        assert!(app.pane_run().is_none());
+        app.toggle_pinned();
+        assert!(app.changed_favorites().is_none());
     }
 
     #[test]
-    fn test_move_up_down_single_column() {
+    fn test_run_numbered() {
         let mut app = create_test_app();
-        app.update_columns(50); // 1 column
         app.set_sort_mode(SortMode::Alpha);
 
-        assert_eq!(app.selected_index(), 0);
-
-        app.move_down();
-        assert_eq!(app.selected_index(), 1);
+        let run = app.run_numbered(3);
+        assert!(run.is_some());
 
-        app.move_down();
+        // Verify the run was successful
+        assert!(!run.unwrap().script.name().is_empty());
         assert_eq!(app.selected_index(), 2);
+        assert!(app.should_quit());
+    }
 
-        app.move_up();
-        assert_eq!(app.selected_index(), 1);
+    #[test]
+    fn test_run_with_args() {
+        let mut app = create_test_app();
+        app.set_sort_mode(SortMode::Alpha);
 
-        app.move_up();
-        assert_eq!(app.selected_index(), 0);
+        let run = app.run_with_args("--watch".to_string());
+        assert!(run.is_some());
 
-        // Should not go above 0
-        app.move_up();
-        assert_eq!(app.selected_index(), 0);
+        let run = run.unwrap();
+        assert_eq!(run.args, Some("--watch".to_string()));
+        assert!(app.should_quit());
     }
 
     #[test]
-    fn test_move_up_down_multi_column() {
+    fn test_quit() {
         let mut app = create_test_app();
-        app.update_columns(100); // 3 columns
-        app.set_sort_mode(SortMode::Alpha);
+        assert!(!app.should_quit());
 
-        // Grid layout (9 items, 3 columns):
-        // 0 1 2
-        // 3 4 5
-        // 6 7 8
+        app.quit();
+        assert!(app.should_quit());
+    }
 
-        assert_eq!(app.selected_index(), 0);
+    // ==================== Mode Tests ====================
 
-        app.move_down(); // 0 -> 3
-        assert_eq!(app.selected_index(), 3);
+    #[test]
+    fn test_toggle_filter_mode() {
+        let mut app = create_test_app();
+        assert_eq!(app.mode(), &AppMode::Normal);
 
-        app.move_down(); // 3 -> 6
-        assert_eq!(app.selected_index(), 6);
+        app.toggle_filter_mode();
+        assert!(matches!(app.mode(), &AppMode::Filter { .. }));
 
-        app.move_right(); // 6 -> 7
-        assert_eq!(app.selected_index(), 7);
+        app.toggle_filter_mode();
+        assert_eq!(app.mode(), &AppMode::Normal);
+    }
 
-        app.move_up(); // 7 -> 4
-        assert_eq!(app.selected_index(), 4);
+    #[test]
+    fn test_toggle_multi_select() {
+        let mut app = create_test_app();
+        assert_eq!(app.mode(), &AppMode::Normal);
 
-        app.move_up(); // 4 -> 1
-        assert_eq!(app.selected_index(), 1);
+        app.toggle_multi_select();
+        assert!(matches!(app.mode(), &AppMode::MultiSelect { .. }));
+
+        app.toggle_multi_select();
+        assert_eq!(app.mode(), &AppMode::Normal);
     }
 
     #[test]
-    fn test_move_to_first_last() {
+    fn test_toggle_help() {
         let mut app = create_test_app();
-        app.set_sort_mode(SortMode::Alpha);
+        assert_eq!(app.mode(), &AppMode::Normal);
 
-        app.move_to_last();
-        assert_eq!(app.selected_index(), 8); // 9 items, 0-indexed
+        app.toggle_help();
+        assert_eq!(app.mode(), &AppMode::Help);
 
-        app.move_to_first();
-        assert_eq!(app.selected_index(), 0);
+        app.toggle_help();
+        assert_eq!(app.mode(), &AppMode::Normal);
     }
 
     #[test]
-    fn test_select_by_number() {
+    fn test_toggle_details() {
         let mut app = create_test_app();
-        app.set_sort_mode(SortMode::Alpha);
+        app.toggle_details();
+        assert_eq!(app.mode(), &AppMode::Details);
+        app.toggle_details();
+        assert_eq!(app.mode(), &AppMode::Normal);
 
-        app.select_by_number(5);
-        assert_eq!(app.selected_index(), 4); // 5 -> index 4
+        // No popover without a selected script
+        app.set_filter("zzzzz".to_string());
+        app.set_mode(AppMode::Normal);
+        app.toggle_details();
+        assert_eq!(app.mode(), &AppMode::Normal);
+    }
 
-        app.select_by_number(1);
-        assert_eq!(app.selected_index(), 0);
+    fn create_workspace_app(history: History) -> App {
+        let mut root_scripts = Scripts::new();
+        root_scripts.add(Script::new("lint", "eslint ."));
+
+        let web = Workspace::with_scripts(
+            "@app/web",
+            "/test/packages/web",
+            vec![
+                Script::new("build", "vite build"),
+                Script::new("dev", "vite"),
+            ],
+        );
 
-        app.select_by_number(9);
-        assert_eq!(app.selected_index(), 8);
+        App::with_workspaces(
+            root_scripts,
+            Config::default(),
+            history,
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+            vec![web],
+        )
+    }
 
-        // Invalid numbers should not change selection
-        app.select_by_number(0);
-        assert_eq!(app.selected_index(), 8);
+    #[test]
+    fn test_history_scoped_to_workspace() {
+        let mut history = History::new();
+        // "dev" is recent at the root, "build" in the workspace
+        history.record_run(Path::new("/test"), "dev", None);
+        history.record_run(Path::new("/test/packages/web"), "build", None);
 
-        app.select_by_number(100);
-        assert_eq!(app.selected_index(), 8);
+        let mut app = create_workspace_app(history);
+        assert_eq!(app.history_path(), Path::new("/test"));
+
+        app.select_workspace(1);
+        assert_eq!(app.history_path(), Path::new("/test/packages/web"));
+
+        let names: Vec<&str> = app.visible_scripts().iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["build", "dev"]);
     }
 
-    // ==================== Filter Tests ====================
+    #[test]
+    fn test_yank_workspace_path() {
+        let mut app = create_workspace_app(History::new());
+        assert_eq!(app.mode(), &AppMode::WorkspaceSelect);
+
+        app.workspace_move_down();
+        app.yank_workspace_path();
+        assert!(app.should_quit());
+        assert_eq!(app.yanked_path(), Some(Path::new("/test/packages/web")));
+
+        let mut app = create_workspace_app(History::new());
+        app.select_workspace(0);
+        app.yank_workspace_path();
+        assert_eq!(app.yanked_path(), Some(Path::new("/test")));
+    }
 
     #[test]
-    fn test_filter_updates_visible() {
-        let mut app = create_test_app();
+    fn test_unreadable_workspace_stays_in_selector() {
+        let workspaces = vec![
+            Workspace::with_scripts("web", "/test/web", vec![Script::new("dev", "vite")]),
+            Workspace::unreadable("broken", "/test/broken", "package.json: permission denied"),
+        ];
+        let mut app = App::with_workspaces(
+            Scripts::new(),
+            Config::default(),
+            History::new(),
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+            workspaces,
+        );
+        assert!(app
+            .current_message()
+            .is_some_and(|m| m.text.contains("Couldn't read 1 workspace manifest")));
+        app.messages.dismiss();
+
+        app.select_workspace(2);
+        assert_eq!(app.mode(), &AppMode::WorkspaceSelect);
+        assert!(app
+            .current_message()
+            .is_some_and(|m| m.text == "broken: package.json: permission denied"));
+
+        app.select_workspace(1);
+        assert_eq!(app.mode(), &AppMode::Normal);
+    }
+
+    fn create_nested_workspace_app() -> App {
+        let mut mobile = Workspace::with_scripts(
+            "mobile",
+            "/test/apps/mobile",
+            vec![Script::new("start", "expo start")],
+        );
+        mobile.set_members(vec![Workspace::with_scripts(
+            "@mobile/ui",
+            "/test/apps/mobile/packages/ui",
+            vec![Script::new("build", "tsc")],
+        )]);
+        let web =
+            Workspace::with_scripts("web", "/test/apps/web", vec![Script::new("dev", "vite")]);
+
+        let mut root_scripts = Scripts::new();
+        root_scripts.add(Script::new("lint", "eslint ."));
+        App::with_workspaces(
+            root_scripts,
+            Config::default(),
+            History::new(),
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+            vec![mobile, web],
+        )
+    }
 
-        app.set_filter("build".to_string());
-        let visible = app.visible_scripts();
+    #[test]
+    fn test_drill_into_nested_workspace() {
+        let mut app = create_nested_workspace_app();
+
+        // Enter on a nested monorepo lists its workspaces
+        app.workspace_move_down();
+        app.select_current_workspace();
+        assert_eq!(app.mode(), &AppMode::WorkspaceSelect);
+        assert_eq!(app.workspace_count(), 2);
+        let trail: Vec<&str> = app.workspace_trail().iter().map(|w| w.name()).collect();
+        assert_eq!(trail, vec!["mobile"]);
+
+        app.workspace_move_down();
+        app.select_current_workspace();
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert_eq!(app.breadcrumb(), "test > mobile > @mobile/ui");
+        assert_eq!(
+            app.history_path(),
+            Path::new("/test/apps/mobile/packages/ui")
+        );
 
-        // Should match: build, build:prod, build:dev
-        assert_eq!(visible.len(), 3);
-        assert!(visible.iter().all(|s| s.name().contains("build")));
+        let run = app.run_selected().unwrap();
+        assert_eq!(run.workspace.as_deref(), Some("@mobile/ui"));
+        assert_eq!(
+            run.run_dir(Path::new("/test")),
+            Path::new("/test/apps/mobile")
+        );
     }
 
     #[test]
-    fn test_filter_adjusts_selection() {
-        let mut app = create_test_app();
-        app.set_sort_mode(SortMode::Alpha);
+    fn test_global_search_opens_workspace() {
+        let mut app = create_nested_workspace_app();
+        app.enter_global_search();
+        let found: Vec<String> = app
+            .global_matches()
+            .iter()
+            .map(|m| format!("{} > {}", m.label, m.script))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "test > lint",
+                "mobile > start",
+                "@mobile/ui > build",
+                "web > dev"
+            ]
+        );
 
-        // Select last item
-        app.move_to_last();
-        assert_eq!(app.selected_index(), 8);
+        app.apply_text_input(TextInput::new("bld"));
+        assert_eq!(app.global_matches().len(), 1);
+        app.open_global_match();
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert_eq!(app.breadcrumb(), "test > mobile > @mobile/ui");
+        assert_eq!(app.selected_script().map(|s| s.name()), Some("build"));
+
+        // Esc goes back to where the search was opened
+        app.back_to_workspace_select();
+        app.enter_global_search();
+        app.close_global_search();
+        assert_eq!(app.mode(), &AppMode::WorkspaceSelect);
+    }
 
-        // Filter to fewer items
-        app.set_filter("test".to_string());
+    #[test]
+    fn test_run_in_all_workspaces() {
+        let mut mobile = Workspace::with_scripts(
+            "mobile",
+            "/test/apps/mobile",
+            vec![Script::new("build", "expo export")],
+        );
+        mobile.set_members(vec![Workspace::with_scripts(
+            "@mobile/ui",
+            "/test/apps/mobile/packages/ui",
+            vec![Script::new("build", "tsc")],
+        )]);
+        let web =
+            Workspace::with_scripts("web", "/test/apps/web", vec![Script::new("dev", "vite")]);
+        let mut app = App::with_workspaces(
+            Scripts::from_vec(vec![
+                Script::new("build", "turbo build"),
+                Script::new("lint", "eslint ."),
+            ]),
+            Config::default(),
+            History::new(),
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+            vec![mobile, web],
+        );
 
-        // Selection should be adjusted to be within bounds
-        assert!(app.selected_index() < app.visible_count());
+        app.run_in_all_workspaces();
+        assert!(app.should_quit());
+        assert!(app.is_all_workspaces_run());
+        let runs = app.scripts_to_run();
+        let workspaces: Vec<_> = runs.iter().map(|r| r.workspace.as_deref()).collect();
+        assert_eq!(workspaces, [Some("mobile"), Some("@mobile/ui")]);
+        assert_eq!(
+            runs[1].run_dir(Path::new("/test")),
+            Path::new("/test/apps/mobile")
+        );
     }
 
     #[test]
-    fn test_filter_clear() {
+    fn test_watch_selected() {
         let mut app = create_test_app();
+        app.watch_selected();
+        assert!(app.should_quit());
+        assert!(app.is_watch_run());
+        assert_eq!(app.scripts_to_run().len(), 1);
 
-        app.set_filter("dev".to_string());
-        assert!(app.visible_count() < 9);
-
-        app.clear_filter();
-        assert_eq!(app.visible_count(), 9);
+        let mut app = create_test_app();
+        app.set_read_only(true);
+        app.watch_selected();
+        assert!(!app.should_quit());
+        assert!(!app.is_watch_run());
     }
 
     #[test]
-    fn test_filter_char_operations() {
-        let mut app = create_test_app();
-
-        app.push_filter_char('t');
-        assert_eq!(app.filter_text(), "t");
+    fn test_parent_scripts_run_from_the_root() {
+        let mut scripts = create_test_scripts();
+        let deploy = Script::new("deploy", "turbo deploy");
+        scripts.add(Script::from_parent(&deploy, Path::new("/test")));
+        let mut app = App::new(
+            scripts,
+            Config::default(),
+            History::new(),
+            "project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        app.set_sort_mode(SortMode::Alpha);
 
-        app.push_filter_char('e');
-        assert_eq!(app.filter_text(), "te");
+        // Listed after the package's own scripts
+        let count = app.visible_count();
+        assert_eq!(app.get_visible_script(0).unwrap().name(), "build");
+        assert_eq!(
+            app.get_visible_script(count - 1).unwrap().name(),
+            "root:deploy"
+        );
 
-        app.pop_filter_char();
-        assert_eq!(app.filter_text(), "t");
+        // Picked with one of the package's own, they can't run together
+        app.toggle_multi_select();
+        app.toggle_current_selection();
+        app.select_by_number(count);
+        app.toggle_current_selection();
+        assert!(app.run_multi_selected().is_empty());
+        app.set_mode(AppMode::Normal);
 
-        app.pop_filter_char();
-        assert_eq!(app.filter_text(), "");
+        let run = app.run_selected().unwrap();
+        assert_eq!(run.script.name(), "deploy");
+        assert_eq!(run.to_string(), "root > deploy");
+        assert_eq!(run.run_dir(Path::new("/test/project")), Path::new("/test"));
     }
 
-    // ==================== Sort Mode Tests ====================
-
     #[test]
-    fn test_cycle_sort_mode() {
-        let mut app = create_test_app();
+    fn test_run_in_all_workspaces_needs_a_workspace_with_the_script() {
+        let mut app = create_workspace_app(History::new());
+        // Only the root defines lint
+        app.run_in_all_workspaces();
+        assert!(!app.should_quit());
+        assert!(app.scripts_to_run().is_empty());
+    }
 
-        assert_eq!(app.sort_mode(), SortMode::Recent); // Default
+    #[test]
+    fn test_nested_workspace_root_and_level_up() {
+        let mut app = create_nested_workspace_app();
+        app.select_workspace_by_number(2);
+        assert_eq!(app.mode(), &AppMode::WorkspaceSelect);
+
+        // The first entry is the nested monorepo's own scripts
+        app.select_workspace(0);
+        assert_eq!(app.breadcrumb(), "test > mobile");
+        let run = app.run_selected().unwrap();
+        assert_eq!(run.script.name(), "start");
+        assert_eq!(run.run_dir(Path::new("/test")), Path::new("/test"));
+
+        app.back_to_workspace_select();
+        assert!(app.workspace_level_up());
+        assert_eq!(app.workspace_selected(), 1);
+        assert_eq!(app.workspace_count(), 3);
+        assert!(!app.workspace_level_up());
+    }
 
-        app.cycle_sort_mode();
-        assert_eq!(app.sort_mode(), SortMode::Alpha);
+    #[test]
+    fn test_select_root_restores_scripts() {
+        let mut app = create_workspace_app(History::new());
 
-        app.cycle_sort_mode();
-        assert_eq!(app.sort_mode(), SortMode::Category);
+        app.select_workspace(1);
+        assert_eq!(app.visible_count(), 2);
 
-        app.cycle_sort_mode();
-        assert_eq!(app.sort_mode(), SortMode::Recent);
+        app.select_workspace(0);
+        let names: Vec<&str> = app.visible_scripts().iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["lint"]);
     }
 
     #[test]
-    fn test_sort_mode_alpha() {
-        let mut app = create_test_app();
+    fn test_run_with_last_args() {
+        let mut history = History::new();
+        history.record_run(Path::new("/test"), "dev", Some("--port 4000".to_string()));
+
+        let mut app = App::new(
+            create_test_scripts(),
+            Config::default(),
+            history,
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+        );
         app.set_sort_mode(SortMode::Alpha);
+        let dev_index = app
+            .visible_scripts()
+            .iter()
+            .position(|s| s.name() == "dev")
+            .unwrap();
+        app.select_by_number(dev_index + 1);
 
-        let visible = app.visible_scripts();
-        let names: Vec<&str> = visible.iter().map(|s| s.name()).collect();
+        assert_eq!(
+            app.last_args_for_selected(),
+            Some("--port 4000".to_string())
+        );
 
-        // Should be alphabetically sorted
-        let mut sorted_names = names.clone();
-        sorted_names.sort();
-        assert_eq!(names, sorted_names);
-    }
+        app.enter_args_mode_with_last();
+        assert!(matches!(app.mode(), AppMode::Args { input, .. } if input == "--port 4000"));
 
-    #[test]
-    fn test_sort_mode_category() {
-        let mut app = create_test_app();
-        app.set_sort_mode(SortMode::Category);
+        let run = app.run_with_last_args().unwrap();
+        assert_eq!(run.args, Some("--port 4000".to_string()));
+    }
 
-        let visible = app.visible_scripts();
-        let names: Vec<&str> = visible.iter().map(|s| s.name()).collect();
+    fn create_args_history_app() -> App {
+        let mut history = History::new();
+        for args in ["--watch", "--filter foo --reporter dot", "--filter bar"] {
+            history.record_run(Path::new("/test"), "test", Some(args.to_string()));
+        }
 
-        // Scripts with "build" prefix should be together
-        let build_indices: Vec<usize> = names
+        let mut app = App::new(
+            create_test_scripts(),
+            Config::default(),
+            history,
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+        );
+        app.set_sort_mode(SortMode::Alpha);
+        let test_index = app
+            .visible_scripts()
             .iter()
-            .enumerate()
-            .filter(|(_, n)| n.starts_with("build"))
-            .map(|(i, _)| i)
-            .collect();
+            .position(|s| s.name() == "test")
+            .unwrap();
+        app.select_by_number(test_index + 1);
+        app
+    }
 
-        // Build scripts should be consecutive
-        if build_indices.len() > 1 {
-            for i in 1..build_indices.len() {
-                assert!(build_indices[i] - build_indices[i - 1] <= 1);
-            }
+    fn args_input(app: &App) -> &str {
+        match app.mode() {
+            AppMode::Args { input, .. } => input,
+            _ => panic!("not in args mode"),
         }
     }
 
-    // ==================== Action Tests ====================
+    #[test]
+    fn test_args_history_recall() {
+        let mut app = create_args_history_app();
+        app.enter_args_mode();
+        app.apply_text_input(TextInput::new("--co"));
+
+        assert!(app.recall_older_args());
+        assert_eq!(args_input(&app), "--filter bar");
+        assert!(app.recall_older_args());
+        assert!(app.recall_older_args());
+        assert_eq!(args_input(&app), "--watch");
+        assert!(!app.recall_older_args());
+
+        assert!(app.recall_newer_args());
+        assert!(app.recall_newer_args());
+        assert_eq!(args_input(&app), "--filter bar");
+        // Past the newest, the typed text comes back
+        assert!(app.recall_newer_args());
+        assert_eq!(args_input(&app), "--co");
+        assert!(!app.recall_newer_args());
+    }
 
     #[test]
-    fn test_run_selected() {
-        let mut app = create_test_app();
-        app.set_sort_mode(SortMode::Alpha);
+    fn test_args_history_recall_skips_prefilled_args() {
+        let mut app = create_args_history_app();
+        app.enter_args_mode_with_last();
+        assert_eq!(args_input(&app), "--filter bar");
+
+        assert!(app.recall_older_args());
+        assert_eq!(args_input(&app), "--filter foo --reporter dot");
+        assert!(app.recall_newer_args());
+        assert_eq!(args_input(&app), "--filter bar");
+        assert!(!app.recall_newer_args());
+    }
 
-        let run = app.run_selected();
-        assert!(run.is_some());
+    #[test]
+    fn test_complete_args_flag() {
+        let mut app = create_args_history_app();
+        app.enter_args_mode();
 
-        let run = run.unwrap();
-        assert_eq!(run.script.name(), "build"); // First alphabetically
-        assert!(run.args.is_none());
-        assert!(app.should_quit());
+        app.apply_text_input(TextInput::new("--r"));
+        assert!(app.complete_args_flag());
+        assert_eq!(args_input(&app), "--reporter ");
+
+        // Ambiguous flags complete as far as they agree, then get listed
+        app.apply_text_input(TextInput::new("x --"));
+        assert!(app.complete_args_flag());
+        assert_eq!(args_input(&app), "x --");
+        assert_eq!(
+            app.current_message().map(|m| m.text.as_str()),
+            Some("--filter  --reporter  --watch")
+        );
+
+        app.apply_text_input(TextInput::new("--fi"));
+        assert!(app.complete_args_flag());
+        assert_eq!(args_input(&app), "--filter ");
+
+        app.apply_text_input(TextInput::new("--x"));
+        assert!(!app.complete_args_flag());
     }
 
     #[test]
-    fn test_run_numbered() {
-        let mut app = create_test_app();
-        app.set_sort_mode(SortMode::Alpha);
+    fn test_common_prefix() {
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(common_prefix(&strings(&["--filter", "--fix"])), "--fi");
+        assert_eq!(common_prefix(&strings(&["--é", "--è"])), "--");
+        assert_eq!(common_prefix(&strings(&["--watch", "--w"])), "--w");
+        assert_eq!(common_prefix(&[]), "");
+    }
 
-        let run = app.run_numbered(3);
-        assert!(run.is_some());
+    #[test]
+    fn test_filter_history_recall() {
+        let mut history = History::new();
+        history.record_filter(Path::new("/test"), "build");
+        history.record_filter(Path::new("/test"), "dev");
 
-        // Verify the run was successful
-        assert!(run.unwrap().script.name().len() > 0);
-        assert_eq!(app.selected_index(), 2);
-        assert!(app.should_quit());
+        let mut app = App::new(
+            create_test_scripts(),
+            Config::default(),
+            history,
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+        );
+        app.set_mode(AppMode::Filter {
+            query: String::new(),
+        });
+
+        assert!(app.recall_older_filter());
+        assert_eq!(app.filter_text(), "dev");
+        assert!(app.recall_older_filter());
+        assert_eq!(app.filter_text(), "build");
+        // Stays on the oldest entry
+        assert!(app.recall_older_filter());
+        assert_eq!(app.filter_text(), "build");
+
+        assert!(app.recall_newer_filter());
+        assert_eq!(app.filter_text(), "dev");
+        assert!(app.recall_newer_filter());
+        assert_eq!(app.filter_text(), "");
+        assert!(!app.recall_newer_filter());
+
+        // Typing ends recall; Up then navigates instead
+        app.apply_text_input(TextInput::new("te"));
+        assert!(!app.recall_older_filter());
     }
 
     #[test]
-    fn test_run_with_args() {
+    fn test_messages_expire() {
         let mut app = create_test_app();
-        app.set_sort_mode(SortMode::Alpha);
+        let start = Instant::now();
+        app.notify(MessageLevel::Warn, "history save failed");
 
-        let run = app.run_with_args("--watch".to_string());
-        assert!(run.is_some());
+        app.tick_messages(start);
+        let message = app.current_message().unwrap();
+        assert_eq!(message.text, "history save failed");
+        assert_eq!(message.level, MessageLevel::Warn);
 
-        let run = run.unwrap();
-        assert_eq!(run.args, Some("--watch".to_string()));
-        assert!(app.should_quit());
+        app.tick_messages(start + MessageLevel::Warn.default_ttl());
+        assert!(app.current_message().is_none());
     }
 
     #[test]
-    fn test_quit() {
+    fn test_out_of_range_number_notifies() {
         let mut app = create_test_app();
+        app.set_filter("lint".to_string());
+        app.set_mode(AppMode::Normal);
+        app.push_number_digit(9);
         assert!(!app.should_quit());
-
-        app.quit();
-        assert!(app.should_quit());
+        assert!(app.current_message().unwrap().text.contains("#9"));
     }
 
-    // ==================== Mode Tests ====================
-
     #[test]
-    fn test_toggle_filter_mode() {
+    fn test_filter_for_run() {
         let mut app = create_test_app();
-        assert_eq!(app.mode(), &AppMode::Normal);
-
-        app.toggle_filter_mode();
-        assert!(matches!(app.mode(), &AppMode::Filter { .. }));
+        app.set_filter("dev".to_string());
+        assert_eq!(app.filter_for_run(), None);
 
-        app.toggle_filter_mode();
-        assert_eq!(app.mode(), &AppMode::Normal);
+        app.run_selected();
+        assert_eq!(app.filter_for_run(), Some("dev"));
     }
 
     #[test]
-    fn test_toggle_multi_select() {
-        let mut app = create_test_app();
-        assert_eq!(app.mode(), &AppMode::Normal);
+    fn test_new_script_adds_to_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let package_json = dir.path().join("package.json");
+        std::fs::write(
+            &package_json,
+            "{\n  \"scripts\": {\n    \"dev\": \"vite\"\n  }\n}\n",
+        )
+        .unwrap();
+        let mut app = App::new(
+            Scripts::from_vec(vec![Script::new("dev", "vite")]),
+            Config::default(),
+            History::new(),
+            "app".to_string(),
+            dir.path().to_path_buf(),
+            Runner::Npm,
+        );
 
-        app.toggle_multi_select();
-        assert!(matches!(app.mode(), &AppMode::MultiSelect { .. }));
+        app.enter_new_script_mode();
+        app.set_mode(AppMode::NewScript {
+            name: None,
+            input: "dev".to_string(),
+        });
+        app.submit_new_script();
+        // Existing names are refused
+        assert!(matches!(app.mode(), AppMode::NewScript { name: None, .. }));
+
+        app.set_mode(AppMode::NewScript {
+            name: None,
+            input: "lint".to_string(),
+        });
+        app.submit_new_script();
+        assert!(matches!(
+            app.mode(),
+            AppMode::NewScript { name: Some(_), .. }
+        ));
+        app.set_mode(AppMode::NewScript {
+            name: Some("lint".to_string()),
+            input: "eslint .".to_string(),
+        });
+        app.submit_new_script();
 
-        app.toggle_multi_select();
         assert_eq!(app.mode(), &AppMode::Normal);
+        assert_eq!(app.selected_script().map(Script::name), Some("lint"));
+        let content = std::fs::read_to_string(&package_json).unwrap();
+        assert!(content.contains("\"dev\": \"vite\",\n    \"lint\": \"eslint .\""));
     }
 
     #[test]
-    fn test_toggle_help() {
-        let mut app = create_test_app();
-        assert_eq!(app.mode(), &AppMode::Normal);
+    fn test_rename_and_remove_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let package_json = dir.path().join("package.json");
+        std::fs::write(
+            &package_json,
+            "{\n  \"scripts\": {\n    \"dev\": \"vite\",\n    \"test\": \"vitest\"\n  }\n}\n",
+        )
+        .unwrap();
+        let mut history = History::new();
+        history.record_run(dir.path(), "dev", Some("--host".to_string()));
+        let mut app = App::new(
+            Scripts::from_vec(vec![
+                Script::new("dev", "vite"),
+                Script::new("test", "vitest"),
+            ]),
+            Config::default(),
+            history,
+            "app".to_string(),
+            dir.path().to_path_buf(),
+            Runner::Npm,
+        );
+        app.toggle_pinned();
 
-        app.toggle_help();
-        assert_eq!(app.mode(), &AppMode::Help);
+        app.enter_rename_mode();
+        assert_eq!(
+            app.mode(),
+            &AppMode::RenameScript {
+                from: "dev".to_string(),
+                input: "dev".to_string()
+            }
+        );
+        app.set_mode(AppMode::RenameScript {
+            from: "dev".to_string(),
+            input: "serve".to_string(),
+        });
+        app.submit_rename();
+        // Nothing is written before confirming
+        assert!(matches!(app.mode(), AppMode::ConfirmEdit { .. }));
+        assert!(std::fs::read_to_string(&package_json)
+            .unwrap()
+            .contains("\"dev\""));
+
+        app.confirm_edit();
+        assert_eq!(app.selected_script().map(Script::name), Some("serve"));
+        assert!(app.is_pinned("serve"));
+        assert!(app.changed_favorites().is_some());
+        assert_eq!(app.last_args_for_selected().as_deref(), Some("--host"));
+        assert!(std::fs::read_to_string(&package_json)
+            .unwrap()
+            .contains("\"serve\": \"vite\","));
+
+        app.remove_selected();
+        app.confirm_edit();
+        assert!(app.scripts().get("serve").is_none());
+        assert!(!app.is_pinned("serve"));
+        assert_eq!(app.script_edits().len(), 2);
+        assert_eq!(
+            std::fs::read_to_string(&package_json).unwrap(),
+            "{\n  \"scripts\": {\n    \"test\": \"vitest\"\n  }\n}\n"
+        );
+    }
 
-        app.toggle_help();
+    #[test]
+    fn test_remove_in_read_only_mode() {
+        let mut app = create_test_app();
+        app.set_read_only(true);
+        app.remove_selected();
         assert_eq!(app.mode(), &AppMode::Normal);
+        assert!(app.script_edits().is_empty());
     }
 
     #[test]
@@ -1275,6 +4696,33 @@ mod tests {
         assert!(!selected.contains(&1));
     }
 
+    #[test]
+    fn test_run_multi_selected_keeps_grid_order() {
+        let mut app = create_test_app();
+        app.toggle_multi_select();
+        app.move_right();
+        app.move_right();
+        app.toggle_current_selection();
+        app.move_left();
+        app.move_left();
+        app.toggle_current_selection();
+
+        let runs = app.run_multi_selected();
+        assert_eq!(runs.len(), 2);
+        assert!(app.should_quit());
+
+        let names: Vec<String> = app
+            .scripts_to_run()
+            .iter()
+            .map(|run| run.script.name().to_string())
+            .collect();
+        let expected: Vec<String> = (0..3)
+            .step_by(2)
+            .map(|i| app.get_visible_script(i).unwrap().name().to_string())
+            .collect();
+        assert_eq!(names, expected);
+    }
+
     // ==================== Column Tests ====================
 
     #[test]
@@ -1305,6 +4753,65 @@ mod tests {
         assert_eq!(app.columns(), 5);
     }
 
+    #[test]
+    fn test_resize_preserves_filter_and_selection() {
+        let mut app = create_test_app();
+        app.resize(100, 30);
+        app.set_filter("e".to_string());
+        app.move_to_last();
+        let selected = app.selected_script().unwrap().name().to_string();
+
+        app.resize(50, 12);
+        assert_eq!(
+            app.mode(),
+            &AppMode::Filter {
+                query: "e".to_string()
+            }
+        );
+        assert_eq!(app.filter_text(), "e");
+        assert_eq!(app.selected_script().unwrap().name(), selected);
+    }
+
+    #[test]
+    fn test_resize_keeps_scroll_anchor() {
+        let mut scripts = Scripts::new();
+        for i in 0..30 {
+            scripts.add(Script::new(format!("script{:02}", i), "echo"));
+        }
+        let mut app = App::new(
+            scripts,
+            Config::default(),
+            History::new(),
+            "test".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+        );
+        app.set_sort_mode(SortMode::Alpha);
+
+        // One column, a handful of rows: scroll down to item 12
+        app.resize(50, 16);
+        assert_eq!(app.columns(), 1);
+        for _ in 0..12 {
+            app.move_down();
+        }
+        assert_eq!(app.selected_index(), 12);
+        let anchor = app.scroll_offset();
+        assert!(anchor > 0);
+
+        // Widen to 3 columns: anchor realigns to its row, selection stays visible
+        app.resize(100, 16);
+        assert_eq!(app.columns(), 3);
+        assert_eq!(app.selected_index(), 12);
+        assert_eq!(app.scroll_offset() % 3, 0);
+        assert!(app.scroll_offset() <= 12);
+        assert!(app.scroll_offset() <= anchor);
+
+        // Back to one column: the selection is still on screen
+        app.resize(50, 16);
+        assert_eq!(app.selected_index(), 12);
+        assert!(app.scroll_offset() <= 12);
+    }
+
     #[test]
     fn test_calculate_column_width() {
         assert_eq!(calculate_column_width(100, 3), 32);
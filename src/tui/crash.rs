@@ -0,0 +1,116 @@
+//! Crash reports for panics inside the TUI.
+//!
+//! Instead of dumping a raw backtrace over the user's prompt, the panic hook
+//! writes a report to a temp file and prints a short note pointing to it.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::utils::clock;
+
+/// Description of the last input handled, included in crash reports.
+static LAST_ACTION: Mutex<Option<String>> = Mutex::new(None);
+
+/// Where crash reports should be filed.
+const ISSUES_URL: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/issues");
+
+/// Remember the last action for a potential crash report.
+pub fn record_action(action: impl Into<String>) {
+    if let Ok(mut last) = LAST_ACTION.lock() {
+        *last = Some(action.into());
+    }
+}
+
+/// Get the last recorded action.
+pub fn last_action() -> Option<String> {
+    LAST_ACTION.lock().ok().and_then(|last| last.clone())
+}
+
+/// Build the text of a crash report.
+pub fn format_crash_report(
+    message: &str,
+    location: Option<&str>,
+    last_action: Option<&str>,
+    backtrace: &str,
+) -> String {
+    format!(
+        "nrs crash report\n\
+         ================\n\
+         \n\
+         Version:     {}\n\
+         OS:          {} ({})\n\
+         Time:        {}\n\
+         Message:     {}\n\
+         Location:    {}\n\
+         Last action: {}\n\
+         \n\
+         Backtrace:\n\
+         {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        clock::now().to_rfc3339(),
+        message,
+        location.unwrap_or("unknown"),
+        last_action.unwrap_or("none"),
+        backtrace,
+    )
+}
+
+/// Write a crash report to the temp directory and return its path.
+pub fn write_crash_report(message: &str, location: Option<&str>) -> Option<PathBuf> {
+    let backtrace = Backtrace::force_capture().to_string();
+    let report = format_crash_report(message, location, last_action().as_deref(), &backtrace);
+
+    let file_name = format!("nrs-crash-{}.txt", clock::now().format("%Y%m%d-%H%M%S"));
+    let path = std::env::temp_dir().join(file_name);
+    fs::write(&path, report).ok()?;
+    Some(path)
+}
+
+/// Print the friendly crash notice.
+pub fn print_crash_notice(message: &str, report: &std::path::Path) {
+    eprintln!("nrs crashed unexpectedly: {message}");
+    eprintln!();
+    eprintln!("A crash report was written to:");
+    eprintln!("  {}", report.display());
+    eprintln!();
+    eprintln!("Please file an issue at {ISSUES_URL} and attach the report.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_crash_report() {
+        let report = format_crash_report(
+            "index out of bounds",
+            Some("src/tui/app.rs:10:5"),
+            Some("Key(Char('j'))"),
+            "0: main",
+        );
+
+        assert!(report.contains(env!("CARGO_PKG_VERSION")));
+        assert!(report.contains(std::env::consts::OS));
+        assert!(report.contains("index out of bounds"));
+        assert!(report.contains("src/tui/app.rs:10:5"));
+        assert!(report.contains("Key(Char('j'))"));
+        assert!(report.contains("0: main"));
+    }
+
+    #[test]
+    fn test_format_crash_report_unknown_fields() {
+        let report = format_crash_report("boom", None, None, "");
+        assert!(report.contains("Location:    unknown"));
+        assert!(report.contains("Last action: none"));
+    }
+
+    #[test]
+    fn test_record_action() {
+        record_action("Key(Enter)");
+        assert_eq!(last_action().as_deref(), Some("Key(Enter)"));
+    }
+}
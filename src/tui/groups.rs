@@ -0,0 +1,214 @@
+//! Category groups of the scripts grid.
+//!
+//! In category sort mode the grid shows a header above each category, and
+//! Tab collapses a group to its header. A collapsed group keeps a single
+//! slot in the list, drawn as its header, so the cursor can land on it and
+//! expand it again.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// One line of the grouped grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupLine {
+    /// Header of a category.
+    Header {
+        /// Category name.
+        category: String,
+        /// Number of scripts in the category.
+        count: usize,
+        /// Slot of a collapsed group. Headers of expanded groups can't be
+        /// selected.
+        slot: Option<usize>,
+    },
+    /// Row of scripts, as a range of slots.
+    Row(Range<usize>),
+}
+
+impl GroupLine {
+    /// Get the slots on the line.
+    pub fn slots(&self) -> Range<usize> {
+        match self {
+            Self::Header {
+                slot: Some(slot), ..
+            } => *slot..*slot + 1,
+            Self::Header { slot: None, .. } => 0..0,
+            Self::Row(slots) => slots.clone(),
+        }
+    }
+
+    /// Get the slot in a column, or the last one if the line is shorter.
+    fn slot_at(&self, column: usize) -> Option<usize> {
+        let slots = self.slots();
+        (!slots.is_empty()).then(|| (slots.start + column).min(slots.end - 1))
+    }
+}
+
+/// Lines of the grouped grid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupLayout {
+    lines: Vec<GroupLine>,
+    columns: usize,
+}
+
+impl GroupLayout {
+    /// Lay out the visible slots in groups.
+    ///
+    /// `categories` holds the category of each slot, with the slots of a
+    /// category next to each other. A collapsed category has a single slot,
+    /// and `count` gives the number of scripts it stands for.
+    pub fn new(
+        categories: &[&str],
+        columns: usize,
+        collapsed: &BTreeSet<String>,
+        count: impl Fn(&str) -> usize,
+    ) -> Self {
+        let columns = columns.max(1);
+        let mut lines = Vec::new();
+        let mut start = 0;
+        while let Some(&category) = categories.get(start) {
+            let end = categories[start..]
+                .iter()
+                .position(|c| *c != category)
+                .map_or(categories.len(), |len| start + len);
+
+            if collapsed.contains(category) {
+                lines.push(GroupLine::Header {
+                    category: category.to_string(),
+                    count: count(category),
+                    slot: Some(start),
+                });
+            } else {
+                lines.push(GroupLine::Header {
+                    category: category.to_string(),
+                    count: end - start,
+                    slot: None,
+                });
+                for row_start in (start..end).step_by(columns) {
+                    lines.push(GroupLine::Row(row_start..(row_start + columns).min(end)));
+                }
+            }
+            start = end;
+        }
+        Self { lines, columns }
+    }
+
+    /// Get the lines, headers included.
+    pub fn lines(&self) -> &[GroupLine] {
+        &self.lines
+    }
+
+    /// Get the number of script columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Get the line a slot is on.
+    pub fn line_of(&self, slot: usize) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| line.slots().contains(&slot))
+    }
+
+    /// Get the slot above a slot, in the same column where possible.
+    pub fn slot_above(&self, slot: usize) -> Option<usize> {
+        let line = self.line_of(slot)?;
+        let column = slot - self.lines[line].slots().start;
+        self.lines[..line]
+            .iter()
+            .rev()
+            .find_map(|line| line.slot_at(column))
+    }
+
+    /// Get the slot below a slot, in the same column where possible.
+    pub fn slot_below(&self, slot: usize) -> Option<usize> {
+        let line = self.line_of(slot)?;
+        let column = slot - self.lines[line].slots().start;
+        self.lines[line + 1..]
+            .iter()
+            .find_map(|line| line.slot_at(column))
+    }
+
+    /// Get the first line to show so a slot is on screen.
+    ///
+    /// The header of a group is kept above its first row. `rows` of 0 means
+    /// the screen height isn't known yet.
+    pub fn scroll(&self, first_line: usize, slot: usize, rows: usize) -> usize {
+        let Some(line) = self.line_of(slot) else {
+            return 0;
+        };
+        let top = match line.checked_sub(1).map(|above| &self.lines[above]) {
+            Some(GroupLine::Header { slot: None, .. }) if rows != 1 => line - 1,
+            _ => line,
+        };
+
+        if top < first_line {
+            top
+        } else if rows > 0 && line >= first_line + rows {
+            line + 1 - rows
+        } else {
+            first_line
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(categories: &[&str], columns: usize, collapsed: &[&str]) -> GroupLayout {
+        let collapsed = collapsed.iter().map(|c| c.to_string()).collect();
+        GroupLayout::new(categories, columns, &collapsed, |_| 3)
+    }
+
+    #[test]
+    fn test_group_lines() {
+        let layout = layout(&["build", "build", "build", "dev", "test"], 2, &["dev"]);
+        assert_eq!(
+            layout.lines(),
+            [
+                GroupLine::Header {
+                    category: "build".to_string(),
+                    count: 3,
+                    slot: None
+                },
+                GroupLine::Row(0..2),
+                GroupLine::Row(2..3),
+                GroupLine::Header {
+                    category: "dev".to_string(),
+                    count: 3,
+                    slot: Some(3)
+                },
+                GroupLine::Header {
+                    category: "test".to_string(),
+                    count: 1,
+                    slot: None
+                },
+                GroupLine::Row(4..5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_navigation() {
+        let layout = layout(&["build", "build", "build", "dev", "test"], 2, &["dev"]);
+        assert_eq!(layout.slot_below(1), Some(2));
+        assert_eq!(layout.slot_below(2), Some(3));
+        assert_eq!(layout.slot_below(3), Some(4));
+        assert_eq!(layout.slot_below(4), None);
+        assert_eq!(layout.slot_above(4), Some(3));
+        assert_eq!(layout.slot_above(3), Some(2));
+        assert_eq!(layout.slot_above(0), None);
+    }
+
+    #[test]
+    fn test_group_scroll_shows_header() {
+        let layout = layout(&["build", "build", "build", "dev", "test"], 2, &["dev"]);
+        // Scrolling up to the first row brings its header along
+        assert_eq!(layout.scroll(2, 0, 3), 0);
+        // Scrolling down stops once the slot's line is the last one
+        assert_eq!(layout.scroll(0, 4, 3), 3);
+        assert_eq!(layout.scroll(1, 2, 3), 1);
+        assert_eq!(layout.scroll(4, 2, 0), 2);
+    }
+}
@@ -1,49 +1,117 @@
 //! Input handling for the TUI.
 
 use anyhow::Result;
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
 use super::app::{App, AppMode};
+use super::keymap::{self, NormalKey, Resolved};
+use super::text_input::TextInput;
 
 /// Handle a terminal event.
 ///
 /// Returns `Ok(true)` if the app should quit, `Ok(false)` to continue.
 pub fn handle_event(app: &mut App, event: Event) -> Result<bool> {
     match event {
+        // Only presses and repeats; releases are reported on some platforms
+        Event::Key(key) if key.kind == KeyEventKind::Release => Ok(false),
         Event::Key(key) => Ok(handle_key(app, key)),
-        Event::Resize(width, _height) => {
-            app.update_columns(width);
+        Event::Resize(width, height) => {
+            app.resize(width, height);
+            Ok(false)
+        }
+        Event::Paste(text) => {
+            handle_paste(app, &text);
             Ok(false)
         }
         _ => Ok(false),
     }
 }
 
+/// Handle a bracketed paste.
+///
+/// The pasted text is inserted as a whole, so digits never trigger quick-run
+/// and multi-word arguments arrive intact. Line breaks become spaces.
+fn handle_paste(app: &mut App, text: &str) {
+    let text = sanitize_paste(text);
+    if text.is_empty() {
+        return;
+    }
+
+    if matches!(app.mode(), AppMode::Normal) {
+        app.set_filter(text.trim().to_string());
+    } else if let Some(mut input) = app.text_input() {
+        input.insert_str(&text);
+        app.apply_text_input(input);
+    }
+}
+
+/// Replace line breaks and other control characters in pasted text.
+fn sanitize_paste(text: &str) -> String {
+    let text = text.trim_end_matches(['\r', '\n']);
+    text.replace("\r\n", " ")
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
 /// Handle a key event.
 ///
 /// Returns true if the app should quit.
 fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    // Ctrl+C stops a script running in the output pane before it quits
+    if (key.code, key.modifiers) == (KeyCode::Char('c'), KeyModifiers::CONTROL)
+        && app.pane_run().is_some_and(|pane| !pane.is_finished())
+    {
+        app.stop_pane();
+        return false;
+    }
+
     // Global quit shortcuts (except in text input modes)
     if matches!(
         (key.code, key.modifiers),
         (KeyCode::Char('c'), KeyModifiers::CONTROL)
-    ) && !matches!(app.mode(), AppMode::Filter { .. } | AppMode::Args { .. })
-    {
+    ) && !matches!(
+        app.mode(),
+        AppMode::Filter { .. }
+            | AppMode::Args { .. }
+            | AppMode::NewScript { .. }
+            | AppMode::RenameScript { .. }
+            | AppMode::GlobalSearch { .. }
+    ) {
         app.quit();
         return true;
     }
 
+    // The debug overlay stays open in every mode
+    if key.code == KeyCode::F(12) {
+        app.toggle_profile();
+        return false;
+    }
+
+    let key = keymap::translate(app.config().keybindings.preset, key);
     match app.mode().clone() {
         AppMode::Normal => handle_normal_mode(app, key),
         AppMode::Filter { query } => handle_filter_mode(app, key, &query),
         AppMode::Help => handle_help_mode(app, key),
+        AppMode::Details
+        | AppMode::Preview
+        | AppMode::Source { .. }
+        | AppMode::Dependencies { .. }
+        | AppMode::ProjectInfo { .. }
+        | AppMode::Stats { .. } => handle_details_mode(app, key),
         AppMode::Error { .. } => handle_error_mode(app, key),
         AppMode::MultiSelect { selected } => handle_multiselect_mode(app, key, &selected),
-        AppMode::Args {
-            script_index,
-            input,
-        } => handle_args_mode(app, key, script_index, &input),
+        AppMode::Args { input, .. } => handle_args_mode(app, key, &input),
+        AppMode::NewScript { .. } => handle_script_input_mode(app, key, App::submit_new_script),
+        AppMode::RenameScript { .. } => handle_script_input_mode(app, key, App::submit_rename),
+        AppMode::ConfirmEdit { .. } => handle_confirm_edit_mode(app, key),
+        AppMode::ConfirmRun { .. } => handle_confirm_run_mode(app, key),
+        AppMode::InstallPrompt { .. } => handle_install_prompt_mode(app, key),
+        AppMode::KeyConflicts { .. } => handle_key_conflicts_mode(app, key),
         AppMode::WorkspaceSelect => handle_workspace_select_mode(app, key),
+        AppMode::Categories => handle_categories_mode(app, key),
+        AppMode::GlobalSearch { query, .. } => handle_global_search_mode(app, key, &query),
+        AppMode::Running => handle_running_mode(app, key),
     }
 
     app.should_quit()
@@ -61,14 +129,76 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
 ///
 /// Actions:
 /// - Enter/o: run selected script
-/// - 1-9: run numbered script
+/// - r: run selected script in the output pane
+/// - Ctrl+Enter: run selected script with its last arguments
+/// - Shift+Enter: edit the last arguments before running
+/// - digits: run numbered script (for 10+ items, type more digits or Enter)
+/// - Alt+1-9: select numbered script without running
 /// - /: enter filter mode
 /// - s: cycle sort mode
 /// - a: enter args mode
 /// - m: enter multi-select mode
+/// - f: pin or unpin the selected script
+/// - v: view full script details
+/// - z: preview the command full screen
+/// - p: view the script's source in package.json
+/// - d: show the scripts the selected script runs
+/// - c: browse categories
+/// - F: search the scripts of every workspace (for monorepos)
 /// - ?: toggle help
 /// - q/Ctrl+C: quit
+/// - Esc: clear filter or category, leave workspace, or quit (see `general.esc_quits`)
+///
+/// These are the vim preset keys; other presets are translated to them first
+/// (see [`keymap::translate_normal`]). Keys bound in the config come before
+/// either, and replace the default keys of their actions.
 fn handle_normal_mode(app: &mut App, key: KeyEvent) {
+    // Esc cancels a chord in progress
+    if key.code == KeyCode::Esc && app.clear_pending_keys() {
+        return;
+    }
+
+    let key = match app.resolve_key(key) {
+        Resolved::Action(action) => keymap::action_key(action),
+        Resolved::Pending => return,
+        Resolved::Unbound => match keymap::translate_normal(app.keymap().preset(), key) {
+            NormalKey::Key(key) if app.keymap().allows_default(key) => key,
+            NormalKey::Key(_) => return,
+            NormalKey::Type(c) => {
+                app.clear_pending_number();
+                app.set_filter(c.to_string());
+                return;
+            }
+        },
+    };
+
+    // Any non-digit key ends a multi-digit quick select; Esc only cancels it
+    if !matches!(key.code, KeyCode::Char(c) if c.is_ascii_digit()) {
+        let was_pending = app.pending_number().is_some();
+        app.clear_pending_number();
+        if was_pending && key.code == KeyCode::Esc {
+            return;
+        }
+    }
+
+    // Modifier-rich bindings (distinguishable with the kitty keyboard protocol)
+    match (key.code, key.modifiers) {
+        (KeyCode::Enter, KeyModifiers::CONTROL) => {
+            app.run_with_last_args();
+            return;
+        }
+        (KeyCode::Enter, KeyModifiers::SHIFT) => {
+            app.enter_args_mode_with_last();
+            return;
+        }
+        (KeyCode::Char(c), KeyModifiers::ALT) if c.is_ascii_digit() && c != '0' => {
+            let num = c.to_digit(10).unwrap() as usize;
+            app.select_by_number(num);
+            return;
+        }
+        _ => {}
+    }
+
     match key.code {
         // Navigation
         KeyCode::Up | KeyCode::Char('k') => app.move_up(),
@@ -83,10 +213,15 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             app.run_selected();
         }
 
-        // Quick select (1-9)
-        KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
-            let num = c.to_digit(10).unwrap() as usize;
-            app.run_numbered(num);
+        // Run selected script in the output pane
+        KeyCode::Char('r') => {
+            app.run_in_pane();
+        }
+
+        // Quick select: runs once the number is unambiguous, else Enter runs it
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            let digit = c.to_digit(10).unwrap() as usize;
+            app.push_number_digit(digit);
         }
 
         // Enter filter mode
@@ -101,6 +236,11 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             app.cycle_sort_mode();
         }
 
+        // Collapse or expand a category group
+        KeyCode::Tab => {
+            app.toggle_group();
+        }
+
         // Enter args mode
         KeyCode::Char('a') => {
             app.enter_args_mode();
@@ -111,6 +251,67 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             app.toggle_multi_select();
         }
 
+        // Pin or unpin the selected script
+        KeyCode::Char('f') => {
+            app.toggle_pinned();
+        }
+
+        // View full details
+        KeyCode::Char('v') => {
+            app.toggle_details();
+        }
+
+        // Preview the command full screen
+        KeyCode::Char('z') => {
+            app.toggle_preview();
+        }
+
+        // View the script's source in package.json
+        KeyCode::Char('p') => {
+            app.toggle_source();
+        }
+
+        // Show the scripts it runs
+        KeyCode::Char('d') => {
+            app.toggle_dependencies();
+        }
+
+        // Show what the package provides besides scripts
+        KeyCode::Char('i') => {
+            app.toggle_project_info();
+        }
+
+        // Show usage statistics
+        KeyCode::Char('u') => {
+            app.toggle_stats();
+        }
+
+        // Add, rename or remove a script
+        KeyCode::Char('n') => {
+            app.enter_new_script_mode();
+        }
+        KeyCode::Char('e') => {
+            app.enter_rename_mode();
+        }
+        KeyCode::Char('x') => {
+            app.remove_selected();
+        }
+
+        // Run in every workspace that defines it
+        KeyCode::Char('A') => {
+            app.run_in_all_workspaces();
+        }
+
+        // Rerun on file changes
+        KeyCode::Char('W') => {
+            app.watch_selected();
+        }
+
+        // Category summary
+        KeyCode::Char('c') => {
+            app.toggle_categories();
+        }
+
         // Help
         KeyCode::Char('?') => {
             app.toggle_help();
@@ -121,11 +322,26 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             app.quit();
         }
 
+        // Clear filter, leave workspace, then quit
+        KeyCode::Esc => {
+            app.escape();
+        }
+
         // Back to workspace selection (for monorepos)
         KeyCode::Char('w') if app.is_monorepo() => {
             app.back_to_workspace_select();
         }
 
+        // Yank current workspace path (for monorepos)
+        KeyCode::Char('y') if app.is_monorepo() => {
+            app.yank_workspace_path();
+        }
+
+        // Search the scripts of every workspace (for monorepos)
+        KeyCode::Char('F') if app.is_monorepo() => {
+            app.enter_global_search();
+        }
+
         _ => {}
     }
 }
@@ -139,9 +355,14 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
 /// - →/l: move right
 ///
 /// Actions:
-/// - Enter: select workspace and show its scripts
+/// - Enter: select workspace and show its scripts, or list the workspaces
+///   of a nested monorepo
+/// - Backspace: back up from a nested monorepo
 /// - 1-9: quick select workspace
-/// - q/Esc: quit
+/// - F: search the scripts of every workspace
+/// - y: print workspace path and quit
+/// - Esc: back up from a nested monorepo, or quit
+/// - q: quit
 fn handle_workspace_select_mode(app: &mut App, key: KeyEvent) {
     match key.code {
         // Navigation
@@ -161,13 +382,31 @@ fn handle_workspace_select_mode(app: &mut App, key: KeyEvent) {
             app.select_workspace_by_number(num);
         }
 
+        // Yank workspace path
+        KeyCode::Char('y') => {
+            app.yank_workspace_path();
+        }
+
+        // Search the scripts of every workspace
+        KeyCode::Char('F') => {
+            app.enter_global_search();
+        }
+
+        // Back up from a nested monorepo; Esc quits at the top level
+        KeyCode::Backspace | KeyCode::Esc => {
+            let went_up = app.workspace_level_up();
+            if !went_up && key.code == KeyCode::Esc {
+                app.quit();
+            }
+        }
+
         // Help
         KeyCode::Char('?') => {
             app.toggle_help();
         }
 
         // Quit
-        KeyCode::Char('q') | KeyCode::Esc => {
+        KeyCode::Char('q') => {
             app.quit();
         }
 
@@ -175,13 +414,101 @@ fn handle_workspace_select_mode(app: &mut App, key: KeyEvent) {
     }
 }
 
+/// Handle keys in the output pane.
+///
+/// - ↑/k, ↓/j: scroll one line
+/// - PageUp/PageDown: scroll one page
+/// - g/G: scroll to the first line / follow the end
+/// - r: run the script again
+/// - Ctrl+C: stop the script (handled in [`handle_key`])
+/// - Esc/q/b: stop the script and go back to the list
+fn handle_running_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        // Scrolling
+        KeyCode::Up | KeyCode::Char('k') => app.pane_scroll_up(1),
+        KeyCode::Down | KeyCode::Char('j') => app.pane_scroll_down(1),
+        KeyCode::PageUp => app.pane_scroll_up(app.pane_page_size()),
+        KeyCode::PageDown => app.pane_scroll_down(app.pane_page_size()),
+        KeyCode::Home | KeyCode::Char('g') => app.pane_scroll_to_top(),
+        KeyCode::End | KeyCode::Char('G') => app.pane_scroll_to_bottom(),
+
+        // Re-run
+        KeyCode::Char('r') => {
+            app.rerun_pane();
+        }
+
+        // Back to the list
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('b') => {
+            app.close_pane();
+        }
+
+        _ => {}
+    }
+}
+
+/// Handle keys in the category summary.
+///
+/// - ↑/k: move up
+/// - ↓/j: move down
+/// - Enter: show the scripts of the highlighted category
+/// - 1-9: quick select category (1 = all scripts)
+/// - c/q/Esc: close
+fn handle_categories_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        // Navigation
+        KeyCode::Up | KeyCode::Char('k') => app.category_move_up(),
+        KeyCode::Down | KeyCode::Char('j') => app.category_move_down(),
+
+        // Drill into category
+        KeyCode::Enter => {
+            app.select_current_category();
+        }
+
+        // Quick select (1-9)
+        KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
+            let num = c.to_digit(10).unwrap() as usize;
+            app.select_category_by_number(num);
+        }
+
+        // Close
+        KeyCode::Char('c') | KeyCode::Char('q') | KeyCode::Esc => {
+            app.toggle_categories();
+        }
+
+        _ => {}
+    }
+}
+
+/// Handle keys while searching the scripts of every workspace.
+///
+/// - Printable characters and editing keys: edit the query (see [`edit_input`])
+/// - ↑/↓: move between matches
+/// - Enter: show the workspace of the highlighted match, with it selected
+/// - Escape: close the search
+fn handle_global_search_mode(app: &mut App, key: KeyEvent, current_query: &str) {
+    match key.code {
+        KeyCode::Esc => app.close_global_search(),
+        KeyCode::Enter => app.open_global_match(),
+        KeyCode::Up => app.global_search_move(false),
+        KeyCode::Down => app.global_search_move(true),
+        _ => {
+            let mut input = TextInput::with_cursor(current_query, app.input_cursor());
+            if edit_input(&mut input, key) {
+                app.apply_text_input(input);
+            }
+        }
+    }
+}
+
 /// Handle keys in filter mode.
 ///
-/// - Printable characters: append to filter
-/// - Backspace: remove last character
+/// - Printable characters (digits included): insert at the cursor
+/// - Alt+1-9: select numbered script
+/// - Backspace/Delete, Ctrl+W, Ctrl+U: edit the filter (see [`edit_input`])
+/// - ←/→/Home/End: move the cursor
 /// - Escape: clear filter and exit filter mode
 /// - Enter: run first visible script
-/// - Navigation keys still work while filtering
+/// - ↑/↓: recall previous filters (from an empty filter), otherwise navigate
 fn handle_filter_mode(app: &mut App, key: KeyEvent, current_query: &str) {
     match key.code {
         // Exit filter mode
@@ -195,39 +522,73 @@ fn handle_filter_mode(app: &mut App, key: KeyEvent, current_query: &str) {
             app.run_selected();
         }
 
-        // Remove last character
-        KeyCode::Backspace => {
-            let mut query = current_query.to_string();
-            query.pop();
-            if query.is_empty() {
-                app.clear_filter();
-                app.set_mode(AppMode::Normal);
-            } else {
-                app.set_filter(query);
+        // Filter history recall, falling back to navigation (←/→ move the cursor)
+        KeyCode::Up => {
+            if !app.recall_older_filter() {
+                app.move_up();
+            }
+        }
+        KeyCode::Down => {
+            if !app.recall_newer_filter() {
+                app.move_down();
             }
         }
 
-        // Navigation in filter mode (arrow keys)
-        KeyCode::Up => app.move_up(),
-        KeyCode::Down => app.move_down(),
-        KeyCode::Left => app.move_left(),
-        KeyCode::Right => app.move_right(),
-
-        // Append character (this must come after arrow keys)
-        KeyCode::Char(c) => {
-            // Quick select still works with empty filter and digits
-            if c.is_ascii_digit() && c != '0' && current_query.is_empty() {
-                let num = c.to_digit(10).unwrap() as usize;
-                app.run_numbered(num);
-            } else {
-                let mut query = current_query.to_string();
-                query.push(c);
-                app.set_filter(query);
+        // Digits type into the filter; Alt+digit selects a numbered script
+        KeyCode::Char(c)
+            if c.is_ascii_digit() && c != '0' && key.modifiers == KeyModifiers::ALT =>
+        {
+            let num = c.to_digit(10).unwrap() as usize;
+            app.select_by_number(num);
+        }
+
+        _ => {
+            let mut input = TextInput::with_cursor(current_query, app.input_cursor());
+            if edit_input(&mut input, key) {
+                if key.code == KeyCode::Backspace && input.text().is_empty() {
+                    app.clear_filter();
+                    app.set_mode(AppMode::Normal);
+                } else {
+                    app.apply_text_input(input);
+                }
             }
         }
+    }
+}
 
-        _ => {}
+/// Apply a line-editing key to a text input.
+///
+/// - ←/→: move the cursor by one grapheme
+/// - Home/End (or Ctrl+A/Ctrl+E): jump to the start/end
+/// - Backspace/Delete: delete the grapheme before/under the cursor
+/// - Ctrl+W: delete the previous word
+/// - Ctrl+U: delete everything before the cursor
+/// - Printable characters: insert at the cursor
+///
+/// Returns true if the key was an editing key.
+pub(super) fn edit_input(input: &mut TextInput, key: KeyEvent) -> bool {
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('w') => input.delete_word_backward(),
+            KeyCode::Char('u') => input.delete_to_start(),
+            KeyCode::Char('a') => input.move_home(),
+            KeyCode::Char('e') => input.move_end(),
+            _ => return false,
+        }
+        return true;
+    }
+
+    match key.code {
+        KeyCode::Left => input.move_left(),
+        KeyCode::Right => input.move_right(),
+        KeyCode::Home => input.move_home(),
+        KeyCode::End => input.move_end(),
+        KeyCode::Backspace => input.delete_backward(),
+        KeyCode::Delete => input.delete_forward(),
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::ALT) => input.insert_char(c),
+        _ => return false,
     }
+    true
 }
 
 /// Handle keys in help mode.
@@ -249,11 +610,27 @@ fn handle_help_mode(app: &mut App, key: KeyEvent) {
     }
 }
 
+/// Handle keys in the details, source and dependencies popovers.
+///
+/// Any key closes the popover.
+fn handle_details_mode(app: &mut App, _key: KeyEvent) {
+    app.set_mode(AppMode::Normal);
+}
+
 /// Handle keys in error mode.
 ///
-/// Any key dismisses the error.
-fn handle_error_mode(app: &mut App, _key: KeyEvent) {
-    // Any key dismisses the error
+/// - 1-9: run the numbered recovery action
+/// - Any other key dismisses the error
+fn handle_error_mode(app: &mut App, key: KeyEvent) {
+    if let KeyCode::Char(c) = key.code {
+        if let Some(num) = c.to_digit(10) {
+            if app.run_error_action(num as usize) {
+                return;
+            }
+        }
+    }
+
+    // Any other key dismisses the error
     app.set_mode(AppMode::Normal);
 }
 
@@ -315,11 +692,10 @@ fn handle_multiselect_mode(
 
 /// Handle keys in args input mode.
 ///
-/// - Printable characters: append to args input
-/// - Backspace: remove last character
+/// - Printable characters and editing keys: edit the input (see [`edit_input`])
 /// - Enter: run script with args
 /// - Escape: cancel and return to normal mode
-fn handle_args_mode(app: &mut App, key: KeyEvent, script_index: usize, current_input: &str) {
+fn handle_args_mode(app: &mut App, key: KeyEvent, current_input: &str) {
     match key.code {
         // Cancel and return to normal mode
         KeyCode::Esc => {
@@ -331,26 +707,81 @@ fn handle_args_mode(app: &mut App, key: KeyEvent, script_index: usize, current_i
             app.run_with_args(current_input.to_string());
         }
 
-        // Remove last character
-        KeyCode::Backspace => {
-            let mut input = current_input.to_string();
-            input.pop();
-            app.set_mode(AppMode::Args {
-                script_index,
-                input,
-            });
+        // Previous arguments for this script
+        KeyCode::Up => {
+            app.recall_older_args();
+        }
+        KeyCode::Down => {
+            app.recall_newer_args();
         }
 
-        // Append character
-        KeyCode::Char(c) => {
-            let mut input = current_input.to_string();
-            input.push(c);
-            app.set_mode(AppMode::Args {
-                script_index,
-                input,
-            });
+        // Complete a flag passed to this script before
+        KeyCode::Tab => {
+            app.complete_args_flag();
+        }
+
+        _ => {
+            let mut input = TextInput::with_cursor(current_input, app.input_cursor());
+            if edit_input(&mut input, key) {
+                app.apply_text_input(input);
+            }
         }
+    }
+}
 
+/// Handle keys while typing a script's name or command, submitting with Enter.
+fn handle_script_input_mode(app: &mut App, key: KeyEvent, submit: fn(&mut App)) {
+    match key.code {
+        KeyCode::Esc => app.set_mode(AppMode::Normal),
+        KeyCode::Enter => submit(app),
+        _ => {
+            if let Some(mut input) = app.text_input() {
+                if edit_input(&mut input, key) {
+                    app.apply_text_input(input);
+                }
+            }
+        }
+    }
+}
+
+/// Handle keys while confirming a rename or removal.
+fn handle_confirm_edit_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => app.confirm_edit(),
+        KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc => app.set_mode(AppMode::Normal),
+        _ => {}
+    }
+}
+
+/// Handle key events while asking before a run.
+///
+/// Enter doesn't confirm, so a double press can't run a dangerous script.
+fn handle_confirm_run_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_run(),
+        KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc => app.cancel_run(),
+        _ => {}
+    }
+}
+
+/// Handle keys in the overlay offering to install missing dependencies.
+fn handle_install_prompt_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('i') => app.install_first(),
+        KeyCode::Char('n') | KeyCode::Char('r') => app.skip_install(),
+        KeyCode::Char('q') | KeyCode::Esc => app.cancel_run(),
+        _ => {}
+    }
+}
+
+/// Handle keys in the key conflicts overlay shown at startup.
+///
+/// These keys are fixed, since the user's bindings are what's broken.
+fn handle_key_conflicts_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('r') => app.restore_default_keys(),
+        KeyCode::Char('e') => app.edit_key_bindings(),
+        KeyCode::Char('c') | KeyCode::Esc => app.close_key_conflicts(),
         _ => {}
     }
 }
@@ -358,9 +789,10 @@ fn handle_args_mode(app: &mut App, key: KeyEvent, script_index: usize, current_i
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::Config;
+    use crate::config::{Config, KeyAction, SortMode};
     use crate::history::History;
-    use crate::package::{Runner, Script, Scripts};
+    use crate::package::{Runner, Script, ScriptEdit, Scripts};
+    use crate::tui::ErrorAction;
     use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
     use std::path::PathBuf;
 
@@ -442,6 +874,196 @@ mod tests {
         assert!(after_j > 0 || app.columns() == 1);
     }
 
+    #[test]
+    fn test_preview_mode_open_and_close() {
+        let mut app = create_test_app();
+        handle_key(&mut app, key_event(KeyCode::Char('z')));
+        assert_eq!(app.mode(), &AppMode::Preview);
+
+        handle_key(&mut app, key_event(KeyCode::Char('j')));
+        assert_eq!(app.mode(), &AppMode::Normal);
+    }
+
+    #[test]
+    fn test_f12_toggles_profile_in_any_mode() {
+        let mut app = create_test_app();
+        app.toggle_filter_mode();
+
+        handle_key(&mut app, key_event(KeyCode::F(12)));
+        assert!(app.is_profile_shown());
+        assert!(matches!(app.mode(), AppMode::Filter { .. }));
+        assert_eq!(app.filter_text(), "");
+
+        handle_key(&mut app, key_event(KeyCode::F(12)));
+        assert!(!app.is_profile_shown());
+    }
+
+    #[test]
+    fn test_emacs_preset_types_into_filter() {
+        let mut config = Config::default();
+        config.keybindings.preset = crate::config::KeymapPreset::Emacs;
+        let mut app = App::new(
+            create_test_scripts(),
+            config,
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        let sort_mode = app.sort_mode();
+
+        // Letters that are actions in the vim preset start a filter
+        handle_key(&mut app, key_event(KeyCode::Char('s')));
+        assert_eq!(app.sort_mode(), sort_mode);
+        handle_key(&mut app, key_event(KeyCode::Char('t')));
+        assert_eq!(app.filter_text(), "st");
+
+        // Ctrl+G leaves the filter, Alt+S cycles the sort mode
+        handle_key(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Char('g'), KeyModifiers::CONTROL),
+        );
+        assert_eq!(app.mode(), &AppMode::Normal);
+        handle_key(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Char('s'), KeyModifiers::ALT),
+        );
+        assert_ne!(app.sort_mode(), sort_mode);
+    }
+
+    fn create_app_with_bindings(bindings: &[(KeyAction, &str)], vim_keys: bool) -> App {
+        let mut config = Config::default();
+        config.keybindings.vim_keys = vim_keys;
+        for (action, key) in bindings {
+            config
+                .keybindings
+                .bindings
+                .entry(*action)
+                .or_default()
+                .push(key.to_string());
+        }
+        App::new(
+            create_test_scripts(),
+            config,
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        )
+    }
+
+    #[test]
+    fn test_rebound_action_replaces_default_key() {
+        let mut app = create_app_with_bindings(&[(KeyAction::Sort, "S")], true);
+        let sort_mode = app.sort_mode();
+
+        handle_key(&mut app, key_event(KeyCode::Char('s')));
+        assert_eq!(app.sort_mode(), sort_mode);
+        handle_key(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Char('S'), KeyModifiers::SHIFT),
+        );
+        assert_ne!(app.sort_mode(), sort_mode);
+
+        // Other defaults are untouched
+        handle_key(&mut app, key_event(KeyCode::Char('/')));
+        assert!(matches!(app.mode(), AppMode::Filter { .. }));
+    }
+
+    #[test]
+    fn test_chord_binding() {
+        let mut app = create_app_with_bindings(
+            &[(KeyAction::First, "g g"), (KeyAction::Quit, "Space q")],
+            true,
+        );
+        handle_key(&mut app, key_event(KeyCode::End));
+        let last = app.selected_index();
+
+        handle_key(&mut app, key_event(KeyCode::Char('g')));
+        assert_eq!(app.selected_index(), last);
+        assert_eq!(app.pending_keys().len(), 1);
+        handle_key(&mut app, key_event(KeyCode::Char('g')));
+        assert_eq!(app.selected_index(), 0);
+        assert!(app.pending_keys().is_empty());
+
+        // Esc cancels a chord, and a broken chord handles the key on its own
+        handle_key(&mut app, key_event(KeyCode::Char(' ')));
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert!(app.pending_keys().is_empty());
+        assert!(!app.should_quit());
+        handle_key(&mut app, key_event(KeyCode::Char(' ')));
+        handle_key(&mut app, key_event(KeyCode::Char('j')));
+        assert_eq!(app.selected_index(), 1.min(app.visible_count() - 1));
+        assert!(!handle_key(&mut app, key_event(KeyCode::Char('q'))));
+        handle_key(&mut app, key_event(KeyCode::Char(' ')));
+        assert!(handle_key(&mut app, key_event(KeyCode::Char('q'))));
+    }
+
+    #[test]
+    fn test_key_conflicts_restore_defaults() {
+        let mut app =
+            create_app_with_bindings(&[(KeyAction::Run, "?"), (KeyAction::Help, "?")], true);
+        assert_eq!(
+            app.mode(),
+            &AppMode::KeyConflicts {
+                actions: vec![KeyAction::Help]
+            }
+        );
+
+        // Bound keys don't apply in the overlay
+        handle_key(&mut app, key_event(KeyCode::Char('?')));
+        assert!(matches!(app.mode(), AppMode::KeyConflicts { .. }));
+        handle_key(&mut app, key_event(KeyCode::Char('r')));
+        assert_eq!(app.mode(), &AppMode::Normal);
+
+        handle_key(&mut app, key_event(KeyCode::Char('?')));
+        assert_eq!(app.mode(), &AppMode::Help);
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        handle_key(&mut app, key_event(KeyCode::Enter));
+        assert!(app.should_quit());
+        assert!(app.script_to_run().is_some());
+    }
+
+    #[test]
+    fn test_key_conflicts_edit_config() {
+        let mut config = Config::default();
+        config.general.esc_quits = Some(false);
+        config.keybindings.bindings.insert(KeyAction::Quit, vec![]);
+        let mut app = App::new(
+            create_test_scripts(),
+            config,
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        assert_eq!(
+            app.mode(),
+            &AppMode::KeyConflicts {
+                actions: vec![KeyAction::Quit]
+            }
+        );
+
+        handle_key(&mut app, key_event(KeyCode::Char('e')));
+        assert_eq!(app.requested_action(), Some(ErrorAction::OpenConfig));
+        assert!(app.should_quit());
+    }
+
+    #[test]
+    fn test_vim_keys_disabled() {
+        let mut app = create_app_with_bindings(&[(KeyAction::Details, "j")], false);
+
+        handle_key(&mut app, key_event(KeyCode::Char('k')));
+        handle_key(&mut app, key_event(KeyCode::Char('G')));
+        assert_eq!(app.selected_index(), 0);
+        handle_key(&mut app, key_event(KeyCode::Down));
+        assert_eq!(app.selected_index(), 1.min(app.visible_count() - 1));
+
+        // Freed keys can be bound to other actions
+        handle_key(&mut app, key_event(KeyCode::Char('j')));
+        assert_eq!(app.mode(), &AppMode::Details);
+    }
+
     #[test]
     fn test_normal_mode_navigation_home_end() {
         let mut app = create_test_app();
@@ -492,6 +1114,93 @@ mod tests {
         assert_eq!(app.selected_index(), 2);
     }
 
+    #[test]
+    fn test_normal_mode_multi_digit_quick_select() {
+        let mut scripts = Scripts::new();
+        for i in 1..=12 {
+            scripts.add(Script::new(format!("task{:02}", i), "echo"));
+        }
+        let mut app = App::new(
+            scripts,
+            Config::default(),
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        app.set_sort_mode(SortMode::Alpha);
+
+        // "1" could still become 10-12, so it only selects
+        handle_normal_mode(&mut app, key_event(KeyCode::Char('1')));
+        assert!(!app.should_quit());
+        assert_eq!(app.pending_number(), Some(1));
+        assert_eq!(app.selected_index(), 0);
+
+        // "12" is unambiguous and runs
+        handle_normal_mode(&mut app, key_event(KeyCode::Char('2')));
+        assert!(app.should_quit());
+        assert_eq!(app.script_to_run().unwrap().script.name(), "task12");
+    }
+
+    #[test]
+    fn test_normal_mode_pending_number_enter_and_escape() {
+        let mut scripts = Scripts::new();
+        for i in 1..=12 {
+            scripts.add(Script::new(format!("task{:02}", i), "echo"));
+        }
+        let mut app = App::new(
+            scripts,
+            Config::default(),
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+        app.set_sort_mode(SortMode::Alpha);
+
+        handle_normal_mode(&mut app, key_event(KeyCode::Char('1')));
+        handle_normal_mode(&mut app, key_event(KeyCode::Esc));
+        assert_eq!(app.pending_number(), None);
+        assert!(!app.should_quit());
+
+        // A fresh "1" followed by Enter runs script 1
+        handle_normal_mode(&mut app, key_event(KeyCode::Char('1')));
+        handle_normal_mode(&mut app, key_event(KeyCode::Enter));
+        assert_eq!(app.script_to_run().unwrap().script.name(), "task01");
+    }
+
+    #[test]
+    fn test_normal_mode_escape_clears_filter_then_quits() {
+        let mut app = create_test_app();
+        app.set_filter("dev".to_string());
+        app.set_mode(AppMode::Normal);
+
+        handle_normal_mode(&mut app, key_event(KeyCode::Esc));
+        assert_eq!(app.filter_text(), "");
+        assert!(!app.should_quit());
+
+        handle_normal_mode(&mut app, key_event(KeyCode::Esc));
+        assert!(app.should_quit());
+        assert!(app.script_to_run().is_none());
+    }
+
+    #[test]
+    fn test_normal_mode_escape_quit_disabled() {
+        let mut config = Config::default();
+        config.general.esc_quits = Some(false);
+        let mut app = App::new(
+            create_test_scripts(),
+            config,
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test/project"),
+            Runner::Npm,
+        );
+
+        handle_normal_mode(&mut app, key_event(KeyCode::Esc));
+        assert!(!app.should_quit());
+    }
+
     #[test]
     fn test_normal_mode_enter_filter() {
         let mut app = create_test_app();
@@ -635,15 +1344,72 @@ mod tests {
     }
 
     #[test]
-    fn test_filter_mode_quick_select_empty_query() {
+    fn test_filter_mode_digits_type_into_filter() {
         let mut app = create_test_app();
         app.set_mode(AppMode::Filter {
             query: String::new(),
         });
 
         handle_filter_mode(&mut app, key_event(KeyCode::Char('2')), "");
-        assert!(app.should_quit());
+        assert!(!app.should_quit());
+        assert_eq!(app.filter_text(), "2");
+    }
+
+    #[test]
+    fn test_filter_mode_alt_digit_selects() {
+        let mut app = create_test_app();
+        app.set_mode(AppMode::Filter {
+            query: String::new(),
+        });
+
+        handle_filter_mode(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Char('2'), KeyModifiers::ALT),
+            "",
+        );
+        assert!(!app.should_quit());
         assert_eq!(app.selected_index(), 1);
+        assert_eq!(app.filter_text(), "");
+    }
+
+    #[test]
+    fn test_filter_mode_cursor_editing() {
+        let mut app = create_test_app();
+        app.set_filter("bild".to_string());
+
+        handle_key(&mut app, key_event(KeyCode::Left));
+        handle_key(&mut app, key_event(KeyCode::Left));
+        handle_key(&mut app, key_event(KeyCode::Left));
+        handle_key(&mut app, key_event(KeyCode::Char('u')));
+        assert_eq!(app.filter_text(), "build");
+        assert_eq!(app.input_cursor(), 2);
+
+        handle_key(&mut app, key_event(KeyCode::End));
+        handle_key(&mut app, key_event(KeyCode::Char('x')));
+        assert_eq!(app.filter_text(), "buildx");
+    }
+
+    #[test]
+    fn test_filter_mode_arrows_do_not_move_selection() {
+        let mut app = create_test_app();
+        app.set_filter("e".to_string());
+        let selected = app.selected_index();
+
+        handle_key(&mut app, key_event(KeyCode::Right));
+        assert_eq!(app.selected_index(), selected);
+    }
+
+    #[test]
+    fn test_filter_mode_kill_line_keeps_filter_mode() {
+        let mut app = create_test_app();
+        app.set_filter("test".to_string());
+
+        handle_key(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Char('u'), KeyModifiers::CONTROL),
+        );
+        assert_eq!(app.filter_text(), "");
+        assert!(matches!(app.mode(), AppMode::Filter { .. }));
     }
 
     // ==================== Multi-Select Mode Tests ====================
@@ -742,7 +1508,7 @@ mod tests {
         let mut app = create_test_app();
         app.enter_args_mode();
 
-        handle_args_mode(&mut app, key_event(KeyCode::Char('-')), 0, "");
+        handle_args_mode(&mut app, key_event(KeyCode::Char('-')), "");
 
         if let AppMode::Args { input, .. } = app.mode() {
             assert_eq!(input, "-");
@@ -756,9 +1522,9 @@ mod tests {
         let mut app = create_test_app();
         app.enter_args_mode();
 
-        handle_args_mode(&mut app, key_event(KeyCode::Char('-')), 0, "");
-        handle_args_mode(&mut app, key_event(KeyCode::Char('-')), 0, "-");
-        handle_args_mode(&mut app, key_event(KeyCode::Char('w')), 0, "--");
+        handle_args_mode(&mut app, key_event(KeyCode::Char('-')), "");
+        handle_args_mode(&mut app, key_event(KeyCode::Char('-')), "-");
+        handle_args_mode(&mut app, key_event(KeyCode::Char('w')), "--");
 
         if let AppMode::Args { input, .. } = app.mode() {
             assert_eq!(input, "--w");
@@ -775,7 +1541,7 @@ mod tests {
             input: "--watch".to_string(),
         });
 
-        handle_args_mode(&mut app, key_event(KeyCode::Backspace), 0, "--watch");
+        handle_args_mode(&mut app, key_event(KeyCode::Backspace), "--watch");
 
         if let AppMode::Args { input, .. } = app.mode() {
             assert_eq!(input, "--watc");
@@ -792,7 +1558,7 @@ mod tests {
             input: "--watch".to_string(),
         });
 
-        handle_args_mode(&mut app, key_event(KeyCode::Enter), 0, "--watch");
+        handle_args_mode(&mut app, key_event(KeyCode::Enter), "--watch");
         assert!(app.should_quit());
 
         let run = app.script_to_run().unwrap();
@@ -807,11 +1573,57 @@ mod tests {
             input: "--watch".to_string(),
         });
 
-        handle_args_mode(&mut app, key_event(KeyCode::Esc), 0, "--watch");
+        handle_args_mode(&mut app, key_event(KeyCode::Esc), "--watch");
         assert!(matches!(app.mode(), AppMode::Normal));
         assert!(!app.should_quit());
     }
 
+    #[test]
+    fn test_args_mode_delete_word() {
+        let mut app = create_test_app();
+        app.set_mode(AppMode::Args {
+            script_index: 0,
+            input: "--watch --coverage".to_string(),
+        });
+
+        handle_key(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Char('w'), KeyModifiers::CONTROL),
+        );
+        assert!(matches!(app.mode(), AppMode::Args { input, .. } if input == "--watch "));
+    }
+
+    #[test]
+    fn test_args_mode_home_insert_and_delete() {
+        let mut app = create_test_app();
+        app.set_mode(AppMode::Args {
+            script_index: 0,
+            input: "watch".to_string(),
+        });
+
+        handle_key(&mut app, key_event(KeyCode::Home));
+        handle_key(&mut app, key_event(KeyCode::Char('-')));
+        handle_key(&mut app, key_event(KeyCode::Char('-')));
+        handle_key(&mut app, key_event(KeyCode::Delete));
+        assert!(matches!(app.mode(), AppMode::Args { input, .. } if input == "--atch"));
+        assert_eq!(app.input_cursor(), 2);
+    }
+
+    #[test]
+    fn test_paste_inserts_at_cursor() {
+        let mut app = create_test_app();
+        app.set_mode(AppMode::Args {
+            script_index: 0,
+            input: "-- --watch".to_string(),
+        });
+
+        handle_key(&mut app, key_event(KeyCode::Home));
+        handle_key(&mut app, key_event(KeyCode::Right));
+        handle_key(&mut app, key_event(KeyCode::Right));
+        handle_event(&mut app, Event::Paste(" --ci".to_string())).unwrap();
+        assert!(matches!(app.mode(), AppMode::Args { input, .. } if input == "-- --ci --watch"));
+    }
+
     // ==================== Help Mode Tests ====================
 
     #[test]
@@ -834,17 +1646,125 @@ mod tests {
 
     // ==================== Error Mode Tests ====================
 
+    #[test]
+    fn test_details_mode_open_and_close() {
+        let mut app = create_test_app();
+        handle_key(&mut app, key_event(KeyCode::Char('v')));
+        assert_eq!(app.mode(), &AppMode::Details);
+
+        handle_key(&mut app, key_event(KeyCode::Char('x')));
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert!(!app.should_quit());
+    }
+
+    #[test]
+    fn test_categories_mode_drill_in_and_out() {
+        let mut app = create_test_app();
+        handle_key(&mut app, key_event(KeyCode::Char('c')));
+        assert_eq!(app.mode(), &AppMode::Categories);
+
+        handle_key(&mut app, key_event(KeyCode::Char('j')));
+        handle_key(&mut app, key_event(KeyCode::Enter));
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert!(app.category().is_some());
+
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert_eq!(app.category(), None);
+        assert!(!app.should_quit());
+    }
+
+    #[test]
+    fn test_categories_mode_escape_closes() {
+        let mut app = create_test_app();
+        handle_key(&mut app, key_event(KeyCode::Char('c')));
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert_eq!(app.mode(), &AppMode::Normal);
+        assert_eq!(app.category(), None);
+        assert!(!app.should_quit());
+    }
+
+    #[test]
+    fn test_n_starts_new_script_and_esc_cancels() {
+        let mut app = create_test_app();
+        handle_key(&mut app, key_event(KeyCode::Char('n')));
+        assert!(matches!(app.mode(), AppMode::NewScript { name: None, .. }));
+
+        handle_key(&mut app, key_event(KeyCode::Char('x')));
+        assert!(matches!(app.mode(), AppMode::NewScript { input, .. } if input == "x"));
+
+        handle_key(&mut app, key_event(KeyCode::Esc));
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    #[test]
+    fn test_confirm_edit_cancels_with_n() {
+        let mut app = create_test_app();
+        app.set_mode(AppMode::ConfirmEdit {
+            edit: ScriptEdit::Remove {
+                name: "dev".to_string(),
+            },
+            preview: Vec::new(),
+        });
+
+        handle_key(&mut app, key_event(KeyCode::Char('j')));
+        assert!(matches!(app.mode(), AppMode::ConfirmEdit { .. }));
+        handle_key(&mut app, key_event(KeyCode::Char('n')));
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(app.script_edits().is_empty());
+    }
+
     #[test]
     fn test_error_mode_any_key_dismisses() {
         let mut app = create_test_app();
         app.set_mode(AppMode::Error {
             message: "Test error".to_string(),
+            actions: vec![ErrorAction::Retry],
         });
 
         handle_error_mode(&mut app, key_event(KeyCode::Enter));
         assert!(matches!(app.mode(), AppMode::Normal));
     }
 
+    #[test]
+    fn test_error_mode_retry_runs_failed_script() {
+        let mut app = create_test_app();
+        app.run_selected();
+        let run = app.script_to_run().cloned().unwrap();
+        let name = run.script.name().to_string();
+
+        let mut app = create_test_app();
+        app.set_failed_run(run);
+        app.show_error(
+            "Failed to execute: npm run dev",
+            vec![ErrorAction::Retry, ErrorAction::SwitchRunner],
+        );
+
+        // Switching runner keeps the overlay open
+        handle_error_mode(&mut app, key_event(KeyCode::Char('2')));
+        assert_eq!(app.runner(), Runner::Yarn);
+        assert!(matches!(app.mode(), AppMode::Error { .. }));
+
+        handle_error_mode(&mut app, key_event(KeyCode::Char('1')));
+        assert!(app.should_quit());
+        assert_eq!(app.script_to_run().unwrap().script.name(), name);
+    }
+
+    #[test]
+    fn test_error_mode_open_config_requests_action() {
+        let mut app = create_test_app();
+        app.show_error("Invalid config", vec![ErrorAction::OpenConfig]);
+
+        // Out-of-range numbers just dismiss
+        handle_error_mode(&mut app, key_event(KeyCode::Char('5')));
+        assert!(matches!(app.mode(), AppMode::Normal));
+
+        app.show_error("Invalid config", vec![ErrorAction::OpenConfig]);
+        handle_error_mode(&mut app, key_event(KeyCode::Char('1')));
+        assert!(app.should_quit());
+        assert_eq!(app.requested_action(), Some(ErrorAction::OpenConfig));
+        assert!(app.script_to_run().is_none());
+    }
+
     // ==================== Resize Tests ====================
 
     #[test]
@@ -860,6 +1780,101 @@ mod tests {
 
     // ==================== handle_event Tests ====================
 
+    #[test]
+    fn test_normal_mode_ctrl_enter_runs_with_last_args() {
+        let mut app = create_test_app();
+        handle_key(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Enter, KeyModifiers::CONTROL),
+        );
+        assert!(app.should_quit());
+        assert!(app.script_to_run().is_some());
+    }
+
+    #[test]
+    fn test_normal_mode_shift_enter_edits_args() {
+        let mut app = create_test_app();
+        handle_key(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Enter, KeyModifiers::SHIFT),
+        );
+        assert!(matches!(app.mode(), AppMode::Args { .. }));
+        assert!(!app.should_quit());
+    }
+
+    #[test]
+    fn test_normal_mode_alt_digit_selects() {
+        let mut app = create_test_app();
+        handle_key(
+            &mut app,
+            key_event_with_modifiers(KeyCode::Char('3'), KeyModifiers::ALT),
+        );
+        assert_eq!(app.selected_index(), 2);
+        assert!(!app.should_quit());
+    }
+
+    #[test]
+    fn test_key_release_ignored() {
+        let mut app = create_test_app();
+        let mut key = key_event(KeyCode::Char('q'));
+        key.kind = KeyEventKind::Release;
+        handle_event(&mut app, Event::Key(key)).unwrap();
+        assert!(!app.should_quit());
+    }
+
+    #[test]
+    fn test_paste_in_filter_does_not_quick_run() {
+        let mut app = create_test_app();
+        app.set_mode(AppMode::Filter {
+            query: String::new(),
+        });
+        handle_event(&mut app, Event::Paste("123".to_string())).unwrap();
+        assert!(!app.should_quit());
+        assert!(app.script_to_run().is_none());
+        assert!(matches!(app.mode(), AppMode::Filter { query } if query == "123"));
+    }
+
+    #[test]
+    fn test_paste_in_normal_mode_starts_filter() {
+        let mut app = create_test_app();
+        handle_event(&mut app, Event::Paste(" build\n".to_string())).unwrap();
+        assert!(matches!(app.mode(), AppMode::Filter { query } if query == "build"));
+    }
+
+    #[test]
+    fn test_paste_in_args_mode_appends() {
+        let mut app = create_test_app();
+        handle_key(&mut app, key_event(KeyCode::Char('a')));
+        handle_key(&mut app, key_event(KeyCode::Char('-')));
+        handle_event(&mut app, Event::Paste("-watch --coverage\n".to_string())).unwrap();
+        assert!(matches!(app.mode(), AppMode::Args { input, .. } if input == "--watch --coverage"));
+    }
+
+    #[test]
+    fn test_sanitize_paste() {
+        assert_eq!(sanitize_paste("a\r\nb\tc\n"), "a b c");
+        assert_eq!(sanitize_paste("\n"), "");
+    }
+
+    #[test]
+    fn test_resize_preserves_args_input() {
+        let mut app = create_test_app();
+        handle_key(&mut app, key_event(KeyCode::Char('a')));
+        handle_key(&mut app, key_event(KeyCode::Char('-')));
+        handle_key(&mut app, key_event(KeyCode::Char('w')));
+
+        handle_event(&mut app, Event::Resize(45, 12)).unwrap();
+        handle_event(&mut app, Event::Resize(120, 40)).unwrap();
+
+        assert_eq!(
+            app.mode(),
+            &AppMode::Args {
+                script_index: 0,
+                input: "-w".to_string()
+            }
+        );
+    }
+
     #[test]
     fn test_handle_event_key() {
         let mut app = create_test_app();
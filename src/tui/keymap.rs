@@ -0,0 +1,819 @@
+//! Keymap presets and user key bindings.
+//!
+//! Input handling is written against the vim-style bindings. The emacs and
+//! plain presets translate their keys to those, and send printable keys in
+//! the script list straight to the filter instead of treating them as actions.
+//!
+//! Keys bound in `[keybindings]` are looked up first, and turned into the
+//! vim-style key of their action. An action's default keys stop working once
+//! it is bound elsewhere.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::{KeyAction, KeybindingsConfig, KeymapPreset};
+
+/// Vim-style keys of each action, the first being the one it's handled as.
+const ACTION_KEYS: &[(KeyAction, &[KeyCode])] = &[
+    (KeyAction::Up, &[KeyCode::Char('k'), KeyCode::Up]),
+    (KeyAction::Down, &[KeyCode::Char('j'), KeyCode::Down]),
+    (KeyAction::Left, &[KeyCode::Char('h'), KeyCode::Left]),
+    (KeyAction::Right, &[KeyCode::Char('l'), KeyCode::Right]),
+    (KeyAction::First, &[KeyCode::Char('g'), KeyCode::Home]),
+    (KeyAction::Last, &[KeyCode::Char('G'), KeyCode::End]),
+    (KeyAction::Run, &[KeyCode::Enter, KeyCode::Char('o')]),
+    (KeyAction::RunInPane, &[KeyCode::Char('r')]),
+    (KeyAction::RunAllWorkspaces, &[KeyCode::Char('A')]),
+    (KeyAction::Watch, &[KeyCode::Char('W')]),
+    (KeyAction::Filter, &[KeyCode::Char('/')]),
+    (KeyAction::Sort, &[KeyCode::Char('s')]),
+    (KeyAction::ToggleGroup, &[KeyCode::Tab]),
+    (KeyAction::Args, &[KeyCode::Char('a')]),
+    (KeyAction::MultiSelect, &[KeyCode::Char('m')]),
+    (KeyAction::Pin, &[KeyCode::Char('f')]),
+    (KeyAction::Details, &[KeyCode::Char('v')]),
+    (KeyAction::Preview, &[KeyCode::Char('z')]),
+    (KeyAction::Source, &[KeyCode::Char('p')]),
+    (KeyAction::Dependencies, &[KeyCode::Char('d')]),
+    (KeyAction::NewScript, &[KeyCode::Char('n')]),
+    (KeyAction::Rename, &[KeyCode::Char('e')]),
+    (KeyAction::Remove, &[KeyCode::Char('x')]),
+    (KeyAction::Categories, &[KeyCode::Char('c')]),
+    (KeyAction::ProjectInfo, &[KeyCode::Char('i')]),
+    (KeyAction::Stats, &[KeyCode::Char('u')]),
+    (KeyAction::Help, &[KeyCode::Char('?')]),
+    (KeyAction::Quit, &[KeyCode::Char('q')]),
+    (KeyAction::WorkspaceBack, &[KeyCode::Char('w')]),
+    (KeyAction::GlobalSearch, &[KeyCode::Char('F')]),
+    (KeyAction::YankPath, &[KeyCode::Char('y')]),
+];
+
+/// Vim-style keys that `vim_keys = false` turns off.
+const VIM_MOTION_KEYS: [char; 6] = ['h', 'j', 'k', 'l', 'g', 'G'];
+
+/// Actions the script list can't be used without, checked at startup.
+pub const ESSENTIAL_ACTIONS: [KeyAction; 3] = [KeyAction::Run, KeyAction::Help, KeyAction::Quit];
+
+/// A script list key after translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalKey {
+    /// Handle as this vim-style key.
+    Key(KeyEvent),
+    /// Start filtering with this character.
+    Type(char),
+}
+
+/// Translate a key pressed in any mode.
+///
+/// Only the emacs preset changes keys here, so Ctrl+N/P/B/F and Ctrl+G also
+/// work in filters, argument input and selection lists.
+pub fn translate(preset: KeymapPreset, key: KeyEvent) -> KeyEvent {
+    if preset != KeymapPreset::Emacs || key.modifiers != KeyModifiers::CONTROL {
+        return key;
+    }
+    match key.code {
+        KeyCode::Char('p') => plain_key(KeyCode::Up),
+        KeyCode::Char('n') => plain_key(KeyCode::Down),
+        KeyCode::Char('b') => plain_key(KeyCode::Left),
+        KeyCode::Char('f') => plain_key(KeyCode::Right),
+        KeyCode::Char('g') => plain_key(KeyCode::Esc),
+        _ => key,
+    }
+}
+
+/// Translate a key pressed in the script list.
+pub fn translate_normal(preset: KeymapPreset, key: KeyEvent) -> NormalKey {
+    if preset == KeymapPreset::Vim {
+        return NormalKey::Key(key);
+    }
+
+    if let KeyCode::Char(c) = key.code {
+        if (key.modifiers - KeyModifiers::SHIFT).is_empty() {
+            return NormalKey::Type(c);
+        }
+    }
+    if key.code == KeyCode::F(1) {
+        return NormalKey::Key(plain_key(KeyCode::Char('?')));
+    }
+
+    let translated = match (preset, key.code, key.modifiers) {
+        (KeymapPreset::Emacs, KeyCode::Char('<'), m) if m.contains(KeyModifiers::ALT) => {
+            plain_key(KeyCode::Home)
+        }
+        (KeymapPreset::Emacs, KeyCode::Char('>'), m) if m.contains(KeyModifiers::ALT) => {
+            plain_key(KeyCode::End)
+        }
+        (KeymapPreset::Emacs, KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+            plain_key(KeyCode::Char('/'))
+        }
+        (KeymapPreset::Emacs, KeyCode::Char('b'), KeyModifiers::ALT) => {
+            plain_key(KeyCode::Char('A'))
+        }
+        (KeymapPreset::Emacs, KeyCode::Char('F'), m) if m.contains(KeyModifiers::ALT) => {
+            plain_key(KeyCode::Char('F'))
+        }
+        // Alt+digit keeps selecting numbered scripts
+        (KeymapPreset::Emacs, KeyCode::Char(c), KeyModifiers::ALT) if !c.is_ascii_digit() => {
+            plain_key(KeyCode::Char(c))
+        }
+        (KeymapPreset::Plain, KeyCode::Char(c), KeyModifiers::CONTROL) => match c {
+            's' | 'a' | 'f' | 'v' | 'z' | 'p' | 'd' | 'n' | 'e' | 'x' | 'r' | 'u' | 'w' | 'y'
+            | 'q' => plain_key(KeyCode::Char(c)),
+            't' => plain_key(KeyCode::Char('m')),
+            'b' => plain_key(KeyCode::Char('A')),
+            'k' => plain_key(KeyCode::Char('F')),
+            'g' => plain_key(KeyCode::Char('c')),
+            'o' => plain_key(KeyCode::Char('i')),
+            _ => key,
+        },
+        _ => key,
+    };
+    NormalKey::Key(translated)
+}
+
+/// Get the label of a vim-style key in a preset, for help and hints.
+///
+/// Returns `None` for keys the preset has no equivalent for.
+pub fn key_label(preset: KeymapPreset, vim: &'static str) -> Option<&'static str> {
+    let label = match (preset, vim) {
+        (KeymapPreset::Vim, _) => vim,
+        // Digits are typed into the filter
+        (_, "1-9") => return None,
+        // Only reachable through [keybindings] outside vim
+        (_, "W") => return None,
+        (_, "?") => "F1",
+
+        (KeymapPreset::Emacs, "j/k") => "C-n/C-p",
+        (KeymapPreset::Emacs, "h/l") => "C-b/C-f",
+        (KeymapPreset::Emacs, "g/G") => "M-</M->",
+        (KeymapPreset::Emacs, "/") => "C-s",
+        (KeymapPreset::Emacs, "q") => "C-c",
+        (KeymapPreset::Emacs, "q/Esc") => "C-c/C-g",
+        (KeymapPreset::Emacs, "r") => "M-r",
+        (KeymapPreset::Emacs, "s") => "M-s",
+        (KeymapPreset::Emacs, "a") => "M-a",
+        (KeymapPreset::Emacs, "m") => "M-m",
+        (KeymapPreset::Emacs, "f") => "M-f",
+        (KeymapPreset::Emacs, "v") => "M-v",
+        (KeymapPreset::Emacs, "z") => "M-z",
+        (KeymapPreset::Emacs, "p") => "M-p",
+        (KeymapPreset::Emacs, "d") => "M-d",
+        (KeymapPreset::Emacs, "n") => "M-n",
+        (KeymapPreset::Emacs, "e") => "M-e",
+        (KeymapPreset::Emacs, "x") => "M-x",
+        (KeymapPreset::Emacs, "A") => "M-b",
+        (KeymapPreset::Emacs, "F") => "M-F",
+        (KeymapPreset::Emacs, "c") => "M-c",
+        (KeymapPreset::Emacs, "i") => "M-i",
+        (KeymapPreset::Emacs, "u") => "M-u",
+
+        (KeymapPreset::Plain, "j/k") => "↓/↑",
+        (KeymapPreset::Plain, "h/l") => "←/→",
+        (KeymapPreset::Plain, "g/G") => "Home/End",
+        (KeymapPreset::Plain, "/") => "type",
+        (KeymapPreset::Plain, "q") => "C-q",
+        (KeymapPreset::Plain, "q/Esc") => "C-q/Esc",
+        (KeymapPreset::Plain, "r") => "C-r",
+        (KeymapPreset::Plain, "s") => "C-s",
+        (KeymapPreset::Plain, "a") => "C-a",
+        (KeymapPreset::Plain, "m") => "C-t",
+        (KeymapPreset::Plain, "f") => "C-f",
+        (KeymapPreset::Plain, "v") => "C-v",
+        (KeymapPreset::Plain, "z") => "C-z",
+        (KeymapPreset::Plain, "p") => "C-p",
+        (KeymapPreset::Plain, "d") => "C-d",
+        (KeymapPreset::Plain, "n") => "C-n",
+        (KeymapPreset::Plain, "e") => "C-e",
+        (KeymapPreset::Plain, "x") => "C-x",
+        (KeymapPreset::Plain, "A") => "C-b",
+        (KeymapPreset::Plain, "F") => "C-k",
+        (KeymapPreset::Plain, "c") => "C-g",
+        (KeymapPreset::Plain, "i") => "C-o",
+        (KeymapPreset::Plain, "u") => "C-u",
+
+        // Enter, Esc and modifier chords are the same everywhere
+        _ => vim,
+    };
+    Some(label)
+}
+
+/// Build a key event without modifiers.
+fn plain_key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+/// Get the vim-style key an action is handled as.
+pub fn action_key(action: KeyAction) -> KeyEvent {
+    let code = ACTION_KEYS
+        .iter()
+        .find(|(a, _)| *a == action)
+        .map_or(KeyCode::Null, |(_, keys)| keys[0]);
+    plain_key(code)
+}
+
+/// Get the action a vim-style key triggers by default.
+fn default_action(key: KeyEvent) -> Option<KeyAction> {
+    let (code, modifiers) = normalize(key);
+    if !modifiers.is_empty() {
+        return None;
+    }
+    ACTION_KEYS
+        .iter()
+        .find(|(_, keys)| keys.contains(&code))
+        .map(|(action, _)| *action)
+}
+
+/// Get the action of one key in a help or hint label, like `j` in `j/k`.
+fn label_action(vim: &str) -> Option<KeyAction> {
+    let code = match vim {
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        _ => {
+            let mut chars = vim.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return None,
+            }
+        }
+    };
+    default_action(plain_key(code))
+}
+
+/// Split a label into its keys, `j/k` into `j` and `k`.
+fn label_parts(label: &str) -> Vec<&str> {
+    if label == "/" {
+        vec![label]
+    } else {
+        label.split('/').collect()
+    }
+}
+
+/// Drop modifiers that don't tell keys apart: Shift is part of the
+/// character already (`G`, `?`).
+fn normalize(key: KeyEvent) -> (KeyCode, KeyModifiers) {
+    match key.code {
+        KeyCode::Char(_) | KeyCode::BackTab => (key.code, key.modifiers - KeyModifiers::SHIFT),
+        code => (code, key.modifiers),
+    }
+}
+
+/// Parse a key like `S`, `Ctrl+f`, `Alt+Enter` or `F5`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the key isn't recognized.
+pub fn parse_key(spec: &str) -> Result<KeyEvent, String> {
+    let (modifier_names, name) = match spec.rsplit_once('+') {
+        // A trailing "+" is the plus key itself
+        Some((rest, "")) if rest.ends_with('+') => (&rest[..rest.len() - 1], "+"),
+        Some((modifiers, name)) if !name.is_empty() => (modifiers, name),
+        _ if spec == "+" => ("", "+"),
+        Some(_) => return Err(format!("missing key in \"{spec}\"")),
+        None => ("", spec),
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    for modifier in modifier_names.split('+').filter(|m| !m.is_empty()) {
+        modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" | "meta" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return Err(format!("unknown modifier \"{modifier}\" in \"{spec}\"")),
+        };
+    }
+
+    let code = match name.to_lowercase().as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        lower => match (lower.strip_prefix('f'), name.chars().count()) {
+            (_, 1) => {
+                let c = name.chars().next().unwrap_or_default();
+                // Shift+s is S; with Ctrl or Alt, terminals report the lowercase letter
+                if modifiers == KeyModifiers::SHIFT {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else if modifiers.is_empty() {
+                    KeyCode::Char(c)
+                } else {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                }
+            }
+            (Some(number), _) => match number.parse::<u8>() {
+                Ok(n @ 1..=24) => KeyCode::F(n),
+                _ => return Err(format!("unknown key \"{name}\" in \"{spec}\"")),
+            },
+            _ => return Err(format!("unknown key \"{name}\" in \"{spec}\"")),
+        },
+    };
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+/// Parse a key or a chord of space-separated keys, like `g g`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if a key isn't recognized.
+pub fn parse_key_sequence(spec: &str) -> Result<Vec<KeyEvent>, String> {
+    let keys = spec
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<Result<Vec<_>, _>>()?;
+    if keys.is_empty() {
+        return Err("empty key".to_string());
+    }
+    Ok(keys)
+}
+
+/// Format a key the way it's written in the config, for hints.
+pub fn format_key(key: KeyEvent) -> String {
+    let (code, modifiers) = normalize(key);
+    let name = match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        code => format!("{code:?}"),
+    };
+    let mut out = String::new();
+    for (modifier, label) in [
+        (KeyModifiers::CONTROL, "Ctrl+"),
+        (KeyModifiers::ALT, "Alt+"),
+        (KeyModifiers::SHIFT, "Shift+"),
+    ] {
+        if modifiers.contains(modifier) {
+            out.push_str(label);
+        }
+    }
+    out.push_str(&name);
+    out
+}
+
+/// How the keys pressed so far resolve against the user's bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolved {
+    /// The keys are bound to this action.
+    Action(KeyAction),
+    /// The keys start a chord; wait for the next key.
+    Pending,
+    /// The keys aren't bound; handle them as the preset does.
+    Unbound,
+}
+
+/// The keymap preset with the user's key bindings on top.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    preset: KeymapPreset,
+    vim_keys: bool,
+    /// Parsed bindings, in config order.
+    bindings: Vec<(Vec<KeyEvent>, KeyAction)>,
+    /// Bound keys as written, for help and hints.
+    labels: BTreeMap<KeyAction, String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&KeybindingsConfig::default()).0
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from the `[keybindings]` config.
+    ///
+    /// Keys that can't be parsed are skipped, with a message for each.
+    pub fn new(config: &KeybindingsConfig) -> (Self, Vec<String>) {
+        let mut keymap = Self {
+            preset: config.preset,
+            vim_keys: config.vim_keys,
+            bindings: Vec::new(),
+            labels: BTreeMap::new(),
+        };
+        let mut errors = Vec::new();
+
+        for (&action, specs) in &config.bindings {
+            let mut written = Vec::new();
+            for spec in specs {
+                match parse_key_sequence(spec) {
+                    Ok(keys) => {
+                        keymap.bindings.push((keys, action));
+                        written.push(spec.trim());
+                    }
+                    Err(err) => errors.push(format!("Ignoring key binding {spec:?}: {err}")),
+                }
+            }
+            // Bound to nothing valid: the defaults are still replaced
+            keymap.labels.insert(action, written.join(","));
+        }
+        (keymap, errors)
+    }
+
+    /// Get the keymap preset.
+    pub fn preset(&self) -> KeymapPreset {
+        self.preset
+    }
+
+    /// Resolve the keys pressed so far against the user's bindings.
+    pub fn resolve(&self, keys: &[KeyEvent]) -> Resolved {
+        let pressed: Vec<_> = keys.iter().copied().map(normalize).collect();
+        let mut pending = false;
+        for (sequence, action) in &self.bindings {
+            let sequence: Vec<_> = sequence.iter().copied().map(normalize).collect();
+            if sequence == pressed {
+                return Resolved::Action(*action);
+            }
+            pending |= sequence.starts_with(&pressed);
+        }
+        if pending {
+            Resolved::Pending
+        } else {
+            Resolved::Unbound
+        }
+    }
+
+    /// Check if a vim-style key still does what it does by default.
+    ///
+    /// It doesn't once its action is bound to other keys, or for hjkl and
+    /// g/G with `vim_keys = false`.
+    pub fn allows_default(&self, key: KeyEvent) -> bool {
+        if !self.vim_keys
+            && key.modifiers.is_empty()
+            && matches!(key.code, KeyCode::Char(c) if VIM_MOTION_KEYS.contains(&c))
+        {
+            return false;
+        }
+        default_action(key).map_or(true, |action| !self.labels.contains_key(&action))
+    }
+
+    /// Get the label of a vim-style key for help and hints, with the user's
+    /// bindings in place of the keys they replace.
+    ///
+    /// Returns `None` for keys the preset has no equivalent for.
+    pub fn label(&self, vim: &'static str) -> Option<Cow<'static, str>> {
+        let motion = matches!(vim, "j/k" | "h/l" | "g/G");
+        let preset = if motion && !self.vim_keys && self.preset == KeymapPreset::Vim {
+            // Arrow keys, Home and End are what's left
+            KeymapPreset::Plain
+        } else {
+            self.preset
+        };
+        let label = key_label(preset, vim)?;
+
+        let vim_parts = label_parts(vim);
+        let bound = |part: &str| label_action(part).and_then(|action| self.labels.get(&action));
+        if !vim_parts.iter().any(|part| bound(part).is_some()) {
+            return Some(Cow::Borrowed(label));
+        }
+
+        let preset_parts = label_parts(label);
+        let parts: Vec<&str> = vim_parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| match bound(part) {
+                Some(keys) if keys.is_empty() => "-",
+                Some(keys) => keys.as_str(),
+                None if preset_parts.len() == vim_parts.len() => preset_parts[i],
+                None => label,
+            })
+            .collect();
+        Some(Cow::Owned(parts.join("/")))
+    }
+
+    /// Get the actions no key triggers in the script list any more.
+    ///
+    /// An action is lost when it's bound to nothing, when its bound keys are
+    /// taken by another binding first, or when a binding takes over every
+    /// default key it has left. With `esc_quits`, Esc still counts for quit.
+    pub fn unreachable(&self, actions: &[KeyAction], esc_quits: bool) -> Vec<KeyAction> {
+        actions
+            .iter()
+            .copied()
+            .filter(|&action| {
+                let bound = self.bindings.iter().any(|(keys, a)| {
+                    *a == action
+                        && (1..=keys.len()).all(|len| {
+                            let resolved = self.resolve(&keys[..len]);
+                            resolved == Resolved::Action(action)
+                                || (len < keys.len() && resolved == Resolved::Pending)
+                        })
+                });
+                !bound && self.default_keys(action, esc_quits).is_empty()
+            })
+            .collect()
+    }
+
+    /// Give actions back their default keys.
+    ///
+    /// Their own bindings are dropped, along with bindings of other actions
+    /// that take over one of the default keys. An action left without any
+    /// bound key that way is given its default keys back as well.
+    pub fn restore_defaults(&mut self, actions: &[KeyAction]) {
+        for &action in actions {
+            self.labels.remove(&action);
+        }
+        self.bindings
+            .retain(|(_, action)| !actions.contains(action));
+
+        let taken: Vec<(KeyCode, KeyModifiers)> = candidate_keys()
+            .filter(|&key| {
+                let key = translate(self.preset, key);
+                match translate_normal(self.preset, key) {
+                    NormalKey::Key(vim) => {
+                        default_action(vim).is_some_and(|a| actions.contains(&a))
+                    }
+                    NormalKey::Type(_) => false,
+                }
+            })
+            .map(normalize)
+            .collect();
+        self.bindings
+            .retain(|(keys, _)| !taken.contains(&normalize(keys[0])));
+
+        // Actions that just lost all their keys get their defaults back too
+        let bindings = &self.bindings;
+        self.labels.retain(|action, written| {
+            written.is_empty() || bindings.iter().any(|(_, a)| a == action)
+        });
+    }
+
+    /// Get the keys that still trigger an action through its default keys.
+    fn default_keys(&self, action: KeyAction, esc_quits: bool) -> Vec<KeyEvent> {
+        candidate_keys()
+            .filter(|&key| {
+                if self.resolve(&[key]) != Resolved::Unbound {
+                    return false;
+                }
+                match translate_normal(self.preset, translate(self.preset, key)) {
+                    NormalKey::Key(vim) if vim.code == KeyCode::Esc && vim.modifiers.is_empty() => {
+                        action == KeyAction::Quit && esc_quits
+                    }
+                    NormalKey::Key(vim) => {
+                        self.allows_default(vim) && default_action(vim) == Some(action)
+                    }
+                    NormalKey::Type(_) => false,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Keys a default action could be on in any preset: printable characters
+/// with or without Ctrl and Alt, and the named keys actions use.
+fn candidate_keys() -> impl Iterator<Item = KeyEvent> {
+    let named = [
+        KeyCode::Enter,
+        KeyCode::Tab,
+        KeyCode::Esc,
+        KeyCode::Up,
+        KeyCode::Down,
+        KeyCode::Left,
+        KeyCode::Right,
+        KeyCode::Home,
+        KeyCode::End,
+        KeyCode::F(1),
+    ];
+    let codes: Vec<KeyCode> = (' '..='~').map(KeyCode::Char).chain(named).collect();
+    [KeyModifiers::NONE, KeyModifiers::CONTROL, KeyModifiers::ALT]
+        .into_iter()
+        .flat_map(move |modifiers| {
+            codes
+                .clone()
+                .into_iter()
+                .map(move |code| KeyEvent::new(code, modifiers))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KeyAction;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_vim_keys_unchanged() {
+        let s = key(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(translate_normal(KeymapPreset::Vim, s), NormalKey::Key(s));
+        let ctrl_n = key(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        assert_eq!(translate(KeymapPreset::Vim, ctrl_n), ctrl_n);
+    }
+
+    #[test]
+    fn test_typing_filters() {
+        for preset in [KeymapPreset::Emacs, KeymapPreset::Plain] {
+            let s = key(KeyCode::Char('s'), KeyModifiers::NONE);
+            assert_eq!(translate_normal(preset, s), NormalKey::Type('s'));
+            let upper = key(KeyCode::Char('B'), KeyModifiers::SHIFT);
+            assert_eq!(translate_normal(preset, upper), NormalKey::Type('B'));
+        }
+    }
+
+    #[test]
+    fn test_emacs_keys() {
+        let ctrl = |c| key(KeyCode::Char(c), KeyModifiers::CONTROL);
+        let alt = |c| key(KeyCode::Char(c), KeyModifiers::ALT);
+        let emacs = KeymapPreset::Emacs;
+
+        assert_eq!(translate(emacs, ctrl('n')).code, KeyCode::Down);
+        assert_eq!(translate(emacs, ctrl('g')).code, KeyCode::Esc);
+        assert_eq!(
+            translate_normal(emacs, alt('s')),
+            NormalKey::Key(plain_key(KeyCode::Char('s')))
+        );
+        assert_eq!(
+            translate_normal(emacs, ctrl('s')),
+            NormalKey::Key(plain_key(KeyCode::Char('/')))
+        );
+        // Alt+digit still selects a numbered script
+        assert_eq!(translate_normal(emacs, alt('2')), NormalKey::Key(alt('2')));
+    }
+
+    #[test]
+    fn test_plain_keys() {
+        let ctrl = |c| key(KeyCode::Char(c), KeyModifiers::CONTROL);
+        let plain = KeymapPreset::Plain;
+
+        assert_eq!(
+            translate_normal(plain, ctrl('t')),
+            NormalKey::Key(plain_key(KeyCode::Char('m')))
+        );
+        assert_eq!(
+            translate_normal(plain, key(KeyCode::F(1), KeyModifiers::NONE)),
+            NormalKey::Key(plain_key(KeyCode::Char('?')))
+        );
+        // Ctrl+N isn't navigation outside the emacs preset
+        assert_eq!(translate(plain, ctrl('n')), ctrl('n'));
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            parse_key("Ctrl+f"),
+            Ok(key(KeyCode::Char('f'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("Shift+s"),
+            Ok(key(KeyCode::Char('S'), KeyModifiers::SHIFT))
+        );
+        assert_eq!(
+            parse_key("alt+enter"),
+            Ok(key(KeyCode::Enter, KeyModifiers::ALT))
+        );
+        assert_eq!(parse_key("F5"), Ok(plain_key(KeyCode::F(5))));
+        assert_eq!(parse_key("f"), Ok(plain_key(KeyCode::Char('f'))));
+        assert_eq!(parse_key("+"), Ok(plain_key(KeyCode::Char('+'))));
+        assert_eq!(
+            parse_key("Ctrl++"),
+            Ok(key(KeyCode::Char('+'), KeyModifiers::CONTROL))
+        );
+
+        assert!(parse_key("Ctrl+").is_err());
+        assert!(parse_key("Hyper+x").unwrap_err().contains("Hyper"));
+        assert!(parse_key("PgUpp").is_err());
+        assert_eq!(
+            parse_key_sequence("Space r"),
+            Ok(vec![
+                plain_key(KeyCode::Char(' ')),
+                plain_key(KeyCode::Char('r'))
+            ])
+        );
+        assert!(parse_key_sequence("  ").is_err());
+    }
+
+    #[test]
+    fn test_format_key() {
+        assert_eq!(
+            format_key(key(KeyCode::Char('f'), KeyModifiers::CONTROL)),
+            "Ctrl+f"
+        );
+        assert_eq!(
+            format_key(key(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            "G"
+        );
+        assert_eq!(format_key(plain_key(KeyCode::Char(' '))), "Space");
+        assert_eq!(format_key(plain_key(KeyCode::PageDown)), "PageDown");
+    }
+
+    #[test]
+    fn test_keymap_resolve() {
+        let mut config = KeybindingsConfig::default();
+        config.bindings.insert(
+            KeyAction::First,
+            vec!["g g".to_string(), "Ctrl+Home".to_string()],
+        );
+        config
+            .bindings
+            .insert(KeyAction::Sort, vec!["S".to_string(), "Ctlr+s".to_string()]);
+        let (keymap, errors) = Keymap::new(&config);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Ctlr+s"));
+
+        let g = plain_key(KeyCode::Char('g'));
+        assert_eq!(keymap.resolve(&[g]), Resolved::Pending);
+        assert_eq!(keymap.resolve(&[g, g]), Resolved::Action(KeyAction::First));
+        assert_eq!(
+            keymap.resolve(&[key(KeyCode::Char('S'), KeyModifiers::SHIFT)]),
+            Resolved::Action(KeyAction::Sort)
+        );
+        assert_eq!(
+            keymap.resolve(&[plain_key(KeyCode::Char('s'))]),
+            Resolved::Unbound
+        );
+
+        // Bound actions lose their default keys
+        assert!(!keymap.allows_default(plain_key(KeyCode::Char('s'))));
+        assert!(!keymap.allows_default(plain_key(KeyCode::Home)));
+        assert!(keymap.allows_default(plain_key(KeyCode::Char('j'))));
+        assert_eq!(action_key(KeyAction::Sort), plain_key(KeyCode::Char('s')));
+    }
+
+    #[test]
+    fn test_keymap_labels() {
+        let mut config = KeybindingsConfig {
+            vim_keys: false,
+            ..Default::default()
+        };
+        config
+            .bindings
+            .insert(KeyAction::Filter, vec!["Ctrl+f".to_string()]);
+        config.bindings.insert(KeyAction::Quit, vec![]);
+        let (keymap, _) = Keymap::new(&config);
+
+        assert_eq!(keymap.label("/").as_deref(), Some("Ctrl+f"));
+        assert_eq!(keymap.label("q/Esc").as_deref(), Some("-/Esc"));
+        assert_eq!(keymap.label("j/k").as_deref(), Some("↓/↑"));
+        assert_eq!(keymap.label("s").as_deref(), Some("s"));
+        assert!(!keymap.allows_default(plain_key(KeyCode::Char('q'))));
+    }
+
+    #[test]
+    fn test_unreachable_actions() {
+        let mut config = KeybindingsConfig::default();
+        config.bindings.insert(KeyAction::Quit, vec![]);
+        config
+            .bindings
+            .insert(KeyAction::Sort, vec!["?".to_string(), "Enter".to_string()]);
+        let (mut keymap, _) = Keymap::new(&config);
+
+        // Enter is taken, but o still runs; Esc still quits if allowed
+        assert_eq!(
+            keymap.unreachable(&ESSENTIAL_ACTIONS, false),
+            [KeyAction::Help, KeyAction::Quit]
+        );
+        assert_eq!(
+            keymap.unreachable(&ESSENTIAL_ACTIONS, true),
+            [KeyAction::Help]
+        );
+
+        keymap.restore_defaults(&[KeyAction::Help, KeyAction::Quit]);
+        assert!(keymap.unreachable(&ESSENTIAL_ACTIONS, false).is_empty());
+        assert_eq!(
+            keymap.resolve(&[plain_key(KeyCode::Enter)]),
+            Resolved::Action(KeyAction::Sort)
+        );
+    }
+
+    #[test]
+    fn test_unreachable_chord_prefix() {
+        let mut config = KeybindingsConfig {
+            preset: KeymapPreset::Plain,
+            ..Default::default()
+        };
+        config
+            .bindings
+            .insert(KeyAction::Quit, vec!["Ctrl+x Ctrl+c".to_string()]);
+        config
+            .bindings
+            .insert(KeyAction::Help, vec!["Ctrl+x".to_string()]);
+        let (keymap, _) = Keymap::new(&config);
+
+        // Help comes first, so the quit chord can never be finished
+        assert_eq!(
+            keymap.unreachable(&ESSENTIAL_ACTIONS, false),
+            [KeyAction::Quit]
+        );
+    }
+
+    #[test]
+    fn test_key_labels() {
+        assert_eq!(key_label(KeymapPreset::Vim, "s"), Some("s"));
+        assert_eq!(key_label(KeymapPreset::Emacs, "s"), Some("M-s"));
+        assert_eq!(key_label(KeymapPreset::Plain, "m"), Some("C-t"));
+        assert_eq!(key_label(KeymapPreset::Plain, "Enter"), Some("Enter"));
+        assert_eq!(key_label(KeymapPreset::Emacs, "1-9"), None);
+    }
+}
@@ -2,7 +2,7 @@
 
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
-use crate::config::AppearanceConfig;
+use crate::config::{AppearanceConfig, Density};
 
 /// Minimum terminal dimensions.
 pub const MIN_WIDTH: u16 = 40;
@@ -21,6 +21,8 @@ pub struct MainLayout {
     pub description: Rect,
     /// Footer area.
     pub footer: Rect,
+    /// Whether the degraded small-terminal layout is in use.
+    pub degraded: bool,
 }
 
 impl MainLayout {
@@ -71,10 +73,13 @@ impl MainLayout {
             scripts: chunks[2],
             description: chunks[3],
             footer: chunks[4],
+            degraded: false,
         }
     }
 
     /// Create minimal layout for small terminals.
+    ///
+    /// Drops the description panel so every remaining row goes to scripts.
     fn minimal_layout(area: Rect) -> Self {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -82,7 +87,7 @@ impl MainLayout {
                 Constraint::Length(1), // Header
                 Constraint::Length(1), // Filter
                 Constraint::Min(1),    // Scripts
-                Constraint::Length(1), // Description
+                Constraint::Length(0), // No description
                 Constraint::Length(1), // Footer
             ])
             .split(area);
@@ -93,6 +98,7 @@ impl MainLayout {
             scripts: chunks[2],
             description: chunks[3],
             footer: chunks[4],
+            degraded: true,
         }
     }
 
@@ -118,6 +124,26 @@ pub fn calculate_columns(width: u16) -> usize {
     }
 }
 
+/// Calculate grid columns based on terminal width and grid density.
+///
+/// Comfortable density uses the standard breakpoints; compact density fits
+/// as many columns as its narrower minimum column width allows. Terminals
+/// narrower than [`MIN_WIDTH`] always get a single column.
+pub fn calculate_columns_for_density(width: u16, density: Density) -> usize {
+    // Small terminals always get a single column
+    if width < MIN_WIDTH {
+        return 1;
+    }
+
+    match density {
+        Density::Comfortable => calculate_columns(width),
+        Density::Compact => {
+            let available = width.saturating_sub(2);
+            ((available / density.min_column_width()) as usize).clamp(1, 8)
+        }
+    }
+}
+
 /// Calculate column width for the scripts grid.
 pub fn calculate_column_width(total_width: u16, columns: usize) -> u16 {
     if columns == 0 {
@@ -264,10 +290,12 @@ mod tests {
         let area = Rect::new(0, 0, 30, 8);
         let layout = MainLayout::new(area);
 
-        // Should create minimal layout
+        // Should create minimal layout without a description panel
+        assert!(layout.degraded);
         assert_eq!(layout.header.height, 1);
         assert_eq!(layout.filter.height, 1);
-        assert_eq!(layout.description.height, 1);
+        assert_eq!(layout.scripts.height, 5);
+        assert_eq!(layout.description.height, 0);
         assert_eq!(layout.footer.height, 1);
     }
 
@@ -284,6 +312,19 @@ mod tests {
         assert_eq!(calculate_columns(160), 5);
     }
 
+    #[test]
+    fn test_calculate_columns_for_density() {
+        // Comfortable keeps the standard breakpoints
+        assert_eq!(calculate_columns_for_density(100, Density::Comfortable), 3);
+        assert_eq!(calculate_columns_for_density(50, Density::Comfortable), 1);
+
+        // Compact fits more columns in the same width
+        assert_eq!(calculate_columns_for_density(100, Density::Compact), 5);
+        assert_eq!(calculate_columns_for_density(10, Density::Compact), 1);
+        assert_eq!(calculate_columns_for_density(38, Density::Compact), 1);
+        assert_eq!(calculate_columns_for_density(400, Density::Compact), 8);
+    }
+
     #[test]
     fn test_calculate_column_width() {
         assert_eq!(calculate_column_width(100, 2), 48);
@@ -0,0 +1,168 @@
+//! Transient footer notifications.
+//!
+//! Messages are queued and shown one at a time in the footer. Each message
+//! stays visible for a time-to-live that starts when it is first shown.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Severity of a footer message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLevel {
+    /// Informational notice.
+    Info,
+    /// Something the user may want to act on.
+    Warn,
+    /// An operation failed.
+    Error,
+}
+
+impl MessageLevel {
+    /// Default time a message of this level stays visible.
+    pub fn default_ttl(self) -> Duration {
+        match self {
+            Self::Info => Duration::from_secs(3),
+            Self::Warn => Duration::from_secs(5),
+            Self::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+/// A footer message.
+#[derive(Debug, Clone)]
+pub struct Message {
+    /// Message text.
+    pub text: String,
+    /// Message severity.
+    pub level: MessageLevel,
+    /// How long the message stays visible once shown.
+    pub ttl: Duration,
+    /// When the message was first shown.
+    shown_at: Option<Instant>,
+}
+
+impl Message {
+    /// Create a message with the default time-to-live for its level.
+    pub fn new(level: MessageLevel, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            level,
+            ttl: level.default_ttl(),
+            shown_at: None,
+        }
+    }
+
+    /// Set a custom time-to-live.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+/// Maximum number of queued messages; older ones are dropped first.
+const MAX_QUEUED_MESSAGES: usize = 8;
+
+/// Queue of footer messages, shown in order.
+#[derive(Debug, Clone, Default)]
+pub struct MessageQueue {
+    messages: VecDeque<Message>,
+}
+
+impl MessageQueue {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a message to the end of the queue.
+    pub fn push(&mut self, message: Message) {
+        if self.messages.len() >= MAX_QUEUED_MESSAGES {
+            // Keep the one on screen, drop the oldest waiting message
+            self.messages.remove(1);
+        }
+        self.messages.push_back(message);
+    }
+
+    /// Get the message currently shown, if any.
+    pub fn current(&self) -> Option<&Message> {
+        self.messages.front()
+    }
+
+    /// Check if there are no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Drop the current message immediately.
+    pub fn dismiss(&mut self) {
+        self.messages.pop_front();
+    }
+
+    /// Advance the queue: start the current message's timer and drop it
+    /// (and any following ones) once expired.
+    pub fn tick(&mut self, now: Instant) {
+        while let Some(message) = self.messages.front_mut() {
+            let shown_at = *message.shown_at.get_or_insert(now);
+            if now.duration_since(shown_at) < message.ttl {
+                break;
+            }
+            self.messages.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_ttl_by_level() {
+        assert!(MessageLevel::Info.default_ttl() < MessageLevel::Warn.default_ttl());
+        assert!(MessageLevel::Warn.default_ttl() < MessageLevel::Error.default_ttl());
+    }
+
+    #[test]
+    fn test_queue_shows_messages_in_order() {
+        let mut queue = MessageQueue::new();
+        let start = Instant::now();
+        queue.push(Message::new(MessageLevel::Info, "first").ttl(Duration::from_secs(1)));
+        queue.push(Message::new(MessageLevel::Error, "second").ttl(Duration::from_secs(1)));
+
+        queue.tick(start);
+        assert_eq!(queue.current().unwrap().text, "first");
+
+        queue.tick(start + Duration::from_millis(500));
+        assert_eq!(queue.current().unwrap().text, "first");
+
+        // The second message's timer starts when it is first shown
+        queue.tick(start + Duration::from_secs(1));
+        assert_eq!(queue.current().unwrap().text, "second");
+        queue.tick(start + Duration::from_millis(1900));
+        assert_eq!(queue.current().unwrap().text, "second");
+
+        queue.tick(start + Duration::from_secs(2));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_queue_dismiss() {
+        let mut queue = MessageQueue::new();
+        queue.push(Message::new(MessageLevel::Warn, "careful"));
+        queue.dismiss();
+        assert!(queue.current().is_none());
+    }
+
+    #[test]
+    fn test_queue_is_bounded() {
+        let mut queue = MessageQueue::new();
+        for i in 0..MAX_QUEUED_MESSAGES + 3 {
+            queue.push(Message::new(MessageLevel::Info, format!("msg{}", i)));
+        }
+        assert_eq!(queue.messages.len(), MAX_QUEUED_MESSAGES);
+        assert_eq!(queue.current().unwrap().text, "msg0");
+        assert_eq!(
+            queue.messages.back().unwrap().text,
+            format!("msg{}", MAX_QUEUED_MESSAGES + 2)
+        );
+    }
+}
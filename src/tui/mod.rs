@@ -3,16 +3,28 @@
 //! Provides the terminal user interface for interactive script selection.
 
 mod app;
+mod crash;
+mod groups;
 mod input;
+mod keymap;
 mod layout;
+mod message;
+mod picker;
+mod profile;
+mod text_input;
 mod theme;
 mod ui;
 pub mod widgets;
 
-pub use app::{calculate_column_width, calculate_columns, App, AppMode, ScriptRun};
+pub use app::{calculate_column_width, calculate_columns, App, AppMode, ErrorAction, ScriptRun};
+pub use groups::{GroupLayout, GroupLine};
 pub use input::handle_event;
 pub use layout::{
     centered_rect, centered_rect_fixed, GridLayout, MainLayout, MIN_HEIGHT, MIN_WIDTH,
 };
+pub use message::{Message, MessageLevel, MessageQueue};
+pub use picker::{pick, PickOutcome, Picker, PICK_ROWS};
+pub use profile::{format_latency, Profile, Timing, FRAME_BUDGET, MATCH_BUDGET, SORT_BUDGET};
+pub use text_input::TextInput;
 pub use theme::Theme;
-pub use ui::{render, restore_terminal, run_tui, TerminalGuard};
+pub use ui::{render, restore_terminal, run_tui, TerminalGuard, TuiOutcome, TuiWriter};
@@ -0,0 +1,295 @@
+//! Inline script picker for shell pipelines.
+//!
+//! `nrs --pick` draws a prompt and the matching scripts below the cursor,
+//! on stderr and without the alternate screen, then prints the chosen
+//! script's name to stdout, like fzf. Nothing is run.
+
+use std::io::{self, IsTerminal, Write};
+
+use anyhow::{bail, Context, Result};
+use crossterm::cursor::{
+    Hide, MoveRight, MoveToColumn, MoveUp, RestorePosition, SavePosition, Show,
+};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Print, PrintStyledContent, Stylize};
+use crossterm::terminal::{self, disable_raw_mode, enable_raw_mode, Clear, ClearType};
+use crossterm::{execute, queue};
+
+use super::input::edit_input;
+use super::text_input::TextInput;
+use crate::config::FilterConfig;
+use crate::filter::filter_scripts_with_config;
+use crate::package::Script;
+
+/// Number of matching scripts shown below the prompt.
+pub const PICK_ROWS: usize = 10;
+
+/// How picking ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PickOutcome {
+    /// A script was chosen.
+    Picked(String),
+    /// The picker was closed without choosing.
+    Cancelled,
+}
+
+/// State of the inline picker.
+#[derive(Debug)]
+pub struct Picker<'a> {
+    scripts: &'a [Script],
+    config: &'a FilterConfig,
+    input: TextInput,
+    /// Indices of the matching scripts, best match first.
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl<'a> Picker<'a> {
+    /// Create a picker listing all scripts.
+    pub fn new(scripts: &'a [Script], config: &'a FilterConfig) -> Self {
+        Self {
+            scripts,
+            config,
+            input: TextInput::default(),
+            matches: (0..scripts.len()).collect(),
+            selected: 0,
+        }
+    }
+
+    /// Get the typed query.
+    pub fn query(&self) -> &str {
+        self.input.text()
+    }
+
+    /// Get the scripts matching the query, best match first.
+    pub fn matches(&self) -> impl Iterator<Item = &Script> {
+        self.matches.iter().map(|&i| &self.scripts[i])
+    }
+
+    /// Get the highlighted script.
+    pub fn selected_script(&self) -> Option<&Script> {
+        self.matches.get(self.selected).map(|&i| &self.scripts[i])
+    }
+
+    /// Handle a key, returning the outcome once picking ends.
+    ///
+    /// Typing edits the query, ↑/↓ (or Ctrl+P/N) move, Enter picks and
+    /// Esc or Ctrl+C cancels.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<PickOutcome> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => {
+                return self
+                    .selected_script()
+                    .map(|script| PickOutcome::Picked(script.name().to_string()));
+            }
+            KeyCode::Esc => return Some(PickOutcome::Cancelled),
+            KeyCode::Char('c' | 'g') if ctrl => return Some(PickOutcome::Cancelled),
+            KeyCode::Up => self.move_up(),
+            KeyCode::Char('p' | 'k') if ctrl => self.move_up(),
+            KeyCode::Down => self.move_down(),
+            KeyCode::Char('n' | 'j') if ctrl => self.move_down(),
+            _ => {
+                if edit_input(&mut self.input, key) {
+                    self.update_matches();
+                }
+            }
+        }
+        None
+    }
+
+    fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = filter_scripts_with_config(self.query(), self.scripts, self.config)
+            .into_iter()
+            .map(|(i, _)| i)
+            .collect();
+        self.selected = 0;
+    }
+}
+
+/// Disables raw mode when picking ends, even on error.
+struct RawModeGuard;
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+/// Let the user pick a script inline, drawing on stderr.
+///
+/// Returns the name of the chosen script, or `None` if cancelled.
+///
+/// # Errors
+///
+/// Returns an error if stderr isn't a terminal or can't be drawn on.
+pub fn pick(scripts: &[Script], config: &FilterConfig) -> Result<Option<String>> {
+    let mut out = io::stderr();
+    if !out.is_terminal() {
+        bail!("--pick needs a terminal to draw on (stderr is redirected)");
+    }
+
+    let (_, height) = terminal_size();
+    let rows = (scripts.len().clamp(1, PICK_ROWS) as u16).min(height.saturating_sub(1).max(1));
+    enable_raw_mode().context("Failed to enable raw mode")?;
+    let _raw_mode = RawModeGuard;
+
+    // Make room below the cursor first, so drawing never scrolls and the
+    // saved position stays the prompt line. Everything is drawn relative to
+    // it: asking the terminal for the cursor position would go to stdout.
+    queue!(
+        out,
+        Print("\n".repeat(usize::from(rows))),
+        MoveUp(rows),
+        MoveToColumn(0),
+        SavePosition
+    )?;
+
+    let mut picker = Picker::new(scripts, config);
+    let outcome = loop {
+        draw_picker(&mut out, &picker, usize::from(rows))?;
+        if let Event::Key(key) = event::read().context("Failed to read key")? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(outcome) = picker.handle_key(key) {
+                break outcome;
+            }
+        }
+    };
+
+    // Leave the shell as it was, without the picker's lines
+    execute!(out, RestorePosition, Clear(ClearType::FromCursorDown), Show)?;
+
+    Ok(match outcome {
+        PickOutcome::Picked(name) => Some(name),
+        PickOutcome::Cancelled => None,
+    })
+}
+
+/// Draw the prompt line and the matching scripts around the selection.
+fn draw_picker(out: &mut impl Write, picker: &Picker, rows: usize) -> io::Result<()> {
+    let width = usize::from(terminal_size().0);
+    queue!(out, Hide, RestorePosition, Clear(ClearType::FromCursorDown))?;
+
+    let count = format!(" {}/{}", picker.matches.len(), picker.scripts.len());
+    queue!(
+        out,
+        PrintStyledContent("> ".bold()),
+        Print(truncate(picker.query(), width.saturating_sub(2))),
+        PrintStyledContent(
+            truncate(
+                &count,
+                width.saturating_sub(2 + picker.query().chars().count())
+            )
+            .dim()
+        ),
+    )?;
+
+    let offset = (picker.selected + 1).saturating_sub(rows);
+    for (i, script) in picker.matches().enumerate().skip(offset).take(rows) {
+        let name = truncate(script.name(), width.saturating_sub(2));
+        let room = width.saturating_sub(4 + name.chars().count());
+        let command = truncate(script.command(), room);
+        queue!(out, Print("\r\n"))?;
+        if i == picker.selected {
+            queue!(
+                out,
+                PrintStyledContent("▌ ".bold()),
+                PrintStyledContent(name.bold().reverse()),
+            )?;
+        } else {
+            queue!(out, Print("  "), Print(name))?;
+        }
+        if !command.is_empty() {
+            queue!(out, PrintStyledContent(format!("  {command}").dim()))?;
+        }
+    }
+
+    // Put the cursor back at the end of the query
+    let column = 2 + picker.query()[..picker.input.cursor()].chars().count();
+    let column = column.min(width.saturating_sub(1)) as u16;
+    queue!(out, RestorePosition, MoveRight(column), Show)?;
+    out.flush()
+}
+
+/// Get the terminal size, assuming 80x24 when it's unknown.
+fn terminal_size() -> (u16, u16) {
+    match terminal::size() {
+        Ok((width, height)) if width > 0 && height > 0 => (width, height),
+        _ => (80, 24),
+    }
+}
+
+/// Cut text to a number of characters, so lines never wrap.
+fn truncate(text: &str, max: usize) -> String {
+    text.chars().take(max).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_text(picker: &mut Picker, text: &str) {
+        for c in text.chars() {
+            assert_eq!(picker.handle_key(key(KeyCode::Char(c))), None);
+        }
+    }
+
+    #[test]
+    fn test_picker_filters_and_picks() {
+        let scripts = vec![
+            Script::new("dev", "vite"),
+            Script::new("build", "vite build"),
+            Script::new("build:prod", "vite build --mode production"),
+        ];
+        let config = FilterConfig::default();
+        let mut picker = Picker::new(&scripts, &config);
+        assert_eq!(picker.matches().count(), 3);
+
+        type_text(&mut picker, "bld");
+        let names: Vec<String> = picker.matches().map(|s| s.name().to_string()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.iter().all(|name| name.starts_with("build")));
+
+        picker.handle_key(key(KeyCode::Down));
+        picker.handle_key(key(KeyCode::Down));
+        let expected = names[1].clone();
+        assert_eq!(
+            picker.handle_key(key(KeyCode::Enter)),
+            Some(PickOutcome::Picked(expected))
+        );
+    }
+
+    #[test]
+    fn test_picker_cancel_and_no_match() {
+        let scripts = vec![Script::new("dev", "vite")];
+        let config = FilterConfig::default();
+        let mut picker = Picker::new(&scripts, &config);
+
+        type_text(&mut picker, "zzz");
+        assert_eq!(picker.selected_script(), None);
+        assert_eq!(picker.handle_key(key(KeyCode::Enter)), None);
+
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(picker.handle_key(ctrl_c), Some(PickOutcome::Cancelled));
+        assert_eq!(
+            picker.handle_key(key(KeyCode::Esc)),
+            Some(PickOutcome::Cancelled)
+        );
+    }
+}
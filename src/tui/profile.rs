@@ -0,0 +1,136 @@
+//! Performance counters for the debug overlay.
+//!
+//! Matching, sorting and drawing are timed on every pass, so F12 can show
+//! concrete numbers when the TUI feels slow. Passes over their latency
+//! budget are counted and logged at debug level.
+
+use std::time::Duration;
+
+use tracing::debug;
+
+/// Time a frame may take to draw before it counts as slow (60 fps).
+pub const FRAME_BUDGET: Duration = Duration::from_millis(16);
+
+/// Time filtering may take before typing feels laggy.
+pub const MATCH_BUDGET: Duration = Duration::from_millis(8);
+
+/// Time sorting may take before typing feels laggy.
+pub const SORT_BUDGET: Duration = Duration::from_millis(4);
+
+/// Timings of one kind of pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timing {
+    /// Duration of the last pass.
+    pub last: Option<Duration>,
+    /// Longest pass so far.
+    pub max: Duration,
+    /// Number of passes.
+    pub count: u64,
+    /// Number of passes over the budget.
+    pub over_budget: u64,
+}
+
+impl Timing {
+    /// Record a pass, returning whether it went over the budget.
+    pub fn record(&mut self, elapsed: Duration, budget: Duration) -> bool {
+        self.last = Some(elapsed);
+        self.max = self.max.max(elapsed);
+        self.count += 1;
+        let over = elapsed > budget;
+        if over {
+            self.over_budget += 1;
+        }
+        over
+    }
+
+    /// Describe the timings, e.g. "1.2ms (max 20.5ms, 1 of 96 slow)".
+    pub fn describe(&self) -> String {
+        let Some(last) = self.last else {
+            return "-".to_string();
+        };
+        format!(
+            "{} (max {}, {} of {} slow)",
+            format_latency(last),
+            format_latency(self.max),
+            self.over_budget,
+            self.count
+        )
+    }
+}
+
+/// Counters shown in the debug overlay.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// Drawing the whole screen.
+    pub frame: Timing,
+    /// Matching scripts against the filter.
+    pub matching: Timing,
+    /// Sorting the matching scripts.
+    pub sorting: Timing,
+}
+
+impl Profile {
+    /// Record the time a frame took to draw.
+    pub fn record_frame(&mut self, elapsed: Duration) {
+        if self.frame.record(elapsed, FRAME_BUDGET) {
+            debug!("Slow frame: {}", format_latency(elapsed));
+        }
+    }
+
+    /// Record the time filtering took.
+    pub fn record_match(&mut self, elapsed: Duration) {
+        if self.matching.record(elapsed, MATCH_BUDGET) {
+            debug!("Slow filter: {}", format_latency(elapsed));
+        }
+    }
+
+    /// Record the time sorting took.
+    pub fn record_sort(&mut self, elapsed: Duration) {
+        if self.sorting.record(elapsed, SORT_BUDGET) {
+            debug!("Slow sort: {}", format_latency(elapsed));
+        }
+    }
+
+    /// Get the timings as labels and values.
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Frame", self.frame.describe()),
+            ("Match", self.matching.describe()),
+            ("Sort", self.sorting.describe()),
+        ]
+    }
+}
+
+/// Format a short duration, e.g. "850µs" or "12.4ms".
+pub fn format_latency(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1000 {
+        format!("{micros}µs")
+    } else {
+        format!("{:.1}ms", micros as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_counts_slow_passes() {
+        let mut profile = Profile::default();
+        assert_eq!(profile.frame.describe(), "-");
+
+        profile.record_frame(Duration::from_millis(2));
+        profile.record_frame(Duration::from_millis(40));
+        profile.record_frame(Duration::from_micros(1500));
+        assert_eq!(profile.frame.count, 3);
+        assert_eq!(profile.frame.over_budget, 1);
+        assert_eq!(profile.frame.describe(), "1.5ms (max 40.0ms, 1 of 3 slow)");
+
+        profile.record_match(Duration::from_micros(300));
+        assert_eq!(
+            profile.rows()[1],
+            ("Match", "300µs (max 300µs, 0 of 1 slow)".to_string())
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Single-line text editing for the filter and args inputs.
+//!
+//! The cursor is a byte offset that always sits on a grapheme cluster
+//! boundary, so combining marks and emoji sequences are moved over and
+//! deleted as a single unit.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// An editable line of text with a cursor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    /// Create an input with the cursor at the end of the text.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let cursor = text.len();
+        Self { text, cursor }
+    }
+
+    /// Create an input with the cursor at the given byte offset.
+    ///
+    /// Offsets past the end or inside a grapheme are moved to the nearest
+    /// preceding boundary.
+    pub fn with_cursor(text: impl Into<String>, cursor: usize) -> Self {
+        let text = text.into();
+        let cursor = floor_boundary(&text, cursor);
+        Self { text, cursor }
+    }
+
+    /// Get the current text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the cursor position as a byte offset.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Consume the input, returning the text.
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    /// Insert a character at the cursor.
+    pub fn insert_char(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+        // A combining mark may have merged with the previous grapheme
+        self.cursor = floor_boundary(&self.text, self.cursor);
+    }
+
+    /// Get the word ending at the cursor, back to the previous whitespace.
+    pub fn word_before_cursor(&self) -> &str {
+        self.text[..self.cursor]
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or("")
+    }
+
+    /// Insert a string at the cursor.
+    pub fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor = floor_boundary(&self.text, self.cursor + s.len());
+    }
+
+    /// Delete the grapheme before the cursor (Backspace).
+    pub fn delete_backward(&mut self) {
+        let start = self.prev_boundary();
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Delete the grapheme under the cursor (Delete).
+    pub fn delete_forward(&mut self) {
+        let end = self.next_boundary();
+        self.text.replace_range(self.cursor..end, "");
+    }
+
+    /// Delete the word before the cursor (Ctrl+W).
+    ///
+    /// Skips trailing whitespace first, then removes up to the previous
+    /// whitespace, like readline's `unix-word-rubout`.
+    pub fn delete_word_backward(&mut self) {
+        let before = &self.text[..self.cursor];
+        let trimmed = before.trim_end();
+        let start = trimmed
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(0);
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Delete everything before the cursor (Ctrl+U).
+    pub fn delete_to_start(&mut self) {
+        self.text.replace_range(..self.cursor, "");
+        self.cursor = 0;
+    }
+
+    /// Move the cursor one grapheme to the left.
+    pub fn move_left(&mut self) {
+        self.cursor = self.prev_boundary();
+    }
+
+    /// Move the cursor one grapheme to the right.
+    pub fn move_right(&mut self) {
+        self.cursor = self.next_boundary();
+    }
+
+    /// Move the cursor to the start of the text.
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Move the cursor to the end of the text.
+    pub fn move_end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    /// Byte offset of the grapheme boundary before the cursor.
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    /// Byte offset of the grapheme boundary after the cursor.
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map(|g| self.cursor + g.len())
+            .unwrap_or(self.cursor)
+    }
+}
+
+/// Split text at the cursor into (before, grapheme under cursor, after).
+///
+/// Used by the input widgets to draw the cursor over the right cell.
+pub fn split_at_cursor(text: &str, cursor: usize) -> (&str, &str, &str) {
+    let cursor = floor_boundary(text, cursor);
+    let (before, rest) = text.split_at(cursor);
+    let current = rest.graphemes(true).next().unwrap_or("");
+    (before, current, &rest[current.len()..])
+}
+
+/// Round a byte offset down to the nearest grapheme boundary.
+fn floor_boundary(text: &str, offset: usize) -> usize {
+    if offset >= text.len() {
+        return text.len();
+    }
+    text.grapheme_indices(true)
+        .map(|(i, _)| i)
+        .take_while(|&i| i <= offset)
+        .last()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_places_cursor_at_end() {
+        let input = TextInput::new("dev");
+        assert_eq!(input.cursor(), 3);
+    }
+
+    #[test]
+    fn test_word_before_cursor() {
+        assert_eq!(TextInput::new("-- --fil").word_before_cursor(), "--fil");
+        assert_eq!(TextInput::new("--watch ").word_before_cursor(), "");
+        assert_eq!(
+            TextInput::with_cursor("--grep x", 3).word_before_cursor(),
+            "--g"
+        );
+    }
+
+    #[test]
+    fn test_insert_in_middle() {
+        let mut input = TextInput::with_cursor("dv", 1);
+        input.insert_char('e');
+        assert_eq!(input.text(), "dev");
+        assert_eq!(input.cursor(), 2);
+    }
+
+    #[test]
+    fn test_move_and_delete_graphemes() {
+        // "e" + combining acute accent is one grapheme
+        let mut input = TextInput::new("cafe\u{301}s");
+        input.move_left();
+        input.move_left();
+        assert_eq!(input.cursor(), 3);
+        input.delete_forward();
+        assert_eq!(input.text(), "cafs");
+
+        let mut input = TextInput::new("a👍🏽");
+        input.delete_backward();
+        assert_eq!(input.text(), "a");
+    }
+
+    #[test]
+    fn test_combining_mark_joins_previous_grapheme() {
+        let mut input = TextInput::new("e");
+        input.insert_char('\u{301}');
+        assert_eq!(input.cursor(), input.text().len());
+        input.delete_backward();
+        assert_eq!(input.text(), "");
+    }
+
+    #[test]
+    fn test_home_end() {
+        let mut input = TextInput::new("build");
+        input.move_home();
+        assert_eq!(input.cursor(), 0);
+        input.move_left();
+        assert_eq!(input.cursor(), 0);
+        input.move_end();
+        input.move_right();
+        assert_eq!(input.cursor(), 5);
+    }
+
+    #[test]
+    fn test_delete_word_backward() {
+        let mut input = TextInput::new("--watch --coverage  ");
+        input.delete_word_backward();
+        assert_eq!(input.text(), "--watch ");
+        input.delete_word_backward();
+        assert_eq!(input.text(), "");
+        input.delete_word_backward();
+        assert_eq!(input.text(), "");
+    }
+
+    #[test]
+    fn test_delete_to_start() {
+        let mut input = TextInput::with_cursor("--port 4000", 7);
+        input.delete_to_start();
+        assert_eq!(input.text(), "4000");
+        assert_eq!(input.cursor(), 0);
+    }
+
+    #[test]
+    fn test_with_cursor_clamps_to_boundary() {
+        let input = TextInput::with_cursor("ñ", 1);
+        assert_eq!(input.cursor(), 0);
+        let input = TextInput::with_cursor("ab", 10);
+        assert_eq!(input.cursor(), 2);
+    }
+
+    #[test]
+    fn test_split_at_cursor() {
+        assert_eq!(split_at_cursor("dev", 1), ("d", "e", "v"));
+        assert_eq!(split_at_cursor("dev", 3), ("dev", "", ""));
+    }
+}
@@ -36,6 +36,13 @@ pub struct Theme {
     error_fg: Color,
     success_fg: Color,
     warning_fg: Color,
+
+    // Status indicators (shapes shown alongside color)
+    selected_marker: &'static str,
+    multiselect_marker: &'static str,
+    pinned_marker: &'static str,
+    error_marker: &'static str,
+    success_marker: &'static str,
 }
 
 impl Default for Theme {
@@ -51,6 +58,7 @@ impl Theme {
             ThemeConfig::Default => Self::default_theme(),
             ThemeConfig::Minimal => Self::minimal_theme(),
             ThemeConfig::None => Self::no_color_theme(),
+            ThemeConfig::Colorblind => Self::colorblind_theme(),
         }
     }
 
@@ -80,6 +88,12 @@ impl Theme {
             error_fg: Color::Red,
             success_fg: Color::Green,
             warning_fg: Color::Yellow,
+
+            selected_marker: ">",
+            multiselect_marker: "*",
+            pinned_marker: "+",
+            error_marker: "",
+            success_marker: "",
         }
     }
 
@@ -109,6 +123,12 @@ impl Theme {
             error_fg: Color::Red,
             success_fg: Color::Green,
             warning_fg: Color::Yellow,
+
+            selected_marker: ">",
+            multiselect_marker: "*",
+            pinned_marker: "+",
+            error_marker: "",
+            success_marker: "",
         }
     }
 
@@ -138,6 +158,51 @@ impl Theme {
             error_fg: Color::Reset,
             success_fg: Color::Reset,
             warning_fg: Color::Reset,
+
+            selected_marker: ">",
+            multiselect_marker: "*",
+            pinned_marker: "+",
+            error_marker: "",
+            success_marker: "",
+        }
+    }
+
+    /// Color-blind friendly theme (deuteranopia).
+    ///
+    /// Avoids red/green pairs by using the Okabe-Ito blue/orange palette,
+    /// and pairs every status color with a shape indicator.
+    fn colorblind_theme() -> Self {
+        let blue = Color::Rgb(0, 114, 178);
+        let sky = Color::Rgb(86, 180, 233);
+        let orange = Color::Rgb(230, 159, 0);
+        let vermillion = Color::Rgb(213, 94, 0);
+        let yellow = Color::Rgb(240, 228, 66);
+
+        Self {
+            header_bg: blue,
+            header_fg: Color::White,
+            filter_fg: yellow,
+            filter_placeholder_fg: Color::DarkGray,
+            number_fg: sky,
+            script_fg: Color::White,
+            selected_bg: blue,
+            selected_fg: Color::White,
+            cursor_fg: orange,
+            multiselect_fg: sky,
+            description_fg: Color::Gray,
+            command_fg: Color::DarkGray,
+            separator_fg: Color::DarkGray,
+            footer_fg: Color::DarkGray,
+            key_fg: sky,
+            error_fg: vermillion,
+            success_fg: blue,
+            warning_fg: orange,
+
+            selected_marker: "▶",
+            multiselect_marker: "✓",
+            pinned_marker: "★",
+            error_marker: "✗",
+            success_marker: "✓",
         }
     }
 
@@ -220,6 +285,13 @@ impl Theme {
             .add_modifier(Modifier::BOLD)
     }
 
+    /// Get the category group header style.
+    pub fn group_header(&self) -> Style {
+        Style::default()
+            .fg(self.key_fg)
+            .add_modifier(Modifier::BOLD)
+    }
+
     /// Get the multiselect marker style.
     pub fn multiselect(&self) -> Style {
         Style::default()
@@ -246,6 +318,44 @@ impl Theme {
         Style::default().fg(self.separator_fg)
     }
 
+    // ==================== Syntax Styles ====================
+
+    /// Get the style of the program a command runs.
+    pub fn syntax_program(&self) -> Style {
+        Style::default()
+            .fg(self.script_fg)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Get the style of chain, pipe and redirect operators.
+    pub fn syntax_operator(&self) -> Style {
+        Style::default()
+            .fg(self.key_fg)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Get the style of environment variables.
+    pub fn syntax_variable(&self) -> Style {
+        Style::default().fg(self.warning_fg)
+    }
+
+    /// Get the style of flags.
+    pub fn syntax_flag(&self) -> Style {
+        Style::default().fg(self.number_fg)
+    }
+
+    /// Get the style of file paths and globs.
+    pub fn syntax_path(&self) -> Style {
+        Style::default()
+            .fg(self.multiselect_fg)
+            .add_modifier(Modifier::UNDERLINED)
+    }
+
+    /// Get the style of quoted strings.
+    pub fn syntax_string(&self) -> Style {
+        Style::default().fg(self.success_fg)
+    }
+
     // ==================== Footer Styles ====================
 
     /// Get the footer style.
@@ -290,6 +400,42 @@ impl Theme {
     pub fn bold(&self) -> Style {
         Style::default().add_modifier(Modifier::BOLD)
     }
+
+    // ==================== Status Indicators ====================
+
+    /// Get the marker shown next to the selected script.
+    pub fn selected_marker(&self) -> &'static str {
+        self.selected_marker
+    }
+
+    /// Get the marker shown next to multi-selected scripts.
+    pub fn multiselect_marker(&self) -> &'static str {
+        self.multiselect_marker
+    }
+
+    /// Get the marker shown next to pinned scripts.
+    pub fn pinned_marker(&self) -> &'static str {
+        self.pinned_marker
+    }
+
+    /// Get the marker prefixed to error messages (may be empty).
+    pub fn error_marker(&self) -> &'static str {
+        self.error_marker
+    }
+
+    /// Get the marker prefixed to success messages (may be empty).
+    pub fn success_marker(&self) -> &'static str {
+        self.success_marker
+    }
+
+    /// Prefix a status label with a marker, if the theme defines one.
+    pub fn with_marker(marker: &str, label: &str) -> String {
+        if marker.is_empty() {
+            label.to_string()
+        } else {
+            format!("{} {}", marker, label)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -333,6 +479,26 @@ mod tests {
         assert!(style.add_modifier.contains(Modifier::UNDERLINED));
     }
 
+    #[test]
+    fn test_colorblind_theme() {
+        let theme = Theme::new(&ThemeConfig::Colorblind);
+        // No red/green pair for error vs success
+        assert_ne!(theme.error_fg, Color::Red);
+        assert_ne!(theme.success_fg, Color::Green);
+        // Status is conveyed by shape as well as color
+        assert_eq!(theme.selected_marker(), "▶");
+        assert_eq!(theme.multiselect_marker(), "✓");
+        assert_eq!(theme.error_marker(), "✗");
+        assert_eq!(Theme::with_marker(theme.error_marker(), "Error"), "✗ Error");
+    }
+
+    #[test]
+    fn test_default_theme_markers() {
+        let theme = Theme::default();
+        assert_eq!(theme.selected_marker(), ">");
+        assert_eq!(Theme::with_marker(theme.error_marker(), "Error"), "Error");
+    }
+
     #[test]
     fn test_no_color_theme() {
         let theme = Theme::new(&ThemeConfig::None);
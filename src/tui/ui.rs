@@ -1,28 +1,49 @@
 //! Main UI rendering and TUI loop.
 
-use std::io::{self, stdout, Stdout, Write};
+use std::io::{self, IsTerminal, Stderr, Stdout, Write};
 use std::panic;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use crossterm::{
-    cursor, event, execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    cursor,
+    event::{
+        self, DisableBracketedPaste, EnableBracketedPaste, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::Alignment,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Modifier,
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Clear, Paragraph, Wrap},
     Frame, Terminal,
 };
 
-use super::app::{App, AppMode, ScriptRun};
+use super::app::{App, AppMode, ErrorAction, ScriptRun};
+use super::crash;
 use super::input::handle_event;
+use super::keymap::{self, Keymap};
 use super::layout::{centered_rect_fixed, MainLayout};
+use super::message::MessageLevel;
 use super::theme::Theme;
-use super::widgets::{ArgsFilter, Description, EmptyScripts, Filter, Footer, Header, ScriptsGrid};
+use super::widgets::{
+    highlight_line, ArgsFilter, CommandPreview, Description, EmptyScripts, Filter, Footer, Header,
+    MessageFooter, OutputPane, OutputStatus, PromptInput, ScriptsGrid,
+};
+use crate::config::{Density, KeyAction, KeymapPreset};
+use crate::history::{weekday, Favorites, ProjectStats, STATS_SCRIPTS};
+use crate::package::{Excerpt, PreviewLine, ProjectInfo, Runner, Script, ScriptEdit, TreeLine};
+use crate::runner::ExecutionResult;
+use crate::utils::{clock, discard_pending_input, USABLE_MIN_HEIGHT, USABLE_MIN_WIDTH};
 
 /// Blink interval for cursor (in milliseconds).
 const CURSOR_BLINK_MS: u64 = 530;
@@ -30,10 +51,54 @@ const CURSOR_BLINK_MS: u64 = 530;
 /// Global flag to track if terminal is in raw mode.
 static TERMINAL_RAW_MODE: AtomicBool = AtomicBool::new(false);
 
+/// Global flag to track if the kitty keyboard protocol was enabled.
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// Global flag to track if the TUI is drawn on stderr.
+static DRAW_ON_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Stream the TUI is drawn on.
+///
+/// When stdout is redirected (e.g. `cmd=$(nrs --popup)`), the TUI is drawn
+/// on stderr so only the final output ends up in the capture.
+pub enum TuiWriter {
+    /// Standard output.
+    Stdout(Stdout),
+    /// Standard error.
+    Stderr(Stderr),
+}
+
+impl TuiWriter {
+    /// Get the writer the TUI is currently drawn on.
+    fn current() -> Self {
+        if DRAW_ON_STDERR.load(Ordering::SeqCst) {
+            Self::Stderr(io::stderr())
+        } else {
+            Self::Stdout(io::stdout())
+        }
+    }
+}
+
+impl Write for TuiWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(out) => out.write(buf),
+            Self::Stderr(err) => err.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(out) => out.flush(),
+            Self::Stderr(err) => err.flush(),
+        }
+    }
+}
+
 /// RAII guard for terminal state.
 /// Ensures terminal is properly restored even on panic.
 pub struct TerminalGuard {
-    terminal: Terminal<CrosstermBackend<Stdout>>,
+    terminal: Terminal<CrosstermBackend<TuiWriter>>,
 }
 
 impl TerminalGuard {
@@ -45,10 +110,29 @@ impl TerminalGuard {
         enable_raw_mode().context("Failed to enable raw mode")?;
         TERMINAL_RAW_MODE.store(true, Ordering::SeqCst);
 
-        let mut stdout = stdout();
+        DRAW_ON_STDERR.store(
+            !io::stdout().is_terminal() && io::stderr().is_terminal(),
+            Ordering::SeqCst,
+        );
+        let mut stdout = TuiWriter::current();
         execute!(stdout, EnterAlternateScreen, cursor::Hide)
             .context("Failed to enter alternate screen")?;
 
+        // Deliver pasted text as a single event instead of a burst of keys
+        let _ = execute!(stdout, EnableBracketedPaste);
+
+        // Enable the kitty keyboard protocol so modifiers on Enter and digits
+        // can be told apart; terminals without it keep the legacy encoding
+        if supports_keyboard_enhancement().unwrap_or(false)
+            && execute!(
+                stdout,
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+            )
+            .is_ok()
+        {
+            KEYBOARD_ENHANCED.store(true, Ordering::SeqCst);
+        }
+
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend).context("Failed to create terminal")?;
 
@@ -56,7 +140,7 @@ impl TerminalGuard {
     }
 
     /// Get a mutable reference to the terminal.
-    pub fn terminal(&mut self) -> &mut Terminal<CrosstermBackend<Stdout>> {
+    pub fn terminal(&mut self) -> &mut Terminal<CrosstermBackend<TuiWriter>> {
         &mut self.terminal
     }
 }
@@ -64,10 +148,14 @@ impl TerminalGuard {
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
         // Restore terminal state
+        if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+            let _ = execute!(self.terminal.backend_mut(), PopKeyboardEnhancementFlags);
+        }
         let _ = disable_raw_mode();
         TERMINAL_RAW_MODE.store(false, Ordering::SeqCst);
         let _ = execute!(
             self.terminal.backend_mut(),
+            DisableBracketedPaste,
             LeaveAlternateScreen,
             cursor::Show
         );
@@ -80,37 +168,107 @@ fn setup_panic_hook() {
 
     panic::set_hook(Box::new(move |panic_info| {
         // Restore terminal
+        if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+            let _ = execute!(TuiWriter::current(), PopKeyboardEnhancementFlags);
+        }
         if TERMINAL_RAW_MODE.load(Ordering::SeqCst) {
             let _ = disable_raw_mode();
-            let _ = execute!(stdout(), LeaveAlternateScreen, cursor::Show);
+            let _ = execute!(
+                TuiWriter::current(),
+                DisableBracketedPaste,
+                LeaveAlternateScreen,
+                cursor::Show
+            );
         }
 
-        // Call the original panic hook
-        original_hook(panic_info);
+        // Write a crash report instead of dumping a backtrace over the prompt
+        let payload = panic_info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let location = panic_info.location().map(|l| l.to_string());
+
+        match crash::write_crash_report(&message, location.as_deref()) {
+            Some(report) => {
+                crash::print_crash_notice(&message, &report);
+                // Keep the raw output available for debugging
+                if std::env::var_os("RUST_BACKTRACE").is_some() {
+                    original_hook(panic_info);
+                }
+            }
+            None => original_hook(panic_info),
+        }
     }));
 }
 
 /// Restore terminal to normal state.
 /// Call this before running external commands.
 pub fn restore_terminal() -> Result<()> {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+        execute!(TuiWriter::current(), PopKeyboardEnhancementFlags)
+            .context("Failed to restore keyboard mode")?;
+    }
     if TERMINAL_RAW_MODE.load(Ordering::SeqCst) {
+        discard_pending_input();
         disable_raw_mode().context("Failed to disable raw mode")?;
-        execute!(stdout(), LeaveAlternateScreen, cursor::Show)
-            .context("Failed to leave alternate screen")?;
+        execute!(
+            TuiWriter::current(),
+            DisableBracketedPaste,
+            LeaveAlternateScreen,
+            cursor::Show
+        )
+        .context("Failed to leave alternate screen")?;
         TERMINAL_RAW_MODE.store(false, Ordering::SeqCst);
     }
-    io::stdout().flush()?;
+    TuiWriter::current().flush()?;
     Ok(())
 }
 
+/// Result of a TUI session.
+#[derive(Debug, Default)]
+pub struct TuiOutcome {
+    /// Scripts to run after the TUI exits.
+    pub scripts: Vec<ScriptRun>,
+    /// Workspace directory yanked for printing.
+    pub yanked_path: Option<PathBuf>,
+    /// Filter query active when the script was chosen.
+    pub filter: Option<String>,
+    /// Runner chosen in the TUI (may differ after switching runner).
+    pub runner: Runner,
+    /// Error action to complete outside the TUI.
+    pub action: Option<ErrorAction>,
+    /// Scripts run in the output pane, with their results.
+    pub pane_runs: Vec<(ScriptRun, ExecutionResult)>,
+    /// Pinned scripts, if any were pinned or unpinned.
+    pub favorites: Option<Favorites>,
+    /// Collapsed category groups, if any were collapsed or expanded.
+    pub collapsed_groups: Option<Vec<String>>,
+    /// Scripts renamed or removed, with the directory of their package.json.
+    pub script_edits: Vec<(PathBuf, ScriptEdit)>,
+    /// Whether `scripts` runs one script in every workspace defining it.
+    pub all_workspaces: bool,
+    /// Whether the script is rerun whenever files change.
+    pub watch: bool,
+    /// Whether the scripts to run were confirmed in the TUI.
+    pub confirmed: bool,
+    /// Whether missing dependencies were asked about in the TUI.
+    pub install_checked: bool,
+    /// Whether to install missing dependencies before running the scripts.
+    pub install_first: bool,
+}
+
 /// Run the TUI application.
 ///
-/// Returns the scripts to run after TUI exits, along with their arguments.
-pub fn run_tui(mut app: App) -> Result<Vec<ScriptRun>> {
+/// Returns the scripts to run after TUI exits, along with their arguments,
+/// or a workspace path to print.
+pub fn run_tui(mut app: App) -> Result<TuiOutcome> {
     let mut guard = TerminalGuard::new()?;
 
     // Main loop
     let result = run_loop(guard.terminal(), &mut app);
+    app.close_pane();
 
     // Guard will restore terminal on drop
     drop(guard);
@@ -118,36 +276,62 @@ pub fn run_tui(mut app: App) -> Result<Vec<ScriptRun>> {
     result?;
 
     // Return all scripts to run
-    if let Some(script_run) = app.script_to_run() {
-        Ok(vec![script_run.clone()])
-    } else {
-        Ok(vec![])
-    }
+    Ok(TuiOutcome {
+        scripts: app.scripts_to_run(),
+        yanked_path: app.yanked_path().map(PathBuf::from),
+        filter: app.filter_for_run().map(String::from),
+        runner: app.runner(),
+        action: app.requested_action(),
+        pane_runs: app.finished_runs().to_vec(),
+        favorites: app.changed_favorites().cloned(),
+        collapsed_groups: app.changed_collapsed_groups(),
+        script_edits: app.script_edits().to_vec(),
+        all_workspaces: app.is_all_workspaces_run(),
+        watch: app.is_watch_run(),
+        confirmed: app.is_run_confirmed(),
+        install_checked: app.is_install_checked(),
+        install_first: app.is_install_first(),
+    })
 }
 
 /// Main TUI loop.
-fn run_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> Result<()> {
+fn run_loop(terminal: &mut Terminal<CrosstermBackend<TuiWriter>>, app: &mut App) -> Result<()> {
     let theme = Theme::new(&app.config().appearance.theme);
     let mut last_blink = Instant::now();
     let mut blink_state = true;
 
     loop {
-        // Update blink state
-        if last_blink.elapsed() >= Duration::from_millis(CURSOR_BLINK_MS) {
+        // Update blink state (pinned on in deterministic mode)
+        if !clock::is_deterministic()
+            && last_blink.elapsed() >= Duration::from_millis(CURSOR_BLINK_MS)
+        {
             blink_state = !blink_state;
             last_blink = Instant::now();
         }
 
-        // Update columns based on terminal size
+        // Expire footer messages
+        app.tick_messages(Instant::now());
+
+        // Collect output of a script running in the output pane
+        app.poll_pane();
+
+        // Update grid geometry based on terminal size
         let size = terminal.size()?;
-        app.update_columns(size.width);
+        app.resize(size.width, size.height);
 
         // Draw UI
+        let started = Instant::now();
         terminal.draw(|frame| render(frame, app, &theme, blink_state))?;
+        app.record_frame(started.elapsed());
 
         // Handle events
         if event::poll(Duration::from_millis(50))? {
             let event = event::read()?;
+            crash::record_action(match &event {
+                // Pasted text may be long or sensitive
+                event::Event::Paste(_) => format!("Paste in {} mode", app.mode().name()),
+                other => format!("{:?} in {} mode", other, app.mode().name()),
+            });
             if handle_event(app, event)? {
                 break;
             }
@@ -167,13 +351,29 @@ fn run_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App
 /// Render the complete UI.
 pub fn render(frame: &mut Frame, app: &App, theme: &Theme, blink_state: bool) {
     let config = &app.config().appearance;
-    let layout = MainLayout::with_config(frame.area(), config);
+    let area = frame.area();
+
+    if area.width < USABLE_MIN_WIDTH || area.height < USABLE_MIN_HEIGHT {
+        render_too_small(frame, theme);
+        return;
+    }
+
+    let layout = MainLayout::with_config(area, config);
+
+    // Small terminals drop inline descriptions and the description panel
+    let density = if layout.degraded {
+        Density::Compact
+    } else {
+        config.density
+    };
 
     // Render main components
     render_header(frame, app, theme, layout.header);
     render_filter(frame, app, theme, layout.filter, blink_state);
-    render_scripts(frame, app, theme, layout.scripts);
-    render_description(frame, app, theme, layout.description);
+    render_scripts(frame, app, theme, layout.scripts, density);
+    if !layout.degraded {
+        render_description(frame, app, theme, layout.description);
+    }
 
     if config.show_footer {
         render_footer(frame, app, theme, layout.footer);
@@ -181,11 +381,48 @@ pub fn render(frame: &mut Frame, app: &App, theme: &Theme, blink_state: bool) {
 
     // Render overlays
     match app.mode() {
-        AppMode::Help => render_help_overlay(frame, theme),
-        AppMode::Error { message } => render_error_overlay(frame, theme, message),
+        AppMode::Help => render_help_overlay(frame, app.keymap(), theme),
+        AppMode::Details => render_details_overlay(frame, app, theme),
+        AppMode::Preview => render_preview_overlay(frame, app, theme),
+        AppMode::Source { excerpt } => render_source_overlay(frame, excerpt, theme),
+        AppMode::Dependencies { tree } => render_dependencies_overlay(frame, tree, theme),
+        AppMode::ProjectInfo { info } => render_project_info_overlay(frame, info, theme),
+        AppMode::Stats { stats } => render_stats_overlay(frame, stats, theme),
+        AppMode::ConfirmEdit { edit, preview } => {
+            render_confirm_edit_overlay(frame, edit, preview, theme)
+        }
+        AppMode::ConfirmRun { names } => render_confirm_run_overlay(frame, names, theme),
+        AppMode::InstallPrompt { command } => render_install_prompt_overlay(frame, command, theme),
+        AppMode::KeyConflicts { actions } => render_key_conflicts_overlay(frame, actions, theme),
+        AppMode::Error { message, actions } => {
+            render_error_overlay(frame, app, theme, message, actions)
+        }
         AppMode::WorkspaceSelect => render_workspace_selector(frame, app, theme, layout.scripts),
+        AppMode::Categories => render_category_summary(frame, app, theme, layout.scripts),
+        AppMode::GlobalSearch { selected, .. } => {
+            render_global_search(frame, app, theme, layout.scripts, *selected)
+        }
+        AppMode::Running => {
+            render_output_pane(frame, app, theme, layout.scripts.union(layout.description))
+        }
         _ => {}
     }
+
+    if app.is_profile_shown() {
+        render_profile_overlay(frame, app, theme);
+    }
+}
+
+/// Render a notice when the terminal is too small to be usable.
+fn render_too_small(frame: &mut Frame, theme: &Theme) {
+    let notice = Paragraph::new(Line::from(Span::styled(
+        "Terminal too small",
+        theme.warning(),
+    )))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+
+    frame.render_widget(notice, frame.area());
 }
 
 /// Render the header.
@@ -193,7 +430,7 @@ fn render_header(frame: &mut Frame, app: &App, theme: &Theme, area: ratatui::lay
     let config = &app.config().appearance;
     // Use breadcrumb if in workspace context
     let title = app.breadcrumb();
-    let header = Header::new(&title, app.runner(), theme, config);
+    let header = Header::new(&title, app.runner(), theme, config).monorepo(app.monorepo_label());
     frame.render_widget(header, area);
 }
 
@@ -209,14 +446,63 @@ fn render_filter(
 
     match app.mode() {
         AppMode::Filter { query } => {
-            let filter = Filter::new(query, true, theme, config).blink(blink_state);
+            let filter = Filter::new(query, true, theme, config)
+                .blink(blink_state)
+                .cursor(app.input_cursor());
             frame.render_widget(filter, area);
         }
         AppMode::Args { input, .. } => {
             let script_name = app.selected_script().map(|s| s.name()).unwrap_or("script");
-            let args_filter = ArgsFilter::new(script_name, input, theme).blink(blink_state);
+            let args_filter = ArgsFilter::new(script_name, input, theme)
+                .blink(blink_state)
+                .cursor(app.input_cursor());
             frame.render_widget(args_filter, area);
         }
+        AppMode::NewScript { name, input } => {
+            let prompt = match name {
+                Some(name) => format!("Command for {name}"),
+                None => "New script name".to_string(),
+            };
+            let prompt_input = PromptInput::new(&prompt, input, theme)
+                .blink(blink_state)
+                .cursor(app.input_cursor());
+            frame.render_widget(prompt_input, area);
+        }
+        AppMode::GlobalSearch { query, .. } => {
+            let prompt_input = PromptInput::new("Search all workspaces", query, theme)
+                .blink(blink_state)
+                .cursor(app.input_cursor());
+            frame.render_widget(prompt_input, area);
+        }
+        AppMode::RenameScript { from, input } => {
+            let prompt = format!("New name for {from}");
+            let prompt_input = PromptInput::new(&prompt, input, theme)
+                .blink(blink_state)
+                .cursor(app.input_cursor());
+            frame.render_widget(prompt_input, area);
+        }
+        AppMode::Normal if !app.pending_keys().is_empty() => {
+            // Chord in progress
+            let keys: Vec<String> = app
+                .pending_keys()
+                .iter()
+                .map(|key| keymap::format_key(*key))
+                .collect();
+            let line = Line::from(vec![
+                Span::styled(format!(" {} …", keys.join(" ")), theme.filter_active()),
+                Span::styled("  Esc to cancel", theme.filter_placeholder()),
+            ]);
+            frame.render_widget(Paragraph::new(line), area);
+        }
+        AppMode::Normal if app.pending_number().is_some() => {
+            // Multi-digit quick select in progress
+            let number = app.pending_number().unwrap_or_default();
+            let line = Line::from(vec![
+                Span::styled(format!(" #{}", number), theme.filter_active()),
+                Span::styled("  Enter to run, Esc to cancel", theme.filter_placeholder()),
+            ]);
+            frame.render_widget(Paragraph::new(line), area);
+        }
         _ => {
             let query = app.filter_text();
             let filter = Filter::new(query, false, theme, config);
@@ -226,7 +512,13 @@ fn render_filter(
 }
 
 /// Render the scripts grid.
-fn render_scripts(frame: &mut Frame, app: &App, theme: &Theme, area: ratatui::layout::Rect) {
+fn render_scripts(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    area: ratatui::layout::Rect,
+    density: Density,
+) {
     let visible = app.visible_scripts();
 
     if visible.is_empty() {
@@ -239,14 +531,24 @@ fn render_scripts(frame: &mut Frame, app: &App, theme: &Theme, area: ratatui::la
         return;
     }
 
-    let mut grid =
-        ScriptsGrid::new(&visible, app.selected_index(), theme).scroll_offset(app.scroll_offset());
+    let groups = app.group_layout();
+    let mut grid = ScriptsGrid::new(&visible, app.selected_index(), theme)
+        .scroll_offset(app.scroll_offset())
+        .density(density);
+    if let Some(layout) = &groups {
+        grid = grid.groups(layout);
+    }
 
     // Add multi-select state if in that mode
     if let AppMode::MultiSelect { selected } = app.mode() {
         grid = grid.multi_selected(selected);
     }
 
+    let pinned = app.pinned_count();
+    if pinned > 0 {
+        grid = grid.pinned(pinned);
+    }
+
     frame.render_widget(grid, area);
 }
 
@@ -254,14 +556,27 @@ fn render_scripts(frame: &mut Frame, app: &App, theme: &Theme, area: ratatui::la
 fn render_description(frame: &mut Frame, app: &App, theme: &Theme, area: ratatui::layout::Rect) {
     let config = &app.config().appearance;
     let script = app.selected_script();
+    let history = script.and_then(|s| app.script_history(s.name()));
     let desc = Description::new(script, theme, config)
-        .with_command_preview(app.config().general.show_command_preview);
+        .with_command_preview(app.config().general.show_command_preview)
+        .history(history);
     frame.render_widget(desc, area);
 }
 
-/// Render the footer.
+/// Render the footer, or the current message in its place.
 fn render_footer(frame: &mut Frame, app: &App, theme: &Theme, area: ratatui::layout::Rect) {
-    let footer = Footer::new(app.mode(), theme);
+    if let Some(message) = app.current_message() {
+        let footer = MessageFooter::new(&message.text, theme);
+        let footer = match message.level {
+            MessageLevel::Info => footer,
+            MessageLevel::Warn => footer.warning(),
+            MessageLevel::Error => footer.error(),
+        };
+        frame.render_widget(footer, area);
+        return;
+    }
+
+    let footer = Footer::new(app.mode(), theme).keymap(app.keymap());
     frame.render_widget(footer, area);
 }
 
@@ -275,14 +590,16 @@ fn render_workspace_selector(
     use ratatui::text::{Line, Span};
     use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
 
-    let workspaces = app.workspaces();
+    let workspaces = app.workspace_level();
     let selected = app.workspace_selected();
+    let trail = app.workspace_trail();
 
     // Build list items: [root, workspace1, workspace2, ...]
     let mut items: Vec<ListItem> = Vec::with_capacity(workspaces.len() + 1);
 
-    // Root item
-    let root_label = format!(" 1  {} (root)", app.project_name());
+    // Root item: the project, or the nested monorepo drilled into
+    let root_name = trail.last().map_or(app.project_name(), |ws| ws.name());
+    let root_label = format!(" 1  {} (root)", root_name);
     let root_style = if selected == 0 {
         theme.selected()
     } else {
@@ -306,15 +623,41 @@ fn render_workspace_selector(
         } else {
             theme.script()
         };
-        items.push(ListItem::new(Line::from(Span::styled(label, style))));
+        let mut spans = vec![Span::styled(label, style)];
+        if let Some(error) = ws.error() {
+            spans.push(Span::styled(format!("  ⚠ {error}"), theme.warning()));
+        }
+        if ws.has_members() {
+            let count = ws.members().len();
+            let noun = if count == 1 {
+                "workspace"
+            } else {
+                "workspaces"
+            };
+            spans.push(Span::styled(
+                format!(" ▸ {count} {noun}"),
+                theme.description(),
+            ));
+        }
+        items.push(ListItem::new(Line::from(spans)));
     }
 
+    // Breadcrumbs of the nested monorepos drilled into
+    let title = if trail.is_empty() {
+        " Select Workspace ".to_string()
+    } else {
+        let crumbs: Vec<&str> = std::iter::once(app.project_name())
+            .chain(trail.iter().map(|ws| ws.name()))
+            .collect();
+        format!(" Select Workspace: {} ", crumbs.join(" › "))
+    };
+
     // Create the list widget
     let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Select Workspace ")
+                .title(title)
                 .title_style(theme.bold())
                 .border_style(theme.separator()),
         )
@@ -327,95 +670,888 @@ fn render_workspace_selector(
     frame.render_stateful_widget(list, area, &mut state);
 }
 
+/// Render the output pane of a script running inside the TUI.
+fn render_output_pane(frame: &mut Frame, app: &App, theme: &Theme, area: ratatui::layout::Rect) {
+    let Some(pane) = app.pane_run() else {
+        return;
+    };
+
+    let title = pane.run().to_string();
+    let status = match pane.exit_code() {
+        Some(code) => OutputStatus::Exited(code),
+        None => OutputStatus::Running,
+    };
+    let output = OutputPane::new(&title, pane.lines(), theme)
+        .status(status)
+        .scroll(pane.scroll());
+    frame.render_widget(output, area);
+}
+
+/// Render the category summary.
+fn render_category_summary(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    area: ratatui::layout::Rect,
+) {
+    use ratatui::widgets::{List, ListItem, ListState};
+
+    let categories = app.categories();
+    let selected = app.category_selected();
+    let name_width = categories
+        .iter()
+        .map(|(category, _)| category.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("all scripts".len());
+
+    // Build list items: [all scripts, category1, category2, ...]
+    let rows = std::iter::once(("all scripts", app.scripts().len())).chain(categories);
+    let items: Vec<ListItem> = rows
+        .enumerate()
+        .map(|(i, (category, count))| {
+            let num = if i < 9 {
+                format!(" {} ", i + 1)
+            } else {
+                "   ".to_string()
+            };
+            let style = if selected == i {
+                theme.selected()
+            } else {
+                theme.script()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(num, theme.number()),
+                Span::styled(format!(" {:width$}", category, width = name_width), style),
+                Span::styled(format!("  {:>3}", count), theme.description()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Categories ")
+                .title_style(theme.bold())
+                .border_style(theme.separator()),
+        )
+        .highlight_style(theme.selected());
+
+    let mut state = ListState::default();
+    state.select(Some(selected));
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Render the scripts matching the search across all workspaces, as
+/// `workspace > script`.
+fn render_global_search(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    area: ratatui::layout::Rect,
+    selected: usize,
+) {
+    use ratatui::widgets::{List, ListItem, ListState};
+
+    let matches = app.global_matches();
+    let items: Vec<ListItem> = matches
+        .iter()
+        .enumerate()
+        .map(|(i, found)| {
+            let style = if selected == i {
+                theme.selected()
+            } else {
+                theme.script()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {}", found.label), theme.description()),
+                Span::styled(" > ", theme.separator()),
+                Span::styled(found.script.as_str(), style),
+            ]))
+        })
+        .collect();
+
+    let title = match matches.len() {
+        1 => " 1 script ".to_string(),
+        count => format!(" {count} scripts "),
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" All workspaces ")
+                .title_bottom(title)
+                .title_style(theme.bold())
+                .border_style(theme.separator()),
+        )
+        .highlight_style(theme.selected());
+
+    let mut state = ListState::default();
+    state.select((!matches.is_empty()).then_some(selected));
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
 /// Render the help overlay.
-fn render_help_overlay(frame: &mut Frame, theme: &Theme) {
+fn render_help_overlay(frame: &mut Frame, keymap: &Keymap, theme: &Theme) {
     let area = frame.area();
-    let help_area = centered_rect_fixed(50, 18, area);
+    let help_area = centered_rect_fixed(50, 35, area);
+
+    // Keys are shown as bound or as the preset's equivalent, and rows without
+    // one are left out
+    let row = |key: &'static str, description: &'static str| {
+        keymap.label(key).map(|label| {
+            Line::from(vec![
+                Span::styled(format!("  {label:<8}"), theme.key()),
+                Span::styled(description, theme.description()),
+            ])
+        })
+    };
+    let blank = || Some(Line::from(""));
+    let title = match keymap.preset() {
+        KeymapPreset::Vim => " Help ",
+        KeymapPreset::Emacs => " Help (emacs keys, type to filter) ",
+        KeymapPreset::Plain => " Help (plain keys, type to filter) ",
+    };
 
     // Clear the area
     frame.render_widget(Clear, help_area);
 
     // Help content
     let help_lines = vec![
-        Line::from(Span::styled("Keyboard Shortcuts", theme.bold())),
+        Some(Line::from(Span::styled("Keyboard Shortcuts", theme.bold()))),
+        blank(),
+        row("j/k", "Move up/down"),
+        row("h/l", "Move left/right (in grid)"),
+        row("g/G", "First/last item"),
+        blank(),
+        row("Enter", "Run selected script"),
+        row("C-Enter", "Run with last arguments"),
+        row("r", "Run in output pane"),
+        row("A", "Run in all workspaces"),
+        row("F", "Search all workspaces"),
+        row("W", "Rerun on file changes"),
+        row("1-9", "Quick run numbered script"),
+        row("M-1-9", "Select numbered script"),
+        row("/", "Filter scripts"),
+        row("s", "Cycle sort mode"),
+        row("Tab", "Collapse/expand category group"),
+        row("f", "Pin/unpin script"),
+        row("v", "View full script details"),
+        row("z", "Preview command full screen"),
+        row("p", "View script in package.json"),
+        row("d", "Show scripts it runs"),
+        row("i", "Show project info"),
+        row("u", "Show usage stats"),
+        row("n", "Add a script to package.json"),
+        row("e", "Rename the script"),
+        row("x", "Remove the script"),
+        row("c", "Browse categories"),
+        blank(),
+        row("?", "Toggle this help"),
+        row("F12", "Toggle debug timings"),
+        row("q/Esc", "Quit"),
+        blank(),
+        Some(Line::from(Span::styled(
+            "Press any key to close",
+            theme.filter_placeholder(),
+        ))),
+    ];
+    let help_lines: Vec<Line> = help_lines.into_iter().flatten().collect();
+
+    let help = Paragraph::new(help_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .style(theme.description()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(help, help_area);
+}
+
+/// Build the lines shown in the details popover.
+fn details_lines<'a>(script: &'a Script, theme: &Theme) -> Vec<Line<'a>> {
+    let description = script.description().unwrap_or("(no description)");
+    let mut command = highlight_line(script.command(), theme, usize::MAX);
+    command.spans.insert(0, Span::styled("$ ", theme.command()));
+
+    vec![
+        Line::from(Span::styled("Name", theme.bold())),
+        Line::from(Span::styled(script.name(), theme.script())),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  j/k     ", theme.key()),
-            Span::styled("Move up/down", theme.description()),
-        ]),
-        Line::from(vec![
-            Span::styled("  h/l     ", theme.key()),
-            Span::styled("Move left/right (in grid)", theme.description()),
-        ]),
-        Line::from(vec![
-            Span::styled("  g/G     ", theme.key()),
-            Span::styled("First/last item", theme.description()),
-        ]),
+        Line::from(Span::styled("Command", theme.bold())),
+        command,
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  Enter   ", theme.key()),
-            Span::styled("Run selected script", theme.description()),
-        ]),
-        Line::from(vec![
-            Span::styled("  1-9     ", theme.key()),
-            Span::styled("Quick run numbered script", theme.description()),
-        ]),
-        Line::from(vec![
-            Span::styled("  /       ", theme.key()),
-            Span::styled("Filter scripts", theme.description()),
-        ]),
-        Line::from(vec![
-            Span::styled("  s       ", theme.key()),
-            Span::styled("Cycle sort mode", theme.description()),
-        ]),
+        Line::from(Span::styled("Description", theme.bold())),
+        Line::from(Span::styled(description, theme.description())),
         Line::from(""),
+        Line::from(Span::styled(
+            "Press any key to close",
+            theme.filter_placeholder(),
+        )),
+    ]
+}
+
+/// Count the rows a set of lines takes once wrapped to `width`.
+fn wrapped_height(lines: &[Line], width: u16) -> u16 {
+    let width = width.max(1) as usize;
+    lines
+        .iter()
+        .map(|line| ((line.width().max(1) + width - 1) / width) as u16)
+        .sum()
+}
+
+/// Render the full details popover for the selected script.
+fn render_details_overlay(frame: &mut Frame, app: &App, theme: &Theme) {
+    let Some(script) = app.selected_script() else {
+        return;
+    };
+
+    let area = frame.area();
+    let width = area.width.saturating_sub(4).min(80);
+    let lines = details_lines(script, theme);
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let details_area = centered_rect_fixed(width, height, area);
+
+    // Clear the area
+    frame.render_widget(Clear, details_area);
+
+    let details = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Details ")
+                .style(theme.description()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(details, details_area);
+}
+
+/// Render the full-screen preview of the selected script's command.
+fn render_preview_overlay(frame: &mut Frame, app: &App, theme: &Theme) {
+    let Some(script) = app.selected_script() else {
+        return;
+    };
+
+    let area = frame.area();
+    let preview_area = Rect::new(
+        area.x + 1,
+        area.y + 1,
+        area.width.saturating_sub(2),
+        area.height.saturating_sub(2),
+    );
+    frame.render_widget(Clear, preview_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", script.name()))
+        .title_bottom(Line::from(" any key to close ").right_aligned())
+        .style(theme.description());
+    let inner = block.inner(preview_area);
+    frame.render_widget(block, preview_area);
+    frame.render_widget(CommandPreview::new(script.command(), theme), inner);
+}
+
+/// Build the lines shown in the source popover, with line numbers.
+///
+/// Gaps between lines that aren't adjacent in the file are marked.
+fn source_lines<'a>(excerpt: &'a Excerpt, theme: &Theme) -> Vec<Line<'a>> {
+    let number_width = excerpt
+        .lines
+        .last()
+        .map_or(1, |line| line.number.to_string().len());
+
+    let mut lines = Vec::new();
+    let mut previous: Option<usize> = None;
+    for line in &excerpt.lines {
+        if previous.is_some_and(|n| line.number > n + 1) {
+            lines.push(Line::from(Span::styled(
+                format!("{:>number_width$} ┆", ""),
+                theme.separator(),
+            )));
+        }
+        previous = Some(line.number);
+
+        let style = if line.is_target {
+            theme.script()
+        } else {
+            theme.description()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>number_width$} │ ", line.number), theme.number()),
+            Span::styled(line.text.as_str(), style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press any key to close",
+        theme.filter_placeholder(),
+    )));
+    lines
+}
+
+/// Render the source popover with the JSON defining the selected script.
+fn render_source_overlay(frame: &mut Frame, excerpt: &Excerpt, theme: &Theme) {
+    let area = frame.area();
+    let width = area.width.saturating_sub(4).min(100);
+    let lines = source_lines(excerpt, theme);
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let source_area = centered_rect_fixed(width, height, area);
+    let title = excerpt
+        .file
+        .file_name()
+        .map(|name| format!(" {} ", name.to_string_lossy()))
+        .unwrap_or_default();
+
+    frame.render_widget(Clear, source_area);
+
+    let source = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .style(theme.description()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(source, source_area);
+}
+
+/// Build the lines shown in the dependencies popover.
+fn dependency_lines<'a>(tree: &'a [TreeLine], theme: &Theme) -> Vec<Line<'a>> {
+    let mut lines: Vec<Line> = tree
+        .iter()
+        .map(|line| match line.annotation() {
+            Some(annotation) => Line::from(vec![
+                Span::styled(line.prefix.as_str(), theme.separator()),
+                Span::styled(line.name.as_str(), theme.description()),
+                Span::styled(format!(" {annotation}"), theme.filter_placeholder()),
+            ]),
+            None => Line::from(Span::styled(line.name.as_str(), theme.script())),
+        })
+        .collect();
+
+    if tree.len() == 1 {
+        lines.push(Line::from(Span::styled(
+            "Doesn't run any other scripts",
+            theme.description(),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press any key to close",
+        theme.filter_placeholder(),
+    )));
+    lines
+}
+
+/// Build the lines shown when confirming a script edit.
+fn confirm_edit_lines<'a>(
+    edit: &ScriptEdit,
+    preview: &'a [PreviewLine],
+    theme: &Theme,
+) -> Vec<Line<'a>> {
+    let mut lines: Vec<Line> = preview
+        .iter()
+        .map(|line| match line {
+            PreviewLine::Unchanged(text) => {
+                Line::from(Span::styled(format!("  {text}"), theme.description()))
+            }
+            PreviewLine::Removed(text) => {
+                Line::from(Span::styled(format!("- {text}"), theme.error()))
+            }
+            PreviewLine::Added(text) => {
+                Line::from(Span::styled(format!("+ {text}"), theme.success()))
+            }
+        })
+        .collect();
+
+    let note = match edit {
+        ScriptEdit::Rename { to, .. } => format!("Its history and pin move to {to}"),
+        ScriptEdit::Remove { .. } => "Its history and pin are forgotten".to_string(),
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(note, theme.filter_placeholder())));
+    lines.push(Line::from(vec![
+        Span::styled("y", theme.key()),
+        Span::styled(" confirm  ", theme.description()),
+        Span::styled("n", theme.key()),
+        Span::styled(" cancel", theme.description()),
+    ]));
+    lines
+}
+
+/// Render the confirmation for renaming or removing a script.
+fn render_confirm_edit_overlay(
+    frame: &mut Frame,
+    edit: &ScriptEdit,
+    preview: &[PreviewLine],
+    theme: &Theme,
+) {
+    let area = frame.area();
+    let width = area.width.saturating_sub(4).min(100);
+    let lines = confirm_edit_lines(edit, preview, theme);
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let confirm_area = centered_rect_fixed(width, height, area);
+    let title = match edit {
+        ScriptEdit::Rename { from, to } => format!(" Rename {from} to {to} in package.json "),
+        ScriptEdit::Remove { name } => format!(" Remove {name} from package.json "),
+    };
+
+    frame.render_widget(Clear, confirm_area);
+
+    let confirm = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .style(theme.description()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(confirm, confirm_area);
+}
+
+/// Render the question asked before running scripts matching
+/// `scripts.confirm`.
+fn render_confirm_run_overlay(frame: &mut Frame, names: &[String], theme: &Theme) {
+    let area = frame.area();
+    let width = area.width.saturating_sub(4).min(60);
+    let mut lines: Vec<Line> = names
+        .iter()
+        .map(|name| Line::from(Span::styled(format!("  {name}"), theme.warning())))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("y", theme.key()),
+        Span::styled(" run  ", theme.description()),
+        Span::styled("n", theme.key()),
+        Span::styled(" cancel", theme.description()),
+    ]));
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let confirm_area = centered_rect_fixed(width, height, area);
+
+    frame.render_widget(Clear, confirm_area);
+
+    let confirm = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Run? ")
+                .style(theme.description()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(confirm, confirm_area);
+}
+
+/// Render the overlay offering to install missing dependencies first.
+fn render_install_prompt_overlay(frame: &mut Frame, command: &str, theme: &Theme) {
+    let area = frame.area();
+    let width = area.width.saturating_sub(4).min(60);
+    let lines = vec![
+        Line::from(Span::styled(
+            "node_modules is missing, so scripts will likely fail.",
+            theme.description(),
+        )),
         Line::from(vec![
-            Span::styled("  ?       ", theme.key()),
-            Span::styled("Toggle this help", theme.description()),
+            Span::styled("Run ", theme.description()),
+            Span::styled(command.to_string(), theme.warning()),
+            Span::styled(" first?", theme.description()),
         ]),
+        Line::from(""),
         Line::from(vec![
-            Span::styled("  q/Esc   ", theme.key()),
-            Span::styled("Quit", theme.description()),
+            Span::styled("y", theme.key()),
+            Span::styled(" install first  ", theme.description()),
+            Span::styled("n", theme.key()),
+            Span::styled(" run anyway  ", theme.description()),
+            Span::styled("Esc", theme.key()),
+            Span::styled(" cancel", theme.description()),
         ]),
-        Line::from(""),
+    ];
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let prompt_area = centered_rect_fixed(width, height, area);
+
+    frame.render_widget(Clear, prompt_area);
+
+    let prompt = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Install dependencies? ")
+                .style(theme.description()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(prompt, prompt_area);
+}
+
+/// Render the overlay for essential actions left without a key.
+fn render_key_conflicts_overlay(frame: &mut Frame, actions: &[KeyAction], theme: &Theme) {
+    let area = frame.area();
+    let width = area.width.saturating_sub(4).min(60);
+    let mut lines = vec![
         Line::from(Span::styled(
-            "Press any key to close",
-            theme.filter_placeholder(),
+            "Your [keybindings] leave no key for:",
+            theme.description(),
         )),
+        Line::from(""),
     ];
+    lines.extend(actions.iter().map(|action| {
+        Line::from(Span::styled(
+            format!("  {}", action.name()),
+            theme.warning(),
+        ))
+    }));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Restoring gives them their default keys until nrs exits.",
+        theme.description(),
+    )));
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("r", theme.key()),
+        Span::styled(" restore  ", theme.description()),
+        Span::styled("e", theme.key()),
+        Span::styled(" edit config  ", theme.description()),
+        Span::styled("c", theme.key()),
+        Span::styled(" continue", theme.description()),
+    ]));
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let conflicts_area = centered_rect_fixed(width, height, area);
+
+    frame.render_widget(Clear, conflicts_area);
+
+    let conflicts = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Key bindings ")
+                .style(theme.description()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
 
-    let help = Paragraph::new(help_lines)
+    frame.render_widget(conflicts, conflicts_area);
+}
+
+/// Render the dependencies popover for the selected script.
+fn render_dependencies_overlay(frame: &mut Frame, tree: &[TreeLine], theme: &Theme) {
+    let area = frame.area();
+    let width = area.width.saturating_sub(4).min(80);
+    let lines = dependency_lines(tree, theme);
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let dependencies_area = centered_rect_fixed(width, height, area);
+
+    frame.render_widget(Clear, dependencies_area);
+
+    let dependencies = Paragraph::new(lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Help ")
+                .title(" Runs ")
                 .style(theme.description()),
         )
         .alignment(Alignment::Left)
-        .wrap(Wrap { trim: true });
+        .wrap(Wrap { trim: false });
 
-    frame.render_widget(help, help_area);
+    frame.render_widget(dependencies, dependencies_area);
 }
 
-/// Render an error overlay.
-fn render_error_overlay(frame: &mut Frame, theme: &Theme, message: &str) {
+/// Build the lines shown in the project info popover.
+///
+/// Sections the package.json doesn't have are left out.
+fn project_info_lines<'a>(info: &'a ProjectInfo, theme: &Theme) -> Vec<Line<'a>> {
+    let mut lines = Vec::new();
+    let name = info.name.as_deref().unwrap_or("(unnamed)");
+    let mut title = vec![Span::styled(name, theme.script())];
+    if let Some(version) = &info.version {
+        title.push(Span::styled(format!(" {version}"), theme.command()));
+    }
+    lines.push(Line::from(title));
+    if let Some(description) = &info.description {
+        lines.push(Line::from(Span::styled(description, theme.description())));
+    }
+    let module_type = if info.is_module {
+        "ES modules (type: module)"
+    } else {
+        "CommonJS"
+    };
+    lines.push(Line::from(Span::styled(module_type, theme.description())));
+
+    let sections = [
+        ("Entry points", &info.entry_points),
+        ("Exports", &info.exports),
+        ("Bin", &info.bin),
+        ("Config", &info.config),
+        ("Engines", &info.engines),
+    ];
+    for (label, entries) in sections {
+        if entries.is_empty() {
+            continue;
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(label, theme.bold())));
+        for (key, value) in entries {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {key}  "), theme.key()),
+                Span::styled(value, theme.description()),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press any key to close",
+        theme.filter_placeholder(),
+    )));
+    lines
+}
+
+/// Render the project info popover.
+fn render_project_info_overlay(frame: &mut Frame, info: &ProjectInfo, theme: &Theme) {
     let area = frame.area();
-    let error_area = centered_rect_fixed(60, 8, area);
+    let width = area.width.saturating_sub(4).min(80);
+    let lines = project_info_lines(info, theme);
+    let height = wrapped_height(&lines, width.saturating_sub(2)) + 2;
+    let info_area = centered_rect_fixed(width, height, area);
 
-    // Clear the area
-    frame.render_widget(Clear, error_area);
+    frame.render_widget(Clear, info_area);
 
-    let error_lines = vec![
-        Line::from(Span::styled("Error", theme.error())),
-        Line::from(""),
-        Line::from(Span::styled(message, theme.description())),
+    let popover = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Project ")
+                .style(theme.description()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(popover, info_area);
+}
+
+/// Height of the runs per day chart, with its labels.
+const DAY_CHART_HEIGHT: u16 = 6;
+
+/// Render the debug overlay with filter, sort and frame timings.
+fn render_profile_overlay(frame: &mut Frame, app: &App, theme: &Theme) {
+    let mut rows = app.profile().rows();
+    rows.push((
+        "Scripts",
+        format!("{} shown of {}", app.visible_count(), app.scripts().len()),
+    ));
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{label:7}  "), theme.key()),
+                Span::styled(value, theme.description()),
+            ])
+        })
+        .collect();
+
+    // Top right, over the header, so the grid stays usable
+    let area = frame.area();
+    let width = area.width.min(50);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let profile_area = Rect::new(area.right() - width, area.y, width, height);
+    frame.render_widget(Clear, profile_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Debug (F12) ")
+        .style(theme.description());
+    frame.render_widget(Paragraph::new(lines).block(block), profile_area);
+}
+
+/// Render the usage statistics overlay, with bar charts of the most run
+/// scripts and of the runs per day of the week.
+fn render_stats_overlay(frame: &mut Frame, stats: &ProjectStats, theme: &Theme) {
+    let area = frame.area();
+    let top = &stats.scripts[..stats.scripts.len().min(STATS_SCRIPTS)];
+    let summary: Vec<Line> = if stats.is_empty() {
+        vec![Line::from(Span::styled(
+            "No runs recorded yet",
+            theme.description(),
+        ))]
+    } else {
+        let rows = stats.summary();
+        let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        rows.into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{label:width$}  "), theme.key()),
+                    Span::styled(value, theme.description()),
+                ])
+            })
+            .collect()
+    };
+    // Each chart comes after a blank line and its heading
+    let scripts_height = if top.is_empty() {
+        0
+    } else {
+        top.len() as u16 + 2
+    };
+    let days_height = if stats.busiest_day().is_some() {
+        DAY_CHART_HEIGHT + 2
+    } else {
+        0
+    };
+
+    let width = area.width.saturating_sub(4).min(70);
+    let height = summary.len() as u16 + scripts_height + days_height + 4;
+    let stats_area = centered_rect_fixed(width, height, area);
+    frame.render_widget(Clear, stats_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Usage ")
+        .style(theme.description());
+    let inner = block.inner(stats_area);
+    frame.render_widget(block, stats_area);
+
+    let [summary_area, scripts_area, days_area, hint_area] = Layout::vertical([
+        Constraint::Length(summary.len() as u16),
+        Constraint::Length(scripts_height),
+        Constraint::Length(days_height),
+        Constraint::Length(2),
+    ])
+    .areas(inner);
+    frame.render_widget(Paragraph::new(summary), summary_area);
+
+    let bar_style = theme.script();
+    let value_style = bar_style.add_modifier(Modifier::REVERSED);
+    if scripts_height > 0 {
+        let chart_area = render_chart_heading(frame, "Most run", scripts_area, theme);
+        let bars: Vec<Bar> = top
+            .iter()
+            .map(|script| {
+                Bar::default()
+                    .label(Line::from(script.name.as_str()))
+                    .value(u64::from(script.runs))
+                    .text_value(script.describe())
+            })
+            .collect();
+        let chart = BarChart::default()
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_style(bar_style)
+            .value_style(value_style)
+            .label_style(theme.key())
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, chart_area);
+    }
+
+    if days_height > 0 {
+        let chart_area = render_chart_heading(frame, "Runs per day", days_area, theme);
+        let bars: Vec<Bar> = stats
+            .weekday_runs
+            .iter()
+            .enumerate()
+            .map(|(index, &runs)| {
+                Bar::default()
+                    .label(Line::from(weekday(index).to_string()))
+                    .value(u64::from(runs))
+            })
+            .collect();
+        let chart = BarChart::default()
+            .bar_width(3)
+            .bar_gap(1)
+            .bar_style(bar_style)
+            .value_style(value_style)
+            .label_style(theme.key())
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, chart_area);
+    }
+
+    let hint = Paragraph::new(vec![
         Line::from(""),
         Line::from(Span::styled(
-            "Press any key to dismiss",
+            "Press any key to close",
             theme.filter_placeholder(),
         )),
+    ]);
+    frame.render_widget(hint, hint_area);
+}
+
+/// Render a chart's heading after a blank line, returning the area left for
+/// the chart.
+fn render_chart_heading(
+    frame: &mut Frame,
+    title: &str,
+    area: ratatui::layout::Rect,
+    theme: &Theme,
+) -> ratatui::layout::Rect {
+    let [heading_area, chart_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(area);
+    let heading = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled(title, theme.bold())),
+    ]);
+    frame.render_widget(heading, heading_area);
+    chart_area
+}
+
+/// Render an error overlay.
+fn render_error_overlay(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    message: &str,
+    actions: &[ErrorAction],
+) {
+    let error_label = Theme::with_marker(theme.error_marker(), "Error");
+    let mut error_lines = vec![
+        Line::from(Span::styled(error_label, theme.error())),
+        Line::from(""),
+        Line::from(Span::styled(message, theme.description())),
+        Line::from(""),
     ];
 
+    // Numbered recovery actions
+    for (i, action) in actions.iter().enumerate() {
+        let label = match action {
+            ErrorAction::SwitchRunner => format!("{} (now {})", action.label(), app.runner()),
+            _ => action.label().to_string(),
+        };
+        error_lines.push(Line::from(vec![
+            Span::styled(format!("{} ", i + 1), theme.key()),
+            Span::styled(label, theme.description()),
+        ]));
+    }
+    if !actions.is_empty() {
+        error_lines.push(Line::from(""));
+    }
+
+    let hint = if actions.is_empty() {
+        "Press any key to dismiss"
+    } else {
+        "Press a number to choose, any other key to dismiss"
+    };
+    error_lines.push(Line::from(Span::styled(hint, theme.filter_placeholder())));
+
+    let area = frame.area();
+    let height = wrapped_height(&error_lines, 58) + 2;
+    let error_area = centered_rect_fixed(60, height, area);
+
+    // Clear the area
+    frame.render_widget(Clear, error_area);
+
     let error = Paragraph::new(error_lines)
         .block(
             Block::default()
@@ -434,7 +1570,7 @@ mod tests {
     use super::*;
     use crate::config::Config;
     use crate::history::History;
-    use crate::package::{Runner, Script, Scripts};
+    use crate::package::{Runner, Scripts};
     use std::path::PathBuf;
 
     fn create_test_app() -> App {
@@ -461,4 +1597,219 @@ mod tests {
         let _theme = Theme::default();
         // Can't easily test render without a terminal, but ensure it compiles
     }
+
+    fn render_to_string(app: &App, width: u16, height: u16) -> String {
+        let backend = ratatui::backend::TestBackend::new(width, height);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let theme = Theme::default();
+        terminal
+            .draw(|frame| render(frame, app, &theme, true))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn test_render_degraded_layout() {
+        let app = create_test_app();
+        let content = render_to_string(&app, 30, 8);
+        assert!(content.contains("dev"));
+        assert!(!content.contains("Terminal too small"));
+    }
+
+    #[test]
+    fn test_render_unusable_terminal() {
+        let app = create_test_app();
+        let content = render_to_string(&app, 19, 4);
+        assert!(content.contains("Terminal"));
+    }
+
+    #[test]
+    fn test_render_nested_workspace_selector() {
+        use crate::package::Workspace;
+
+        let mut mobile = Workspace::new("mobile", "/test/apps/mobile");
+        mobile.set_members(vec![Workspace::new("@mobile/ui", "/test/apps/mobile/ui")]);
+        let mut app = App::with_workspaces(
+            Scripts::new(),
+            Config::default(),
+            History::new(),
+            "repo".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+            vec![mobile],
+        );
+
+        let content = render_to_string(&app, 80, 24);
+        assert!(content.contains("mobile ▸ 1 workspace"));
+
+        app.workspace_move_down();
+        app.select_current_workspace();
+        let content = render_to_string(&app, 80, 24);
+        assert!(content.contains("Select Workspace: repo › mobile"));
+        assert!(content.contains("1  mobile (root)"));
+        assert!(content.contains("2  @mobile/ui"));
+    }
+
+    #[test]
+    fn test_render_confirm_edit() {
+        let mut app = create_test_app();
+        app.set_mode(AppMode::ConfirmEdit {
+            edit: ScriptEdit::Rename {
+                from: "dev".to_string(),
+                to: "serve".to_string(),
+            },
+            preview: vec![
+                PreviewLine::Unchanged("\"scripts\": {".to_string()),
+                PreviewLine::Removed("  \"dev\": \"vite\",".to_string()),
+                PreviewLine::Added("  \"serve\": \"vite\",".to_string()),
+            ],
+        });
+
+        let content = render_to_string(&app, 80, 24);
+        assert!(content.contains("Rename dev to serve in package.json"));
+        assert!(content.contains("-   \"dev\": \"vite\","));
+        assert!(content.contains("+   \"serve\": \"vite\","));
+        assert!(content.contains("y confirm  n cancel"));
+    }
+
+    #[test]
+    fn test_render_preview() {
+        let mut scripts = Scripts::new();
+        scripts.add(Script::new(
+            "release",
+            "npm run build && npm test && npm publish --access public",
+        ));
+        let mut app = App::new(
+            scripts,
+            Config::default(),
+            History::new(),
+            "test-project".to_string(),
+            PathBuf::from("/test"),
+            Runner::Npm,
+        );
+
+        app.toggle_preview();
+        let content = render_to_string(&app, 80, 24);
+        assert!(content.contains(" release "));
+        assert!(content.contains("npm run build"));
+        assert!(content.contains("  && npm test"));
+        assert!(content.contains("  && npm publish --access public"));
+        assert!(content.contains("any key to close"));
+    }
+
+    #[test]
+    fn test_render_profile() {
+        let mut app = create_test_app();
+        let content = render_to_string(&app, 80, 24);
+        assert!(!content.contains("Debug (F12)"));
+
+        app.toggle_profile();
+        app.record_frame(std::time::Duration::from_millis(20));
+        let content = render_to_string(&app, 80, 24);
+        assert!(content.contains("Debug (F12)"));
+        assert!(content.contains("Frame    20.0ms (max 20.0ms, 1 of 1 slow)"));
+        assert!(content.contains("Scripts  3 shown of 3"));
+    }
+
+    #[test]
+    fn test_render_category_summary() {
+        let mut app = create_test_app();
+        app.toggle_categories();
+        let content = render_to_string(&app, 80, 24);
+        assert!(content.contains("Categories"));
+        assert!(content.contains("all scripts    3"));
+        assert!(content.contains("build          1"));
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let mut history = History::new();
+        let project = PathBuf::from("/test");
+        for _ in 0..4 {
+            history.record_run(&project, "dev", None);
+        }
+        history.record_run(&project, "build", None);
+        history.record_result(&project, "build", 0, std::time::Duration::from_secs(3));
+        let mut app = App::new(
+            Scripts::new(),
+            Config::default(),
+            history,
+            "test-project".to_string(),
+            project,
+            Runner::Npm,
+        );
+
+        app.toggle_stats();
+        let content = render_to_string(&app, 80, 40);
+        assert!(content.contains("Total runs   5 in 2 scripts"));
+        assert!(content.contains("Most run"));
+        assert!(content.contains("4 runs"));
+        assert!(content.contains("1 run, avg 3s, 0% failed"));
+        assert!(content.contains("Runs per day"));
+    }
+
+    #[test]
+    fn test_details_lines_untruncated() {
+        let theme = Theme::default();
+        let long_command = "node scripts/build.js --mode production --sourcemap --verbose";
+        let script = Script::with_description("build:production", long_command, "Build it");
+        let lines = details_lines(&script, &theme);
+
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert!(text.contains(&"build:production".to_string()));
+        assert!(text.contains(&format!("$ {}", long_command)));
+        assert!(text.contains(&"Build it".to_string()));
+    }
+
+    #[test]
+    fn test_source_lines_mark_gaps() {
+        use crate::package::SourceLine;
+
+        let line = |number: usize, text: &str, is_target: bool| SourceLine {
+            number,
+            text: text.to_string(),
+            is_target,
+        };
+        let excerpt = Excerpt {
+            file: PathBuf::from("/test/package.json"),
+            lines: vec![
+                line(7, r#"    "prebuild": "rimraf dist","#, false),
+                line(8, r#"    "build": "tsc","#, true),
+                line(12, r#"    "postbuild": "echo done""#, false),
+            ],
+        };
+
+        let text: Vec<String> = source_lines(&excerpt, &Theme::default())
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(text[0], r#" 7 │     "prebuild": "rimraf dist","#);
+        assert_eq!(text[1], r#" 8 │     "build": "tsc","#);
+        assert_eq!(text[2], "   ┆");
+        assert_eq!(text[3], r#"12 │     "postbuild": "echo done""#);
+    }
+
+    #[test]
+    fn test_dependency_lines_without_dependencies() {
+        let tree = vec![TreeLine {
+            prefix: String::new(),
+            name: "lint".to_string(),
+            kind: None,
+            cycle: false,
+        }];
+        let text: Vec<String> = dependency_lines(&tree, &Theme::default())
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(text[0], "lint");
+        assert_eq!(text[1], "Doesn't run any other scripts");
+    }
+
+    #[test]
+    fn test_wrapped_height() {
+        let lines = vec![Line::from("a".repeat(25)), Line::from("")];
+        assert_eq!(wrapped_height(&lines, 10), 4);
+    }
 }
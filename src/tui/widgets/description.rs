@@ -8,13 +8,17 @@ use ratatui::{
 };
 
 use crate::config::AppearanceConfig;
+use crate::history::ScriptHistory;
 use crate::package::{get_description, Script};
 use crate::tui::theme::Theme;
+use crate::tui::widgets::highlight_line;
+use crate::utils::format_elapsed;
 
 /// Description panel widget.
 pub struct Description<'a> {
     script: Option<&'a Script>,
     theme: &'a Theme,
+    history: Option<&'a ScriptHistory>,
     show_command: bool,
     compact: bool,
 }
@@ -25,6 +29,7 @@ impl<'a> Description<'a> {
         Self {
             script,
             theme,
+            history: None,
             show_command: config.icons, // Reusing icons flag for command preview
             compact: config.compact,
         }
@@ -36,6 +41,40 @@ impl<'a> Description<'a> {
         self
     }
 
+    /// Set the run history of the script, to show how its last run went.
+    pub fn history(mut self, history: Option<&'a ScriptHistory>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Build the "last run: 12s, failed" spans, if the last run was timed.
+    fn last_run_spans(&self) -> Vec<Span<'a>> {
+        let Some(history) = self.history else {
+            return Vec::new();
+        };
+        let Some(duration) = history.last_duration() else {
+            return Vec::new();
+        };
+
+        let mut text = format!("  last run: {}", format_elapsed(duration));
+        if let Some(average) = history
+            .average_duration()
+            .filter(|_| history.timed_runs > 1)
+        {
+            text.push_str(&format!(" (avg {})", format_elapsed(average)));
+        }
+        let mut spans = vec![Span::styled(text, self.theme.dim())];
+        match history.last_exit_code {
+            Some(0) => spans.push(Span::styled(", ok", self.theme.success())),
+            Some(code) => spans.push(Span::styled(
+                format!(", failed ({code})"),
+                self.theme.error(),
+            )),
+            None => {}
+        }
+        spans
+    }
+
     /// Build lines for the description panel.
     fn build_lines(&self, width: u16) -> Vec<Line<'a>> {
         let Some(script) = self.script else {
@@ -54,10 +93,9 @@ impl<'a> Description<'a> {
         } else {
             desc.to_string()
         };
-        lines.push(Line::from(Span::styled(
-            desc_text,
-            self.theme.description(),
-        )));
+        let mut desc_spans = vec![Span::styled(desc_text, self.theme.description())];
+        desc_spans.extend(self.last_run_spans());
+        lines.push(Line::from(desc_spans));
 
         // Separator (only in non-compact mode)
         if !self.compact && self.show_command {
@@ -66,17 +104,16 @@ impl<'a> Description<'a> {
             lines.push(Line::from(Span::styled(separator, self.theme.separator())));
         }
 
-        // Command preview
+        // Command preview, cut to one line
         if self.show_command {
-            let command = script.command();
-            let cmd_display = if command.len() > width as usize - 4 {
-                // Truncate long commands
-                let truncated: String = command.chars().take(width as usize - 7).collect();
-                format!("$ {}...", truncated)
-            } else {
-                format!("$ {}", command)
-            };
-            lines.push(Line::from(Span::styled(cmd_display, self.theme.command())));
+            let mut line = highlight_line(
+                script.command(),
+                self.theme,
+                (width as usize).saturating_sub(4),
+            );
+            line.spans
+                .insert(0, Span::styled("$ ", self.theme.command()));
+            lines.push(line);
         }
 
         lines
@@ -110,7 +147,10 @@ impl<'a> ErrorDisplay<'a> {
     /// Build lines for the error display.
     fn build_lines(&self) -> Vec<Line<'a>> {
         vec![
-            Line::from(Span::styled("Error", self.theme.error())),
+            Line::from(Span::styled(
+                Theme::with_marker(self.theme.error_marker(), "Error"),
+                self.theme.error(),
+            )),
             Line::from(Span::styled(self.message, self.theme.description())),
         ]
     }
@@ -203,6 +243,34 @@ mod tests {
         assert!(content.contains("..."));
     }
 
+    #[test]
+    fn test_description_last_run() {
+        let theme = Theme::default();
+        let config = AppearanceConfig::default();
+        let script = Script::with_description("test", "vitest", "Run tests");
+        let mut history = ScriptHistory::new();
+        let content = |history: &ScriptHistory| -> String {
+            Description::new(Some(&script), &theme, &config)
+                .history(Some(history))
+                .build_lines(80)[0]
+                .spans
+                .iter()
+                .map(|s| s.content.to_string())
+                .collect()
+        };
+
+        assert_eq!(content(&history), "Run tests");
+
+        history.record_result(0, std::time::Duration::from_secs(10));
+        assert_eq!(content(&history), "Run tests  last run: 10s, ok");
+
+        history.record_result(1, std::time::Duration::from_secs(14));
+        assert_eq!(
+            content(&history),
+            "Run tests  last run: 14s (avg 12s), failed (1)"
+        );
+    }
+
     #[test]
     fn test_error_display() {
         let theme = Theme::default();
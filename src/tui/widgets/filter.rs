@@ -3,13 +3,42 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Paragraph, Widget},
 };
 
 use crate::config::AppearanceConfig;
+use crate::tui::text_input::split_at_cursor;
 use crate::tui::theme::Theme;
 
+/// Build the spans for an input with a cursor.
+///
+/// At the end of the text the cursor is drawn as `_`; inside the text the
+/// grapheme under the cursor is shown reversed.
+fn input_spans(text: &str, cursor: usize, blink_state: bool, style: Style) -> Vec<Span<'static>> {
+    let (before, current, after) = split_at_cursor(text, cursor);
+    let mut spans = vec![Span::styled(before.to_string(), style)];
+
+    if current.is_empty() {
+        if blink_state {
+            spans.push(Span::styled("_", style));
+        } else {
+            spans.push(Span::raw(" "));
+        }
+    } else {
+        let cursor_style = if blink_state {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        };
+        spans.push(Span::styled(current.to_string(), cursor_style));
+        spans.push(Span::styled(after.to_string(), style));
+    }
+
+    spans
+}
+
 /// Filter bar widget.
 pub struct Filter<'a> {
     query: &'a str,
@@ -17,6 +46,7 @@ pub struct Filter<'a> {
     theme: &'a Theme,
     show_icon: bool,
     blink_state: bool,
+    cursor: usize,
 }
 
 impl<'a> Filter<'a> {
@@ -33,6 +63,7 @@ impl<'a> Filter<'a> {
             theme,
             show_icon: config.icons,
             blink_state: true, // Default to cursor visible
+            cursor: query.len(),
         }
     }
 
@@ -42,23 +73,28 @@ impl<'a> Filter<'a> {
         self
     }
 
+    /// Set the cursor position (byte offset into the query).
+    pub fn cursor(mut self, cursor: usize) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
     /// Build the filter line.
     fn build_line(&self) -> Line<'a> {
         let icon = if self.show_icon { " " } else { "" }; // magnifying glass
 
         if self.is_active {
             // Active filter mode
-            let mut spans = vec![
-                Span::styled(format!("{} / ", icon), self.theme.filter_active()),
-                Span::styled(self.query.to_string(), self.theme.filter_active()),
-            ];
-
-            // Add blinking cursor
-            if self.blink_state {
-                spans.push(Span::styled("_", self.theme.filter_active()));
-            } else {
-                spans.push(Span::raw(" "));
-            }
+            let mut spans = vec![Span::styled(
+                format!("{} / ", icon),
+                self.theme.filter_active(),
+            )];
+            spans.extend(input_spans(
+                self.query,
+                self.cursor,
+                self.blink_state,
+                self.theme.filter_active(),
+            ));
 
             Line::from(spans)
         } else if !self.query.is_empty() {
@@ -96,6 +132,7 @@ pub struct ArgsFilter<'a> {
     args: &'a str,
     theme: &'a Theme,
     blink_state: bool,
+    cursor: usize,
 }
 
 impl<'a> ArgsFilter<'a> {
@@ -106,6 +143,7 @@ impl<'a> ArgsFilter<'a> {
             args,
             theme,
             blink_state: true,
+            cursor: args.len(),
         }
     }
 
@@ -115,21 +153,25 @@ impl<'a> ArgsFilter<'a> {
         self
     }
 
+    /// Set the cursor position (byte offset into the args).
+    pub fn cursor(mut self, cursor: usize) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
     /// Build the args input line.
     fn build_line(&self) -> Line<'a> {
         let mut spans = vec![
             Span::styled(" Args for ", self.theme.filter_placeholder()),
             Span::styled(self.script_name.to_string(), self.theme.filter()),
             Span::styled(": ", self.theme.filter_placeholder()),
-            Span::styled(self.args.to_string(), self.theme.filter_active()),
         ];
-
-        // Add blinking cursor
-        if self.blink_state {
-            spans.push(Span::styled("_", self.theme.filter_active()));
-        } else {
-            spans.push(Span::raw(" "));
-        }
+        spans.extend(input_spans(
+            self.args,
+            self.cursor,
+            self.blink_state,
+            self.theme.filter_active(),
+        ));
 
         Line::from(spans)
     }
@@ -147,6 +189,66 @@ impl Widget for ArgsFilter<'_> {
     }
 }
 
+/// Text input with a prompt, for questions like a new script's name.
+pub struct PromptInput<'a> {
+    prompt: &'a str,
+    input: &'a str,
+    theme: &'a Theme,
+    blink_state: bool,
+    cursor: usize,
+}
+
+impl<'a> PromptInput<'a> {
+    /// Create a new prompt input widget.
+    pub fn new(prompt: &'a str, input: &'a str, theme: &'a Theme) -> Self {
+        Self {
+            prompt,
+            input,
+            theme,
+            blink_state: true,
+            cursor: input.len(),
+        }
+    }
+
+    /// Set the blink state for the cursor.
+    pub fn blink(mut self, state: bool) -> Self {
+        self.blink_state = state;
+        self
+    }
+
+    /// Set the cursor position (byte offset into the input).
+    pub fn cursor(mut self, cursor: usize) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    /// Build the prompt line.
+    fn build_line(&self) -> Line<'a> {
+        let mut spans = vec![Span::styled(
+            format!(" {}: ", self.prompt),
+            self.theme.filter_placeholder(),
+        )];
+        spans.extend(input_spans(
+            self.input,
+            self.cursor,
+            self.blink_state,
+            self.theme.filter_active(),
+        ));
+
+        Line::from(spans)
+    }
+}
+
+impl Widget for PromptInput<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+
+        Paragraph::new(self.build_line()).render(area, buf);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,4 +302,30 @@ mod tests {
         assert!(content.contains("dev"));
         assert!(content.contains("--watch"));
     }
+
+    #[test]
+    fn test_prompt_input() {
+        let theme = Theme::default();
+        let prompt = PromptInput::new("Command for lint", "eslint", &theme);
+
+        let line = prompt.build_line();
+        let content: String = line.spans.iter().map(|s| s.content.to_string()).collect();
+
+        assert!(content.starts_with(" Command for lint: eslint"));
+    }
+
+    #[test]
+    fn test_filter_cursor_in_middle() {
+        let theme = Theme::default();
+        let config = AppearanceConfig::default();
+        let filter = Filter::new("dev", true, &theme, &config).cursor(1);
+
+        let line = filter.build_line();
+        let content: String = line.spans.iter().map(|s| s.content.to_string()).collect();
+
+        assert!(content.ends_with("dev"));
+        assert!(!content.contains("_"));
+        let cursor = line.spans.iter().find(|s| s.content == "e").unwrap();
+        assert!(cursor.style.add_modifier.contains(Modifier::REVERSED));
+    }
 }
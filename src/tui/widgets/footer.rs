@@ -1,5 +1,7 @@
 //! Footer widget for the TUI.
 
+use std::borrow::Cow;
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -8,45 +10,120 @@ use ratatui::{
 };
 
 use crate::tui::app::AppMode;
+use crate::tui::keymap::Keymap;
 use crate::tui::theme::Theme;
 
 /// Footer widget showing keybinding hints.
 pub struct Footer<'a> {
     mode: &'a AppMode,
     theme: &'a Theme,
+    keymap: Option<&'a Keymap>,
 }
 
 impl<'a> Footer<'a> {
     /// Create a new footer widget.
     pub fn new(mode: &'a AppMode, theme: &'a Theme) -> Self {
-        Self { mode, theme }
+        Self {
+            mode,
+            theme,
+            keymap: None,
+        }
+    }
+
+    /// Show the keys of a keymap preset and the user's key bindings.
+    pub fn keymap(mut self, keymap: &'a Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
     }
 
     /// Get keybinding hints for the current mode.
-    fn get_hints(&self) -> Vec<(&'static str, &'static str)> {
-        match self.mode {
-            AppMode::Normal => vec![
-                ("j/k", "move"),
-                ("Enter", "run"),
-                ("1-9", "quick"),
-                ("/", "filter"),
-                ("?", "help"),
-                ("q", "quit"),
-            ],
+    fn get_hints(&self) -> Vec<(Cow<'static, str>, &'static str)> {
+        let hints = match self.mode {
+            AppMode::Normal => {
+                return [
+                    ("j/k", "move"),
+                    ("Enter", "run"),
+                    ("1-9", "quick"),
+                    ("/", "filter"),
+                    ("?", "help"),
+                    ("q", "quit"),
+                ]
+                .into_iter()
+                .filter_map(|(key, action)| {
+                    let label = match self.keymap {
+                        Some(keymap) => keymap.label(key)?,
+                        None => Cow::Borrowed(key),
+                    };
+                    Some((label, action))
+                })
+                .collect();
+            }
             AppMode::Filter { .. } => vec![("j/k", "move"), ("Enter", "run"), ("Esc", "cancel")],
-            AppMode::Help => vec![("any key", "close")],
+            AppMode::Help
+            | AppMode::Details
+            | AppMode::Preview
+            | AppMode::Source { .. }
+            | AppMode::Dependencies { .. }
+            | AppMode::ProjectInfo { .. }
+            | AppMode::Stats { .. } => vec![("any key", "close")],
+            AppMode::Error { actions, .. } if !actions.is_empty() => {
+                vec![("1-9", "action"), ("any key", "dismiss")]
+            }
             AppMode::Error { .. } => vec![("any key", "dismiss")],
             AppMode::MultiSelect { .. } => {
                 vec![("Space", "toggle"), ("Enter", "run"), ("Esc", "cancel")]
             }
-            AppMode::Args { .. } => vec![("Enter", "run"), ("Esc", "cancel")],
+            AppMode::NewScript { name: None, .. } => vec![("Enter", "next"), ("Esc", "cancel")],
+            AppMode::NewScript { .. } => vec![("Enter", "add"), ("Esc", "cancel")],
+            AppMode::RenameScript { .. } => vec![("Enter", "preview"), ("Esc", "cancel")],
+            AppMode::ConfirmEdit { .. } => vec![("y", "confirm"), ("n/Esc", "cancel")],
+            AppMode::ConfirmRun { .. } => vec![("y", "run"), ("n/Esc", "cancel")],
+            AppMode::InstallPrompt { .. } => vec![
+                ("y", "install first"),
+                ("n", "run anyway"),
+                ("Esc", "cancel"),
+            ],
+            AppMode::KeyConflicts { .. } => {
+                vec![
+                    ("r", "restore"),
+                    ("e", "edit config"),
+                    ("c/Esc", "continue"),
+                ]
+            }
+            AppMode::Args { .. } => vec![
+                ("Enter", "run"),
+                ("↑/↓", "history"),
+                ("Tab", "complete"),
+                ("Esc", "cancel"),
+            ],
             AppMode::WorkspaceSelect => vec![
                 ("j/k", "move"),
                 ("Enter", "select"),
                 ("1-9", "quick"),
+                ("F", "search all"),
+                ("y", "yank path"),
                 ("q", "quit"),
             ],
-        }
+            AppMode::Running => vec![
+                ("j/k", "scroll"),
+                ("r", "re-run"),
+                ("C-c", "stop"),
+                ("Esc", "back"),
+            ],
+            AppMode::Categories => vec![
+                ("j/k", "move"),
+                ("Enter", "show"),
+                ("1-9", "quick"),
+                ("Esc", "close"),
+            ],
+            AppMode::GlobalSearch { .. } => {
+                vec![("↑/↓", "move"), ("Enter", "open"), ("Esc", "cancel")]
+            }
+        };
+        hints
+            .into_iter()
+            .map(|(key, action)| (Cow::Borrowed(key), action))
+            .collect()
     }
 
     /// Build the footer line with adaptive width.
@@ -65,7 +142,7 @@ impl<'a> Footer<'a> {
         if (width as usize) >= full_width + 2 {
             // Full display
             for (i, (key, action)) in hints.iter().enumerate() {
-                spans.push(Span::styled(*key, self.theme.key()));
+                spans.push(Span::styled(key.clone(), self.theme.key()));
                 spans.push(Span::styled(format!(" {} ", action), self.theme.footer()));
                 if i < hints.len() - 1 {
                     spans.push(Span::styled(" ", self.theme.footer()));
@@ -74,7 +151,7 @@ impl<'a> Footer<'a> {
         } else if (width as usize) >= hints.len() * 4 {
             // Compact display (just keys)
             for (i, (key, _)) in hints.iter().enumerate() {
-                spans.push(Span::styled(*key, self.theme.key()));
+                spans.push(Span::styled(key.clone(), self.theme.key()));
                 if i < hints.len() - 1 {
                     spans.push(Span::styled(" ", self.theme.footer()));
                 }
@@ -83,7 +160,7 @@ impl<'a> Footer<'a> {
             // Ultra compact - just show first few hints
             let max_hints = ((width as usize) / 6).max(1).min(hints.len());
             for (i, (key, _)) in hints.iter().take(max_hints).enumerate() {
-                spans.push(Span::styled(*key, self.theme.key()));
+                spans.push(Span::styled(key.clone(), self.theme.key()));
                 if i < max_hints - 1 {
                     spans.push(Span::styled(" ", self.theme.footer()));
                 }
@@ -111,6 +188,7 @@ pub struct MessageFooter<'a> {
     message: &'a str,
     theme: &'a Theme,
     is_error: bool,
+    is_warning: bool,
 }
 
 impl<'a> MessageFooter<'a> {
@@ -120,6 +198,7 @@ impl<'a> MessageFooter<'a> {
             message,
             theme,
             is_error: false,
+            is_warning: false,
         }
     }
 
@@ -128,6 +207,12 @@ impl<'a> MessageFooter<'a> {
         self.is_error = true;
         self
     }
+
+    /// Mark this as a warning message.
+    pub fn warning(mut self) -> Self {
+        self.is_warning = true;
+        self
+    }
 }
 
 impl Widget for MessageFooter<'_> {
@@ -136,13 +221,18 @@ impl Widget for MessageFooter<'_> {
             return;
         }
 
-        let style = if self.is_error {
-            self.theme.error()
+        let (style, text) = if self.is_error {
+            (
+                self.theme.error(),
+                Theme::with_marker(self.theme.error_marker(), self.message),
+            )
+        } else if self.is_warning {
+            (self.theme.warning(), self.message.to_string())
         } else {
-            self.theme.footer()
+            (self.theme.footer(), self.message.to_string())
         };
 
-        let line = Line::from(vec![Span::raw(" "), Span::styled(self.message, style)]);
+        let line = Line::from(vec![Span::raw(" "), Span::styled(text, style)]);
         let paragraph = Paragraph::new(line);
         paragraph.render(area, buf);
     }
@@ -151,6 +241,7 @@ impl Widget for MessageFooter<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{KeyAction, KeybindingsConfig, KeymapPreset};
 
     #[test]
     fn test_footer_normal_mode() {
@@ -163,6 +254,41 @@ mod tests {
         assert!(hints.iter().any(|(k, _)| *k == "q"));
     }
 
+    #[test]
+    fn test_footer_keymap_preset() {
+        let mode = AppMode::Normal;
+        let theme = Theme::default();
+        let (keymap, _) = Keymap::new(&KeybindingsConfig {
+            preset: KeymapPreset::Emacs,
+            ..Default::default()
+        });
+        let footer = Footer::new(&mode, &theme).keymap(&keymap);
+
+        let hints = footer.get_hints();
+        assert!(hints.iter().any(|(k, _)| *k == "C-n/C-p"));
+        // Digits are typed into the filter
+        assert!(!hints.iter().any(|(k, _)| *k == "1-9"));
+    }
+
+    #[test]
+    fn test_footer_key_bindings() {
+        let mode = AppMode::Normal;
+        let theme = Theme::default();
+        let mut config = KeybindingsConfig::default();
+        config
+            .bindings
+            .insert(KeyAction::Quit, vec!["Ctrl+q".to_string()]);
+        config
+            .bindings
+            .insert(KeyAction::Up, vec!["Ctrl+p".to_string()]);
+        let (keymap, _) = Keymap::new(&config);
+        let footer = Footer::new(&mode, &theme).keymap(&keymap);
+
+        let hints = footer.get_hints();
+        assert!(hints.iter().any(|(k, _)| *k == "Ctrl+q"));
+        assert!(hints.iter().any(|(k, _)| *k == "j/Ctrl+p"));
+    }
+
     #[test]
     fn test_footer_filter_mode() {
         let mode = AppMode::Filter {
@@ -216,4 +342,12 @@ mod tests {
         let footer = MessageFooter::new("Error occurred", &theme).error();
         assert!(footer.is_error);
     }
+
+    #[test]
+    fn test_message_footer_warning() {
+        let theme = Theme::default();
+        let footer = MessageFooter::new("Careful", &theme).warning();
+        assert!(footer.is_warning);
+        assert!(!footer.is_error);
+    }
 }
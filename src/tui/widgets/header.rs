@@ -17,6 +17,7 @@ pub struct Header<'a> {
     runner: Runner,
     theme: &'a Theme,
     show_icons: bool,
+    monorepo: Option<&'a str>,
 }
 
 impl<'a> Header<'a> {
@@ -32,9 +33,16 @@ impl<'a> Header<'a> {
             runner,
             theme,
             show_icons: config.icons,
+            monorepo: None,
         }
     }
 
+    /// Set the monorepo description (e.g. `pnpm workspaces + turbo`).
+    pub fn monorepo(mut self, monorepo: Option<&'a str>) -> Self {
+        self.monorepo = monorepo;
+        self
+    }
+
     /// Build the header line.
     fn build_line(&self, width: u16) -> Line<'a> {
         let icon = if self.show_icons {
@@ -51,7 +59,15 @@ impl<'a> Header<'a> {
         let help_len = help_hint.len() + 2; // help + spaces
         let fixed_parts = icon_len + runner_part.len() + help_len + 4; // padding/separators
 
-        let max_project_len = (width as usize).saturating_sub(fixed_parts);
+        // Monorepo info is only shown if it leaves room for the project name
+        let monorepo_part = self
+            .monorepo
+            .map(|m| format!(" {} │", m))
+            .filter(|m| fixed_parts + m.chars().count() + 12 <= width as usize)
+            .unwrap_or_default();
+
+        let max_project_len =
+            (width as usize).saturating_sub(fixed_parts + monorepo_part.chars().count());
         let project_display = truncate_with_ellipsis(self.project_name, max_project_len);
 
         // Build spans
@@ -69,14 +85,17 @@ impl<'a> Header<'a> {
 
         // Calculate padding to right-align runner info
         let left_len = spans.iter().map(|s| s.content.len()).sum::<usize>();
-        let right_content = format!("{} {} ", runner_part, help_hint);
-        let padding_len = (width as usize).saturating_sub(left_len + right_content.len());
+        let right_content = format!("{}{} {} ", monorepo_part, runner_part, help_hint);
+        let padding_len = (width as usize).saturating_sub(left_len + right_content.chars().count());
 
         if padding_len > 0 {
             spans.push(Span::styled(" ".repeat(padding_len), self.theme.header()));
         }
 
-        // Right side: runner + help
+        // Right side: monorepo tool + runner + help
+        if !monorepo_part.is_empty() {
+            spans.push(Span::styled(monorepo_part, self.theme.header()));
+        }
         spans.push(Span::styled(runner_part, self.theme.header_runner()));
         spans.push(Span::styled(help_hint, self.theme.header()));
         spans.push(Span::raw(" "));
@@ -179,6 +198,24 @@ mod tests {
         assert!(content.contains("[?]"));
     }
 
+    #[test]
+    fn test_header_monorepo_info() {
+        let theme = Theme::default();
+        let config = AppearanceConfig::default();
+        let header = Header::new("my-project", Runner::Pnpm, &theme, &config)
+            .monorepo(Some("pnpm workspaces + turbo"));
+
+        let line = header.build_line(80);
+        let content: String = line.spans.iter().map(|s| s.content.to_string()).collect();
+        assert!(content.contains("pnpm workspaces + turbo"));
+        assert!(content.contains("my-project"));
+
+        // Dropped when the terminal is too narrow
+        let line = header.build_line(40);
+        let content: String = line.spans.iter().map(|s| s.content.to_string()).collect();
+        assert!(!content.contains("turbo"));
+    }
+
     #[test]
     fn test_header_unicode_project_name() {
         let theme = Theme::default();
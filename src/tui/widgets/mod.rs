@@ -6,10 +6,14 @@ mod description;
 mod filter;
 mod footer;
 mod header;
+mod output;
+mod preview;
 mod scripts;
 
 pub use description::{Description, ErrorDisplay};
-pub use filter::{ArgsFilter, Filter};
+pub use filter::{ArgsFilter, Filter, PromptInput};
 pub use footer::{Footer, MessageFooter};
 pub use header::{truncate_with_ellipsis, Header};
+pub use output::{OutputPane, OutputStatus};
+pub use preview::{chained_lines, highlight_line, tokenize, CommandPreview, TokenKind};
 pub use scripts::{EmptyScripts, ScriptsGrid};
@@ -0,0 +1,191 @@
+//! Output pane widget for scripts run inside the TUI.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::runner::OutputLine;
+use crate::tui::theme::Theme;
+
+/// State of the run shown in the output pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStatus {
+    /// The script is still running.
+    Running,
+    /// The script exited with a code, or was killed (`None`).
+    Exited(Option<i32>),
+}
+
+/// Scrollable pane showing the output of a running script.
+pub struct OutputPane<'a> {
+    title: &'a str,
+    lines: &'a [OutputLine],
+    status: OutputStatus,
+    scroll: usize,
+    theme: &'a Theme,
+}
+
+impl<'a> OutputPane<'a> {
+    /// Create a new output pane.
+    pub fn new(title: &'a str, lines: &'a [OutputLine], theme: &'a Theme) -> Self {
+        Self {
+            title,
+            lines,
+            status: OutputStatus::Running,
+            scroll: 0,
+            theme,
+        }
+    }
+
+    /// Set the run status shown in the title.
+    pub fn status(mut self, status: OutputStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Set how many lines the view is scrolled up from the end.
+    pub fn scroll(mut self, scroll: usize) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    /// Get the range of lines visible in a pane of the given height.
+    fn visible_range(&self, height: usize) -> (usize, usize) {
+        let len = self.lines.len();
+        let end = len.saturating_sub(self.scroll).max(height.min(len));
+        (end.saturating_sub(height), end)
+    }
+
+    /// Build the title with the run status.
+    fn title_line(&self) -> Line<'a> {
+        let (label, style) = match self.status {
+            OutputStatus::Running => ("running".to_string(), self.theme.warning()),
+            OutputStatus::Exited(Some(0)) => (
+                Theme::with_marker(self.theme.success_marker(), "exit 0"),
+                self.theme.success(),
+            ),
+            OutputStatus::Exited(Some(code)) => (
+                Theme::with_marker(self.theme.error_marker(), &format!("exit {code}")),
+                self.theme.error(),
+            ),
+            OutputStatus::Exited(None) => ("stopped".to_string(), self.theme.error()),
+        };
+
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled(self.title, self.theme.bold()),
+            Span::raw(" "),
+            Span::styled(label, style),
+            Span::raw(" "),
+        ])
+    }
+}
+
+impl Widget for OutputPane<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title_line())
+            .border_style(self.theme.separator());
+
+        let inner_height = area.height.saturating_sub(2) as usize;
+        let (start, end) = self.visible_range(inner_height);
+        let below = self.lines.len() - end;
+        if below > 0 {
+            block = block.title_bottom(Line::from(Span::styled(
+                format!(" ↓ {below} more "),
+                self.theme.dim(),
+            )));
+        }
+
+        let lines: Vec<Line> = if self.lines.is_empty() {
+            vec![Line::from(Span::styled(
+                "Waiting for output...",
+                self.theme.filter_placeholder(),
+            ))]
+        } else {
+            self.lines[start..end]
+                .iter()
+                .map(|line| {
+                    let style = if line.is_hidden() {
+                        self.theme.dim()
+                    } else if line.stderr {
+                        self.theme.warning()
+                    } else {
+                        self.theme.description()
+                    };
+                    Line::from(Span::styled(line.text.as_str(), style))
+                })
+                .collect()
+        };
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(count: usize) -> Vec<OutputLine> {
+        (0..count)
+            .map(|i| OutputLine {
+                text: format!("line {i}"),
+                stderr: false,
+                hidden: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_visible_range_follows_end() {
+        let theme = Theme::default();
+        let output = lines(100);
+        let pane = OutputPane::new("build", &output, &theme);
+        assert_eq!(pane.visible_range(10), (90, 100));
+    }
+
+    #[test]
+    fn test_visible_range_scrolled() {
+        let theme = Theme::default();
+        let output = lines(100);
+        assert_eq!(
+            OutputPane::new("build", &output, &theme)
+                .scroll(5)
+                .visible_range(10),
+            (85, 95)
+        );
+        // Scrolling past the start stops at the first page
+        assert_eq!(
+            OutputPane::new("build", &output, &theme)
+                .scroll(500)
+                .visible_range(10),
+            (0, 10)
+        );
+    }
+
+    #[test]
+    fn test_visible_range_short_output() {
+        let theme = Theme::default();
+        let output = lines(3);
+        let pane = OutputPane::new("build", &output, &theme).scroll(2);
+        assert_eq!(pane.visible_range(10), (0, 3));
+    }
+
+    #[test]
+    fn test_title_shows_status() {
+        let theme = Theme::default();
+        let pane = OutputPane::new("build", &[], &theme).status(OutputStatus::Exited(Some(2)));
+        let title = pane.title_line().to_string();
+        assert!(title.contains("build"));
+        assert!(title.contains("exit 2"));
+    }
+}
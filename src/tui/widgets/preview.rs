@@ -0,0 +1,372 @@
+//! Shell-aware command preview.
+//!
+//! Splits a script's command into words and operators and styles them by
+//! role: the program run, `&&`/`|`/`>` operators, environment variables,
+//! flags and file paths. The full-screen preview puts each command of a
+//! chain on its own line.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Paragraph, Widget, Wrap},
+};
+
+use crate::tui::theme::Theme;
+
+/// Operators, longest first so `&&` isn't read as two `&`.
+const OPERATORS: [&str; 9] = ["&&", "||", ">>", "2>", "|", ";", "&", ">", "<"];
+
+/// Role of a piece of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Program being run, like `tsc` or `npm`.
+    Program,
+    /// Chain, pipe or redirect operator.
+    Operator,
+    /// Environment variable, assigned (`NODE_ENV=prod`) or read (`$PORT`).
+    Variable,
+    /// Option, like `--watch` or `-p`.
+    Flag,
+    /// File, directory or glob.
+    Path,
+    /// Quoted string.
+    Quoted,
+    /// Any other argument.
+    Text,
+    /// Whitespace between words.
+    Space,
+}
+
+impl TokenKind {
+    /// Get the style of the token in a theme.
+    pub fn style(self, theme: &Theme) -> Style {
+        match self {
+            Self::Program => theme.syntax_program(),
+            Self::Operator => theme.syntax_operator(),
+            Self::Variable => theme.syntax_variable(),
+            Self::Flag => theme.syntax_flag(),
+            Self::Path => theme.syntax_path(),
+            Self::Quoted => theme.syntax_string(),
+            Self::Text | Self::Space => theme.description(),
+        }
+    }
+
+    /// Check if the token separates commands of a chain.
+    fn is_chain(self, text: &str) -> bool {
+        self == Self::Operator && matches!(text, "&&" | "||" | ";" | "|" | "&")
+    }
+}
+
+/// Split a command into styled pieces.
+///
+/// Joining the pieces gives back the command. Unterminated quotes run to
+/// the end.
+pub fn tokenize(command: &str) -> Vec<(TokenKind, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = command;
+    // The next word is a program, or a redirect target
+    let mut at_program = true;
+    let mut at_redirect = false;
+
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_whitespace() {
+            let len = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            tokens.push((TokenKind::Space, &rest[..len]));
+            len
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push((TokenKind::Operator, *op));
+            at_program = !matches!(*op, ">>" | "2>" | ">" | "<");
+            at_redirect = !at_program;
+            op.len()
+        } else if c == '\'' || c == '"' {
+            let len = quoted_len(rest);
+            tokens.push((TokenKind::Quoted, &rest[..len]));
+            at_program = false;
+            at_redirect = false;
+            len
+        } else {
+            let len = word_len(rest);
+            let word = &rest[..len];
+            let kind = if (at_program && is_assignment(word)) || word.starts_with('$') {
+                TokenKind::Variable
+            } else if at_program {
+                TokenKind::Program
+            } else if at_redirect {
+                TokenKind::Path
+            } else if word.starts_with('-') {
+                TokenKind::Flag
+            } else if is_path(word) {
+                TokenKind::Path
+            } else {
+                TokenKind::Text
+            };
+            // Assignments before the program leave it next
+            at_program = at_program && is_assignment(word);
+            at_redirect = false;
+            tokens.push((kind, word));
+            len
+        };
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+/// Get the length of a quoted string at the start of `text`.
+fn quoted_len(text: &str) -> usize {
+    let quote = text.chars().next().unwrap_or('"');
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quote == '"' => escaped = true,
+            _ if c == quote => return i + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Get the length of the word at the start of `text`, with any quotes in it.
+fn word_len(text: &str) -> usize {
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        if c.is_whitespace() || matches!(c, '&' | '|' | ';' | '<' | '>') {
+            return i;
+        }
+        if (c == '\'' || c == '"') && i > 0 {
+            let len = quoted_len(&text[i..]);
+            while chars.peek().is_some_and(|&(j, _)| j < i + len) {
+                chars.next();
+            }
+            continue;
+        }
+        chars.next();
+    }
+    text.len()
+}
+
+/// Check if a word assigns an environment variable, like `NODE_ENV=prod`.
+fn is_assignment(word: &str) -> bool {
+    let Some((name, _)) = word.split_once('=') else {
+        return false;
+    };
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Check if a word looks like a file, directory or glob.
+fn is_path(word: &str) -> bool {
+    if word.contains('/') || word.contains('*') || word.starts_with('~') {
+        return true;
+    }
+    // A file name with an extension, like server.js (but not 1.2)
+    word.rsplit_once('.').is_some_and(|(stem, extension)| {
+        !stem.is_empty()
+            && (1..=4).contains(&extension.len())
+            && extension.starts_with(|c: char| c.is_ascii_alphabetic())
+            && extension.chars().all(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+/// Style a command as one line, cut to `max_width` characters with "...".
+pub fn highlight_line<'a>(command: &'a str, theme: &Theme, max_width: usize) -> Line<'a> {
+    let truncate = command.chars().count() > max_width;
+    let mut room = if truncate {
+        max_width.saturating_sub(3)
+    } else {
+        max_width
+    };
+
+    let mut spans = Vec::new();
+    for (kind, text) in tokenize(command) {
+        if room == 0 {
+            break;
+        }
+        let count = text.chars().count();
+        if count <= room {
+            spans.push(Span::styled(text, kind.style(theme)));
+            room -= count;
+        } else {
+            let cut: String = text.chars().take(room).collect();
+            spans.push(Span::styled(cut, kind.style(theme)));
+            room = 0;
+        }
+    }
+    if truncate {
+        spans.push(Span::styled("...", theme.command()));
+    }
+    Line::from(spans)
+}
+
+/// Style a command with each command of a chain on its own line.
+///
+/// Lines after the first start with the operator joining them to the
+/// previous one, indented.
+pub fn chained_lines<'a>(command: &'a str, theme: &Theme) -> Vec<Line<'a>> {
+    let mut lines: Vec<Vec<Span<'a>>> = vec![Vec::new()];
+    for (kind, text) in tokenize(command.trim()) {
+        if kind.is_chain(text) {
+            // Drop the space before the operator
+            let current = lines.last_mut().expect("lines start non-empty");
+            if current
+                .last()
+                .is_some_and(|span| span.content.trim().is_empty())
+            {
+                current.pop();
+            }
+            lines.push(vec![Span::raw("  "), Span::styled(text, kind.style(theme))]);
+            continue;
+        }
+        push_token(
+            lines.last_mut().expect("lines start non-empty"),
+            kind,
+            text,
+            theme,
+        );
+    }
+    lines.into_iter().map(Line::from).collect()
+}
+
+/// Add a token to a line, collapsing runs of whitespace to one space.
+fn push_token<'a>(line: &mut Vec<Span<'a>>, kind: TokenKind, text: &'a str, theme: &Theme) {
+    if kind == TokenKind::Space {
+        line.push(Span::raw(" "));
+    } else {
+        line.push(Span::styled(text, kind.style(theme)));
+    }
+}
+
+/// Full-screen preview of a command, one chained command per line.
+pub struct CommandPreview<'a> {
+    command: &'a str,
+    theme: &'a Theme,
+}
+
+impl<'a> CommandPreview<'a> {
+    /// Create a preview of a command.
+    pub fn new(command: &'a str, theme: &'a Theme) -> Self {
+        Self { command, theme }
+    }
+}
+
+impl Widget for CommandPreview<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+
+        let lines = chained_lines(self.command, self.theme);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(command: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(command)
+            .into_iter()
+            .filter(|(kind, _)| *kind != TokenKind::Space)
+            .collect()
+    }
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        use TokenKind::*;
+
+        let command = "NODE_ENV=production tsc -p . && node dist/server.js --port=$PORT > out.log";
+        let tokens = tokenize(command);
+        assert_eq!(
+            tokens.iter().map(|(_, text)| *text).collect::<String>(),
+            command
+        );
+        assert_eq!(
+            kinds(command),
+            [
+                (Variable, "NODE_ENV=production"),
+                (Program, "tsc"),
+                (Flag, "-p"),
+                (Text, "."),
+                (Operator, "&&"),
+                (Program, "node"),
+                (Path, "dist/server.js"),
+                (Flag, "--port=$PORT"),
+                (Operator, ">"),
+                (Path, "out.log"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_quotes_and_pipes() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds(r#"echo "a && b" | grep 'x'|wc -l"#),
+            [
+                (Program, "echo"),
+                (Quoted, r#""a && b""#),
+                (Operator, "|"),
+                (Program, "grep"),
+                (Quoted, "'x'"),
+                (Operator, "|"),
+                (Program, "wc"),
+                (Flag, "-l"),
+            ]
+        );
+        assert_eq!(
+            kinds(r#"eslint --ext=".ts,.tsx" "src/**""#),
+            [
+                (Program, "eslint"),
+                (Flag, r#"--ext=".ts,.tsx""#),
+                (Quoted, r#""src/**""#),
+            ]
+        );
+        assert_eq!(kinds("echo 'open"), [(Program, "echo"), (Quoted, "'open")]);
+        assert_eq!(
+            kinds("npm version 1.2"),
+            [(Program, "npm"), (Text, "version"), (Text, "1.2")]
+        );
+    }
+
+    #[test]
+    fn test_highlight_line_truncates() {
+        let theme = Theme::default();
+        let line = highlight_line("vite build --mode production", &theme, 14);
+        assert_eq!(text(&line), "vite build ...");
+        assert_eq!(line.spans[0].style, theme.syntax_program());
+
+        let line = highlight_line("vite", &theme, 14);
+        assert_eq!(text(&line), "vite");
+    }
+
+    #[test]
+    fn test_chained_lines() {
+        let theme = Theme::default();
+        let lines = chained_lines("rimraf dist  &&  tsc ; cp -r assets dist || exit 1", &theme);
+        let texts: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(
+            texts,
+            [
+                "rimraf dist",
+                "  && tsc",
+                "  ; cp -r assets dist",
+                "  || exit 1"
+            ]
+        );
+    }
+}
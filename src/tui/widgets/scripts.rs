@@ -5,12 +5,15 @@ use std::collections::HashSet;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::Modifier,
     text::{Line, Span},
     widgets::Widget,
 };
 
+use crate::config::Density;
 use crate::package::Script;
-use crate::tui::layout::{calculate_column_width, calculate_columns};
+use crate::tui::groups::{GroupLayout, GroupLine};
+use crate::tui::layout::{calculate_column_width, calculate_columns_for_density};
 use crate::tui::theme::Theme;
 use crate::tui::widgets::header::truncate_with_ellipsis;
 
@@ -21,6 +24,9 @@ pub struct ScriptsGrid<'a> {
     scroll_offset: usize,
     theme: &'a Theme,
     multi_selected: Option<&'a HashSet<usize>>,
+    pinned: usize,
+    density: Density,
+    groups: Option<&'a GroupLayout>,
 }
 
 impl<'a> ScriptsGrid<'a> {
@@ -32,6 +38,9 @@ impl<'a> ScriptsGrid<'a> {
             scroll_offset: 0,
             theme,
             multi_selected: None,
+            pinned: 0,
+            density: Density::default(),
+            groups: None,
         }
     }
 
@@ -47,6 +56,154 @@ impl<'a> ScriptsGrid<'a> {
         self
     }
 
+    /// Set how many scripts at the start of the list are pinned.
+    pub fn pinned(mut self, count: usize) -> Self {
+        self.pinned = count;
+        self
+    }
+
+    /// Set the grid density.
+    pub fn density(mut self, density: Density) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Show the scripts in category groups, with a header above each.
+    ///
+    /// The scroll offset is then a line of the layout.
+    pub fn groups(mut self, layout: &'a GroupLayout) -> Self {
+        self.groups = Some(layout);
+        self
+    }
+
+    /// Get the first index to render so the selected item stays visible.
+    fn effective_scroll_offset(&self, columns: usize, rows: usize) -> usize {
+        let first_row = self.scroll_offset / columns;
+        let selected_row = self.selected / columns;
+
+        let first_row = if selected_row < first_row {
+            selected_row
+        } else if selected_row >= first_row + rows {
+            selected_row + 1 - rows
+        } else {
+            first_row
+        };
+
+        first_row * columns
+    }
+
+    /// Width of the quick-select number column (digits of the largest number).
+    fn number_width(&self) -> usize {
+        self.scripts.len().to_string().len()
+    }
+
+    /// Render the inline description line shown under a script name.
+    fn render_inline_description(&self, script: &Script, max_width: u16) -> Option<Span<'a>> {
+        let description = script.description()?;
+        let indent = self.number_width() + 3; // Align with the script name
+        let width =
+            (max_width as usize).saturating_sub(indent + self.density.cell_padding() as usize);
+        let text = truncate_with_ellipsis(description, width);
+        Some(Span::styled(
+            format!("{}{}", " ".repeat(indent), text),
+            self.theme
+                .description()
+                .add_modifier(ratatui::style::Modifier::DIM),
+        ))
+    }
+
+    /// Render a group header, with the cursor if it is collapsed and selected.
+    fn render_group_header(
+        &self,
+        category: &str,
+        count: usize,
+        slot: Option<usize>,
+    ) -> Vec<Span<'a>> {
+        let is_selected = slot == Some(self.selected);
+        let marker = if is_selected {
+            self.theme.selected_marker()
+        } else {
+            " "
+        };
+        let arrow = if slot.is_some() { "▸" } else { "▾" };
+        let name_style = if is_selected {
+            self.theme.selected()
+        } else {
+            self.theme.group_header()
+        };
+
+        vec![
+            Span::raw(" ".repeat(self.number_width() + 1)),
+            Span::styled(format!("{marker} "), self.theme.cursor()),
+            Span::styled(format!("{arrow} {category}"), name_style),
+            Span::styled(format!(" {count}"), self.theme.filter_placeholder()),
+        ]
+    }
+
+    /// Render the scripts in groups, one layout line per grid row.
+    fn render_groups(&self, layout: &GroupLayout, area: Rect, buf: &mut Buffer) {
+        let columns = layout.columns();
+        let column_width = calculate_column_width(area.width, columns);
+        let row_height = self.density.row_height();
+        let rows = ((area.height / row_height) as usize).max(1);
+        let first_line = layout.scroll(self.scroll_offset, self.selected, rows);
+
+        for (row, line) in layout
+            .lines()
+            .iter()
+            .skip(first_line)
+            .take(rows)
+            .enumerate()
+        {
+            let y = area.y + row as u16 * row_height;
+            match line {
+                GroupLine::Header {
+                    category,
+                    count,
+                    slot,
+                } => {
+                    let spans = self.render_group_header(category, *count, *slot);
+                    buf.set_line(area.x, y, &Line::from(spans), area.width);
+                }
+                GroupLine::Row(slots) => {
+                    for (col, script_idx) in slots.clone().enumerate() {
+                        let x = area.x + col as u16 * column_width;
+                        self.render_cell(script_idx, x, y, column_width, area, buf);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Render the script in a slot at a position, with its description.
+    fn render_cell(
+        &self,
+        script_idx: usize,
+        x: u16,
+        y: u16,
+        column_width: u16,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let script = self.scripts[script_idx];
+        let is_selected = script_idx == self.selected;
+        let is_multi = self
+            .multi_selected
+            .map(|m| m.contains(&script_idx))
+            .unwrap_or(false);
+
+        // Render the script item (numbers follow the quick-run index)
+        let spans = self.render_script(script, script_idx, is_selected, is_multi, column_width);
+        buf.set_line(x, y, &Line::from(spans), column_width);
+
+        // Inline description under the name
+        if self.density.inline_descriptions() && y + 1 < area.y + area.height {
+            if let Some(span) = self.render_inline_description(script, column_width) {
+                buf.set_line(x, y + 1, &Line::from(span), column_width);
+            }
+        }
+    }
+
     /// Render a single script item.
     fn render_script(
         &self,
@@ -56,25 +213,39 @@ impl<'a> ScriptsGrid<'a> {
         is_multi_selected: bool,
         max_width: u16,
     ) -> Vec<Span<'a>> {
-        // Number prefix (1-9 for first 9 visible items)
-        let num_str = if index < 9 {
-            format!("{}", index + 1)
-        } else {
-            " ".to_string()
-        };
+        // Number prefix, right-aligned so multi-digit numbers line up
+        let num_str = format!("{:>width$}", index + 1, width = self.number_width());
 
         // Cursor/marker
         let marker = if is_selected {
-            ">"
+            self.theme.selected_marker()
         } else if is_multi_selected {
-            "*"
+            self.theme.multiselect_marker()
+        } else if index < self.pinned {
+            self.theme.pinned_marker()
         } else {
             " "
         };
 
-        // Calculate name width (accounting for num, marker, and padding)
-        let prefix_len = 4; // " N > " or " N * " etc
-        let name_width = (max_width as usize).saturating_sub(prefix_len);
+        // Groups, aliases, root scripts and Turborepo or Nx tasks are badged
+        let badge = if script.group().is_some() {
+            Some(" ⛓".to_string())
+        } else if script.parent().is_some() {
+            Some(" ↑".to_string())
+        } else if let Some(alias) = script.alias() {
+            Some(format!(" → {}", alias.script))
+        } else {
+            script
+                .runner()
+                .filter(|runner| runner.is_task_runner())
+                .map(|runner| format!(" {}", runner.display_name()))
+        };
+
+        // Calculate name width (accounting for num, marker, badge, and padding)
+        let prefix_len = self.number_width() + 3; // "N > " or "N * " etc
+        let badge_len = badge.as_ref().map_or(0, |badge| badge.chars().count());
+        let name_width = (max_width as usize)
+            .saturating_sub(prefix_len + badge_len + self.density.cell_padding() as usize);
         let name = truncate_with_ellipsis(script.name(), name_width);
 
         // Build spans
@@ -84,17 +255,24 @@ impl<'a> ScriptsGrid<'a> {
             self.theme.cursor()
         };
 
-        let name_style = if is_selected {
+        let mut name_style = if is_selected {
             self.theme.selected()
         } else {
             self.theme.script()
         };
+        if script.alias().is_some() {
+            name_style = name_style.add_modifier(Modifier::ITALIC);
+        }
 
-        vec![
+        let mut spans = vec![
             Span::styled(format!("{} ", num_str), self.theme.number()),
             Span::styled(format!("{} ", marker), marker_style),
             Span::styled(name, name_style),
-        ]
+        ];
+        if let Some(badge) = badge {
+            spans.push(Span::styled(badge, self.theme.filter_placeholder()));
+        }
+        spans
     }
 }
 
@@ -103,14 +281,19 @@ impl Widget for ScriptsGrid<'_> {
         if area.height == 0 || area.width == 0 || self.scripts.is_empty() {
             return;
         }
+        if let Some(layout) = self.groups {
+            self.render_groups(layout, area, buf);
+            return;
+        }
 
-        let columns = calculate_columns(area.width);
+        let columns = calculate_columns_for_density(area.width, self.density);
         let column_width = calculate_column_width(area.width, columns);
-        let rows = area.height as usize;
+        let row_height = self.density.row_height();
+        let rows = ((area.height / row_height) as usize).max(1);
 
         // Calculate which items to show
         let total_visible = rows * columns;
-        let start_idx = self.scroll_offset;
+        let start_idx = self.effective_scroll_offset(columns, rows);
         let end_idx = (start_idx + total_visible).min(self.scripts.len());
 
         // Render items in horizontal-first order
@@ -127,28 +310,13 @@ impl Widget for ScriptsGrid<'_> {
 
             // Calculate screen position
             let x = area.x + (col as u16 * column_width);
-            let y = area.y + row as u16;
+            let y = area.y + row as u16 * row_height;
 
             if y >= area.y + area.height {
                 break;
             }
 
-            // Get script and render state
-            let script = self.scripts[script_idx];
-            let is_selected = script_idx == self.selected;
-            let is_multi = self
-                .multi_selected
-                .map(|m| m.contains(&script_idx))
-                .unwrap_or(false);
-
-            // Render the script item
-            let spans =
-                self.render_script(script, display_idx, is_selected, is_multi, column_width);
-            let line = Line::from(spans);
-
-            // Render to buffer
-            let item_area = Rect::new(x, y, column_width, 1);
-            buf.set_line(item_area.x, item_area.y, &line, item_area.width);
+            self.render_cell(script_idx, x, y, column_width, area, buf);
         }
     }
 }
@@ -260,7 +428,11 @@ impl Widget for EmptyScripts<'_> {
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
     use super::*;
+    use crate::package::{Runner, ScriptGroup};
+    use crate::tui::layout::calculate_columns;
 
     fn create_test_scripts() -> Vec<Script> {
         vec![
@@ -310,6 +482,156 @@ mod tests {
         assert!(content.contains("*")); // Multi-select marker
     }
 
+    #[test]
+    fn test_render_script_badges() {
+        let scripts = [
+            Script::new("build", "turbo run build").with_runner(Runner::Turbo),
+            Script::new("fmt", "deno fmt").with_runner(Runner::Deno),
+            Script::from_group(
+                "ci",
+                ScriptGroup {
+                    scripts: vec!["lint".to_string(), "test".to_string()],
+                    parallel: false,
+                },
+            ),
+            Script::from_parent(&Script::new("deploy", "turbo deploy"), Path::new("/repo")),
+        ];
+        let script_refs: Vec<&Script> = scripts.iter().collect();
+        let theme = Theme::default();
+
+        let grid = ScriptsGrid::new(&script_refs, 0, &theme);
+        let render = |index: usize, width: u16| -> String {
+            grid.render_script(&scripts[index], index, false, false, width)
+                .iter()
+                .map(|s| s.content.to_string())
+                .collect()
+        };
+        assert!(render(0, 30).ends_with("build turbo"));
+        assert!(render(0, 16).ends_with("bui… turbo"));
+        assert!(render(1, 30).ends_with("fmt"));
+        assert!(render(2, 30).ends_with("ci ⛓"));
+        assert!(render(3, 30).ends_with("root:deploy ↑"));
+    }
+
+    #[test]
+    fn test_render_script_pinned_marker() {
+        let scripts = create_test_scripts();
+        let script_refs: Vec<&Script> = scripts.iter().collect();
+        let theme = Theme::default();
+
+        let grid = ScriptsGrid::new(&script_refs, 0, &theme).pinned(1);
+        let render = |index: usize| -> String {
+            grid.render_script(&scripts[index], index, false, false, 30)
+                .iter()
+                .map(|s| s.content.to_string())
+                .collect()
+        };
+        assert!(render(0).contains("+ dev"));
+        assert!(!render(1).contains('+'));
+    }
+
+    #[test]
+    fn test_render_script_colorblind_markers() {
+        let scripts = create_test_scripts();
+        let script_refs: Vec<&Script> = scripts.iter().collect();
+        let theme = Theme::new(&crate::config::Theme::Colorblind);
+
+        let grid = ScriptsGrid::new(&script_refs, 0, &theme);
+        let selected: String = grid
+            .render_script(&scripts[0], 0, true, false, 30)
+            .iter()
+            .map(|s| s.content.to_string())
+            .collect();
+        assert!(selected.contains("▶"));
+
+        let multi: String = grid
+            .render_script(&scripts[1], 1, false, true, 30)
+            .iter()
+            .map(|s| s.content.to_string())
+            .collect();
+        assert!(multi.contains("✓"));
+    }
+
+    #[test]
+    fn test_inline_description_by_density() {
+        let script = Script::with_description("dev", "vite", "Start dev server");
+        let scripts = vec![&script];
+        let theme = Theme::default();
+
+        let grid = ScriptsGrid::new(&scripts, 0, &theme).density(Density::Comfortable);
+        let span = grid.render_inline_description(&script, 30).unwrap();
+        assert!(span.content.contains("Start dev server"));
+
+        // Scripts without a description get no inline line
+        let plain = Script::new("build", "vite build");
+        assert!(grid.render_inline_description(&plain, 30).is_none());
+    }
+
+    #[test]
+    fn test_compact_grid_renders_single_line_rows() {
+        let scripts = create_test_scripts();
+        let script_refs: Vec<&Script> = scripts.iter().collect();
+        let theme = Theme::default();
+
+        let area = Rect::new(0, 0, 40, 5);
+        let mut buf = Buffer::empty(area);
+        ScriptsGrid::new(&script_refs, 0, &theme)
+            .density(Density::Compact)
+            .render(area, &mut buf);
+
+        // Compact mode at 40 columns fits two columns, one row per line
+        let row: String = (0..area.width)
+            .map(|x| buf[(x, 1)].symbol().to_string())
+            .collect();
+        assert!(row.contains("test"));
+    }
+
+    #[test]
+    fn test_grouped_grid_renders_headers() {
+        let scripts = [
+            Script::new("build", "vite build"),
+            Script::new("build:prod", "vite build --mode production"),
+            Script::new("test", "vitest"),
+        ];
+        let script_refs: Vec<&Script> = scripts.iter().collect();
+        let theme = Theme::default();
+        let collapsed = ["test".to_string()].into_iter().collect();
+        let layout = GroupLayout::new(&["build", "build", "test"], 1, &collapsed, |_| 4);
+
+        let area = Rect::new(0, 0, 40, 5);
+        let mut buf = Buffer::empty(area);
+        ScriptsGrid::new(&script_refs, 2, &theme)
+            .density(Density::Compact)
+            .groups(&layout)
+            .render(area, &mut buf);
+
+        let row = |y: u16| -> String {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect()
+        };
+        assert!(row(0).contains("▾ build 2"));
+        assert!(row(1).contains("1   build"));
+        assert!(row(2).contains("build:prod"));
+        assert!(row(3).contains("> ▸ test 4"));
+    }
+
+    #[test]
+    fn test_scroll_keeps_selection_visible() {
+        let scripts: Vec<Script> = (0..20)
+            .map(|i| Script::new(format!("s{}", i), "cmd"))
+            .collect();
+        let script_refs: Vec<&Script> = scripts.iter().collect();
+        let theme = Theme::default();
+
+        let grid = ScriptsGrid::new(&script_refs, 15, &theme);
+        // 1 column, 4 rows: item 15 must be on the last visible row
+        assert_eq!(grid.effective_scroll_offset(1, 4), 12);
+
+        let grid = ScriptsGrid::new(&script_refs, 2, &theme);
+        assert_eq!(grid.effective_scroll_offset(1, 4), 0);
+    }
+
     #[test]
     fn test_calculate_columns() {
         assert_eq!(calculate_columns(50), 1);
@@ -0,0 +1,70 @@
+//! Clock and randomness that can be pinned for reproducible output.
+//!
+//! The hidden `--frozen-time` and `--seed` flags pin the current time and
+//! random values, and turn off cursor blinking, so snapshot tests and
+//! recorded demos render the same on every run.
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+
+/// Pinned current time, if frozen.
+static FROZEN_TIME: OnceLock<DateTime<Utc>> = OnceLock::new();
+
+/// Seed for random values, if pinned.
+static SEED: OnceLock<u64> = OnceLock::new();
+
+/// Pin the current time for the rest of the process.
+///
+/// Only the first call has an effect.
+pub fn freeze_time(time: DateTime<Utc>) {
+    let _ = FROZEN_TIME.set(time);
+}
+
+/// Pin the seed used for random values for the rest of the process.
+///
+/// Only the first call has an effect.
+pub fn set_seed(seed: u64) {
+    let _ = SEED.set(seed);
+}
+
+/// Get the current time, or the frozen time if pinned.
+pub fn now() -> DateTime<Utc> {
+    FROZEN_TIME.get().copied().unwrap_or_else(Utc::now)
+}
+
+/// Get the pinned seed, if any.
+pub fn seed() -> Option<u64> {
+    SEED.get().copied()
+}
+
+/// Check if output should be deterministic (no blinking or other animation).
+pub fn is_deterministic() -> bool {
+    FROZEN_TIME.get().is_some() || SEED.get().is_some()
+}
+
+/// Parse a `--frozen-time` value (RFC 3339, e.g. `2024-01-01T12:00:00Z`).
+pub fn parse_frozen_time(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| format!("expected an RFC 3339 timestamp: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frozen_time() {
+        let time = parse_frozen_time("2024-01-01T12:00:00+02:00").unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-01-01T10:00:00+00:00");
+        assert!(parse_frozen_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_now_without_freeze_is_current() {
+        // The global clock is never frozen in unit tests
+        let before = Utc::now();
+        assert!(now() >= before);
+    }
+}
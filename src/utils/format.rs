@@ -0,0 +1,281 @@
+//! Text formatting helpers for plain (non-TUI) output.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Utc};
+
+/// Wrap text at word boundaries so no line exceeds `width` characters.
+///
+/// Words longer than `width` are split. A width of 0 disables wrapping.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![text.to_string()];
+    }
+
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_len = 0;
+
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+
+        if line_len > 0 && line_len + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+            line_len = 0;
+        }
+
+        while word.len() > width {
+            let rest = word.split_off(width);
+            if line_len > 0 {
+                lines.push(std::mem::take(&mut line));
+                line_len = 0;
+            }
+            lines.push(word.into_iter().collect());
+            word = rest;
+        }
+
+        if line_len > 0 {
+            line.push(' ');
+            line_len += 1;
+        }
+        line_len += word.len();
+        line.extend(word);
+    }
+
+    if line_len > 0 || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Format a past time relative to `now` (e.g. "5m ago", "3d ago").
+pub fn format_time_ago(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - time).num_seconds();
+    match secs {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    }
+}
+
+/// Format an elapsed duration compactly (e.g. "42s", "3m 05s", "1h 02m").
+pub fn format_elapsed(elapsed: StdDuration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m {:02}s", s / 60, s % 60),
+        s => format!("{}h {:02}m", s / 3600, s % 3600 / 60),
+    }
+}
+
+/// Parse a duration like "30s", "5m" or "1h30m".
+///
+/// Units are `ms`, `s`, `m` and `h`; a bare number is seconds.
+pub fn parse_duration(text: &str) -> Option<StdDuration> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<u64>() {
+        return Some(StdDuration::from_secs(secs));
+    }
+
+    let mut total = StdDuration::ZERO;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let value: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        total += match &rest[..unit] {
+            "ms" => StdDuration::from_millis(value),
+            "s" => StdDuration::from_secs(value),
+            "m" => StdDuration::from_secs(value * 60),
+            "h" => StdDuration::from_secs(value * 3600),
+            _ => return None,
+        };
+        rest = &rest[unit..];
+    }
+    (!text.is_empty()).then_some(total)
+}
+
+/// Format a duration the way [`parse_duration`] reads it (e.g. "1h30m").
+pub fn format_duration(duration: StdDuration) -> String {
+    let secs = duration.as_secs();
+    let parts = [
+        (secs / 3600, "h"),
+        (secs % 3600 / 60, "m"),
+        (secs % 60, "s"),
+        (u64::from(duration.subsec_millis()), "ms"),
+    ];
+    let text: String = parts
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect();
+    if text.is_empty() {
+        "0s".to_string()
+    } else {
+        text
+    }
+}
+
+/// Partial blocks, from one eighth to seven eighths of a cell.
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Draw a bar of block characters that is `width` cells long at `max`.
+///
+/// Bars are drawn in eighths of a cell, and any non-zero value shows.
+pub fn format_bar(value: u64, max: u64, width: usize) -> String {
+    if max == 0 || value == 0 {
+        return String::new();
+    }
+    let eighths = u128::from(value.min(max)) * (width as u128) * 8 / u128::from(max);
+    let eighths = usize::try_from(eighths).unwrap_or(width * 8).max(1);
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+        bar.push(PARTIAL_BLOCKS[eighths % 8 - 1]);
+    }
+    bar
+}
+
+/// Words of a command, each marked `true` if it changed.
+pub type DiffWords<'a> = Vec<(&'a str, bool)>;
+
+/// Compare two commands word by word.
+///
+/// Returns the words of `old` and of `new`, with the words missing from the
+/// other command marked as changed.
+pub fn diff_words<'a>(old: &'a str, new: &'a str) -> (DiffWords<'a>, DiffWords<'a>) {
+    let old: Vec<&str> = old.split_whitespace().collect();
+    let new: Vec<&str> = new.split_whitespace().collect();
+
+    // Longest common subsequence lengths of the suffixes
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_words = Vec::with_capacity(old.len());
+    let mut new_words = Vec::with_capacity(new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            old_words.push((old[i], false));
+            new_words.push((new[j], false));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            old_words.push((old[i], true));
+            i += 1;
+        } else {
+            new_words.push((new[j], true));
+            j += 1;
+        }
+    }
+    (old_words, new_words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap_text("run the full test suite", 10),
+            ["run the", "full test", "suite"]
+        );
+        assert_eq!(wrap_text("short", 10), ["short"]);
+        assert_eq!(wrap_text("", 10), [""]);
+        assert_eq!(wrap_text("no wrapping here", 0), ["no wrapping here"]);
+    }
+
+    #[test]
+    fn test_wrap_text_splits_long_words() {
+        assert_eq!(
+            wrap_text("see node_modules/.bin/vite", 8),
+            ["see", "node_mod", "ules/.bi", "n/vite"]
+        );
+    }
+
+    #[test]
+    fn test_format_time_ago() {
+        let now = Utc::now();
+        assert_eq!(format_time_ago(now, now), "just now");
+        assert_eq!(format_time_ago(now - Duration::minutes(5), now), "5m ago");
+        assert_eq!(format_time_ago(now - Duration::hours(3), now), "3h ago");
+        assert_eq!(format_time_ago(now - Duration::days(2), now), "2d ago");
+        // Clock skew shouldn't produce negative output
+        assert_eq!(format_time_ago(now + Duration::hours(1), now), "just now");
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(StdDuration::from_secs(42)), "42s");
+        assert_eq!(format_elapsed(StdDuration::from_secs(185)), "3m 05s");
+        assert_eq!(format_elapsed(StdDuration::from_secs(3720)), "1h 02m");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Some(StdDuration::from_secs(90)));
+        assert_eq!(parse_duration("30s"), Some(StdDuration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Some(StdDuration::from_secs(300)));
+        assert_eq!(parse_duration("1h30m"), Some(StdDuration::from_secs(5400)));
+        assert_eq!(parse_duration("250ms"), Some(StdDuration::from_millis(250)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("m"), None);
+
+        for text in ["1h30m", "5m", "45s", "1m250ms"] {
+            assert_eq!(format_duration(parse_duration(text).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn test_format_bar() {
+        assert_eq!(format_bar(10, 10, 4), "████");
+        assert_eq!(format_bar(5, 10, 4), "██");
+        assert_eq!(format_bar(3, 10, 4), "█▏");
+        assert_eq!(format_bar(1, 1000, 4), "▏");
+        assert_eq!(format_bar(0, 10, 4), "");
+        assert_eq!(format_bar(3, 0, 4), "");
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let (old, new) = diff_words("yarn run test --watch", "npm run test -- --watch");
+        assert_eq!(
+            old,
+            [
+                ("yarn", true),
+                ("run", false),
+                ("test", false),
+                ("--watch", false)
+            ]
+        );
+        assert_eq!(
+            new,
+            [
+                ("npm", true),
+                ("run", false),
+                ("test", false),
+                ("--", true),
+                ("--watch", false)
+            ]
+        );
+
+        let (old, new) = diff_words("npm run dev", "npm run dev");
+        assert!(old.iter().chain(&new).all(|(_, changed)| !changed));
+    }
+}
@@ -0,0 +1,111 @@
+//! Diagnostic logging.
+//!
+//! Log events are emitted with `tracing` and printed to stderr or a file.
+//! The level comes from `-v`/`-vv`/`-vvv` (or `--debug`), and the `NRS_LOG`
+//! environment variable accepts full filter directives such as
+//! `nrs=debug,npm_run_scripts::history=trace`.
+
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use tracing::Level;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::EnvFilter;
+
+use super::paths::state_dir;
+
+/// Environment variable holding log filter directives.
+pub const LOG_ENV_VAR: &str = "NRS_LOG";
+
+/// Map the number of `-v` flags to a log level.
+pub fn level_for_verbosity(verbosity: u8) -> Level {
+    match verbosity {
+        0 => Level::WARN,
+        1 => Level::INFO,
+        2 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Build the default filter for a level, covering the binary and the library.
+pub fn default_filter(level: Level) -> String {
+    let level = level.to_string().to_lowercase();
+    format!("warn,nrs={level},npm_run_scripts={level}")
+}
+
+/// Default log file used while the TUI owns the terminal.
+///
+/// Kept in the state directory, or the temp directory if there is none.
+pub fn default_log_file() -> PathBuf {
+    state_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("nrs.log")
+}
+
+/// Install the global log subscriber.
+///
+/// `NRS_LOG` overrides the level given by `verbosity`. Logs are appended to
+/// `log_file` when set, otherwise written to stderr.
+pub fn init_logging(verbosity: u8, log_file: Option<&Path>) -> Result<()> {
+    let filter = match std::env::var(LOG_ENV_VAR) {
+        Ok(directives) if !directives.is_empty() => EnvFilter::try_new(&directives)
+            .with_context(|| format!("Invalid {LOG_ENV_VAR} filter: {directives}"))?,
+        _ => EnvFilter::new(default_filter(level_for_verbosity(verbosity))),
+    };
+
+    let writer = match log_file {
+        Some(path) => {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+            }
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            BoxMakeWriter::new(Mutex::new(file))
+        }
+        None => BoxMakeWriter::new(std::io::stderr),
+    };
+
+    // Plain output everywhere: colors need tracing-subscriber's `ansi` feature,
+    // and enabling them without it panics
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_target(false);
+
+    // Timestamps only help in files; on stderr they just add noise
+    let result = if log_file.is_some() {
+        builder.try_init()
+    } else {
+        builder.without_time().try_init()
+    };
+    result.map_err(|err| anyhow::anyhow!("Failed to set up logging: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_for_verbosity() {
+        assert_eq!(level_for_verbosity(0), Level::WARN);
+        assert_eq!(level_for_verbosity(1), Level::INFO);
+        assert_eq!(level_for_verbosity(2), Level::DEBUG);
+        assert_eq!(level_for_verbosity(5), Level::TRACE);
+    }
+
+    #[test]
+    fn test_default_filter() {
+        assert_eq!(
+            default_filter(Level::DEBUG),
+            "warn,nrs=debug,npm_run_scripts=debug"
+        );
+        assert!(EnvFilter::try_new(default_filter(Level::TRACE)).is_ok());
+    }
+}
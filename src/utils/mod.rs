@@ -2,16 +2,29 @@
 //!
 //! Common utilities for paths, terminal handling, and other helpers.
 
+pub mod clock;
+mod format;
+mod logging;
 mod paths;
+mod sort;
 mod terminal;
 
+pub use format::{
+    diff_words, format_bar, format_duration, format_elapsed, format_time_ago, parse_duration,
+    wrap_text, DiffWords,
+};
+pub use logging::{
+    default_filter, default_log_file, init_logging, level_for_verbosity, LOG_ENV_VAR,
+};
 pub use paths::{
-    config_dir, find_package_json, find_project_root, global_config_file, history_file,
-    local_config_file, MAX_SEARCH_DEPTH,
+    cache_dir, config_dir, find_package_json, find_project_root, global_config_file, history_file,
+    local_config_file, migrate_state_files, project_slug, state_dir, CONFIG_DIR_ENV, DATA_DIR_ENV,
+    MAX_SEARCH_DEPTH,
 };
+pub use sort::natural_cmp;
 pub use terminal::{
-    check_terminal_size, cleanup_terminal, disable_raw_mode, enable_raw_mode,
-    enter_alternate_screen, hide_cursor, is_raw_mode_enabled, leave_alternate_screen,
-    prepare_for_script_execution, restore_for_tui, show_cursor, TerminalSize, MIN_HEIGHT,
-    MIN_WIDTH,
+    check_terminal_size, cleanup_terminal, disable_raw_mode, discard_pending_input,
+    enable_raw_mode, enter_alternate_screen, hide_cursor, is_raw_mode_enabled,
+    leave_alternate_screen, prepare_for_script_execution, restore_for_tui, show_cursor,
+    TerminalSize, MIN_HEIGHT, MIN_WIDTH, USABLE_MIN_HEIGHT, USABLE_MIN_WIDTH,
 };
@@ -1,8 +1,10 @@
 //! Path utilities.
 
+use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
 
 use crate::error::NrsError;
 
@@ -23,13 +25,15 @@ pub fn find_package_json(start_dir: &Path) -> Result<PathBuf> {
             start_dir.display()
         )
     })?;
+    let start = simplify_path(start);
 
     let mut current = start.as_path();
     let mut depth = 0;
 
     while depth < MAX_SEARCH_DEPTH {
+        // A broken symlink counts, so reading it reports what's wrong
         let package_json = current.join("package.json");
-        if package_json.exists() {
+        if package_json.symlink_metadata().is_ok() {
             return Ok(package_json);
         }
 
@@ -49,6 +53,24 @@ pub fn find_package_json(start_dir: &Path) -> Result<PathBuf> {
     .into())
 }
 
+/// Strip the `\\?\` prefix Windows adds to canonicalized paths.
+///
+/// Scripts run from such verbatim paths trip up cmd.exe and npm, and they
+/// wouldn't match the paths in history. UNC paths go back to `\\server\share`.
+pub fn simplify_path(path: PathBuf) -> PathBuf {
+    let Some(text) = path.to_str() else {
+        return path;
+    };
+    if let Some(rest) = text.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{rest}"));
+    }
+    match text.strip_prefix(r"\\?\") {
+        // Only drive paths; other verbatim paths have no plain form
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
 /// Find the project root (directory containing package.json).
 ///
 /// # Errors
@@ -62,23 +84,157 @@ pub fn find_project_root(start_dir: &Path) -> Result<PathBuf> {
         .to_path_buf())
 }
 
+/// Environment variable overriding the config directory.
+pub const CONFIG_DIR_ENV: &str = "NRS_CONFIG_DIR";
+
+/// Environment variable overriding the state and cache directories.
+pub const DATA_DIR_ENV: &str = "NRS_DATA_DIR";
+
+/// Cache files, rebuilt whenever they're missing.
+const CACHE_FILES: [&str; 1] = ["brief.json"];
+
 /// Get the config directory for nrs.
 ///
-/// Returns `~/.config/nrs` on Unix-like systems.
+/// Returns `$NRS_CONFIG_DIR` when set, otherwise `~/.config/nrs` on
+/// Unix-like systems.
 pub fn config_dir() -> Option<PathBuf> {
-    dirs::config_dir().map(|p| p.join("nrs"))
+    env_dir(CONFIG_DIR_ENV).or_else(|| dirs::config_dir().map(|p| p.join("nrs")))
+}
+
+/// Get the directory nrs keeps its state in: history, pinned scripts and logs.
+///
+/// Returns `$NRS_DATA_DIR` when set, otherwise `$XDG_STATE_HOME/nrs`
+/// (`~/.local/state/nrs`) on Linux and the local data directory elsewhere.
+pub fn state_dir() -> Option<PathBuf> {
+    env_dir(DATA_DIR_ENV).or_else(|| {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|p| p.join("nrs"))
+    })
+}
+
+/// Get the directory for caches, which can be deleted at any time.
+///
+/// Returns `$NRS_DATA_DIR` when set, otherwise `$XDG_CACHE_HOME/nrs`
+/// (`~/.cache/nrs`) on Linux.
+pub fn cache_dir() -> Option<PathBuf> {
+    env_dir(DATA_DIR_ENV).or_else(|| dirs::cache_dir().map(|p| p.join("nrs")))
 }
 
 /// Get the history file path.
 ///
-/// Returns `~/.config/nrs/history.json`.
+/// Returns `history.json` in the state directory.
 pub fn history_file() -> Option<PathBuf> {
-    config_dir().map(|p| p.join("history.json"))
+    state_dir().map(|p| p.join("history.json"))
+}
+
+/// Move state and cache files that older versions kept in the config
+/// directory to their own directories.
+///
+/// Files already at the new location are left alone. Nothing moves when
+/// `$NRS_DATA_DIR` is set. Returns the new paths of the moved files.
+///
+/// # Errors
+///
+/// Returns an error if a file can't be moved.
+pub fn migrate_state_files() -> Result<Vec<PathBuf>> {
+    if env_dir(DATA_DIR_ENV).is_some() {
+        return Ok(Vec::new());
+    }
+    match (config_dir(), state_dir(), cache_dir()) {
+        (Some(config), Some(state), Some(cache)) => {
+            migrate_state_files_from(&config, &state, &cache)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn migrate_state_files_from(config: &Path, state: &Path, cache: &Path) -> Result<Vec<PathBuf>> {
+    let Ok(entries) = fs::read_dir(config) else {
+        return Ok(Vec::new());
+    };
+
+    let mut moved = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str() else {
+            continue;
+        };
+        let dir = if CACHE_FILES.contains(&name) {
+            cache
+        } else if is_state_file(name) {
+            state
+        } else {
+            continue;
+        };
+        let target = dir.join(name);
+        if dir == config || target.exists() {
+            continue;
+        }
+
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+        move_file(&entry.path(), &target)?;
+        moved.push(target);
+    }
+    Ok(moved)
+}
+
+/// Check if a file in the config directory holds state: the history, its
+/// backups and rotated snapshots, or pinned scripts.
+fn is_state_file(name: &str) -> bool {
+    let snapshot =
+        name.starts_with("history-") && (name.ends_with(".json") || name.ends_with(".json.gz"));
+    snapshot
+        || matches!(
+            name,
+            "history.json" | "history.json.bak" | "history.json.bak.repaired" | "favorites.json"
+        )
+}
+
+/// Move a file, copying it when it's on another file system.
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)
+        .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))?;
+    fs::remove_file(from).with_context(|| format!("Failed to remove {}", from.display()))
+}
+
+/// Read a directory from an environment variable, ignoring it when empty.
+fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Get a directory name for a project's files in the state directory.
+///
+/// It's the project's folder name plus a short hash of its path, so
+/// projects with the same name don't share files.
+pub fn project_slug(project_dir: &Path) -> String {
+    let name: String = project_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let hash = Sha256::digest(project_dir.to_string_lossy().as_bytes());
+    let short: String = hash[..4].iter().map(|b| format!("{b:02x}")).collect();
+    format!("{name}-{short}")
 }
 
 /// Get the global config file path.
 ///
-/// Returns `~/.config/nrs/config.toml`.
+/// Returns `config.toml` in the config directory.
 pub fn global_config_file() -> Option<PathBuf> {
     config_dir().map(|p| p.join("config.toml"))
 }
@@ -100,6 +256,15 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_project_slug() {
+        let a = project_slug(Path::new("/work/a/my app"));
+        let b = project_slug(Path::new("/work/b/my app"));
+        assert_ne!(a, b);
+        assert!(a.starts_with("my_app-"));
+        assert_eq!(a.len(), "my_app-".len() + 8);
+    }
+
     #[test]
     fn test_find_package_json_in_current_dir() {
         let temp = TempDir::new().unwrap();
@@ -128,4 +293,74 @@ mod tests {
         let result = find_package_json(temp.path());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_simplify_path() {
+        assert_eq!(
+            simplify_path(PathBuf::from(r"\\?\C:\work\app")),
+            PathBuf::from(r"C:\work\app")
+        );
+        assert_eq!(
+            simplify_path(PathBuf::from(r"\\?\UNC\server\share\app")),
+            PathBuf::from(r"\\server\share\app")
+        );
+        assert_eq!(
+            simplify_path(PathBuf::from(r"\\?\Volume{1234}\app")),
+            PathBuf::from(r"\\?\Volume{1234}\app")
+        );
+        assert_eq!(
+            simplify_path(PathBuf::from("/work/app")),
+            PathBuf::from("/work/app")
+        );
+    }
+
+    #[test]
+    fn test_migrate_state_files() {
+        let temp = TempDir::new().unwrap();
+        let config = temp.path().join("config");
+        let state = temp.path().join("state");
+        let cache = temp.path().join("cache");
+        fs::create_dir_all(&config).unwrap();
+        for name in [
+            "config.toml",
+            "trusted.json",
+            "history.json",
+            "history-20240101T000000.json.gz",
+            "favorites.json",
+            "brief.json",
+        ] {
+            fs::write(config.join(name), name).unwrap();
+        }
+        // Newer state wins over what's left behind
+        fs::create_dir_all(&state).unwrap();
+        fs::write(state.join("favorites.json"), "newer").unwrap();
+
+        let mut moved = migrate_state_files_from(&config, &state, &cache).unwrap();
+        moved.sort();
+        assert_eq!(
+            moved,
+            [
+                cache.join("brief.json"),
+                state.join("history-20240101T000000.json.gz"),
+                state.join("history.json"),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(state.join("history.json")).unwrap(),
+            "history.json"
+        );
+        assert_eq!(
+            fs::read_to_string(state.join("favorites.json")).unwrap(),
+            "newer"
+        );
+        assert!(config.join("config.toml").exists());
+        assert!(config.join("trusted.json").exists());
+        assert!(config.join("favorites.json").exists());
+        assert!(!config.join("history.json").exists());
+
+        // Nothing moves when state lives in the config directory
+        assert!(migrate_state_files_from(&config, &config, &config)
+            .unwrap()
+            .is_empty());
+    }
 }
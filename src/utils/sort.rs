@@ -0,0 +1,107 @@
+//! Sorting helpers.
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Compare two strings using a case-insensitive, numeric-aware "natural" order.
+///
+/// Runs of ASCII digits are compared by numeric value, so `task2` sorts
+/// before `task10`. Letters are compared case-insensitively. Strings that
+/// are equal under these rules fall back to byte ordering so the result is
+/// always deterministic.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use npm_run_scripts::utils::natural_cmp;
+///
+/// assert_eq!(natural_cmp("task2", "task10"), Ordering::Less);
+/// assert_eq!(natural_cmp("Build", "audit"), Ordering::Greater);
+/// ```
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut chars_a = a.chars().peekable();
+    let mut chars_b = b.chars().peekable();
+
+    loop {
+        match (chars_a.peek().copied(), chars_b.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ca), Some(cb)) if ca.is_ascii_digit() && cb.is_ascii_digit() => {
+                let num_a = take_digits(&mut chars_a);
+                let num_b = take_digits(&mut chars_b);
+                let ordering = compare_numeric(&num_a, &num_b);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(ca), Some(cb)) => {
+                let ordering = ca.to_lowercase().cmp(cb.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                chars_a.next();
+                chars_b.next();
+            }
+        }
+    }
+}
+
+/// Consume a run of ASCII digits.
+fn take_digits(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+        digits.push(c);
+        chars.next();
+    }
+    digits
+}
+
+/// Compare two digit runs by numeric value without parsing (no overflow).
+fn compare_numeric(a: &str, b: &str) -> Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp_numbers() {
+        assert_eq!(natural_cmp("task2", "task10"), Ordering::Less);
+        assert_eq!(natural_cmp("task10", "task2"), Ordering::Greater);
+        assert_eq!(natural_cmp("v1.9", "v1.10"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_natural_cmp_case_insensitive() {
+        assert_eq!(natural_cmp("Build", "audit"), Ordering::Greater);
+        assert_eq!(natural_cmp("dev", "Deploy"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_natural_cmp_leading_zeros_and_ties() {
+        assert_eq!(natural_cmp("a01", "a1"), Ordering::Less);
+        assert_eq!(natural_cmp("Dev", "dev"), Ordering::Less);
+        assert_eq!(natural_cmp("dev", "dev"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_natural_cmp_sorts_list() {
+        let mut names = vec!["task10", "Task1", "task2", "build"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, vec!["build", "Task1", "task2", "task10"]);
+    }
+
+    #[test]
+    fn test_natural_cmp_large_numbers() {
+        assert_eq!(
+            natural_cmp("x99999999999999999999999", "x100000000000000000000000"),
+            Ordering::Less
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! Terminal utilities.
 
 use std::io::{self, Write};
+use std::time::Duration;
 
+use crossterm::event;
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use crossterm::{cursor, execute};
 
@@ -9,6 +11,12 @@ use crossterm::{cursor, execute};
 pub const MIN_WIDTH: u16 = 40;
 pub const MIN_HEIGHT: u16 = 10;
 
+/// Smallest terminal dimensions the TUI can render at all.
+///
+/// Between these and [`MIN_WIDTH`]/[`MIN_HEIGHT`] a degraded layout is used.
+pub const USABLE_MIN_WIDTH: u16 = 20;
+pub const USABLE_MIN_HEIGHT: u16 = 5;
+
 /// Terminal size information.
 #[derive(Debug, Clone, Copy)]
 pub struct TerminalSize {
@@ -24,6 +32,16 @@ impl TerminalSize {
         self.width >= MIN_WIDTH && self.height >= MIN_HEIGHT
     }
 
+    /// Check if the terminal is large enough to render the TUI at all.
+    pub fn is_usable(&self) -> bool {
+        self.width >= USABLE_MIN_WIDTH && self.height >= USABLE_MIN_HEIGHT
+    }
+
+    /// Check if the TUI has to fall back to the degraded layout.
+    pub fn is_degraded(&self) -> bool {
+        self.is_usable() && !self.is_valid()
+    }
+
     /// Calculate the number of columns for the scripts grid.
     pub fn grid_columns(&self) -> usize {
         match self.width {
@@ -74,6 +92,19 @@ pub fn is_raw_mode_enabled() -> bool {
     terminal::is_raw_mode_enabled().unwrap_or(false)
 }
 
+/// Drop input events still waiting to be read.
+///
+/// Keys typed while the TUI hands over the terminal would otherwise be read
+/// by the script about to run. This matters most on Windows, where the
+/// console input buffer is shared with child processes.
+pub fn discard_pending_input() {
+    while matches!(event::poll(Duration::ZERO), Ok(true)) {
+        if event::read().is_err() {
+            break;
+        }
+    }
+}
+
 /// Enter the alternate screen buffer.
 ///
 /// This preserves the current terminal content and provides
@@ -135,6 +166,7 @@ pub fn hide_cursor() -> io::Result<()> {
 pub fn prepare_for_script_execution() -> io::Result<()> {
     // Only disable raw mode if it's enabled
     if is_raw_mode_enabled() {
+        discard_pending_input();
         disable_raw_mode()?;
     }
 
@@ -226,6 +258,30 @@ mod tests {
         assert!(!too_small.is_valid());
     }
 
+    #[test]
+    fn test_terminal_size_degraded() {
+        let small = TerminalSize {
+            width: 30,
+            height: 8,
+        };
+        assert!(small.is_usable());
+        assert!(small.is_degraded());
+
+        let tiny = TerminalSize {
+            width: 19,
+            height: 8,
+        };
+        assert!(!tiny.is_usable());
+        assert!(!tiny.is_degraded());
+
+        let normal = TerminalSize {
+            width: 80,
+            height: 24,
+        };
+        assert!(normal.is_usable());
+        assert!(!normal.is_degraded());
+    }
+
     #[test]
     fn test_grid_columns() {
         assert_eq!(
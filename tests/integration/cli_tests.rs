@@ -7,16 +7,18 @@ use assert_cmd::Command;
 use predicates::prelude::*;
 
 use crate::integration::fixtures::{
-    create_empty_project, create_large_project, create_project, create_project_invalid_json,
-    create_project_no_scripts, create_project_with_config, create_project_with_descriptions,
-    create_project_with_lifecycle_scripts, create_project_with_lockfile,
-    create_project_with_package_manager, scripts_with_special_chars, standard_scripts,
-    unicode_scripts, LockfileType,
+    create_empty_project, create_large_project, create_monorepo_project, create_project,
+    create_project_invalid_json, create_project_no_scripts, create_project_with_config,
+    create_project_with_descriptions, create_project_with_lifecycle_scripts,
+    create_project_with_lockfile, create_project_with_package_manager, scripts_with_special_chars,
+    shared_state_dir, standard_scripts, state_env, unicode_scripts, LockfileType,
 };
 
 /// Get a Command for the nrs binary.
 fn nrs() -> Command {
-    cargo_bin_cmd!("nrs")
+    let mut cmd = cargo_bin_cmd!("nrs");
+    cmd.envs(state_env(shared_state_dir()));
+    cmd
 }
 
 // ==================== Help and Version ====================
@@ -188,6 +190,389 @@ fn test_list_with_descriptions() {
         .stdout(predicate::str::contains("build"));
 }
 
+#[test]
+fn test_list_with_description_sources() {
+    let project = create_project_with_descriptions(&[("dev", "vite", "Start development server")]);
+    std::fs::write(
+        project.path().join(".nrsrc.toml"),
+        "[descriptions]\nsources = [\"comments\"]\n",
+    )
+    .unwrap();
+
+    nrs()
+        .args(["--list", "--long"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dev  vite"))
+        .stdout(predicate::str::contains("Start development server").not());
+}
+
+#[test]
+fn test_profile_overrides_config() {
+    let project = create_project_with_config(
+        &[("build", "vite build"), ("demo:seed", "node seed.js")],
+        "[profile.oss.exclude]\npatterns = [\"demo:*\"]\n",
+    );
+
+    nrs()
+        .arg("--list")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("demo:seed"));
+    nrs()
+        .args(["--list", "--profile", "oss"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("build"))
+        .stdout(predicate::str::contains("demo:seed").not());
+    nrs()
+        .arg("--list")
+        .env("NRS_PROFILE", "oss")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("demo:seed").not());
+
+    nrs()
+        .args(["--list", "--profile", "work"])
+        .current_dir(project.path())
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains(
+            "Unknown profile 'work' (available: oss)",
+        ));
+}
+
+#[test]
+fn test_list_long_does_not_truncate() {
+    let command = "node scripts/build.js --mode production --sourcemap --minify --out dist";
+    let project = create_project(&[("build", command)]);
+
+    nrs()
+        .arg("--list")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(command).not())
+        .stdout(predicate::str::contains("..."));
+
+    nrs()
+        .args(["--list", "--long"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(command));
+}
+
+#[test]
+fn test_list_columns() {
+    let project = create_project_with_descriptions(&[("dev", "vite", "Start development server")]);
+
+    nrs()
+        .args(["--list", "--columns", "name,lastrun"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dev"))
+        .stdout(predicate::str::contains("never"))
+        .stdout(predicate::str::contains("vite").not())
+        .stdout(predicate::str::contains("Start development server").not());
+}
+
+#[test]
+fn test_list_long_puts_columns_on_separate_lines() {
+    let project = create_project_with_descriptions(&[("dev", "vite", "Start development server")]);
+
+    nrs()
+        .args(["--list", "--long", "--columns", "name,command,desc"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "  dev  vite\n       Start development server\n",
+        ));
+}
+
+#[test]
+fn test_summary_counts_categories() {
+    let project = create_project(&[
+        ("build", "vite build"),
+        ("test", "vitest"),
+        ("test:unit", "vitest unit"),
+        ("test:e2e", "playwright test"),
+        ("deploy:prod", "deploy prod"),
+    ]);
+
+    nrs()
+        .arg("--summary")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  test      3"))
+        .stdout(predicate::str::contains("  deploy    1"))
+        .stdout(predicate::str::contains("5 scripts in 3 categories"));
+}
+
+#[test]
+fn test_team_config_below_project_config() {
+    let project = create_project_with_config(
+        &[
+            ("build", "vite build"),
+            ("e2e", "playwright test"),
+            ("test", "vitest"),
+        ],
+        "[scripts.descriptions]\nbuild = \"Personal build notes\"\n",
+    );
+    std::fs::create_dir(project.path().join(".nrs")).unwrap();
+    std::fs::write(
+        project.path().join(".nrs/team.toml"),
+        r#"
+[scripts.descriptions]
+build = "Team build notes"
+test = "Run the unit tests"
+
+[scripts.categories]
+e2e = "test"
+"#,
+    )
+    .unwrap();
+
+    nrs()
+        .args(["--list", "--long"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Personal build notes"))
+        .stdout(predicate::str::contains("Run the unit tests"))
+        .stdout(predicate::str::contains("Team build notes").not());
+
+    nrs()
+        .arg("--summary")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  test     2"));
+}
+
+#[test]
+fn test_list_json() {
+    let project = create_project_with_descriptions(&[
+        ("dev", "vite", "Start the dev server"),
+        ("build", "vite build", ""),
+    ]);
+    let config_home = tempfile::tempdir().unwrap();
+
+    let output = nrs()
+        .args(["--list", "--json"])
+        .current_dir(project.path())
+        .envs(state_env(config_home.path()))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["runner"], "npm");
+    let scripts = json["scripts"].as_array().unwrap();
+    assert!(!scripts.is_empty());
+    assert_eq!(scripts.len(), 2);
+    let dev = scripts.iter().find(|s| s["name"] == "dev").unwrap();
+    assert_eq!(dev["description"], "Start the dev server");
+    for script in scripts {
+        assert!(script["name"].is_string());
+        assert!(script["command"].is_string());
+        assert_eq!(script["runner"], "npm");
+        assert!(script["workspace"].is_null());
+        assert!(script["history"].is_null());
+    }
+}
+
+#[test]
+fn test_list_plain_format() {
+    let project = create_project(&[("build", "vite build"), ("dev", "vite")]);
+
+    nrs()
+        .args([
+            "--list",
+            "--format",
+            "plain",
+            "--columns",
+            "name,desc,command",
+        ])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout("build\t\tvite build\ndev\t\tvite\n");
+}
+
+#[test]
+fn test_list_workspace_json() {
+    let project = create_monorepo_project();
+
+    let output = nrs()
+        .args(["--list", "--json", "--workspace", "@app/web"])
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["workspace"], "@app/web");
+    let names: Vec<&str> = json["scripts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"build"));
+    assert!(!names.contains(&"lint"));
+}
+
+#[test]
+fn test_list_favorites() {
+    let project = create_project(&[("build", "vite build"), ("test", "vitest")]);
+    let config_home = tempfile::tempdir().unwrap();
+
+    // Nothing pinned yet
+    nrs()
+        .args(["--list", "--favorites"])
+        .current_dir(project.path())
+        .envs(state_env(config_home.path()))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No pinned scripts"));
+
+    std::fs::write(
+        project.path().join(".nrsrc.toml"),
+        "[scripts]\nfavorites = [\"test\"]\n",
+    )
+    .unwrap();
+
+    nrs()
+        .args(["--list", "--favorites"])
+        .current_dir(project.path())
+        .envs(state_env(config_home.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test"))
+        .stdout(predicate::str::contains("build").not());
+}
+
+#[test]
+fn test_graph() {
+    let project = create_project(&[
+        ("prebuild", "rimraf dist"),
+        ("build", "run-p build:*"),
+        ("build:css", "sass src:dist"),
+        ("build:js", "npm run lint && esbuild src/index.ts"),
+        ("lint", "eslint ."),
+    ]);
+
+    nrs()
+        .args(["--graph", "build"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "build\n├─ prebuild (pre)\n├─ build:css (run-p)\n└─ build:js (run-p)\n   └─ lint (run)\n",
+        ));
+
+    nrs()
+        .args(["--graph", "lint"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "lint doesn't run any other scripts",
+        ));
+
+    nrs()
+        .args(["--graph", "buidl"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("build"));
+}
+
+#[test]
+fn test_list_category() {
+    let project = create_project(&[
+        ("build", "vite build"),
+        ("test:unit", "vitest unit"),
+        ("test:e2e", "playwright test"),
+    ]);
+
+    nrs()
+        .args(["--list", "--category", "test"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test:unit"))
+        .stdout(predicate::str::contains("test:e2e"))
+        .stdout(predicate::str::contains("vite build").not())
+        .stdout(predicate::str::contains("2 scripts found"));
+
+    nrs()
+        .args(["--list", "--category", "lint"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No scripts in category \"lint\""));
+}
+
+/// Run git in a directory, panicking on failure.
+fn git(dir: &std::path::Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=nrs", "-c", "user.email=nrs@example.test"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("git should be installed")
+        .status;
+    assert!(status.success(), "git {args:?} failed");
+}
+
+#[test]
+fn test_diff_against_git_ref() {
+    let project = create_project(&[("build", "vite build"), ("format", "prettier")]);
+    git(project.path(), &["init", "-q"]);
+    git(project.path(), &["add", "package.json"]);
+    git(project.path(), &["commit", "-q", "-m", "initial"]);
+
+    std::fs::write(
+        project.path().join("package.json"),
+        r#"{"name": "test-project", "scripts": {"build": "vite build --mode prod", "lint": "eslint ."}}"#,
+    )
+    .unwrap();
+
+    nrs()
+        .args(["diff", "HEAD"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+ lint"))
+        .stdout(predicate::str::contains("- format"))
+        .stdout(predicate::str::contains("~ build"))
+        .stdout(predicate::str::contains("1 added, 1 removed, 1 changed"));
+}
+
+#[test]
+fn test_diff_unknown_ref() {
+    let project = create_project(&[("build", "vite build")]);
+    git(project.path(), &["init", "-q"]);
+
+    nrs()
+        .args(["diff", "no-such-branch"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Failed to read package.json at no-such-branch",
+        ));
+}
+
 // ==================== Script Execution ====================
 
 #[test]
@@ -214,6 +599,88 @@ fn test_script_short_flag() {
         .stdout(predicate::str::contains("success"));
 }
 
+#[test]
+fn test_log_saves_output_for_nrs_logs() {
+    let project = create_project(&[("test:unit", "echo logged-line")]);
+    let state = tempfile::tempdir().unwrap();
+
+    nrs()
+        .args(["-n", "test:unit", "--log"])
+        .current_dir(project.path())
+        .envs(state_env(state.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("logged-line"));
+
+    nrs()
+        .arg("logs")
+        .current_dir(project.path())
+        .envs(state_env(state.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test:unit"));
+
+    nrs()
+        .args(["logs", "--tail", "test:unit"])
+        .current_dir(project.path())
+        .envs(state_env(state.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("logged-line"));
+}
+
+#[test]
+fn test_record_writes_asciicast() {
+    let project = create_project(&[("build", "echo recorded-line")]);
+    let cast = project.path().join("out.cast");
+
+    nrs()
+        .args(["-n", "build", "--record"])
+        .arg(&cast)
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("recorded-line"));
+
+    let content = std::fs::read_to_string(&cast).unwrap();
+    let mut lines = content.lines();
+    let header: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert_eq!(header["version"], 2);
+    let output: String = lines
+        .map(|line| {
+            let event: serde_json::Value = serde_json::from_str(line).unwrap();
+            event[2].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert!(output.contains("$ npm run build"), "{output}");
+    assert!(output.contains("recorded-line\r\n"), "{output}");
+    assert!(output.contains("exit code 0"), "{output}");
+}
+
+#[test]
+fn test_record_leaves_out_interactive_scripts() {
+    let project = create_project(&[("shell", "echo interactive-line")]);
+    std::fs::write(
+        project.path().join(".nrsrc.toml"),
+        "[scripts]\ninteractive = [\"shell\"]\n",
+    )
+    .unwrap();
+    let cast = project.path().join("out.cast");
+
+    nrs()
+        .args(["-n", "shell", "--record"])
+        .arg(&cast)
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("interactive-line"));
+
+    let content = std::fs::read_to_string(&cast).unwrap();
+    assert!(content.contains("$ npm run shell"), "{content}");
+    assert!(content.contains("output not recorded"), "{content}");
+    assert!(!content.contains("interactive-line"), "{content}");
+}
+
 #[test]
 fn test_script_invalid() {
     let project = create_project(&standard_scripts());
@@ -279,57 +746,720 @@ fn test_dry_run() {
 }
 
 #[test]
-fn test_dry_run_short_flag() {
+fn test_read_only_does_not_run_or_record() {
     let project = create_project(&standard_scripts());
+    let config_home = tempfile::tempdir().unwrap();
 
     nrs()
-        .args(["-n", "dev", "-d"])
+        .args(["-n", "dev", "--read-only"])
         .current_dir(project.path())
+        .envs(state_env(config_home.path()))
         .assert()
         .success()
-        .stdout(predicate::str::contains("Would run:"));
+        .stdout(predicate::str::contains("Would run: npm run dev"));
+
+    assert!(!config_home.path().join("history.json").exists());
 }
 
 #[test]
-fn test_dry_run_with_runner() {
+fn test_dry_run_short_flag() {
     let project = create_project(&standard_scripts());
 
     nrs()
-        .args(["--script", "dev", "--dry-run", "--runner", "pnpm"])
+        .args(["-n", "dev", "-d"])
         .current_dir(project.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("pnpm dev"));
+        .stdout(predicate::str::contains("Would run:"));
 }
 
 #[test]
-fn test_dry_run_with_args() {
+fn test_deno_tasks_run_with_deno() {
     let project = create_project(&standard_scripts());
+    std::fs::write(
+        project.path().join("deno.jsonc"),
+        r#"{
+            // Deno side of the project
+            "tasks": { "fmt:deno": "deno fmt" }
+        }"#,
+    )
+    .unwrap();
 
     nrs()
-        .args(["--script", "test", "--dry-run", "--args", "--watch"])
+        .arg("--list")
         .current_dir(project.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("-- --watch"));
-}
+        .stdout(predicate::str::contains("fmt:deno"))
+        .stdout(predicate::str::contains("dev"));
 
-// ==================== Exclude Patterns ====================
+    nrs()
+        .args(["-n", "fmt:deno", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would run: deno task fmt:deno"));
+}
 
 #[test]
-fn test_exclude_single_pattern() {
-    let project = create_project(&standard_scripts());
+fn test_script_pattern_runs_matching_scripts() {
+    let project = create_project(&[
+        ("test:unit", "vitest"),
+        ("test:e2e", "playwright test"),
+        ("test:e2e:ci", "playwright test --ci"),
+        ("build", "vite build"),
+    ]);
 
-    let output = nrs()
-        .args(["--list", "--exclude", "test"])
+    nrs()
+        .args(["-n", "test:*", "--dry-run"])
         .current_dir(project.path())
-        .output()
-        .expect("Failed to run nrs");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("\"test:*\" matches 2 scripts:"))
+        .stdout(predicate::str::contains("Would run: npm run test:unit"))
+        .stdout(predicate::str::contains("Would run: npm run test:e2e"))
+        .stdout(predicate::str::contains("test:e2e:ci").not())
+        .stdout(predicate::str::contains("build").not());
 
-    // Should have 4 scripts (excluding "test")
-    assert!(
+    nrs()
+        .args(["-n", "lint:*", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No scripts match \"lint:*\""));
+}
+
+#[test]
+fn test_group_runs_members() {
+    let config = r#"
+[groups]
+ci = ["lint", "test"]
+"#;
+    let project = create_project_with_config(&standard_scripts(), config);
+
+    nrs()
+        .arg("--list")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("lint && test"));
+
+    nrs()
+        .args(["-n", "ci", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would run: npm run lint"))
+        .stdout(predicate::str::contains("Would run: npm run test"));
+}
+
+#[test]
+fn test_recipe_runs_members_in_order() {
+    let config = r#"
+[recipes]
+ship = ["lint", "test", "build"]
+"#;
+    let project = create_project_with_config(&standard_scripts(), config);
+
+    let output = nrs()
+        .args(["--script", "ship", "--dry-run"])
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let runs: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("Would run: npm run "))
+        .collect();
+    assert_eq!(runs, ["lint", "test", "build"], "{stdout}");
+}
+
+#[test]
+fn test_confirm_refuses_without_terminal() {
+    let project = create_project_with_config(
+        &[("deploy", "echo deploying")],
+        "[scripts]\nconfirm = [\"deploy*\"]\n",
+    );
+
+    nrs()
+        .args(["--script", "deploy"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Pass --yes"));
+}
+
+#[test]
+fn test_concurrency_group_warns_about_running_script() {
+    let project = create_project_with_config(
+        &[("dev", "sleep 10"), ("dev:https", "echo serving")],
+        "[concurrency]\nservers = [\"dev*\"]\n",
+    );
+    let state = tempfile::tempdir().unwrap();
+
+    let mut dev = std::process::Command::new(env!("CARGO_BIN_EXE_nrs"))
+        .args(["--script", "dev"])
+        .current_dir(project.path())
+        .envs(state_env(state.path()))
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Wait for the dev run to be recorded
+    let jobs_dir = state.path().join("jobs");
+    let recorded = || {
+        std::fs::read_dir(&jobs_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|project| std::fs::read_dir(project.path()).ok())
+            .flatten()
+            .flatten()
+            .any(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+    };
+    for _ in 0..100 {
+        if recorded() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+
+    let assert = nrs()
+        .args(["--script", "dev:https"])
+        .current_dir(project.path())
+        .envs(state_env(state.path()))
+        .assert();
+    let _ = dev.kill();
+    let _ = dev.wait();
+
+    assert
+        .success()
+        .stdout(predicate::str::contains("serving"))
+        .stderr(predicate::str::contains("dev is already running"))
+        .stderr(predicate::str::contains("(group servers)"));
+}
+
+#[test]
+fn test_timeout_stops_script() {
+    let project = create_project_with_config(
+        &[("slow", "echo started && sleep 10")],
+        "[scripts.slow]\ntimeout = \"1s\"\n",
+    );
+
+    nrs()
+        .args(["--script", "slow"])
+        .current_dir(project.path())
+        .timeout(std::time::Duration::from_secs(8))
+        .assert()
+        .code(124)
+        .stdout(predicate::str::contains("started"))
+        .stderr(predicate::str::contains("slow timed out after 1s"));
+}
+
+#[test]
+fn test_retries_failed_script() {
+    let project = create_project_with_config(
+        &[("flaky", "echo trying && exit 3")],
+        "[scripts.flaky]\nretries = 1\n",
+    );
+
+    let output = nrs()
+        .args(["--script", "flaky"])
+        .current_dir(project.path())
+        .assert()
+        .code(3)
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stdout.lines().filter(|line| *line == "trying").count(),
+        2,
+        "{stdout}"
+    );
+    assert!(stderr.contains("retrying in 1s (attempt 2/2)"), "{stderr}");
+}
+
+#[test]
+fn test_enforce_package_manager_version() {
+    let project = create_project_with_package_manager(&[("hello", "echo hello")], "npm@0.0.1");
+
+    std::fs::write(
+        project.path().join(".nrsrc.toml"),
+        "[general]\nenforce_package_manager = \"fail\"\n",
+    )
+    .unwrap();
+    nrs()
+        .args(["--script", "hello"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("packageManager pins npm 0.0.1"))
+        .stderr(predicate::str::contains("corepack enable"));
+
+    std::fs::write(
+        project.path().join(".nrsrc.toml"),
+        "[general]\nenforce_package_manager = \"warn\"\n",
+    )
+    .unwrap();
+    nrs()
+        .args(["--script", "hello"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello"))
+        .stderr(predicate::str::contains(
+            "Warning: packageManager pins npm 0.0.1",
+        ));
+}
+
+#[test]
+fn test_missing_node_modules_is_reported() {
+    let project = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        project.path().join("package.json"),
+        r#"{"scripts": {"hello": "echo hello"}, "dependencies": {"left-pad": "^1.3.0"}}"#,
+    )
+    .unwrap();
+
+    // Without a terminal the script runs anyway
+    nrs()
+        .args(["--script", "hello"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello"))
+        .stderr(predicate::str::contains("Dependencies aren't installed"))
+        .stderr(predicate::str::contains(
+            "Run npm install if the script fails",
+        ));
+
+    std::fs::create_dir(project.path().join("node_modules")).unwrap();
+    nrs()
+        .args(["--script", "hello"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Dependencies").not());
+}
+
+#[test]
+fn test_parallel_requires_script() {
+    let project = create_project(&standard_scripts());
+    nrs()
+        .arg("--parallel")
+        .current_dir(project.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_turbo_tasks_run_with_turbo() {
+    let project = create_project(&standard_scripts());
+    std::fs::write(
+        project.path().join("turbo.json"),
+        r#"{ "tasks": { "build": {}, "typecheck": { "dependsOn": ["^build"] } } }"#,
+    )
+    .unwrap();
+
+    nrs()
+        .arg("--list")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("typecheck"));
+
+    nrs()
+        .args(["-n", "typecheck", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would run: turbo run typecheck"));
+
+    // The package.json script wins over the task of the same name
+    nrs()
+        .args(["-n", "build", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("turbo").not());
+}
+
+#[test]
+fn test_dry_run_shows_limits() {
+    let config = r#"
+[limits.scripts]
+"b*" = { nice = 10, max_memory_mb = 2048 }
+"#;
+    let project = create_project_with_config(&standard_scripts(), config);
+
+    nrs()
+        .args(["-n", "build", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "npm run build (nice 10, max memory 2048 MB)",
+        ));
+
+    nrs()
+        .args(["-n", "dev", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run dev\n"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_run_with_nice_limit() {
+    if std::process::Command::new("npm")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        return;
+    }
+
+    let project = create_project_with_config(
+        &[("niceness", "echo niceness=$(nice)")],
+        "[limits]\nnice = 5\n",
+    );
+
+    nrs()
+        .args(["-n", "niceness"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("niceness=5"));
+}
+
+#[test]
+fn test_run_passes_exit_code() {
+    // Runs npm for real, through npm.cmd on Windows
+    if std::process::Command::new("node")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        return;
+    }
+
+    let project = create_project(&[("fail", "node fail.js"), ("args", "node args.js")]);
+    std::fs::write(project.path().join("fail.js"), "process.exit(3);\n").unwrap();
+    std::fs::write(
+        project.path().join("args.js"),
+        "console.log(process.argv.slice(2).join('|'));\n",
+    )
+    .unwrap();
+    let config_home = tempfile::tempdir().unwrap();
+
+    nrs()
+        .args(["-n", "fail", "--runner", "npm"])
+        .current_dir(project.path())
+        .envs(state_env(config_home.path()))
+        .assert()
+        .code(3);
+
+    // The exit code is kept in history for --list --verbose
+    nrs()
+        .args(["--list", "--verbose", "--format", "plain"])
+        .current_dir(project.path())
+        .envs(state_env(config_home.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("failed (3)"));
+
+    nrs()
+        .args([
+            "-n",
+            "args",
+            "--runner",
+            "npm",
+            "--args",
+            "--watch src/app.ts",
+        ])
+        .current_dir(project.path())
+        .envs(state_env(config_home.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--watch|src/app.ts"));
+}
+
+#[test]
+fn test_dry_run_with_runner() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--script", "dev", "--dry-run", "--runner", "pnpm"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pnpm dev"));
+}
+
+#[test]
+fn test_dry_run_with_args() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--script", "test", "--dry-run", "--args", "--watch"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-- --watch"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dry_run_keeps_quoted_args_together() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["-n", "test", "--dry-run", "--args", "--grep 'a b'"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-- --grep 'a b'"));
+}
+
+// ==================== Workspaces ====================
+
+#[test]
+fn test_workspace_script_dry_run() {
+    let project = create_monorepo_project();
+
+    nrs()
+        .args([
+            "-n",
+            "build",
+            "--workspace",
+            "@app/web",
+            "--args",
+            "--mode prod",
+            "--dry-run",
+        ])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "npm run -w @app/web build -- --mode prod",
+        ));
+}
+
+#[test]
+fn test_workspace_by_path() {
+    let project = create_monorepo_project();
+
+    nrs()
+        .args(["-n", "dev", "-w", "packages/api", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run -w @app/api dev"));
+}
+
+#[test]
+fn test_workspace_not_found() {
+    let project = create_monorepo_project();
+
+    nrs()
+        .args(["-n", "build", "--workspace", "@app/nope", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Workspace '@app/nope' not found"))
+        .stderr(predicate::str::contains("@app/web"));
+}
+
+#[test]
+fn test_workspace_script_not_found() {
+    let project = create_monorepo_project();
+
+    nrs()
+        .args(["-n", "deploy", "--workspace", "@app/web", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Script 'deploy' not found"));
+}
+
+#[test]
+fn test_print_workspace_path() {
+    let project = create_monorepo_project();
+    let expected = project.path().join("packages").join("web");
+
+    nrs()
+        .args(["--print-workspace-path", "web"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(format!(
+            "{}\n",
+            expected.canonicalize().unwrap().display()
+        )));
+}
+
+#[test]
+fn test_workspace_requires_script() {
+    let project = create_monorepo_project();
+
+    nrs()
+        .args(["--workspace", "@app/web"])
+        .current_dir(project.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_matrix_shows_scripts_per_workspace() {
+    let project = create_monorepo_project();
+    let docs = project.path().join("packages/docs");
+    std::fs::create_dir_all(&docs).unwrap();
+    std::fs::write(
+        docs.join("package.json"),
+        r#"{"name": "docs", "scripts": {"build": "vitepress build"}}"#,
+    )
+    .unwrap();
+
+    let output = nrs()
+        .arg("matrix")
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines[0].split_whitespace().collect::<Vec<_>>(),
+        ["build", "dev"]
+    );
+    let docs_row = lines.iter().find(|l| l.starts_with("docs")).unwrap();
+    assert_eq!(
+        docs_row.split_whitespace().collect::<Vec<_>>(),
+        ["docs", "✓", "–"]
+    );
+    assert!(stdout.contains("Missing: dev (1 of 3)"), "{stdout}");
+
+    nrs()
+        .args(["matrix", "--scripts", "test"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Missing: test (3 of 3)"));
+
+    nrs()
+        .args(["matrix", "--format", "csv"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("workspace,build,dev\n"))
+        .stdout(predicate::str::contains("docs,true,false\n"));
+}
+
+#[test]
+fn test_all_workspaces_dry_run() {
+    let project = create_monorepo_project();
+
+    nrs()
+        .args(["--all-workspaces", "build", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run -w @app/web build"))
+        .stdout(predicate::str::contains("npm run -w @app/api build"));
+
+    nrs()
+        .args(["--all-workspaces", "lint", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No workspace defines"));
+}
+
+#[test]
+fn test_workspace_resolution_falls_back_to_globs() {
+    let project = create_monorepo_project();
+    std::fs::write(project.path().join("yarn.lock"), "").unwrap();
+    std::fs::write(
+        project.path().join(".nrsrc.toml"),
+        "[general]\nworkspace_resolution = \"manager\"\n",
+    )
+    .unwrap();
+
+    // Found by yarn berry, or from the globs when yarn can't list them
+    nrs()
+        .args(["--all-workspaces", "build", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("yarn workspace @app/web build"))
+        .stdout(predicate::str::contains("yarn workspace @app/api build"));
+}
+
+#[test]
+fn test_parent_scripts_listed_in_package() {
+    let project = create_monorepo_project();
+    let web = project.path().join("packages").join("web");
+    std::fs::write(web.join(".nrsrc.toml"), "[parent]\nscripts = [\"lint\"]\n").unwrap();
+
+    nrs()
+        .arg("--list")
+        .current_dir(&web)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("root:lint"));
+
+    // Runs the root script from the monorepo root
+    nrs()
+        .args(["-n", "root:lint", "--dry-run"])
+        .current_dir(&web)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run lint"));
+}
+
+#[test]
+fn test_unreadable_workspace_manifest() {
+    let project = create_monorepo_project();
+    let broken = project.path().join("packages").join("broken");
+    std::fs::create_dir_all(&broken).unwrap();
+    std::fs::write(broken.join("package.json"), "{ not json").unwrap();
+
+    nrs()
+        .args(["--all-workspaces", "build", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run -w @app/web build"))
+        .stderr(predicate::str::contains("skipping broken"));
+
+    nrs()
+        .args(["-n", "build", "--workspace", "broken", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Can't read workspace broken"))
+        .stderr(predicate::str::contains("invalid JSON"));
+}
+
+// ==================== Exclude Patterns ====================
+
+#[test]
+fn test_exclude_single_pattern() {
+    let project = create_project(&standard_scripts());
+
+    let output = nrs()
+        .args(["--list", "--exclude", "test"])
+        .current_dir(project.path())
+        .output()
+        .expect("Failed to run nrs");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Should have 4 scripts (excluding "test")
+    assert!(
         stdout.contains("4 scripts found"),
         "Expected 4 scripts, got: {}",
         stdout
@@ -343,267 +1473,698 @@ fn test_exclude_single_pattern() {
 }
 
 #[test]
-fn test_exclude_multiple_patterns() {
+fn test_exclude_multiple_patterns() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--list", "--exclude", "test", "--exclude", "lint"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 scripts found"));
+}
+
+#[test]
+fn test_exclude_wildcard() {
+    let project = create_project(&scripts_with_special_chars());
+
+    nrs()
+        .args(["--list", "--exclude", "build:*"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 scripts found"));
+}
+
+#[test]
+fn test_exclude_lifecycle_scripts() {
+    let project = create_project_with_lifecycle_scripts();
+
+    // By default, lifecycle scripts should be filtered
+    nrs()
+        .arg("--list")
+        .current_dir(project.path())
+        .assert()
+        .success();
+}
+
+// ==================== Runner Override ====================
+
+#[test]
+fn test_runner_npm() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--list", "--runner", "npm"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm"));
+}
+
+#[test]
+fn test_runner_yarn() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--list", "--runner", "yarn"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("yarn"));
+}
+
+#[test]
+fn test_runner_pnpm() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--list", "--runner", "pnpm"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pnpm"));
+}
+
+#[test]
+fn test_runner_bun() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--list", "--runner", "bun"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bun"));
+}
+
+#[test]
+fn test_runner_invalid() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--list", "--runner", "invalid"])
+        .current_dir(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid"));
+}
+
+// ==================== Exit Codes ====================
+
+#[test]
+fn test_exit_code_success() {
+    let project = create_project(&[("test", "true")]);
+
+    nrs()
+        .args(["--script", "test"])
+        .current_dir(project.path())
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn test_exit_code_no_package_json() {
+    let temp = tempfile::tempdir().unwrap();
+
+    nrs()
+        .arg("--list")
+        .current_dir(temp.path())
+        .assert()
+        .code(2); // NO_PACKAGE_JSON
+}
+
+#[test]
+fn test_exit_code_no_scripts() {
+    let project = create_empty_project();
+
+    nrs()
+        .arg("--list")
+        .current_dir(project.path())
+        .assert()
+        .code(3); // NO_SCRIPTS
+}
+
+#[test]
+fn test_exit_code_script_failed() {
+    let project = create_project(&[("fail", "exit 42")]);
+
+    nrs()
+        .args(["--script", "fail"])
+        .current_dir(project.path())
+        .assert()
+        .code(42); // Script's exit code
+}
+
+#[test]
+fn test_exit_code_policy() {
+    let project = create_project_with_config(
+        &[("fail", "exit 42"), ("lint", "exit 1")],
+        "[general]\nexit_code = \"remap\"\nexit_code_map = { \"1\" = 0 }\n",
+    );
+
+    nrs()
+        .args(["--script", "lint"])
+        .current_dir(project.path())
+        .assert()
+        .code(0)
+        .stderr(predicate::str::contains("Exit code 1, exiting with 0"));
+    nrs()
+        .args(["--script", "fail"])
+        .current_dir(project.path())
+        .assert()
+        .code(42);
+
+    std::fs::write(
+        project.path().join(".nrsrc.toml"),
+        "[general]\nexit_code = \"zero\"\n",
+    )
+    .unwrap();
+    nrs()
+        .args(["--script", "fail"])
+        .current_dir(project.path())
+        .assert()
+        .code(0);
+}
+
+// ==================== Debug Mode ====================
+
+#[test]
+fn test_debug_mode() {
     let project = create_project(&standard_scripts());
 
     nrs()
-        .args(["--list", "--exclude", "test", "--exclude", "lint"])
+        .args(["--list", "--debug"])
         .current_dir(project.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("3 scripts found"));
+        .stderr(predicate::str::contains("=== nrs debug mode ==="))
+        .stderr(predicate::str::contains("Version:"))
+        .stderr(predicate::str::contains("Project directory"))
+        .stderr(predicate::str::contains("Package manager"));
 }
 
 #[test]
-fn test_exclude_wildcard() {
-    let project = create_project(&scripts_with_special_chars());
+fn test_debug_shows_file_locations() {
+    let project = create_project(&standard_scripts());
 
     nrs()
-        .args(["--list", "--exclude", "build:*"])
+        .args(["--list", "--debug"])
         .current_dir(project.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("3 scripts found"));
+        .stderr(predicate::str::contains("History file:"))
+        .stderr(predicate::str::contains("package.json:"));
 }
 
+// ==================== Path Argument ====================
+
 #[test]
-fn test_exclude_lifecycle_scripts() {
-    let project = create_project_with_lifecycle_scripts();
+fn test_path_argument() {
+    let project = create_project(&standard_scripts());
+
+    nrs()
+        .args(["--list", project.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dev"));
+}
+
+#[test]
+fn test_path_nonexistent() {
+    nrs()
+        .args(["--list", "/nonexistent/path"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot access directory"));
+}
+
+// ==================== Invalid JSON ====================
+
+#[test]
+fn test_invalid_json() {
+    let project = create_project_invalid_json();
 
-    // By default, lifecycle scripts should be filtered
     nrs()
         .arg("--list")
         .current_dir(project.path())
         .assert()
-        .success();
+        .failure()
+        .stderr(predicate::str::contains("Failed to parse"));
+}
+
+#[test]
+fn test_too_many_scripts_needs_force() {
+    let names: Vec<String> = (0..10_001).map(|i| format!("script:{i}")).collect();
+    let scripts: Vec<(&str, &str)> = names.iter().map(|n| (n.as_str(), "echo")).collect();
+    let project = create_project(&scripts);
+
+    nrs()
+        .args(["--list", "--format", "plain"])
+        .current_dir(project.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("defines 10001 scripts"))
+        .stderr(predicate::str::contains("--force"));
+
+    nrs()
+        .args(["--list", "--format", "plain", "--force"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("script:10000"));
+}
+
+// ==================== Config ====================
+
+#[test]
+fn test_config_file_loaded() {
+    let config = r#"
+[appearance]
+icons = false
+
+[exclude]
+patterns = ["lint"]
+"#;
+    let project = create_project_with_config(&standard_scripts(), config);
+
+    // With config excluding "lint", should have 4 scripts
+    nrs()
+        .arg("--list")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4 scripts found"));
+}
+
+#[test]
+fn test_no_config_flag() {
+    let config = r#"
+[exclude]
+patterns = ["lint", "test"]
+"#;
+    let project = create_project_with_config(&standard_scripts(), config);
+
+    // With --no-config, should have all 5 scripts
+    nrs()
+        .args(["--list", "--no-config"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 scripts found"));
+}
+
+#[test]
+fn test_aliases_listed_and_run() {
+    let config = r#"
+[scripts.aliases]
+t = "test -- --watch"
+"#;
+    let project = create_project_with_config(&standard_scripts(), config);
+
+    nrs()
+        .arg("--list")
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("6 scripts found"))
+        .stdout(predicate::str::contains("Alias of test -- --watch"));
+
+    nrs()
+        .args(["t", "--dry-run"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run test -- --watch"));
 }
 
-// ==================== Runner Override ====================
+// ==================== Last Script ====================
 
 #[test]
-fn test_runner_npm() {
+fn test_last_no_history() {
     let project = create_project(&standard_scripts());
 
+    // Create a temporary history dir to isolate the test
     nrs()
-        .args(["--list", "--runner", "npm"])
+        .arg("--last")
         .current_dir(project.path())
         .assert()
-        .success()
-        .stdout(predicate::str::contains("npm"));
+        .failure()
+        .stderr(predicate::str::contains("No previous script"));
 }
 
 #[test]
-fn test_runner_yarn() {
-    let project = create_project(&standard_scripts());
+fn test_watch_failed_stops_once_passing() {
+    let project = create_project(&[("check", "test -f fixed"), ("build", "true")]);
+    let state = tempfile::tempdir().unwrap();
 
     nrs()
-        .args(["--list", "--runner", "yarn"])
+        .arg("--watch-failed")
+        .envs(state_env(state.path()))
         .current_dir(project.path())
         .assert()
-        .success()
-        .stdout(predicate::str::contains("yarn"));
-}
+        .failure()
+        .stderr(predicate::str::contains("No failing scripts"));
 
-#[test]
-fn test_runner_pnpm() {
-    let project = create_project(&standard_scripts());
+    nrs()
+        .args(["-n", "check"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .assert()
+        .failure();
+    std::fs::write(project.path().join("fixed"), "").unwrap();
 
     nrs()
-        .args(["--list", "--runner", "pnpm"])
+        .arg("--watch-failed")
+        .envs(state_env(state.path()))
         .current_dir(project.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("pnpm"));
+        .stderr(predicate::str::contains("Watching check"))
+        .stderr(predicate::str::contains("check passes, stopped watching"));
 }
 
 #[test]
-fn test_runner_bun() {
+fn test_last_matching_pattern_and_history() {
     let project = create_project(&standard_scripts());
+    let state = tempfile::tempdir().unwrap();
+
+    for args in [
+        vec!["-n", "test", "--args", "--watch", "--dry-run"],
+        vec!["-n", "lint", "--dry-run"],
+    ] {
+        nrs()
+            .args(args)
+            .envs(state_env(state.path()))
+            .current_dir(project.path())
+            .assert()
+            .success();
+    }
+
+    let output = nrs()
+        .arg("--history")
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{stdout}");
+    assert!(lines[0].starts_with("1  ") && lines[0].ends_with("lint"));
+    assert!(lines[1].ends_with("test -- --watch"));
 
     nrs()
-        .args(["--list", "--runner", "bun"])
+        .args(["--history", "1"])
+        .envs(state_env(state.path()))
         .current_dir(project.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("bun"));
-}
+        .stdout(predicate::str::contains("test").not());
 
-#[test]
-fn test_runner_invalid() {
-    let project = create_project(&standard_scripts());
+    nrs()
+        .args(["--last", "tst", "--dry-run"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run test -- --watch"));
 
     nrs()
-        .args(["--list", "--runner", "invalid"])
+        .args(["--last", "deploy"])
+        .envs(state_env(state.path()))
         .current_dir(project.path())
         .assert()
         .failure()
-        .stderr(predicate::str::contains("invalid"));
+        .stderr(predicate::str::contains("No script matching 'deploy'"));
 }
 
-// ==================== Exit Codes ====================
+// ==================== New Script ====================
 
 #[test]
-fn test_exit_code_success() {
-    let project = create_project(&[("test", "true")]);
+fn test_new_script_adds_to_package_json() {
+    let project = create_project(&standard_scripts());
 
     nrs()
-        .args(["--script", "test"])
+        .args(["new-script", "typecheck", "tsc", "--noEmit"])
         .current_dir(project.path())
         .assert()
-        .code(0);
-}
+        .success()
+        .stdout(predicate::str::contains("Added typecheck"));
 
-#[test]
-fn test_exit_code_no_package_json() {
-    let temp = tempfile::tempdir().unwrap();
+    let content = std::fs::read_to_string(project.path().join("package.json")).unwrap();
+    assert!(content.contains(r#""typecheck": "tsc --noEmit""#));
 
     nrs()
-        .arg("--list")
-        .current_dir(temp.path())
+        .args(["new-script", "typecheck", "tsc"])
+        .current_dir(project.path())
         .assert()
-        .code(2); // NO_PACKAGE_JSON
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
 }
 
 #[test]
-fn test_exit_code_no_scripts() {
-    let project = create_empty_project();
+fn test_suggest_writes_missing_scripts() {
+    let project = create_project(&[("dev", "vite")]);
+    std::fs::write(
+        project.path().join("package.json"),
+        r#"{
+  "name": "app",
+  "scripts": {
+    "dev": "vite"
+  },
+  "devDependencies": {
+    "vite": "^5.0.0",
+    "prettier": "^3.0.0"
+  }
+}
+"#,
+    )
+    .unwrap();
 
     nrs()
-        .arg("--list")
+        .arg("suggest")
         .current_dir(project.path())
         .assert()
-        .code(3); // NO_SCRIPTS
-}
-
-#[test]
-fn test_exit_code_script_failed() {
-    let project = create_project(&[("fail", "exit 42")]);
+        .success()
+        .stdout(predicate::str::contains("prettier --write ."))
+        .stdout(predicate::str::contains("Added").not());
 
     nrs()
-        .args(["--script", "fail"])
+        .args(["suggest", "--write"])
         .current_dir(project.path())
         .assert()
-        .code(42); // Script's exit code
-}
-
-// ==================== Debug Mode ====================
+        .success()
+        .stdout(predicate::str::contains("Added 3 scripts"));
 
-#[test]
-fn test_debug_mode() {
-    let project = create_project(&standard_scripts());
+    let content = std::fs::read_to_string(project.path().join("package.json")).unwrap();
+    assert!(content.contains(r#""build": "vite build""#));
+    assert!(content.contains(r#""format": "prettier --write .""#));
 
     nrs()
-        .args(["--list", "--debug"])
+        .arg("suggest")
         .current_dir(project.path())
         .assert()
         .success()
-        .stderr(predicate::str::contains("=== nrs debug mode ==="))
-        .stderr(predicate::str::contains("Version:"))
-        .stderr(predicate::str::contains("Project directory"))
-        .stderr(predicate::str::contains("Package manager"));
+        .stdout(predicate::str::contains("No scripts to suggest"));
 }
 
 #[test]
-fn test_debug_shows_file_locations() {
-    let project = create_project(&standard_scripts());
+fn test_stats_summarizes_runs() {
+    let project = create_project(&[("hello", "echo hi"), ("broken", "exit 3")]);
+    let state = tempfile::tempdir().unwrap();
 
     nrs()
-        .args(["--list", "--debug"])
+        .arg("stats")
         .current_dir(project.path())
+        .envs(state_env(state.path()))
         .assert()
         .success()
-        .stderr(predicate::str::contains("History file:"))
-        .stderr(predicate::str::contains("package.json:"));
-}
+        .stdout(predicate::str::contains("No runs recorded"));
 
-// ==================== Path Argument ====================
-
-#[test]
-fn test_path_argument() {
-    let project = create_project(&standard_scripts());
+    for script in ["hello", "hello", "broken"] {
+        nrs()
+            .args(["-n", script])
+            .current_dir(project.path())
+            .envs(state_env(state.path()))
+            .assert();
+    }
 
     nrs()
-        .args(["--list", project.path().to_str().unwrap()])
+        .arg("stats")
+        .current_dir(project.path())
+        .envs(state_env(state.path()))
         .assert()
         .success()
-        .stdout(predicate::str::contains("dev"));
-}
+        .stdout(predicate::str::contains("Total runs   3 in 2 scripts"))
+        .stdout(predicate::str::contains("1 of 3 timed runs (33%)"))
+        .stdout(predicate::str::contains("Busiest day  "))
+        .stdout(predicate::str::contains("100% failed"));
 
-#[test]
-fn test_path_nonexistent() {
-    nrs()
-        .args(["--list", "/nonexistent/path"])
-        .assert()
-        .failure()
-        .stderr(predicate::str::contains("Cannot access directory"));
+    let output = nrs()
+        .args(["stats", "--format", "json"])
+        .current_dir(project.path())
+        .envs(state_env(state.path()))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let rows: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(rows[0]["script"], "hello");
+    assert_eq!(rows[0]["runs"], 2);
+    assert_eq!(rows[1]["script"], "broken");
+    assert_eq!(rows[1]["failure_rate"], 1.0);
 }
 
-// ==================== Invalid JSON ====================
-
 #[test]
-fn test_invalid_json() {
-    let project = create_project_invalid_json();
+fn test_history_and_search_formats() {
+    let project = create_project(&standard_scripts());
+    let state = tempfile::tempdir().unwrap();
+
+    for args in [
+        vec!["-n", "test", "--args", "--watch", "--dry-run"],
+        vec!["-n", "lint", "--dry-run"],
+    ] {
+        nrs()
+            .args(args)
+            .envs(state_env(state.path()))
+            .current_dir(project.path())
+            .assert()
+            .success();
+    }
+
+    let output = nrs()
+        .args(["--history", "--format", "json"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let rows: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(rows[0]["script"], "lint");
+    assert_eq!(rows[0]["args"], serde_json::Value::Null);
+    assert_eq!(rows[1]["script"], "test");
+    assert_eq!(rows[1]["args"], "--watch");
 
     nrs()
-        .arg("--list")
+        .args(["history", "search", "watch", "--format", "csv"])
+        .envs(state_env(state.path()))
         .current_dir(project.path())
         .assert()
-        .failure()
-        .stderr(predicate::str::contains("Failed to parse"));
+        .success()
+        .stdout(predicate::str::starts_with(
+            "last_run,project,script,args\n",
+        ))
+        .stdout(predicate::str::contains(",test,--watch"))
+        .stdout(predicate::str::contains("lint").not());
 }
 
-// ==================== Config ====================
+// ==================== State Directories ====================
 
 #[test]
-fn test_config_file_loaded() {
-    let config = r#"
-[appearance]
-icons = false
-
-[exclude]
-patterns = ["lint"]
-"#;
-    let project = create_project_with_config(&standard_scripts(), config);
+fn test_config_and_data_dirs_from_env() {
+    let project = create_project(&[("hello", "echo hi"), ("lint", "eslint .")]);
+    let config_dir = tempfile::tempdir().unwrap();
+    let data_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        config_dir.path().join("config.toml"),
+        "[exclude]\npatterns = [\"lint\"]\n",
+    )
+    .unwrap();
 
-    // With config excluding "lint", should have 4 scripts
     nrs()
-        .arg("--list")
+        .args(["--list", "--format", "plain"])
         .current_dir(project.path())
+        .env("NRS_CONFIG_DIR", config_dir.path())
+        .env("NRS_DATA_DIR", data_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("4 scripts found"));
+        .stdout(predicate::str::contains("hello"))
+        .stdout(predicate::str::contains("lint").not());
+
+    nrs()
+        .args(["-n", "hello"])
+        .current_dir(project.path())
+        .env("NRS_CONFIG_DIR", config_dir.path())
+        .env("NRS_DATA_DIR", data_dir.path())
+        .assert()
+        .success();
+    assert!(data_dir.path().join("history.json").exists());
+    assert!(!config_dir.path().join("history.json").exists());
 }
 
 #[test]
-fn test_no_config_flag() {
-    let config = r#"
-[exclude]
-patterns = ["lint", "test"]
-"#;
-    let project = create_project_with_config(&standard_scripts(), config);
+fn test_completions_install() {
+    let home = tempfile::tempdir().unwrap();
+    let fish = home.path().join(".config/fish/completions/nrs.fish");
 
-    // With --no-config, should have all 5 scripts
     nrs()
-        .args(["--list", "--no-config"])
-        .current_dir(project.path())
+        .args(["completions", "install", "fish", "--dry-run"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
         .assert()
         .success()
-        .stdout(predicate::str::contains("5 scripts found"));
+        .stdout(predicate::str::contains("Would write fish completions to"));
+    assert!(!fish.exists());
+
+    nrs()
+        .args(["completions", "install"])
+        .env("HOME", home.path())
+        .env("SHELL", "/usr/bin/fish")
+        .env_remove("XDG_CONFIG_HOME")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed fish completions"));
+    let script = std::fs::read_to_string(&fish).unwrap();
+    assert!(script.contains("complete -c nrs"));
+
+    nrs()
+        .args(["completions", "install"])
+        .env("SHELL", "/bin/sh")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Could not detect your shell"));
 }
 
-// ==================== Last Script ====================
+// ==================== Brief ====================
 
 #[test]
-fn test_last_no_history() {
+fn test_brief_line_is_cached_until_package_json_changes() {
+    let mut scripts = standard_scripts();
+    scripts.push(("typecheck", "tsc"));
+    let project = create_project(&scripts);
+    let config_home = tempfile::tempdir().unwrap();
+    let brief = || {
+        nrs()
+            .arg("--brief")
+            .current_dir(project.path())
+            .envs(state_env(config_home.path()))
+            .assert()
+            .success()
+    };
+
+    brief().stdout("nrs: build, dev, format, lint, test +1 more\n");
+    assert!(config_home.path().join("brief.json").exists());
+    brief().stdout("nrs: build, dev, format, lint, test +1 more\n");
+
+    std::fs::write(
+        project.path().join("package.json"),
+        r#"{"name": "app", "scripts": {"start": "node ."}}"#,
+    )
+    .unwrap();
+    brief().stdout("nrs: start\n");
+}
+
+#[test]
+fn test_pick_needs_a_terminal() {
     let project = create_project(&standard_scripts());
 
-    // Create a temporary history dir to isolate the test
     nrs()
-        .arg("--last")
+        .arg("--pick")
         .current_dir(project.path())
         .assert()
         .failure()
-        .stderr(predicate::str::contains("No previous script"));
+        .stdout("")
+        .stderr(predicate::str::contains("--pick needs a terminal"));
 }
 
 // ==================== Sort Mode ====================
@@ -722,3 +2283,207 @@ fn test_detects_from_package_manager_field() {
         .stderr(predicate::str::contains("pnpm"))
         .stderr(predicate::str::contains("packageManager"));
 }
+
+#[test]
+fn test_history_projects_pin_and_prune() {
+    let kept = create_project(&standard_scripts());
+    let gone = create_project(&standard_scripts());
+    let state = tempfile::tempdir().unwrap();
+
+    for project in [&kept, &gone] {
+        nrs()
+            .args(["-n", "dev", "--dry-run"])
+            .envs(state_env(state.path()))
+            .current_dir(project.path())
+            .assert()
+            .success();
+    }
+
+    nrs()
+        .args(["history", "pin"])
+        .envs(state_env(state.path()))
+        .current_dir(kept.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Pinned"));
+
+    let gone_name = gone
+        .path()
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    drop(gone);
+
+    let output = nrs()
+        .args(["history", "projects"])
+        .envs(state_env(state.path()))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{stdout}");
+    assert!(lines[0].starts_with('★'), "{stdout}");
+    assert!(lines[1].contains(&gone_name) && lines[1].ends_with("(missing)"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("nrs history prune"));
+
+    nrs()
+        .args(["history", "prune", "--dry-run"])
+        .envs(state_env(state.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Would remove 1 missing project(s)",
+        ));
+
+    nrs()
+        .args(["history", "prune"])
+        .envs(state_env(state.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(gone_name.as_str()))
+        .stdout(predicate::str::contains("Removed 1 missing project(s)"));
+
+    nrs()
+        .args(["history", "projects"])
+        .envs(state_env(state.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(missing)").not())
+        .stderr("");
+
+    nrs()
+        .args(["history", "projects", "--format", "markdown"])
+        .envs(state_env(state.path()))
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "| path | last_run | runs | pinned | missing |\n|---|---|---|---|---|\n",
+        ))
+        .stdout(predicate::str::contains("| true | false |"));
+}
+
+#[test]
+fn test_last_dry_run_shows_command_change() {
+    let project = create_project(&standard_scripts());
+    let state = tempfile::tempdir().unwrap();
+
+    nrs()
+        .args(["-n", "test", "--args", "--watch", "--dry-run"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .assert()
+        .success();
+
+    // Pretend the last real run went through yarn
+    let history_path = state.path().join("history.json");
+    let mut history: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&history_path).unwrap()).unwrap();
+    let project_key = project.path().canonicalize().unwrap();
+    history["projects"][project_key.to_str().unwrap()]["scripts"]["test"]["last_command"] =
+        "yarn run test --watch".into();
+    std::fs::write(&history_path, history.to_string()).unwrap();
+
+    nrs()
+        .args(["--last", "--dry-run"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Would run: npm run test -- --watch",
+        ))
+        .stderr(predicate::str::contains(
+            "  Last run: yarn run test --watch\n            ^^^^\n",
+        ))
+        .stderr(predicate::str::contains(
+            "  Now:      npm run test -- --watch\n            ^^^          ^^\n",
+        ));
+}
+
+#[test]
+fn test_hashed_paths_keep_salt_out_of_history_file() {
+    let project = create_project_with_config(&standard_scripts(), "[history]\nhash_paths = true\n");
+    let state = tempfile::tempdir().unwrap();
+
+    nrs()
+        .args(["-n", "test", "--dry-run"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .assert()
+        .success();
+
+    let history = std::fs::read_to_string(state.path().join("history.json")).unwrap();
+    let salt = std::fs::read_to_string(state.path().join("history-salt")).unwrap();
+    assert!(!salt.trim().is_empty());
+    assert!(!history.contains(salt.trim()), "{history}");
+    assert!(!history.contains("\"salt\""), "{history}");
+    assert!(history.contains("sha256:"), "{history}");
+
+    nrs()
+        .args(["--last", "--dry-run"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would run: npm run test"));
+}
+
+#[test]
+fn test_seeded_run_doesnt_save_salt() {
+    let project = create_project_with_config(&standard_scripts(), "[history]\nhash_paths = true\n");
+    let state = tempfile::tempdir().unwrap();
+
+    nrs()
+        .args(["--seed", "1", "-n", "test", "--dry-run"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .assert()
+        .success();
+
+    assert!(state.path().join("history.json").exists());
+    assert!(!state.path().join("history-salt").exists());
+}
+
+#[test]
+fn test_doctor_reports_broken_config() {
+    let project = create_project_with_config(&standard_scripts(), "[general\nrunner = 1");
+    let state = tempfile::tempdir().unwrap();
+
+    let output = nrs()
+        .arg("doctor")
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("fail  config"), "{stdout}");
+    assert!(stdout.contains(".nrsrc.toml: TOML parse error"), "{stdout}");
+    assert!(stdout.contains("fix: Fix the file"), "{stdout}");
+    assert!(
+        stdout.contains("pass  history     No history yet"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("pass  workspaces  Not a monorepo"),
+        "{stdout}"
+    );
+    assert!(stdout.contains(" failed\n"), "{stdout}");
+
+    let output = nrs()
+        .args(["doctor", "--format", "json"])
+        .envs(state_env(state.path()))
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let checks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(checks[0]["status"], "fail");
+    assert_eq!(checks[0]["check"], "config");
+    assert!(checks[0]["fix"]
+        .as_str()
+        .unwrap()
+        .starts_with("Fix the file"));
+}
@@ -40,6 +40,7 @@ fn test_config_merge() {
         },
         exclude: ExcludeConfig {
             patterns: vec!["overlay".to_string()],
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -61,6 +62,7 @@ fn test_exclude_patterns_merge() {
     let overlay = Config {
         exclude: ExcludeConfig {
             patterns: vec!["b".to_string(), "c".to_string()],
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -206,7 +208,7 @@ aliases = { d = "dev", b = "build" }
         config.scripts.descriptions.get("dev"),
         Some(&"Start dev server".to_string())
     );
-    assert_eq!(config.scripts.aliases.get("d"), Some(&"dev".to_string()));
+    assert_eq!(config.scripts.aliases.get("d"), Some("dev"));
 }
 
 // ==================== Full Config ====================
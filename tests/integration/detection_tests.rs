@@ -42,6 +42,13 @@ fn test_detect_bun_from_lockfile() {
     assert_eq!(runner, Runner::Bun);
 }
 
+#[test]
+fn test_detect_deno_from_lockfile() {
+    let project = create_project_with_lockfile(&standard_scripts(), LockfileType::Deno);
+    let runner = detect_runner(project.path());
+    assert_eq!(runner, Runner::Deno);
+}
+
 // ==================== packageManager Field Detection ====================
 
 #[test]
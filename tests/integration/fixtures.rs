@@ -1,8 +1,23 @@
 //! Test fixtures and helper functions for integration tests.
 
 use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
 use tempfile::TempDir;
 
+/// Environment pointing nrs at a config and data directory of its own.
+pub fn state_env(dir: &Path) -> [(&'static str, &Path); 2] {
+    [("NRS_CONFIG_DIR", dir), ("NRS_DATA_DIR", dir)]
+}
+
+/// State directory shared by tests that don't make their own, so no test
+/// reads or writes the real history, pinned scripts or trust store.
+pub fn shared_state_dir() -> &'static Path {
+    static DIR: OnceLock<TempDir> = OnceLock::new();
+    DIR.get_or_init(|| TempDir::new().expect("Failed to create state directory"))
+        .path()
+}
+
 /// Lock file types for testing runner detection.
 #[derive(Debug, Clone, Copy)]
 pub enum LockfileType {
@@ -10,6 +25,7 @@ pub enum LockfileType {
     Yarn,
     Pnpm,
     Bun,
+    Deno,
 }
 
 impl LockfileType {
@@ -20,6 +36,7 @@ impl LockfileType {
             LockfileType::Yarn => "yarn.lock",
             LockfileType::Pnpm => "pnpm-lock.yaml",
             LockfileType::Bun => "bun.lockb",
+            LockfileType::Deno => "deno.lock",
         }
     }
 
@@ -30,6 +47,7 @@ impl LockfileType {
             LockfileType::Yarn => "# THIS IS AN AUTOGENERATED FILE\n__metadata:\n  version: 6\n",
             LockfileType::Pnpm => "lockfileVersion: '9.0'\n",
             LockfileType::Bun => "binary lockfile content",
+            LockfileType::Deno => r#"{"version": "4"}"#,
         }
     }
 }
@@ -192,6 +210,40 @@ pub fn create_project_with_pm_and_lockfile(
     temp
 }
 
+/// Create a monorepo with `@app/web` and `@app/api` workspaces under `packages/`.
+pub fn create_monorepo_project() -> TempDir {
+    let temp = TempDir::new().expect("Failed to create temp directory");
+
+    let root_json = r#"{
+  "name": "monorepo",
+  "private": true,
+  "workspaces": ["packages/*"],
+  "scripts": {
+    "lint": "eslint ."
+  }
+}"#;
+    fs::write(temp.path().join("package.json"), root_json).expect("Failed to write package.json");
+
+    for (dir, name) in [("web", "@app/web"), ("api", "@app/api")] {
+        let package_dir = temp.path().join("packages").join(dir);
+        fs::create_dir_all(&package_dir).expect("Failed to create workspace directory");
+        let package_json = format!(
+            r#"{{
+  "name": "{}",
+  "scripts": {{
+    "build": "vite build",
+    "dev": "vite"
+  }}
+}}"#,
+            name
+        );
+        fs::write(package_dir.join("package.json"), package_json)
+            .expect("Failed to write package.json");
+    }
+
+    temp
+}
+
 /// Create an empty project (no scripts).
 pub fn create_empty_project() -> TempDir {
     let temp = TempDir::new().expect("Failed to create temp directory");
@@ -260,6 +312,14 @@ pub fn create_large_project(script_count: usize) -> TempDir {
     create_project(&scripts_ref)
 }
 
+/// Time pinned by [`deterministic_args`].
+pub const FROZEN_TIME: &str = "2024-01-01T12:00:00Z";
+
+/// Arguments that make nrs output reproducible (pinned time and seed).
+pub fn deterministic_args() -> [&'static str; 4] {
+    ["--frozen-time", FROZEN_TIME, "--seed", "42"]
+}
+
 /// Standard scripts for testing.
 pub fn standard_scripts() -> Vec<(&'static str, &'static str)> {
     vec![
@@ -5,7 +5,7 @@ use std::path::PathBuf;
 
 use chrono::Utc;
 use npm_run_scripts::config::HistoryConfig;
-use npm_run_scripts::history::History;
+use npm_run_scripts::history::{search_history, History};
 use tempfile::TempDir;
 
 /// Create a temporary history file location.
@@ -111,6 +111,23 @@ fn test_history_save_and_load() {
     assert_eq!(project_history.unwrap().scripts.len(), 2);
 }
 
+#[test]
+fn test_history_search_after_reload() {
+    let mut history = History::default();
+    let project = PathBuf::from("/test/project");
+    history.record_run(&project, "lint", Some("--fix --cache".to_string()));
+    history.record_run(&project, "lint", Some("--quiet".to_string()));
+    history.record_run(&PathBuf::from("/test/other"), "build", None);
+
+    let content = serde_json::to_string(&history).expect("Failed to serialize");
+    let loaded: History = serde_json::from_str(&content).expect("Failed to parse");
+
+    let found = search_history(&loaded, "fix");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].project, project);
+    assert_eq!(found[0].command(), "lint -- --fix --cache");
+}
+
 #[test]
 fn test_history_missing_file() {
     let temp = temp_history_dir();
@@ -161,6 +178,7 @@ fn test_history_cleanup_projects() {
         enabled: true,
         max_projects: 3,
         max_scripts: 100,
+        ..HistoryConfig::default()
     };
 
     // Add 5 projects
@@ -185,6 +203,7 @@ fn test_history_cleanup_scripts() {
         enabled: true,
         max_projects: 10,
         max_scripts: 3,
+        ..HistoryConfig::default()
     };
 
     // Add 5 scripts
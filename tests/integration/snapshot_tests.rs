@@ -6,12 +6,15 @@ use assert_cmd::cargo::cargo_bin_cmd;
 use assert_cmd::Command;
 
 use crate::integration::fixtures::{
-    create_project, create_project_with_descriptions, standard_scripts,
+    create_project, create_project_with_descriptions, deterministic_args, shared_state_dir,
+    standard_scripts, state_env, FROZEN_TIME,
 };
 
 /// Get a Command for the nrs binary.
 fn nrs() -> Command {
-    cargo_bin_cmd!("nrs")
+    let mut cmd = cargo_bin_cmd!("nrs");
+    cmd.envs(state_env(shared_state_dir()));
+    cmd
 }
 
 // ==================== Help Output Snapshots ====================
@@ -20,7 +23,7 @@ fn nrs() -> Command {
 fn test_snapshot_help_output() {
     let output = nrs().arg("--help").output().expect("Failed to run nrs");
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = normalize_help_output(&String::from_utf8_lossy(&output.stdout));
 
     insta::assert_snapshot!("help_output", stdout);
 }
@@ -29,11 +32,18 @@ fn test_snapshot_help_output() {
 fn test_snapshot_help_short_output() {
     let output = nrs().arg("-h").output().expect("Failed to run nrs");
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = normalize_help_output(&String::from_utf8_lossy(&output.stdout));
 
     insta::assert_snapshot!("help_short_output", stdout);
 }
 
+/// Normalize help output across platforms.
+///
+/// Usage lines show the binary name, which is `nrs.exe` on Windows.
+fn normalize_help_output(output: &str) -> String {
+    output.replace("nrs.exe", "nrs").replace("\r\n", "\n")
+}
+
 // ==================== Version Output Snapshots ====================
 
 #[test]
@@ -307,10 +317,49 @@ fn normalize_error_path(error: &str) -> String {
     // - /var/folders/.../T/.tmpXXX/...
     // - /private/var/folders/.../T/.tmpXXX/...
     // - /tmp/...
-    let re = regex::Regex::new(r"(/private)?/var/folders/[^\s]+|/tmp/[^\s]+").unwrap();
+    // - C:\Users\...\AppData\Local\Temp\.tmpXXX\...
+    let re =
+        regex::Regex::new(r"(/private)?/var/folders/[^\s]+|/tmp/[^\s]+|[A-Z]:\\[^\s]+").unwrap();
     re.replace_all(error, "<TEMP_PATH>").to_string()
 }
 
+// ==================== Deterministic Mode ====================
+
+#[test]
+fn test_deterministic_args_are_accepted() {
+    let project = create_project(&standard_scripts());
+
+    let output = nrs()
+        .args(deterministic_args())
+        .arg("--list")
+        .current_dir(project.path())
+        .output()
+        .expect("Failed to run nrs");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    insta::assert_snapshot!("deterministic_list_output", stdout);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_frozen_time_pins_history_timestamps() {
+    let project = create_project(&standard_scripts());
+    let config_home = tempfile::TempDir::new().unwrap();
+
+    nrs()
+        .args(deterministic_args())
+        .args(["-n", "dev", "--dry-run"])
+        .envs(state_env(config_home.path()))
+        .current_dir(project.path())
+        .assert()
+        .success();
+
+    let history = std::fs::read_to_string(config_home.path().join("history.json")).unwrap();
+    let expected = FROZEN_TIME.replace('Z', "");
+    assert!(history.contains(&expected), "{history}");
+}
+
 // ==================== Debug Output Snapshots ====================
 
 #[test]
@@ -335,10 +384,15 @@ fn test_snapshot_debug_output() {
 fn normalize_debug_output(output: &str) -> String {
     let mut result = output.to_string();
 
-    // Replace absolute paths
+    // Replace absolute paths, including Windows drive paths
     let path_re =
         regex::Regex::new(r"(/[^\s:]+)+").unwrap_or_else(|_| regex::Regex::new(r"/.+").unwrap());
     result = path_re.replace_all(&result, "<PATH>").to_string();
+    let drive_re = regex::Regex::new(r"[A-Z]:\\[^\s]*").unwrap();
+    result = drive_re.replace_all(&result, "<PATH>").to_string();
+
+    // Paths containing spaces (e.g. macOS "Application Support") split into two matches
+    result = result.replace("<PATH> Support<PATH>", "<PATH>");
 
     // Keep the structure but normalize variable parts
     result.replace("\r\n", "\n")